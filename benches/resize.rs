@@ -0,0 +1,56 @@
+//! Benchmark window resizing over a deep widget tree
+//!
+//! Run with `cargo bench --bench resize`. This compares resizing the window,
+//! where size rules are cached, against solving layout with all size rules
+//! invalidated (as happens on [`TkAction::RESIZE`]).
+
+use kas::prelude::*;
+use kas::test::TestWindow;
+use kas::widgets::{BoxColumn, BoxRow, Label, Window};
+use std::time::{Duration, Instant};
+
+const DEPTH: u32 = 10;
+const ITERS: u32 = 50;
+
+type BoxWidget = Box<dyn Widget<Msg = VoidMsg>>;
+
+/// A tree of alternating rows and columns with `2^depth` labels at the leaves
+fn tree(depth: u32) -> BoxWidget {
+    let label = |n: u32| -> BoxWidget { Box::new(Label::new(format!("Label {}", n))) };
+    if depth == 0 {
+        return label(0);
+    }
+    let children = vec![tree(depth - 1), label(depth), tree(depth - 1)];
+    if depth % 2 == 0 {
+        Box::new(BoxColumn::new(children))
+    } else {
+        Box::new(BoxRow::new(children))
+    }
+}
+
+/// Mean time to re-solve layout
+///
+/// If `invalidate`, layout is solved after invalidating size rules; otherwise
+/// the window's width is changed.
+fn measure<W: kas::Window>(tw: &mut TestWindow<W>, invalidate: bool) -> Duration {
+    let size = tw.size();
+    let start = Instant::now();
+    for i in 0..ITERS {
+        if invalidate {
+            tw.send_action(TkAction::RESIZE);
+        } else {
+            let width = size.0 + 20 * (i % 2) as i32;
+            tw.resize(Size(width, size.1));
+        }
+    }
+    start.elapsed() / ITERS
+}
+
+fn main() {
+    let mut tw = TestWindow::new(Window::new("Resize", tree(DEPTH)));
+    let cached = measure(&mut tw, false);
+    let uncached = measure(&mut tw, true);
+    println!("Resize of tree with depth {}:", DEPTH);
+    println!("  resize (cached rules):  {:?}", cached);
+    println!("  re-solve (no cache):    {:?}", uncached);
+}
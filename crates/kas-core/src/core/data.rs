@@ -14,8 +14,8 @@ use super::Layout;
 use super::Widget;
 use crate::event::{self, Manager};
 use crate::geom::Rect;
-use crate::layout::StorageChain;
-use crate::{dir::Direction, WindowId};
+use crate::layout::{SizeRulesCache, StorageChain};
+use crate::{dir::Direction, WindowAttributes, WindowId, WindowPlacement};
 
 #[cfg(feature = "winit")]
 pub use winit::window::Icon;
@@ -48,14 +48,29 @@ impl Icon {
 /// `Option<WidgetId>` is a free extension (requires no extra memory).
 ///
 /// Identifiers are assigned when configured and when re-configured
-/// (via [`crate::TkAction::RECONFIGURE`]). Since user-code is not notified of a
-/// re-configure, user-code should not store a `WidgetId`.
+/// (via [`crate::TkAction::RECONFIGURE`]). On re-configure, each widget keeps
+/// its existing identifier where this is consistent with the order of
+/// identifiers (see [`crate::WidgetChildren#child-order`]): thus identifiers
+/// remain valid when widgets are unchanged or following widgets are removed,
+/// but widgets after an inserted (or moved) widget may be renumbered. Event
+/// state held by the [`crate::event::Manager`] (focus, grabs, timers) follows
+/// renumbered widgets; user-code storing a `WidgetId` is not notified.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct WidgetId(NonZeroU32);
 
 impl WidgetId {
     pub(crate) const FIRST: WidgetId = WidgetId(unsafe { NonZeroU32::new_unchecked(1) });
-    const LAST: WidgetId = WidgetId(unsafe { NonZeroU32::new_unchecked(u32::MAX) });
+    pub(crate) const LAST: WidgetId = WidgetId(unsafe { NonZeroU32::new_unchecked(u32::MAX) });
+
+    /// True if this is the placeholder value of an unconfigured widget
+    pub(crate) fn is_unconfigured(self) -> bool {
+        self == WidgetId::LAST
+    }
+
+    pub(crate) fn advance(self, n: u32) -> Self {
+        let id = self.0.get().saturating_add(n).min(u32::MAX - 1);
+        WidgetId(NonZeroU32::new(id).unwrap())
+    }
 
     pub(crate) fn next(self) -> Self {
         WidgetId(NonZeroU32::new(self.0.get() + 1).unwrap())
@@ -122,9 +137,57 @@ pub struct CoreData {
     pub rect: Rect,
     pub id: WidgetId,
     pub disabled: bool,
+    pub(crate) size_cache: SizeRulesCache,
+    generation: u64,
 }
 
-/// Note: the clone has default-initialised layout storage and identifier.
+impl CoreData {
+    /// Get the solved rect of a named layout region
+    ///
+    /// Regions are named within the `layout` attribute of the
+    /// [`widget`](https://docs.rs/kas/latest/kas/macros#the-widget-macro)
+    /// macro via `region(NAME): LAYOUT` or via [`crate::layout::Layout::region`].
+    /// Returns `None` if no region `name` exists or before layout is solved.
+    #[inline]
+    pub fn layout_region(&mut self, name: &str) -> Option<Rect> {
+        self.layout.region(name)
+    }
+
+    /// Get the content generation
+    ///
+    /// See [`CoreData::bump_generation`].
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Mark content as changed
+    ///
+    /// Widgets should call this whenever their content changes in a way which
+    /// may affect their size rules (e.g. from `set_string`). This invalidates
+    /// size rules cached by the widget and its ancestors (see
+    /// [`crate::layout::SizeRulesCache`]). It is also called on configure.
+    #[inline]
+    pub fn bump_generation(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// True if the assigned rect is smaller than the minimum size
+    ///
+    /// When a window is too small for its content, widgets are assigned
+    /// reduced (possibly empty) rects and their content overflows. This
+    /// compares the assigned rect with the size rules cached by the default
+    /// implementation of [`crate::Layout::size_rules`], thus is always false
+    /// for widgets which do not use this cache.
+    #[inline]
+    pub fn overflow(&self) -> bool {
+        let min = self.size_cache.min_size();
+        self.rect.size.0 < min.0 || self.rect.size.1 < min.1
+    }
+}
+
+/// Note: the clone has default-initialised layout storage, size rules cache,
+/// generation and identifier.
 /// Configuration and layout solving is required as for any other widget.
 impl Clone for CoreData {
     fn clone(&self) -> Self {
@@ -133,6 +196,8 @@ impl Clone for CoreData {
             rect: self.rect,
             id: WidgetId::default(),
             disabled: self.disabled,
+            size_cache: SizeRulesCache::default(),
+            generation: 0,
         }
     }
 }
@@ -184,6 +249,36 @@ pub trait Window: Widget<Msg = event::VoidMsg> {
     /// windows.
     fn restrict_dimensions(&self) -> (bool, bool);
 
+    /// Window attributes
+    ///
+    /// This is queried by the shell on window creation; size limits are also
+    /// re-queried when the window is resized. By default, a decorated,
+    /// resizable window is created.
+    fn attributes(&self) -> WindowAttributes {
+        WindowAttributes::default()
+    }
+
+    /// Initial placement of the window
+    ///
+    /// This is queried by the shell on window creation, allowing selection of
+    /// the monitor and position and opening fullscreen. By default, placement
+    /// is left to the shell.
+    fn placement(&self) -> WindowPlacement {
+        WindowPlacement::default()
+    }
+
+    /// Key under which to persist window geometry, if any
+    ///
+    /// If some key is returned, the shell saves the window's size and
+    /// position (see [`crate::state`]) and restores these when the window is
+    /// next created, taking priority over [`Self::attributes`] and
+    /// [`Self::placement`]. By default, geometry is not persisted.
+    ///
+    /// Not all shells support this.
+    fn state_key(&self) -> Option<&str> {
+        None
+    }
+
     /// Add a pop-up as a layer in the current window
     ///
     /// Each [`Popup`] is assigned a [`WindowId`]; both are passed.
@@ -77,12 +77,30 @@ impl<M: 'static> WidgetConfig for Box<dyn Widget<Msg = M>> {
     fn key_nav(&self) -> bool {
         self.as_ref().key_nav()
     }
+    fn focus_scope(&self) -> bool {
+        self.as_ref().focus_scope()
+    }
+    fn tab_index(&self) -> Option<u32> {
+        self.as_ref().tab_index()
+    }
+    fn nav_wrap(&self) -> bool {
+        self.as_ref().nav_wrap()
+    }
+    fn initial_focus(&self) -> bool {
+        self.as_ref().initial_focus()
+    }
     fn hover_highlight(&self) -> bool {
         self.as_ref().hover_highlight()
     }
     fn cursor_icon(&self) -> event::CursorIcon {
         self.as_ref().cursor_icon()
     }
+    fn tooltip(&self) -> Option<String> {
+        self.as_ref().tooltip()
+    }
+    fn hover_focus(&self) -> event::HoverFocus {
+        self.as_ref().hover_focus()
+    }
 }
 
 impl<M: 'static> Layout for Box<dyn Widget<Msg = M>> {
@@ -79,6 +79,10 @@ pub trait WidgetCore: Any + fmt::Debug {
     ///
     /// The disabled status is inherited by children: events should not be
     /// passed to them, and they should also be drawn greyed out.
+    ///
+    /// This does not notify the widget or its descendants; where this is
+    /// required (e.g. to cancel grabs or close popups), use
+    /// [`Manager::set_disabled`] instead.
     #[inline]
     fn set_disabled(&mut self, disabled: bool) -> TkAction {
         self.core_data_mut().disabled = disabled;
@@ -162,6 +166,18 @@ pub trait WidgetCore: Any + fmt::Debug {
 /// Dynamic widgets must implement this trait manually, since [`derive(Widget)`]
 /// cannot currently handle fields like `Vec<SomeWidget>`.
 ///
+/// # Child order
+///
+/// Children are indexed `0..num_children()`. This order is stable: it is the
+/// order in which children are configured (see
+/// [`WidgetConfig::configure_recurse`]), thus child identifiers strictly
+/// increase with index (and each child's range of identifiers, from
+/// [`WidgetChildren::first_id`] to [`WidgetCore::id`], precedes that of the
+/// next child). The [`derive(Widget)`] macro lists children in the order
+/// fields are declared. Manual implementations must uphold this guarantee,
+/// which [`WidgetChildren::find_child`] and [`crate::util::find_child_index`]
+/// rely on.
+///
 /// Whenever the number of child widgets changes or child widgets are replaced,
 /// one must send [`TkAction::RECONFIGURE`].
 /// (TODO: this is slow. Find an option for partial reconfigures. This requires
@@ -207,6 +223,32 @@ pub trait WidgetChildren: WidgetCore {
     /// This method may be removed in the future.
     fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig>;
 
+    /// Iterate over child widgets, in order
+    #[inline]
+    fn children(&self) -> ChildIter<'_> {
+        ChildIter {
+            parent: self.as_widget(),
+            range: 0..self.num_children(),
+        }
+    }
+
+    /// Call `f` on each child widget, in order
+    ///
+    /// This is the mutable equivalent of [`Self::children`]. (An iterator
+    /// over mutable references cannot be implemented over
+    /// [`Self::get_child_mut`] without `unsafe` code.)
+    fn for_each_child_mut<F>(&mut self, mut f: F)
+    where
+        Self: Sized,
+        F: FnMut(usize, &mut dyn WidgetConfig),
+    {
+        for index in 0..self.num_children() {
+            if let Some(child) = self.get_child_mut(index) {
+                f(index, child);
+            }
+        }
+    }
+
     /// Check whether `id` is a descendant
     ///
     /// This function assumes that `id` is a valid widget.
@@ -309,12 +351,9 @@ pub trait WidgetConfig: Layout {
     /// widgets with pop-ups.
     fn configure_recurse(&mut self, mut cmgr: ConfigureManager) {
         self.record_first_id(cmgr.peek_next());
-        for i in 0..self.num_children() {
-            if let Some(w) = self.get_child_mut(i) {
-                w.configure_recurse(cmgr.child());
-            }
-        }
+        cmgr.configure_children(self);
         self.core_data_mut().id = cmgr.next_id(self.id());
+        self.core_data_mut().bump_generation();
         self.configure(cmgr.mgr());
     }
 
@@ -326,6 +365,63 @@ pub trait WidgetConfig: Layout {
         false
     }
 
+    /// Is this widget a focus scope?
+    ///
+    /// While navigation focus is on a descendant of a focus scope, Tab
+    /// navigation cycles through the scope's descendants only. The scope is
+    /// exited via the Escape key (if not otherwise handled) or
+    /// [`event::Manager::exit_focus_scope`].
+    ///
+    /// Defaults to `false`.
+    #[inline]
+    fn focus_scope(&self) -> bool {
+        false
+    }
+
+    /// Explicit position in Tab navigation order
+    ///
+    /// Within each focus scope (or window), widgets with an explicit index are
+    /// visited first, in increasing order of index (ties are broken by
+    /// document order), followed by all other widgets in document order.
+    /// This is only relevant where [`Self::key_nav`] returns true.
+    ///
+    /// Whether a scope uses any tab index is cached until the widget tree is
+    /// next (re)configured, thus changes should be followed by a reconfigure.
+    ///
+    /// Defaults to `None`.
+    #[inline]
+    fn tab_index(&self) -> Option<u32> {
+        None
+    }
+
+    /// Does Tab navigation wrap around within this widget?
+    ///
+    /// This applies where the widget is a [focus scope](Self::focus_scope) or
+    /// the root of a window or pop-up. If false, navigation stops at the first
+    /// and last navigable descendants instead of cycling, as is common in
+    /// gamepad-driven UIs.
+    ///
+    /// Defaults to `true`.
+    #[inline]
+    fn nav_wrap(&self) -> bool {
+        true
+    }
+
+    /// Should this widget receive initial navigation focus?
+    ///
+    /// When no widget within the current focus scope (or window or pop-up)
+    /// has navigation focus, the first Tab (or equivalent) input gives focus
+    /// to the first widget returning true here (in navigation order), instead
+    /// of to the first navigable widget. This is only relevant where
+    /// [`Self::key_nav`] returns true; it may be used e.g. to select the
+    /// default button of a dialog.
+    ///
+    /// Defaults to `false`.
+    #[inline]
+    fn initial_focus(&self) -> bool {
+        false
+    }
+
     /// Does this widget have hover-state highlighting?
     ///
     /// If true, a redraw will be requested whenever this widget gains or loses
@@ -342,6 +438,32 @@ pub trait WidgetConfig: Layout {
     fn cursor_icon(&self) -> event::CursorIcon {
         event::CursorIcon::Default
     }
+
+    /// Tooltip to show on hover
+    ///
+    /// This is queried when the mouse rests over this widget or a descendant
+    /// (the innermost widget providing a tooltip is used). Text widgets
+    /// typically return their full text when it is truncated.
+    ///
+    /// Defaults to `None`.
+    #[inline]
+    fn tooltip(&self) -> Option<String> {
+        None
+    }
+
+    /// Focus-follows-mouse policy for this widget and its descendants
+    ///
+    /// Where enabled, resting the mouse over a [`Self::key_nav`] widget for
+    /// some delay gives it navigation focus, as is common in tool palettes.
+    /// The innermost policy other than [`event::HoverFocus::Inherit`] among
+    /// the widget and its ancestors applies; if none, the policy is given by
+    /// [`event::Config::hover_focus`].
+    ///
+    /// Defaults to [`event::HoverFocus::Inherit`].
+    #[inline]
+    fn hover_focus(&self) -> event::HoverFocus {
+        event::HoverFocus::Inherit
+    }
 }
 
 /// Positioning and drawing routines for widgets
@@ -383,7 +505,14 @@ pub trait Layout: WidgetChildren {
     /// For widgets with children, a [`crate::layout::RulesSolver`] engine may be
     /// useful to calculate requirements of complex layouts.
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
-        self.layout().size_rules(size_handle, axis)
+        let generation = crate::layout::subtree_generation(self);
+        if let Some(rules) = self.core_data().size_cache.get(axis, generation) {
+            return rules;
+        }
+        let rules = self.layout().size_rules(size_handle, axis);
+        let cache = &mut self.core_data_mut().size_cache;
+        cache.insert(axis, generation, rules);
+        rules
     }
 
     /// Apply a given `rect` to self
@@ -432,7 +561,8 @@ pub trait Layout: WidgetChildren {
     /// otherwise return the first or last child.
     ///
     /// The default implementation often suffices: it will navigate through
-    /// children in order.
+    /// children in order, or for a [grid layout](crate::layout::Layout::grid),
+    /// by row then by column.
     fn spatial_nav(
         &mut self,
         mgr: &mut Manager,
@@ -445,6 +575,23 @@ pub trait Layout: WidgetChildren {
             return None;
         }
 
+        if let Some(order) = self.layout().grid_order() {
+            let order: Vec<usize> = order
+                .into_iter()
+                .filter_map(|id| self.find_child(id))
+                .collect();
+            // Only use grid order if it covers all children
+            if order.len() == last + 1 {
+                let pos = from.and_then(|index| order.iter().position(|i| *i == index));
+                return match (pos, reverse) {
+                    (None, false) => order.first().cloned(),
+                    (None, true) => order.last().cloned(),
+                    (Some(pos), false) => order.get(pos + 1).cloned(),
+                    (Some(pos), true) => pos.checked_sub(1).map(|pos| order[pos]),
+                };
+            }
+        }
+
         let reverse = reverse ^ self.layout().is_reversed();
 
         if let Some(index) = from {
@@ -539,3 +686,35 @@ pub trait Layout: WidgetChildren {
 ///
 /// [`derive(Widget)`]: https://docs.rs/kas/latest/kas/macros/index.html#the-derivewidget-macro
 pub trait Widget: event::SendEvent {}
+
+/// Iterator over a widget's children
+///
+/// This is constructed by [`WidgetChildren::children`].
+pub struct ChildIter<'a> {
+    parent: &'a dyn WidgetConfig,
+    range: std::ops::Range<usize>,
+}
+
+impl<'a> Iterator for ChildIter<'a> {
+    type Item = &'a dyn WidgetConfig;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let parent = self.parent;
+        self.range.find_map(|index| parent.get_child(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.range.len()))
+    }
+}
+
+impl<'a> DoubleEndedIterator for ChildIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let parent = self.parent;
+        self.range
+            .by_ref()
+            .rev()
+            .find_map(|index| parent.get_child(index))
+    }
+}
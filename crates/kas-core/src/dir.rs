@@ -138,3 +138,64 @@ impl Directional for Direction {
         self
     }
 }
+
+/// Horizontal layout direction
+///
+/// Under right-to-left layout, horizontal directions are mirrored: children of
+/// a row placed in direction [`Direction::Right`] are positioned from right to
+/// left, horizontal alignment is mirrored, menus open towards the left and the
+/// Left/Right arrow keys are swapped.
+///
+/// Widgets specify directions in *logical* terms; use [`Self::apply`] to
+/// convert between logical and visual directions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayoutDirection {
+    /// Left-to-right layout
+    Ltr,
+    /// Right-to-left layout
+    Rtl,
+}
+
+impl Default for LayoutDirection {
+    fn default() -> Self {
+        LayoutDirection::Ltr
+    }
+}
+
+impl LayoutDirection {
+    /// True if right-to-left
+    #[inline]
+    pub fn is_rtl(self) -> bool {
+        self == LayoutDirection::Rtl
+    }
+
+    /// Map a logical direction to a visual direction (and vice-versa)
+    ///
+    /// Under [`LayoutDirection::Rtl`] horizontal directions are reversed;
+    /// otherwise `dir` is returned unchanged.
+    #[inline]
+    pub fn apply<D: Directional>(self, dir: D) -> Direction {
+        let dir = dir.as_direction();
+        if self.is_rtl() && dir.is_horizontal() {
+            dir.reversed()
+        } else {
+            dir
+        }
+    }
+
+    /// Get the conventional direction for `locale` (e.g. `"ar-EG"`)
+    ///
+    /// Only the language subtag is considered.
+    pub fn from_locale(locale: &str) -> Self {
+        const RTL_LANGS: &[&str] = &[
+            "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ku", "ps", "sd", "ug", "ur", "yi",
+        ];
+        let lang = locale.split(|c| c == '-' || c == '_').next().unwrap_or("");
+        if RTL_LANGS.iter().any(|l| lang.eq_ignore_ascii_case(l)) {
+            LayoutDirection::Rtl
+        } else {
+            LayoutDirection::Ltr
+        }
+    }
+}
@@ -7,9 +7,10 @@
 
 use super::color::Rgba;
 #[allow(unused)]
-use super::{DrawHandle, DrawRounded, DrawRoundedImpl};
-use super::{DrawSharedImpl, ImageId, PassId, PassType, SharedState};
-use crate::geom::{Offset, Quad, Rect, Vec2};
+use super::{DrawHandle, DrawRounded, DrawRoundedDyn};
+use super::{DrawRoundedImpl, DrawShadedImpl};
+use super::{DrawSharedImpl, Gradient, ImageId, PassId, PassType, SharedState};
+use crate::geom::{Offset, Quad, Rect, Transform, Vec2};
 use crate::text::{Effect, TextDisplay};
 use std::any::Any;
 
@@ -19,20 +20,20 @@ use std::any::Any;
 /// functionality over this object.
 ///
 /// This type is used to present a unified mid-level draw interface, as
-/// available from [`DrawHandle::draw_device`]. A concrete `DrawIface` object may be
-/// obtained via downcast, e.g.:
+/// available from [`DrawHandle::draw_device`]. A concrete `DrawIface` object
+/// may be obtained via downcast given the shell's [`DrawSharedImpl`] type,
+/// though this ties the caller to that shell. Extension traits are usually
+/// better accessed via a shell-independent wrapper such as
+/// [`DrawRoundedDyn`], e.g.:
 /// ```ignore
-/// # use kas::draw::{DrawIface, DrawRoundedImpl, DrawSharedImpl, DrawHandle, DrawRounded, color::Rgba};
+/// # use kas::draw::{DrawHandle, DrawRounded, DrawRoundedDyn, color::Rgba};
 /// # use kas::geom::Rect;
-/// # struct CircleWidget<DS> {
+/// # struct CircleWidget {
 /// #     rect: Rect,
-/// #     _pd: std::marker::PhantomData<DS>,
 /// # }
 /// impl CircleWidget {
 ///     fn draw(&mut self, draw: &mut dyn DrawHandle) {
-///         // This type assumes usage of kas_wgpu without a custom draw pipe:
-///         type DrawIface = DrawIface<kas_wgpu::draw::DrawPipe<()>>;
-///         if let Some(mut draw) = DrawIface::downcast_from(draw.draw_device()) {
+///         if let Some(mut draw) = DrawRoundedDyn::downcast_from(draw.draw_device()) {
 ///             draw.circle(self.rect.into(), 0.9, Rgba::BLACK);
 ///         }
 ///     }
@@ -93,6 +94,10 @@ impl<'a, DS: DrawSharedImpl> DrawIface<'a, DS> {
     /// Case `class == PassType::Overlay`: the new pass is derived from the
     /// base pass (i.e. the window). Draw operations still happen after those in
     /// `parent_pass`.
+    ///
+    /// Case `class == PassType::Layer(id)`: the new pass draws to layer `id`
+    /// instead of the window. `rect` is specified relative to `parent_pass` and
+    /// is mapped to the whole layer (without clipping by the parent).
     pub fn new_pass(&mut self, rect: Rect, offset: Offset, class: PassType) -> DrawIface<DS> {
         let pass = self.draw.new_pass(self.pass, rect, offset, class);
         DrawIface {
@@ -101,6 +106,34 @@ impl<'a, DS: DrawSharedImpl> DrawIface<'a, DS> {
             pass,
         }
     }
+
+    /// Add a transformed draw pass
+    ///
+    /// Adds a new draw pass, clipped to `rect` (relative to the parent pass
+    /// and intersected with the parent's "clip rect"), in which coordinates
+    /// are mapped to the parent's coordinate system via `transform`.
+    pub fn new_transform_pass(&mut self, rect: Rect, transform: Transform) -> DrawIface<DS> {
+        let pass = self.draw.new_transform_pass(self.pass, rect, transform);
+        DrawIface {
+            draw: &mut *self.draw,
+            shared: &mut *self.shared,
+            pass,
+        }
+    }
+
+    /// Add a draw pass with opacity
+    ///
+    /// Adds a new draw pass, clipped to `rect` (relative to the parent pass
+    /// and intersected with the parent's "clip rect"), whose contents are
+    /// composited onto the parent with opacity `alpha`.
+    pub fn new_alpha_pass(&mut self, rect: Rect, alpha: f32) -> DrawIface<DS> {
+        let pass = self.draw.new_alpha_pass(self.pass, rect, alpha);
+        DrawIface {
+            draw: &mut *self.draw,
+            shared: &mut *self.shared,
+            pass,
+        }
+    }
 }
 
 /// Base drawing interface for [`DrawIface`]
@@ -119,6 +152,11 @@ pub trait Draw {
     #[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
     fn get_fields_as_any_mut(&mut self) -> (&mut dyn Any, &mut dyn Any);
 
+    /// Access the shell's per-window draw state
+    #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
+    #[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
+    fn get_draw_impl_mut(&mut self) -> &mut dyn DrawImpl;
+
     /// Add a draw pass
     ///
     /// Adds a new draw pass. Passes affect draw order (operations in new passes
@@ -134,6 +172,10 @@ pub trait Draw {
     /// Case `class == PassType::Overlay`: the new pass is derived from the
     /// base pass (i.e. the window). Draw operations still happen after those in
     /// `parent_pass`.
+    ///
+    /// Case `class == PassType::Layer(id)`: the new pass draws to layer `id`
+    /// instead of the window. `rect` is specified relative to `parent_pass` and
+    /// is mapped to the whole layer (without clipping by the parent).
     #[cfg(feature = "stack_dst")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "stack_dst")))]
     fn new_dyn_pass<'b>(
@@ -152,6 +194,17 @@ pub trait Draw {
     /// [`DrawIface::new_pass`].)
     fn get_clip_rect(&self) -> Rect;
 
+    /// Get the transform from the current pass's coordinates to window
+    /// coordinates (physical pixels)
+    ///
+    /// This may be used to locate a widget within the window, for example to
+    /// position a native child surface (see [`crate::ShellWindow::raw_window_handle`]).
+    ///
+    /// The default implementation returns [`Transform::IDENTITY`].
+    fn get_transform(&self) -> Transform {
+        Transform::IDENTITY
+    }
+
     /// Draw a rectangle of uniform colour
     ///
     /// Note: where the implementation batches and/or re-orders draw calls,
@@ -164,6 +217,18 @@ pub trait Draw {
     /// The frame is defined by the area inside `outer` and not inside `inner`.
     fn frame(&mut self, outer: Quad, inner: Quad, col: Rgba);
 
+    /// Draw a rectangle with a gradient fill
+    ///
+    /// Gradient coordinates are relative to `rect`. Like [`Draw::rect`], this
+    /// is drawn behind most other items.
+    fn rect_gradient(&mut self, rect: Quad, gradient: &Gradient);
+
+    /// Draw a frame with a gradient fill
+    ///
+    /// The frame is defined by the area inside `outer` and not inside `inner`.
+    /// Gradient coordinates are relative to `outer`.
+    fn frame_gradient(&mut self, outer: Quad, inner: Quad, gradient: &Gradient);
+
     /// Draw the image in the given `rect`
     fn image(&mut self, id: ImageId, rect: Quad);
 
@@ -199,6 +264,10 @@ impl<'a, DS: DrawSharedImpl> Draw for DrawIface<'a, DS> {
         (self.draw, self.shared)
     }
 
+    fn get_draw_impl_mut(&mut self) -> &mut dyn DrawImpl {
+        self.draw
+    }
+
     #[cfg(feature = "stack_dst")]
     fn new_dyn_pass<'b>(
         &'b mut self,
@@ -215,12 +284,22 @@ impl<'a, DS: DrawSharedImpl> Draw for DrawIface<'a, DS> {
         self.draw.get_clip_rect(self.pass)
     }
 
+    fn get_transform(&self) -> Transform {
+        self.draw.get_transform(self.pass)
+    }
+
     fn rect(&mut self, rect: Quad, col: Rgba) {
         self.draw.rect(self.pass, rect, col);
     }
     fn frame(&mut self, outer: Quad, inner: Quad, col: Rgba) {
         self.draw.frame(self.pass, outer, inner, col);
     }
+    fn rect_gradient(&mut self, rect: Quad, gradient: &Gradient) {
+        self.draw.rect_gradient(self.pass, rect, gradient);
+    }
+    fn frame_gradient(&mut self, outer: Quad, inner: Quad, gradient: &Gradient) {
+        self.draw.frame_gradient(self.pass, outer, inner, gradient);
+    }
 
     fn image(&mut self, id: ImageId, rect: Quad) {
         self.shared.draw.draw_image(self.draw, self.pass, id, rect);
@@ -267,8 +346,10 @@ impl<'a, DS: DrawSharedImpl> Draw for DrawIface<'a, DS> {
 /// Draw operations take place over multiple render passes, identified by a
 /// handle of type [`PassId`]. In general the user only needs to pass this value
 /// into methods as required. [`DrawImpl::new_pass`] creates a new [`PassId`].
-#[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
-#[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
+///
+/// Implementations of extension traits should also be exposed via methods
+/// such as [`DrawImpl::as_rounded_impl`], allowing use of these extensions
+/// without knowledge of the shell's types.
 pub trait DrawImpl: Any {
     /// Add a draw pass
     ///
@@ -285,6 +366,10 @@ pub trait DrawImpl: Any {
     /// Case `class == PassType::Overlay`: the new pass is derived from the
     /// base pass (i.e. the window). Draw operations still happen after those in
     /// `parent_pass`.
+    ///
+    /// Case `class == PassType::Layer(id)`: the new pass draws to layer `id`
+    /// instead of the window. `rect` is specified relative to `parent_pass` and
+    /// is mapped to the whole layer (without clipping by the parent).
     fn new_pass(
         &mut self,
         parent_pass: PassId,
@@ -293,6 +378,28 @@ pub trait DrawImpl: Any {
         class: PassType,
     ) -> PassId;
 
+    /// Add a transformed draw pass
+    ///
+    /// Adds a new draw pass derived from `parent_pass`, clipped to `rect`
+    /// (relative to the parent and intersected with the parent's "clip rect").
+    /// Coordinates `p` in the new pass correspond to coordinates
+    /// `transform.map(p)` in the parent pass.
+    fn new_transform_pass(
+        &mut self,
+        parent_pass: PassId,
+        rect: Rect,
+        transform: Transform,
+    ) -> PassId;
+
+    /// Add a draw pass with opacity
+    ///
+    /// Adds a new draw pass derived from `parent_pass`, clipped to `rect`
+    /// (relative to the parent and intersected with the parent's "clip rect").
+    /// The contents of the new pass (including any passes derived from it)
+    /// are composited as a group onto the parent with opacity `alpha`, thus
+    /// overlapping contents do not show through each other.
+    fn new_alpha_pass(&mut self, parent_pass: PassId, rect: Rect, alpha: f32) -> PassId;
+
     /// Get drawable rect for a draw `pass`
     ///
     /// The result is in the current target's coordinate system, thus normally
@@ -302,9 +409,51 @@ pub trait DrawImpl: Any {
     /// [`DrawImpl::new_pass`].)
     fn get_clip_rect(&self, pass: PassId) -> Rect;
 
+    /// Get the transform from `pass` coordinates to window coordinates
+    ///
+    /// The default implementation returns [`Transform::IDENTITY`].
+    fn get_transform(&self, _pass: PassId) -> Transform {
+        Transform::IDENTITY
+    }
+
     /// Draw a rectangle of uniform colour
     fn rect(&mut self, pass: PassId, rect: Quad, col: Rgba);
 
     /// Draw a frame of uniform colour
     fn frame(&mut self, pass: PassId, outer: Quad, inner: Quad, col: Rgba);
+
+    /// Draw a rectangle with a gradient fill
+    fn rect_gradient(&mut self, pass: PassId, rect: Quad, gradient: &Gradient);
+
+    /// Draw a frame with a gradient fill
+    fn frame_gradient(&mut self, pass: PassId, outer: Quad, inner: Quad, gradient: &Gradient);
+
+    /// Access as [`DrawRoundedImpl`], if supported
+    ///
+    /// Implementations of [`DrawRoundedImpl`] should override this to return
+    /// `Some(self)`. The default implementation returns `None`.
+    #[inline]
+    fn as_rounded_impl(&mut self) -> Option<&mut dyn DrawRoundedImpl> {
+        None
+    }
+
+    /// Access as [`DrawShadedImpl`], if supported
+    ///
+    /// Implementations of [`DrawShadedImpl`] should override this to return
+    /// `Some(self)`. The default implementation returns `None`.
+    #[inline]
+    fn as_shaded_impl(&mut self) -> Option<&mut dyn DrawShadedImpl> {
+        None
+    }
+
+    /// Access shell-specific draw extensions, if any
+    ///
+    /// This allows shells to support operations beyond the scope of the
+    /// [`Draw`] traits (for example, `kas_wgpu` supports render callbacks).
+    /// Users downcast the result to the expected type. The default
+    /// implementation returns `None`.
+    #[inline]
+    fn extension_mut(&mut self) -> Option<&mut dyn Any> {
+        None
+    }
 }
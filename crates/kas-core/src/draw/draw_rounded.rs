@@ -6,8 +6,14 @@
 //! Drawing APIs — draw rounded
 
 use super::color::Rgba;
-use super::{Draw, DrawIface, DrawImpl, DrawSharedImpl, PassId};
-use crate::geom::{Quad, Vec2};
+#[cfg(feature = "stack_dst")]
+use super::PassType;
+use super::{Draw, DrawIface, DrawImpl, DrawSharedImpl, Gradient, ImageId, PassId, Stroke};
+#[cfg(feature = "stack_dst")]
+use crate::geom::Offset;
+use crate::geom::{Quad, Rect, Transform, Vec2};
+use crate::text::{Effect, TextDisplay};
+use std::any::Any;
 
 /// Extension over [`Draw`] for rounded shapes
 ///
@@ -23,6 +29,17 @@ pub trait DrawRounded: Draw {
     /// preferred.
     fn rounded_line(&mut self, p1: Vec2, p2: Vec2, radius: f32, col: Rgba);
 
+    /// Draw a stroked polyline of uniform colour
+    ///
+    /// The path passes through each of `points` in turn. If `closed`, the
+    /// path returns from the last point to the first. The `stroke` specifies
+    /// width, dash pattern and cap style; dash patterns continue around
+    /// corners. Segments are joined with round joins.
+    ///
+    /// Where segments overlap (e.g. at joins), a translucent colour may be
+    /// drawn more than once.
+    fn polyline(&mut self, points: &[Vec2], closed: bool, stroke: &Stroke, col: Rgba);
+
     /// Draw a circle or oval of uniform colour
     ///
     /// More generally, this shape is an axis-aligned oval which may be hollow.
@@ -64,6 +81,26 @@ pub trait DrawRounded: Draw {
     /// Note: this is drawn *before* other drawables, allowing it to be used
     /// for shadows without masking.
     fn rounded_frame_2col(&mut self, outer: Quad, inner: Quad, c1: Rgba, c2: Rgba);
+
+    /// Draw a drop shadow
+    ///
+    /// The shadow is the shape of a rect with rounded corners of the given
+    /// `radius`, blurred with a Gaussian kernel of standard deviation `blur`.
+    /// The result extends up to `3 * blur` beyond `rect`.
+    ///
+    /// Note: this is drawn *before* other drawables, allowing it to be used
+    /// under the element casting the shadow.
+    fn shadow(&mut self, rect: Quad, radius: f32, blur: f32, col: Rgba);
+
+    /// Blur the background within `rect`
+    ///
+    /// Content previously drawn *by other passes* to the same target within
+    /// `rect` is blurred with a Gaussian kernel of standard deviation `blur`.
+    /// This happens before any drawing of this pass, thus is typically used
+    /// beneath a translucent background, e.g. for pop-ups.
+    ///
+    /// Support is optional: the implementation may do nothing.
+    fn blur(&mut self, rect: Quad, blur: f32);
 }
 
 impl<'a, DS: DrawSharedImpl> DrawRounded for DrawIface<'a, DS>
@@ -75,6 +112,10 @@ where
         self.draw.rounded_line(self.pass, p1, p2, radius, col);
     }
     #[inline]
+    fn polyline(&mut self, points: &[Vec2], closed: bool, stroke: &Stroke, col: Rgba) {
+        self.draw.polyline(self.pass, points, closed, stroke, col);
+    }
+    #[inline]
     fn circle(&mut self, rect: Quad, inner_radius: f32, col: Rgba) {
         self.draw.circle(self.pass, rect, inner_radius, col);
     }
@@ -92,6 +133,153 @@ where
         self.draw
             .rounded_frame_2col(self.pass, outer, inner, c1, c2);
     }
+    #[inline]
+    fn shadow(&mut self, rect: Quad, radius: f32, blur: f32, col: Rgba) {
+        self.draw.shadow(self.pass, rect, radius, blur, col);
+    }
+    #[inline]
+    fn blur(&mut self, rect: Quad, blur: f32) {
+        self.draw.blur(self.pass, rect, blur);
+    }
+}
+
+/// Shell-independent [`DrawRounded`] interface
+///
+/// This wraps a `&mut dyn Draw` (as available from
+/// [`DrawHandle::draw_device`](super::DrawHandle::draw_device)), providing
+/// [`DrawRounded`] over any shell supporting it. Unlike
+/// [`DrawIface::downcast_from`], this does not require naming the shell's
+/// draw types.
+pub struct DrawRoundedDyn<'a> {
+    draw: &'a mut dyn Draw,
+}
+
+impl<'a> DrawRoundedDyn<'a> {
+    /// Attempt to wrap a `&mut dyn Draw`
+    ///
+    /// Returns `None` if the shell does not support [`DrawRoundedImpl`].
+    pub fn downcast_from(obj: &'a mut dyn Draw) -> Option<Self> {
+        obj.get_draw_impl_mut().as_rounded_impl()?;
+        Some(DrawRoundedDyn { draw: obj })
+    }
+
+    /// Reborrow with a new lifetime
+    pub fn reborrow<'b>(&'b mut self) -> DrawRoundedDyn<'b>
+    where
+        'a: 'b,
+    {
+        DrawRoundedDyn {
+            draw: &mut *self.draw,
+        }
+    }
+
+    fn rounded(&mut self) -> (&mut dyn DrawRoundedImpl, PassId) {
+        let pass = self.draw.get_pass();
+        let draw = self.draw.get_draw_impl_mut().as_rounded_impl();
+        (draw.expect("DrawRoundedImpl support"), pass)
+    }
+}
+
+impl<'a> Draw for DrawRoundedDyn<'a> {
+    fn get_pass(&self) -> PassId {
+        self.draw.get_pass()
+    }
+
+    fn get_fields_as_any_mut(&mut self) -> (&mut dyn Any, &mut dyn Any) {
+        self.draw.get_fields_as_any_mut()
+    }
+
+    fn get_draw_impl_mut(&mut self) -> &mut dyn DrawImpl {
+        self.draw.get_draw_impl_mut()
+    }
+
+    #[cfg(feature = "stack_dst")]
+    fn new_dyn_pass<'b>(
+        &'b mut self,
+        rect: Rect,
+        offset: Offset,
+        class: PassType,
+    ) -> stack_dst::ValueA<dyn Draw + 'b, [usize; 4]> {
+        self.draw.new_dyn_pass(rect, offset, class)
+    }
+
+    fn get_clip_rect(&self) -> Rect {
+        self.draw.get_clip_rect()
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.draw.get_transform()
+    }
+
+    fn rect(&mut self, rect: Quad, col: Rgba) {
+        self.draw.rect(rect, col);
+    }
+    fn frame(&mut self, outer: Quad, inner: Quad, col: Rgba) {
+        self.draw.frame(outer, inner, col);
+    }
+    fn rect_gradient(&mut self, rect: Quad, gradient: &Gradient) {
+        self.draw.rect_gradient(rect, gradient);
+    }
+    fn frame_gradient(&mut self, outer: Quad, inner: Quad, gradient: &Gradient) {
+        self.draw.frame_gradient(outer, inner, gradient);
+    }
+
+    fn image(&mut self, id: ImageId, rect: Quad) {
+        self.draw.image(id, rect);
+    }
+
+    fn text(&mut self, pos: Vec2, text: &TextDisplay, col: Rgba) {
+        self.draw.text(pos, text, col);
+    }
+
+    fn text_col_effects(
+        &mut self,
+        pos: Vec2,
+        text: &TextDisplay,
+        col: Rgba,
+        effects: &[Effect<()>],
+    ) {
+        self.draw.text_col_effects(pos, text, col, effects);
+    }
+
+    fn text_effects(&mut self, pos: Vec2, text: &TextDisplay, effects: &[Effect<Rgba>]) {
+        self.draw.text_effects(pos, text, effects);
+    }
+}
+
+impl<'a> DrawRounded for DrawRoundedDyn<'a> {
+    fn rounded_line(&mut self, p1: Vec2, p2: Vec2, radius: f32, col: Rgba) {
+        let (draw, pass) = self.rounded();
+        draw.rounded_line(pass, p1, p2, radius, col);
+    }
+    fn polyline(&mut self, points: &[Vec2], closed: bool, stroke: &Stroke, col: Rgba) {
+        let (draw, pass) = self.rounded();
+        draw.polyline(pass, points, closed, stroke, col);
+    }
+    fn circle(&mut self, rect: Quad, inner_radius: f32, col: Rgba) {
+        let (draw, pass) = self.rounded();
+        draw.circle(pass, rect, inner_radius, col);
+    }
+    fn circle_2col(&mut self, rect: Quad, col1: Rgba, col2: Rgba) {
+        let (draw, pass) = self.rounded();
+        draw.circle_2col(pass, rect, col1, col2);
+    }
+    fn rounded_frame(&mut self, outer: Quad, inner: Quad, inner_radius: f32, col: Rgba) {
+        let (draw, pass) = self.rounded();
+        draw.rounded_frame(pass, outer, inner, inner_radius, col);
+    }
+    fn rounded_frame_2col(&mut self, outer: Quad, inner: Quad, c1: Rgba, c2: Rgba) {
+        let (draw, pass) = self.rounded();
+        draw.rounded_frame_2col(pass, outer, inner, c1, c2);
+    }
+    fn shadow(&mut self, rect: Quad, radius: f32, blur: f32, col: Rgba) {
+        let (draw, pass) = self.rounded();
+        draw.shadow(pass, rect, radius, blur, col);
+    }
+    fn blur(&mut self, rect: Quad, blur: f32) {
+        let (draw, pass) = self.rounded();
+        draw.blur(pass, rect, blur);
+    }
 }
 
 /// Drawing commands for rounded shapes
@@ -101,12 +289,13 @@ where
 /// The primitives provided by this trait are partially transparent.
 /// If the implementation buffers draw commands, it should draw these
 /// primitives after solid primitives.
-#[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
-#[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
 pub trait DrawRoundedImpl: DrawImpl {
     /// Draw a line with rounded ends and uniform colour
     fn rounded_line(&mut self, pass: PassId, p1: Vec2, p2: Vec2, radius: f32, col: Rgba);
 
+    /// Draw a stroked polyline of uniform colour
+    fn polyline(&mut self, pass: PassId, points: &[Vec2], closed: bool, stroke: &Stroke, col: Rgba);
+
     /// Draw a circle or oval of uniform colour
     fn circle(&mut self, pass: PassId, rect: Quad, inner_radius: f32, col: Rgba);
 
@@ -118,4 +307,10 @@ pub trait DrawRoundedImpl: DrawImpl {
 
     /// Draw a frame with rounded corners with two colours
     fn rounded_frame_2col(&mut self, pass: PassId, outer: Quad, inner: Quad, c1: Rgba, c2: Rgba);
+
+    /// Draw a drop shadow
+    fn shadow(&mut self, pass: PassId, rect: Quad, radius: f32, blur: f32, col: Rgba);
+
+    /// Blur the background within `rect`
+    fn blur(&mut self, pass: PassId, rect: Quad, blur: f32);
 }
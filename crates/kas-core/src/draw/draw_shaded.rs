@@ -0,0 +1,251 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Drawing APIs — shaded drawing
+
+use super::color::Rgba;
+#[cfg(feature = "stack_dst")]
+use super::PassType;
+use super::{Draw, DrawIface, DrawImpl, DrawSharedImpl, Gradient, ImageId, PassId};
+#[cfg(feature = "stack_dst")]
+use crate::geom::Offset;
+use crate::geom::{Quad, Rect, Transform, Vec2};
+use crate::text::{Effect, TextDisplay};
+use std::any::Any;
+
+/// Extension over [`Draw`] for shaded shapes
+///
+/// All methods draw some feature.
+///
+/// Methods are parameterised via a pair of normals, `(inner, outer)`, which
+/// specify the surface normal direction at inner and outer edges of the feature
+/// respectively (with interpolation between these edges). These have values
+/// from the closed range `[-1, 1]`, where -1 points towards the inside of the
+/// feature, 1 points away from the feature, and 0 is perpendicular to the
+/// screen towards the viewer.
+pub trait DrawShaded: Draw {
+    /// Add a shaded square to the draw buffer
+    ///
+    /// For shading purposes, the mid-point is considered the inner edge.
+    fn shaded_square(&mut self, rect: Quad, norm: (f32, f32), col: Rgba);
+
+    /// Add a shaded circle to the draw buffer
+    ///
+    /// For shading purposes, the mid-point is considered the inner edge.
+    fn shaded_circle(&mut self, rect: Quad, norm: (f32, f32), col: Rgba);
+
+    /// Add a shaded frame with square corners to the draw buffer
+    fn shaded_square_frame(
+        &mut self,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        outer_col: Rgba,
+        inner_col: Rgba,
+    );
+
+    /// Add a shaded frame with rounded corners to the draw buffer
+    fn shaded_round_frame(&mut self, outer: Quad, inner: Quad, norm: (f32, f32), col: Rgba);
+}
+
+impl<'a, DS: DrawSharedImpl> DrawShaded for DrawIface<'a, DS>
+where
+    DS::Draw: DrawShadedImpl,
+{
+    fn shaded_square(&mut self, rect: Quad, norm: (f32, f32), col: Rgba) {
+        self.draw.shaded_square(self.pass, rect, norm, col);
+    }
+
+    fn shaded_circle(&mut self, rect: Quad, norm: (f32, f32), col: Rgba) {
+        self.draw.shaded_circle(self.pass, rect, norm, col);
+    }
+
+    fn shaded_square_frame(
+        &mut self,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        outer_col: Rgba,
+        inner_col: Rgba,
+    ) {
+        self.draw
+            .shaded_square_frame(self.pass, outer, inner, norm, outer_col, inner_col);
+    }
+
+    fn shaded_round_frame(&mut self, outer: Quad, inner: Quad, norm: (f32, f32), col: Rgba) {
+        self.draw
+            .shaded_round_frame(self.pass, outer, inner, norm, col);
+    }
+}
+
+/// Shell-independent [`DrawShaded`] interface
+///
+/// This wraps a `&mut dyn Draw`, providing [`DrawShaded`] over any shell
+/// supporting it. See also [`DrawRoundedDyn`](super::DrawRoundedDyn).
+pub struct DrawShadedDyn<'a> {
+    draw: &'a mut dyn Draw,
+}
+
+impl<'a> DrawShadedDyn<'a> {
+    /// Attempt to wrap a `&mut dyn Draw`
+    ///
+    /// Returns `None` if the shell does not support [`DrawShadedImpl`].
+    pub fn downcast_from(obj: &'a mut dyn Draw) -> Option<Self> {
+        obj.get_draw_impl_mut().as_shaded_impl()?;
+        Some(DrawShadedDyn { draw: obj })
+    }
+
+    /// Reborrow with a new lifetime
+    pub fn reborrow<'b>(&'b mut self) -> DrawShadedDyn<'b>
+    where
+        'a: 'b,
+    {
+        DrawShadedDyn {
+            draw: &mut *self.draw,
+        }
+    }
+
+    fn shaded(&mut self) -> (&mut dyn DrawShadedImpl, PassId) {
+        let pass = self.draw.get_pass();
+        let draw = self.draw.get_draw_impl_mut().as_shaded_impl();
+        (draw.expect("DrawShadedImpl support"), pass)
+    }
+}
+
+impl<'a> Draw for DrawShadedDyn<'a> {
+    fn get_pass(&self) -> PassId {
+        self.draw.get_pass()
+    }
+
+    fn get_fields_as_any_mut(&mut self) -> (&mut dyn Any, &mut dyn Any) {
+        self.draw.get_fields_as_any_mut()
+    }
+
+    fn get_draw_impl_mut(&mut self) -> &mut dyn DrawImpl {
+        self.draw.get_draw_impl_mut()
+    }
+
+    #[cfg(feature = "stack_dst")]
+    fn new_dyn_pass<'b>(
+        &'b mut self,
+        rect: Rect,
+        offset: Offset,
+        class: PassType,
+    ) -> stack_dst::ValueA<dyn Draw + 'b, [usize; 4]> {
+        self.draw.new_dyn_pass(rect, offset, class)
+    }
+
+    fn get_clip_rect(&self) -> Rect {
+        self.draw.get_clip_rect()
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.draw.get_transform()
+    }
+
+    fn rect(&mut self, rect: Quad, col: Rgba) {
+        self.draw.rect(rect, col);
+    }
+    fn frame(&mut self, outer: Quad, inner: Quad, col: Rgba) {
+        self.draw.frame(outer, inner, col);
+    }
+    fn rect_gradient(&mut self, rect: Quad, gradient: &Gradient) {
+        self.draw.rect_gradient(rect, gradient);
+    }
+    fn frame_gradient(&mut self, outer: Quad, inner: Quad, gradient: &Gradient) {
+        self.draw.frame_gradient(outer, inner, gradient);
+    }
+
+    fn image(&mut self, id: ImageId, rect: Quad) {
+        self.draw.image(id, rect);
+    }
+
+    fn text(&mut self, pos: Vec2, text: &TextDisplay, col: Rgba) {
+        self.draw.text(pos, text, col);
+    }
+
+    fn text_col_effects(
+        &mut self,
+        pos: Vec2,
+        text: &TextDisplay,
+        col: Rgba,
+        effects: &[Effect<()>],
+    ) {
+        self.draw.text_col_effects(pos, text, col, effects);
+    }
+
+    fn text_effects(&mut self, pos: Vec2, text: &TextDisplay, effects: &[Effect<Rgba>]) {
+        self.draw.text_effects(pos, text, effects);
+    }
+}
+
+impl<'a> DrawShaded for DrawShadedDyn<'a> {
+    fn shaded_square(&mut self, rect: Quad, norm: (f32, f32), col: Rgba) {
+        let (draw, pass) = self.shaded();
+        draw.shaded_square(pass, rect, norm, col);
+    }
+
+    fn shaded_circle(&mut self, rect: Quad, norm: (f32, f32), col: Rgba) {
+        let (draw, pass) = self.shaded();
+        draw.shaded_circle(pass, rect, norm, col);
+    }
+
+    fn shaded_square_frame(
+        &mut self,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        outer_col: Rgba,
+        inner_col: Rgba,
+    ) {
+        let (draw, pass) = self.shaded();
+        draw.shaded_square_frame(pass, outer, inner, norm, outer_col, inner_col);
+    }
+
+    fn shaded_round_frame(&mut self, outer: Quad, inner: Quad, norm: (f32, f32), col: Rgba) {
+        let (draw, pass) = self.shaded();
+        draw.shaded_round_frame(pass, outer, inner, norm, col);
+    }
+}
+
+/// Drawing commands for shaded shapes
+///
+/// This trait is an extension over [`DrawImpl`] providing solid shaded shapes.
+///
+/// Some drawing primitives (the "round" ones) are partially transparent.
+/// If the implementation buffers draw commands, it should draw these
+/// primitives after solid primitives.
+///
+/// Methods are parameterised via a pair of normals, `(inner, outer)`. These may
+/// have values from the closed range `[-1, 1]`, where -1 points inwards,
+/// 0 is perpendicular to the screen towards the viewer, and 1 points outwards.
+pub trait DrawShadedImpl: DrawImpl {
+    /// Add a shaded square to the draw buffer
+    fn shaded_square(&mut self, pass: PassId, rect: Quad, norm: (f32, f32), col: Rgba);
+
+    /// Add a shaded circle to the draw buffer
+    fn shaded_circle(&mut self, pass: PassId, rect: Quad, norm: (f32, f32), col: Rgba);
+
+    /// Add a square shaded frame to the draw buffer.
+    fn shaded_square_frame(
+        &mut self,
+        pass: PassId,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        outer_col: Rgba,
+        inner_col: Rgba,
+    );
+
+    /// Add a rounded shaded frame to the draw buffer.
+    fn shaded_round_frame(
+        &mut self,
+        pass: PassId,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        col: Rgba,
+    );
+}
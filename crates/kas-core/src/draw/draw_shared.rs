@@ -6,7 +6,7 @@
 //! Drawing APIs — shared draw state
 
 use super::color::Rgba;
-use super::{images, DrawImpl, ImageError, ImageFormat, ImageId, PassId};
+use super::{images, DrawImpl, ImageError, ImageFormat, ImageId, OwnedImageId, PassId};
 use crate::geom::{Quad, Size, Vec2};
 use crate::text::{Effect, TextDisplay};
 use std::any::Any;
@@ -28,8 +28,6 @@ pub struct SharedState<DS: DrawSharedImpl> {
     images: images::Images,
 }
 
-#[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
-#[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
 impl<DS: DrawSharedImpl> SharedState<DS> {
     /// Construct (this is only called by the shell)
     pub fn new(draw: DS) -> Self {
@@ -68,6 +66,33 @@ pub trait DrawShared {
     /// This reduces the reference count and frees if zero.
     fn image_free_from_path(&mut self, path: &Path);
 
+    /// Allocate a layer
+    ///
+    /// A layer is an image whose contents are drawn via a draw pass of type
+    /// [`PassType::Layer`]. Like other images, it may be drawn with
+    /// [`DrawHandle::image`] and must be freed with [`DrawShared::image_free`].
+    ///
+    /// Not all graphics backends support layers; in this case
+    /// [`ImageError::Unsupported`] is returned (as by the default
+    /// implementation).
+    ///
+    /// [`PassType::Layer`]: super::PassType::Layer
+    /// [`DrawHandle::image`]: super::DrawHandle::image
+    fn layer_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
+        let _ = size;
+        Err(ImageError::Unsupported)
+    }
+
+    /// Allocate a layer, freed when the returned identifier is dropped
+    ///
+    /// This is otherwise identical to [`DrawShared::layer_alloc`], and is
+    /// useful where the owner (e.g. a widget) has no opportunity to call
+    /// [`DrawShared::image_free`] before being dropped.
+    fn layer_alloc_owned(&mut self, size: (u32, u32)) -> Result<OwnedImageId, ImageError> {
+        let _ = size;
+        Err(ImageError::Unsupported)
+    }
+
     /// Free an image
     fn image_free(&mut self, id: ImageId);
 
@@ -78,6 +103,7 @@ pub trait DrawShared {
 impl<DS: DrawSharedImpl> DrawShared for SharedState<DS> {
     #[inline]
     fn image_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
+        self.images.free_dropped(&mut self.draw);
         self.draw.image_alloc(size)
     }
 
@@ -88,6 +114,7 @@ impl<DS: DrawSharedImpl> DrawShared for SharedState<DS> {
 
     #[inline]
     fn image_from_path(&mut self, path: &Path) -> Result<ImageId, ImageError> {
+        self.images.free_dropped(&mut self.draw);
         self.images.load_path(&mut self.draw, path)
     }
 
@@ -96,6 +123,17 @@ impl<DS: DrawSharedImpl> DrawShared for SharedState<DS> {
         self.images.remove_path(&mut self.draw, path);
     }
 
+    #[inline]
+    fn layer_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
+        self.images.free_dropped(&mut self.draw);
+        self.draw.layer_alloc(size)
+    }
+
+    fn layer_alloc_owned(&mut self, size: (u32, u32)) -> Result<OwnedImageId, ImageError> {
+        let id = self.layer_alloc(size)?;
+        Ok(self.images.owned(id))
+    }
+
     #[inline]
     fn image_free(&mut self, id: ImageId) {
         self.images.remove_id(&mut self.draw, id);
@@ -110,8 +148,6 @@ impl<DS: DrawSharedImpl> DrawShared for SharedState<DS> {
 /// Trait over shared data of draw object
 ///
 /// This is typically used via [`SharedState`].
-#[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
-#[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
 pub trait DrawSharedImpl: Any {
     type Draw: DrawImpl;
 
@@ -126,7 +162,15 @@ pub trait DrawSharedImpl: Any {
     /// called again to update the image contents.
     fn image_upload(&mut self, id: ImageId, data: &[u8], format: ImageFormat);
 
-    /// Free an image allocation
+    /// Allocate a layer
+    ///
+    /// By default this is unsupported.
+    fn layer_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
+        let _ = size;
+        Err(ImageError::Unsupported)
+    }
+
+    /// Free an image or layer allocation
     fn image_free(&mut self, id: ImageId);
 
     /// Query an image's size
@@ -0,0 +1,114 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Drawing APIs — gradient fills
+
+use super::color::Rgba;
+use crate::geom::Vec2;
+
+/// Shape of a [`Gradient`]
+///
+/// Coordinates are relative to the filled rect: `(0, 0)` is the top-left
+/// corner and `(1, 1)` is the bottom-right corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GradientShape {
+    /// A linear gradient
+    ///
+    /// Offset `0.0` is at `start` and offset `1.0` is at `end`; colour is
+    /// constant along lines perpendicular to `start → end`.
+    Linear { start: Vec2, end: Vec2 },
+    /// A radial (elliptical) gradient
+    ///
+    /// Offset `0.0` is at `centre` and offset `1.0` is on the ellipse with
+    /// semi-axes `radius`.
+    Radial { centre: Vec2, radius: Vec2 },
+}
+
+/// A gradient fill
+///
+/// A gradient is a [`GradientShape`] together with a list of colour stops.
+/// Colour is interpolated linearly between stops; before the first stop and
+/// after the last the colour of that stop is used.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    /// The gradient's shape
+    pub shape: GradientShape,
+    stops: Vec<(f32, Rgba)>,
+}
+
+impl Gradient {
+    /// Construct from a shape and a list of colour stops
+    ///
+    /// Each stop is an `(offset, colour)` pair. Stops must be non-empty and
+    /// sorted by ascending offset. Offsets should normally be in the range
+    /// `0..=1`; some backends clamp to this range.
+    pub fn new(shape: GradientShape, stops: Vec<(f32, Rgba)>) -> Self {
+        assert!(!stops.is_empty(), "Gradient::new: no stops");
+        assert!(
+            stops.windows(2).all(|w| w[0].0 <= w[1].0),
+            "Gradient::new: stops not in ascending order"
+        );
+        Gradient { shape, stops }
+    }
+
+    /// Construct a linear gradient from `start` to `end` with two stops
+    pub fn linear(start: Vec2, end: Vec2, col_start: Rgba, col_end: Rgba) -> Self {
+        let shape = GradientShape::Linear { start, end };
+        Gradient::new(shape, vec![(0.0, col_start), (1.0, col_end)])
+    }
+
+    /// Construct a vertical gradient from `top` to `bottom`
+    pub fn vertical(top: Rgba, bottom: Rgba) -> Self {
+        Self::linear(Vec2::ZERO, Vec2(0.0, 1.0), top, bottom)
+    }
+
+    /// Construct a horizontal gradient from `left` to `right`
+    pub fn horizontal(left: Rgba, right: Rgba) -> Self {
+        Self::linear(Vec2::ZERO, Vec2(1.0, 0.0), left, right)
+    }
+
+    /// Construct a radial gradient with two stops
+    pub fn radial(centre: Vec2, radius: Vec2, inner: Rgba, outer: Rgba) -> Self {
+        let shape = GradientShape::Radial { centre, radius };
+        Gradient::new(shape, vec![(0.0, inner), (1.0, outer)])
+    }
+
+    /// Add a colour stop, builder style
+    ///
+    /// The stop is inserted after any existing stops with the same offset.
+    pub fn with_stop(mut self, offset: f32, col: Rgba) -> Self {
+        let index = self.stops.partition_point(|s| s.0 <= offset);
+        self.stops.insert(index, (offset, col));
+        self
+    }
+
+    /// Access colour stops
+    ///
+    /// This list is never empty and is sorted by ascending offset.
+    #[inline]
+    pub fn stops(&self) -> &[(f32, Rgba)] {
+        &self.stops
+    }
+
+    /// Evaluate the colour at a given offset
+    pub fn color_at(&self, offset: f32) -> Rgba {
+        let index = self.stops.partition_point(|s| s.0 <= offset);
+        if index == 0 {
+            return self.stops[0].1;
+        } else if index == self.stops.len() {
+            return self.stops[index - 1].1;
+        }
+        let (s0, c0) = self.stops[index - 1];
+        let (s1, c1) = self.stops[index];
+        let f = (offset - s0) / (s1 - s0);
+        let mix = |a: f32, b: f32| a + (b - a) * f;
+        Rgba::rgba(
+            mix(c0.r, c1.r),
+            mix(c0.g, c1.g),
+            mix(c0.b, c1.b),
+            mix(c0.a, c1.a),
+        )
+    }
+}
@@ -10,9 +10,10 @@ use std::ops::{Bound, Deref, DerefMut, Range, RangeBounds};
 
 use crate::dir::Direction;
 use crate::draw::{color::Rgb, Draw, ImageId, PassType};
-use crate::geom::{Coord, Offset, Rect, Size};
+use crate::event::Severity;
+use crate::geom::{Coord, Offset, Rect, Size, Transform, Vec2};
 use crate::layout::{AxisInfo, FrameRules, Margins, SizeRules};
-use crate::text::{AccelString, Text, TextApi, TextDisplay};
+use crate::text::{AccelString, Annotation, Text, TextApi, TextDisplay};
 
 // for doc use
 #[allow(unused)]
@@ -126,6 +127,21 @@ impl Default for TextClass {
     }
 }
 
+/// Class of a widget-inspector element
+///
+/// See [`DrawHandle::inspector`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InspectorClass {
+    /// Outline of a widget
+    Bounds,
+    /// Outline of an ancestor of the selected widget
+    Ancestor,
+    /// Outline of the selected widget
+    Selected,
+    /// Background of the inspector's side panel
+    Panel,
+}
+
 /// A handle to the active theme, used for sizing
 ///
 /// The shell provides widgets a `&dyn SizeHandle` in [`crate::Layout::size_rules`].
@@ -276,7 +292,8 @@ pub trait SizeHandle {
 /// -   [`Self::size_handle`] provides access to a [`SizeHandle`]
 /// -   [`Self::draw_device`] provides a lower-level interface for draw operations
 /// -   [`Self::new_pass`], [`DrawHandleExt::with_clip_region`],
-///     [`DrawHandleExt::with_overlay`] construct new draw passes
+///     [`DrawHandleExt::with_overlay`], [`DrawHandleExt::with_transform`],
+///     [`DrawHandleExt::with_alpha`] construct new draw passes
 /// -   [`Self::get_clip_rect`] returns the clip rect
 ///
 /// See also [`SizeHandle`].
@@ -298,6 +315,33 @@ pub trait DrawHandle {
         f: &mut dyn FnMut(&mut dyn DrawHandle),
     );
 
+    /// Add a transformed draw pass
+    ///
+    /// The default implementation ignores scaling, adding a
+    /// [`PassType::Clip`] pass offset by the translation.
+    #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
+    #[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
+    fn new_transform_pass(
+        &mut self,
+        rect: Rect,
+        transform: Transform,
+        f: &mut dyn FnMut(&mut dyn DrawHandle),
+    ) {
+        let offset = Offset::from(transform.translate * -1.0);
+        self.new_pass(rect, offset, PassType::Clip, f);
+    }
+
+    /// Add a draw pass with opacity
+    ///
+    /// The default implementation ignores `alpha`, adding an opaque
+    /// [`PassType::Clip`] pass.
+    #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
+    #[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
+    fn new_alpha_pass(&mut self, rect: Rect, alpha: f32, f: &mut dyn FnMut(&mut dyn DrawHandle)) {
+        let _ = alpha;
+        self.new_pass(rect, Offset::ZERO, PassType::Clip, f);
+    }
+
     /// Target area for drawing
     ///
     /// Drawing is restricted to this [`Rect`], which may be the whole window, a
@@ -327,6 +371,105 @@ pub trait DrawHandle {
     /// of size `inner_margin` that is expected to be present around this box.
     fn selection_box(&mut self, rect: Rect);
 
+    /// Draw a rubber-band (drag-select) rectangle
+    ///
+    /// This is drawn over the content within `rect`, usually as a translucent
+    /// fill with a border.
+    ///
+    /// The default implementation draws a [`Self::selection_box`].
+    fn rubber_band(&mut self, rect: Rect) {
+        self.selection_box(rect);
+    }
+
+    /// Draw a drop-position marker
+    ///
+    /// This indicates where a dragged item would be placed, for example as
+    /// a line between items of a list (filling `rect`).
+    ///
+    /// The default implementation draws a [`Self::separator`].
+    fn drop_marker(&mut self, rect: Rect) {
+        self.separator(rect);
+    }
+
+    /// Draw the background of a group header
+    ///
+    /// This is an opaque background over `rect` (group headers may be drawn
+    /// over other content), with a separator along the trailing edge: the
+    /// bottom edge if `vert`, otherwise the right edge.
+    ///
+    /// The default implementation draws an [`Self::outer_frame`].
+    fn group_header(&mut self, rect: Rect, vert: bool) {
+        let _ = vert;
+        self.outer_frame(rect);
+    }
+
+    /// Draw an overflow indicator
+    ///
+    /// This is drawn over content within `rect` where that content was
+    /// assigned insufficient space, along the right edge if `axes.0` and the
+    /// bottom edge if `axes.1`.
+    ///
+    /// The default implementation draws nothing.
+    fn overflow_indicator(&mut self, rect: Rect, axes: (bool, bool)) {
+        let _ = (rect, axes);
+    }
+
+    /// Draw an invalid-input indicator
+    ///
+    /// This is drawn over content within `rect` (usually an edit field) whose
+    /// value is invalid, for example as an underline along the bottom edge.
+    ///
+    /// The default implementation draws nothing.
+    fn invalid_marker(&mut self, rect: Rect) {
+        let _ = rect;
+    }
+
+    /// Draw a toast notification's severity marker
+    ///
+    /// This is drawn within the overlay of a toast notification (see
+    /// [`crate::event::Manager::notify`]), for example as a bar along the
+    /// leading edge of `rect` in a colour depending on `severity`.
+    ///
+    /// The default implementation draws nothing.
+    fn toast(&mut self, rect: Rect, severity: Severity) {
+        let _ = (rect, severity);
+    }
+
+    /// Draw the background of a graph node
+    ///
+    /// This is an opaque background with a frame filling `rect`, over which
+    /// the node's contents are drawn. It may be highlighted if `selected`.
+    ///
+    /// The default implementation draws an [`Self::outer_frame`], with a
+    /// [`Self::selection_box`] if `selected`.
+    fn graph_node(&mut self, rect: Rect, selected: bool) {
+        self.outer_frame(rect);
+        if selected {
+            self.selection_box(rect);
+        }
+    }
+
+    /// Draw a connection port of a graph node
+    ///
+    /// The port fills `rect` (usually a small square centred on an edge of
+    /// the node). It may be drawn differently when `connected`.
+    ///
+    /// The default implementation draws a [`Self::radiobox`].
+    fn graph_port(&mut self, rect: Rect, connected: bool, state: InputState) {
+        self.radiobox(rect, connected, state);
+    }
+
+    /// Draw a link between ports of graph nodes
+    ///
+    /// The link follows `path`, a polyline (usually approximating a curve).
+    /// A `pending` link (one being created by the user) may be drawn
+    /// differently, for example dashed.
+    ///
+    /// The default implementation draws nothing.
+    fn graph_link(&mut self, path: &[Vec2], pending: bool) {
+        let _ = (path, pending);
+    }
+
     /// Draw some text using the standard font
     ///
     /// The dimensions required for this text may be queried with [`SizeHandle::text_bound`].
@@ -365,6 +508,43 @@ pub trait DrawHandle {
         state: InputState,
     );
 
+    /// Draw decorations for text annotations
+    ///
+    /// This should be called after drawing the `text` itself, with the same
+    /// `pos`. Each annotation is drawn according to its
+    /// [`crate::text::AnnotationClass`], for example as a squiggly underline.
+    ///
+    /// The default implementation draws nothing.
+    fn text_annotations(
+        &mut self,
+        pos: Coord,
+        text: &TextDisplay,
+        annotations: &[Annotation],
+        state: InputState,
+    ) {
+        let _ = (pos, text, annotations, state);
+    }
+
+    /// Draw text with annotations
+    ///
+    /// This draws `text` as [`DrawHandle::text_effects`] does, except that
+    /// text within [`crate::text::AnnotationClass::Hyperlink`] annotations
+    /// uses the theme's link colour, then draws decorations as
+    /// [`DrawHandle::text_annotations`] does.
+    ///
+    /// The default implementation does not use a link colour.
+    fn text_annotated(
+        &mut self,
+        pos: Coord,
+        text: &dyn TextApi,
+        annotations: &[Annotation],
+        class: TextClass,
+        state: InputState,
+    ) {
+        self.text_effects(pos, text, class, state);
+        self.text_annotations(pos, text.display(), annotations, state);
+    }
+
     /// Draw an edit marker at the given `byte` index on this `text`
     fn edit_marker(&mut self, pos: Coord, text: &TextDisplay, class: TextClass, byte: usize);
 
@@ -418,6 +598,22 @@ pub trait DrawHandle {
 
     /// Draw an image
     fn image(&mut self, id: ImageId, rect: Rect);
+
+    /// Draw an element of the widget inspector
+    ///
+    /// The inspector is a debugging tool toggled by [`Command::Debug`]. It is
+    /// drawn over the window's contents.
+    ///
+    /// The default implementation draws an [`Self::outer_frame`] for the
+    /// panel and a [`Self::selection_box`] otherwise.
+    ///
+    /// [`Command::Debug`]: crate::event::Command::Debug
+    fn inspector(&mut self, rect: Rect, class: InspectorClass) {
+        match class {
+            InspectorClass::Panel => self.outer_frame(rect),
+            _ => self.selection_box(rect),
+        }
+    }
 }
 
 /// Extension trait over [`DrawHandle`]
@@ -450,6 +646,50 @@ pub trait DrawHandleExt: DrawHandle {
         self.new_pass(rect, Offset::ZERO, PassType::Overlay, f);
     }
 
+    /// Draw to a new pass with clipping and scaling (e.g. for zooming)
+    ///
+    /// Adds a new draw pass with draw operations clipped to `rect` and
+    /// coordinates mapped to the parent's coordinate system via `transform`.
+    /// This allows drawing a subtree at a different scale without adjusting
+    /// its layout. Note that text and images are scaled after rasterization,
+    /// thus may appear blurry when enlarged.
+    ///
+    /// Within `f`, [`DrawHandle::get_clip_rect`] returns the clip region in
+    /// the transformed coordinate system.
+    fn with_transform(
+        &mut self,
+        rect: Rect,
+        transform: Transform,
+        f: &mut dyn FnMut(&mut dyn DrawHandle),
+    ) {
+        self.new_transform_pass(rect, transform, f);
+    }
+
+    /// Draw to a new pass with opacity (e.g. for fading)
+    ///
+    /// Adds a new draw pass with draw operations clipped to `rect` and
+    /// composited onto the parent with opacity `alpha` (in the range `0..=1`).
+    /// Contents are composited as a group: overlapping items within the pass
+    /// do not show through each other. (Graphics backends may implement this
+    /// by drawing to an offscreen texture.)
+    fn with_alpha(&mut self, alpha: f32, rect: Rect, f: &mut dyn FnMut(&mut dyn DrawHandle)) {
+        self.new_alpha_pass(rect, alpha, f);
+    }
+
+    /// Draw to a new pass targeting an image layer (e.g. for caching)
+    ///
+    /// Adds a new draw pass of type [`PassType::Layer`], with draw operations
+    /// in `rect` drawn to the layer `id` instead of the window. The layer may
+    /// then be drawn (now or in later frames) with [`DrawHandle::image`].
+    ///
+    /// The layer should be allocated with [`DrawShared::layer_alloc`], with
+    /// size equal to that of `rect`.
+    ///
+    /// [`DrawShared::layer_alloc`]: crate::draw::DrawShared::layer_alloc
+    fn with_layer(&mut self, id: ImageId, rect: Rect, f: &mut dyn FnMut(&mut dyn DrawHandle)) {
+        self.new_pass(rect, Offset::ZERO, PassType::Layer(id), f);
+    }
+
     /// Draw some text using the standard font, with a subset selected
     ///
     /// Other than visually highlighting the selection, this method behaves
@@ -566,6 +806,17 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
     ) {
         self.deref_mut().new_pass(rect, offset, class, f);
     }
+    fn new_transform_pass(
+        &mut self,
+        rect: Rect,
+        transform: Transform,
+        f: &mut dyn FnMut(&mut dyn DrawHandle),
+    ) {
+        self.deref_mut().new_transform_pass(rect, transform, f);
+    }
+    fn new_alpha_pass(&mut self, rect: Rect, alpha: f32, f: &mut dyn FnMut(&mut dyn DrawHandle)) {
+        self.deref_mut().new_alpha_pass(rect, alpha, f);
+    }
     fn get_clip_rect(&self) -> Rect {
         self.deref().get_clip_rect()
     }
@@ -581,6 +832,33 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
     fn selection_box(&mut self, rect: Rect) {
         self.deref_mut().selection_box(rect);
     }
+    fn rubber_band(&mut self, rect: Rect) {
+        self.deref_mut().rubber_band(rect);
+    }
+    fn drop_marker(&mut self, rect: Rect) {
+        self.deref_mut().drop_marker(rect);
+    }
+    fn group_header(&mut self, rect: Rect, vert: bool) {
+        self.deref_mut().group_header(rect, vert);
+    }
+    fn overflow_indicator(&mut self, rect: Rect, axes: (bool, bool)) {
+        self.deref_mut().overflow_indicator(rect, axes);
+    }
+    fn invalid_marker(&mut self, rect: Rect) {
+        self.deref_mut().invalid_marker(rect);
+    }
+    fn toast(&mut self, rect: Rect, severity: Severity) {
+        self.deref_mut().toast(rect, severity);
+    }
+    fn graph_node(&mut self, rect: Rect, selected: bool) {
+        self.deref_mut().graph_node(rect, selected);
+    }
+    fn graph_port(&mut self, rect: Rect, connected: bool, state: InputState) {
+        self.deref_mut().graph_port(rect, connected, state);
+    }
+    fn graph_link(&mut self, path: &[Vec2], pending: bool) {
+        self.deref_mut().graph_link(path, pending);
+    }
     fn text(&mut self, pos: Coord, text: &TextDisplay, class: TextClass, state: InputState) {
         self.deref_mut().text(pos, text, class, state)
     }
@@ -614,6 +892,27 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
         self.deref_mut()
             .text_selected_range(pos, text, range, class, state);
     }
+    fn text_annotations(
+        &mut self,
+        pos: Coord,
+        text: &TextDisplay,
+        annotations: &[Annotation],
+        state: InputState,
+    ) {
+        self.deref_mut()
+            .text_annotations(pos, text, annotations, state);
+    }
+    fn text_annotated(
+        &mut self,
+        pos: Coord,
+        text: &dyn TextApi,
+        annotations: &[Annotation],
+        class: TextClass,
+        state: InputState,
+    ) {
+        self.deref_mut()
+            .text_annotated(pos, text, annotations, class, state);
+    }
     fn edit_marker(&mut self, pos: Coord, text: &TextDisplay, class: TextClass, byte: usize) {
         self.deref_mut().edit_marker(pos, text, class, byte)
     }
@@ -644,6 +943,9 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
     fn image(&mut self, id: ImageId, rect: Rect) {
         self.deref_mut().image(id, rect);
     }
+    fn inspector(&mut self, rect: Rect, class: InspectorClass) {
+        self.deref_mut().inspector(rect, class);
+    }
 }
 
 #[cfg(feature = "stack_dst")]
@@ -666,6 +968,17 @@ where
     ) {
         self.deref_mut().new_pass(rect, offset, class, f);
     }
+    fn new_transform_pass(
+        &mut self,
+        rect: Rect,
+        transform: Transform,
+        f: &mut dyn FnMut(&mut dyn DrawHandle),
+    ) {
+        self.deref_mut().new_transform_pass(rect, transform, f);
+    }
+    fn new_alpha_pass(&mut self, rect: Rect, alpha: f32, f: &mut dyn FnMut(&mut dyn DrawHandle)) {
+        self.deref_mut().new_alpha_pass(rect, alpha, f);
+    }
     fn get_clip_rect(&self) -> Rect {
         self.deref().get_clip_rect()
     }
@@ -681,6 +994,33 @@ where
     fn selection_box(&mut self, rect: Rect) {
         self.deref_mut().selection_box(rect);
     }
+    fn rubber_band(&mut self, rect: Rect) {
+        self.deref_mut().rubber_band(rect);
+    }
+    fn drop_marker(&mut self, rect: Rect) {
+        self.deref_mut().drop_marker(rect);
+    }
+    fn group_header(&mut self, rect: Rect, vert: bool) {
+        self.deref_mut().group_header(rect, vert);
+    }
+    fn overflow_indicator(&mut self, rect: Rect, axes: (bool, bool)) {
+        self.deref_mut().overflow_indicator(rect, axes);
+    }
+    fn invalid_marker(&mut self, rect: Rect) {
+        self.deref_mut().invalid_marker(rect);
+    }
+    fn toast(&mut self, rect: Rect, severity: Severity) {
+        self.deref_mut().toast(rect, severity);
+    }
+    fn graph_node(&mut self, rect: Rect, selected: bool) {
+        self.deref_mut().graph_node(rect, selected);
+    }
+    fn graph_port(&mut self, rect: Rect, connected: bool, state: InputState) {
+        self.deref_mut().graph_port(rect, connected, state);
+    }
+    fn graph_link(&mut self, path: &[Vec2], pending: bool) {
+        self.deref_mut().graph_link(path, pending);
+    }
     fn text(&mut self, pos: Coord, text: &TextDisplay, class: TextClass, state: InputState) {
         self.deref_mut().text(pos, text, class, state)
     }
@@ -714,6 +1054,27 @@ where
         self.deref_mut()
             .text_selected_range(pos, text, range, class, state);
     }
+    fn text_annotations(
+        &mut self,
+        pos: Coord,
+        text: &TextDisplay,
+        annotations: &[Annotation],
+        state: InputState,
+    ) {
+        self.deref_mut()
+            .text_annotations(pos, text, annotations, state);
+    }
+    fn text_annotated(
+        &mut self,
+        pos: Coord,
+        text: &dyn TextApi,
+        annotations: &[Annotation],
+        class: TextClass,
+        state: InputState,
+    ) {
+        self.deref_mut()
+            .text_annotated(pos, text, annotations, class, state);
+    }
     fn edit_marker(&mut self, pos: Coord, text: &TextDisplay, class: TextClass, byte: usize) {
         self.deref_mut().edit_marker(pos, text, class, byte)
     }
@@ -744,6 +1105,9 @@ where
     fn image(&mut self, id: ImageId, rect: Rect) {
         self.deref_mut().image(id, rect);
     }
+    fn inspector(&mut self, rect: Rect, class: InspectorClass) {
+        self.deref_mut().inspector(rect, class);
+    }
 }
 
 #[cfg(test)]
@@ -7,9 +7,11 @@
 
 use super::DrawSharedImpl;
 use image::RgbaImage;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::num::NonZeroU32;
 use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
 use thiserror::Error;
 
 /// Identifier for an image allocation
@@ -31,6 +33,34 @@ impl ImageId {
     }
 }
 
+/// An image identifier which frees its image when dropped
+///
+/// This is returned by [`DrawShared::layer_alloc_owned`]. When dropped, the
+/// image is queued for removal; this happens before the next allocation.
+///
+/// [`DrawShared::layer_alloc_owned`]: super::DrawShared::layer_alloc_owned
+#[derive(Debug)]
+pub struct OwnedImageId {
+    id: ImageId,
+    dropped: Weak<RefCell<Vec<ImageId>>>,
+}
+
+impl OwnedImageId {
+    /// Get the image identifier
+    #[inline]
+    pub fn id(&self) -> ImageId {
+        self.id
+    }
+}
+
+impl Drop for OwnedImageId {
+    fn drop(&mut self) {
+        if let Some(dropped) = self.dropped.upgrade() {
+            dropped.borrow_mut().push(self.id);
+        }
+    }
+}
+
 /// Image formats available for upload
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum ImageFormat {
@@ -47,11 +77,14 @@ pub enum ImageError {
     Image(#[from] image::ImageError),
     #[error("failed to allocate texture space for image")]
     Allocation,
+    #[error("operation not supported by the graphics backend")]
+    Unsupported,
 }
 
 pub struct Images {
     paths: HashMap<PathBuf, (ImageId, u32)>,
     images: HashMap<ImageId, RgbaImage>,
+    dropped: Rc<RefCell<Vec<ImageId>>>,
 }
 
 impl Images {
@@ -60,6 +93,21 @@ impl Images {
         Images {
             paths: HashMap::new(),
             images: HashMap::new(),
+            dropped: Default::default(),
+        }
+    }
+
+    /// Wrap `id` such that it is freed (by [`Self::free_dropped`]) once dropped
+    pub fn owned(&self, id: ImageId) -> OwnedImageId {
+        let dropped = Rc::downgrade(&self.dropped);
+        OwnedImageId { id, dropped }
+    }
+
+    /// Free images whose [`OwnedImageId`] has been dropped
+    pub fn free_dropped<DS: DrawSharedImpl>(&mut self, draw: &mut DS) {
+        let dropped = std::mem::take(&mut *self.dropped.borrow_mut());
+        for id in dropped {
+            self.remove_id(draw, id);
         }
     }
 
@@ -120,8 +168,9 @@ impl Images {
     /// (It also removes images not created through [`Images::load_path`].)
     pub fn remove_id<DS: DrawSharedImpl>(&mut self, draw: &mut DS, id: ImageId) {
         // We don't have a map from id to path, hence have to iterate. We can
-        // however do a fast check that id is used.
+        // however do a fast check that id was loaded from a path.
         if !self.images.contains_key(&id) {
+            draw.image_free(id);
             return;
         }
 
@@ -25,8 +25,15 @@
 //! The theme draws widget components over a [`DrawIface`] object.
 //! Widgets may access this same API via [`DrawHandle::draw_device`].
 //!
-//! The traits [`Draw`] and [`DrawRounded`] provide functinality over a
-//! [`DrawIface`] object. Additional interfaces may be defined in external crates.
+//! The traits [`Draw`], [`DrawRounded`] and [`DrawShaded`] provide
+//! functinality over a [`DrawIface`] object. Additional interfaces may be
+//! defined in external crates.
+//!
+//! Since [`DrawHandle::draw_device`] returns a `&mut dyn Draw`, extension
+//! traits are accessed via a wrapper: [`DrawRoundedDyn`] and
+//! [`DrawShadedDyn`] work with any shell implementing the corresponding
+//! `*Impl` trait. Themes should be written purely against these traits (with
+//! bounds on [`DrawSharedImpl::Draw`]), allowing use with any shell.
 //!
 //! ### Low-level interface
 //!
@@ -58,18 +65,26 @@ pub mod color;
 #[allow(clippy::module_inception)]
 mod draw;
 mod draw_rounded;
+mod draw_shaded;
 mod draw_shared;
+mod gradient;
 mod handle;
 mod images;
+mod stats;
+mod stroke;
 mod theme;
 
 use crate::cast::Cast;
 
 pub use draw::{Draw, DrawIface, DrawImpl};
-pub use draw_rounded::{DrawRounded, DrawRoundedImpl};
+pub use draw_rounded::{DrawRounded, DrawRoundedDyn, DrawRoundedImpl};
+pub use draw_shaded::{DrawShaded, DrawShadedDyn, DrawShadedImpl};
 pub use draw_shared::{DrawShared, DrawSharedImpl, SharedState};
-pub use handle::{DrawHandle, DrawHandleExt, InputState, SizeHandle, TextClass};
-pub use images::{ImageError, ImageFormat, ImageId};
+pub use gradient::{Gradient, GradientShape};
+pub use handle::{DrawHandle, DrawHandleExt, InputState, InspectorClass, SizeHandle, TextClass};
+pub use images::{ImageError, ImageFormat, ImageId, OwnedImageId};
+pub use stats::{DrawStats, PassStats};
+pub use stroke::{LineCap, Stroke};
 pub use theme::ThemeApi;
 
 /// Draw pass identifier
@@ -115,4 +130,12 @@ pub enum PassType {
     /// or combobox menu. The rect and offset are relative to the base window.
     /// The theme may draw a shadow or border around this rect.
     Overlay,
+    /// New pass is drawn to an image layer
+    ///
+    /// The layer must be allocated with [`DrawShared::layer_alloc`]. Instead of
+    /// drawing to the window, the pass draws to the layer, with the rect (relative
+    /// to the parent) mapped to the whole layer; this rect is not clipped by the
+    /// parent. Layer contents persist until the layer is drawn again, thus may
+    /// be re-used via [`DrawHandle::image`] without redrawing the contents.
+    Layer(ImageId),
 }
@@ -0,0 +1,90 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Draw statistics
+
+use std::fmt;
+use std::time::Duration;
+
+/// Statistics of a single draw pass
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PassStats {
+    /// Number of draw calls
+    pub draw_calls: u32,
+    /// Number of vertices drawn (over all instances)
+    pub vertices: u32,
+    /// Bytes of vertex data uploaded
+    pub upload_bytes: u64,
+    /// GPU time spent executing this pass, if measured
+    pub gpu_time: Option<Duration>,
+}
+
+/// Statistics of a drawn frame
+///
+/// These are collected by the shell when enabled (if supported); see
+/// [`ManagerState::draw_stats`](crate::event::ManagerState::draw_stats).
+///
+/// GPU times are measured using timestamp queries where supported by the
+/// graphics device. Since results are read back asynchronously, GPU times
+/// may lag other statistics by a frame or two. Draw calls by custom pipes
+/// may not be counted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DrawStats {
+    /// Statistics of each draw pass, indexed by [`PassId::pass`]
+    ///
+    /// [`PassId::pass`]: super::PassId::pass
+    pub passes: Vec<PassStats>,
+    /// CPU time spent preparing and submitting render commands
+    pub cpu_time: Duration,
+    /// GPU time spent executing all passes, if measured
+    pub gpu_time: Option<Duration>,
+}
+
+impl DrawStats {
+    /// Total number of draw calls
+    pub fn draw_calls(&self) -> u32 {
+        self.passes.iter().map(|pass| pass.draw_calls).sum()
+    }
+
+    /// Total number of vertices drawn
+    pub fn vertices(&self) -> u32 {
+        self.passes.iter().map(|pass| pass.vertices).sum()
+    }
+
+    /// Total bytes of vertex data uploaded
+    pub fn upload_bytes(&self) -> u64 {
+        self.passes.iter().map(|pass| pass.upload_bytes).sum()
+    }
+}
+
+/// Formats a multi-line summary: totals followed by one line per pass
+impl fmt::Display for DrawStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1e3;
+        write!(
+            f,
+            "{} passes: {} draw calls, {} vertices, {:.1} KiB uploaded; CPU {:.2}ms",
+            self.passes.len(),
+            self.draw_calls(),
+            self.vertices(),
+            self.upload_bytes() as f64 / 1024.0,
+            ms(self.cpu_time),
+        )?;
+        if let Some(time) = self.gpu_time {
+            write!(f, ", GPU {:.2}ms", ms(time))?;
+        }
+        for (index, pass) in self.passes.iter().enumerate() {
+            write!(
+                f,
+                "\npass {}: {} draw calls, {} vertices, {} bytes uploaded",
+                index, pass.draw_calls, pass.vertices, pass.upload_bytes,
+            )?;
+            if let Some(time) = pass.gpu_time {
+                write!(f, "; GPU {:.3}ms", ms(time))?;
+            }
+        }
+        Ok(())
+    }
+}
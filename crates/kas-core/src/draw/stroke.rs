@@ -0,0 +1,101 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Drawing APIs — stroke style
+
+/// Shape of the ends of a stroke (and of each dash)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    /// The stroke ends exactly at the end point
+    Butt,
+    /// The stroke extends half its width beyond the end point
+    Square,
+    /// The stroke ends in a semi-circle centred on the end point
+    Round,
+}
+
+impl Default for LineCap {
+    fn default() -> Self {
+        LineCap::Butt
+    }
+}
+
+/// Style of a stroked path
+///
+/// See [`DrawRounded::polyline`](super::DrawRounded::polyline).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stroke {
+    /// Width of the stroke
+    pub width: f32,
+    /// Cap applied to the ends of the path and of each dash
+    pub cap: LineCap,
+    /// Dash pattern: alternating lengths of "on" and "off" sections
+    ///
+    /// If empty, the stroke is solid. If of odd length, the pattern is
+    /// repeated to give an even length.
+    pub dash: Vec<f32>,
+    /// Offset of the start of the path into the dash pattern
+    pub dash_offset: f32,
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Stroke::hairline()
+    }
+}
+
+impl Stroke {
+    /// Construct a solid stroke of the given `width`
+    pub fn new(width: f32) -> Self {
+        Stroke {
+            width,
+            cap: LineCap::Butt,
+            dash: vec![],
+            dash_offset: 0.0,
+        }
+    }
+
+    /// Construct a solid hairline stroke
+    ///
+    /// This is one unit (normally one physical pixel) wide.
+    pub fn hairline() -> Self {
+        Stroke::new(1.0)
+    }
+
+    /// Set the cap style, builder style
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Set a dash pattern, builder style
+    ///
+    /// The `pattern` consists of alternating lengths of "on" and "off"
+    /// sections; `offset` is the distance into this pattern at which the path
+    /// starts.
+    pub fn with_dash(mut self, pattern: &[f32], offset: f32) -> Self {
+        self.dash = pattern.to_vec();
+        self.dash_offset = offset;
+        self
+    }
+
+    /// Get the dash pattern, if dashed
+    ///
+    /// This returns `None` if the stroke is solid or the pattern is invalid
+    /// (contains a negative length or has zero total length). Otherwise, the
+    /// result has even length.
+    pub fn dash_pattern(&self) -> Option<Vec<f32>> {
+        let sum: f32 = self.dash.iter().sum();
+        let valid = sum > 0.0 && self.dash.iter().all(|x| *x >= 0.0);
+        if !valid {
+            return None;
+        }
+        let mut dash = self.dash.clone();
+        if dash.len() % 2 == 1 {
+            dash.extend_from_slice(&self.dash);
+        }
+        Some(dash)
+    }
+}
@@ -29,6 +29,14 @@ pub trait ThemeApi {
     /// List available colour schemes
     fn list_schemes(&self) -> Vec<&str>;
 
+    /// Set the font family used for all text
+    ///
+    /// Glyphs missing from this family (e.g. CJK or emoji) are drawn using
+    /// fallback fonts. Themes not supporting font selection do nothing.
+    fn set_font_family(&mut self, _family: &str) -> TkAction {
+        TkAction::empty()
+    }
+
     /// Switch the theme
     ///
     /// Most themes do not react to this method; `kas_theme::MultiTheme` uses
@@ -48,6 +56,9 @@ impl<T: ThemeApi> ThemeApi for Box<T> {
     fn list_schemes(&self) -> Vec<&str> {
         self.deref().list_schemes()
     }
+    fn set_font_family(&mut self, family: &str) -> TkAction {
+        self.deref_mut().set_font_family(family)
+    }
     fn set_theme(&mut self, theme: &str) -> TkAction {
         self.deref_mut().set_theme(theme)
     }
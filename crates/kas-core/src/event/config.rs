@@ -18,6 +18,9 @@ pub struct Config {
     #[cfg_attr(feature = "config", serde(default = "defaults::menu_delay_ns"))]
     menu_delay_ns: u32,
 
+    #[cfg_attr(feature = "config", serde(default = "defaults::tooltip_delay_ns"))]
+    tooltip_delay_ns: u32,
+
     #[cfg_attr(
         feature = "config",
         serde(default = "defaults::touch_text_sel_delay_ns")
@@ -27,15 +30,37 @@ pub struct Config {
     #[cfg_attr(feature = "config", serde(default = "defaults::pan_dist_thresh"))]
     pan_dist_thresh: f32,
 
+    #[cfg_attr(feature = "config", serde(default = "defaults::scroll_anim_ns"))]
+    scroll_anim_ns: u32,
+    #[cfg_attr(feature = "config", serde(default = "defaults::scroll_easing"))]
+    scroll_easing: ScrollEasing,
+    #[cfg_attr(feature = "config", serde(default = "defaults::layout_anim_ns"))]
+    layout_anim_ns: u32,
+    #[cfg_attr(feature = "config", serde(default = "defaults::reduce_motion"))]
+    reduce_motion: bool,
+
     #[cfg_attr(feature = "config", serde(default = "defaults::mouse_pan"))]
     mouse_pan: MousePan,
     #[cfg_attr(feature = "config", serde(default = "defaults::mouse_text_pan"))]
     mouse_text_pan: MousePan,
 
+    #[cfg_attr(feature = "config", serde(default = "defaults::accel_reassign"))]
+    accel_reassign: bool,
+
     #[cfg_attr(feature = "config", serde(default = "defaults::mouse_nav_focus"))]
     mouse_nav_focus: bool,
     #[cfg_attr(feature = "config", serde(default = "defaults::touch_nav_focus"))]
     touch_nav_focus: bool,
+    #[cfg_attr(feature = "config", serde(default = "defaults::hover_focus"))]
+    hover_focus: bool,
+    #[cfg_attr(feature = "config", serde(default = "defaults::hover_focus_delay_ns"))]
+    hover_focus_delay_ns: u32,
+
+    #[cfg_attr(
+        feature = "config",
+        serde(default = "defaults::sensitive_clipboard_history")
+    )]
+    sensitive_clipboard_history: bool,
 
     #[cfg_attr(feature = "config", serde(default = "Shortcuts::platform_defaults"))]
     shortcuts: Shortcuts,
@@ -45,12 +70,21 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             menu_delay_ns: defaults::menu_delay_ns(),
+            tooltip_delay_ns: defaults::tooltip_delay_ns(),
             touch_text_sel_delay_ns: defaults::touch_text_sel_delay_ns(),
             pan_dist_thresh: defaults::pan_dist_thresh(),
+            scroll_anim_ns: defaults::scroll_anim_ns(),
+            scroll_easing: defaults::scroll_easing(),
+            layout_anim_ns: defaults::layout_anim_ns(),
+            reduce_motion: defaults::reduce_motion(),
             mouse_pan: defaults::mouse_pan(),
             mouse_text_pan: defaults::mouse_text_pan(),
+            accel_reassign: defaults::accel_reassign(),
             mouse_nav_focus: defaults::mouse_nav_focus(),
             touch_nav_focus: defaults::touch_nav_focus(),
+            hover_focus: defaults::hover_focus(),
+            hover_focus_delay_ns: defaults::hover_focus_delay_ns(),
+            sensitive_clipboard_history: defaults::sensitive_clipboard_history(),
             shortcuts: Shortcuts::platform_defaults(),
         }
     }
@@ -64,6 +98,12 @@ impl Config {
         Duration::from_nanos(self.menu_delay_ns.cast())
     }
 
+    /// Delay before showing a tooltip on mouse hover
+    #[inline]
+    pub fn tooltip_delay(&self) -> Duration {
+        Duration::from_nanos(self.tooltip_delay_ns.cast())
+    }
+
     /// Delay before switching from panning to text-selection mode
     #[inline]
     pub fn touch_text_sel_delay(&self) -> Duration {
@@ -80,6 +120,47 @@ impl Config {
         self.pan_dist_thresh
     }
 
+    /// Duration of smooth-scroll animation for mouse-wheel steps
+    ///
+    /// If zero, wheel steps scroll immediately. This is zero when
+    /// [`Self::reduce_motion`] is true.
+    #[inline]
+    pub fn scroll_anim_duration(&self) -> Duration {
+        match self.reduce_motion {
+            true => Duration::ZERO,
+            false => Duration::from_nanos(self.scroll_anim_ns.cast()),
+        }
+    }
+
+    /// Easing function used by smooth-scroll animation
+    #[inline]
+    pub fn scroll_easing(&self) -> ScrollEasing {
+        self.scroll_easing
+    }
+
+    /// Duration of layout animations
+    ///
+    /// This is used by containers animating child geometry on layout change
+    /// (see [`crate::layout::RectAnimation`]). If zero, widgets move
+    /// immediately. This is zero when [`Self::reduce_motion`] is true.
+    #[inline]
+    pub fn layout_anim_duration(&self) -> Duration {
+        match self.reduce_motion {
+            true => Duration::ZERO,
+            false => Duration::from_nanos(self.layout_anim_ns.cast()),
+        }
+    }
+
+    /// Whether to reduce motion
+    ///
+    /// When true, non-essential animations (smooth scrolling, layout
+    /// animations) are disabled. Widgets implementing their own animations
+    /// should respect this setting.
+    #[inline]
+    pub fn reduce_motion(&self) -> bool {
+        self.reduce_motion
+    }
+
     /// When to pan general widgets (unhandled events) with the mouse
     #[inline]
     pub fn mouse_pan(&self) -> MousePan {
@@ -92,6 +173,17 @@ impl Config {
         self.mouse_text_pan
     }
 
+    /// Whether to reassign conflicting accelerator keys
+    ///
+    /// When two widgets in one accelerator layer request the same key, the
+    /// second widget is assigned another letter from its label (where
+    /// registered via [`Manager::add_accel_label`](super::Manager::add_accel_label)).
+    /// Otherwise the conflict is only logged.
+    #[inline]
+    pub fn accel_reassign(&self) -> bool {
+        self.accel_reassign
+    }
+
     /// Whether mouse clicks set keyboard navigation focus
     #[inline]
     pub fn mouse_nav_focus(&self) -> bool {
@@ -104,6 +196,33 @@ impl Config {
         self.touch_nav_focus
     }
 
+    /// Whether hovering a widget with the mouse sets navigation focus
+    ///
+    /// This "focus follows mouse" policy applies to all widgets except where
+    /// overridden by [`WidgetConfig::hover_focus`](crate::WidgetConfig::hover_focus).
+    #[inline]
+    pub fn hover_focus(&self) -> bool {
+        self.hover_focus
+    }
+
+    /// Delay before setting navigation focus on mouse hover
+    ///
+    /// See [`Self::hover_focus`].
+    #[inline]
+    pub fn hover_focus_delay(&self) -> Duration {
+        Duration::from_nanos(self.hover_focus_delay_ns.cast())
+    }
+
+    /// Whether content copied from sensitive fields may enter clipboard history
+    ///
+    /// When false, [`Manager::set_clipboard_sensitive`](super::Manager::set_clipboard_sensitive)
+    /// asks the shell to exclude content from any clipboard history or
+    /// synchronisation service, where supported.
+    #[inline]
+    pub fn sensitive_clipboard_history(&self) -> bool {
+        self.sensitive_clipboard_history
+    }
+
     /// Read shortcut config
     #[inline]
     pub fn shortcuts(&self) -> &Shortcuts {
@@ -153,18 +272,75 @@ impl MousePan {
     }
 }
 
+/// Focus-follows-mouse policy of a widget and its descendants
+///
+/// See [`WidgetConfig::hover_focus`](crate::WidgetConfig::hover_focus).
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum HoverFocus {
+    /// Use the parent's policy (at the root: [`Config::hover_focus`])
+    Inherit,
+    /// Hovering does not set navigation focus
+    Disabled,
+    /// Hovering sets navigation focus after [`Config::hover_focus_delay`]
+    Enabled,
+    /// Hovering sets navigation focus after the given delay
+    Delay(Duration),
+}
+
+/// Easing function for smooth-scroll animation
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(Serialize, Deserialize))]
+pub enum ScrollEasing {
+    /// Constant speed
+    Linear,
+    /// Start fast and decelerate (quadratic)
+    EaseOutQuad,
+    /// Start fast and decelerate (cubic)
+    EaseOutCubic,
+}
+
+impl ScrollEasing {
+    /// Map the animation's progress to the fraction of distance covered
+    ///
+    /// Input `t` is clamped to the range `0..=1`; output `0` corresponds to
+    /// the start position and `1` to the end position.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.max(0.0).min(1.0);
+        match self {
+            ScrollEasing::Linear => t,
+            ScrollEasing::EaseOutQuad => 1.0 - (1.0 - t).powi(2),
+            ScrollEasing::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+        }
+    }
+}
+
 mod defaults {
-    use super::MousePan;
+    use super::{MousePan, ScrollEasing};
 
     pub fn menu_delay_ns() -> u32 {
         250_000_000
     }
+    pub fn tooltip_delay_ns() -> u32 {
+        700_000_000
+    }
     pub fn touch_text_sel_delay_ns() -> u32 {
         1_000_000_000
     }
     pub fn pan_dist_thresh() -> f32 {
         2.1
     }
+    pub fn scroll_anim_ns() -> u32 {
+        120_000_000
+    }
+    pub fn scroll_easing() -> ScrollEasing {
+        ScrollEasing::EaseOutCubic
+    }
+    pub fn layout_anim_ns() -> u32 {
+        150_000_000
+    }
+    pub fn reduce_motion() -> bool {
+        false
+    }
     pub fn mouse_pan() -> MousePan {
         MousePan::Always
     }
@@ -178,10 +354,22 @@ mod defaults {
             MousePan::WithCtrl
         }
     }
+    pub fn accel_reassign() -> bool {
+        true
+    }
     pub fn mouse_nav_focus() -> bool {
         true
     }
     pub fn touch_nav_focus() -> bool {
         true
     }
+    pub fn hover_focus() -> bool {
+        false
+    }
+    pub fn hover_focus_delay_ns() -> u32 {
+        300_000_000
+    }
+    pub fn sensitive_clipboard_history() -> bool {
+        true
+    }
 }
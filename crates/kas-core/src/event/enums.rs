@@ -3,11 +3,11 @@
 // You may obtain a copy of the License in the LICENSE-APACHE file or at:
 //     https://www.apache.org/licenses/LICENSE-2.0
 
-//! Event enum fallbacks for use without winit
+//! Input enums: keys, modifiers, mouse buttons and cursor icons
 //!
-//! These are simply copied from winit.
-
-#![allow(unused)]
+//! These types are owned by KAS so that neither widgets nor shells need depend
+//! on winit. Variants are copied from winit; with the `winit` feature,
+//! lossless conversions to and from winit's equivalents are provided.
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -78,7 +78,7 @@ pub enum MouseButton {
     Left,
     Right,
     Middle,
-    Other(u8),
+    Other(u16),
 }
 
 impl ModifiersState {
@@ -329,3 +329,482 @@ pub enum VirtualKeyCode {
     Paste,
     Cut,
 }
+
+#[cfg(feature = "winit")]
+mod winit_impls {
+    //! Conversions from (and to) winit types
+    //!
+    //! Variants match those of winit, thus conversions are lossless.
+
+    use super::*;
+    use winit::event as we;
+    use winit::window as ww;
+
+    impl From<ww::CursorIcon> for CursorIcon {
+        fn from(value: ww::CursorIcon) -> Self {
+            match value {
+                ww::CursorIcon::Default => CursorIcon::Default,
+                ww::CursorIcon::Crosshair => CursorIcon::Crosshair,
+                ww::CursorIcon::Hand => CursorIcon::Hand,
+                ww::CursorIcon::Arrow => CursorIcon::Arrow,
+                ww::CursorIcon::Move => CursorIcon::Move,
+                ww::CursorIcon::Text => CursorIcon::Text,
+                ww::CursorIcon::Wait => CursorIcon::Wait,
+                ww::CursorIcon::Help => CursorIcon::Help,
+                ww::CursorIcon::Progress => CursorIcon::Progress,
+                ww::CursorIcon::NotAllowed => CursorIcon::NotAllowed,
+                ww::CursorIcon::ContextMenu => CursorIcon::ContextMenu,
+                ww::CursorIcon::Cell => CursorIcon::Cell,
+                ww::CursorIcon::VerticalText => CursorIcon::VerticalText,
+                ww::CursorIcon::Alias => CursorIcon::Alias,
+                ww::CursorIcon::Copy => CursorIcon::Copy,
+                ww::CursorIcon::NoDrop => CursorIcon::NoDrop,
+                ww::CursorIcon::Grab => CursorIcon::Grab,
+                ww::CursorIcon::Grabbing => CursorIcon::Grabbing,
+                ww::CursorIcon::AllScroll => CursorIcon::AllScroll,
+                ww::CursorIcon::ZoomIn => CursorIcon::ZoomIn,
+                ww::CursorIcon::ZoomOut => CursorIcon::ZoomOut,
+                ww::CursorIcon::EResize => CursorIcon::EResize,
+                ww::CursorIcon::NResize => CursorIcon::NResize,
+                ww::CursorIcon::NeResize => CursorIcon::NeResize,
+                ww::CursorIcon::NwResize => CursorIcon::NwResize,
+                ww::CursorIcon::SResize => CursorIcon::SResize,
+                ww::CursorIcon::SeResize => CursorIcon::SeResize,
+                ww::CursorIcon::SwResize => CursorIcon::SwResize,
+                ww::CursorIcon::WResize => CursorIcon::WResize,
+                ww::CursorIcon::EwResize => CursorIcon::EwResize,
+                ww::CursorIcon::NsResize => CursorIcon::NsResize,
+                ww::CursorIcon::NeswResize => CursorIcon::NeswResize,
+                ww::CursorIcon::NwseResize => CursorIcon::NwseResize,
+                ww::CursorIcon::ColResize => CursorIcon::ColResize,
+                ww::CursorIcon::RowResize => CursorIcon::RowResize,
+            }
+        }
+    }
+
+    impl From<CursorIcon> for ww::CursorIcon {
+        fn from(value: CursorIcon) -> Self {
+            match value {
+                CursorIcon::Default => ww::CursorIcon::Default,
+                CursorIcon::Crosshair => ww::CursorIcon::Crosshair,
+                CursorIcon::Hand => ww::CursorIcon::Hand,
+                CursorIcon::Arrow => ww::CursorIcon::Arrow,
+                CursorIcon::Move => ww::CursorIcon::Move,
+                CursorIcon::Text => ww::CursorIcon::Text,
+                CursorIcon::Wait => ww::CursorIcon::Wait,
+                CursorIcon::Help => ww::CursorIcon::Help,
+                CursorIcon::Progress => ww::CursorIcon::Progress,
+                CursorIcon::NotAllowed => ww::CursorIcon::NotAllowed,
+                CursorIcon::ContextMenu => ww::CursorIcon::ContextMenu,
+                CursorIcon::Cell => ww::CursorIcon::Cell,
+                CursorIcon::VerticalText => ww::CursorIcon::VerticalText,
+                CursorIcon::Alias => ww::CursorIcon::Alias,
+                CursorIcon::Copy => ww::CursorIcon::Copy,
+                CursorIcon::NoDrop => ww::CursorIcon::NoDrop,
+                CursorIcon::Grab => ww::CursorIcon::Grab,
+                CursorIcon::Grabbing => ww::CursorIcon::Grabbing,
+                CursorIcon::AllScroll => ww::CursorIcon::AllScroll,
+                CursorIcon::ZoomIn => ww::CursorIcon::ZoomIn,
+                CursorIcon::ZoomOut => ww::CursorIcon::ZoomOut,
+                CursorIcon::EResize => ww::CursorIcon::EResize,
+                CursorIcon::NResize => ww::CursorIcon::NResize,
+                CursorIcon::NeResize => ww::CursorIcon::NeResize,
+                CursorIcon::NwResize => ww::CursorIcon::NwResize,
+                CursorIcon::SResize => ww::CursorIcon::SResize,
+                CursorIcon::SeResize => ww::CursorIcon::SeResize,
+                CursorIcon::SwResize => ww::CursorIcon::SwResize,
+                CursorIcon::WResize => ww::CursorIcon::WResize,
+                CursorIcon::EwResize => ww::CursorIcon::EwResize,
+                CursorIcon::NsResize => ww::CursorIcon::NsResize,
+                CursorIcon::NeswResize => ww::CursorIcon::NeswResize,
+                CursorIcon::NwseResize => ww::CursorIcon::NwseResize,
+                CursorIcon::ColResize => ww::CursorIcon::ColResize,
+                CursorIcon::RowResize => ww::CursorIcon::RowResize,
+            }
+        }
+    }
+
+    impl From<we::MouseButton> for MouseButton {
+        fn from(button: we::MouseButton) -> Self {
+            match button {
+                we::MouseButton::Left => MouseButton::Left,
+                we::MouseButton::Right => MouseButton::Right,
+                we::MouseButton::Middle => MouseButton::Middle,
+                we::MouseButton::Other(n) => MouseButton::Other(n),
+            }
+        }
+    }
+
+    impl From<MouseButton> for we::MouseButton {
+        fn from(button: MouseButton) -> Self {
+            match button {
+                MouseButton::Left => we::MouseButton::Left,
+                MouseButton::Right => we::MouseButton::Right,
+                MouseButton::Middle => we::MouseButton::Middle,
+                MouseButton::Other(n) => we::MouseButton::Other(n),
+            }
+        }
+    }
+
+    impl From<we::ModifiersState> for ModifiersState {
+        fn from(state: we::ModifiersState) -> Self {
+            let mut result = ModifiersState::empty();
+            result.set(ModifiersState::SHIFT, state.shift());
+            result.set(ModifiersState::CTRL, state.ctrl());
+            result.set(ModifiersState::ALT, state.alt());
+            result.set(ModifiersState::LOGO, state.logo());
+            result
+        }
+    }
+
+    impl From<ModifiersState> for we::ModifiersState {
+        fn from(state: ModifiersState) -> Self {
+            let mut result = we::ModifiersState::empty();
+            result.set(we::ModifiersState::SHIFT, state.shift());
+            result.set(we::ModifiersState::CTRL, state.ctrl());
+            result.set(we::ModifiersState::ALT, state.alt());
+            result.set(we::ModifiersState::LOGO, state.logo());
+            result
+        }
+    }
+
+    impl From<we::VirtualKeyCode> for VirtualKeyCode {
+        fn from(value: we::VirtualKeyCode) -> Self {
+            match value {
+                we::VirtualKeyCode::Key1 => VirtualKeyCode::Key1,
+                we::VirtualKeyCode::Key2 => VirtualKeyCode::Key2,
+                we::VirtualKeyCode::Key3 => VirtualKeyCode::Key3,
+                we::VirtualKeyCode::Key4 => VirtualKeyCode::Key4,
+                we::VirtualKeyCode::Key5 => VirtualKeyCode::Key5,
+                we::VirtualKeyCode::Key6 => VirtualKeyCode::Key6,
+                we::VirtualKeyCode::Key7 => VirtualKeyCode::Key7,
+                we::VirtualKeyCode::Key8 => VirtualKeyCode::Key8,
+                we::VirtualKeyCode::Key9 => VirtualKeyCode::Key9,
+                we::VirtualKeyCode::Key0 => VirtualKeyCode::Key0,
+                we::VirtualKeyCode::A => VirtualKeyCode::A,
+                we::VirtualKeyCode::B => VirtualKeyCode::B,
+                we::VirtualKeyCode::C => VirtualKeyCode::C,
+                we::VirtualKeyCode::D => VirtualKeyCode::D,
+                we::VirtualKeyCode::E => VirtualKeyCode::E,
+                we::VirtualKeyCode::F => VirtualKeyCode::F,
+                we::VirtualKeyCode::G => VirtualKeyCode::G,
+                we::VirtualKeyCode::H => VirtualKeyCode::H,
+                we::VirtualKeyCode::I => VirtualKeyCode::I,
+                we::VirtualKeyCode::J => VirtualKeyCode::J,
+                we::VirtualKeyCode::K => VirtualKeyCode::K,
+                we::VirtualKeyCode::L => VirtualKeyCode::L,
+                we::VirtualKeyCode::M => VirtualKeyCode::M,
+                we::VirtualKeyCode::N => VirtualKeyCode::N,
+                we::VirtualKeyCode::O => VirtualKeyCode::O,
+                we::VirtualKeyCode::P => VirtualKeyCode::P,
+                we::VirtualKeyCode::Q => VirtualKeyCode::Q,
+                we::VirtualKeyCode::R => VirtualKeyCode::R,
+                we::VirtualKeyCode::S => VirtualKeyCode::S,
+                we::VirtualKeyCode::T => VirtualKeyCode::T,
+                we::VirtualKeyCode::U => VirtualKeyCode::U,
+                we::VirtualKeyCode::V => VirtualKeyCode::V,
+                we::VirtualKeyCode::W => VirtualKeyCode::W,
+                we::VirtualKeyCode::X => VirtualKeyCode::X,
+                we::VirtualKeyCode::Y => VirtualKeyCode::Y,
+                we::VirtualKeyCode::Z => VirtualKeyCode::Z,
+                we::VirtualKeyCode::Escape => VirtualKeyCode::Escape,
+                we::VirtualKeyCode::F1 => VirtualKeyCode::F1,
+                we::VirtualKeyCode::F2 => VirtualKeyCode::F2,
+                we::VirtualKeyCode::F3 => VirtualKeyCode::F3,
+                we::VirtualKeyCode::F4 => VirtualKeyCode::F4,
+                we::VirtualKeyCode::F5 => VirtualKeyCode::F5,
+                we::VirtualKeyCode::F6 => VirtualKeyCode::F6,
+                we::VirtualKeyCode::F7 => VirtualKeyCode::F7,
+                we::VirtualKeyCode::F8 => VirtualKeyCode::F8,
+                we::VirtualKeyCode::F9 => VirtualKeyCode::F9,
+                we::VirtualKeyCode::F10 => VirtualKeyCode::F10,
+                we::VirtualKeyCode::F11 => VirtualKeyCode::F11,
+                we::VirtualKeyCode::F12 => VirtualKeyCode::F12,
+                we::VirtualKeyCode::F13 => VirtualKeyCode::F13,
+                we::VirtualKeyCode::F14 => VirtualKeyCode::F14,
+                we::VirtualKeyCode::F15 => VirtualKeyCode::F15,
+                we::VirtualKeyCode::F16 => VirtualKeyCode::F16,
+                we::VirtualKeyCode::F17 => VirtualKeyCode::F17,
+                we::VirtualKeyCode::F18 => VirtualKeyCode::F18,
+                we::VirtualKeyCode::F19 => VirtualKeyCode::F19,
+                we::VirtualKeyCode::F20 => VirtualKeyCode::F20,
+                we::VirtualKeyCode::F21 => VirtualKeyCode::F21,
+                we::VirtualKeyCode::F22 => VirtualKeyCode::F22,
+                we::VirtualKeyCode::F23 => VirtualKeyCode::F23,
+                we::VirtualKeyCode::F24 => VirtualKeyCode::F24,
+                we::VirtualKeyCode::Snapshot => VirtualKeyCode::Snapshot,
+                we::VirtualKeyCode::Scroll => VirtualKeyCode::Scroll,
+                we::VirtualKeyCode::Pause => VirtualKeyCode::Pause,
+                we::VirtualKeyCode::Insert => VirtualKeyCode::Insert,
+                we::VirtualKeyCode::Home => VirtualKeyCode::Home,
+                we::VirtualKeyCode::Delete => VirtualKeyCode::Delete,
+                we::VirtualKeyCode::End => VirtualKeyCode::End,
+                we::VirtualKeyCode::PageDown => VirtualKeyCode::PageDown,
+                we::VirtualKeyCode::PageUp => VirtualKeyCode::PageUp,
+                we::VirtualKeyCode::Left => VirtualKeyCode::Left,
+                we::VirtualKeyCode::Up => VirtualKeyCode::Up,
+                we::VirtualKeyCode::Right => VirtualKeyCode::Right,
+                we::VirtualKeyCode::Down => VirtualKeyCode::Down,
+                we::VirtualKeyCode::Back => VirtualKeyCode::Back,
+                we::VirtualKeyCode::Return => VirtualKeyCode::Return,
+                we::VirtualKeyCode::Space => VirtualKeyCode::Space,
+                we::VirtualKeyCode::Compose => VirtualKeyCode::Compose,
+                we::VirtualKeyCode::Caret => VirtualKeyCode::Caret,
+                we::VirtualKeyCode::Numlock => VirtualKeyCode::Numlock,
+                we::VirtualKeyCode::Numpad0 => VirtualKeyCode::Numpad0,
+                we::VirtualKeyCode::Numpad1 => VirtualKeyCode::Numpad1,
+                we::VirtualKeyCode::Numpad2 => VirtualKeyCode::Numpad2,
+                we::VirtualKeyCode::Numpad3 => VirtualKeyCode::Numpad3,
+                we::VirtualKeyCode::Numpad4 => VirtualKeyCode::Numpad4,
+                we::VirtualKeyCode::Numpad5 => VirtualKeyCode::Numpad5,
+                we::VirtualKeyCode::Numpad6 => VirtualKeyCode::Numpad6,
+                we::VirtualKeyCode::Numpad7 => VirtualKeyCode::Numpad7,
+                we::VirtualKeyCode::Numpad8 => VirtualKeyCode::Numpad8,
+                we::VirtualKeyCode::Numpad9 => VirtualKeyCode::Numpad9,
+                we::VirtualKeyCode::NumpadAdd => VirtualKeyCode::NumpadAdd,
+                we::VirtualKeyCode::NumpadDivide => VirtualKeyCode::NumpadDivide,
+                we::VirtualKeyCode::NumpadDecimal => VirtualKeyCode::NumpadDecimal,
+                we::VirtualKeyCode::NumpadComma => VirtualKeyCode::NumpadComma,
+                we::VirtualKeyCode::NumpadEnter => VirtualKeyCode::NumpadEnter,
+                we::VirtualKeyCode::NumpadEquals => VirtualKeyCode::NumpadEquals,
+                we::VirtualKeyCode::NumpadMultiply => VirtualKeyCode::NumpadMultiply,
+                we::VirtualKeyCode::NumpadSubtract => VirtualKeyCode::NumpadSubtract,
+                we::VirtualKeyCode::AbntC1 => VirtualKeyCode::AbntC1,
+                we::VirtualKeyCode::AbntC2 => VirtualKeyCode::AbntC2,
+                we::VirtualKeyCode::Apostrophe => VirtualKeyCode::Apostrophe,
+                we::VirtualKeyCode::Apps => VirtualKeyCode::Apps,
+                we::VirtualKeyCode::Asterisk => VirtualKeyCode::Asterisk,
+                we::VirtualKeyCode::At => VirtualKeyCode::At,
+                we::VirtualKeyCode::Ax => VirtualKeyCode::Ax,
+                we::VirtualKeyCode::Backslash => VirtualKeyCode::Backslash,
+                we::VirtualKeyCode::Calculator => VirtualKeyCode::Calculator,
+                we::VirtualKeyCode::Capital => VirtualKeyCode::Capital,
+                we::VirtualKeyCode::Colon => VirtualKeyCode::Colon,
+                we::VirtualKeyCode::Comma => VirtualKeyCode::Comma,
+                we::VirtualKeyCode::Convert => VirtualKeyCode::Convert,
+                we::VirtualKeyCode::Equals => VirtualKeyCode::Equals,
+                we::VirtualKeyCode::Grave => VirtualKeyCode::Grave,
+                we::VirtualKeyCode::Kana => VirtualKeyCode::Kana,
+                we::VirtualKeyCode::Kanji => VirtualKeyCode::Kanji,
+                we::VirtualKeyCode::LAlt => VirtualKeyCode::LAlt,
+                we::VirtualKeyCode::LBracket => VirtualKeyCode::LBracket,
+                we::VirtualKeyCode::LControl => VirtualKeyCode::LControl,
+                we::VirtualKeyCode::LShift => VirtualKeyCode::LShift,
+                we::VirtualKeyCode::LWin => VirtualKeyCode::LWin,
+                we::VirtualKeyCode::Mail => VirtualKeyCode::Mail,
+                we::VirtualKeyCode::MediaSelect => VirtualKeyCode::MediaSelect,
+                we::VirtualKeyCode::MediaStop => VirtualKeyCode::MediaStop,
+                we::VirtualKeyCode::Minus => VirtualKeyCode::Minus,
+                we::VirtualKeyCode::Mute => VirtualKeyCode::Mute,
+                we::VirtualKeyCode::MyComputer => VirtualKeyCode::MyComputer,
+                we::VirtualKeyCode::NavigateForward => VirtualKeyCode::NavigateForward,
+                we::VirtualKeyCode::NavigateBackward => VirtualKeyCode::NavigateBackward,
+                we::VirtualKeyCode::NextTrack => VirtualKeyCode::NextTrack,
+                we::VirtualKeyCode::NoConvert => VirtualKeyCode::NoConvert,
+                we::VirtualKeyCode::OEM102 => VirtualKeyCode::OEM102,
+                we::VirtualKeyCode::Period => VirtualKeyCode::Period,
+                we::VirtualKeyCode::PlayPause => VirtualKeyCode::PlayPause,
+                we::VirtualKeyCode::Plus => VirtualKeyCode::Plus,
+                we::VirtualKeyCode::Power => VirtualKeyCode::Power,
+                we::VirtualKeyCode::PrevTrack => VirtualKeyCode::PrevTrack,
+                we::VirtualKeyCode::RAlt => VirtualKeyCode::RAlt,
+                we::VirtualKeyCode::RBracket => VirtualKeyCode::RBracket,
+                we::VirtualKeyCode::RControl => VirtualKeyCode::RControl,
+                we::VirtualKeyCode::RShift => VirtualKeyCode::RShift,
+                we::VirtualKeyCode::RWin => VirtualKeyCode::RWin,
+                we::VirtualKeyCode::Semicolon => VirtualKeyCode::Semicolon,
+                we::VirtualKeyCode::Slash => VirtualKeyCode::Slash,
+                we::VirtualKeyCode::Sleep => VirtualKeyCode::Sleep,
+                we::VirtualKeyCode::Stop => VirtualKeyCode::Stop,
+                we::VirtualKeyCode::Sysrq => VirtualKeyCode::Sysrq,
+                we::VirtualKeyCode::Tab => VirtualKeyCode::Tab,
+                we::VirtualKeyCode::Underline => VirtualKeyCode::Underline,
+                we::VirtualKeyCode::Unlabeled => VirtualKeyCode::Unlabeled,
+                we::VirtualKeyCode::VolumeDown => VirtualKeyCode::VolumeDown,
+                we::VirtualKeyCode::VolumeUp => VirtualKeyCode::VolumeUp,
+                we::VirtualKeyCode::Wake => VirtualKeyCode::Wake,
+                we::VirtualKeyCode::WebBack => VirtualKeyCode::WebBack,
+                we::VirtualKeyCode::WebFavorites => VirtualKeyCode::WebFavorites,
+                we::VirtualKeyCode::WebForward => VirtualKeyCode::WebForward,
+                we::VirtualKeyCode::WebHome => VirtualKeyCode::WebHome,
+                we::VirtualKeyCode::WebRefresh => VirtualKeyCode::WebRefresh,
+                we::VirtualKeyCode::WebSearch => VirtualKeyCode::WebSearch,
+                we::VirtualKeyCode::WebStop => VirtualKeyCode::WebStop,
+                we::VirtualKeyCode::Yen => VirtualKeyCode::Yen,
+                we::VirtualKeyCode::Copy => VirtualKeyCode::Copy,
+                we::VirtualKeyCode::Paste => VirtualKeyCode::Paste,
+                we::VirtualKeyCode::Cut => VirtualKeyCode::Cut,
+            }
+        }
+    }
+
+    impl From<VirtualKeyCode> for we::VirtualKeyCode {
+        fn from(value: VirtualKeyCode) -> Self {
+            match value {
+                VirtualKeyCode::Key1 => we::VirtualKeyCode::Key1,
+                VirtualKeyCode::Key2 => we::VirtualKeyCode::Key2,
+                VirtualKeyCode::Key3 => we::VirtualKeyCode::Key3,
+                VirtualKeyCode::Key4 => we::VirtualKeyCode::Key4,
+                VirtualKeyCode::Key5 => we::VirtualKeyCode::Key5,
+                VirtualKeyCode::Key6 => we::VirtualKeyCode::Key6,
+                VirtualKeyCode::Key7 => we::VirtualKeyCode::Key7,
+                VirtualKeyCode::Key8 => we::VirtualKeyCode::Key8,
+                VirtualKeyCode::Key9 => we::VirtualKeyCode::Key9,
+                VirtualKeyCode::Key0 => we::VirtualKeyCode::Key0,
+                VirtualKeyCode::A => we::VirtualKeyCode::A,
+                VirtualKeyCode::B => we::VirtualKeyCode::B,
+                VirtualKeyCode::C => we::VirtualKeyCode::C,
+                VirtualKeyCode::D => we::VirtualKeyCode::D,
+                VirtualKeyCode::E => we::VirtualKeyCode::E,
+                VirtualKeyCode::F => we::VirtualKeyCode::F,
+                VirtualKeyCode::G => we::VirtualKeyCode::G,
+                VirtualKeyCode::H => we::VirtualKeyCode::H,
+                VirtualKeyCode::I => we::VirtualKeyCode::I,
+                VirtualKeyCode::J => we::VirtualKeyCode::J,
+                VirtualKeyCode::K => we::VirtualKeyCode::K,
+                VirtualKeyCode::L => we::VirtualKeyCode::L,
+                VirtualKeyCode::M => we::VirtualKeyCode::M,
+                VirtualKeyCode::N => we::VirtualKeyCode::N,
+                VirtualKeyCode::O => we::VirtualKeyCode::O,
+                VirtualKeyCode::P => we::VirtualKeyCode::P,
+                VirtualKeyCode::Q => we::VirtualKeyCode::Q,
+                VirtualKeyCode::R => we::VirtualKeyCode::R,
+                VirtualKeyCode::S => we::VirtualKeyCode::S,
+                VirtualKeyCode::T => we::VirtualKeyCode::T,
+                VirtualKeyCode::U => we::VirtualKeyCode::U,
+                VirtualKeyCode::V => we::VirtualKeyCode::V,
+                VirtualKeyCode::W => we::VirtualKeyCode::W,
+                VirtualKeyCode::X => we::VirtualKeyCode::X,
+                VirtualKeyCode::Y => we::VirtualKeyCode::Y,
+                VirtualKeyCode::Z => we::VirtualKeyCode::Z,
+                VirtualKeyCode::Escape => we::VirtualKeyCode::Escape,
+                VirtualKeyCode::F1 => we::VirtualKeyCode::F1,
+                VirtualKeyCode::F2 => we::VirtualKeyCode::F2,
+                VirtualKeyCode::F3 => we::VirtualKeyCode::F3,
+                VirtualKeyCode::F4 => we::VirtualKeyCode::F4,
+                VirtualKeyCode::F5 => we::VirtualKeyCode::F5,
+                VirtualKeyCode::F6 => we::VirtualKeyCode::F6,
+                VirtualKeyCode::F7 => we::VirtualKeyCode::F7,
+                VirtualKeyCode::F8 => we::VirtualKeyCode::F8,
+                VirtualKeyCode::F9 => we::VirtualKeyCode::F9,
+                VirtualKeyCode::F10 => we::VirtualKeyCode::F10,
+                VirtualKeyCode::F11 => we::VirtualKeyCode::F11,
+                VirtualKeyCode::F12 => we::VirtualKeyCode::F12,
+                VirtualKeyCode::F13 => we::VirtualKeyCode::F13,
+                VirtualKeyCode::F14 => we::VirtualKeyCode::F14,
+                VirtualKeyCode::F15 => we::VirtualKeyCode::F15,
+                VirtualKeyCode::F16 => we::VirtualKeyCode::F16,
+                VirtualKeyCode::F17 => we::VirtualKeyCode::F17,
+                VirtualKeyCode::F18 => we::VirtualKeyCode::F18,
+                VirtualKeyCode::F19 => we::VirtualKeyCode::F19,
+                VirtualKeyCode::F20 => we::VirtualKeyCode::F20,
+                VirtualKeyCode::F21 => we::VirtualKeyCode::F21,
+                VirtualKeyCode::F22 => we::VirtualKeyCode::F22,
+                VirtualKeyCode::F23 => we::VirtualKeyCode::F23,
+                VirtualKeyCode::F24 => we::VirtualKeyCode::F24,
+                VirtualKeyCode::Snapshot => we::VirtualKeyCode::Snapshot,
+                VirtualKeyCode::Scroll => we::VirtualKeyCode::Scroll,
+                VirtualKeyCode::Pause => we::VirtualKeyCode::Pause,
+                VirtualKeyCode::Insert => we::VirtualKeyCode::Insert,
+                VirtualKeyCode::Home => we::VirtualKeyCode::Home,
+                VirtualKeyCode::Delete => we::VirtualKeyCode::Delete,
+                VirtualKeyCode::End => we::VirtualKeyCode::End,
+                VirtualKeyCode::PageDown => we::VirtualKeyCode::PageDown,
+                VirtualKeyCode::PageUp => we::VirtualKeyCode::PageUp,
+                VirtualKeyCode::Left => we::VirtualKeyCode::Left,
+                VirtualKeyCode::Up => we::VirtualKeyCode::Up,
+                VirtualKeyCode::Right => we::VirtualKeyCode::Right,
+                VirtualKeyCode::Down => we::VirtualKeyCode::Down,
+                VirtualKeyCode::Back => we::VirtualKeyCode::Back,
+                VirtualKeyCode::Return => we::VirtualKeyCode::Return,
+                VirtualKeyCode::Space => we::VirtualKeyCode::Space,
+                VirtualKeyCode::Compose => we::VirtualKeyCode::Compose,
+                VirtualKeyCode::Caret => we::VirtualKeyCode::Caret,
+                VirtualKeyCode::Numlock => we::VirtualKeyCode::Numlock,
+                VirtualKeyCode::Numpad0 => we::VirtualKeyCode::Numpad0,
+                VirtualKeyCode::Numpad1 => we::VirtualKeyCode::Numpad1,
+                VirtualKeyCode::Numpad2 => we::VirtualKeyCode::Numpad2,
+                VirtualKeyCode::Numpad3 => we::VirtualKeyCode::Numpad3,
+                VirtualKeyCode::Numpad4 => we::VirtualKeyCode::Numpad4,
+                VirtualKeyCode::Numpad5 => we::VirtualKeyCode::Numpad5,
+                VirtualKeyCode::Numpad6 => we::VirtualKeyCode::Numpad6,
+                VirtualKeyCode::Numpad7 => we::VirtualKeyCode::Numpad7,
+                VirtualKeyCode::Numpad8 => we::VirtualKeyCode::Numpad8,
+                VirtualKeyCode::Numpad9 => we::VirtualKeyCode::Numpad9,
+                VirtualKeyCode::NumpadAdd => we::VirtualKeyCode::NumpadAdd,
+                VirtualKeyCode::NumpadDivide => we::VirtualKeyCode::NumpadDivide,
+                VirtualKeyCode::NumpadDecimal => we::VirtualKeyCode::NumpadDecimal,
+                VirtualKeyCode::NumpadComma => we::VirtualKeyCode::NumpadComma,
+                VirtualKeyCode::NumpadEnter => we::VirtualKeyCode::NumpadEnter,
+                VirtualKeyCode::NumpadEquals => we::VirtualKeyCode::NumpadEquals,
+                VirtualKeyCode::NumpadMultiply => we::VirtualKeyCode::NumpadMultiply,
+                VirtualKeyCode::NumpadSubtract => we::VirtualKeyCode::NumpadSubtract,
+                VirtualKeyCode::AbntC1 => we::VirtualKeyCode::AbntC1,
+                VirtualKeyCode::AbntC2 => we::VirtualKeyCode::AbntC2,
+                VirtualKeyCode::Apostrophe => we::VirtualKeyCode::Apostrophe,
+                VirtualKeyCode::Apps => we::VirtualKeyCode::Apps,
+                VirtualKeyCode::Asterisk => we::VirtualKeyCode::Asterisk,
+                VirtualKeyCode::At => we::VirtualKeyCode::At,
+                VirtualKeyCode::Ax => we::VirtualKeyCode::Ax,
+                VirtualKeyCode::Backslash => we::VirtualKeyCode::Backslash,
+                VirtualKeyCode::Calculator => we::VirtualKeyCode::Calculator,
+                VirtualKeyCode::Capital => we::VirtualKeyCode::Capital,
+                VirtualKeyCode::Colon => we::VirtualKeyCode::Colon,
+                VirtualKeyCode::Comma => we::VirtualKeyCode::Comma,
+                VirtualKeyCode::Convert => we::VirtualKeyCode::Convert,
+                VirtualKeyCode::Equals => we::VirtualKeyCode::Equals,
+                VirtualKeyCode::Grave => we::VirtualKeyCode::Grave,
+                VirtualKeyCode::Kana => we::VirtualKeyCode::Kana,
+                VirtualKeyCode::Kanji => we::VirtualKeyCode::Kanji,
+                VirtualKeyCode::LAlt => we::VirtualKeyCode::LAlt,
+                VirtualKeyCode::LBracket => we::VirtualKeyCode::LBracket,
+                VirtualKeyCode::LControl => we::VirtualKeyCode::LControl,
+                VirtualKeyCode::LShift => we::VirtualKeyCode::LShift,
+                VirtualKeyCode::LWin => we::VirtualKeyCode::LWin,
+                VirtualKeyCode::Mail => we::VirtualKeyCode::Mail,
+                VirtualKeyCode::MediaSelect => we::VirtualKeyCode::MediaSelect,
+                VirtualKeyCode::MediaStop => we::VirtualKeyCode::MediaStop,
+                VirtualKeyCode::Minus => we::VirtualKeyCode::Minus,
+                VirtualKeyCode::Mute => we::VirtualKeyCode::Mute,
+                VirtualKeyCode::MyComputer => we::VirtualKeyCode::MyComputer,
+                VirtualKeyCode::NavigateForward => we::VirtualKeyCode::NavigateForward,
+                VirtualKeyCode::NavigateBackward => we::VirtualKeyCode::NavigateBackward,
+                VirtualKeyCode::NextTrack => we::VirtualKeyCode::NextTrack,
+                VirtualKeyCode::NoConvert => we::VirtualKeyCode::NoConvert,
+                VirtualKeyCode::OEM102 => we::VirtualKeyCode::OEM102,
+                VirtualKeyCode::Period => we::VirtualKeyCode::Period,
+                VirtualKeyCode::PlayPause => we::VirtualKeyCode::PlayPause,
+                VirtualKeyCode::Plus => we::VirtualKeyCode::Plus,
+                VirtualKeyCode::Power => we::VirtualKeyCode::Power,
+                VirtualKeyCode::PrevTrack => we::VirtualKeyCode::PrevTrack,
+                VirtualKeyCode::RAlt => we::VirtualKeyCode::RAlt,
+                VirtualKeyCode::RBracket => we::VirtualKeyCode::RBracket,
+                VirtualKeyCode::RControl => we::VirtualKeyCode::RControl,
+                VirtualKeyCode::RShift => we::VirtualKeyCode::RShift,
+                VirtualKeyCode::RWin => we::VirtualKeyCode::RWin,
+                VirtualKeyCode::Semicolon => we::VirtualKeyCode::Semicolon,
+                VirtualKeyCode::Slash => we::VirtualKeyCode::Slash,
+                VirtualKeyCode::Sleep => we::VirtualKeyCode::Sleep,
+                VirtualKeyCode::Stop => we::VirtualKeyCode::Stop,
+                VirtualKeyCode::Sysrq => we::VirtualKeyCode::Sysrq,
+                VirtualKeyCode::Tab => we::VirtualKeyCode::Tab,
+                VirtualKeyCode::Underline => we::VirtualKeyCode::Underline,
+                VirtualKeyCode::Unlabeled => we::VirtualKeyCode::Unlabeled,
+                VirtualKeyCode::VolumeDown => we::VirtualKeyCode::VolumeDown,
+                VirtualKeyCode::VolumeUp => we::VirtualKeyCode::VolumeUp,
+                VirtualKeyCode::Wake => we::VirtualKeyCode::Wake,
+                VirtualKeyCode::WebBack => we::VirtualKeyCode::WebBack,
+                VirtualKeyCode::WebFavorites => we::VirtualKeyCode::WebFavorites,
+                VirtualKeyCode::WebForward => we::VirtualKeyCode::WebForward,
+                VirtualKeyCode::WebHome => we::VirtualKeyCode::WebHome,
+                VirtualKeyCode::WebRefresh => we::VirtualKeyCode::WebRefresh,
+                VirtualKeyCode::WebSearch => we::VirtualKeyCode::WebSearch,
+                VirtualKeyCode::WebStop => we::VirtualKeyCode::WebStop,
+                VirtualKeyCode::Yen => we::VirtualKeyCode::Yen,
+                VirtualKeyCode::Copy => we::VirtualKeyCode::Copy,
+                VirtualKeyCode::Paste => we::VirtualKeyCode::Paste,
+                VirtualKeyCode::Cut => we::VirtualKeyCode::Cut,
+            }
+        }
+    }
+}
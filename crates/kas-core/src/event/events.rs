@@ -8,9 +8,9 @@
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use super::{BusMessage, MouseButton, UpdateHandle, VirtualKeyCode};
 #[allow(unused)]
-use super::{GrabMode, Manager, Response, SendEvent}; // for doc-links
-use super::{MouseButton, UpdateHandle, VirtualKeyCode};
+use super::{GrabMode, Manager, Response, SendEvent, TypedUpdateHandle}; // for doc-links
 
 use crate::geom::{Coord, DVec2, Offset};
 use crate::{dir::Direction, WidgetId, WindowId};
@@ -56,6 +56,11 @@ pub enum Event {
     /// being sent to a widget.
     ReceivedCharacter(char),
     /// A mouse or touchpad scroll event
+    ///
+    /// The source device is available via [`Manager::scroll_source`]; this
+    /// allows widgets to choose between stepped behaviour (e.g. moving by
+    /// whole items for a mouse wheel) and smooth behaviour (following a
+    /// touchpad precisely).
     Scroll(ScrollDelta),
     /// A mouse or touch-screen move/zoom/rotate event
     ///
@@ -111,6 +116,12 @@ pub enum Event {
         /// Translation component
         delta: DVec2,
     },
+    /// A multi-touch gesture (pinch-zoom, rotation and translation)
+    ///
+    /// This is received given a grab with [`GrabMode::Gesture`]. It carries
+    /// the same information as [`Event::Pan`] but decomposed into scale,
+    /// rotation and translation about a focal point; see [`Gesture`].
+    Gesture(Gesture),
     /// A mouse button was pressed or touch event started
     PressStart {
         source: PressSource,
@@ -137,6 +148,14 @@ pub enum Event {
         end_id: Option<WidgetId>,
         coord: Coord,
     },
+    /// Movement of the mouse cursor over a widget
+    ///
+    /// This is sent to the widget under the mouse cursor when the mouse moves
+    /// without a press grab or open pop-up. It may be used to implement
+    /// hover effects over parts of a widget; in most cases, highlighting of
+    /// the whole widget is handled by the theme (see
+    /// [`Manager::is_hovered`]).
+    CursorMove { coord: Coord },
     /// Update from a timer
     ///
     /// This event is received after requesting timed wake-up(s)
@@ -151,14 +170,26 @@ pub enum Event {
     /// [`Manager::update_on_handle`].
     ///
     /// A user-defined payload is passed. Interpretation of this payload is
-    /// user-defined and unfortunately not type safe.
+    /// user-defined; where a [`TypedUpdateHandle`] is used, the payload may be
+    /// decoded via [`TypedUpdateHandle::payload`].
     HandleUpdate { handle: UpdateHandle, payload: u64 },
+    /// A message published on the bus
+    ///
+    /// This event may be received after subscribing to the message's type via
+    /// [`Manager::subscribe`]. See [`Manager::publish`].
+    Bus(BusMessage),
     /// Notification that a popup has been destroyed
     ///
     /// This is sent to the popup's parent after a popup has been removed.
     /// Since popups may be removed directly by the Manager, the parent should
     /// clean up any associated state here.
     PopupRemoved(WindowId),
+    /// The action button of a toast notification was clicked
+    ///
+    /// This is sent to the widget named by the toast's
+    /// [`ToastAction`](super::ToastAction) (see [`Manager::notify`]). The
+    /// payload is that of the action.
+    ToastAction(u64),
     /// Sent when a widget receives keyboard navigation focus
     ///
     /// This event may be used to react (e.g. by requesting char focus) or to
@@ -176,6 +207,38 @@ pub enum Event {
     /// *before* calling `handle_generic`, which might require a custom
     /// implementation of [`SendEvent`].
     NavFocus(bool),
+    /// The window's scale factor changed
+    ///
+    /// This is sent to the window (root widget) after its scale factor
+    /// changes, usually because the window moved to a monitor with a different
+    /// DPI. The payload is the new scale factor. Size rules have already been
+    /// invalidated and the window will be resized; this event is only needed
+    /// by widgets with resources depending on the scale factor (e.g. raster
+    /// images), and may be forwarded to children as required.
+    ///
+    /// See also [`Manager::monitors`].
+    ScaleFactorChanged(f32),
+    /// The widget's effective disabled state changed
+    ///
+    /// This is sent via [`Manager::set_disabled`] to each widget in the
+    /// affected subtree whose effective state changed (i.e. excluding
+    /// descendants which were already disabled). The payload is the new
+    /// state: `true` when disabled.
+    ///
+    /// When disabling, this is sent *before* the state changes (so that the
+    /// event may be routed to the subtree), and widgets should release
+    /// resources such as grabs, character focus and popups. When enabling,
+    /// this is sent after the state changes.
+    DisabledChanged(bool),
+    /// Request to make the widget visible
+    ///
+    /// This is sent via [`Manager::scroll_to`]. The widget should respond with
+    /// [`Response::Reveal`], which scroll regions among its ancestors use to
+    /// scroll such that the widget is visible.
+    ///
+    /// Widgets using [`Manager::handle_generic`] should note that this event
+    /// is trapped and responded to with the widget's rect.
+    ScrollTo,
 }
 
 /// Command input ([`Event::Command`])
@@ -186,8 +249,12 @@ pub enum Event {
 ///
 /// The exact mapping between the keyboard and these commands is OS-specific.
 /// In the future it should be customisable (see `shortcuts` module).
+///
+/// New commands may be added in minor releases, thus matches should include a
+/// catch-all arm.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum Command {
     /// Escape key
     ///
@@ -289,6 +356,11 @@ pub enum Command {
     Copy,
     /// Copy from clipboard
     Paste,
+    /// Copy from clipboard, discarding any formatting
+    ///
+    /// Widgets which do not support formatted text should treat this the same
+    /// as [`Command::Paste`].
+    PasteAsPlainText,
     /// Undo the last action
     Undo,
     /// Redo the last undone action
@@ -335,6 +407,8 @@ pub enum Command {
     Menu,
     /// Make view fullscreen
     Fullscreen,
+    /// Toggle the widget inspector (a debugging tool)
+    Debug,
 
     /// Close window/tab/popup
     Close,
@@ -393,6 +467,22 @@ impl Command {
             _ => None,
         }
     }
+
+    /// Swap left and right movement commands
+    ///
+    /// This maps [`Command::Left`] ↔ [`Command::Right`] and
+    /// [`Command::WordLeft`] ↔ [`Command::WordRight`]; other commands are
+    /// returned unchanged. Text widgets use this to convert visual movement
+    /// to logical movement within right-to-left text.
+    pub fn mirrored(self) -> Self {
+        match self {
+            Command::Left => Command::Right,
+            Command::Right => Command::Left,
+            Command::WordLeft => Command::WordRight,
+            Command::WordRight => Command::WordLeft,
+            cmd => cmd,
+        }
+    }
 }
 
 /// Source of `EventChild::Press`
@@ -415,6 +505,62 @@ pub enum PressSource {
     Touch(u64),
 }
 
+/// Transform data of a multi-touch gesture
+///
+/// See [`Event::Gesture`]. With one touch (or a mouse), only `translation` is
+/// non-trivial. With two touches, the focal point is the midpoint of the
+/// touches, `scale` is the ratio of the new distance between touches to the
+/// old distance and `rotation` is the change in angle between touches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gesture {
+    /// Focal point, after movement (window coordinates)
+    pub focus: DVec2,
+    /// Movement of the focal point
+    pub translation: DVec2,
+    /// Scale factor (greater than 1 when zooming in)
+    pub scale: f64,
+    /// Rotation, in radians (clockwise, since the y-axis points down)
+    pub rotation: f64,
+    /// Number of touches used (1 or 2)
+    pub touches: u16,
+}
+
+impl Gesture {
+    /// Construct from an affine transform
+    ///
+    /// The transform `(alpha, delta)` is as for [`Event::Pan`]; `focus` is the
+    /// focal point after movement.
+    pub fn from_transform(alpha: DVec2, delta: DVec2, focus: DVec2, touches: u16) -> Self {
+        // The old focal point p satisfies: focus = alpha * p + delta
+        let old_focus = (focus - delta).complex_div(alpha);
+        Gesture {
+            focus,
+            translation: focus - old_focus,
+            scale: alpha.sum_square().sqrt(),
+            rotation: alpha.1.atan2(alpha.0),
+            touches,
+        }
+    }
+
+    /// Get the equivalent affine transform `(alpha, delta)`
+    ///
+    /// This is the transform as for [`Event::Pan`], mapping old coordinates
+    /// `p` to new coordinates `alpha * p + delta` (using complex
+    /// multiplication).
+    pub fn transform(&self) -> (DVec2, DVec2) {
+        let alpha = DVec2(self.rotation.cos(), self.rotation.sin()) * self.scale;
+        let old_focus = self.focus - self.translation;
+        let delta = self.focus - alpha.complex_mul(old_focus);
+        (alpha, delta)
+    }
+
+    /// True if this gesture includes scaling or rotation
+    #[inline]
+    pub fn is_pinch(&self) -> bool {
+        self.scale != 1.0 || self.rotation != 0.0
+    }
+}
+
 impl PressSource {
     /// Returns true if this represents the left mouse button or a touch event
     #[inline]
@@ -448,7 +594,40 @@ impl PressSource {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ScrollDelta {
     /// Scroll a given number of lines
+    ///
+    /// Values may be fractional when the source is [`ScrollSource::is_precise`].
     LineDelta(f32, f32),
     /// Scroll a given number of pixels
-    PixelDelta(Offset),
+    ///
+    /// Values may be fractional (from high-precision touchpads).
+    PixelDelta(DVec2),
+}
+
+/// Source device of an [`Event::Scroll`]
+///
+/// This is available via [`Manager::scroll_source`].
+///
+/// Shells classify the source as best they can; where the platform does not
+/// report this, [`ScrollSource::Wheel`] is assumed for line deltas and
+/// [`ScrollSource::Touchpad`] for pixel or fractional line deltas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ScrollSource {
+    /// A mouse wheel, scrolling in discrete steps
+    Wheel,
+    /// A touchpad or other precise (continuous) scroll device
+    Touchpad,
+    /// Panning on a touch screen
+    Touch,
+}
+
+impl ScrollSource {
+    /// True if the source reports precise (continuous) deltas
+    ///
+    /// Widgets should follow precise deltas smoothly, without snapping to
+    /// items or lines.
+    #[inline]
+    pub fn is_precise(self) -> bool {
+        self != ScrollSource::Wheel
+    }
 }
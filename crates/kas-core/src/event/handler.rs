@@ -6,9 +6,8 @@
 //! Event handling - handler
 
 use super::*;
-#[allow(unused)]
-use crate::Widget; // for doc-links
-use crate::{WidgetConfig, WidgetId};
+use crate::layout::AlignHints;
+use crate::{Widget, WidgetConfig, WidgetId};
 
 /// Event handling for a [`Widget`]
 ///
@@ -116,6 +115,10 @@ pub trait SendEvent: Handler {
     /// When the child's [`Handler::Msg`] type is not [`VoidMsg`], its response
     /// messages can be handled here (in place of `.into()` above).
     ///
+    /// Parents with a list of children (`Vec<W>` or similar) should use
+    /// [`Manager::dispatch_to_child`] or [`Manager::send_to_child`] in place of
+    /// scanning over children.
+    ///
     /// The example above uses [`Manager::handle_generic`], which is an optional
     /// tool able to perform some simplifications on events. It is also valid to
     /// call [`Handler::handle`] directly or simply to embed handling logic here.
@@ -123,6 +126,68 @@ pub trait SendEvent: Handler {
 }
 
 impl<'a> Manager<'a> {
+    /// Send an event to the appropriate child, if any
+    ///
+    /// Finds the child of `children` which is an ancestor of `id` (see
+    /// [`crate::util::find_child_index`]) and calls [`SendEvent::send`] on it,
+    /// returning its index and response, or `None` if no child matches.
+    ///
+    /// This is a helper for [`SendEvent::send`] implementations over a
+    /// list of children.
+    pub fn send_to_child<W: Widget>(
+        &mut self,
+        children: &mut [W],
+        id: WidgetId,
+        event: Event,
+    ) -> Option<(usize, Response<W::Msg>)> {
+        let index = crate::util::find_child_index(children, id)?;
+        let response = children[index].send(self, id, event);
+        Some((index, response))
+    }
+
+    /// Dispatch an event to the appropriate child, converting the response
+    ///
+    /// This extends [`Manager::send_to_child`]: where the child responds with
+    /// [`Response::Msg`], the message is logged (as received by `parent`) and
+    /// converted via `f`, which is passed the child's index. Other responses
+    /// are passed through. Returns `None` if no child matches `id`.
+    ///
+    /// Typical usage within [`SendEvent::send`]:
+    /// ```no_test
+    /// if !self.is_disabled() {
+    ///     if let Some(r) = mgr.dispatch_to_child(self.id(), &mut self.widgets, id, event, |_, _, msg| msg.into()) {
+    ///         return r;
+    ///     }
+    /// }
+    /// Response::Unhandled
+    /// ```
+    pub fn dispatch_to_child<W, M, F>(
+        &mut self,
+        parent: WidgetId,
+        children: &mut [W],
+        id: WidgetId,
+        event: Event,
+        f: F,
+    ) -> Option<Response<M>>
+    where
+        W: Widget,
+        F: FnOnce(&mut Self, usize, W::Msg) -> Response<M>,
+    {
+        let (index, r) = self.send_to_child(children, id, event)?;
+        Some(match Response::try_from(r) {
+            Ok(r) => r,
+            Err(msg) => {
+                log::trace!(
+                    "Received by {} from {}: {:?}",
+                    parent,
+                    id,
+                    crate::util::TryFormat(&msg)
+                );
+                f(self, index, msg)
+            }
+        })
+    }
+
     /// Generic event simplifier
     ///
     /// This is a free function often called from [`SendEvent::send`] to
@@ -158,6 +223,9 @@ impl<'a> Manager<'a> {
         if widget.focus_on_key_nav() && event == Event::NavFocus(true) {
             return Response::Focus(widget.rect());
         }
+        if event == Event::ScrollTo {
+            return Response::Reveal(widget.rect(), AlignHints::NONE);
+        }
 
         widget.handle(mgr, event)
     }
@@ -8,24 +8,38 @@
 // Without winit, several things go unused
 #![cfg_attr(not(feature = "winit"), allow(unused))]
 
-use linear_map::{set::LinearSet, LinearMap};
+use instant::Instant;
+use linear_map::LinearMap;
 use log::trace;
 use smallvec::SmallVec;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
-use std::time::Instant;
 use std::u16;
 
 use super::*;
 use crate::cast::Cast;
-use crate::geom::Coord;
-#[allow(unused)]
-use crate::WidgetConfig; // for doc-links
-use crate::{ShellWindow, TkAction, Widget, WidgetId, WindowId};
-
+use crate::dir::LayoutDirection;
+use crate::draw::InputState;
+use crate::geom::{Coord, Size};
+use crate::{ShellWindow, TkAction, Widget, WidgetChildren, WidgetConfig, WidgetId, WindowId};
+use small_map::SmallMap;
+
+mod bus;
+mod disabled;
+mod frame;
+mod hover_focus;
+mod inspector;
+mod key_diag;
 mod mgr_pub;
 mod mgr_shell;
+mod reconfigure;
+mod small_map;
+mod toast;
+mod tooltip;
+
+pub use bus::BusMessage;
+pub use toast::{Severity, Toast, ToastAction};
 
 /// Controls the types of events delivered by [`Manager::request_grab`]
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -40,6 +54,8 @@ pub enum GrabMode {
     PanRotate,
     /// Deliver [`Event::Pan`] events, without scaling or rotation
     PanOnly,
+    /// Deliver [`Event::Gesture`] events, with scaling and rotation
+    Gesture,
 }
 
 #[derive(Clone, Debug)]
@@ -64,6 +80,14 @@ struct TouchGrab {
 
 const MAX_PAN_GRABS: usize = 2;
 
+/// Inline capacity for simultaneous touches (and pan grabs)
+///
+/// Exceeding this is supported but causes allocation.
+const TOUCH_CAPACITY: usize = 16;
+
+/// Inline capacity for simultaneously depressed keys
+const KEY_CAPACITY: usize = 8;
+
 #[derive(Clone, Debug)]
 struct PanGrab {
     id: WidgetId,
@@ -79,6 +103,7 @@ enum Pending {
     LostCharFocus(WidgetId),
     LostSelFocus(WidgetId),
     SetNavFocus(WidgetId, bool),
+    ScrollTo(WidgetId),
 }
 
 /// Event manager state
@@ -89,11 +114,9 @@ enum Pending {
 ///
 /// Besides event handling, this struct also configures widgets.
 ///
-/// Some methods are intended only for usage by KAS shells and are hidden from
-/// documentation unless the `internal_doc` feature is enabled. Only [winit]
-/// events are currently supported; changes will be required to generalise this.
-///
-/// [winit]: https://github.com/rust-windowing/winit
+/// Some methods (under "Shell API") are intended only for usage by KAS shells;
+/// see the `kas-shell` crate. Input may be passed via the `handle_*` methods
+/// of [`Manager`] or, with the `winit` feature, `ManagerState::handle_winit`.
 //
 // Note that the most frequent usage of fields is to check highlighting states
 // for each widget during drawing. Most fields contain only a few values, hence
@@ -102,6 +125,9 @@ enum Pending {
 pub struct ManagerState {
     config: Rc<RefCell<Config>>,
     scale_factor: f32,
+    window_size: Size,
+    layout_dir: LayoutDirection,
+    layout_dir_override: Option<LayoutDirection>,
     end_id: WidgetId,
     modifiers: ModifiersState,
     /// char focus is on same widget as sel_focus; otherwise its value is ignored
@@ -111,30 +137,59 @@ pub struct ManagerState {
     nav_fallback: Option<WidgetId>,
     hover: Option<WidgetId>,
     hover_icon: CursorIcon,
-    key_depress: LinearMap<u32, WidgetId>,
+    // Interaction states forced by ManagerState::simulate_state
+    simulated: HashMap<WidgetId, InputState>,
+    key_depress: SmallMap<[(u32, WidgetId); KEY_CAPACITY]>,
     last_mouse_coord: Coord,
     last_click_button: MouseButton,
     last_click_repetitions: u32,
     last_click_timeout: Instant,
+    scroll_source: ScrollSource,
     mouse_grab: Option<MouseGrab>,
-    touch_grab: LinearMap<u64, TouchGrab>,
-    pan_grab: SmallVec<[PanGrab; 4]>,
+    touch_grab: SmallMap<[(u64, TouchGrab); TOUCH_CAPACITY]>,
+    pan_grab: SmallVec<[PanGrab; TOUCH_CAPACITY]>,
     accel_stack: Vec<(bool, HashMap<VirtualKeyCode, WidgetId>)>,
     accel_layers: HashMap<WidgetId, (bool, HashMap<VirtualKeyCode, WidgetId>)>,
+    // Accelerator keys reassigned due to conflicts: (byte index in label, key)
+    accel_reassigned: HashMap<WidgetId, (usize, VirtualKeyCode)>,
+    // Whether each focus scope (or root) contains an explicit tab index
+    tab_index_scopes: HashMap<WidgetId, bool>,
     // For each: (WindowId of popup, popup descriptor, old nav focus)
     popups: SmallVec<[(WindowId, crate::Popup, Option<WidgetId>); 16]>,
     new_popups: SmallVec<[WidgetId; 16]>,
     popup_removed: SmallVec<[(WidgetId, WindowId); 16]>,
     time_updates: Vec<(Instant, WidgetId, u64)>,
+    reconfigure: Vec<WidgetId>,
     // TODO(opt): consider other containers, e.g. C++ multimap
     // or sorted Vec with binary search yielding a range
-    handle_updates: HashMap<UpdateHandle, LinearSet<WidgetId>>,
+    handle_updates: HashMap<UpdateHandle, LinearMap<WidgetId, Option<UpdateFilter>>>,
     pending: SmallVec<[Pending; 8]>,
+    inspector: inspector::Inspector,
+    key_diag: key_diag::KeyDiagnostics,
+    tooltip: tooltip::Tooltip,
+    hover_focus: hover_focus::HoverFocusState,
+    toasts: toast::Toasts,
+    frame: frame::FrameTiming,
+    bus: bus::Bus,
+    theme_generation: u64,
     action: TkAction,
 }
 
 /// internals
 impl ManagerState {
+    /// Update the effective layout direction, returning true if changed
+    fn update_layout_direction(&mut self, shell: &dyn ShellWindow) -> bool {
+        let dir = self.layout_dir_override.unwrap_or_else(|| {
+            let locale = shell.locale();
+            locale
+                .map(|l| LayoutDirection::from_locale(&l))
+                .unwrap_or_default()
+        });
+        let changed = dir != self.layout_dir;
+        self.layout_dir = dir;
+        changed
+    }
+
     #[inline]
     fn char_focus(&self) -> Option<WidgetId> {
         if self.char_focus {
@@ -233,8 +288,7 @@ impl ManagerState {
 /// in order to provide a convenient user-interface during event processing.
 ///
 /// It exposes two interfaces: one aimed at users implementing widgets and UIs
-/// and one aimed at shells. The latter is hidden
-/// from documentation unless the `internal_doc` feature is enabled.
+/// and one aimed at shells (under "Shell API"; see the `kas-shell` crate).
 #[must_use]
 pub struct Manager<'a> {
     state: &'a mut ManagerState,
@@ -266,6 +320,8 @@ impl<'a> Manager<'a> {
                 }
             }
             self.state.hover = w_id;
+            self.reset_tooltip();
+            self.reset_hover_focus(widget);
 
             if let Some(id) = w_id {
                 let mut icon = widget.cursor_icon();
@@ -287,6 +343,73 @@ impl<'a> Manager<'a> {
         }
     }
 
+    /// Send a [`Command`] to the most appropriate recipient
+    ///
+    /// Returns true if the command was handled.
+    fn send_command<W>(&mut self, widget: &mut W, cmd: Command, shift: bool) -> bool
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        if self.state.char_focus {
+            if let Some(id) = self.state.sel_focus {
+                if self.try_send_command(widget, "char focus", id, cmd, shift) {
+                    return true;
+                }
+            }
+        }
+
+        if !self.state.modifiers.alt() {
+            if let Some(id) = self.state.nav_focus {
+                if self.try_send_command(widget, "nav focus", id, cmd, shift) {
+                    return true;
+                }
+            }
+        }
+
+        if let Some(id) = self.state.popups.last().map(|popup| popup.1.parent) {
+            if self.try_send_command(widget, "popup parent", id, cmd, shift) {
+                return true;
+            }
+        }
+
+        if self.state.sel_focus != self.state.nav_focus && cmd.suitable_for_sel_focus() {
+            if let Some(id) = self.state.sel_focus {
+                if self.try_send_command(widget, "sel focus", id, cmd, shift) {
+                    return true;
+                }
+            }
+        }
+
+        if let Some(id) = self.state.nav_fallback {
+            if self.try_send_command(widget, "nav fallback", id, cmd, shift) {
+                return true;
+            }
+        }
+
+        self.key_diag(|| format!("{:?} was not handled", cmd));
+        false
+    }
+
+    /// Send a [`Command`] to `id`, logging diagnostics with label `target`
+    fn try_send_command<W>(
+        &mut self,
+        widget: &mut W,
+        target: &str,
+        id: WidgetId,
+        cmd: Command,
+        shift: bool,
+    ) -> bool
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        let handled = self.try_send_event(widget, id, Event::Command(cmd, shift));
+        self.key_diag(|| {
+            let result = if handled { "handled" } else { "unhandled" };
+            format!("sent {:?} to {} {}: {}", cmd, target, id, result)
+        });
+        handled
+    }
+
     fn start_key_event<W>(&mut self, widget: &mut W, vkey: VirtualKeyCode, scancode: u32)
     where
         W: Widget<Msg = VoidMsg> + ?Sized,
@@ -307,42 +430,26 @@ impl<'a> Manager<'a> {
             .borrow()
             .shortcuts()
             .get(self.state.modifiers, vkey);
+        self.key_diag(|| {
+            format!(
+                "key press {:?} (scancode {}, modifiers {:?}); shortcut: {:?}",
+                vkey, scancode, self.state.modifiers, opt_command
+            )
+        });
 
-        if let Some(cmd) = opt_command {
-            if self.state.char_focus {
-                if let Some(id) = self.state.sel_focus {
-                    if self.try_send_event(widget, id, Event::Command(cmd, shift)) {
-                        return;
-                    }
-                }
-            }
-
-            if !self.state.modifiers.alt() {
-                if let Some(id) = self.state.nav_focus {
-                    if self.try_send_event(widget, id, Event::Command(cmd, shift)) {
-                        return;
-                    }
-                }
-            }
-
-            if let Some(id) = self.state.popups.last().map(|popup| popup.1.parent) {
-                if self.try_send_event(widget, id, Event::Command(cmd, shift)) {
-                    return;
-                }
-            }
-
-            if self.state.sel_focus != self.state.nav_focus && cmd.suitable_for_sel_focus() {
-                if let Some(id) = self.state.sel_focus {
-                    if self.try_send_event(widget, id, Event::Command(cmd, shift)) {
-                        return;
-                    }
-                }
-            }
+        if opt_command == Some(Command::Debug) {
+            self.state.inspector.active = !self.state.inspector.active;
+            self.send_action(TkAction::REDRAW);
+            return;
+        } else if self.state.inspector.active && opt_command == Some(Command::Escape) {
+            self.state.inspector.active = false;
+            self.send_action(TkAction::REDRAW);
+            return;
+        }
 
-            if let Some(id) = self.state.nav_fallback {
-                if self.try_send_event(widget, id, Event::Command(cmd, shift)) {
-                    return;
-                }
+        if let Some(cmd) = opt_command {
+            if self.send_command(widget, cmd, shift) {
+                return;
             }
         }
 
@@ -356,12 +463,17 @@ impl<'a> Manager<'a> {
         {
             if let Some(layer) = self.state.accel_layers.get(&id) {
                 // but only when Alt is held or alt-bypass is enabled:
-                if self.state.modifiers.alt() || layer.0 {
-                    if let Some(id) = layer.1.get(&vkey).cloned() {
-                        target = Some(id);
-                        n = i;
-                        break;
-                    }
+                let enabled = self.state.modifiers.alt() || layer.0;
+                let found = layer.1.get(&vkey).cloned();
+                self.key_diag(|| match (enabled, found) {
+                    (false, _) => format!("accel layer {}: skipped (Alt not held)", id),
+                    (true, Some(t)) => format!("accel layer {}: {:?} bound to {}", id, vkey, t),
+                    (true, None) => format!("accel layer {}: no binding for {:?}", id, vkey),
+                });
+                if let Some(id) = found.filter(|_| enabled) {
+                    target = Some(id);
+                    n = i;
+                    break;
                 }
             }
         }
@@ -379,22 +491,33 @@ impl<'a> Manager<'a> {
             if widget.find_leaf(id).map(|w| w.key_nav()).unwrap_or(false) {
                 self.set_nav_focus(id, true);
             }
+            self.key_diag(|| format!("sent Activate to {} (accelerator)", id));
             self.add_key_depress(scancode, id);
             self.send_event(widget, id, Event::Activate);
         } else if vkey == VK::Tab {
+            self.key_diag(|| "Tab: moving nav focus".to_string());
             self.clear_char_focus();
             self.next_nav_focus(widget.as_widget_mut(), shift, true);
         } else if vkey == VK::Escape {
             if let Some(id) = self.state.popups.last().map(|(id, _, _)| *id) {
+                self.key_diag(|| format!("Escape: closing popup {}", id));
                 self.close_window(id, true);
+            } else if self.exit_focus_scope(widget.as_widget_mut()) {
+                self.key_diag(|| "Escape: exited focus scope".to_string());
+            } else {
+                self.key_diag(|| "Escape: no popup to close or focus scope to exit".to_string());
             }
         } else if !self.state.char_focus {
-            if let Some(id) = self.state.nav_focus {
-                if vkey == VK::Space || vkey == VK::Return || vkey == VK::NumpadEnter {
+            match self.state.nav_focus {
+                Some(id) if vkey == VK::Space || vkey == VK::Return || vkey == VK::NumpadEnter => {
+                    self.key_diag(|| format!("sent Activate to nav focus {}", id));
                     self.add_key_depress(scancode, id);
                     self.send_event(widget, id, Event::Activate);
                 }
+                _ => self.key_diag(|| format!("{:?} was not consumed", vkey)),
             }
+        } else {
+            self.key_diag(|| format!("{:?} not consumed (char focus: expecting input)", vkey));
         }
     }
 
@@ -500,6 +623,11 @@ impl<'a> Manager<'a> {
     ) -> bool {
         trace!("Send to {}: {:?}", id, event);
         let r = widget.send(self, id, event);
+        match r {
+            Response::FocusOn(id) => self.set_nav_focus(id, false),
+            Response::SizeChanged => *self |= TkAction::RESIZE,
+            _ => (),
+        }
         !matches!(r, Response::Unhandled)
     }
 
@@ -518,12 +646,25 @@ impl<'a> Manager<'a> {
 }
 
 /// Helper used during widget configuration
+///
+/// Identifiers are allocated in order (children before parents; see
+/// [`crate::WidgetChildren::first_id`]) and are kept across reconfigures
+/// where possible. To allow this when children are added, a small gap is left
+/// before each child and before each parent's own identifier when these are
+/// first allocated. An inserted child is allocated within the gap before the
+/// next existing sibling; only where this is insufficient are later siblings
+/// renumbered.
 pub struct ConfigureManager<'a: 'b, 'b> {
     id: &'b mut WidgetId,
+    // Upper bound (exclusive) on new identifiers, or LAST if unbounded
+    limit: WidgetId,
     map: &'b mut HashMap<WidgetId, WidgetId>,
     mgr: &'b mut Manager<'a>,
 }
 
+/// Size of gaps left between newly allocated identifiers
+const ID_GAP: u32 = 4;
+
 impl<'a: 'b, 'b> ConfigureManager<'a, 'b> {
     /// Reborrow self to pass to a child
     pub fn child<'c>(&'c mut self) -> ConfigureManager<'a, 'c>
@@ -532,21 +673,98 @@ impl<'a: 'b, 'b> ConfigureManager<'a, 'b> {
     {
         ConfigureManager {
             id: &mut *self.id,
+            limit: self.limit,
             map: &mut *self.map,
             mgr: &mut *self.mgr,
         }
     }
 
+    /// Configure all children of `widget`
+    ///
+    /// This calls [`WidgetConfig::configure_recurse`] on each child, keeping
+    /// the identifiers of existing children where possible. It should be
+    /// called after [`crate::WidgetChildren::record_first_id`] and before
+    /// [`Self::next_id`].
+    pub fn configure_children<W: WidgetChildren + ?Sized>(&mut self, widget: &mut W) {
+        let n = widget.num_children();
+        // New children are allocated before the widget's old ID, if kept
+        let old_id = widget.id();
+        let scope = if !old_id.is_unconfigured() && old_id >= *self.id && old_id < self.limit {
+            old_id
+        } else {
+            self.limit
+        };
+
+        for i in 0..n {
+            let first = match widget.get_child(i) {
+                Some(w) if !w.id().is_unconfigured() => Some(w.first_id()),
+                Some(_) => None,
+                None => continue,
+            };
+            let limit = match first {
+                Some(first) if first >= *self.id && first < scope => {
+                    // Keep existing identifiers (and the gap before these)
+                    *self.id = first;
+                    scope
+                }
+                _ => {
+                    // Allocate before the next kept sibling
+                    let next = (i + 1..n)
+                        .filter_map(|j| widget.get_child(j))
+                        .filter(|w| !w.id().is_unconfigured())
+                        .map(|w| w.first_id())
+                        .find(|first| *first >= *self.id && *first < scope);
+                    let limit = next.unwrap_or(scope);
+                    if limit == WidgetId::LAST {
+                        *self.id = self.id.advance(ID_GAP);
+                    }
+                    limit
+                }
+            };
+            let w = widget.get_child_mut(i).unwrap();
+            w.configure_recurse(ConfigureManager {
+                id: &mut *self.id,
+                limit,
+                map: &mut *self.map,
+                mgr: &mut *self.mgr,
+            });
+        }
+
+        if n > 0 && scope == WidgetId::LAST {
+            // Leave room to append children
+            *self.id = self.id.advance(ID_GAP);
+        }
+    }
+
     /// Get the next [`WidgetId`], without advancing the counter
     pub fn peek_next(&self) -> WidgetId {
         *self.id
     }
 
+    /// Reserve identifiers for later use
+    ///
+    /// This should be called after configuring children but before
+    /// [`Self::next_id`], passing the same `old_id`. Unless the old ID will be
+    /// kept, `n` identifiers are skipped, leaving room for children added later
+    /// to be configured via [`Manager::reconfigure_subtree`] (without
+    /// reconfiguring the whole window).
+    pub fn reserve_ids(&mut self, old_id: WidgetId, n: u32) {
+        if old_id.is_unconfigured() || old_id < *self.id {
+            *self.id = self.id.advance(n);
+        }
+    }
+
     /// Get a new [`WidgetId`] for the widget
     ///
     /// Pass the old ID (`self.id()`), even if not yet configured.
+    ///
+    /// The old ID is kept where possible: that is, when the widget was
+    /// previously configured and `old_id` is not less than [`Self::peek_next`]
+    /// (nor within the range of a following sibling). Otherwise, the next ID
+    /// is allocated.
     pub fn next_id(&mut self, old_id: WidgetId) -> WidgetId {
-        let id = *self.id;
+        let keep = !old_id.is_unconfigured() && old_id >= *self.id && old_id < self.limit;
+        let id = if keep { old_id } else { *self.id };
         *self.id = id.next();
         self.map.insert(old_id, id);
         id
@@ -0,0 +1,142 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Typed message bus
+//!
+//! Messages normally bubble up the widget tree to the parent, which must
+//! handle or convert them. The bus instead delivers messages by type to any
+//! subscribed widget within the same window, allowing distant widgets (e.g. an
+//! editor and a status bar) to communicate without involving the message
+//! types of intermediate parents.
+
+use linear_map::set::LinearSet;
+use std::any::{Any, TypeId};
+use std::fmt;
+
+use super::*;
+
+/// A message published via [`Manager::publish`]
+///
+/// Subscribers receive this as [`Event::Bus`]. The message is shared between
+/// all subscribers and may be accessed via [`BusMessage::downcast_ref`].
+#[derive(Clone)]
+pub struct BusMessage {
+    type_name: &'static str,
+    msg: Rc<dyn Any>,
+}
+
+impl BusMessage {
+    /// Construct
+    pub fn new<T: 'static>(msg: T) -> Self {
+        BusMessage {
+            type_name: std::any::type_name::<T>(),
+            msg: Rc::new(msg),
+        }
+    }
+
+    /// True if the message has type `T`
+    #[inline]
+    pub fn is<T: 'static>(&self) -> bool {
+        self.msg.is::<T>()
+    }
+
+    /// Access the message, if it has type `T`
+    #[inline]
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.msg.downcast_ref()
+    }
+
+    fn type_id(&self) -> TypeId {
+        (*self.msg).type_id()
+    }
+}
+
+impl fmt::Debug for BusMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BusMessage<{}>", self.type_name)
+    }
+}
+
+/// Compares by identity
+impl PartialEq for BusMessage {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.msg, &other.msg)
+    }
+}
+
+/// Message bus state
+#[derive(Debug, Default)]
+pub(super) struct Bus {
+    subscribers: HashMap<TypeId, LinearSet<WidgetId>>,
+    queue: Vec<BusMessage>,
+}
+
+impl Bus {
+    /// Clear subscriptions (these are renewed on configure)
+    pub(super) fn clear_subscribers(&mut self) {
+        self.subscribers.clear();
+    }
+
+    /// Remove subscriptions of widgets matching `f`
+    pub(super) fn remove_subscribers<F: Fn(WidgetId) -> bool>(&mut self, f: F) {
+        for ids in self.subscribers.values_mut() {
+            let removed: Vec<WidgetId> = ids.iter().cloned().filter(|id| f(*id)).collect();
+            for id in removed {
+                ids.remove(&id);
+            }
+        }
+    }
+}
+
+/// Message bus
+impl<'a> Manager<'a> {
+    /// Deliver queued bus messages
+    ///
+    /// Messages published during delivery are delivered in the same call.
+    pub(super) fn deliver_bus_messages<W: Widget + ?Sized>(&mut self, widget: &mut W) {
+        while !self.state.bus.queue.is_empty() {
+            let queue = std::mem::take(&mut self.state.bus.queue);
+            for msg in queue {
+                let ids = match self.state.bus.subscribers.get(&msg.type_id()) {
+                    Some(ids) => ids.clone(),
+                    None => continue,
+                };
+                for id in ids {
+                    trace!("Manager: deliver {:?} to {}", msg, id);
+                    self.send_event(widget, id, Event::Bus(msg.clone()));
+                }
+            }
+        }
+    }
+
+    /// Subscribe to messages of type `T`
+    ///
+    /// The widget will receive [`Event::Bus`] for each message of type `T`
+    /// published within this window via [`Manager::publish`].
+    ///
+    /// This should be called from [`WidgetConfig::configure`].
+    pub fn subscribe<T: 'static>(&mut self, w_id: WidgetId) {
+        trace!(
+            "Manager::subscribe: {} to {}",
+            w_id,
+            std::any::type_name::<T>()
+        );
+        self.state
+            .bus
+            .subscribers
+            .entry(TypeId::of::<T>())
+            .or_insert_with(Default::default)
+            .insert(w_id);
+    }
+
+    /// Publish a message to all subscribers
+    ///
+    /// All widgets in this window subscribed to type `T` (see
+    /// [`Manager::subscribe`]) will receive `msg` as [`Event::Bus`]. Delivery
+    /// is deferred until the current event has been handled.
+    pub fn publish<T: 'static>(&mut self, msg: T) {
+        self.state.bus.queue.push(BusMessage::new(msg));
+    }
+}
@@ -0,0 +1,104 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Disabled-state propagation
+//!
+//! [`crate::WidgetCore::set_disabled`] only changes a flag; descendants are
+//! not notified. [`Manager::set_disabled`] additionally sends
+//! [`Event::DisabledChanged`] to the affected subtree and releases grabs and
+//! focus held within this subtree.
+
+use log::debug;
+
+use super::*;
+
+/// Disabled-state propagation
+impl<'a> Manager<'a> {
+    /// Set the disabled state of `widget`, notifying its subtree
+    ///
+    /// This is like [`crate::WidgetCore::set_disabled`], but additionally
+    /// sends [`Event::DisabledChanged`] to `widget` and to each descendant
+    /// whose effective state changes (descendants which are themselves
+    /// disabled are skipped). Responses to these events are discarded.
+    ///
+    /// When disabling, any mouse or touch grab started within the subtree is
+    /// cancelled and navigation focus is cleared if held within the subtree.
+    ///
+    /// Does nothing if `widget.is_disabled() == disabled`.
+    pub fn set_disabled<W: Widget + ?Sized>(&mut self, widget: &mut W, disabled: bool) {
+        if widget.is_disabled() == disabled {
+            return;
+        }
+
+        fn collect(w: &dyn WidgetConfig, ids: &mut Vec<WidgetId>) {
+            for index in 0..w.num_children() {
+                if let Some(child) = w.get_child(index) {
+                    if !child.is_disabled() {
+                        collect(child, ids);
+                    }
+                }
+            }
+            ids.push(w.id());
+        }
+        let mut ids = vec![];
+        collect(widget.as_widget(), &mut ids);
+        debug!(
+            "Manager::set_disabled: {} = {} ({} widgets)",
+            widget.id(),
+            disabled,
+            ids.len()
+        );
+
+        // Events cannot be routed through a disabled widget, hence when
+        // disabling we must send before changing state.
+        if disabled {
+            for id in ids {
+                self.send_event(widget, id, Event::DisabledChanged(true));
+            }
+            *self |= widget.set_disabled(true);
+
+            let (first, last) = (widget.first_id(), widget.id());
+            self.release_subtree(first, last);
+        } else {
+            *self |= widget.set_disabled(false);
+            for id in ids {
+                self.send_event(widget, id, Event::DisabledChanged(false));
+            }
+        }
+    }
+
+    /// Release grabs and navigation focus held within the range `first..=last`
+    fn release_subtree(&mut self, first: WidgetId, last: WidgetId) {
+        let in_range = |x: WidgetId| first <= x && x <= last;
+
+        if let Some(grab) = self.state.mouse_grab.as_ref() {
+            if in_range(grab.start_id) {
+                let grab = self.state.mouse_grab.take().unwrap();
+                trace!("Manager: cancel mouse grab by {}", grab.start_id);
+                self.shell.set_cursor_icon(self.state.hover_icon);
+                self.redraw(grab.start_id);
+                self.state.remove_pan_grab(grab.pan_grab);
+            }
+        }
+
+        let touches: SmallVec<[u64; TOUCH_CAPACITY]> = (self.state.touch_grab.iter())
+            .filter(|(_, grab)| in_range(grab.start_id))
+            .map(|(touch_id, _)| *touch_id)
+            .collect();
+        for touch_id in touches {
+            if let Some(grab) = self.state.touch_grab.remove(&touch_id) {
+                trace!("Manager: cancel touch grab by {}", grab.start_id);
+                self.redraw(grab.start_id);
+                self.state.remove_pan_grab(grab.pan_grab);
+            }
+        }
+
+        self.state.key_depress.retain(|_, id| !in_range(*id));
+
+        if self.state.nav_focus.map(in_range).unwrap_or(false) {
+            self.clear_nav_focus();
+        }
+    }
+}
@@ -0,0 +1,231 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Display frame timing
+//!
+//! Shells report the presentation time of each frame via
+//! [`ManagerState::frame_presented`], along with the display's refresh
+//! interval where this is known. Animations should interpolate their state
+//! for [`Manager::next_frame_time`] (rather than for the current time), and
+//! request frames via [`Manager::update_on_frame`].
+//!
+//! Shells may also report statistics of each frame drawn via
+//! [`ManagerState::set_draw_stats`].
+
+use instant::Instant;
+use std::time::Duration;
+
+use super::*;
+use crate::draw::DrawStats;
+
+/// Assumed refresh interval until another is reported or measured (60Hz)
+const DEFAULT_INTERVAL: Duration = Duration::from_nanos(16_666_667);
+/// Frames further apart than this are not used to estimate the interval
+const MAX_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Frame timing state
+#[derive(Clone, Debug)]
+pub(super) struct FrameTiming {
+    /// Presentation time of the last frame
+    last: Option<Instant>,
+    /// Display refresh interval
+    interval: Duration,
+    /// True if a frame was requested for the next refresh
+    continuous: bool,
+    /// Statistics of the last frame drawn, if reported
+    draw_stats: Option<DrawStats>,
+}
+
+impl Default for FrameTiming {
+    fn default() -> Self {
+        FrameTiming {
+            last: None,
+            interval: DEFAULT_INTERVAL,
+            continuous: false,
+            draw_stats: None,
+        }
+    }
+}
+
+impl FrameTiming {
+    fn presented(&mut self, time: Instant, interval: Option<Duration>) {
+        let continuous = std::mem::replace(&mut self.continuous, false);
+        if let Some(interval) = interval.filter(|d| *d > Duration::ZERO) {
+            self.interval = interval;
+        } else if let (true, Some(last)) = (continuous, self.last) {
+            let delta = time.saturating_duration_since(last);
+            if delta > Duration::ZERO && delta < MAX_INTERVAL {
+                self.interval = (self.interval * 7 + delta) / 8;
+            }
+        }
+        self.last = Some(time);
+    }
+
+    // Predicted presentation time of the first frame after `now`
+    fn next_after(&self, now: Instant) -> Instant {
+        let interval = self.interval;
+        match self.last {
+            Some(last) if last > now => last + interval,
+            Some(last) => {
+                let n = (now - last).as_nanos() / interval.as_nanos() + 1;
+                last + interval * u32::try_from(n).unwrap_or(u32::MAX)
+            }
+            None => now + interval,
+        }
+    }
+}
+
+impl ManagerState {
+    /// Report presentation of a frame
+    ///
+    /// `time` should be the (possibly predicted) time at which the frame
+    /// appears on the display. `interval` should be the refresh interval of
+    /// the display currently showing the window, if known; otherwise this is
+    /// estimated from the timing of consecutive animation frames (thus
+    /// adapting when the window moves to another display).
+    #[inline]
+    pub fn frame_presented(&mut self, time: Instant, interval: Option<Duration>) {
+        self.frame.presented(time, interval);
+    }
+
+    /// Report statistics of the last frame drawn
+    #[inline]
+    pub fn set_draw_stats(&mut self, stats: DrawStats) {
+        self.frame.draw_stats = Some(stats);
+    }
+
+    /// Statistics of the last frame drawn, if available
+    ///
+    /// Statistics are only available when collection is enabled in the shell
+    /// (for `kas-wgpu`, see `KAS_DRAW_STATS`).
+    #[inline]
+    pub fn draw_stats(&self) -> Option<&DrawStats> {
+        self.frame.draw_stats.as_ref()
+    }
+
+    /// Refresh interval of the display showing this window
+    ///
+    /// This is reported by the shell or measured; before either is possible
+    /// a 60Hz display is assumed.
+    #[inline]
+    pub fn frame_interval(&self) -> Duration {
+        self.frame.interval
+    }
+
+    /// Predicted presentation time of the next frame
+    ///
+    /// Animations should interpolate their state for this time, which is
+    /// aligned to the display's refresh cycle, instead of using
+    /// [`Instant::now`] (which varies with event-loop latency and causes
+    /// stutter, especially on high refresh-rate displays).
+    #[inline]
+    pub fn next_frame_time(&self) -> Instant {
+        self.frame.next_after(Instant::now())
+    }
+}
+
+/// Frame timing
+impl<'a> Manager<'a> {
+    /// Refresh interval of the display showing this window
+    ///
+    /// See [`ManagerState::frame_interval`].
+    #[inline]
+    pub fn frame_interval(&self) -> Duration {
+        self.state.frame_interval()
+    }
+
+    /// Predicted presentation time of the next frame
+    ///
+    /// See [`ManagerState::next_frame_time`].
+    #[inline]
+    pub fn next_frame_time(&self) -> Instant {
+        self.state.next_frame_time()
+    }
+
+    /// Statistics of the last frame drawn, if available
+    ///
+    /// See [`ManagerState::draw_stats`].
+    #[inline]
+    pub fn draw_stats(&self) -> Option<&DrawStats> {
+        self.state.draw_stats()
+    }
+
+    /// Schedule an update for the next frame
+    ///
+    /// The widget will receive [`Event::TimerUpdate`] (with this `payload`)
+    /// in time to prepare the next frame. This should be used for animation,
+    /// in place of [`Manager::update_on_timer`] with a fixed frame time.
+    /// The widget should interpolate its state for
+    /// [`Manager::next_frame_time`] and request a redraw.
+    pub fn update_on_frame(&mut self, w_id: WidgetId, payload: u64) {
+        self.state.frame.continuous = true;
+        let start = self.state.next_frame_time() - self.state.frame.interval;
+        let delay = start.saturating_duration_since(Instant::now());
+        self.update_on_timer(delay, w_id, payload);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn next_frame() {
+        let t0 = Instant::now();
+        let mut frame = FrameTiming::default();
+        assert_eq!(frame.next_after(t0), t0 + DEFAULT_INTERVAL);
+
+        frame.presented(t0, Some(10 * MS));
+        assert_eq!(frame.interval, 10 * MS);
+        // Aligned to the refresh cycle of the last frame
+        assert_eq!(frame.next_after(t0), t0 + 10 * MS);
+        assert_eq!(frame.next_after(t0 + 3 * MS), t0 + 10 * MS);
+        assert_eq!(frame.next_after(t0 + 10 * MS), t0 + 20 * MS);
+        assert_eq!(frame.next_after(t0 + 25 * MS), t0 + 30 * MS);
+
+        // Presentation may be predicted in the future
+        frame.presented(t0 + 10 * MS, None);
+        assert_eq!(frame.next_after(t0 + 5 * MS), t0 + 20 * MS);
+    }
+
+    #[test]
+    fn reported_interval() {
+        let t0 = Instant::now();
+        let mut frame = FrameTiming::default();
+        frame.presented(t0, Some(7 * MS));
+        assert_eq!(frame.interval, 7 * MS);
+        // A reported interval replaces the estimate immediately
+        frame.continuous = true;
+        frame.presented(t0 + 20 * MS, Some(4 * MS));
+        assert_eq!(frame.interval, 4 * MS);
+        assert_eq!(frame.last, Some(t0 + 20 * MS));
+        // Zero is ignored
+        frame.presented(t0 + 24 * MS, Some(Duration::ZERO));
+        assert_eq!(frame.interval, 4 * MS);
+    }
+
+    #[test]
+    fn measured_interval() {
+        let t0 = Instant::now();
+        let mut frame = FrameTiming::default();
+        frame.presented(t0, Some(16 * MS));
+
+        // Frames not requested for animation are not measured
+        frame.presented(t0 + 8 * MS, None);
+        assert_eq!(frame.interval, 16 * MS);
+
+        frame.continuous = true;
+        frame.presented(t0 + 16 * MS, None);
+        assert_eq!(frame.interval, 15 * MS);
+        assert!(!frame.continuous);
+
+        // Long gaps are ignored
+        frame.continuous = true;
+        frame.presented(t0 + 116 * MS, None);
+        assert_eq!(frame.interval, 15 * MS);
+    }
+}
@@ -0,0 +1,90 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Focus follows mouse
+//!
+//! Where enabled (see [`Config::hover_focus`] and
+//! [`WidgetConfig::hover_focus`]), when the mouse rests over a widget for the
+//! applicable delay, the innermost [`WidgetConfig::key_nav`] widget along the
+//! path to the hovered widget receives navigation focus.
+
+use instant::Instant;
+
+use super::*;
+#[allow(unused)]
+use crate::WidgetConfig; // for doc-links
+
+/// Hover-focus state
+#[derive(Clone, Debug, Default)]
+pub(super) struct HoverFocusState {
+    /// Time at which to focus the given widget
+    pending: Option<(Instant, WidgetId)>,
+}
+
+impl ManagerState {
+    /// Time at which the pending hover focus (if any) should be applied
+    pub(super) fn hover_focus_resume(&self) -> Option<Instant> {
+        self.hover_focus.pending.map(|(time, _)| time)
+    }
+}
+
+/// Hover-focus methods
+impl<'a> Manager<'a> {
+    /// (Re)start the delay for the hovered widget
+    pub(super) fn reset_hover_focus<W: Widget + ?Sized>(&mut self, widget: &W) {
+        self.state.hover_focus.pending = None;
+        let id = match self.state.hover {
+            Some(id) => id,
+            None => return,
+        };
+
+        let (enabled, default_delay) = {
+            let config = self.state.config.borrow();
+            (config.hover_focus(), config.hover_focus_delay())
+        };
+        let mut delay = enabled.then(|| default_delay);
+
+        // Find the innermost navigable widget and the policy applying to it
+        let mut target = None;
+        let mut node = widget.as_widget();
+        loop {
+            if node.is_disabled() {
+                break;
+            }
+            match node.hover_focus() {
+                HoverFocus::Inherit => (),
+                HoverFocus::Disabled => delay = None,
+                HoverFocus::Enabled => delay = Some(default_delay),
+                HoverFocus::Delay(d) => delay = Some(d),
+            }
+            if node.key_nav() {
+                target = Some((node.id(), delay));
+            }
+            match node.find_child(id) {
+                Some(index) => node = node.get_child(index).unwrap(),
+                None => break,
+            }
+        }
+
+        if let Some((target, Some(delay))) = target {
+            if self.state.nav_focus != Some(target) {
+                self.state.hover_focus.pending = Some((Instant::now() + delay, target));
+            }
+        }
+    }
+
+    /// Set navigation focus if the delay has elapsed
+    pub(super) fn update_hover_focus(&mut self, now: Instant) {
+        match self.state.hover_focus.pending {
+            Some((time, id)) if time <= now => {
+                self.state.hover_focus.pending = None;
+                if self.state.mouse_grab.is_none() {
+                    self.set_nav_focus(id, false);
+                }
+            }
+            _ => (),
+        }
+    }
+}
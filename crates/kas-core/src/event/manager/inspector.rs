@@ -0,0 +1,206 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Widget inspector
+//!
+//! The inspector is a debugging tool, toggled by [`Command::Debug`]
+//! (by default, Ctrl+Shift+I). While active, it outlines all widgets and shows
+//! the widget tree in a side panel. Clicking a widget selects it: its
+//! ancestors are highlighted and details (including size rules) are shown.
+//! Mouse clicks are captured by the inspector; keyboard input is passed to
+//! widgets as usual, except for Escape which closes the inspector.
+
+use std::fmt::Write;
+
+use super::*;
+use crate::cast::CastFloat;
+use crate::draw::{DrawHandle, DrawHandleExt, InputState, InspectorClass, TextClass};
+use crate::geom::{Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::text::Text;
+use crate::{WidgetConfig, WidgetId};
+
+/// Inspector state
+#[derive(Clone, Debug, Default)]
+pub(super) struct Inspector {
+    pub(super) active: bool,
+    pub(super) selected: Option<WidgetId>,
+}
+
+/// Widget inspector
+impl ManagerState {
+    /// True if the widget inspector is active
+    #[inline]
+    pub fn inspector_active(&self) -> bool {
+        self.inspector.active
+    }
+
+    /// The widget selected in the inspector, if any
+    #[inline]
+    pub fn inspector_selection(&self) -> Option<WidgetId> {
+        self.inspector.selected.filter(|_| self.inspector.active)
+    }
+
+    /// Draw the widget inspector, if active
+    ///
+    /// Shells should call this after drawing the window's widget.
+    pub fn draw_inspector(&self, widget: &dyn WidgetConfig, draw: &mut dyn DrawHandle) {
+        if !self.inspector.active {
+            return;
+        }
+
+        let rect = widget.rect();
+        let selected = self.inspector.selected;
+        draw.with_overlay(rect, &mut |draw| {
+            let mut highlights = vec![];
+            outline(widget, selected, draw, &mut highlights);
+            for (rect, class) in highlights {
+                draw.inspector(rect, class);
+            }
+
+            let width = draw.size_handle().pixels_from_em(24.0).cast_nearest();
+            let width = rect.size.0.min(width);
+            let panel = Rect {
+                pos: rect.pos + Size(rect.size.0 - width, 0),
+                size: Size(width, rect.size.1),
+            };
+            draw.inspector(panel, InspectorClass::Panel);
+
+            let mut s = String::new();
+            self.describe(widget, selected, &mut s);
+            let mut text = Text::new_multi(s);
+            draw.size_handle()
+                .text_bound(&mut text, TextClass::Label, AxisInfo::new(false, None));
+            let inner = panel.shrink(draw.size_handle().inner_margin().0);
+            text.update_env(|env| env.set_bounds(inner.size.into()));
+            draw.text(
+                inner.pos,
+                text.as_ref(),
+                TextClass::Label,
+                InputState::empty(),
+            );
+        });
+    }
+
+    /// Write the panel contents to `s`
+    fn describe(&self, widget: &dyn WidgetConfig, selected: Option<WidgetId>, s: &mut String) {
+        let selected = match selected.and_then(|id| widget.find_leaf(id).map(|_| id)) {
+            Some(id) => id,
+            None => {
+                let _ = writeln!(s, "Click a widget to select it\n");
+                self.describe_tree(widget, None, 0, usize::MAX, s);
+                return;
+            }
+        };
+
+        // Show the path from the root to the selection, then its children
+        let mut depth = 0;
+        let mut node = widget;
+        while node.id() != selected {
+            self.describe_node(node, false, depth, s);
+            let next = (0..node.num_children())
+                .filter_map(|i| node.get_child(i))
+                .find(|child| child.is_ancestor_of(selected));
+            match next {
+                Some(child) => node = child,
+                None => break,
+            }
+            depth += 1;
+        }
+        self.describe_tree(node, Some(selected), depth, depth + 1, s);
+
+        // Details for the selection
+        let node = widget.find_leaf(selected).unwrap();
+        let rect = node.rect();
+        let _ = writeln!(s, "\n{} {}", node.id(), node.widget_name());
+        let _ = writeln!(s, "pos: {:?}\nsize: {:?}", rect.pos, rect.size);
+        // Rules are those recorded during the last solve: calling size_rules
+        // here would mutate layout storage between solving and drawing.
+        let (horiz, vert) = node.core_data().size_cache.last_rules();
+        let describe = |rules: Option<SizeRules>| match rules {
+            Some(rules) => format!("{:?}", rules),
+            None => "not recorded".to_string(),
+        };
+        let _ = writeln!(
+            s,
+            "horizontal rules: {}\nvertical rules: {}",
+            describe(horiz),
+            describe(vert)
+        );
+        let (char_focus, sel_focus) = self.has_char_focus(selected);
+        let _ = writeln!(
+            s,
+            "nav focus: {}\nchar focus: {}\nsel focus: {}\nhover: {}",
+            self.nav_focus(selected),
+            char_focus,
+            sel_focus,
+            self.hover == Some(selected),
+        );
+    }
+
+    fn describe_tree(
+        &self,
+        node: &dyn WidgetConfig,
+        selected: Option<WidgetId>,
+        depth: usize,
+        max_depth: usize,
+        s: &mut String,
+    ) {
+        self.describe_node(node, selected == Some(node.id()), depth, s);
+        if depth < max_depth {
+            for child in (0..node.num_children()).filter_map(|i| node.get_child(i)) {
+                self.describe_tree(child, selected, depth + 1, max_depth, s);
+            }
+        }
+    }
+
+    fn describe_node(&self, node: &dyn WidgetConfig, selected: bool, depth: usize, s: &mut String) {
+        let id = node.id();
+        let rect = node.rect();
+        let _ = write!(
+            s,
+            "{}{}{} {} ({}, {}; {}×{})",
+            if selected { "> " } else { "" },
+            "  ".repeat(depth),
+            id,
+            node.widget_name(),
+            rect.pos.0,
+            rect.pos.1,
+            rect.size.0,
+            rect.size.1,
+        );
+        if self.nav_focus(id) {
+            s.push_str(" [nav]");
+        }
+        if self.has_char_focus(id).0 {
+            s.push_str(" [char]");
+        }
+        if self.hover == Some(id) {
+            s.push_str(" [hover]");
+        }
+        s.push('\n');
+    }
+}
+
+/// Outline `node` and its descendants
+///
+/// Outlines of the selection and its ancestors are deferred to `highlights`.
+fn outline(
+    node: &dyn WidgetConfig,
+    selected: Option<WidgetId>,
+    draw: &mut dyn DrawHandle,
+    highlights: &mut Vec<(Rect, InspectorClass)>,
+) {
+    match selected {
+        Some(id) if id == node.id() => highlights.push((node.rect(), InspectorClass::Selected)),
+        Some(id) if node.is_ancestor_of(id) => {
+            highlights.push((node.rect(), InspectorClass::Ancestor))
+        }
+        _ => draw.inspector(node.rect(), InspectorClass::Bounds),
+    }
+    for child in (0..node.num_children()).filter_map(|i| node.get_child(i)) {
+        outline(child, selected, draw, highlights);
+    }
+}
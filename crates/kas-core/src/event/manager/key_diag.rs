@@ -0,0 +1,66 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Key event diagnostics
+//!
+//! When enabled, the routing of each key event is logged (at `info` level):
+//! the shortcut resolved (if any), each focus target to which a [`Command`]
+//! was sent and whether it was handled, the accelerator layers consulted and
+//! finally which widget consumed the key and how (as [`Event::Command`],
+//! [`Event::Activate`], [`Event::ReceivedCharacter`] or navigation).
+//!
+//! This is intended to answer questions like "why doesn't my shortcut fire?"
+//! and is toggled via [`Manager::set_key_diagnostics`] (or, in some shells,
+//! an environment variable).
+
+use log::info;
+
+use super::*;
+
+/// Key event diagnostics state
+#[derive(Clone, Debug, Default)]
+pub(super) struct KeyDiagnostics {
+    pub(super) active: bool,
+}
+
+/// Key event diagnostics
+impl ManagerState {
+    /// True if key event diagnostics are enabled
+    ///
+    /// When enabled, the routing of each key event is logged at `info`
+    /// level, including the shortcut resolved, each focus target tried and
+    /// the accelerator layers consulted, and which widget consumed the key.
+    #[inline]
+    pub fn key_diagnostics(&self) -> bool {
+        self.key_diag.active
+    }
+
+    /// Enable or disable key event diagnostics
+    #[inline]
+    pub fn set_key_diagnostics(&mut self, active: bool) {
+        self.key_diag.active = active;
+    }
+}
+
+/// Key event diagnostics
+impl<'a> Manager<'a> {
+    /// Enable or disable key event diagnostics
+    ///
+    /// Diagnostics are per-window. See [`ManagerState::key_diagnostics`].
+    #[inline]
+    pub fn set_key_diagnostics(&mut self, active: bool) {
+        self.state.set_key_diagnostics(active);
+    }
+
+    /// Log a diagnostic message, if enabled
+    ///
+    /// The message is only constructed when diagnostics are enabled.
+    #[inline]
+    pub(super) fn key_diag<F: FnOnce() -> String>(&self, f: F) {
+        if self.state.key_diag.active {
+            info!("Key diagnostics: {}", f());
+        }
+    }
+}
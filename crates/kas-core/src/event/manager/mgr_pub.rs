@@ -5,16 +5,22 @@
 
 //! Event manager — public API
 
-use log::{debug, error, trace};
-use std::time::{Duration, Instant};
+use instant::Instant;
+use log::{debug, trace, warn};
+use std::time::Duration;
 use std::u16;
 
 use super::*;
-use crate::draw::{DrawShared, SizeHandle, ThemeApi};
-use crate::geom::{Coord, Offset, Vec2};
+use crate::dir::LayoutDirection;
+use crate::draw::{DrawShared, InputState, SizeHandle, ThemeApi};
+use crate::geom::{Coord, Offset, Size, Vec2};
+use crate::text::AccelString;
 #[allow(unused)]
 use crate::WidgetConfig; // for doc-links
-use crate::{TkAction, WidgetId, WindowId};
+use crate::{CaptureRequest, Future, WidgetCore};
+use crate::{MonitorInfo, ResizeDirection, ShellCapabilities, TkAction, WidgetId, WindowId};
+use image::RgbaImage;
+use raw_window_handle::RawWindowHandle;
 
 impl<'a> std::ops::BitOrAssign<TkAction> for Manager<'a> {
     #[inline]
@@ -25,6 +31,70 @@ impl<'a> std::ops::BitOrAssign<TkAction> for Manager<'a> {
 
 /// Public API (around event manager state)
 impl ManagerState {
+    /// Get the reassigned accelerator key of widget `id`, if any
+    ///
+    /// Where [`Manager::add_accel_label`] reassigned the accelerator key due
+    /// to a conflict, this returns the byte index of the underlined char
+    /// within the label text and the effective key.
+    #[inline]
+    pub fn accel_reassignment(&self, id: WidgetId) -> Option<(usize, VirtualKeyCode)> {
+        self.accel_reassigned.get(&id).cloned()
+    }
+
+    /// Get the window's layout direction
+    ///
+    /// Layouts over [`Directional`](crate::dir::Directional) types should map
+    /// logical directions to visual ones via [`LayoutDirection::apply`]; see
+    /// also [`Manager::set_layout_direction`].
+    #[inline]
+    pub fn layout_direction(&self) -> LayoutDirection {
+        self.layout_dir
+    }
+
+    /// Force the visual interaction state of widget `id`
+    ///
+    /// The `HOVER`, `DEPRESS`, `NAV_FOCUS`, `CHAR_FOCUS` and `SEL_FOCUS` flags
+    /// of `state` are reported by [`ManagerState::is_hovered`] etc. (thus also
+    /// by [`WidgetCore::input_state`]) in addition to those resulting from
+    /// real input. Event handling is not affected.
+    ///
+    /// This allows drawing widgets in any interaction state deterministically,
+    /// e.g. for demos, documentation screenshots and golden-image tests.
+    /// Passing [`InputState::empty`] removes the simulated state. Simulated
+    /// states follow their widget when a window is reconfigured, and are
+    /// removed with the widget.
+    ///
+    /// [`WidgetCore::input_state`]: crate::WidgetCore::input_state
+    pub fn simulate_state(&mut self, id: WidgetId, state: InputState) {
+        if state.is_empty() {
+            self.simulated.remove(&id);
+        } else {
+            self.simulated.insert(id, state);
+        }
+        self.action |= TkAction::REDRAW;
+    }
+
+    /// Remove all simulated interaction states
+    ///
+    /// See [`ManagerState::simulate_state`].
+    pub fn clear_simulated_states(&mut self) {
+        if !self.simulated.is_empty() {
+            self.simulated.clear();
+            self.action |= TkAction::REDRAW;
+        }
+    }
+
+    /// Get the simulated interaction state of widget `id`
+    ///
+    /// See [`ManagerState::simulate_state`].
+    #[inline]
+    pub fn simulated_state(&self, id: WidgetId) -> InputState {
+        self.simulated
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(InputState::empty)
+    }
+
     /// True when accelerator key labels should be shown
     ///
     /// (True when Alt is held and no widget has character focus.)
@@ -43,6 +113,12 @@ impl ManagerState {
     /// Note that `char_focus` implies `sel_focus`.
     #[inline]
     pub fn has_char_focus(&self, w_id: WidgetId) -> (bool, bool) {
+        let simulated = self.simulated_state(w_id);
+        if simulated.char_focus() {
+            return (true, true);
+        } else if simulated.sel_focus() {
+            return (false, true);
+        }
         if let Some(id) = self.sel_focus {
             if id == w_id {
                 return (self.char_focus, true);
@@ -54,22 +130,24 @@ impl ManagerState {
     /// Get whether this widget has keyboard navigation focus
     #[inline]
     pub fn nav_focus(&self, w_id: WidgetId) -> bool {
-        self.nav_focus == Some(w_id)
+        self.nav_focus == Some(w_id) || self.simulated_state(w_id).nav_focus()
     }
 
     /// Get whether the widget is under the mouse cursor
     #[inline]
     pub fn is_hovered(&self, w_id: WidgetId) -> bool {
-        self.mouse_grab.is_none() && self.hover == Some(w_id)
+        (self.mouse_grab.is_none() && self.hover == Some(w_id))
+            || self.simulated_state(w_id).hover()
     }
 
     /// Check whether the given widget is visually depressed
     #[inline]
     pub fn is_depressed(&self, w_id: WidgetId) -> bool {
-        for (_, id) in &self.key_depress {
-            if *id == w_id {
-                return true;
-            }
+        if self.simulated_state(w_id).depress() {
+            return true;
+        }
+        if self.key_depress.values().any(|id| *id == w_id) {
+            return true;
         }
         if self.mouse_grab.as_ref().and_then(|grab| grab.depress) == Some(w_id) {
             return true;
@@ -81,6 +159,35 @@ impl ManagerState {
         }
         false
     }
+
+    /// Iterate over widgets with some interaction state
+    ///
+    /// This yields each widget which is hovered, has navigation or selection
+    /// focus, is depressed or has a simulated state (possibly more than once).
+    /// Widgets caching the drawing of their descendants may use this to detect
+    /// state changes requiring a redraw.
+    pub fn interacting_widgets(&self) -> impl Iterator<Item = WidgetId> + '_ {
+        let hover = self.hover.filter(|_| self.mouse_grab.is_none());
+        let grab = self.mouse_grab.as_ref().and_then(|grab| grab.depress);
+        hover
+            .into_iter()
+            .chain(self.nav_focus)
+            .chain(self.sel_focus)
+            .chain(grab)
+            .chain(self.key_depress.values().cloned())
+            .chain(self.touch_grab.values().filter_map(|grab| grab.depress))
+            .chain(self.simulated.keys().cloned())
+    }
+
+    /// Get the theme generation
+    ///
+    /// This value changes whenever the theme (or its configuration) changes in
+    /// a way which may affect drawing, e.g. a change of colour scheme. Widgets
+    /// caching the drawing of their descendants should compare this.
+    #[inline]
+    pub fn theme_generation(&self) -> u64 {
+        self.theme_generation
+    }
 }
 
 /// Public API (around toolkit and shell functionality)
@@ -97,6 +204,14 @@ impl<'a> Manager<'a> {
         self.state.config.borrow()
     }
 
+    /// Get the source device of the last [`Event::Scroll`]
+    ///
+    /// While handling [`Event::Scroll`], this is the source of that event.
+    #[inline]
+    pub fn scroll_source(&self) -> ScrollSource {
+        self.state.scroll_source
+    }
+
     /// Is mouse panning enabled?
     #[inline]
     pub fn config_enable_mouse_pan(&self) -> bool {
@@ -126,6 +241,52 @@ impl<'a> Manager<'a> {
         self.state.scale_factor
     }
 
+    /// Force the visual interaction state of widget `id`
+    ///
+    /// See [`ManagerState::simulate_state`].
+    #[inline]
+    pub fn simulate_state(&mut self, id: WidgetId, state: InputState) {
+        self.state.simulate_state(id, state);
+    }
+
+    /// Get the window's layout direction
+    ///
+    /// See [`ManagerState::layout_direction`].
+    #[inline]
+    pub fn layout_direction(&self) -> LayoutDirection {
+        self.state.layout_dir
+    }
+
+    /// Set the window's layout direction
+    ///
+    /// By default (`None`), the direction follows the current locale (see
+    /// [`Self::locale`]), or is left-to-right if no localizer is configured.
+    /// The window is resized if the effective direction changes.
+    pub fn set_layout_direction(&mut self, dir: Option<LayoutDirection>) {
+        self.state.layout_dir_override = dir;
+        if self.state.update_layout_direction(self.shell) {
+            *self |= TkAction::RESIZE;
+        }
+    }
+
+    /// Get the features supported by the shell
+    ///
+    /// Widgets may use this to adapt to the environment instead of relying on
+    /// features which may silently fail.
+    #[inline]
+    pub fn shell_capabilities(&self) -> ShellCapabilities {
+        self.shell.capabilities()
+    }
+
+    /// Get the window's size
+    ///
+    /// This is the size most recently used to solve the window's layout; it
+    /// is zero before the first layout.
+    #[inline]
+    pub fn window_size(&self) -> Size {
+        self.state.window_size
+    }
+
     /// Schedule an update
     ///
     /// Widgets requiring animation should schedule an update; as a result,
@@ -135,7 +296,8 @@ impl<'a> Manager<'a> {
     /// Timings may be a few ms out, but should be sufficient for e.g. updating
     /// a clock each second. Very short positive durations (e.g. 1ns) may be
     /// used to schedule an update on the next frame. Frames should in any case
-    /// be limited by vsync, avoiding excessive frame rates.
+    /// be limited by vsync, avoiding excessive frame rates. For animation,
+    /// prefer [`Manager::update_on_frame`].
     ///
     /// If multiple updates with the same `w_id` and `payload` are requested,
     /// these are merged (using the earliest time). Updates with differing
@@ -188,7 +350,33 @@ impl<'a> Manager<'a> {
             .handle_updates
             .entry(handle)
             .or_insert_with(Default::default)
-            .insert(w_id);
+            .insert(w_id, None);
+    }
+
+    /// Subscribe to an update handle, with filter
+    ///
+    /// This is equivalent to [`Manager::update_on_handle`] except that the
+    /// widget is only sent [`Event::HandleUpdate`] where the payload passes
+    /// `filter`. This may be used to avoid redundant updates, e.g. where the
+    /// payload identifies the item changed.
+    ///
+    /// This should be called from [`WidgetConfig::configure`].
+    pub fn update_on_handle_filtered(
+        &mut self,
+        handle: UpdateHandle,
+        w_id: WidgetId,
+        filter: UpdateFilter,
+    ) {
+        trace!(
+            "Manager::update_on_handle_filtered: update {} on handle {:?}",
+            w_id,
+            handle
+        );
+        self.state
+            .handle_updates
+            .entry(handle)
+            .or_insert_with(Default::default)
+            .insert(w_id, Some(filter));
     }
 
     /// Notify that a widget must be redrawn
@@ -306,6 +494,18 @@ impl<'a> Manager<'a> {
         self.shell.trigger_update(handle, payload);
     }
 
+    /// Updates all subscribed widgets, with typed payload
+    ///
+    /// This is equivalent to [`Manager::trigger_update`].
+    #[inline]
+    pub fn trigger_typed_update<T: UpdatePayload>(
+        &mut self,
+        handle: TypedUpdateHandle<T>,
+        payload: T,
+    ) {
+        self.trigger_update(handle.handle(), payload.to_payload());
+    }
+
     /// Attempt to get clipboard contents
     ///
     /// In case of failure, paste actions will simply fail. The implementation
@@ -321,6 +521,216 @@ impl<'a> Manager<'a> {
         self.shell.set_clipboard(content)
     }
 
+    /// Attempt to set clipboard contents copied from a sensitive field
+    ///
+    /// Unless [`Config::sensitive_clipboard_history`] allows otherwise, the
+    /// shell is asked to exclude `content` from clipboard history.
+    #[inline]
+    pub fn set_clipboard_sensitive(&mut self, content: String) {
+        if self.state.config.borrow().sensitive_clipboard_history() {
+            self.shell.set_clipboard(content)
+        } else {
+            self.shell.set_clipboard_sensitive(content)
+        }
+    }
+
+    /// Attempt to open `url` with the system's default handler
+    ///
+    /// Typically this opens a web browser. Returns false if the shell does not
+    /// support this or the attempt failed.
+    ///
+    /// The URL is passed to the system as-is, thus URLs from untrusted sources
+    /// should be checked (e.g. for an `https` scheme) before being opened.
+    #[inline]
+    pub fn open_url(&mut self, url: &str) -> bool {
+        self.shell.open_url(url)
+    }
+
+    /// Capture the rendered contents of a window
+    ///
+    /// Window `id` is rendered to an image at its current size. The returned
+    /// [`Future`] yields this image, or `None` on failure (including where
+    /// the shell does not support [`ShellCapabilities::CAPTURE`]). Capture
+    /// happens asynchronously, usually after the next frame is drawn; the
+    /// returned [`UpdateHandle`] is triggered on completion (see
+    /// [`Manager::update_on_handle`]).
+    ///
+    /// This may be used to implement screenshots or to test rendered output.
+    pub fn capture_window(&mut self, id: WindowId) -> (Future<Option<RgbaImage>>, UpdateHandle) {
+        self.capture_impl(Some(id), None)
+    }
+
+    /// Capture the rendered contents of a widget
+    ///
+    /// This is like [`Manager::capture_window`] for the current window, except
+    /// that only `widget` (and its descendants) is drawn, at the origin of the
+    /// image. Overlapping widgets and pop-ups are not drawn and it does not
+    /// matter whether the widget is currently visible (e.g. within a scroll
+    /// region). The image is the size of the widget's rect, clipped to the
+    /// window size.
+    ///
+    /// This may be used to implement "export as image" for a chart or canvas.
+    pub fn capture(
+        &mut self,
+        widget: &dyn WidgetCore,
+    ) -> (Future<Option<RgbaImage>>, UpdateHandle) {
+        self.capture_impl(None, Some(widget.id()))
+    }
+
+    fn capture_impl(
+        &mut self,
+        window: Option<WindowId>,
+        widget: Option<WidgetId>,
+    ) -> (Future<Option<RgbaImage>>, UpdateHandle) {
+        let (future, finish) = Future::new_pending();
+        let handle = UpdateHandle::new();
+        let request = CaptureRequest::new(window, widget, finish, handle);
+        self.shell.capture(request);
+        (future, handle)
+    }
+
+    /// Load persisted UI state saved under `key`
+    ///
+    /// Widgets supporting persisted state typically call this from
+    /// [`WidgetConfig::configure`]. Returns `None` if no state was saved
+    /// (or the shell does not support this). See [`crate::state`].
+    #[inline]
+    pub fn load_state(&self, key: &str) -> Option<String> {
+        self.shell.load_state(key)
+    }
+
+    /// Save persisted UI state under `key`
+    ///
+    /// State is typically saved whenever it changes; the storage backend
+    /// decides when to write it out. See [`crate::state`].
+    #[inline]
+    pub fn save_state(&mut self, key: &str, value: String) {
+        self.shell.save_state(key, value);
+    }
+
+    /// Resolve localized message `key`
+    ///
+    /// If no translation is found (or no localizer is configured), `key`
+    /// itself is returned, thus missing translations remain visible.
+    /// See [`crate::l10n`].
+    pub fn translate(&self, key: &str) -> String {
+        self.shell.translate(key).unwrap_or_else(|| key.to_string())
+    }
+
+    /// Get the current locale, if a localizer is configured
+    #[inline]
+    pub fn locale(&self) -> Option<String> {
+        self.shell.locale()
+    }
+
+    /// Switch the current locale
+    ///
+    /// If the locale changes, all windows are reconfigured, thus text
+    /// constructed from message keys is re-resolved and windows resized.
+    #[inline]
+    pub fn set_locale(&mut self, locale: &str) {
+        self.shell.set_locale(locale);
+    }
+
+    /// Enumerate available monitors
+    ///
+    /// The list may be empty if the shell does not support enumeration.
+    /// See also [`crate::WindowPlacement`].
+    #[inline]
+    pub fn monitors(&self) -> Vec<MonitorInfo> {
+        self.shell.monitors()
+    }
+
+    /// Index of the monitor currently showing this window, if known
+    ///
+    /// This is an index into the list returned by [`Manager::monitors`].
+    #[inline]
+    pub fn current_monitor(&self) -> Option<usize> {
+        self.shell.current_monitor()
+    }
+
+    /// Set whether the window has decorations
+    ///
+    /// See [`crate::WindowAttributes::decorations`].
+    #[inline]
+    pub fn set_decorations(&mut self, decorations: bool) {
+        self.shell.set_decorations(decorations);
+    }
+
+    /// Set whether the window is resizable
+    ///
+    /// See [`crate::WindowAttributes::resizable`].
+    #[inline]
+    pub fn set_resizable(&mut self, resizable: bool) {
+        self.shell.set_resizable(resizable);
+    }
+
+    /// Set whether the window is kept above other windows
+    ///
+    /// See [`crate::WindowAttributes::always_on_top`].
+    #[inline]
+    pub fn set_always_on_top(&mut self, always_on_top: bool) {
+        self.shell.set_always_on_top(always_on_top);
+    }
+
+    /// Request a new inner size for the window (physical pixels)
+    ///
+    /// The size may be constrained by the window's size limits or ignored.
+    #[inline]
+    pub fn set_window_size(&mut self, size: Size) {
+        self.shell.set_inner_size(size);
+    }
+
+    /// Start moving the window with the pressed mouse button
+    ///
+    /// This should be called on [`Event::PressStart`] (e.g. from a custom
+    /// title bar) to let the platform move the window.
+    #[inline]
+    pub fn drag_window(&mut self) {
+        self.shell.drag_window();
+    }
+
+    /// Start resizing the window with the pressed mouse button
+    ///
+    /// This should be called on [`Event::PressStart`]. Returns false if the
+    /// shell does not support this, in which case the caller may resize the
+    /// window via [`Manager::set_window_size`] instead.
+    #[inline]
+    pub fn drag_resize_window(&mut self, direction: ResizeDirection) -> bool {
+        self.shell.drag_resize_window(direction)
+    }
+
+    /// Minimize the window
+    #[inline]
+    pub fn minimize_window(&mut self) {
+        self.shell.set_minimized();
+    }
+
+    /// Set whether the window is maximized
+    #[inline]
+    pub fn set_maximized(&mut self, maximized: bool) {
+        self.shell.set_maximized(maximized);
+    }
+
+    /// Whether the window is maximized
+    #[inline]
+    pub fn is_maximized(&self) -> bool {
+        self.shell.is_maximized()
+    }
+
+    /// Get the platform-specific handle of the native window
+    ///
+    /// This allows embedding of native child surfaces, e.g. a video player or
+    /// web view rendering via its own swapchain; see
+    /// [`ShellCapabilities::NATIVE_SURFACES`]. Returns `None` when the window
+    /// is not backed by a native window (e.g. headless or embedded usage).
+    ///
+    /// The handle is only valid while the window exists.
+    #[inline]
+    pub fn raw_window_handle(&self) -> Option<RawWindowHandle> {
+        self.shell.raw_window_handle()
+    }
+
     /// Adjust the theme
     #[inline]
     pub fn adjust_theme<F: FnMut(&mut dyn ThemeApi) -> TkAction>(&mut self, mut f: F) {
@@ -427,14 +837,96 @@ impl<'a> Manager<'a> {
     /// The top-most active layer gets first priority in matching input, but
     /// does not block previous layers.
     ///
+    /// If a key is already bound to another widget in the current layer, the
+    /// first binding is kept and the conflict is logged (at `warn` level).
+    /// See also [`Manager::add_accel_label`].
+    ///
     /// This should only be called from [`WidgetConfig::configure`].
     // TODO(type safety): consider only implementing on ConfigureManager
-    #[inline]
     pub fn add_accel_keys(&mut self, id: WidgetId, keys: &[VirtualKeyCode]) {
         if let Some(last) = self.state.accel_stack.last_mut() {
             for key in keys {
-                last.1.insert(*key, id);
+                match last.1.get(key) {
+                    Some(other) if *other != id => warn!(
+                        "Manager: accelerator key {:?} of {} conflicts with {}; ignoring",
+                        key, id, other
+                    ),
+                    _ => {
+                        last.1.insert(*key, id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds the accelerator key(s) of a label, resolving conflicts
+    ///
+    /// This is like [`Manager::add_accel_keys`] using the keys of `label`,
+    /// except that if all of these keys are already bound to other widgets in
+    /// the current layer and [`Config::accel_reassign`] is enabled, another
+    /// letter or digit from the label is chosen (the first not yet bound).
+    ///
+    /// If reassigned, a copy of `label` with the effective accelerator key is
+    /// returned; the caller should display this in place of `label` (so that
+    /// the correct letter is underlined). The effective assignment may also be
+    /// queried via [`ManagerState::accel_reassignment`].
+    ///
+    /// This should only be called from [`WidgetConfig::configure`].
+    pub fn add_accel_label(&mut self, id: WidgetId, label: &AccelString) -> Option<AccelString> {
+        let reassign = self.config().accel_reassign();
+        let layer = match self.state.accel_stack.last_mut() {
+            Some(layer) => &mut layer.1,
+            None => return None,
+        };
+
+        let is_free = |layer: &HashMap<VirtualKeyCode, WidgetId>, key: &VirtualKeyCode| {
+            layer.get(key).map(|other| *other == id).unwrap_or(true)
+        };
+        let keys = label.keys();
+        if keys.is_empty() || keys.iter().any(|key| is_free(layer, key)) {
+            self.add_accel_keys(id, keys);
+            return None;
+        }
+
+        let other = layer.get(&keys[0]).cloned().unwrap();
+        if !reassign {
+            warn!(
+                "Manager: accelerator key {:?} of {} (label {:?}) conflicts with {}; ignoring",
+                keys[0],
+                id,
+                label.text(),
+                other
+            );
+            return None;
+        }
+
+        let found = (label.text().char_indices())
+            .filter(|(_, c)| c.is_ascii_alphanumeric())
+            .filter_map(|(index, _)| label.with_accel_at(index).map(|s| (index, s)))
+            .find(|(_, s)| s.keys().iter().all(|key| is_free(layer, key)));
+        if let Some((index, string)) = found {
+            debug!(
+                "Manager: accelerator key {:?} of {} conflicts with {}; reassigned to {:?}",
+                keys[0],
+                id,
+                other,
+                string.keys()[0]
+            );
+            for key in string.keys() {
+                layer.insert(*key, id);
             }
+            let assignment = (index, string.keys()[0]);
+            self.state.accel_reassigned.insert(id, assignment);
+            Some(string)
+        } else {
+            warn!(
+                "Manager: accelerator key {:?} of {} (label {:?}) conflicts with {}; no free key",
+                keys[0],
+                id,
+                label.text(),
+                other
+            );
+            None
         }
     }
 
@@ -474,6 +966,21 @@ impl<'a> Manager<'a> {
         true
     }
 
+    /// Release selection (and character) focus held by widget `id`
+    ///
+    /// Does nothing if `id` does not have selection focus. Unlike when focus
+    /// is taken by another widget, no [`Event::LostSelFocus`] or
+    /// [`Event::LostCharFocus`] is sent: the caller is responsible for any
+    /// clean-up (e.g. clearing its selection).
+    pub fn clear_sel_focus(&mut self, id: WidgetId) {
+        if self.state.sel_focus == Some(id) {
+            trace!("Manager::clear_sel_focus: {}", id);
+            self.state.sel_focus = None;
+            self.state.char_focus = false;
+            self.redraw(id);
+        }
+    }
+
     /// Request a grab on the given input `source`
     ///
     /// On success, this method returns true and corresponding mouse/touch
@@ -491,6 +998,9 @@ impl<'a> Manager<'a> {
     /// -   [`GrabMode::Grab`]: simple / low-level interpretation of input
     ///     which delivers [`Event::PressMove`] and [`Event::PressEnd`] events.
     ///     Multiple event sources may be grabbed simultaneously.
+    /// -   [`GrabMode::Gesture`]: generates [`Event::Gesture`] events,
+    ///     describing the scale, rotation and translation of touches about
+    ///     their focal point. As below, multiple touches may be grabbed.
     /// -   All other [`GrabMode`] values: generates [`Event::Pan`] events.
     ///     Requesting additional grabs on the same widget from the same source
     ///     (i.e. multiple touches) allows generation of rotation and scale
@@ -577,6 +1087,22 @@ impl<'a> Manager<'a> {
         }
     }
 
+    /// Update the mouse cursor while hovering widget `id`
+    ///
+    /// Normally the cursor icon is determined by [`WidgetConfig::cursor_icon`]
+    /// when the mouse cursor moves onto a widget. This method allows the
+    /// hovered widget to change the icon, e.g. when over some part of the
+    /// widget (see [`Event::CursorMove`]). It does nothing if widget `id` is not
+    /// hovered or during a mouse grab.
+    pub fn update_hover_cursor(&mut self, id: WidgetId, icon: CursorIcon) {
+        if self.state.hover == Some(id) && icon != self.state.hover_icon {
+            self.state.hover_icon = icon;
+            if self.state.mouse_grab.is_none() {
+                self.shell.set_cursor_icon(icon);
+            }
+        }
+    }
+
     /// Set a grab's depress target
     ///
     /// When a grab on mouse or touch input is in effect
@@ -621,6 +1147,21 @@ impl<'a> Manager<'a> {
         self.state.nav_focus
     }
 
+    /// Scroll ancestors of widget `id` such that it becomes visible
+    ///
+    /// This sends [`Event::ScrollTo`] to the widget (on the next update); in
+    /// response, scroll regions among its ancestors (see [`Response::Reveal`])
+    /// scroll such that the widget is visible, animating where enabled by
+    /// [`Config::scroll_anim_duration`]. Unlike [`Manager::set_nav_focus`],
+    /// this does not affect focus.
+    ///
+    /// This may be used e.g. to reveal an input with a validation error or
+    /// a search result.
+    pub fn scroll_to(&mut self, id: WidgetId) {
+        trace!("Manager::scroll_to: {}", id);
+        self.state.pending.push(Pending::ScrollTo(id));
+    }
+
     /// Clear keyboard navigation focus
     pub fn clear_nav_focus(&mut self) {
         if let Some(id) = self.state.nav_focus {
@@ -658,11 +1199,19 @@ impl<'a> Manager<'a> {
     ///
     /// If some widget currently has nav focus, this will give focus to the next
     /// (or previous) widget under `widget` where [`WidgetConfig::key_nav`]
-    /// returns true; otherwise this will give focus to the first (or last)
-    /// such widget.
+    /// returns true; otherwise this will give focus to the first widget marked
+    /// with [`WidgetConfig::initial_focus`], if any, or else to the first (or
+    /// last) such widget.
     ///
-    /// Returns true on success, false if there are no navigable widgets or
-    /// some error occurred.
+    /// Navigation order is document order, except that widgets with an
+    /// explicit [`WidgetConfig::tab_index`] come first. While nav focus is
+    /// within a [focus scope](WidgetConfig::focus_scope), navigation cycles
+    /// within the innermost such scope, unless [`WidgetConfig::nav_wrap`] is
+    /// false for the scope (or root), in which case it stops at either end.
+    ///
+    /// Returns true on success, false if there are no navigable widgets, the
+    /// end was reached without wrapping (focus is unchanged) or some error
+    /// occurred.
     ///
     /// The target widget will receive [`Event::NavFocus`] with `key_focus` as
     /// the payload. This boolean should be true if focussing in response to
@@ -686,108 +1235,47 @@ impl<'a> Manager<'a> {
         // processing, we can push directly to self.state.action.
         self.state.send_action(TkAction::REDRAW);
 
-        fn nav(
-            mgr: &mut Manager,
-            widget: &mut dyn WidgetConfig,
-            focus: Option<WidgetId>,
-            rev: bool,
-        ) -> Option<WidgetId> {
-            let last = widget.num_children().wrapping_sub(1);
-            if widget.is_disabled() {
-                return None;
-            } else if last == usize::MAX {
-                if focus != Some(widget.id()) && widget.key_nav() {
-                    return Some(widget.id());
-                }
-                return None;
-            }
-
-            let mut child = None;
-            if let Some(id) = focus {
-                // Checking is_ancestor_of is just an optimisation
-                if widget.is_ancestor_of(id) && id != widget.id() {
-                    // TODO(opt): add WidgetChildren::find_ancestor_of method to
-                    // allow optimisations for widgets with many children?
-                    for index in 0..=last {
-                        if widget
-                            .get_child(index)
-                            .map(|w| w.is_ancestor_of(id))
-                            .unwrap_or(false)
-                        {
-                            child = Some(index);
-                            break;
-                        }
-                    }
+        let focus = self.state.nav_focus;
+        if let Some(scope) = focus.and_then(|id| focus_scope_of(widget, id)) {
+            widget = widget.find_leaf_mut(scope).unwrap();
+        }
 
-                    if child.is_none() {
-                        error!("unable to find widget {}", id);
-                        return None;
-                    }
-                }
+        let wrap = widget.nav_wrap();
+        let opt_id = if has_tab_index(self, widget) {
+            let list = nav_list(self, widget, focus);
+            let start = focus.and_then(|id| list.iter().position(|entry| entry.id == id));
+            let initial = start
+                .is_none()
+                .then(|| list.iter().find(|entry| entry.key_nav && entry.initial))
+                .flatten();
+            let opt_id = match initial {
+                Some(entry) => Some(entry.id),
+                None => next_in_list(&list, start, reverse, wrap, |_| true),
+            };
+            if opt_id.is_none() && start.is_some() && !wrap {
+                // End reached: keep the current focus
+                return false;
             }
-
-            if !rev {
-                if let Some(index) = child {
-                    if let Some(id) = widget
-                        .get_child_mut(index)
-                        .and_then(|w| nav(mgr, w, focus, rev))
-                    {
-                        return Some(id);
-                    }
-                } else if focus != Some(widget.id()) && widget.key_nav() {
-                    return Some(widget.id());
-                }
-
-                loop {
-                    if let Some(index) = widget.spatial_nav(mgr, rev, child) {
-                        if let Some(id) = widget
-                            .get_child_mut(index)
-                            .and_then(|w| nav(mgr, w, focus, rev))
-                        {
-                            return Some(id);
-                        }
-                        child = Some(index);
-                    } else {
-                        return None;
-                    }
-                }
-            } else {
-                if let Some(index) = child {
-                    if let Some(id) = widget
-                        .get_child_mut(index)
-                        .and_then(|w| nav(mgr, w, focus, rev))
-                    {
-                        return Some(id);
-                    }
-                }
-
-                loop {
-                    if let Some(index) = widget.spatial_nav(mgr, rev, child) {
-                        if let Some(id) = widget
-                            .get_child_mut(index)
-                            .and_then(|w| nav(mgr, w, focus, rev))
-                        {
-                            return Some(id);
-                        }
-                        child = Some(index);
-                    } else {
-                        return if focus != Some(widget.id()) && widget.key_nav() {
-                            Some(widget.id())
-                        } else {
-                            None
-                        };
-                    }
+            opt_id
+        } else {
+            // Without tab indices, document order: walk only as far as needed
+            let focus = focus.filter(|id| widget.is_ancestor_of(*id));
+            let mut opt_id = match focus {
+                Some(_) => None,
+                None => nav_initial(self, widget),
+            };
+            if opt_id.is_none() {
+                opt_id = nav(self, widget, focus, reverse, false);
+            }
+            if opt_id.is_none() && focus.is_some() {
+                if !wrap {
+                    // End reached: keep the current focus
+                    return false;
                 }
+                opt_id = nav(self, widget, None, reverse, false);
             }
-        }
-
-        // Whether to restart from the beginning on failure
-        let restart = self.state.nav_focus.is_some();
-
-        let mut opt_id = nav(self, widget, self.state.nav_focus, reverse);
-        if restart && opt_id.is_none() {
-            opt_id = nav(self, widget, None, reverse);
-        }
+            opt_id
+        };
 
         trace!("Manager: nav_focus = {:?}", opt_id);
         self.state.nav_focus = opt_id;
@@ -804,4 +1292,248 @@ impl<'a> Manager<'a> {
         }
         opt_id.is_some()
     }
+
+    /// Exit the focus scope containing the navigation focus
+    ///
+    /// If nav focus is within a [focus scope](WidgetConfig::focus_scope),
+    /// focus is given to the scope widget itself when this is navigable, or
+    /// otherwise to the next navigable widget following the scope. (If there
+    /// is no such widget, nav focus is cleared.)
+    ///
+    /// This is called when the Escape key is pressed (and not otherwise
+    /// handled). Returns true if a scope was exited.
+    pub fn exit_focus_scope(&mut self, mut widget: &mut dyn WidgetConfig) -> bool {
+        if let Some(id) = self.state.popups.last().map(|(_, p, _)| p.id) {
+            match widget.find_leaf_mut(id) {
+                Some(w) => widget = w,
+                None => return false,
+            }
+        }
+
+        let focus = match self.state.nav_focus {
+            Some(id) => id,
+            None => return false,
+        };
+        let scope = match focus_scope_of(widget, focus) {
+            Some(scope) => scope,
+            None => return false,
+        };
+        let (first, key_nav) = match widget.find_leaf(scope) {
+            Some(w) => (w.first_id(), w.key_nav()),
+            None => return false,
+        };
+        trace!("Manager::exit_focus_scope: exiting {}", scope);
+
+        if key_nav {
+            self.set_nav_focus(scope, true);
+            return true;
+        }
+
+        if let Some(outer) = focus_scope_of(widget, scope) {
+            widget = widget.find_leaf_mut(outer).unwrap();
+        }
+        let in_scope = |id: WidgetId| first <= id && id <= scope;
+        let wrap = widget.nav_wrap();
+        let opt_id = if has_tab_index(self, widget) {
+            let list = nav_list(self, widget, None);
+            let start = list.iter().rposition(|entry| in_scope(entry.id));
+            next_in_list(&list, start, false, wrap, |id| !in_scope(id))
+        } else {
+            let mut opt_id = nav(self, widget, Some(scope), false, true);
+            if opt_id.is_none() && wrap {
+                // If the first widget is in scope, there is nothing outside it
+                opt_id = nav(self, widget, None, false, false).filter(|id| !in_scope(*id));
+            }
+            opt_id
+        };
+        match opt_id {
+            Some(id) => self.set_nav_focus(id, true),
+            None => self.clear_nav_focus(),
+        }
+        true
+    }
+}
+
+/// Entry in the Tab navigation order
+struct NavEntry {
+    id: WidgetId,
+    /// False for a focussed widget which is not otherwise navigable
+    key_nav: bool,
+    /// True if marked with [`WidgetConfig::initial_focus`]
+    initial: bool,
+}
+
+/// Find the innermost focus scope strictly containing `id`, if any
+fn focus_scope_of(widget: &dyn WidgetConfig, id: WidgetId) -> Option<WidgetId> {
+    let mut scope = None;
+    let mut widget = widget;
+    while let Some(index) = widget.find_child(id) {
+        widget = widget.get_child(index)?;
+        if widget.id() != id && widget.focus_scope() {
+            scope = Some(widget.id());
+        }
+    }
+    scope
+}
+
+/// Whether any widget under `widget` has an explicit tab index
+///
+/// The result is cached per scope root until the next (re)configure.
+fn has_tab_index(mgr: &mut Manager, widget: &dyn WidgetConfig) -> bool {
+    fn any(widget: &dyn WidgetConfig) -> bool {
+        widget.tab_index().is_some()
+            || (0..widget.num_children())
+                .filter_map(|index| widget.get_child(index))
+                .any(any)
+    }
+
+    *mgr.state
+        .tab_index_scopes
+        .entry(widget.id())
+        .or_insert_with(|| any(widget))
+}
+
+/// Find the first navigable widget marked with [`WidgetConfig::initial_focus`]
+fn nav_initial(mgr: &mut Manager, widget: &mut dyn WidgetConfig) -> Option<WidgetId> {
+    if widget.is_disabled() {
+        return None;
+    } else if widget.key_nav() && widget.initial_focus() {
+        return Some(widget.id());
+    }
+
+    let mut child = None;
+    while let Some(index) = widget.spatial_nav(mgr, false, child) {
+        if let Some(id) = widget
+            .get_child_mut(index)
+            .and_then(|w| nav_initial(mgr, w))
+        {
+            return Some(id);
+        }
+        child = Some(index);
+    }
+    None
+}
+
+/// Find the next navigable widget under `widget` after `focus`
+///
+/// This walks the tree in document order, visiting only as much as needed.
+/// If `focus` is `None`, this finds the first (or last) navigable widget. If
+/// `skip`, descendants of `focus` are excluded.
+fn nav(
+    mgr: &mut Manager,
+    widget: &mut dyn WidgetConfig,
+    focus: Option<WidgetId>,
+    rev: bool,
+    skip: bool,
+) -> Option<WidgetId> {
+    if widget.is_disabled() {
+        return None;
+    }
+
+    let is_focus = focus == Some(widget.id());
+    if is_focus && (rev || skip) {
+        // Nothing under widget comes before it in forward order
+        return None;
+    }
+
+    let mut child = focus.and_then(|id| widget.find_child(id));
+    if let Some(index) = child {
+        if let Some(id) = widget
+            .get_child_mut(index)
+            .and_then(|w| nav(mgr, w, focus, rev, skip))
+        {
+            return Some(id);
+        }
+    } else if !rev && !is_focus && widget.key_nav() {
+        return Some(widget.id());
+    }
+
+    while let Some(index) = widget.spatial_nav(mgr, rev, child) {
+        if let Some(id) = widget
+            .get_child_mut(index)
+            .and_then(|w| nav(mgr, w, None, rev, skip))
+        {
+            return Some(id);
+        }
+        child = Some(index);
+    }
+
+    if rev && !is_focus && widget.key_nav() {
+        Some(widget.id())
+    } else {
+        None
+    }
+}
+
+/// List widgets under `widget` in Tab navigation order
+///
+/// The list includes `focus` even if it is not navigable (so that navigation
+/// may continue from its position).
+fn nav_list(
+    mgr: &mut Manager,
+    widget: &mut dyn WidgetConfig,
+    focus: Option<WidgetId>,
+) -> Vec<NavEntry> {
+    fn collect(
+        mgr: &mut Manager,
+        widget: &mut dyn WidgetConfig,
+        focus: Option<WidgetId>,
+        list: &mut Vec<(NavEntry, Option<u32>)>,
+    ) {
+        if widget.is_disabled() {
+            return;
+        }
+        let key_nav = widget.key_nav();
+        if key_nav || focus == Some(widget.id()) {
+            let entry = NavEntry {
+                id: widget.id(),
+                key_nav,
+                initial: widget.initial_focus(),
+            };
+            list.push((entry, widget.tab_index()));
+        }
+
+        let mut child = None;
+        while let Some(index) = widget.spatial_nav(mgr, false, child) {
+            if let Some(w) = widget.get_child_mut(index) {
+                collect(mgr, w, focus, list);
+            }
+            child = Some(index);
+        }
+    }
+
+    let mut list = vec![];
+    collect(mgr, widget, focus, &mut list);
+    // Explicit tab indices first; the sort is stable
+    list.sort_by_key(|(_, index)| (index.is_none(), *index));
+    list.into_iter().map(|(entry, _)| entry).collect()
+}
+
+/// Find the next navigable entry after `start` satisfying `filter`
+///
+/// If `start` is `None`, this starts from the beginning (or end). If `wrap`,
+/// the search continues from the other end of the list.
+fn next_in_list<F: Fn(WidgetId) -> bool>(
+    list: &[NavEntry],
+    start: Option<usize>,
+    reverse: bool,
+    wrap: bool,
+    filter: F,
+) -> Option<WidgetId> {
+    let len = list.len();
+    let steps = match (start, reverse, wrap) {
+        (Some(s), false, false) => len - 1 - s,
+        (Some(s), true, false) => s,
+        _ => len,
+    };
+    (1..=steps)
+        .map(|i| match (start, reverse) {
+            (Some(s), false) => (s + i) % len,
+            (Some(s), true) => (s + len - i) % len,
+            (None, false) => i - 1,
+            (None, true) => len - i,
+        })
+        .map(|i| &list[i])
+        .find(|entry| entry.key_nav && filter(entry.id))
+        .map(|entry| entry.id)
 }
@@ -5,15 +5,16 @@
 
 //! Event manager — shell API
 
+use instant::Instant;
 use log::*;
 use smallvec::SmallVec;
 use std::collections::HashMap;
 use std::mem::swap;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use super::*;
 use crate::cast::Conv;
-use crate::geom::{Coord, DVec2, Offset};
+use crate::geom::{Coord, DVec2, Size};
 #[allow(unused)]
 use crate::WidgetConfig; // for doc-links
 use crate::{ShellWindow, TkAction, Widget, WidgetId};
@@ -24,8 +25,6 @@ const DOUBLE_CLICK_TIMEOUT: Duration = Duration::from_secs(1);
 const FAKE_MOUSE_BUTTON: MouseButton = MouseButton::Other(0);
 
 /// Shell API
-#[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
-#[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
 impl ManagerState {
     /// Construct an event manager per-window data struct
     #[inline]
@@ -33,6 +32,9 @@ impl ManagerState {
         ManagerState {
             config,
             scale_factor,
+            window_size: Size::ZERO,
+            layout_dir: Default::default(),
+            layout_dir_override: None,
             end_id: Default::default(),
             modifiers: ModifiersState::empty(),
             char_focus: false,
@@ -41,22 +43,35 @@ impl ManagerState {
             nav_fallback: None,
             hover: None,
             hover_icon: CursorIcon::Default,
+            simulated: HashMap::new(),
             key_depress: Default::default(),
             last_mouse_coord: Coord::ZERO,
             last_click_button: FAKE_MOUSE_BUTTON,
             last_click_repetitions: 0,
             last_click_timeout: Instant::now(), // unimportant value
+            scroll_source: ScrollSource::Wheel,
             mouse_grab: None,
             touch_grab: Default::default(),
             pan_grab: SmallVec::new(),
             accel_stack: vec![],
             accel_layers: HashMap::new(),
+            accel_reassigned: HashMap::new(),
+            tab_index_scopes: HashMap::new(),
             popups: Default::default(),
             new_popups: Default::default(),
             popup_removed: Default::default(),
             time_updates: vec![],
+            reconfigure: vec![],
             handle_updates: HashMap::new(),
             pending: SmallVec::new(),
+            inspector: Default::default(),
+            key_diag: Default::default(),
+            tooltip: Default::default(),
+            hover_focus: Default::default(),
+            toasts: Default::default(),
+            frame: Default::default(),
+            bus: Default::default(),
+            theme_generation: 0,
             action: TkAction::empty(),
         }
     }
@@ -65,6 +80,48 @@ impl ManagerState {
         self.scale_factor = scale_factor;
     }
 
+    /// Notify that the theme may have changed
+    ///
+    /// The shell should call this on [`TkAction::THEME_UPDATE`] and on any
+    /// redraw resulting from an action shared between windows (e.g. from
+    /// [`ShellWindow::adjust_theme`]). This increments the
+    /// [`ManagerState::theme_generation`] and requests a redraw.
+    pub fn theme_changed(&mut self) {
+        self.theme_generation = self.theme_generation.wrapping_add(1);
+        self.send_action(TkAction::REDRAW);
+    }
+
+    /// Update the window size
+    ///
+    /// The shell should call this before solving the window's layout.
+    pub fn update_window_size(&mut self, size: Size) {
+        self.window_size = size;
+    }
+
+    /// Handle a change in scale factor
+    ///
+    /// This updates the scale factor (as [`ManagerState::set_scale_factor`])
+    /// and sends [`Event::ScaleFactorChanged`] to the root `widget`. The shell
+    /// remains responsible for updating the theme and resizing the window.
+    pub fn handle_scale_factor_changed<W>(
+        &mut self,
+        shell: &mut dyn ShellWindow,
+        widget: &mut W,
+        scale_factor: f32,
+    ) where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        if scale_factor == self.scale_factor {
+            return;
+        }
+        trace!("Manager::handle_scale_factor_changed: {}", scale_factor);
+        self.set_scale_factor(scale_factor);
+        self.with(shell, |mgr| {
+            let id = widget.id();
+            mgr.send_event(widget, id, Event::ScaleFactorChanged(scale_factor));
+        });
+    }
+
     /// Configure event manager for a widget tree.
     ///
     /// This should be called by the toolkit on the widget tree when the window
@@ -81,8 +138,9 @@ impl ManagerState {
         debug!("Manager::configure");
         self.action = TkAction::empty();
 
-        // Re-assigning WidgetIds might invalidate state; to avoid this we map
-        // existing ids to new ids
+        // Widgets keep their WidgetId where possible (see
+        // ConfigureManager::next_id), but some may be renumbered; to avoid
+        // invalidating state we map existing ids to new ids
         let mut renames = HashMap::new();
         let mut id = WidgetId::FIRST;
 
@@ -90,7 +148,12 @@ impl ManagerState {
         debug_assert!(self.accel_stack.is_empty());
         self.accel_stack.clear();
         self.accel_layers.clear();
+        self.accel_reassigned.clear();
+        self.tab_index_scopes.clear();
         self.nav_fallback = None;
+        self.bus.clear_subscribers();
+        self.reconfigure.clear();
+        self.update_layout_direction(shell);
         // These we merge later:
         let mut old_time_updates = Default::default();
         swap(&mut self.time_updates, &mut old_time_updates);
@@ -103,6 +166,7 @@ impl ManagerState {
             mgr.push_accel_layer(false);
             widget.configure_recurse(ConfigureManager {
                 id: &mut id,
+                limit: WidgetId::LAST,
                 map: &mut renames,
                 mgr,
             });
@@ -123,15 +187,29 @@ impl ManagerState {
         // Update input state to account for renamed widgets. Assumption: none
         // of this state is adjusted within widget configure methods.
         // TODO(safety): ensure these fields cannot be updated by configure?
+        self.rename_ids(old_time_updates, old_handle_updates, |id| {
+            renames.get(&id).cloned()
+        });
+    }
 
-        self.sel_focus = self.sel_focus.and_then(|id| renames.get(&id).cloned());
-        self.nav_focus = self.nav_focus.and_then(|id| renames.get(&id).cloned());
+    /// Update state for renamed and removed widgets
+    ///
+    /// `rename` maps old to new identifiers, returning `None` for removed
+    /// widgets. `old_time_updates` and `old_handle_updates` are merged.
+    pub(super) fn rename_ids<F: Fn(WidgetId) -> Option<WidgetId>>(
+        &mut self,
+        mut old_time_updates: Vec<(Instant, WidgetId, u64)>,
+        mut old_handle_updates: HashMap<UpdateHandle, LinearMap<WidgetId, Option<UpdateFilter>>>,
+        rename: F,
+    ) {
+        self.sel_focus = self.sel_focus.and_then(&rename);
+        self.nav_focus = self.nav_focus.and_then(&rename);
         self.mouse_grab = self.mouse_grab.as_ref().and_then(|grab| {
-            renames.get(&grab.start_id).map(|id| MouseGrab {
+            rename(grab.start_id).map(|id| MouseGrab {
                 button: grab.button,
                 repetitions: grab.repetitions,
-                start_id: *id,
-                depress: grab.depress.and_then(|id| renames.get(&id).cloned()),
+                start_id: id,
+                depress: grab.depress.and_then(&rename),
                 mode: grab.mode,
                 pan_grab: grab.pan_grab,
             })
@@ -139,8 +217,8 @@ impl ManagerState {
 
         let mut i = 0;
         while i < self.pan_grab.len() {
-            if let Some(id) = renames.get(&self.pan_grab[i].id) {
-                self.pan_grab[i].id = *id;
+            if let Some(id) = rename(self.pan_grab[i].id) {
+                self.pan_grab[i].id = id;
                 i += 1;
             } else {
                 self.remove_pan(i);
@@ -148,10 +226,10 @@ impl ManagerState {
         }
 
         self.touch_grab.retain(|_, grab| {
-            if let Some(id) = renames.get(&grab.start_id) {
-                grab.start_id = *id;
+            if let Some(id) = rename(grab.start_id) {
+                grab.start_id = id;
                 if let Some(cur_id) = grab.cur_id {
-                    grab.cur_id = renames.get(&cur_id).cloned();
+                    grab.cur_id = rename(cur_id);
                 }
                 true
             } else {
@@ -160,19 +238,24 @@ impl ManagerState {
         });
 
         self.key_depress.retain(|_, depress_id| {
-            if let Some(id) = renames.get(depress_id) {
-                *depress_id = *id;
+            if let Some(id) = rename(*depress_id) {
+                *depress_id = id;
                 true
             } else {
                 false
             }
         });
 
+        self.simulated = std::mem::take(&mut self.simulated)
+            .into_iter()
+            .filter_map(|(id, state)| rename(id).map(|id| (id, state)))
+            .collect();
+
         // We have to handle time_updates and handle_updates carefully since
         // these may be set during configure, *and* may carry old state forward
         // which must be renamed.
         'old: for (time, old_id, payload) in old_time_updates.drain(..) {
-            if let Some(new_id) = renames.get(&old_id).cloned() {
+            if let Some(new_id) = rename(old_id) {
                 // Insert into our data structure. We sort everything below.
                 'insert: loop {
                     for row in &mut self.time_updates {
@@ -198,31 +281,41 @@ impl ManagerState {
                 .handle_updates
                 .entry(handle)
                 .or_insert_with(Default::default);
-            for id in ids.drain().filter_map(|id| renames.get(&id)).cloned() {
-                new_ids.insert(id);
+            for (id, filter) in ids.drain() {
+                if let Some(id) = rename(id) {
+                    new_ids.insert(id, filter);
+                }
             }
         }
 
         self.pending.retain(|item| match item {
             Pending::LostCharFocus(id) => {
-                if let Some(new_id) = renames.get(id) {
-                    *item = Pending::LostCharFocus(*new_id);
+                if let Some(new_id) = rename(*id) {
+                    *item = Pending::LostCharFocus(new_id);
                     true
                 } else {
                     false
                 }
             }
             Pending::LostSelFocus(id) => {
-                if let Some(new_id) = renames.get(id) {
-                    *item = Pending::LostSelFocus(*new_id);
+                if let Some(new_id) = rename(*id) {
+                    *item = Pending::LostSelFocus(new_id);
                     true
                 } else {
                     false
                 }
             }
             Pending::SetNavFocus(id, key_focus) => {
-                if let Some(new_id) = renames.get(id) {
-                    *item = Pending::SetNavFocus(*new_id, *key_focus);
+                if let Some(new_id) = rename(*id) {
+                    *item = Pending::SetNavFocus(new_id, *key_focus);
+                    true
+                } else {
+                    false
+                }
+            }
+            Pending::ScrollTo(id) => {
+                if let Some(new_id) = rename(*id) {
+                    *item = Pending::ScrollTo(new_id);
                     true
                 } else {
                     false
@@ -251,7 +344,12 @@ impl ManagerState {
 
     /// Get the next resume time
     pub fn next_resume(&self) -> Option<Instant> {
-        self.time_updates.last().map(|time| time.0)
+        let time = self.time_updates.last().map(|time| time.0);
+        let (tooltip, hover_focus) = (self.tooltip_resume(), self.hover_focus_resume());
+        [time, tooltip, hover_focus, self.toast_resume()]
+            .into_iter()
+            .flatten()
+            .min()
     }
 
     /// Set an action
@@ -334,7 +432,7 @@ impl ManagerState {
                 let (pd, qd) = (p2 - p1, q2 - q1);
 
                 alpha = match grab.mode {
-                    GrabMode::PanFull => qd.complex_div(pd),
+                    GrabMode::PanFull | GrabMode::Gesture => qd.complex_div(pd),
                     GrabMode::PanScale => DVec2((qd.sum_square() / pd.sum_square()).sqrt(), 0.0),
                     GrabMode::PanRotate => {
                         let a = qd.complex_div(pd);
@@ -349,7 +447,18 @@ impl ManagerState {
 
             let id = grab.id;
             if alpha != DVec2(1.0, 0.0) || delta != DVec2::ZERO {
-                let event = Event::Pan { alpha, delta };
+                let event = if grab.mode == GrabMode::Gesture {
+                    let (focus, touches) = match grab.n {
+                        1 => (DVec2::from(grab.coords[0].1), 1),
+                        _ => {
+                            let q2 = DVec2::from(grab.coords[1].1);
+                            ((DVec2::from(grab.coords[0].1) + q2) * 0.5, 2)
+                        }
+                    };
+                    Event::Gesture(Gesture::from_transform(alpha, delta, focus, touches))
+                } else {
+                    Event::Pan { alpha, delta }
+                };
                 mgr.send_event(widget, id, event);
             }
         }
@@ -362,10 +471,14 @@ impl ManagerState {
                 Pending::LostCharFocus(id) => (id, Event::LostCharFocus),
                 Pending::LostSelFocus(id) => (id, Event::LostSelFocus),
                 Pending::SetNavFocus(id, key_focus) => (id, Event::NavFocus(key_focus)),
+                Pending::ScrollTo(id) => (id, Event::ScrollTo),
             };
             mgr.send_event(widget, id, event);
         }
 
+        mgr.reconfigure_subtrees(widget);
+        mgr.deliver_bus_messages(widget);
+
         let action = mgr.action | self.action;
         self.action = TkAction::empty();
         action
@@ -373,13 +486,16 @@ impl ManagerState {
 }
 
 /// Shell API
-#[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
-#[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
 impl<'a> Manager<'a> {
     /// Update widgets due to timer
     pub fn update_timer<W: Widget + ?Sized>(&mut self, widget: &mut W) {
-        let now = Instant::now();
+        self.update_timer_at(widget, Instant::now());
+    }
 
+    /// Update widgets due to timer, as if the current time were `now`
+    ///
+    /// All timers scheduled at or before `now` are fired.
+    pub fn update_timer_at<W: Widget + ?Sized>(&mut self, widget: &mut W, now: Instant) {
         // assumption: time_updates are sorted in reverse order
         while !self.state.time_updates.is_empty() {
             if self.state.time_updates.last().unwrap().0 > now {
@@ -391,6 +507,10 @@ impl<'a> Manager<'a> {
         }
 
         self.state.time_updates.sort_by(|a, b| b.cmp(a)); // reverse sort
+
+        self.update_tooltip(widget, now);
+        self.update_hover_focus(now);
+        self.update_toasts(now);
     }
 
     /// Update widgets due to handle
@@ -402,9 +522,227 @@ impl<'a> Manager<'a> {
     ) {
         // NOTE: to avoid borrow conflict, we must clone values!
         if let Some(mut values) = self.state.handle_updates.get(&handle).cloned() {
-            for w_id in values.drain() {
-                let event = Event::HandleUpdate { handle, payload };
-                self.send_event(widget, w_id, event);
+            for (w_id, filter) in values.drain() {
+                if filter.map(|f| f.matches(payload)).unwrap_or(true) {
+                    let event = Event::HandleUpdate { handle, payload };
+                    self.send_event(widget, w_id, event);
+                }
+            }
+        }
+    }
+
+    /// Handle a received character
+    ///
+    /// The character is sent to the widget with character focus, if any.
+    pub fn handle_received_char<W>(&mut self, widget: &mut W, c: char)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        if let Some(id) = self.state.char_focus() {
+            // Filter out control codes (Unicode 5.11). These may be
+            // generated from combinations such as Ctrl+C by some other
+            // layer. We use our own shortcut system instead.
+            if c >= '\x20' && !('\x7f'..='\u{9f}').contains(&c) {
+                self.key_diag(|| format!("sent ReceivedCharacter({:?}) to char focus {}", c, id));
+                let event = Event::ReceivedCharacter(c);
+                self.send_event(widget, id, event);
+            }
+        } else {
+            self.key_diag(|| format!("character {:?} dropped: no char focus", c));
+        }
+    }
+
+    /// Handle loss of window focus
+    ///
+    /// This closes all pop-ups.
+    pub fn handle_focus_lost(&mut self) {
+        while let Some(id) = self.state.popups.last().map(|(id, _, _)| *id) {
+            self.close_window(id, true);
+        }
+    }
+
+    /// Handle a key press
+    ///
+    /// The `scancode` is used to match the corresponding release.
+    pub fn handle_key_press<W>(&mut self, widget: &mut W, vkey: VirtualKeyCode, scancode: u32)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.start_key_event(widget, vkey, scancode);
+    }
+
+    /// Handle a key release
+    pub fn handle_key_release(&mut self, scancode: u32) {
+        self.end_key_event(scancode);
+    }
+
+    /// Handle a [`Command`] directly, bypassing shortcut resolution
+    ///
+    /// Returns true if some widget handled the command.
+    pub fn handle_command<W>(&mut self, widget: &mut W, cmd: Command) -> bool
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        let shift = self.state.modifiers.shift();
+        self.send_command(widget, cmd, shift)
+    }
+
+    /// Handle a change in modifier state
+    pub fn handle_modifiers(&mut self, state: ModifiersState) {
+        if state.alt() != self.state.modifiers.alt() {
+            // This controls drawing of accelerator key indicators
+            self.state.send_action(TkAction::REDRAW);
+        }
+        self.state.modifiers = state;
+    }
+
+    /// Handle mouse cursor motion
+    ///
+    /// The `coord` is relative to the window.
+    pub fn handle_cursor_moved<W>(&mut self, widget: &mut W, coord: Coord)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.state.last_click_button = FAKE_MOUSE_BUTTON;
+
+        // Update hovered widget
+        let cur_id = widget.find_id(coord);
+        let delta = coord - self.state.last_mouse_coord;
+        self.set_hover(widget, cur_id);
+
+        if let Some(grab) = self.mouse_grab() {
+            if grab.mode == GrabMode::Grab {
+                let source = PressSource::Mouse(grab.button, grab.repetitions);
+                let event = Event::PressMove {
+                    source,
+                    cur_id,
+                    coord,
+                    delta,
+                };
+                self.send_event(widget, grab.start_id, event);
+            } else if let Some(pan) = self.state.pan_grab.get_mut(usize::conv(grab.pan_grab.0)) {
+                pan.coords[usize::conv(grab.pan_grab.1)].1 = coord;
+            }
+        } else if let Some(id) = self.state.popups.last().map(|(_, p, _)| p.parent) {
+            let source = PressSource::Mouse(FAKE_MOUSE_BUTTON, 0);
+            let event = Event::PressMove {
+                source,
+                cur_id,
+                coord,
+                delta,
+            };
+            self.send_event(widget, id, event);
+        } else if let Some(id) = cur_id {
+            // Without a grab, only the hovered widget receives movement
+            self.send_event(widget, id, Event::CursorMove { coord });
+        }
+
+        self.state.last_mouse_coord = coord;
+    }
+
+    /// Handle the mouse cursor leaving the window
+    pub fn handle_cursor_left<W>(&mut self, widget: &mut W)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.state.last_click_button = FAKE_MOUSE_BUTTON;
+
+        if self.mouse_grab().is_none() {
+            // If there's a mouse grab, we will continue to receive
+            // coordinates; if not, set a fake coordinate off the window
+            self.state.last_mouse_coord = Coord(-1, -1);
+            self.set_hover(widget, None);
+        }
+    }
+
+    /// Handle a mouse wheel or touchpad scroll
+    ///
+    /// Shells should classify the `source` device as best they can; see
+    /// [`ScrollSource`]. Deltas should use the same sign convention as winit:
+    /// positive values move content down or to the right.
+    pub fn handle_scroll<W>(&mut self, widget: &mut W, delta: ScrollDelta, source: ScrollSource)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.state.last_click_button = FAKE_MOUSE_BUTTON;
+        self.hide_tooltip();
+
+        self.state.scroll_source = source;
+        let event = Event::Scroll(delta);
+        if let Some(id) = self.state.hover {
+            self.send_event(widget, id, event);
+        }
+    }
+
+    /// Handle a mouse button press or release
+    ///
+    /// The press occurs at the last coordinate passed to
+    /// [`Manager::handle_cursor_moved`].
+    pub fn handle_mouse_input<W>(&mut self, widget: &mut W, pressed: bool, button: MouseButton)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        let coord = self.state.last_mouse_coord;
+
+        if pressed {
+            let now = Instant::now();
+            if button != self.state.last_click_button || self.state.last_click_timeout < now {
+                self.state.last_click_button = button;
+                self.state.last_click_repetitions = 0;
+            }
+            self.state.last_click_repetitions += 1;
+            self.state.last_click_timeout = now + DOUBLE_CLICK_TIMEOUT;
+            self.hide_tooltip();
+
+            if self.mouse_grab().is_none() && self.click_toast(widget, coord) {
+                return;
+            }
+        }
+
+        if self.state.inspector.active && self.mouse_grab().is_none() {
+            // The inspector captures clicks to select widgets
+            if pressed && button == MouseButton::Left {
+                self.state.inspector.selected = self.state.hover;
+                self.send_action(TkAction::REDRAW);
+            }
+            return;
+        }
+
+        if let Some(grab) = self.mouse_grab() {
+            if grab.mode == GrabMode::Grab {
+                // Mouse grab active: send events there
+                debug_assert!(!pressed);
+                let source = PressSource::Mouse(button, grab.repetitions);
+                let event = Event::PressEnd {
+                    source,
+                    end_id: self.state.hover,
+                    coord,
+                };
+                self.send_event(widget, grab.start_id, event);
+                // Pan events do not receive Start/End notifications
+            };
+
+            if !pressed {
+                self.end_mouse_grab(button);
+            }
+        } else if let Some(start_id) = self.state.hover {
+            // No mouse grab but have a hover target
+            if pressed {
+                let source = PressSource::Mouse(button, self.state.last_click_repetitions);
+                let event = Event::PressStart {
+                    source,
+                    start_id,
+                    coord,
+                };
+                self.send_popup_first(widget, start_id, event);
+
+                if self.state.config.borrow().mouse_nav_focus() {
+                    if let Some(w) = widget.find_leaf(start_id) {
+                        if w.key_nav() {
+                            self.set_nav_focus(w.id(), false);
+                        }
+                    }
+                }
             }
         }
     }
@@ -433,22 +771,10 @@ impl<'a> Manager<'a> {
             HoveredFile(path) => ,
             HoveredFileCancelled => ,
             */
-            ReceivedCharacter(c) => {
-                if let Some(id) = self.state.char_focus() {
-                    // Filter out control codes (Unicode 5.11). These may be
-                    // generated from combinations such as Ctrl+C by some other
-                    // layer. We use our own shortcut system instead.
-                    if c >= '\x20' && !('\x7f'..='\u{9f}').contains(&c) {
-                        let event = Event::ReceivedCharacter(c);
-                        self.send_event(widget, id, event);
-                    }
-                }
-            }
+            ReceivedCharacter(c) => self.handle_received_char(widget, c),
             Focused(false) => {
                 // Window focus lost: close all popups
-                while let Some(id) = self.state.popups.last().map(|(id, _, _)| *id) {
-                    self.close_window(id, true);
-                }
+                self.handle_focus_lost();
             }
             KeyboardInput {
                 input,
@@ -457,136 +783,39 @@ impl<'a> Manager<'a> {
             } => {
                 if input.state == ElementState::Pressed && !is_synthetic {
                     if let Some(vkey) = input.virtual_keycode {
-                        self.start_key_event(widget, vkey, input.scancode);
+                        self.handle_key_press(widget, vkey.into(), input.scancode);
                     }
                 } else if input.state == ElementState::Released {
-                    self.end_key_event(input.scancode);
-                }
-            }
-            ModifiersChanged(state) => {
-                if state.alt() != self.state.modifiers.alt() {
-                    // This controls drawing of accelerator key indicators
-                    self.state.send_action(TkAction::REDRAW);
+                    self.handle_key_release(input.scancode);
                 }
-                self.state.modifiers = state;
-            }
-            CursorMoved { position, .. } => {
-                self.state.last_click_button = FAKE_MOUSE_BUTTON;
-                let coord = position.into();
-
-                // Update hovered widget
-                let cur_id = widget.find_id(coord);
-                let delta = coord - self.state.last_mouse_coord;
-                self.set_hover(widget, cur_id);
-
-                if let Some(grab) = self.mouse_grab() {
-                    if grab.mode == GrabMode::Grab {
-                        let source = PressSource::Mouse(grab.button, grab.repetitions);
-                        let event = Event::PressMove {
-                            source,
-                            cur_id,
-                            coord,
-                            delta,
-                        };
-                        self.send_event(widget, grab.start_id, event);
-                    } else if let Some(pan) =
-                        self.state.pan_grab.get_mut(usize::conv(grab.pan_grab.0))
-                    {
-                        pan.coords[usize::conv(grab.pan_grab.1)].1 = coord;
-                    }
-                } else if let Some(id) = self.state.popups.last().map(|(_, p, _)| p.parent) {
-                    let source = PressSource::Mouse(FAKE_MOUSE_BUTTON, 0);
-                    let event = Event::PressMove {
-                        source,
-                        cur_id,
-                        coord,
-                        delta,
-                    };
-                    self.send_event(widget, id, event);
-                } else {
-                    // We don't forward move events without a grab
-                }
-
-                self.state.last_mouse_coord = coord;
             }
+            ModifiersChanged(state) => self.handle_modifiers(state.into()),
+            CursorMoved { position, .. } => self.handle_cursor_moved(widget, position.into()),
             // CursorEntered { .. },
-            CursorLeft { .. } => {
-                self.state.last_click_button = FAKE_MOUSE_BUTTON;
-
-                if self.mouse_grab().is_none() {
-                    // If there's a mouse grab, we will continue to receive
-                    // coordinates; if not, set a fake coordinate off the window
-                    self.state.last_mouse_coord = Coord(-1, -1);
-                    self.set_hover(widget, None);
-                }
-            }
+            CursorLeft { .. } => self.handle_cursor_left(widget),
             MouseWheel { delta, .. } => {
-                self.state.last_click_button = FAKE_MOUSE_BUTTON;
-
-                let event = Event::Scroll(match delta {
-                    MouseScrollDelta::LineDelta(x, y) => ScrollDelta::LineDelta(x, y),
+                // winit does not report the device. Wheels report whole
+                // lines; touchpads report pixels or (on X11) fractional lines.
+                let (delta, source) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => {
+                        let source = match x.fract() == 0.0 && y.fract() == 0.0 {
+                            true => ScrollSource::Wheel,
+                            false => ScrollSource::Touchpad,
+                        };
+                        (ScrollDelta::LineDelta(x, y), source)
+                    }
                     MouseScrollDelta::PixelDelta(pos) => {
                         // The delta is given as a PhysicalPosition, so we need
-                        // to convert to our vector type (Offset) here.
-                        let coord = Coord::from(pos);
-                        ScrollDelta::PixelDelta(Offset(coord.0, coord.1))
+                        // to convert to our vector type (DVec2) here. We do
+                        // not round since sub-pixel deltas are significant.
+                        let delta = ScrollDelta::PixelDelta(DVec2(pos.x, pos.y));
+                        (delta, ScrollSource::Touchpad)
                     }
-                });
-                if let Some(id) = self.state.hover {
-                    self.send_event(widget, id, event);
-                }
+                };
+                self.handle_scroll(widget, delta, source);
             }
             MouseInput { state, button, .. } => {
-                let coord = self.state.last_mouse_coord;
-
-                if state == ElementState::Pressed {
-                    let now = Instant::now();
-                    if button != self.state.last_click_button || self.state.last_click_timeout < now
-                    {
-                        self.state.last_click_button = button;
-                        self.state.last_click_repetitions = 0;
-                    }
-                    self.state.last_click_repetitions += 1;
-                    self.state.last_click_timeout = now + DOUBLE_CLICK_TIMEOUT;
-                }
-
-                if let Some(grab) = self.mouse_grab() {
-                    if grab.mode == GrabMode::Grab {
-                        // Mouse grab active: send events there
-                        debug_assert_eq!(state, ElementState::Released);
-                        let source = PressSource::Mouse(button, grab.repetitions);
-                        let event = Event::PressEnd {
-                            source,
-                            end_id: self.state.hover,
-                            coord,
-                        };
-                        self.send_event(widget, grab.start_id, event);
-                        // Pan events do not receive Start/End notifications
-                    };
-
-                    if state == ElementState::Released {
-                        self.end_mouse_grab(button);
-                    }
-                } else if let Some(start_id) = self.state.hover {
-                    // No mouse grab but have a hover target
-                    if state == ElementState::Pressed {
-                        let source = PressSource::Mouse(button, self.state.last_click_repetitions);
-                        let event = Event::PressStart {
-                            source,
-                            start_id,
-                            coord,
-                        };
-                        self.send_popup_first(widget, start_id, event);
-
-                        if self.state.config.borrow().mouse_nav_focus() {
-                            if let Some(w) = widget.find_leaf(start_id) {
-                                if w.key_nav() {
-                                    self.set_nav_focus(w.id(), false);
-                                }
-                            }
-                        }
-                    }
-                }
+                self.handle_mouse_input(widget, state == ElementState::Pressed, button.into());
             }
             // TouchpadPressure { pressure: f32, stage: i64, },
             // AxisMotion { axis: AxisId, value: f64, },
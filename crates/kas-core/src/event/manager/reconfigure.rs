@@ -0,0 +1,167 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Subtree reconfiguration
+//!
+//! [`TkAction::RECONFIGURE`] configures the whole window, then resizes it.
+//! [`Manager::reconfigure_subtree`] instead configures a single widget and its
+//! descendants, assigning identifiers within the subtree's existing range, and
+//! (where possible) lays out only this subtree.
+
+use log::{debug, warn};
+use std::mem::take;
+
+use super::*;
+use crate::layout::{AlignHints, AxisInfo};
+
+/// Subtree reconfiguration
+impl<'a> Manager<'a> {
+    /// Reconfigure a widget and its descendants
+    ///
+    /// This is a cheaper alternative to [`TkAction::RECONFIGURE`] for use when
+    /// a widget adds or replaces some of its children. The subtree is
+    /// configured on the next update; if its new size requirements fit within
+    /// its current rect, only the subtree is laid out, otherwise the window is
+    /// resized.
+    ///
+    /// Identifiers are assigned within the widget's existing range (from
+    /// [`crate::WidgetChildren::first_id`] to [`crate::WidgetCore::id`]).
+    /// Widgets expecting to add children should leave room via
+    /// [`ConfigureManager::reserve_ids`]. Where the range is insufficient, the
+    /// whole window is reconfigured.
+    pub fn reconfigure_subtree(&mut self, id: WidgetId) {
+        if !self.state.reconfigure.contains(&id) {
+            self.state.reconfigure.push(id);
+        }
+    }
+
+    /// Reconfigure subtrees queued by [`Self::reconfigure_subtree`]
+    pub(super) fn reconfigure_subtrees<W: Widget + ?Sized>(&mut self, widget: &mut W) {
+        let mut ids = take(&mut self.state.reconfigure);
+        if (self.action | self.state.action).contains(TkAction::RECONFIGURE) {
+            return;
+        }
+
+        // Ancestors have greater identifiers; configure these first
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        let mut done: Vec<(WidgetId, WidgetId)> = vec![];
+        for id in ids {
+            if done.iter().any(|(first, last)| *first <= id && id <= *last) {
+                continue;
+            }
+            if let Some(range) = self.reconfigure_subtree_now(widget, id) {
+                done.push(range);
+            }
+            if self.action.contains(TkAction::RECONFIGURE) {
+                break;
+            }
+        }
+    }
+
+    /// Reconfigure the subtree `id`, returning its (old) range of identifiers
+    fn reconfigure_subtree_now<W: Widget + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        id: WidgetId,
+    ) -> Option<(WidgetId, WidgetId)> {
+        let w = widget.find_leaf_mut(id)?;
+        let first = w.first_id();
+        let in_range = |x: WidgetId| first <= x && x <= id;
+        debug!("Manager::reconfigure_subtree: {} (from {})", id, first);
+
+        // We re-create these:
+        for layer in self.state.accel_layers.values_mut() {
+            layer.1.retain(|_, x| !in_range(*x));
+        }
+        self.state.accel_layers.retain(|x, _| !in_range(*x));
+        // The subtree may add or remove tab indices of any enclosing scope
+        self.state.tab_index_scopes.clear();
+        if self.state.nav_fallback.map(in_range).unwrap_or(false) {
+            self.state.nav_fallback = None;
+        }
+        self.state.bus.remove_subscribers(in_range);
+        // These we merge later:
+        let (old_time_updates, time_updates): (Vec<_>, Vec<_>) = take(&mut self.state.time_updates)
+            .into_iter()
+            .partition(|row| in_range(row.1));
+        self.state.time_updates = time_updates;
+        let mut old_handle_updates = HashMap::new();
+        for (handle, ids) in self.state.handle_updates.iter_mut() {
+            let mut old_ids = LinearMap::new();
+            ids.retain(|x, filter| {
+                if in_range(*x) {
+                    old_ids.insert(*x, filter.take());
+                    false
+                } else {
+                    true
+                }
+            });
+            old_handle_updates.insert(*handle, old_ids);
+        }
+
+        let mut renames = HashMap::new();
+        let mut next = first;
+        self.push_accel_layer(false);
+        w.configure_recurse(ConfigureManager {
+            id: &mut next,
+            limit: id.next(),
+            map: &mut renames,
+            mgr: self,
+        });
+        let accel_keys = self.state.accel_stack.pop().map(|layer| layer.1);
+
+        if w.id() != id {
+            warn!(
+                "Manager::reconfigure_subtree: insufficient identifiers for {}; reconfiguring window",
+                id
+            );
+            *self |= TkAction::RECONFIGURE;
+        } else {
+            let rect = w.rect();
+            let (w_rules, h_rules) = self.size_handle(|size_handle| {
+                let w_rules = w.size_rules(size_handle, AxisInfo::new(false, Some(rect.size.1)));
+                let h_rules = w.size_rules(size_handle, AxisInfo::new(true, Some(rect.size.0)));
+                (w_rules, h_rules)
+            });
+            if w_rules.min_size() > rect.size.0 || h_rules.min_size() > rect.size.1 {
+                *self |= TkAction::RESIZE;
+            } else {
+                w.set_rect(self, rect, AlignHints::NONE);
+                *self |= TkAction::REGION_MOVED;
+            }
+        }
+
+        // Merge accelerator keys into the nearest enclosing layer
+        if let Some(keys) = accel_keys {
+            let owner = self
+                .state
+                .accel_layers
+                .keys()
+                .cloned()
+                .filter(|owner| {
+                    *owner > id
+                        && widget
+                            .find_leaf(*owner)
+                            .map(|w| w.is_ancestor_of(id))
+                            .unwrap_or(false)
+                })
+                .min();
+            if let Some(layer) = owner.and_then(|owner| self.state.accel_layers.get_mut(&owner)) {
+                layer.1.extend(keys);
+            }
+        }
+
+        self.state
+            .rename_ids(old_time_updates, old_handle_updates, |x| {
+                if in_range(x) {
+                    renames.get(&x).cloned()
+                } else {
+                    Some(x)
+                }
+            });
+
+        Some((first, id))
+    }
+}
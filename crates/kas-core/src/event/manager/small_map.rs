@@ -0,0 +1,131 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Linear map with inline storage
+
+use smallvec::{Array, SmallVec};
+use std::fmt;
+
+/// A linear map with inline storage
+///
+/// This is used for per-interaction state (touch grabs, depressed keys), which
+/// usually holds only a handful of entries. Up to `A::size()` entries are
+/// stored inline, thus starting and ending interactions does not allocate;
+/// beyond this capacity, contents spill to the heap. Lookup is a linear scan.
+pub(crate) struct SmallMap<A: Array>(SmallVec<A>);
+
+impl<A: Array> Default for SmallMap<A> {
+    fn default() -> Self {
+        SmallMap(SmallVec::new())
+    }
+}
+
+impl<K, V, A> fmt::Debug for SmallMap<A>
+where
+    K: PartialEq + fmt::Debug,
+    V: fmt::Debug,
+    A: Array<Item = (K, V)>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: PartialEq, V, A: Array<Item = (K, V)>> SmallMap<A> {
+    /// Number of entries
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// True if contents have spilled to the heap
+    #[cfg(test)]
+    pub fn spilled(&self) -> bool {
+        self.0.spilled()
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.0.iter_mut().find(|e| e.0 == *key).map(|e| &mut e.1)
+    }
+
+    /// Insert, returning the previous value under `key` if any
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(v) = self.get_mut(&key) {
+            return Some(std::mem::replace(v, value));
+        }
+        self.0.push((key, value));
+        None
+    }
+
+    /// Remove the entry under `key`
+    ///
+    /// This does not preserve order of remaining entries.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index = self.0.iter().position(|e| e.0 == *key)?;
+        Some(self.0.swap_remove(index).1)
+    }
+
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        self.0.retain(|e| f(&e.0, &mut e.1));
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.0.iter().map(|e| (&e.0, &e.1))
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.0.iter_mut().map(|e| (&e.0, &mut e.1))
+    }
+
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.0.iter().map(|e| &e.1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SmallMap;
+
+    #[test]
+    fn many_touches_inline() {
+        let mut map = SmallMap::<[(u64, u32); 16]>::default();
+        // 12 simultaneous touches, each moved several times, ending in turn
+        for id in 0..12 {
+            assert_eq!(map.insert(id, 0), None);
+        }
+        for step in 1..=5 {
+            for id in 0..12 {
+                *map.get_mut(&id).unwrap() = step;
+            }
+        }
+        assert!(map.values().all(|v| *v == 5));
+        for id in (0..12).step_by(2) {
+            assert_eq!(map.remove(&id), Some(5));
+        }
+        assert_eq!(map.len(), 6);
+        assert!(map.get_mut(&4).is_none());
+        assert!(!map.spilled());
+    }
+
+    #[test]
+    fn overflow() {
+        let mut map = SmallMap::<[(u64, u32); 4]>::default();
+        for id in 0..10 {
+            map.insert(id, id as u32);
+        }
+        assert!(map.spilled());
+        assert_eq!(map.len(), 10);
+        map.retain(|id, _| *id >= 5);
+        let mut keys: Vec<u64> = map.iter().map(|(id, _)| *id).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, vec![5, 6, 7, 8, 9]);
+        assert_eq!(map.insert(7, 0), Some(7));
+        assert_eq!(map.len(), 5);
+    }
+}
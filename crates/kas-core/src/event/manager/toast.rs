@@ -0,0 +1,376 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Toast notifications
+//!
+//! Any widget may post a transient notification via [`Manager::notify`].
+//! Notifications are drawn as overlays (like pop-ups) stacked above the bottom
+//! edge of the window, sliding in on arrival and out on dismissal. A toast is
+//! dismissed after its duration or when clicked. Its optional action button
+//! sends [`Event::ToastAction`] to the requesting widget.
+
+use instant::Instant;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::*;
+use crate::cast::{CastFloat, Conv};
+use crate::draw::{DrawHandle, InputState, SizeHandle, TextClass};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::AxisInfo;
+use crate::text::Text;
+use crate::{WidgetConfig, WidgetId};
+
+/// Maximum number of toasts shown at once; others are queued
+const MAX_SHOWN: usize = 4;
+/// Duration of the slide-in and slide-out animations
+const ANIM_DURATION: Duration = Duration::from_millis(150);
+/// Default display duration of a [`Toast`]
+const DEFAULT_DURATION: Duration = Duration::from_secs(4);
+
+/// Severity of a [`Toast`]
+///
+/// This determines how the theme marks the notification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// Information
+    Info,
+    /// A warning
+    Warning,
+    /// An error
+    Error,
+}
+
+impl Default for Severity {
+    fn default() -> Self {
+        Severity::Info
+    }
+}
+
+/// An action button on a [`Toast`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ToastAction {
+    /// Button label
+    pub label: String,
+    /// Widget to which [`Event::ToastAction`] is sent
+    pub id: WidgetId,
+    /// Payload of [`Event::ToastAction`]
+    pub payload: u64,
+}
+
+/// A toast notification
+///
+/// See [`Manager::notify`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Toast {
+    /// Message text
+    pub text: String,
+    /// Severity
+    pub severity: Severity,
+    /// Display duration (excluding animation)
+    ///
+    /// If `None`, the toast is shown until clicked.
+    pub duration: Option<Duration>,
+    /// Optional action button
+    pub action: Option<ToastAction>,
+}
+
+impl Toast {
+    /// Construct an informational toast, shown for a default duration
+    pub fn new<S: ToString>(text: S) -> Self {
+        Toast {
+            text: text.to_string(),
+            severity: Severity::Info,
+            duration: Some(DEFAULT_DURATION),
+            action: None,
+        }
+    }
+
+    /// Set the severity (inline)
+    #[inline]
+    #[must_use]
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Set the display duration (inline)
+    ///
+    /// If `None`, the toast is shown until clicked.
+    #[inline]
+    #[must_use]
+    pub fn with_duration(mut self, duration: Option<Duration>) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Add an action button (inline)
+    ///
+    /// When the button is clicked, the toast is dismissed and
+    /// [`Event::ToastAction`] with this `payload` is sent to widget `id`.
+    #[inline]
+    #[must_use]
+    pub fn with_action<S: ToString>(mut self, label: S, id: WidgetId, payload: u64) -> Self {
+        self.action = Some(ToastAction {
+            label: label.to_string(),
+            id,
+            payload,
+        });
+        self
+    }
+}
+
+/// A shown toast
+#[derive(Clone, Debug)]
+struct Shown {
+    toast: Toast,
+    /// Start of the slide-in animation
+    start: Instant,
+    /// Start of the slide-out animation, if scheduled
+    end: Option<Instant>,
+}
+
+impl Shown {
+    /// Visible fraction at time `t`, in the range `0.0..=1.0`
+    fn visible(&self, t: Instant) -> f32 {
+        let anim = ANIM_DURATION.as_secs_f32();
+        let f = match self.end {
+            Some(end) if end <= t => 1.0 - (t - end).as_secs_f32() / anim,
+            _ => t.saturating_duration_since(self.start).as_secs_f32() / anim,
+        };
+        f.max(0.0).min(1.0)
+    }
+
+    fn is_animating(&self, t: Instant) -> bool {
+        t < self.start + ANIM_DURATION || self.end.map(|end| end <= t).unwrap_or(false)
+    }
+
+    fn dismiss(&mut self, now: Instant) {
+        if self.end.map(|end| end > now).unwrap_or(true) {
+            self.end = Some(now);
+        }
+    }
+}
+
+/// Placement of a shown toast
+struct Placed {
+    index: usize,
+    rect: Rect,
+    text: Text<String>,
+    text_pos: Coord,
+    button: Option<(Rect, Text<String>, Coord)>,
+}
+
+/// Toast state
+#[derive(Clone, Debug, Default)]
+pub(super) struct Toasts {
+    /// Shown toasts, oldest first
+    shown: Vec<Shown>,
+    queue: VecDeque<Toast>,
+}
+
+impl Toasts {
+    /// Remove expired toasts and show queued ones
+    ///
+    /// Returns true if a redraw is required.
+    fn update(&mut self, now: Instant) -> bool {
+        let len = self.shown.len();
+        self.shown
+            .retain(|s| s.end.map(|end| now < end + ANIM_DURATION).unwrap_or(true));
+        let mut redraw = self.shown.len() != len;
+
+        while self.shown.len() < MAX_SHOWN {
+            let toast = match self.queue.pop_front() {
+                Some(toast) => toast,
+                None => break,
+            };
+            let end = toast.duration.map(|d| now + ANIM_DURATION + d);
+            self.shown.push(Shown {
+                toast,
+                start: now,
+                end,
+            });
+            redraw = true;
+        }
+
+        redraw || self.shown.iter().any(|s| s.is_animating(now))
+    }
+
+    /// Place toasts within `window` as at time `t`, newest first
+    fn layout(&self, sh: &mut dyn SizeHandle, window: Rect, t: Instant) -> Vec<Placed> {
+        let max_width = sh.pixels_from_em(30.0).cast_nearest();
+        let spacing = i32::conv(sh.outer_margins().vert.1);
+        // Padding within the frame
+        let inner = sh.inner_margin() * 2;
+
+        let mut y = window.pos2().1;
+        let mut placed = Vec::with_capacity(self.shown.len());
+        for (index, shown) in self.shown.iter().enumerate().rev() {
+            let button = shown.toast.action.as_ref().map(|action| {
+                let mut text = Text::new_single(action.label.clone());
+                let w = sh.text_bound(&mut text, TextClass::Button, AxisInfo::new(false, None));
+                let w = w.ideal_size();
+                let h = sh.text_bound(&mut text, TextClass::Button, AxisInfo::new(true, Some(w)));
+                (Size(w, h.ideal_size()), text)
+            });
+            let button_width = button.as_ref().map(|b| b.0 .0 + inner.0 * 3).unwrap_or(0);
+
+            let mut text = Text::new_multi(shown.toast.text.clone());
+            let avail = (window.size.0 - 2 * spacing).min(max_width);
+            let width = sh.text_bound(&mut text, TextClass::Label, AxisInfo::new(false, None));
+            let width = width.ideal_size().min(avail - button_width - inner.0 * 2);
+            let height = sh.text_bound(
+                &mut text,
+                TextClass::Label,
+                AxisInfo::new(true, Some(width)),
+            );
+            let height = height.ideal_size();
+            text.update_env(|env| env.set_bounds(Size(width, height).into()));
+
+            let button_height = button.as_ref().map(|b| b.0 .1 + inner.1 * 2).unwrap_or(0);
+            let frame = Size(width + button_width, height.max(button_height)) + inner * 2;
+
+            let f = shown.visible(t);
+            let dy: i32 = (f * (frame.1 + spacing) as f32).cast_nearest();
+            y -= dy;
+            let pos = Coord(window.pos.0 + (window.size.0 - frame.0) / 2, y);
+            let text_pos = pos + inner + Size(0, (frame.1 - inner.1 * 2 - height) / 2);
+
+            let button = button.map(|(size, mut text)| {
+                let size = size + inner * 2;
+                let p = pos + Size(frame.0 - inner.0 - size.0, (frame.1 - size.1) / 2);
+                text.update_env(|env| env.set_bounds((size - inner * 2).into()));
+                (Rect::new(p, size), text, p + inner)
+            });
+
+            placed.push(Placed {
+                index,
+                rect: Rect::new(pos, frame),
+                text,
+                text_pos,
+                button,
+            });
+        }
+        placed
+    }
+}
+
+impl ManagerState {
+    /// Time at which toasts next require an update
+    pub(super) fn toast_resume(&self) -> Option<Instant> {
+        let now = Instant::now();
+        let mut next: Option<Instant> = None;
+        for shown in &self.toasts.shown {
+            let time = if shown.is_animating(now) {
+                // Update in time for the next frame, as Manager::update_on_frame does
+                self.next_frame_time() - self.frame_interval()
+            } else if let Some(end) = shown.end {
+                end
+            } else {
+                continue;
+            };
+            next = Some(next.map(|t| t.min(time)).unwrap_or(time));
+        }
+        next
+    }
+
+    /// Draw toast notifications, if any
+    ///
+    /// Shells should call this after drawing the window's widget (and after
+    /// [`ManagerState::draw_inspector`], but before
+    /// [`ManagerState::draw_tooltip`]).
+    pub fn draw_toasts(&self, widget: &dyn WidgetConfig, draw: &mut dyn DrawHandle) {
+        if self.toasts.shown.is_empty() {
+            return;
+        }
+
+        let time = self.next_frame_time();
+        let placed = self.toasts.layout(draw.size_handle(), widget.rect(), time);
+        for toast in placed {
+            let severity = self.toasts.shown[toast.index].toast.severity;
+            draw.with_overlay(toast.rect, &mut |draw| {
+                draw.toast(toast.rect, severity);
+                let state = InputState::empty();
+                draw.text(toast.text_pos, toast.text.as_ref(), TextClass::Label, state);
+                if let Some((rect, text, pos)) = toast.button.as_ref() {
+                    let mut state = InputState::empty();
+                    if rect.contains(self.last_mouse_coord) {
+                        state |= InputState::HOVER;
+                    }
+                    draw.button(*rect, None, state);
+                    draw.text(*pos, text.as_ref(), TextClass::Button, state);
+                }
+            });
+        }
+    }
+}
+
+/// Toast notifications
+impl<'a> Manager<'a> {
+    /// Show a toast notification
+    ///
+    /// The toast is drawn over the window's content, stacked above the bottom
+    /// edge with other toasts. If too many toasts are shown, it is queued
+    /// until space is available. It is dismissed after
+    /// [`Toast::duration`] or when clicked; see also [`Toast::with_action`].
+    pub fn notify(&mut self, toast: Toast) {
+        self.state.toasts.queue.push_back(toast);
+        if self.state.toasts.update(Instant::now()) {
+            self.send_action(TkAction::REDRAW);
+        }
+    }
+
+    /// Dismiss all toast notifications, including queued ones
+    pub fn clear_toasts(&mut self) {
+        let now = Instant::now();
+        self.state.toasts.queue.clear();
+        for shown in &mut self.state.toasts.shown {
+            shown.dismiss(now);
+        }
+        self.send_action(TkAction::REDRAW);
+    }
+
+    /// Update toasts as at time `now`
+    pub(super) fn update_toasts(&mut self, now: Instant) {
+        if self.state.toasts.update(now) {
+            self.send_action(TkAction::REDRAW);
+        }
+    }
+
+    /// Handle a click at `coord`, returning true if it hit a toast
+    pub(super) fn click_toast<W>(&mut self, widget: &mut W, coord: Coord) -> bool
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        if self.state.toasts.shown.is_empty() {
+            return false;
+        }
+
+        let now = Instant::now();
+        let window = widget.rect();
+        let toasts = std::mem::take(&mut self.state.toasts);
+        let hit = self.size_handle(|sh| {
+            let placed = toasts.layout(sh, window, now);
+            placed.into_iter().find_map(|toast| {
+                let on_button = toast.button.map(|b| b.0.contains(coord)).unwrap_or(false);
+                toast.rect.contains(coord).then(|| (toast.index, on_button))
+            })
+        });
+        self.state.toasts = toasts;
+
+        let (index, on_button) = match hit {
+            Some(hit) => hit,
+            None => return false,
+        };
+        let shown = &mut self.state.toasts.shown[index];
+        shown.dismiss(now);
+        let action = shown.toast.action.as_ref().filter(|_| on_button);
+        if let Some(ToastAction { id, payload, .. }) = action.cloned() {
+            self.send_event(widget, id, Event::ToastAction(payload));
+        }
+        self.send_action(TkAction::REDRAW);
+        true
+    }
+}
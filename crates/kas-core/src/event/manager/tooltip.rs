@@ -0,0 +1,131 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Hover tooltips
+//!
+//! When the mouse rests over a widget for [`Config::tooltip_delay`], the
+//! hovered widget and its ancestors are asked for a tooltip via
+//! [`WidgetConfig::tooltip`]. The innermost result, if any, is drawn near the
+//! cursor until the hovered widget changes or a mouse button is pressed.
+
+use instant::Instant;
+
+use super::*;
+use crate::cast::CastFloat;
+use crate::draw::{DrawHandle, DrawHandleExt, InputState, TextClass};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::AxisInfo;
+use crate::text::Text;
+use crate::WidgetConfig;
+
+/// Tooltip state
+#[derive(Clone, Debug, Default)]
+pub(super) struct Tooltip {
+    /// Time at which to query the hovered widget's tooltip
+    pending: Option<Instant>,
+    /// Text and position of the shown tooltip
+    shown: Option<(String, Coord)>,
+}
+
+impl ManagerState {
+    /// Time at which the pending tooltip (if any) should be shown
+    pub(super) fn tooltip_resume(&self) -> Option<Instant> {
+        self.tooltip.pending
+    }
+
+    /// Draw the hover tooltip, if any
+    ///
+    /// Shells should call this after drawing the window's widget (and after
+    /// [`ManagerState::draw_inspector`]).
+    pub fn draw_tooltip(&self, widget: &dyn WidgetConfig, draw: &mut dyn DrawHandle) {
+        let (s, coord) = match self.tooltip.shown.as_ref() {
+            Some(shown) => shown,
+            None => return,
+        };
+
+        let window = widget.rect();
+        let mut text = Text::new_multi(s.clone());
+        let (frame, inner) = {
+            let sh = draw.size_handle();
+            let max_width = sh.pixels_from_em(30.0).cast_nearest();
+            let width = sh.text_bound(&mut text, TextClass::Label, AxisInfo::new(false, None));
+            let width = width.ideal_size().min(max_width).min(window.size.0);
+            let height = sh.text_bound(
+                &mut text,
+                TextClass::Label,
+                AxisInfo::new(true, Some(width)),
+            );
+            // Padding within the frame
+            let margin = sh.inner_margin() * 2;
+            (Size(width, height.ideal_size()) + margin * 2, margin)
+        };
+        text.update_env(|env| env.set_bounds((frame - inner * 2).into()));
+
+        // Place below the cursor, within the window where possible
+        let offset = draw.size_handle().line_height(TextClass::Label);
+        let mut pos = *coord + Size(0, offset);
+        let max = window.pos2() - frame;
+        pos.0 = pos.0.min(max.0).max(window.pos.0);
+        if pos.1 > max.1 {
+            pos.1 = (coord.1 - frame.1).max(window.pos.1);
+        }
+
+        let rect = Rect::new(pos, frame);
+        draw.with_overlay(rect, &mut |draw| {
+            draw.outer_frame(rect);
+            let state = InputState::empty();
+            draw.text(pos + inner, text.as_ref(), TextClass::Label, state);
+        });
+    }
+}
+
+/// Tooltip methods
+impl<'a> Manager<'a> {
+    /// Hide any shown tooltip and (re)start the delay for the hovered widget
+    pub(super) fn reset_tooltip(&mut self) {
+        if self.state.tooltip.shown.take().is_some() {
+            self.send_action(TkAction::REDRAW);
+        }
+        self.state.tooltip.pending = self.state.hover.map(|_| {
+            let delay = self.state.config.borrow().tooltip_delay();
+            Instant::now() + delay
+        });
+    }
+
+    /// Hide any shown tooltip without restarting the delay
+    pub(super) fn hide_tooltip(&mut self) {
+        if self.state.tooltip.shown.take().is_some() {
+            self.send_action(TkAction::REDRAW);
+        }
+        self.state.tooltip.pending = None;
+    }
+
+    /// Show the tooltip if its delay has elapsed
+    pub(super) fn update_tooltip<W: Widget + ?Sized>(&mut self, widget: &W, now: Instant) {
+        match self.state.tooltip.pending {
+            Some(time) if time <= now => self.state.tooltip.pending = None,
+            _ => return,
+        }
+        let id = match self.state.hover {
+            Some(id) if self.state.mouse_grab.is_none() => id,
+            _ => return,
+        };
+
+        // Use the innermost tooltip along the path to the hovered widget
+        let mut node = widget.as_widget();
+        let mut tooltip = node.tooltip();
+        while let Some(index) = node.find_child(id) {
+            node = node.get_child(index).unwrap();
+            if let Some(s) = node.tooltip() {
+                tooltip = Some(s);
+            }
+        }
+
+        if let Some(s) = tooltip {
+            self.state.tooltip.shown = Some((s, self.state.last_mouse_coord));
+            self.send_action(TkAction::REDRAW);
+        }
+    }
+}
@@ -28,8 +28,9 @@
 //!
 //! The [`Response`] enum has a few variants; most important is `Msg(msg)`
 //! which passes a user-defined payload up to a parent widget. The
-//! `Unhandled(event)` and `Focus(rect)` variants may be trapped by any parent
-//! for secondary purposes, e.g. to adjust a `ScrollRegion`.
+//! `Unhandled(event)`, `Focus(rect)` and `Reveal(rect, align)` variants may
+//! be trapped by any parent for secondary purposes, e.g. to adjust a
+//! `ScrollRegion`.
 //!
 //! ## Mouse and touch events
 //!
@@ -68,7 +69,6 @@
 //! [`WidgetId`]: crate::WidgetId
 
 mod config;
-#[cfg(not(feature = "winit"))]
 mod enums;
 mod events;
 mod handler;
@@ -86,19 +86,16 @@ use std::fmt::Debug;
 #[allow(unused)]
 use crate::{draw::InputState, Layout, WidgetCore};
 
-#[cfg(feature = "winit")]
-pub use winit::event::{ModifiersState, MouseButton, VirtualKeyCode};
-#[cfg(feature = "winit")]
-pub use winit::window::CursorIcon;
-
-pub use config::Config;
-#[cfg(not(feature = "winit"))]
+pub use config::{Config, HoverFocus, ScrollEasing};
 pub use enums::{CursorIcon, ModifiersState, MouseButton, VirtualKeyCode};
 pub use events::*;
 pub use handler::{Handler, SendEvent};
-pub use manager::{ConfigureManager, GrabMode, Manager, ManagerState};
+pub use manager::{
+    BusMessage, ConfigureManager, GrabMode, Manager, ManagerState, Severity, Toast, ToastAction,
+};
 pub use response::Response;
-pub use update::UpdateHandle;
+pub use shortcuts::Shortcuts;
+pub use update::{TypedUpdateHandle, UpdateFilter, UpdateHandle, UpdatePayload};
 
 /// A type supporting a small number of key bindings
 ///
@@ -155,16 +152,79 @@ impl_void_msg!(u8, u16, u32, u64, u128, usize);
 impl_void_msg!(i8, i16, i32, i64, i128, isize);
 impl_void_msg!(f32, f64);
 impl_void_msg!(&'static str, String);
-impl_void_msg!(std::time::Duration, std::time::Instant);
+impl_void_msg!(std::time::Duration, instant::Instant);
+
+/// The kind of input which triggered a message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputKind {
+    /// A mouse press, with the given button
+    Mouse(MouseButton),
+    /// A touch press
+    Touch,
+    /// Keyboard input (including navigation and activation via keys)
+    Key,
+    /// Anything else (e.g. a programmatic update)
+    Other,
+}
+
+impl InputKind {
+    /// Determine the kind of input from an event
+    pub fn from_event(event: &Event) -> Self {
+        match event {
+            Event::PressStart { source, .. }
+            | Event::PressMove { source, .. }
+            | Event::PressEnd { source, .. } => match source {
+                PressSource::Mouse(button, _) => InputKind::Mouse(*button),
+                PressSource::Touch(_) => InputKind::Touch,
+            },
+            Event::Activate | Event::Command(..) | Event::ReceivedCharacter(_) => InputKind::Key,
+            _ => InputKind::Other,
+        }
+    }
+}
+
+/// Context of a message from a view item
+///
+/// See [`ChildMsg`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ItemContext {
+    /// Index of the data item
+    ///
+    /// For a list this is the data index; for a matrix it is the row.
+    pub index: usize,
+    /// Column index of the data item, for a matrix
+    pub column: Option<usize>,
+    /// The kind of input which triggered the message
+    pub input: InputKind,
+}
 
 /// A keyed message from a child
 ///
 /// This type is used by some containers to forward messages from children.
+/// Messages concerning a single item include an [`ItemContext`], allowing
+/// e.g. different handling of a middle-click without re-querying data or
+/// event state.
 #[derive(Clone, Debug)]
 pub enum ChildMsg<K, M> {
-    Select(K),
-    Deselect(K),
-    Child(K, M),
+    Select(K, ItemContext),
+    Deselect(K, ItemContext),
+    /// The selection of multiple items changed (e.g. via drag-select)
+    ///
+    /// The new selection may be queried from the sending widget.
+    SelectionChanged,
+    Child(K, M, ItemContext),
+}
+
+impl<K, M> ChildMsg<K, M> {
+    /// Get the item context, if any
+    pub fn context(&self) -> Option<&ItemContext> {
+        match self {
+            ChildMsg::Select(_, ctx) | ChildMsg::Deselect(_, ctx) | ChildMsg::Child(_, _, ctx) => {
+                Some(ctx)
+            }
+            ChildMsg::SelectionChanged => None,
+        }
+    }
 }
 
 impl<K, M> From<VoidMsg> for ChildMsg<K, M> {
@@ -178,7 +238,7 @@ impl<K, M> From<Response<ChildMsg<K, M>>> for Response<M> {
         match Response::try_from(r) {
             Ok(r) => r,
             Err(msg) => match msg {
-                ChildMsg::Child(_, msg) => Response::Msg(msg),
+                ChildMsg::Child(_, msg, _) => Response::Msg(msg),
                 _ => Response::None,
             },
         }
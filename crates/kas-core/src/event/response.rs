@@ -7,6 +7,8 @@
 
 use super::VoidResponse;
 use crate::geom::{Offset, Rect};
+use crate::layout::AlignHints;
+use crate::WidgetId;
 
 /// Response type from [`Handler::handle`].
 ///
@@ -15,6 +17,7 @@ use crate::geom::{Offset, Rect};
 ///
 /// [`Handler::handle`]: super::Handler::handle
 /// [`Handler::Msg`]: super::Handler::Msg
+#[non_exhaustive]
 #[derive(Clone, Debug)]
 #[must_use]
 pub enum Response<M> {
@@ -36,6 +39,25 @@ pub enum Response<M> {
     Pan(Offset),
     /// (Keyboard) focus has changed. This region should be made visible.
     Focus(Rect),
+    /// This region should be made visible, with the given alignment
+    ///
+    /// Unlike [`Response::Focus`], this does not imply a change of focus.
+    /// Scroll regions should scroll such that the rect is aligned within the
+    /// visible area according to the given hints (on axes without a hint, by
+    /// the minimal distance to make the rect visible), then return this
+    /// variant with the rect adjusted for the scroll offset.
+    Reveal(Rect, AlignHints),
+    /// Navigation focus should be given to the widget with this identifier
+    ///
+    /// This may be trapped by a parent; otherwise the event manager sets
+    /// navigation focus (see [`super::Manager::set_nav_focus`]).
+    FocusOn(WidgetId),
+    /// The widget's size requirements have changed
+    ///
+    /// Scroll regions and views may use this to adjust their content size.
+    /// Unless trapped by a parent, the event manager resizes the window (see
+    /// [`crate::TkAction::RESIZE`]).
+    SizeChanged,
     /// Widget wishes to be selected (or have selection status toggled)
     Select,
     /// Notify of update to widget's data
@@ -123,6 +145,9 @@ impl<M> Response<M> {
             Unhandled => Ok(Unhandled),
             Pan(delta) => Ok(Pan(delta)),
             Focus(rect) => Ok(Focus(rect)),
+            Reveal(rect, align) => Ok(Reveal(rect, align)),
+            FocusOn(id) => Ok(FocusOn(id)),
+            SizeChanged => Ok(SizeChanged),
             Select => Ok(Select),
             Update => Ok(Update),
             Msg(m) => Err(m),
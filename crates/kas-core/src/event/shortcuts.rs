@@ -173,6 +173,8 @@ impl Shortcuts {
         let map = self.map.entry(modifiers).or_insert_with(Default::default);
         let shortcuts = [
             (VK::A, Command::Deselect),
+            (VK::I, Command::Debug),
+            (VK::V, Command::PasteAsPlainText),
             (VK::Z, Command::Redo),
             (VK::Tab, Command::TabPrev),
         ];
@@ -215,6 +217,51 @@ impl Shortcuts {
         }
         None
     }
+
+    /// Find a shortcut bound to `cmd`
+    ///
+    /// Where multiple shortcuts are bound, that with the fewest modifiers is
+    /// preferred.
+    pub fn find(&self, cmd: Command) -> Option<(ModifiersState, VirtualKeyCode)> {
+        self.map
+            .iter()
+            .flat_map(|(modifiers, map)| {
+                map.iter()
+                    .filter(move |item| *item.1 == cmd)
+                    .map(move |item| (*modifiers, *item.0))
+            })
+            .min_by_key(|(m, vkey)| (m.bits().count_ones(), m.bits(), *vkey))
+    }
+
+    /// Describe a shortcut for display, e.g. `Ctrl+Shift+Z`
+    pub fn describe(modifiers: ModifiersState, vkey: VirtualKeyCode) -> String {
+        #[cfg(target_os = "macos")]
+        const LOGO: &str = "Cmd";
+        #[cfg(not(target_os = "macos"))]
+        const LOGO: &str = "Super";
+
+        let mut s = String::new();
+        let names = [
+            (ModifiersState::CTRL, "Ctrl"),
+            (ModifiersState::ALT, "Alt"),
+            (ModifiersState::SHIFT, "Shift"),
+            (ModifiersState::LOGO, LOGO),
+        ];
+        for (state, name) in names {
+            if modifiers.contains(state) {
+                s.push_str(name);
+                s.push('+');
+            }
+        }
+
+        let key = format!("{:?}", vkey);
+        // Digit keys are named Key0, Key1, etc.
+        match key.strip_prefix("Key") {
+            Some(digit) => s.push_str(digit),
+            None => s.push_str(&key),
+        }
+        s
+    }
 }
 
 #[cfg(feature = "config")]
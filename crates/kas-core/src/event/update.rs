@@ -5,7 +5,10 @@
 
 //! Event handling: updates
 
+use std::fmt;
+use std::marker::PhantomData;
 use std::num::NonZeroU32;
+use std::rc::Rc;
 use std::sync::atomic::{AtomicU32, Ordering::Relaxed};
 
 /// An update handle
@@ -41,3 +44,163 @@ impl Default for UpdateHandle {
         Self::new()
     }
 }
+
+/// A payload type for [`TypedUpdateHandle`]
+///
+/// Update payloads are transmitted as a `u64` (since updates may be triggered
+/// from other threads via a toolkit proxy); this trait converts to and from
+/// that representation.
+pub trait UpdatePayload: Copy + 'static {
+    /// Convert to the transmitted representation
+    fn to_payload(self) -> u64;
+
+    /// Convert from the transmitted representation
+    ///
+    /// This should not fail for any value produced by [`Self::to_payload`];
+    /// other values may be mapped arbitrarily.
+    fn from_payload(payload: u64) -> Self;
+}
+
+impl UpdatePayload for () {
+    #[inline]
+    fn to_payload(self) -> u64 {
+        0
+    }
+    #[inline]
+    fn from_payload(_: u64) -> Self {}
+}
+
+impl UpdatePayload for bool {
+    #[inline]
+    fn to_payload(self) -> u64 {
+        self as u64
+    }
+    #[inline]
+    fn from_payload(payload: u64) -> Self {
+        payload != 0
+    }
+}
+
+impl UpdatePayload for u64 {
+    #[inline]
+    fn to_payload(self) -> u64 {
+        self
+    }
+    #[inline]
+    fn from_payload(payload: u64) -> Self {
+        payload
+    }
+}
+
+macro_rules! impl_payload_int {
+    ($($t:ty),*) => {
+        $(
+            impl UpdatePayload for $t {
+                #[inline]
+                fn to_payload(self) -> u64 {
+                    self as u64
+                }
+                #[inline]
+                fn from_payload(payload: u64) -> Self {
+                    payload as $t
+                }
+            }
+        )*
+    };
+}
+impl_payload_int!(u8, u16, u32, usize, i8, i16, i32, i64, isize);
+
+/// An update handle with a typed payload
+///
+/// This is a thin wrapper over an [`UpdateHandle`], associating a payload
+/// type. Trigger via [`crate::event::Manager::trigger_typed_update`]; when
+/// handling [`crate::event::Event::HandleUpdate`], decode the payload with
+/// [`TypedUpdateHandle::payload`].
+pub struct TypedUpdateHandle<T> {
+    handle: UpdateHandle,
+    _payload: PhantomData<fn() -> T>,
+}
+
+impl<T: UpdatePayload> TypedUpdateHandle<T> {
+    /// Issue a new handle
+    ///
+    /// See [`UpdateHandle::new`].
+    #[inline]
+    pub fn new() -> Self {
+        TypedUpdateHandle {
+            handle: UpdateHandle::new(),
+            _payload: PhantomData,
+        }
+    }
+
+    /// Get the untyped handle
+    ///
+    /// This may be used to subscribe to updates.
+    #[inline]
+    pub fn handle(self) -> UpdateHandle {
+        self.handle
+    }
+
+    /// Decode the payload of an update on this handle
+    ///
+    /// Returns `None` if `handle` does not match `self`.
+    #[inline]
+    pub fn payload(self, handle: UpdateHandle, payload: u64) -> Option<T> {
+        (handle == self.handle).then(|| T::from_payload(payload))
+    }
+}
+
+impl<T: UpdatePayload> Default for TypedUpdateHandle<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for TypedUpdateHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for TypedUpdateHandle<T> {}
+
+impl<T> fmt::Debug for TypedUpdateHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedUpdateHandle")
+            .field(&self.handle)
+            .finish()
+    }
+}
+
+/// A filter over update payloads
+///
+/// When subscribing to an update handle via
+/// [`crate::event::Manager::update_on_handle_filtered`], the widget is only
+/// sent updates whose payload passes this filter.
+#[derive(Clone)]
+pub struct UpdateFilter(Rc<dyn Fn(u64) -> bool>);
+
+impl UpdateFilter {
+    /// Construct from a filter over the raw payload
+    pub fn new<F: Fn(u64) -> bool + 'static>(f: F) -> Self {
+        UpdateFilter(Rc::new(f))
+    }
+
+    /// Construct from a filter over a typed payload
+    ///
+    /// The type `T` should match that of the [`TypedUpdateHandle`] subscribed to.
+    pub fn typed<T: UpdatePayload, F: Fn(T) -> bool + 'static>(f: F) -> Self {
+        UpdateFilter(Rc::new(move |payload| f(T::from_payload(payload))))
+    }
+
+    /// Test whether `payload` passes the filter
+    #[inline]
+    pub fn matches(&self, payload: u64) -> bool {
+        (self.0)(payload)
+    }
+}
+
+impl fmt::Debug for UpdateFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "UpdateFilter")
+    }
+}
@@ -33,6 +33,16 @@ impl<T: 'static> Future<T> {
         (Future(target), finish)
     }
 
+    /// Construct a pending future
+    ///
+    /// Returns the future and a `finish` closure to set the value when done.
+    pub fn new_pending() -> (Self, Box<dyn FnOnce(T)>) {
+        let target: Rc<RefCell<Option<T>>> = Default::default();
+        let t2 = target.clone();
+        let finish: Box<dyn FnOnce(T)> = Box::new(move |value| *t2.borrow_mut() = Some(value));
+        (Future(target), finish)
+    }
+
     /// Check whether this is finished
     pub fn is_finished(&self) -> bool {
         Rc::strong_count(&self.0) == 1
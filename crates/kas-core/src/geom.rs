@@ -10,7 +10,9 @@ use crate::dir::Directional;
 #[cfg(feature = "winit")]
 use winit::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize, Pixel};
 
+mod transform;
 mod vector;
+pub use transform::Transform;
 pub use vector::{DVec2, Quad, Vec2, Vec3};
 
 macro_rules! impl_common {
@@ -132,6 +134,55 @@ macro_rules! impl_common {
     };
 }
 
+/// Checked and saturating arithmetic (for types supporting addition)
+macro_rules! impl_checked {
+    ($T:ty) => {
+        impl $T {
+            /// Component-wise addition, saturating at the numeric bounds
+            #[inline]
+            pub fn saturating_add(self, rhs: Self) -> Self {
+                Self(self.0.saturating_add(rhs.0), self.1.saturating_add(rhs.1))
+            }
+
+            /// Component-wise subtraction, saturating at the numeric bounds
+            #[inline]
+            pub fn saturating_sub(self, rhs: Self) -> Self {
+                Self(self.0.saturating_sub(rhs.0), self.1.saturating_sub(rhs.1))
+            }
+
+            /// Multiplication by a scalar, saturating at the numeric bounds
+            #[inline]
+            pub fn saturating_mul(self, x: i32) -> Self {
+                Self(self.0.saturating_mul(x), self.1.saturating_mul(x))
+            }
+
+            /// Component-wise multiplication, saturating at the numeric bounds
+            #[inline]
+            pub fn saturating_cwise_mul(self, rhs: Self) -> Self {
+                Self(self.0.saturating_mul(rhs.0), self.1.saturating_mul(rhs.1))
+            }
+
+            /// Component-wise addition, returning `None` on overflow
+            #[inline]
+            pub fn checked_add(self, rhs: Self) -> Option<Self> {
+                Some(Self(self.0.checked_add(rhs.0)?, self.1.checked_add(rhs.1)?))
+            }
+
+            /// Component-wise subtraction, returning `None` on overflow
+            #[inline]
+            pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+                Some(Self(self.0.checked_sub(rhs.0)?, self.1.checked_sub(rhs.1)?))
+            }
+
+            /// Multiplication by a scalar, returning `None` on overflow
+            #[inline]
+            pub fn checked_mul(self, x: i32) -> Option<Self> {
+                Some(Self(self.0.checked_mul(x)?, self.1.checked_mul(x)?))
+            }
+        }
+    };
+}
+
 /// A 2D coordinate, also known as a point
 ///
 /// A coordinate (or point) is an absolute position. One cannot add a point to
@@ -155,6 +206,24 @@ impl Coord {
         Self(n, n)
     }
 
+    /// Add an offset, saturating at the numeric bounds
+    #[inline]
+    pub fn saturating_add_offset(self, offset: Offset) -> Self {
+        Coord(
+            self.0.saturating_add(offset.0),
+            self.1.saturating_add(offset.1),
+        )
+    }
+
+    /// Add an offset, returning `None` on overflow
+    #[inline]
+    pub fn checked_add_offset(self, offset: Offset) -> Option<Self> {
+        Some(Coord(
+            self.0.checked_add(offset.0)?,
+            self.1.checked_add(offset.1)?,
+        ))
+    }
+
     /// Convert from a logical position
     #[cfg(feature = "winit")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "winit")))]
@@ -277,6 +346,7 @@ impl<X: Pixel> From<Coord> for PhysicalPosition<X> {
 pub struct Size(pub i32, pub i32);
 
 impl_common!(Size);
+impl_checked!(Size);
 
 impl Size {
     /// Construct
@@ -477,6 +547,7 @@ impl From<Size> for winit::dpi::Size {
 pub struct Offset(pub i32, pub i32);
 
 impl_common!(Offset);
+impl_checked!(Offset);
 
 impl Offset {
     /// Construct
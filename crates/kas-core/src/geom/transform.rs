@@ -0,0 +1,104 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Transforms
+
+use super::{Coord, Quad, Rect, Vec2};
+
+/// A 2D transform: uniform scaling followed by translation
+///
+/// This maps a point `p` to `p * scale + translate`. It is used to draw a
+/// subtree of widgets at a different scale; see
+/// [`DrawHandleExt::with_transform`](crate::draw::DrawHandleExt::with_transform).
+///
+/// Rotation is not supported since drawing primitives are axis-aligned.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform {
+    /// Scale factor (must be positive)
+    pub scale: f32,
+    /// Translation (applied after scaling)
+    pub translate: Vec2,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    /// The identity transform
+    pub const IDENTITY: Transform = Transform::new(1.0, Vec2::ZERO);
+
+    /// Construct
+    #[inline]
+    pub const fn new(scale: f32, translate: Vec2) -> Self {
+        Transform { scale, translate }
+    }
+
+    /// Construct a translation
+    #[inline]
+    pub const fn translation(translate: Vec2) -> Self {
+        Transform::new(1.0, translate)
+    }
+
+    /// Construct a scaling about a fixed point `centre`
+    #[inline]
+    pub fn scale_about(scale: f32, centre: Vec2) -> Self {
+        Transform::new(scale, centre - centre * scale)
+    }
+
+    /// True if this is the identity transform
+    #[inline]
+    pub fn is_identity(&self) -> bool {
+        *self == Transform::IDENTITY
+    }
+
+    /// Get the inverse transform
+    #[inline]
+    pub fn inverse(self) -> Self {
+        let scale = 1.0 / self.scale;
+        Transform::new(scale, self.translate * -scale)
+    }
+
+    /// Compose with another transform
+    ///
+    /// The result maps `p` to `next.map(self.map(p))`.
+    #[inline]
+    pub fn then(self, next: Transform) -> Self {
+        let scale = self.scale * next.scale;
+        Transform::new(scale, next.map(self.translate))
+    }
+
+    /// Map a point
+    #[inline]
+    pub fn map(self, p: Vec2) -> Vec2 {
+        p * self.scale + self.translate
+    }
+
+    /// Map a coordinate (rounding to the nearest pixel)
+    #[inline]
+    pub fn map_coord(self, coord: Coord) -> Coord {
+        self.map(coord.into()).into()
+    }
+
+    /// Map a quad
+    #[inline]
+    pub fn map_quad(self, quad: Quad) -> Quad {
+        Quad::with_coords(self.map(quad.a), self.map(quad.b))
+    }
+
+    /// Map a rect
+    ///
+    /// The result is the smallest (pixel-aligned) rect enclosing the mapped
+    /// `rect`.
+    pub fn map_rect(self, rect: Rect) -> Rect {
+        let quad = self.map_quad(rect.into());
+        let a = quad.a.floor();
+        let b = quad.b.ceil();
+        Rect::new(a.into(), (b - a).into())
+    }
+}
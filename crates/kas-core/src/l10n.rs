@@ -0,0 +1,175 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Localization
+//!
+//! A [`Localizer`] resolves message keys to text in the current locale. It is
+//! configured on the shell's `Toolkit` and accessed via
+//! [`Manager::translate`]. Widgets such as `Label` and `TextButton` may be
+//! constructed from a message key (e.g. `Label::msg("file-open")`); these
+//! resolve their text when configured.
+//!
+//! The locale may be switched at run-time via [`Manager::set_locale`], which
+//! reconfigures all windows, thus re-resolving keyed text and resizing.
+//!
+//! [`Catalog`] is a simple [`Localizer`] reading (a subset of) the
+//! [Fluent](https://projectfluent.org/) syntax.
+//!
+//! [`Manager::translate`]: crate::event::Manager::translate
+//! [`Manager::set_locale`]: crate::event::Manager::set_locale
+
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// A message resolver
+pub trait Localizer {
+    /// Get the current locale (e.g. `"en-GB"`)
+    fn locale(&self) -> &str;
+
+    /// Set the current locale
+    ///
+    /// Returns true if the locale was changed.
+    fn set_locale(&mut self, locale: &str) -> bool;
+
+    /// Resolve message `key` in the current locale
+    ///
+    /// Returns `None` if no translation is found (after locale fallback).
+    fn translate(&self, key: &str) -> Option<String>;
+}
+
+/// Message catalog parse error
+#[derive(Error, Debug)]
+#[error("line {line}: {msg}")]
+pub struct ParseError {
+    /// Line number (one-based)
+    pub line: usize,
+    msg: &'static str,
+}
+
+/// A message catalog over multiple locales
+///
+/// Messages are added per locale from Fluent (`.ftl`) sources. Only simple
+/// messages are supported:
+/// ```text
+/// # Comment
+/// file-open = Open…
+/// about-text = A message spanning
+///     multiple lines
+/// ```
+/// (Attributes, terms, placeables and selectors are not supported.)
+///
+/// Lookup falls back through less specific locales and finally to the
+/// fallback locale: for locale `de-AT` and fallback `en`, the locales `de-AT`,
+/// `de` and `en` are searched in order.
+#[derive(Clone, Debug)]
+pub struct Catalog {
+    locale: String,
+    fallback: String,
+    messages: HashMap<String, HashMap<String, String>>,
+}
+
+impl Catalog {
+    /// Construct an empty catalog
+    ///
+    /// The initial locale is `fallback`.
+    pub fn new(fallback: &str) -> Self {
+        Catalog {
+            locale: fallback.to_string(),
+            fallback: fallback.to_string(),
+            messages: HashMap::new(),
+        }
+    }
+
+    /// Add messages for `locale` from Fluent `source`
+    ///
+    /// Existing messages with the same key are replaced.
+    pub fn add_fluent(&mut self, locale: &str, source: &str) -> Result<(), ParseError> {
+        let messages = self.messages.entry(locale.to_string()).or_default();
+        let mut current: Option<(String, String)> = None;
+        for (i, line) in source.lines().enumerate() {
+            let error = |msg| ParseError { line: i + 1, msg };
+            if line.starts_with(|c: char| c == ' ' || c == '\t') && !line.trim().is_empty() {
+                match current.as_mut() {
+                    Some((_, value)) => {
+                        if !value.is_empty() {
+                            value.push('\n');
+                        }
+                        value.push_str(line.trim());
+                    }
+                    None => return Err(error("unexpected indented line")),
+                }
+                continue;
+            }
+
+            if let Some((key, value)) = current.take() {
+                messages.insert(key, value);
+            }
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| error("expected `=`"))?;
+            let key = key.trim();
+            let valid = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+            if !key.starts_with(|c: char| c.is_ascii_alphabetic()) || !key.chars().all(valid) {
+                return Err(error("invalid message identifier"));
+            }
+            current = Some((key.to_string(), value.trim().to_string()));
+        }
+        if let Some((key, value)) = current {
+            messages.insert(key, value);
+        }
+        Ok(())
+    }
+
+    /// Add messages for `locale` from Fluent `source` (inline)
+    pub fn with_fluent(mut self, locale: &str, source: &str) -> Result<Self, ParseError> {
+        self.add_fluent(locale, source)?;
+        Ok(self)
+    }
+
+    /// Set the current locale (inline)
+    pub fn with_locale(mut self, locale: &str) -> Self {
+        self.set_locale(locale);
+        self
+    }
+
+    /// Iterate over locales with messages
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.messages.keys().map(|s| s.as_str())
+    }
+
+    fn lookup(&self, locale: &str, key: &str) -> Option<&String> {
+        self.messages.get(locale).and_then(|m| m.get(key))
+    }
+}
+
+impl Localizer for Catalog {
+    fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    fn set_locale(&mut self, locale: &str) -> bool {
+        if self.locale == locale {
+            return false;
+        }
+        self.locale = locale.to_string();
+        true
+    }
+
+    fn translate(&self, key: &str) -> Option<String> {
+        let mut locale = self.locale.as_str();
+        loop {
+            if let Some(value) = self.lookup(locale, key) {
+                return Some(value.clone());
+            }
+            match locale.rfind('-') {
+                Some(index) => locale = &locale[..index],
+                None => break,
+            }
+        }
+        self.lookup(&self.fallback, key).cloned()
+    }
+}
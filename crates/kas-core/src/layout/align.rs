@@ -29,10 +29,16 @@ pub use crate::text::Align;
 ///     .aligned_rect(pref_size, rect);
 /// // self.core.rect = rect;
 /// ```
+///
+/// Additionally, a parent may request that a child's first text baseline is
+/// placed at a given offset from the top of its `rect`; see
+/// [`AlignHints::baseline_rect`].
 #[derive(Copy, Clone, Debug, Default)]
 pub struct AlignHints {
     pub horiz: Option<Align>,
     pub vert: Option<Align>,
+    /// Requested baseline position, relative to the top of the assigned rect
+    pub baseline: Option<i32>,
 }
 
 impl AlignHints {
@@ -47,7 +53,18 @@ impl AlignHints {
 
     /// Construct with optional horiz. and vert. alignment
     pub const fn new(horiz: Option<Align>, vert: Option<Align>) -> Self {
-        Self { horiz, vert }
+        Self {
+            horiz,
+            vert,
+            baseline: None,
+        }
+    }
+
+    /// Set the requested baseline position
+    #[inline]
+    pub fn with_baseline(mut self, baseline: Option<i32>) -> Self {
+        self.baseline = baseline;
+        self
     }
 
     /// Combine two hints (first takes priority)
@@ -55,14 +72,51 @@ impl AlignHints {
         Self {
             horiz: self.horiz.or(rhs.horiz),
             vert: self.vert.or(rhs.vert),
+            baseline: self.baseline.or(rhs.baseline),
         }
     }
 
+    /// Mirror horizontal alignment, for right-to-left layout
+    ///
+    /// This swaps [`Align::TL`] and [`Align::BR`]; [`Align::Default`] (usually
+    /// equivalent to `TL`) becomes `BR`.
+    pub fn mirrored(mut self) -> Self {
+        self.horiz = self.horiz.map(|align| match align {
+            Align::Default | Align::TL => Align::BR,
+            Align::BR => Align::TL,
+            other => other,
+        });
+        self
+    }
+
     /// Unwrap type's alignments or substitute parameters
     pub fn unwrap_or(self, horiz: Align, vert: Align) -> (Align, Align) {
         (self.horiz.unwrap_or(horiz), self.vert.unwrap_or(vert))
     }
 
+    /// Apply baseline alignment
+    ///
+    /// Given the widget's own `baseline` (distance from the top of its content
+    /// to its first baseline; see [`crate::layout::SizeRules::baseline`]), if
+    /// a baseline position was requested which is below the widget's own, the
+    /// top of `rect` is moved down to match and vertical alignment is set to
+    /// [`Align::TL`]. Otherwise `rect` and `self` are returned unchanged.
+    pub fn baseline_rect(self, mut rect: Rect, baseline: Option<i32>) -> (Rect, Self) {
+        match (self.baseline, baseline) {
+            (Some(target), Some(own)) if target >= own => {
+                let offset = (target - own).min(rect.size.1);
+                rect.pos.1 += offset;
+                rect.size.1 -= offset;
+                let hints = AlignHints {
+                    vert: Some(Align::TL),
+                    ..self
+                };
+                (rect, hints)
+            }
+            _ => (rect, self),
+        }
+    }
+
     /// Complete via default alignments
     pub fn complete(&self, horiz: Align, vert: Align) -> CompleteAlignment {
         CompleteAlignment {
@@ -0,0 +1,187 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Layout animation
+
+use super::AlignHints;
+use crate::cast::{Conv, ConvFloat};
+use crate::event::{Manager, ScrollEasing};
+use crate::geom::{Coord, Rect, Size};
+use crate::{TkAction, WidgetConfig, WidgetId};
+use instant::Instant;
+use std::time::Duration;
+
+/// Animation of child geometry on layout change
+///
+/// This component allows a container to animate its children from their old
+/// to their new rects when a layout change (for example, removal of a child)
+/// moves or resizes them, instead of snapping to the new layout. The
+/// animation is implemented by calling `set_rect` on each moving child with
+/// interpolated rects over the next frames, thus drawing and event handling
+/// remain consistent throughout.
+///
+/// Usage by a container with children `children: &mut [W]`:
+///
+/// -   Call [`Self::note_change`] when adding, removing or reordering children
+/// -   In `set_rect`, call [`Self::begin`] before setting children's rects
+///     and pass the result to [`Self::start`] afterwards
+/// -   On [`Event::TimerUpdate`](crate::event::Event::TimerUpdate) with
+///     payload [`Self::TIMER`], call [`Self::animate`]; when this returns
+///     true, set the final layout again (this restores exact alignment)
+///
+/// Only changes following [`Self::note_change`] are animated; other layout
+/// changes (for example, due to resizing the window) are applied
+/// immediately. Children without a previous rect (new children) are not
+/// animated. The animation is disabled by default and its duration is
+/// [`Config::layout_anim_duration`](crate::event::Config::layout_anim_duration),
+/// which respects the reduce-motion setting.
+#[derive(Clone, Debug, Default)]
+pub struct RectAnimation {
+    enabled: bool,
+    changed: bool,
+    align: AlignHints,
+    start: Option<Instant>,
+    duration: Duration,
+    items: Vec<(usize, Rect, Rect)>,
+}
+
+impl RectAnimation {
+    /// Timer payload used for layout animation
+    pub const TIMER: u64 = 1 << 60;
+
+    /// Construct
+    #[inline]
+    pub fn new(enabled: bool) -> Self {
+        RectAnimation {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    /// Get whether animation is enabled
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Set whether animation is enabled
+    ///
+    /// Disabling does not stop an animation in progress.
+    #[inline]
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// True if an animation is in progress
+    #[inline]
+    pub fn is_animating(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// Note a change to the container's children
+    ///
+    /// The next layout change will be animated (if enabled).
+    #[inline]
+    pub fn note_change(&mut self) {
+        self.changed = self.enabled;
+    }
+
+    /// Prepare for a new layout
+    ///
+    /// Call this with the children's current rects before setting the new
+    /// layout. Returns these rects if the new layout should be animated.
+    /// Any animation in progress is stopped.
+    pub fn begin<I: IntoIterator<Item = Rect>>(&mut self, rects: I) -> Option<Vec<Rect>> {
+        self.start = None;
+        self.items.clear();
+        match std::mem::replace(&mut self.changed, false) {
+            true => Some(rects.into_iter().collect()),
+            false => None,
+        }
+    }
+
+    /// Start an animation from `old` rects (see [`Self::begin`])
+    ///
+    /// Call this after setting the new layout. Children which moved are
+    /// returned to their old rects and an animation is scheduled for widget
+    /// `w_id` (the container).
+    pub fn start<W: WidgetConfig>(
+        &mut self,
+        mgr: &mut Manager,
+        w_id: WidgetId,
+        children: &mut [W],
+        old: Option<Vec<Rect>>,
+        align: AlignHints,
+    ) {
+        let old = match old {
+            Some(old) => old,
+            None => return,
+        };
+        self.duration = mgr.config().layout_anim_duration();
+        if self.duration == Duration::ZERO {
+            return;
+        }
+
+        for (i, (child, from)) in children.iter().zip(old.into_iter()).enumerate() {
+            let to = child.rect();
+            if from.size != Size::ZERO && from != to {
+                self.items.push((i, from, to));
+            }
+        }
+        if self.items.is_empty() {
+            return;
+        }
+
+        self.align = align;
+        self.start = Some(Instant::now());
+        for (i, from, _) in self.items.iter() {
+            children[*i].set_rect(mgr, *from, align);
+        }
+        mgr.update_on_frame(w_id, Self::TIMER);
+    }
+
+    /// Advance the animation for the next frame
+    ///
+    /// Returns true when the animation completes, in which case the caller
+    /// should set the final layout.
+    pub fn animate<W: WidgetConfig>(
+        &mut self,
+        mgr: &mut Manager,
+        w_id: WidgetId,
+        children: &mut [W],
+    ) -> bool {
+        let start = match self.start {
+            Some(start) => start,
+            None => return false,
+        };
+        let elapsed = mgr.next_frame_time().saturating_duration_since(start);
+        let t = elapsed.as_secs_f64() / self.duration.as_secs_f64();
+        if t >= 1.0 {
+            self.start = None;
+            self.items.clear();
+            // Hover targets may have changed
+            mgr.send_action(TkAction::REGION_MOVED);
+            return true;
+        }
+
+        let f = ScrollEasing::EaseOutCubic.apply(t);
+        for (i, from, to) in self.items.iter() {
+            if let Some(child) = children.get_mut(*i) {
+                child.set_rect(mgr, lerp(*from, *to, f), self.align);
+            }
+        }
+        mgr.redraw(w_id);
+        mgr.update_on_frame(w_id, Self::TIMER);
+        false
+    }
+}
+
+/// Interpolate between rects `a` and `b` at fraction `f`
+fn lerp(a: Rect, b: Rect, f: f64) -> Rect {
+    let lerp = |a: i32, b: i32| a + i32::conv_nearest(f64::conv(b - a) * f);
+    let pos = Coord(lerp(a.pos.0, b.pos.0), lerp(a.pos.1, b.pos.1));
+    let size = Size(lerp(a.size.0, b.size.0), lerp(a.size.1, b.size.1));
+    Rect { pos, size }
+}
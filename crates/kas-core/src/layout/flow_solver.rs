@@ -0,0 +1,235 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Flow (wrapping row) solver
+
+use std::any::Any;
+use std::ops::Range;
+
+use super::{Align, AlignHints, AxisInfo, RulesSetter, RulesSolver, SizeRules, Storage};
+use crate::geom::{Coord, Rect, Size};
+
+/// Storage for [`FlowSolver`] and [`FlowSetter`]
+#[derive(Clone, Debug, Default)]
+pub struct FlowStorage {
+    horiz: Vec<SizeRules>,
+    vert: Vec<SizeRules>,
+    widths: Vec<i32>,
+    // Index of the first child on each line
+    lines: Vec<usize>,
+}
+
+impl Storage for FlowStorage {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl FlowStorage {
+    fn set_len(&mut self, len: usize) {
+        self.horiz.resize(len, SizeRules::EMPTY);
+        self.vert.resize(len, SizeRules::EMPTY);
+        self.widths.resize(len, 0);
+    }
+
+    /// Assign children to lines given the available `width`
+    ///
+    /// Each child is given its ideal width (limited to `width` but not below
+    /// its minimum). Lines are broken greedily.
+    fn break_lines(&mut self, width: i32) {
+        self.lines.clear();
+        let mut used = 0;
+        for (i, rules) in self.horiz.iter().enumerate() {
+            let w = rules.ideal_size().min(width).max(rules.min_size());
+            self.widths[i] = w;
+            if i == 0 {
+                self.lines.push(0);
+                used = w;
+                continue;
+            }
+
+            let margin = i32::from(self.horiz[i - 1].margins().1.max(rules.margins().0));
+            if used + margin + w > width {
+                self.lines.push(i);
+                used = w;
+            } else {
+                used += margin + w;
+            }
+        }
+    }
+
+    fn line_range(&self, line: usize) -> Range<usize> {
+        let end = self.lines.get(line + 1).cloned();
+        self.lines[line]..end.unwrap_or(self.horiz.len())
+    }
+
+    /// Vertical rules of each line
+    fn line_rules(&self) -> Vec<SizeRules> {
+        (0..self.lines.len())
+            .map(|line| {
+                let range = self.line_range(line);
+                self.vert[range]
+                    .iter()
+                    .fold(SizeRules::EMPTY, |acc, rules| acc.max(*rules))
+            })
+            .collect()
+    }
+
+    /// Number of lines, as of the last layout
+    pub fn num_lines(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+/// A [`RulesSolver`] for flow layouts
+///
+/// Children are placed left-to-right, wrapping to a new line when the width
+/// is exhausted. The horizontal axis must be solved first: the vertical rules
+/// depend on the line breaks implied by the width given by
+/// [`AxisInfo::other`] (or, if this is not fixed, on a single line).
+///
+/// Horizontal rules are: the largest minimum width of any child and the sum
+/// of ideal widths (i.e. all children on a single line).
+pub struct FlowSolver {
+    axis: AxisInfo,
+}
+
+impl FlowSolver {
+    /// Construct.
+    ///
+    /// Argument order is consistent with other [`RulesSolver`]s.
+    ///
+    /// - `axis`: `AxisInfo` instance passed into `size_rules`
+    /// - `len`: number of children
+    /// - `storage`: reference to persistent storage
+    pub fn new(axis: AxisInfo, len: usize, storage: &mut FlowStorage) -> Self {
+        storage.set_len(len);
+        if axis.is_vertical() {
+            storage.break_lines(axis.other().unwrap_or(i32::MAX));
+        }
+        FlowSolver { axis }
+    }
+}
+
+impl RulesSolver for FlowSolver {
+    type Storage = FlowStorage;
+    type ChildInfo = usize;
+
+    fn for_child<CR: FnOnce(AxisInfo) -> SizeRules>(
+        &mut self,
+        storage: &mut Self::Storage,
+        index: Self::ChildInfo,
+        child_rules: CR,
+    ) {
+        if self.axis.is_horizontal() {
+            storage.horiz[index] = child_rules(AxisInfo::new(false, None));
+        } else {
+            let width = storage.widths[index];
+            storage.vert[index] = child_rules(AxisInfo::new(true, Some(width)));
+        }
+    }
+
+    fn finish(self, storage: &mut Self::Storage) -> SizeRules {
+        if self.axis.is_horizontal() {
+            let max = storage
+                .horiz
+                .iter()
+                .fold(SizeRules::EMPTY, |acc, rules| acc.max(*rules));
+            let sum = SizeRules::sum(&storage.horiz);
+            SizeRules::new(
+                max.min_size(),
+                sum.ideal_size(),
+                max.margins(),
+                sum.stretch(),
+            )
+        } else {
+            SizeRules::sum(&storage.line_rules())
+        }
+    }
+}
+
+/// A [`RulesSetter`] for flow layouts
+///
+/// Lines are aligned according to the horizontal [`AlignHints`]:
+/// [`Align::Stretch`] distributes excess width over children per their
+/// stretch priorities; other values position each line within the width.
+pub struct FlowSetter {
+    rects: Vec<Rect>,
+}
+
+impl FlowSetter {
+    /// Construct
+    ///
+    /// Argument order is consistent with other [`RulesSetter`]s.
+    ///
+    /// -   `rect`: the [`Rect`] within which to position children
+    /// -   `len`: number of children
+    /// -   `align`: alignment hints
+    /// -   `storage`: access to the solver's storage
+    pub fn new(rect: Rect, len: usize, align: AlignHints, storage: &mut FlowStorage) -> Self {
+        storage.set_len(len);
+        storage.break_lines(rect.size.0);
+
+        let line_rules = storage.line_rules();
+        let mut heights = vec![0; line_rules.len()];
+        SizeRules::solve_seq(&mut heights, &line_rules, rect.size.1);
+
+        let mut rects = vec![Rect::default(); len];
+        let mut y = rect.pos.1;
+        for line in 0..line_rules.len() {
+            if line > 0 {
+                let margin = line_rules[line - 1]
+                    .margins()
+                    .1
+                    .max(line_rules[line].margins().0);
+                y += heights[line - 1] + i32::from(margin);
+            }
+
+            let range = storage.line_range(line);
+            let horiz = &storage.horiz[range.clone()];
+            let mut widths = storage.widths[range.clone()].to_vec();
+            let margin = |i: usize| i32::from(horiz[i - 1].margins().1.max(horiz[i].margins().0));
+
+            let mut x = rect.pos.0;
+            if align.horiz == Some(Align::Stretch) {
+                SizeRules::solve_seq(&mut widths, horiz, rect.size.0);
+            } else {
+                let used = widths.iter().sum::<i32>() + (1..widths.len()).map(margin).sum::<i32>();
+                let extra = (rect.size.0 - used).max(0);
+                x += match align.horiz {
+                    Some(Align::Center) => extra / 2,
+                    Some(Align::BR) => extra,
+                    _ => 0,
+                };
+            }
+
+            for (i, index) in range.enumerate() {
+                if i > 0 {
+                    x += widths[i - 1] + margin(i);
+                }
+                rects[index] = Rect::new(Coord(x, y), Size(widths[i], heights[line]));
+            }
+        }
+
+        FlowSetter { rects }
+    }
+}
+
+impl RulesSetter for FlowSetter {
+    type Storage = FlowStorage;
+    type ChildInfo = usize;
+
+    fn child_rect(&mut self, _: &mut Self::Storage, index: Self::ChildInfo) -> Rect {
+        self.rects[index]
+    }
+
+    /// Calculates the maximal rect of a given child
+    ///
+    /// Since children do not share space on a line other than through
+    /// stretching, this is the same as [`RulesSetter::child_rect`].
+    fn maximal_rect_of(&mut self, _: &mut Self::Storage, index: Self::ChildInfo) -> Rect {
+        self.rects[index]
+    }
+}
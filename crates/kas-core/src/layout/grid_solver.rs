@@ -343,6 +343,26 @@ impl<RT: RowTemp, CT: RowTemp, S: GridStorage> GridSetter<RT, CT, S> {
             _s: Default::default(),
         }
     }
+
+    /// Axis on which to re-solve children's rules before construction, if any
+    ///
+    /// Column widths may [depend on row heights](SizeRules::depends_on_other).
+    /// Since these are only known once `rect` is assigned, the caller should
+    /// in this case solve the grid's rules again for the returned axis (using
+    /// a [`GridSolver`], which passes the height of each child's rows to the
+    /// child) before calling [`GridSetter::new`]. Otherwise, `None` is
+    /// returned.
+    pub fn resolve_axis(rect: Rect, storage: &mut S) -> Option<AxisInfo> {
+        let (_, _, total) = storage.widths_rules_total();
+        if total.depends_on_other() {
+            // Row heights are solved for no more than their maximum size
+            let (_, _, heights) = storage.heights_rules_total();
+            let height = rect.size.1.min(heights.max_size());
+            Some(AxisInfo::new(false, Some(height)))
+        } else {
+            None
+        }
+    }
 }
 
 impl<RT: RowTemp, CT: RowTemp, S: GridStorage> RulesSetter for GridSetter<RT, CT, S> {
@@ -31,14 +31,22 @@
 //! -   [`GridSolver`] and [`GridSetter`] set out children assigned to grid
 //!     cells with optional cell-spans. This is the most powerful and flexible
 //!     layout engine.
+//! -   [`FlowSolver`] and [`FlowSetter`] set out children left-to-right,
+//!     wrapping to new lines as required (using [`FlowStorage`]).
 //!
 //! [`RowPositionSolver`] may be used with widgets set out by [`RowSetter`]
 //! to quickly locate children from a `coord` or `rect`.
+//!
+//! [`RectAnimation`] allows containers to animate children to new positions
+//! on layout change.
 
 mod align;
+mod animate;
+mod flow_solver;
 mod grid_solver;
 mod row_solver;
 mod single_solver;
+mod size_cache;
 mod size_rules;
 mod size_types;
 mod sizer;
@@ -48,19 +56,24 @@ mod visitor;
 use crate::dir::{Direction, Directional};
 
 pub use align::{Align, AlignHints, CompleteAlignment};
+pub use animate::RectAnimation;
+pub use flow_solver::{FlowSetter, FlowSolver, FlowStorage};
 pub use grid_solver::{DefaultWithLen, GridChildInfo, GridDimensions, GridSetter, GridSolver};
 pub use row_solver::{RowPositionSolver, RowSetter, RowSolver};
 pub use single_solver::{SingleSetter, SingleSolver};
+pub use size_cache::SizeRulesCache;
+pub(crate) use size_cache::{clear_size_rules_cache, subtree_generation};
 pub use size_rules::SizeRules;
 pub use size_types::{
-    AspectScaling, FrameRules, MarginSelector, Margins, SpriteDisplay, SpriteScaling, Stretch,
+    AspectRatio, AspectScaling, FrameRules, MarginSelector, Margins, SpriteDisplay, SpriteScaling,
+    Stretch,
 };
 pub use sizer::{solve_size_rules, RulesSetter, RulesSolver, SolveCache};
 pub use storage::{
     DynGridStorage, DynRowStorage, FixedGridStorage, FixedRowStorage, GridStorage, RowStorage,
     RowTemp, Storage,
 };
-pub use visitor::{FrameStorage, Layout, StorageChain, TextStorage};
+pub use visitor::{FrameStorage, Layout, RegionStorage, StorageChain, TextStorage};
 
 /// Information on which axis is being resized
 ///
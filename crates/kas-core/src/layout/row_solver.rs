@@ -25,6 +25,8 @@ pub struct RowSolver<S: RowStorage> {
     axis_is_vertical: bool,
     axis_is_reversed: bool,
     rules: Option<SizeRules>,
+    // Baseline alignment: Some((above, below_min, below_ideal)) when enabled
+    baseline: Option<(i32, i32, i32)>,
     _s: PhantomData<S>,
 }
 
@@ -46,11 +48,15 @@ impl<S: RowStorage> RowSolver<S> {
             SizeRules::solve_seq_total(widths, rules, total, axis.other_axis);
         }
 
+        let baseline_align = axis.is_vertical() && dir.is_horizontal() && storage.baseline_align();
+        let baseline = baseline_align.then(|| (0, 0, 0));
+
         RowSolver {
             axis,
             axis_is_vertical,
             axis_is_reversed: dir.is_reversed(),
             rules: None,
+            baseline,
             _s: Default::default(),
         }
     }
@@ -82,6 +88,11 @@ impl<S: RowStorage> RulesSolver for RowSolver<S> {
                 self.rules = Some(child_rules);
             }
         } else {
+            if let (Some(b), Some(child_b)) = (self.baseline.as_mut(), child_rules.baseline()) {
+                b.0 = b.0.max(child_b);
+                b.1 = b.1.max(child_rules.min_size() - child_b);
+                b.2 = b.2.max(child_rules.ideal_size() - child_b);
+            }
             self.rules = Some(
                 self.rules
                     .map(|rules| rules.max(child_rules))
@@ -91,11 +102,28 @@ impl<S: RowStorage> RulesSolver for RowSolver<S> {
     }
 
     fn finish(self, storage: &mut Self::Storage) -> SizeRules {
-        let rules = self.rules.unwrap_or(SizeRules::EMPTY);
+        let mut rules = self.rules.unwrap_or(SizeRules::EMPTY);
         if !self.axis_is_vertical {
             storage.set_total(rules);
         }
 
+        if let Some((above, below_min, below_ideal)) = self.baseline {
+            // Children are offset such that their baselines match
+            if rules.baseline().is_some() {
+                let aligned = SizeRules::new(
+                    above + below_min,
+                    above + below_ideal,
+                    rules.margins(),
+                    rules.stretch(),
+                );
+                rules = rules.max(aligned);
+                rules.set_baseline(Some(above));
+                storage.set_baseline(Some(above));
+            } else {
+                storage.set_baseline(None);
+            }
+        }
+
         rules
     }
 }
@@ -111,6 +139,7 @@ pub struct RowSetter<D, T: RowTemp, S: RowStorage> {
     rect: Rect,
     offsets: T,
     direction: D,
+    baseline: Option<i32>,
     _s: PhantomData<S>,
 }
 
@@ -140,7 +169,7 @@ impl<D: Directional, T: RowTemp, S: RowStorage> RowSetter<D, T, S> {
             let max_size = total.max_size();
             let align = if is_horiz { align.horiz } else { align.vert };
             let align = align.unwrap_or(Align::Default);
-            if rect.size.0 > max_size {
+            if width > max_size {
                 let extra = width - max_size;
                 width = max_size;
                 let offset = match align {
@@ -157,17 +186,42 @@ impl<D: Directional, T: RowTemp, S: RowStorage> RowSetter<D, T, S> {
             SizeRules::solve_seq_total(widths, rules, total, width);
         }
 
+        let mut baseline = None;
+        if direction.is_horizontal() && storage.baseline_align() {
+            baseline = storage
+                .baseline()
+                .map(|b| b.max(align.baseline.unwrap_or(0)));
+        }
+
         let _s = Default::default();
         let mut row = RowSetter {
             rect,
             offsets,
             direction,
+            baseline,
             _s,
         };
         row.update_offsets(storage);
         row
     }
 
+    /// Axis on which to re-solve children's rules before construction, if any
+    ///
+    /// Children of a horizontal row may have rules which
+    /// [depend on their height](SizeRules::depends_on_other). Since the height
+    /// is only known once `rect` is assigned, the caller should in this case
+    /// solve the row's rules again for the returned axis (using a
+    /// [`RowSolver`], which passes the height of `rect` to each child) before
+    /// calling [`RowSetter::new`]. Otherwise, `None` is returned.
+    pub fn resolve_axis(rect: Rect, direction: D, storage: &mut S) -> Option<AxisInfo> {
+        let (_, _, total) = storage.widths_rules_total();
+        if direction.is_horizontal() && total.depends_on_other() {
+            Some(AxisInfo::new(false, Some(rect.size.1)))
+        } else {
+            None
+        }
+    }
+
     /// Construct without solving
     ///
     /// In this case, it is assumed that the storage was already solved by a
@@ -186,10 +240,24 @@ impl<D: Directional, T: RowTemp, S: RowStorage> RowSetter<D, T, S> {
             rect,
             offsets,
             direction,
+            baseline: None,
             _s,
         }
     }
 
+    /// Alignment hints for children
+    ///
+    /// When baseline alignment is enabled (see
+    /// [`RowStorage::set_baseline_align`]), this requests that children place
+    /// their first baseline on the row's shared baseline. Otherwise `align` is
+    /// returned unchanged.
+    pub fn child_hints(&self, align: AlignHints) -> AlignHints {
+        match self.baseline {
+            Some(baseline) => align.with_baseline(Some(baseline)),
+            None => align,
+        }
+    }
+
     pub fn update_offsets(&mut self, storage: &mut S) {
         let offsets = self.offsets.as_mut();
         let len = offsets.len();
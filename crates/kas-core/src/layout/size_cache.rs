@@ -0,0 +1,115 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Size rules cache
+
+use super::{AxisInfo, SizeRules};
+use crate::{WidgetChildren, WidgetConfig};
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    other: Option<i32>,
+    generation: u64,
+    rules: SizeRules,
+}
+
+/// Cached size rules of a widget
+///
+/// This caches the result of the last [`crate::Layout::size_rules`] call on
+/// each axis, keyed by the size of the other axis (if fixed) and by a
+/// *generation* summarising the content of the widget and its descendants
+/// (see [`crate::CoreData::bump_generation`]). Entries are also cleared when
+/// the window's size rules are refreshed (e.g. on [`crate::TkAction::RESIZE`]
+/// or a theme change).
+///
+/// Skipping a call to `size_rules` is only valid where the widget's state
+/// (including layout storage) is unchanged since the cached call. Since the
+/// last call per axis is cached, this holds for widgets whose size
+/// requirements depend only on their content; the default implementation of
+/// [`crate::Layout::size_rules`] uses this cache (via [`crate::CoreData`]).
+#[derive(Clone, Debug, Default)]
+pub struct SizeRulesCache {
+    entries: [Option<Entry>; 2],
+    last: [Option<SizeRules>; 2],
+}
+
+impl SizeRulesCache {
+    /// Get cached rules for `axis`, if valid for `generation`
+    pub fn get(&self, axis: AxisInfo, generation: u64) -> Option<SizeRules> {
+        let entry = self.entries[usize::from(axis.is_vertical())];
+        entry
+            .filter(|e| e.other == axis.other() && e.generation == generation)
+            .map(|e| e.rules)
+    }
+
+    /// Cache `rules` for `axis` and `generation`
+    pub fn insert(&mut self, axis: AxisInfo, generation: u64, rules: SizeRules) {
+        self.entries[usize::from(axis.is_vertical())] = Some(Entry {
+            other: axis.other(),
+            generation,
+            rules,
+        });
+        self.record(axis, rules);
+    }
+
+    /// Record `rules` as the last solved for `axis`
+    ///
+    /// This does not affect cache validity.
+    pub fn record(&mut self, axis: AxisInfo, rules: SizeRules) {
+        self.last[usize::from(axis.is_vertical())] = Some(rules);
+    }
+
+    /// Rules last solved on each axis, if recorded
+    ///
+    /// Rules are recorded by [`Self::insert`] and [`Self::record`] (the latter
+    /// is called for children of a [`crate::layout::Layout`]), and are kept
+    /// when the cache is cleared. This allows inspection of layout without
+    /// calling [`crate::Layout::size_rules`].
+    pub fn last_rules(&self) -> (Option<SizeRules>, Option<SizeRules>) {
+        (self.last[0], self.last[1])
+    }
+
+    /// Minimum size of the last cached rules, on each axis
+    ///
+    /// This ignores validity of the cache, thus reports the rules last used
+    /// to solve layout. Where no rules are cached, zero is reported.
+    pub fn min_size(&self) -> (i32, i32) {
+        let min = |i: usize| self.entries[i].map(|e| e.rules.min_size()).unwrap_or(0);
+        (min(0), min(1))
+    }
+
+    /// Clear cached rules
+    ///
+    /// This does not clear rules reported by [`Self::last_rules`].
+    pub fn clear(&mut self) {
+        self.entries = [None; 2];
+    }
+}
+
+/// Generation of a widget and its descendants
+///
+/// This combines the generation of each widget in the subtree (in order),
+/// thus changes when the content of any descendant changes or when children
+/// are added or removed.
+pub(crate) fn subtree_generation<W: WidgetChildren + ?Sized>(widget: &W) -> u64 {
+    let mut hash = widget.core_data().generation();
+    for i in 0..widget.num_children() {
+        if let Some(child) = widget.get_child(i) {
+            let gen = subtree_generation(child);
+            hash = (hash.rotate_left(5) ^ gen).wrapping_mul(0x517c_c1b7_2722_0a95);
+        }
+    }
+    hash
+}
+
+/// Clear cached size rules of a widget and its descendants
+pub(crate) fn clear_size_rules_cache(widget: &mut dyn WidgetConfig) {
+    for i in 0..widget.num_children() {
+        if let Some(child) = widget.get_child_mut(i) {
+            clear_size_rules_cache(child);
+        }
+    }
+    widget.core_data_mut().size_cache.clear();
+}
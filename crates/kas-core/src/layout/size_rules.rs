@@ -8,7 +8,7 @@
 use smallvec::SmallVec;
 use std::iter::Sum;
 
-use super::{Margins, Stretch};
+use super::{AspectRatio, AxisInfo, Margins, Stretch};
 use crate::cast::{Cast, CastFloat, Conv, ConvFloat};
 use crate::dir::Directional;
 use crate::geom::Size;
@@ -89,6 +89,10 @@ pub struct SizeRules {
     // (pre, post) margins
     m: (u16, u16),
     stretch: Stretch,
+    // distance from start of content to first baseline (vertical axis only)
+    baseline: Option<i32>,
+    // true if dependent on the size of the other axis
+    other_dep: bool,
 }
 
 impl SizeRules {
@@ -110,6 +114,8 @@ impl SizeRules {
             b: 0,
             m: (0, 0),
             stretch,
+            baseline: None,
+            other_dep: false,
         }
     }
 
@@ -122,6 +128,8 @@ impl SizeRules {
             b: size,
             m: margins,
             stretch: Stretch::None,
+            baseline: None,
+            other_dep: false,
         }
     }
 
@@ -165,6 +173,8 @@ impl SizeRules {
             b: ideal.max(min),
             m: margins,
             stretch,
+            baseline: None,
+            other_dep: false,
         }
     }
 
@@ -192,6 +202,32 @@ impl SizeRules {
         SizeRules::new(min, ideal, (m, m), stretch)
     }
 
+    /// Construct rules respecting an aspect ratio
+    ///
+    /// If the size of the other axis is fixed (see [`AxisInfo::other`]), the
+    /// ideal size is that required to preserve the given `ratio`, but no less
+    /// than `min`. Otherwise, the given `ideal` size is used.
+    ///
+    /// The result [depends on the other axis](SizeRules::depends_on_other).
+    /// The layout engine usually solves the horizontal axis before the
+    /// vertical axis; for such rules it also calculates horizontal rules for
+    /// the available height (see [`super::SolveCache::apply_rect`] and
+    /// [`super::RowSetter::resolve_axis`]).
+    #[inline]
+    pub fn with_aspect(
+        axis: AxisInfo,
+        ratio: AspectRatio,
+        min: i32,
+        ideal: i32,
+        margins: (u16, u16),
+        stretch: Stretch,
+    ) -> Self {
+        let ideal = ratio.length(axis).unwrap_or(ideal);
+        let mut rules = SizeRules::new(min, ideal, margins, stretch);
+        rules.other_dep = true;
+        rules
+    }
+
     /// Get the minimum size
     #[inline]
     pub fn min_size(self) -> i32 {
@@ -228,6 +264,12 @@ impl SizeRules {
         (self.m.0.into(), self.m.1.into())
     }
 
+    /// Set the `(pre, post)` margin sizes
+    #[inline]
+    pub fn set_margins(&mut self, margins: (u16, u16)) {
+        self.m = margins;
+    }
+
     /// Get the stretch priority
     #[inline]
     pub fn stretch(self) -> Stretch {
@@ -240,6 +282,53 @@ impl SizeRules {
         self.stretch = stretch;
     }
 
+    /// Get the baseline, if any
+    ///
+    /// This is the distance from the start of the content (excluding margins)
+    /// to the first text baseline. It is only meaningful for the vertical
+    /// axis. See [`SizeRules::with_baseline`].
+    #[inline]
+    pub fn baseline(self) -> Option<i32> {
+        self.baseline
+    }
+
+    /// Set the baseline
+    #[inline]
+    pub fn set_baseline(&mut self, baseline: Option<i32>) {
+        self.baseline = baseline;
+    }
+
+    /// Construct with the given baseline
+    ///
+    /// Text-based widgets should report the position of their first baseline
+    /// via the rules for the vertical axis. This allows rows to align children
+    /// on a shared baseline (see [`super::RowStorage::set_baseline_align`]).
+    /// Parents wrapping a single child should preserve (and offset) the
+    /// child's baseline; [`SizeRules::max`] and [`SizeRules::appended`] do so.
+    #[inline]
+    pub fn with_baseline(mut self, baseline: i32) -> Self {
+        self.baseline = Some(baseline);
+        self
+    }
+
+    /// Whether these rules depend on the size of the other axis
+    ///
+    /// This is set by [`SizeRules::with_aspect`] and preserved when rules are
+    /// combined (e.g. by [`SizeRules::max`] and [`SizeRules::appended`]).
+    #[inline]
+    pub fn depends_on_other(self) -> bool {
+        self.other_dep
+    }
+
+    /// Set whether these rules depend on the size of the other axis
+    ///
+    /// Widgets constructing new rules from those of a child should copy this
+    /// from the child's rules.
+    #[inline]
+    pub fn set_depends_on_other(&mut self, depends: bool) {
+        self.other_dep = depends;
+    }
+
     /// Set margins to max of own margins and given margins
     pub fn include_margins(&mut self, margins: (u16, u16)) {
         self.m.0 = self.m.0.max(margins.0);
@@ -254,6 +343,8 @@ impl SizeRules {
             b: self.b.max(rhs.b),
             m: (self.m.0.max(rhs.m.0), self.m.1.max(rhs.m.1)),
             stretch: self.stretch.max(rhs.stretch),
+            baseline: self.baseline.or(rhs.baseline),
+            other_dep: self.other_dep || rhs.other_dep,
         }
     }
 
@@ -267,15 +358,19 @@ impl SizeRules {
     ///
     /// E.g. given `margin = margins.0 + margins.1` and factors `(2, 5)`, the
     /// minimum size is set to `min * 2 + margin` and the ideal to
-    /// `ideal * 5 + 4 * margin`.
+    /// `ideal * 5 + 4 * margin`. Results saturate at `i32::MAX`.
     ///
     /// Panics if either factor is 0.
     pub fn multiply_with_margin(&mut self, min_factor: i32, ideal_factor: i32) {
         let margin = i32::from(self.m.0).max(i32::from(self.m.1));
         assert!(min_factor > 0);
         assert!(ideal_factor > 0);
-        self.a = min_factor * self.a + (min_factor - 1) * margin;
-        self.b = ideal_factor * self.b + (ideal_factor - 1) * margin;
+        let mul = |x: i32, f: i32| {
+            x.saturating_mul(f)
+                .saturating_add((f - 1).saturating_mul(margin))
+        };
+        self.a = mul(self.a, min_factor);
+        self.b = mul(self.b, ideal_factor);
     }
 
     /// Append the rules for `rhs` to self
@@ -285,12 +380,15 @@ impl SizeRules {
     ///
     /// Note also that appending [`SizeRules::EMPTY`] does include interior
     /// margins (those between `EMPTY` and the other rules) within the result.
+    ///
+    /// Sizes saturate at `i32::MAX`.
     pub fn append(&mut self, rhs: SizeRules) {
         let c: i32 = self.m.1.max(rhs.m.0).into();
-        self.a += rhs.a + c;
-        self.b += rhs.b + c;
+        self.a = self.a.saturating_add(rhs.a).saturating_add(c);
+        self.b = self.b.saturating_add(rhs.b).saturating_add(c);
         self.m.1 = rhs.m.1;
         self.stretch = self.stretch.max(rhs.stretch);
+        self.other_dep |= rhs.other_dep;
     }
 
     /// Return the rules for self appended by `rhs`
@@ -301,14 +399,18 @@ impl SizeRules {
     ///
     /// Note also that appending [`SizeRules::EMPTY`] does include interior
     /// margins (those between `EMPTY` and the other rules) within the result.
+    ///
+    /// Sizes saturate at `i32::MAX`.
     #[inline]
     pub fn appended(self, rhs: SizeRules) -> Self {
         let c: i32 = self.m.1.max(rhs.m.0).into();
         SizeRules {
-            a: self.a + rhs.a + c,
-            b: self.b + rhs.b + c,
+            a: self.a.saturating_add(rhs.a).saturating_add(c),
+            b: self.b.saturating_add(rhs.b).saturating_add(c),
             m: (self.m.0, rhs.m.1),
             stretch: self.stretch.max(rhs.stretch),
+            baseline: self.baseline,
+            other_dep: self.other_dep || rhs.other_dep,
         }
     }
 
@@ -327,7 +429,9 @@ impl SizeRules {
 
         let mut rules = range[0];
         for r in &range[1..] {
-            rules.a += i32::from(rules.m.1.max(r.m.0)) + r.a;
+            let c = i32::from(rules.m.1.max(r.m.0));
+            rules.a = rules.a.saturating_add(c).saturating_add(r.a);
+            rules.other_dep |= r.other_dep;
         }
         rules.b = rules.a;
         rules.m.1 = range[range.len() - 1].m.1;
@@ -343,6 +447,7 @@ impl SizeRules {
         self.b = (self.b - x.b + y.b).max(0);
         self.m.1 = y.m.1;
         self.stretch = self.stretch.max(y.stretch);
+        self.other_dep |= y.other_dep;
     }
 
     /// Reduce the minimum size
@@ -611,6 +716,8 @@ impl SizeRules {
         } else {
             // Below minimum size: in this case we can ignore prior contents
             // of `out`.We reduce the maximum allowed size to hit our target.
+            // Sizes are never reduced below zero, thus if the target is
+            // smaller than total margins (or negative), the result overflows.
             let mut excess = total.a - target;
 
             let mut largest = 0;
@@ -630,7 +737,7 @@ impl SizeRules {
                 }
             }
 
-            while excess > 0 {
+            while excess > 0 && largest > 0 {
                 let step = (excess / num_equal).min(largest - next_largest);
                 if step == 0 {
                     for n in 0..N {
@@ -695,6 +802,9 @@ impl SizeRules {
 
         rules[0].m.0 = rules[0].m.0.max(self.m.0);
         rules[len1].m.1 = rules[len1].m.1.max(self.m.1);
+        for r in rules.iter_mut() {
+            r.other_dep |= self.other_dep;
+        }
 
         let excess_a = (self.a - sum.a).max(0);
         let excess_b = (self.b - sum.b).max(0);
@@ -89,6 +89,7 @@ impl From<Size> for Margins {
 
 /// Margins (selectable)
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum MarginSelector {
     /// Use the theme's default around-widget margins
     Outer,
@@ -150,6 +151,62 @@ impl Default for Stretch {
     }
 }
 
+/// Aspect-ratio constraint
+///
+/// Some widgets (e.g. images and video views) prefer a size on one axis which
+/// depends on the size assigned to the other axis. Since the layout engine
+/// solves each axis separately, this dependency is expressed via
+/// [`AxisInfo::other`]: when the other axis is fixed, [`AspectRatio::length`]
+/// yields the preferred length on this axis. See also
+/// [`SizeRules::with_aspect`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AspectRatio {
+    width: f32,
+    height: f32,
+}
+
+impl AspectRatio {
+    /// Construct from a width and height
+    ///
+    /// Only the ratio `width / height` is significant. Both values should be
+    /// positive and finite.
+    #[inline]
+    pub fn new(width: f32, height: f32) -> Self {
+        debug_assert!(width > 0.0 && height > 0.0);
+        AspectRatio { width, height }
+    }
+
+    /// The ratio of width to height
+    #[inline]
+    pub fn ratio(self) -> f32 {
+        self.width / self.height
+    }
+
+    /// Length on the given axis for a length `other` on the other axis
+    #[inline]
+    pub fn length_for(self, axis: AxisInfo, other: i32) -> i32 {
+        let other = f32::conv(other);
+        if axis.is_vertical() {
+            (other * self.height / self.width).cast_nearest()
+        } else {
+            (other * self.width / self.height).cast_nearest()
+        }
+    }
+
+    /// Length on the given axis, if the size of the other axis is fixed
+    #[inline]
+    pub fn length(self, axis: AxisInfo) -> Option<i32> {
+        axis.other().map(|other| self.length_for(axis, other))
+    }
+}
+
+impl From<Size> for AspectRatio {
+    #[inline]
+    fn from(size: Size) -> Self {
+        AspectRatio::new(size.0.cast(), size.1.cast())
+    }
+}
+
 /// Scaling of image according to scale factor
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum SpriteScaling {
@@ -214,6 +271,14 @@ impl SpriteDisplay {
             SpriteScaling::Integer => i32::conv_nearest(sh.scale_factor()) * size,
             SpriteScaling::Real => (sh.scale_factor() * f32::conv(size)).cast_nearest(),
         };
+        if self.aspect == AspectScaling::Fixed
+            && self.stretch > Stretch::None
+            && self.size.0 > 0
+            && self.size.1 > 0
+        {
+            let ratio = AspectRatio::from(self.size);
+            return SizeRules::with_aspect(axis, ratio, size, size, margins, self.stretch);
+        }
         SizeRules::new(size, size, margins, self.stretch)
     }
 
@@ -299,12 +364,14 @@ impl FrameRules {
         let offset = self.offset + m0;
         let size = self.size + m0 + m1;
 
-        let rules = SizeRules::new(
+        let mut rules = SizeRules::new(
             content.min_size() + size,
             content.ideal_size() + size,
             self.m,
             content.stretch(),
         );
+        rules.set_baseline(content.baseline().map(|b| b + offset));
+        rules.set_depends_on_other(content.depends_on_other());
         (rules, offset, size)
     }
 
@@ -322,12 +389,14 @@ impl FrameRules {
         let m1 = u16::conv((i32::conv(m1) + offset - size).max(0));
         let margins = (self.m.0.max(m0), self.m.1.max(m1));
 
-        let rules = SizeRules::new(
+        let mut rules = SizeRules::new(
             content.min_size() + size,
             content.ideal_size() + size,
             margins,
             content.stretch(),
         );
+        rules.set_baseline(content.baseline().map(|b| b + offset));
+        rules.set_depends_on_other(content.depends_on_other());
         (rules, offset, size)
     }
 }
@@ -9,7 +9,7 @@ use log::trace;
 use std::fmt;
 
 use super::{AlignHints, AxisInfo, Margins, SizeRules};
-use crate::draw::SizeHandle;
+use crate::draw::{DrawHandle, SizeHandle};
 use crate::event::Manager;
 use crate::geom::{Rect, Size};
 use crate::{Widget, WidgetConfig};
@@ -95,6 +95,10 @@ pub fn solve_size_rules<W: Widget>(
 ///
 /// [`SolveCache::apply_rect`] accepts a [`Rect`], updates constraints as
 /// necessary and sets widget positions within this `rect`.
+///
+/// Where the `rect` is smaller than the minimum size, layout overflows: child
+/// sizes are reduced (to zero if necessary, but never below) and some
+/// content will not be visible. This is reported by [`SolveCache::overflow`].
 pub struct SolveCache {
     // Technically we don't need to store min and ideal here, but it simplifies
     // the API for very little real cost.
@@ -103,6 +107,10 @@ pub struct SolveCache {
     margins: Margins,
     refresh_rules: bool,
     last_width: i32,
+    last_height: i32,
+    // true if horizontal rules depend on the height
+    height_dep: bool,
+    overflow: (bool, bool),
 }
 
 impl SolveCache {
@@ -133,12 +141,29 @@ impl SolveCache {
         self.margins
     }
 
+    /// Whether the last layout overflowed: `(horizontal, vertical)`
+    ///
+    /// This is true on an axis where the `rect` passed to
+    /// [`SolveCache::apply_rect`] was smaller than the minimum size.
+    pub fn overflow(&self) -> (bool, bool) {
+        self.overflow
+    }
+
+    /// Draw an overflow indicator over `rect`, if the layout overflowed
+    ///
+    /// Shells should call this after drawing the window's widget.
+    pub fn draw_overflow(&self, rect: Rect, draw: &mut dyn DrawHandle) {
+        if self.overflow.0 || self.overflow.1 {
+            draw.overflow_indicator(rect, self.overflow);
+        }
+    }
+
     /// Calculate required size of widget
     pub fn find_constraints(
         widget: &mut dyn WidgetConfig,
         size_handle: &mut dyn SizeHandle,
     ) -> Self {
-        let start = std::time::Instant::now();
+        let start = instant::Instant::now();
 
         let w = widget.size_rules(size_handle, AxisInfo::new(false, None));
         let h = widget.size_rules(size_handle, AxisInfo::new(true, Some(w.ideal_size())));
@@ -156,12 +181,18 @@ impl SolveCache {
         );
         let refresh_rules = false;
         let last_width = ideal.0;
+        // Horizontal rules have not yet been calculated for any height
+        let last_height = -1;
+        let height_dep = w.depends_on_other();
         SolveCache {
             min,
             ideal,
             margins,
             refresh_rules,
             last_width,
+            last_height,
+            height_dep,
+            overflow: (false, false),
         }
     }
 
@@ -184,6 +215,13 @@ impl SolveCache {
     /// last calculated then this method will recalculate all rules; otherwise
     /// it will only do so if necessary (when dimensions do not match those
     /// last used).
+    ///
+    /// Where horizontal rules [depend on the height](SizeRules::depends_on_other)
+    /// (see [`SizeRules::with_aspect`]), these are calculated for the
+    /// available height before vertical rules are calculated for the available
+    /// width. This allows widgets whose width depends on their height to be
+    /// assigned an appropriate width. Otherwise, horizontal rules are not
+    /// re-calculated when only the height changes.
     pub fn apply_rect(
         &mut self,
         widget: &mut dyn WidgetConfig,
@@ -191,22 +229,34 @@ impl SolveCache {
         mut rect: Rect,
         inner_margin: bool,
     ) {
-        let start = std::time::Instant::now();
+        let start = instant::Instant::now();
 
-        let mut width = rect.size.0;
+        let mut width = rect.size.0.max(0);
+        let mut height = rect.size.1.max(0);
         if inner_margin {
-            width -= self.margins.sum_horiz();
+            width = (width - self.margins.sum_horiz()).max(0);
+            height = (height - self.margins.sum_vert()).max(0);
         }
 
         // We call size_rules not because we want the result, but because our
         // spec requires that we do so before calling set_rect.
-        if self.refresh_rules || width != self.last_width {
+        let height_changed = self.height_dep && height != self.last_height;
+        if self.refresh_rules || width != self.last_width || height_changed {
             mgr.size_handle(|size_handle| {
                 if self.refresh_rules {
+                    super::clear_size_rules_cache(widget);
                     let w = widget.size_rules(size_handle, AxisInfo::new(false, None));
                     self.min.0 = w.min_size();
                     self.ideal.0 = w.ideal_size();
                     self.margins.horiz = w.margins();
+                    self.height_dep = w.depends_on_other();
+                }
+
+                if self.height_dep {
+                    // The result is not used for min/ideal size, which are
+                    // independent of the size assigned to the window.
+                    widget.size_rules(size_handle, AxisInfo::new(false, Some(height)));
+                    self.last_height = height;
                 }
 
                 let h = widget.size_rules(size_handle, AxisInfo::new(true, Some(width)));
@@ -217,11 +267,15 @@ impl SolveCache {
             });
         }
 
+        self.overflow = (width < self.min.0, height < self.min.1);
+        if self.overflow.0 || self.overflow.1 {
+            trace!("layout::apply_rect: overflow {:?}", self.overflow);
+        }
+
         if inner_margin {
             rect.pos += Size::from((self.margins.horiz.0, self.margins.vert.0));
-            rect.size.0 = width;
-            rect.size.1 -= self.margins.sum_vert();
         }
+        rect.size = Size(width, height);
         widget.set_rect(mgr, rect, AlignHints::NONE);
 
         trace!(target: "kas_perf", "layout::apply_rect: {}ms", start.elapsed().as_millis());
@@ -51,6 +51,22 @@ pub trait RowStorage: sealed::Sealed + Clone {
 
     #[doc(hidden)]
     fn widths_rules_total(&mut self) -> (&mut [i32], &mut [SizeRules], SizeRules);
+
+    /// Enable or disable baseline alignment (default: disabled)
+    ///
+    /// When enabled, children of a horizontal row are aligned such that their
+    /// first text baselines match (see [`SizeRules::with_baseline`]). This
+    /// has no effect on columns or on children not reporting a baseline.
+    fn set_baseline_align(&mut self, enable: bool);
+
+    #[doc(hidden)]
+    fn baseline_align(&self) -> bool;
+
+    #[doc(hidden)]
+    fn set_baseline(&mut self, baseline: Option<i32>);
+
+    #[doc(hidden)]
+    fn baseline(&self) -> Option<i32>;
 }
 
 /// Fixed-length row storage
@@ -61,6 +77,8 @@ pub struct FixedRowStorage<const C: usize> {
     rules: [SizeRules; C],
     total: SizeRules,
     widths: [i32; C],
+    baseline_align: bool,
+    baseline: Option<i32>,
 }
 
 impl<const C: usize> Default for FixedRowStorage<C> {
@@ -69,6 +87,8 @@ impl<const C: usize> Default for FixedRowStorage<C> {
             rules: [SizeRules::default(); C],
             total: SizeRules::default(),
             widths: [0; C],
+            baseline_align: false,
+            baseline: None,
         }
     }
 }
@@ -92,6 +112,21 @@ impl<const C: usize> RowStorage for FixedRowStorage<C> {
     fn widths_rules_total(&mut self) -> (&mut [i32], &mut [SizeRules], SizeRules) {
         (self.widths.as_mut(), self.rules.as_mut(), self.total)
     }
+    fn set_baseline_align(&mut self, enable: bool) {
+        self.baseline_align = enable;
+    }
+
+    fn baseline_align(&self) -> bool {
+        self.baseline_align
+    }
+
+    fn set_baseline(&mut self, baseline: Option<i32>) {
+        self.baseline = baseline;
+    }
+
+    fn baseline(&self) -> Option<i32> {
+        self.baseline
+    }
 }
 
 /// Variable-length row storage
@@ -100,6 +135,8 @@ pub struct DynRowStorage {
     rules: Vec<SizeRules>,
     total: SizeRules,
     widths: Vec<i32>,
+    baseline_align: bool,
+    baseline: Option<i32>,
 }
 
 impl Storage for DynRowStorage {
@@ -121,6 +158,21 @@ impl RowStorage for DynRowStorage {
     fn widths_rules_total(&mut self) -> (&mut [i32], &mut [SizeRules], SizeRules) {
         (&mut self.widths, &mut self.rules, self.total)
     }
+    fn set_baseline_align(&mut self, enable: bool) {
+        self.baseline_align = enable;
+    }
+
+    fn baseline_align(&self) -> bool {
+        self.baseline_align
+    }
+
+    fn set_baseline(&mut self, baseline: Option<i32>) {
+        self.baseline = baseline;
+    }
+
+    fn baseline(&self) -> Option<i32> {
+        self.baseline
+    }
 }
 
 /// Temporary storage type.
@@ -5,15 +5,17 @@
 
 //! Layout visitor
 
-use super::{AlignHints, AxisInfo, RulesSetter, RulesSolver, SizeRules, Storage};
+use super::{AlignHints, AxisInfo, MarginSelector, RulesSetter, RulesSolver};
 use super::{DynRowStorage, RowPositionSolver, RowSetter, RowSolver, RowStorage};
+use super::{FlowSetter, FlowSolver, FlowStorage};
 use super::{GridChildInfo, GridDimensions, GridSetter, GridSolver, GridStorage};
+use super::{SizeRules, Storage, Stretch};
+use crate::dir::{Direction, Directional};
 use crate::draw::{color::Rgb, DrawHandle, InputState, SizeHandle, TextClass};
 use crate::event::{Manager, ManagerState};
 use crate::geom::{Coord, Offset, Rect, Size};
 use crate::text::{Align, TextApi, TextApiExt};
-use crate::WidgetId;
-use crate::{dir::Directional, WidgetConfig};
+use crate::{WidgetConfig, WidgetId};
 use std::any::Any;
 use std::iter::ExactSizeIterator;
 
@@ -49,6 +51,23 @@ impl StorageChain {
             _ => unreachable!(),
         }
     }
+
+    /// Get the solved rect of a named layout region
+    ///
+    /// Returns `None` if no region `name` exists (or layout storage has not
+    /// been initialised yet). See [`Layout::region`].
+    pub fn region(&mut self, name: &str) -> Option<Rect> {
+        let mut chain = self;
+        while let StorageChain(Some(b)) = chain {
+            if let Some(storage) = b.1.downcast_mut::<RegionStorage>() {
+                if storage.name == name {
+                    return Some(storage.rect);
+                }
+            }
+            chain = &mut b.0;
+        }
+        None
+    }
 }
 
 /// Implementation helper for layout of children
@@ -61,6 +80,11 @@ trait Visitor {
 
     fn is_reversed(&mut self) -> bool;
 
+    /// Get child widgets in grid order, if this is a grid
+    fn grid_order(&mut self) -> Option<Vec<WidgetId>> {
+        None
+    }
+
     fn find_id(&mut self, coord: Coord) -> Option<WidgetId>;
 
     fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, state: InputState);
@@ -84,12 +108,18 @@ enum LayoutType<'a> {
     AlignSingle(&'a mut dyn WidgetConfig, AlignHints),
     /// Apply alignment hints to some sub-layout
     AlignLayout(Box<Layout<'a>>, AlignHints),
+    /// Override the stretch priority of some sub-layout
+    Stretch(Box<Layout<'a>>, Stretch),
+    /// Override the margins of some sub-layout
+    Margins(Box<Layout<'a>>, MarginSelector),
     /// Frame around content
     Frame(Box<Layout<'a>>, &'a mut FrameStorage),
     /// Navigation frame around content
     NavFrame(Box<Layout<'a>>, &'a mut FrameStorage),
     /// Button frame around content
     Button(Box<Layout<'a>>, &'a mut FrameStorage, Option<Rgb>),
+    /// Named region around content
+    Region(Box<Layout<'a>>, &'a mut RegionStorage),
     /// An embedded layout
     Visitor(Box<dyn Visitor + 'a>),
 }
@@ -125,6 +155,22 @@ impl<'a> Layout<'a> {
         Layout { layout }
     }
 
+    /// Override the stretch priority of a sub-layout
+    ///
+    /// The [`SizeRules`] of `layout` on both axes are given priority `stretch`.
+    pub fn stretch(layout: Self, stretch: Stretch) -> Self {
+        let layout = LayoutType::Stretch(Box::new(layout), stretch);
+        Layout { layout }
+    }
+
+    /// Override the margins of a sub-layout
+    ///
+    /// The margins of `layout` are replaced by those chosen by `margins`.
+    pub fn margins(layout: Self, margins: MarginSelector) -> Self {
+        let layout = LayoutType::Margins(Box::new(layout), margins);
+        Layout { layout }
+    }
+
     /// Construct a frame around a sub-layout
     ///
     /// This frame has dimensions according to [`SizeHandle::frame`].
@@ -150,6 +196,17 @@ impl<'a> Layout<'a> {
         Layout { layout }
     }
 
+    /// Construct a named region around a sub-layout
+    ///
+    /// This does not affect layout, but records the `Rect` assigned to the
+    /// sub-layout under `name`, which may later be retrieved via
+    /// [`crate::CoreData::layout_region`] (e.g. to draw over the region).
+    pub fn region(data: &'a mut RegionStorage, name: &'static str, child: Self) -> Self {
+        data.name = name;
+        let layout = LayoutType::Region(Box::new(child), data);
+        Layout { layout }
+    }
+
     /// Place a text element in the layout
     pub fn text(data: &'a mut TextStorage, text: &'a mut dyn TextApi, class: TextClass) -> Self {
         let layout = LayoutType::Visitor(Box::new(Text { data, text, class }));
@@ -190,6 +247,21 @@ impl<'a> Layout<'a> {
         Layout { layout }
     }
 
+    /// Construct a flow layout over a slice of widgets
+    ///
+    /// Children are placed left-to-right, wrapping to a new line when the
+    /// available width is exhausted. See [`FlowSolver`].
+    pub fn flow<W>(slice: &'a mut [W], data: &'a mut FlowStorage) -> Self
+    where
+        W: WidgetConfig,
+    {
+        let layout = LayoutType::Visitor(Box::new(Flow {
+            data,
+            children: slice,
+        }));
+        Layout { layout }
+    }
+
     /// Construct a grid layout over an iterator of `(cell, layout)` items
     pub fn grid<I, S>(iter: I, dim: GridDimensions, data: &'a mut S) -> Self
     where
@@ -212,9 +284,23 @@ impl<'a> Layout<'a> {
     fn size_rules_(&mut self, sh: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         match &mut self.layout {
             LayoutType::None => SizeRules::EMPTY,
-            LayoutType::Single(child) => child.size_rules(sh, axis),
-            LayoutType::AlignSingle(child, _) => child.size_rules(sh, axis),
+            LayoutType::Single(child) | LayoutType::AlignSingle(child, _) => {
+                let rules = child.size_rules(sh, axis);
+                child.core_data_mut().size_cache.record(axis, rules);
+                rules
+            }
             LayoutType::AlignLayout(layout, _) => layout.size_rules_(sh, axis),
+            LayoutType::Stretch(layout, stretch) => {
+                let mut rules = layout.size_rules_(sh, axis);
+                rules.set_stretch(*stretch);
+                rules
+            }
+            LayoutType::Margins(layout, margins) => {
+                let mut rules = layout.size_rules_(sh, axis);
+                rules.set_margins(margins.select(sh).extract(axis));
+                rules
+            }
+            LayoutType::Region(layout, _) => layout.size_rules_(sh, axis),
             LayoutType::Frame(child, storage) => {
                 let frame_rules = sh.frame(axis.is_vertical());
                 let child_rules = child.size_rules_(sh, axis);
@@ -253,11 +339,18 @@ impl<'a> Layout<'a> {
             LayoutType::None => (),
             LayoutType::Single(child) => child.set_rect(mgr, rect, align),
             LayoutType::AlignSingle(child, hints) => {
-                let align = hints.combine(align);
+                let align = mirror_hints(mgr, *hints).combine(align);
                 child.set_rect(mgr, rect, align);
             }
             LayoutType::AlignLayout(layout, hints) => {
-                let align = hints.combine(align);
+                let align = mirror_hints(mgr, *hints).combine(align);
+                layout.set_rect_(mgr, rect, align);
+            }
+            LayoutType::Stretch(layout, _) | LayoutType::Margins(layout, _) => {
+                layout.set_rect_(mgr, rect, align)
+            }
+            LayoutType::Region(layout, storage) => {
+                storage.rect = rect;
                 layout.set_rect_(mgr, rect, align);
             }
             LayoutType::Frame(child, storage)
@@ -265,7 +358,7 @@ impl<'a> Layout<'a> {
             | LayoutType::Button(child, storage, _) => {
                 storage.rect = rect;
                 rect.pos += storage.offset;
-                rect.size -= storage.size;
+                rect.size = rect.size.clamped_sub(storage.size);
                 child.set_rect_(mgr, rect, align);
             }
             LayoutType::Visitor(layout) => layout.set_rect(mgr, rect, align),
@@ -284,13 +377,54 @@ impl<'a> Layout<'a> {
             LayoutType::None => false,
             LayoutType::Single(_) | LayoutType::AlignSingle(_, _) => false,
             LayoutType::AlignLayout(layout, _)
+            | LayoutType::Stretch(layout, _)
+            | LayoutType::Margins(layout, _)
             | LayoutType::Frame(layout, _)
             | LayoutType::NavFrame(layout, _)
-            | LayoutType::Button(layout, _, _) => layout.is_reversed_(),
+            | LayoutType::Button(layout, _, _)
+            | LayoutType::Region(layout, _) => layout.is_reversed_(),
             LayoutType::Visitor(layout) => layout.is_reversed(),
         }
     }
 
+    /// Get child widgets in grid order
+    ///
+    /// If this layout is (or wraps) a grid, this returns the identifiers of
+    /// cell widgets ordered by row, then by column. Cells not consisting of a
+    /// single widget are omitted. Returns `None` for other layouts.
+    ///
+    /// This is used to implement [`crate::Layout::spatial_nav`] in visual order.
+    #[inline]
+    pub fn grid_order(mut self) -> Option<Vec<WidgetId>> {
+        self.grid_order_()
+    }
+    fn grid_order_(&mut self) -> Option<Vec<WidgetId>> {
+        match &mut self.layout {
+            LayoutType::AlignLayout(layout, _)
+            | LayoutType::Stretch(layout, _)
+            | LayoutType::Margins(layout, _)
+            | LayoutType::Frame(layout, _)
+            | LayoutType::NavFrame(layout, _)
+            | LayoutType::Region(layout, _) => layout.grid_order_(),
+            LayoutType::Visitor(layout) => layout.grid_order(),
+            _ => None,
+        }
+    }
+
+    /// Get the identifier of the widget, if this layout is a single widget
+    fn single_id(&mut self) -> Option<WidgetId> {
+        match &mut self.layout {
+            LayoutType::Single(child) | LayoutType::AlignSingle(child, _) => Some(child.id()),
+            LayoutType::AlignLayout(layout, _)
+            | LayoutType::Stretch(layout, _)
+            | LayoutType::Margins(layout, _)
+            | LayoutType::Frame(layout, _)
+            | LayoutType::NavFrame(layout, _)
+            | LayoutType::Region(layout, _) => layout.single_id(),
+            _ => None,
+        }
+    }
+
     /// Find a widget by coordinate
     ///
     /// Does not return the widget's own identifier. See example usage in
@@ -303,7 +437,10 @@ impl<'a> Layout<'a> {
         match &mut self.layout {
             LayoutType::None => None,
             LayoutType::Single(child) | LayoutType::AlignSingle(child, _) => child.find_id(coord),
-            LayoutType::AlignLayout(layout, _) => layout.find_id_(coord),
+            LayoutType::AlignLayout(layout, _)
+            | LayoutType::Stretch(layout, _)
+            | LayoutType::Margins(layout, _)
+            | LayoutType::Region(layout, _) => layout.find_id_(coord),
             LayoutType::Frame(child, _) | LayoutType::NavFrame(child, _) => child.find_id_(coord),
             // Buttons steal clicks, hence Button never returns ID of content
             LayoutType::Button(_, _, _) => None,
@@ -323,7 +460,10 @@ impl<'a> Layout<'a> {
             LayoutType::Single(child) | LayoutType::AlignSingle(child, _) => {
                 child.draw(draw, mgr, disabled)
             }
-            LayoutType::AlignLayout(layout, _) => layout.draw_(draw, mgr, state),
+            LayoutType::AlignLayout(layout, _)
+            | LayoutType::Stretch(layout, _)
+            | LayoutType::Margins(layout, _)
+            | LayoutType::Region(layout, _) => layout.draw_(draw, mgr, state),
             LayoutType::Frame(child, storage) => {
                 draw.outer_frame(storage.rect);
                 child.draw_(draw, mgr, state);
@@ -341,6 +481,14 @@ impl<'a> Layout<'a> {
     }
 }
 
+/// Hints given by a layout are mirrored under right-to-left layout
+fn mirror_hints(mgr: &Manager, hints: AlignHints) -> AlignHints {
+    match mgr.layout_direction().is_rtl() {
+        false => hints,
+        true => hints.mirrored(),
+    }
+}
+
 /// Implement row/column layout for children
 struct List<'a, S, D, I> {
     data: &'a mut S,
@@ -362,10 +510,23 @@ where
     }
 
     fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
-        let dim = (self.direction, self.children.len());
-        let mut setter = RowSetter::<D, Vec<i32>, _>::new(rect, dim, align, self.data);
+        let direction = mgr.layout_direction().apply(self.direction);
+        let dim = (direction, self.children.len());
+        let mut children: Vec<Layout<'a>> = (&mut self.children).collect();
+        if let Some(axis) = RowSetter::<_, Vec<i32>, _>::resolve_axis(rect, direction, self.data) {
+            let dim = (self.direction, children.len());
+            mgr.size_handle(|sh| {
+                let mut solver = RowSolver::new(axis, dim, self.data);
+                for (n, child) in children.iter_mut().enumerate() {
+                    solver.for_child(self.data, n, |axis| child.size_rules_(sh, axis));
+                }
+                solver.finish(self.data);
+            });
+        }
+        let mut setter = RowSetter::<_, Vec<i32>, _>::new(rect, dim, align, self.data);
 
-        for (n, child) in (&mut self.children).enumerate() {
+        let align = setter.child_hints(align);
+        for (n, child) in children.into_iter().enumerate() {
             child.set_rect(mgr, setter.child_rect(self.data, n), align);
         }
     }
@@ -404,9 +565,21 @@ impl<'a, W: WidgetConfig, D: Directional> Visitor for Slice<'a, W, D> {
     }
 
     fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
-        let dim = (self.direction, self.children.len());
-        let mut setter = RowSetter::<D, Vec<i32>, _>::new(rect, dim, align, self.data);
+        let direction = mgr.layout_direction().apply(self.direction);
+        let dim = (direction, self.children.len());
+        if let Some(axis) = RowSetter::<_, Vec<i32>, _>::resolve_axis(rect, direction, self.data) {
+            let dim = (self.direction, self.children.len());
+            mgr.size_handle(|sh| {
+                let mut solver = RowSolver::new(axis, dim, self.data);
+                for (n, child) in self.children.iter_mut().enumerate() {
+                    solver.for_child(self.data, n, |axis| child.size_rules(sh, axis));
+                }
+                solver.finish(self.data);
+            });
+        }
+        let mut setter = RowSetter::<_, Vec<i32>, _>::new(rect, dim, align, self.data);
 
+        let align = setter.child_hints(align);
         for (n, child) in self.children.iter_mut().enumerate() {
             child.set_rect(mgr, setter.child_rect(self.data, n), align);
         }
@@ -417,20 +590,82 @@ impl<'a, W: WidgetConfig, D: Directional> Visitor for Slice<'a, W, D> {
     }
 
     fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
-        let solver = RowPositionSolver::new(self.direction);
+        let solver = RowPositionSolver::new(self.visual_direction());
         solver
             .find_child_mut(self.children, coord)
             .and_then(|child| child.find_id(coord))
     }
 
     fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, state: InputState) {
-        let solver = RowPositionSolver::new(self.direction);
+        let solver = RowPositionSolver::new(self.visual_direction());
         solver.for_children(self.children, draw.get_clip_rect(), |w| {
             w.draw(draw, mgr, state.contains(InputState::DISABLED))
         });
     }
 }
 
+impl<'a, W: WidgetConfig, D: Directional> Slice<'a, W, D> {
+    /// Direction in which children were positioned
+    ///
+    /// This differs from `self.direction` under right-to-left layout; we infer
+    /// it from child positions since `find_id` has no access to the manager.
+    fn visual_direction(&self) -> Direction {
+        let dir = self.direction.as_direction();
+        let (first, last) = match (self.children.first(), self.children.last()) {
+            (Some(w0), Some(w1)) if dir.is_horizontal() => (w0.rect().pos.0, w1.rect().pos.0),
+            _ => return dir,
+        };
+        if first != last && (first > last) != dir.is_reversed() {
+            dir.reversed()
+        } else {
+            dir
+        }
+    }
+}
+
+/// A wrapping row over a slice
+struct Flow<'a, W: WidgetConfig> {
+    data: &'a mut FlowStorage,
+    children: &'a mut [W],
+}
+
+impl<'a, W: WidgetConfig> Visitor for Flow<'a, W> {
+    fn size_rules(&mut self, sh: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let mut solver = FlowSolver::new(axis, self.children.len(), self.data);
+        for (n, child) in self.children.iter_mut().enumerate() {
+            solver.for_child(self.data, n, |axis| child.size_rules(sh, axis));
+        }
+        solver.finish(self.data)
+    }
+
+    fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
+        let len = self.children.len();
+        let mut setter = FlowSetter::new(rect, len, align, self.data);
+
+        for (n, child) in self.children.iter_mut().enumerate() {
+            child.set_rect(mgr, setter.child_rect(self.data, n), align);
+        }
+    }
+
+    fn is_reversed(&mut self) -> bool {
+        false
+    }
+
+    fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
+        // TODO(opt): more efficient search strategy?
+        self.children
+            .iter_mut()
+            .find_map(|child| child.find_id(coord))
+    }
+
+    fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, state: InputState) {
+        let disabled = state.contains(InputState::DISABLED);
+        for child in self.children.iter_mut() {
+            child.draw(draw, mgr, disabled);
+        }
+    }
+}
+
 /// Implement grid layout for children
 struct Grid<'a, S, I> {
     data: &'a mut S,
@@ -451,17 +686,38 @@ where
     }
 
     fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
+        let mut children: Vec<(GridChildInfo, Layout<'a>)> = (&mut self.children).collect();
+        if let Some(axis) = GridSetter::<Vec<_>, Vec<_>, _>::resolve_axis(rect, self.data) {
+            mgr.size_handle(|sh| {
+                let mut solver = GridSolver::<Vec<_>, Vec<_>, _>::new(axis, self.dim, self.data);
+                for (info, child) in children.iter_mut() {
+                    solver.for_child(self.data, *info, |axis| child.size_rules_(sh, axis));
+                }
+                solver.finish(self.data);
+            });
+        }
         let mut setter = GridSetter::<Vec<_>, Vec<_>, _>::new(rect, self.dim, align, self.data);
-        for (info, child) in &mut self.children {
+        for (info, child) in children {
             child.set_rect(mgr, setter.child_rect(self.data, info), align);
         }
     }
 
     fn is_reversed(&mut self) -> bool {
-        // TODO: replace is_reversed with direct implementation of spatial_nav
         false
     }
 
+    fn grid_order(&mut self) -> Option<Vec<WidgetId>> {
+        let mut cells = vec![];
+        for (info, mut child) in &mut self.children {
+            if let Some(id) = child.single_id() {
+                cells.push((info.row, info.col, id));
+            }
+        }
+        // Stable sort: overlapping cells keep declaration order
+        cells.sort_by_key(|cell| (cell.0, cell.1));
+        Some(cells.into_iter().map(|cell| cell.2).collect())
+    }
+
     fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
         // TODO(opt): more efficient search strategy?
         self.children.find_map(|(_, child)| child.find_id(coord))
@@ -492,11 +748,25 @@ impl Storage for FrameStorage {
     }
 }
 
+/// Layout storage for a named region
+#[derive(Clone, Default, Debug)]
+pub struct RegionStorage {
+    name: &'static str,
+    rect: Rect,
+}
+impl Storage for RegionStorage {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
 /// Layout storage for text element
 #[derive(Clone, Default, Debug)]
 pub struct TextStorage {
     /// Position of text
     pub pos: Coord,
+    /// Baseline of first line, relative to the top of the text
+    pub baseline: Option<i32>,
 }
 
 struct Text<'a> {
@@ -507,10 +777,15 @@ struct Text<'a> {
 
 impl<'a> Visitor for Text<'a> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
-        size_handle.text_bound(self.text, self.class, axis)
+        let rules = size_handle.text_bound(self.text, self.class, axis);
+        if axis.is_vertical() {
+            self.data.baseline = rules.baseline();
+        }
+        rules
     }
 
     fn set_rect(&mut self, _mgr: &mut Manager, rect: Rect, align: AlignHints) {
+        let (rect, align) = align.baseline_rect(rect, self.data.baseline);
         let halign = match self.class {
             TextClass::Button => Align::Center,
             _ => Align::Default,
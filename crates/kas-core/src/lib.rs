@@ -16,6 +16,7 @@ extern crate bitflags;
 
 pub extern crate easy_cast as cast;
 pub extern crate kas_macros as macros;
+pub extern crate raw_window_handle;
 
 // internal modules:
 mod core;
@@ -31,8 +32,10 @@ pub mod dir;
 pub mod draw;
 pub mod event;
 pub mod geom;
+pub mod l10n;
 pub mod layout;
 pub mod prelude;
+pub mod state;
 pub mod text;
 pub mod updatable;
 pub mod util;
@@ -13,7 +13,7 @@ pub use crate::cast::{Cast, CastFloat, Conv, ConvFloat};
 #[doc(no_inline)]
 pub use crate::class::*;
 #[doc(no_inline)]
-pub use crate::dir::{Direction, Directional};
+pub use crate::dir::{Direction, Directional, LayoutDirection};
 #[doc(no_inline)]
 pub use crate::draw::{
     DrawHandle, DrawHandleExt, DrawShared, ImageId, InputState, SizeHandle, ThemeApi,
@@ -0,0 +1,180 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Persisted UI state
+//!
+//! Widgets may save small pieces of state (e.g. scroll offsets, splitter
+//! positions, window geometry) under a key, and restore it when next
+//! configured — typically in the next run of the application. Since widget
+//! identifiers are not stable across runs, keys are assigned by the user,
+//! usually path-like, e.g. `"main/sidebar/split"`; widgets supporting this
+//! have a `with_state_key` method. Keys should be unique within the
+//! application.
+//!
+//! Widgets access state via [`Manager::load_state`] and
+//! [`Manager::save_state`]. Storage is provided by a [`StateStore`],
+//! configured on the shell's `Toolkit`; by default state is held in memory
+//! only ([`MemoryStore`]), while [`FileStore`] persists state to a file.
+//!
+//! [`Manager::load_state`]: crate::event::Manager::load_state
+//! [`Manager::save_state`]: crate::event::Manager::save_state
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A storage backend for persisted UI state
+///
+/// Values are short strings, keyed by (user-assigned) path-like strings.
+pub trait StateStore {
+    /// Load the value stored under `key`, if any
+    fn load(&self, key: &str) -> Option<String>;
+
+    /// Store `value` under `key`, replacing any previous value
+    fn save(&mut self, key: &str, value: String);
+
+    /// Write any pending changes to backing storage
+    ///
+    /// This is called by the shell on exit. The default implementation does
+    /// nothing.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// In-memory state storage
+///
+/// State is not persisted beyond the lifetime of this object.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStore {
+    entries: BTreeMap<String, String>,
+}
+
+impl MemoryStore {
+    /// Construct an empty store
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StateStore for MemoryStore {
+    fn load(&self, key: &str) -> Option<String> {
+        self.entries.get(key).cloned()
+    }
+
+    fn save(&mut self, key: &str, value: String) {
+        self.entries.insert(key.to_string(), value);
+    }
+}
+
+/// File-backed state storage
+///
+/// State is read on construction and written by [`StateStore::flush`]
+/// (if changed). The file format is plain text with one `key<TAB>value`
+/// entry per line; backslash, tab and newline characters are escaped.
+#[derive(Clone, Debug)]
+pub struct FileStore {
+    path: PathBuf,
+    entries: BTreeMap<String, String>,
+    dirty: bool,
+}
+
+impl FileStore {
+    /// Open the store at `path`
+    ///
+    /// If no file exists at `path`, the store is empty (and the file is
+    /// created on flush). Malformed lines are skipped.
+    pub fn open<P: Into<PathBuf>>(path: P) -> io::Result<Self> {
+        let path = path.into();
+        let mut entries = BTreeMap::new();
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                for line in contents.lines() {
+                    if let Some((key, value)) = line.split_once('\t') {
+                        entries.insert(unescape(key), unescape(value));
+                    }
+                }
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+            Err(error) => return Err(error),
+        }
+        Ok(FileStore {
+            path,
+            entries,
+            dirty: false,
+        })
+    }
+
+    /// Get the path of the backing file
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl StateStore for FileStore {
+    fn load(&self, key: &str) -> Option<String> {
+        self.entries.get(key).cloned()
+    }
+
+    fn save(&mut self, key: &str, value: String) {
+        if self.entries.get(key) != Some(&value) {
+            self.entries.insert(key.to_string(), value);
+            self.dirty = true;
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut contents = String::new();
+        for (key, value) in &self.entries {
+            contents.push_str(&escape(key));
+            contents.push('\t');
+            contents.push_str(&escape(value));
+            contents.push('\n');
+        }
+        fs::write(&self.path, contents)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
@@ -9,10 +9,19 @@
 //! lower level of integration than other parts of the library. The [`util`]
 //! module is an extension providing some integration.
 //!
+//! KAS Text applies the Unicode bidirectional algorithm and (given the
+//! `shaping` feature, or `harfbuzz` as an alternative backend) shapes text,
+//! thus complex scripts such as Arabic, Hebrew and Indic scripts are supported.
+//! Text widgets move the cursor over grapheme clusters, visually with respect
+//! to the Left/Right keys (see [`util::line_is_rtl`]).
+//!
 //! [KAS Text]: https://github.com/kas-gui/kas-text/
 
 pub use kas_text::*;
 
+mod annotation;
+pub use annotation::{Annotation, AnnotationAction, AnnotationClass, Annotations};
+
 mod selection;
 pub use selection::SelectionHelper;
 
@@ -21,9 +30,10 @@ pub use string::AccelString;
 
 /// Utilities integrating `kas-text` functionality
 pub mod util {
-    use super::{fonts, format, EditableTextApi, Text, TextApi, Vec2};
+    use super::{fonts, format, EditableTextApi, Text, TextApi, TextApiExt, Vec2};
     use crate::{geom::Size, TkAction};
     use log::trace;
+    use unicode_segmentation::UnicodeSegmentation;
 
     /// Set the text and prepare
     ///
@@ -72,8 +82,7 @@ pub mod util {
             return TkAction::empty();
         }
         if let Some(req) = text.prepare() {
-            let avail = Vec2::from(avail);
-            if !(req.0 <= avail.0 && req.1 <= avail.1) {
+            if is_truncated(req, avail) {
                 trace!(
                     "set_text_and_prepare triggers RESIZE: req={:?}, avail={:?}",
                     req,
@@ -84,4 +93,122 @@ pub mod util {
         }
         TkAction::REDRAW
     }
+
+    /// True if the line containing `index` is laid out right-to-left
+    ///
+    /// This is the direction of the line's paragraph, as resolved by the
+    /// Unicode bidirectional algorithm in `kas-text`; runs of the opposite
+    /// direction within the line (e.g. a Latin word in Hebrew text) do not
+    /// affect the result. Returns `false` if text is not prepared.
+    ///
+    /// Text widgets use this to make the Left/Right keys move the cursor
+    /// visually (see [`crate::event::Command::mirrored`]).
+    pub fn line_is_rtl<T: format::FormattableText>(text: &Text<T>, index: usize) -> bool {
+        text.find_line(index)
+            .and_then(|(line, _)| text.line_is_rtl(line))
+            .unwrap_or(false)
+    }
+
+    /// Check whether text requiring size `req` is truncated within `avail`
+    ///
+    /// Text is truncated (clipped) when its required size, as returned by
+    /// [`TextApiExt::update_env`](super::TextApiExt::update_env) or
+    /// [`TextApi::prepare`], exceeds the available size on either axis.
+    /// Widgets may use this to provide the full text as a tooltip (see
+    /// [`crate::WidgetConfig::tooltip`]).
+    pub fn is_truncated(req: Vec2, avail: Size) -> bool {
+        let avail = Vec2::from(avail);
+        !(req.0 <= avail.0 && req.1 <= avail.1)
+    }
+
+    /// Text elision mode
+    ///
+    /// See [`set_text_elided`].
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Elide {
+        /// Remove text from the end (`"/usr/share/doc/k…"`)
+        End,
+        /// Remove text from the middle (`"/usr/sh…/README"`)
+        ///
+        /// This is preferable for path-like strings. At least `min_start` and
+        /// `min_end` grapheme clusters are kept at the start and end
+        /// respectively, even if the result does not fit.
+        Middle { min_start: usize, min_end: usize },
+    }
+
+    /// Elide `text` to `keep` grapheme clusters plus an ellipsis
+    ///
+    /// Returns `text` unchanged if it is no longer than this. The ellipsis is
+    /// enclosed by directional marks matching the base direction of `text`,
+    /// hence it remains between the kept parts under bidi reordering.
+    pub fn elide(text: &str, keep: usize, mode: Elide) -> String {
+        let bounds: Vec<usize> = text.grapheme_indices(true).map(|(i, _)| i).collect();
+        let len = bounds.len();
+        let (start, end) = match mode {
+            Elide::End => (keep, 0),
+            Elide::Middle { min_start, min_end } => {
+                let end = (keep / 2).max(min_end);
+                (keep.saturating_sub(end).max(min_start), end)
+            }
+        };
+        if start + end >= len {
+            return text.to_string();
+        }
+
+        let mark = if base_is_rtl(text) {
+            '\u{200F}'
+        } else {
+            '\u{200E}'
+        };
+        let mut s = String::with_capacity(text.len());
+        s.push_str(&text[..bounds[start]]);
+        s.push(mark);
+        s.push('…');
+        s.push(mark);
+        if end > 0 {
+            s.push_str(&text[bounds[len - end]..]);
+        }
+        s
+    }
+
+    /// Set `text` to `full`, elided as required to fit the available width
+    ///
+    /// The available width is taken from the text's bounds, hence these should
+    /// be set first (e.g. via [`TextApiExt::update_env`] in `set_rect`). Text
+    /// should not be line-wrapped. Returns true if text was elided.
+    pub fn set_text_elided(text: &mut Text<String>, full: &str, mode: Elide) -> bool {
+        let width = text.env().bounds.0;
+        let fits = |text: &mut Text<String>, s: String| {
+            text.set_text(s);
+            text.prepare().map(|req| req.0 <= width).unwrap_or(true)
+        };
+        if fits(text, full.to_string()) {
+            return false;
+        }
+
+        // Find the largest number of clusters to keep such that text fits
+        let (mut keep, mut too_long) = (0, full.graphemes(true).count());
+        while too_long - keep > 1 {
+            let mid = (keep + too_long) / 2;
+            if fits(text, elide(full, mid, mode)) {
+                keep = mid;
+            } else {
+                too_long = mid;
+            }
+        }
+        fits(text, elide(full, keep, mode));
+        true
+    }
+
+    /// Approximate the base direction via the first strong character
+    fn base_is_rtl(text: &str) -> bool {
+        for c in text.chars() {
+            match c as u32 {
+                0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF => return true,
+                _ if c.is_alphabetic() => return false,
+                _ => (),
+            }
+        }
+        false
+    }
 }
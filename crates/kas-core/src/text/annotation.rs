@@ -0,0 +1,141 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Text annotations
+
+use std::ops::Range;
+
+/// Class of a text annotation
+///
+/// This determines how the theme draws the annotation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AnnotationClass {
+    /// An error, e.g. a spelling mistake (usually a squiggly underline)
+    Error,
+    /// A warning (usually a squiggly underline)
+    Warning,
+    /// A hyperlink (usually a straight underline)
+    Hyperlink,
+}
+
+/// An annotation over a range of text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Annotation {
+    /// Byte range of the annotated text
+    pub range: Range<usize>,
+    /// Class
+    pub class: AnnotationClass,
+}
+
+impl Annotation {
+    /// Construct
+    #[inline]
+    pub fn new(range: Range<usize>, class: AnnotationClass) -> Self {
+        Annotation { range, class }
+    }
+}
+
+/// An action on an annotation
+///
+/// Widgets supporting annotations report these to their user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnnotationAction {
+    /// The mouse cursor moved onto annotation `index` (or off annotations)
+    Hover(Option<usize>),
+    /// Annotation `index` was clicked
+    Click(usize),
+}
+
+/// A list of annotations over some text
+///
+/// Annotations are kept in order of their start position. They may not
+/// overlap.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Annotations(Vec<Annotation>);
+
+impl Annotations {
+    /// Construct an empty list
+    #[inline]
+    pub fn new() -> Self {
+        Annotations(vec![])
+    }
+
+    /// True if there are no annotations
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the annotations as a slice
+    #[inline]
+    pub fn as_slice(&self) -> &[Annotation] {
+        &self.0
+    }
+
+    /// Get annotation `index`
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Annotation> {
+        self.0.get(index)
+    }
+
+    /// Replace all annotations
+    ///
+    /// Annotations are sorted by start position; empty and overlapping
+    /// annotations are removed.
+    pub fn set(&mut self, mut list: Vec<Annotation>) {
+        list.retain(|a| a.range.start < a.range.end);
+        list.sort_by_key(|a| a.range.start);
+        let mut end = 0;
+        list.retain(|a| {
+            let keep = a.range.start >= end;
+            if keep {
+                end = a.range.end;
+            }
+            keep
+        });
+        self.0 = list;
+    }
+
+    /// Remove all annotations
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Find the annotation containing byte `index`, if any
+    pub fn find(&self, index: usize) -> Option<usize> {
+        let i = self.0.partition_point(|a| a.range.end <= index);
+        self.0.get(i).filter(|a| a.range.start <= index).map(|_| i)
+    }
+
+    /// Adjust annotations for an edit from `old` to `new` text
+    ///
+    /// Annotations touching the edited range are removed; those following are
+    /// moved. This allows annotations to remain useful while the text is
+    /// edited, until the owner next updates them.
+    pub fn apply_edit(&mut self, old: &str, new: &str) {
+        if old == new || self.0.is_empty() {
+            return;
+        }
+        let (a, b) = (old.as_bytes(), new.as_bytes());
+        let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+        let max = a.len().min(b.len()) - prefix;
+        let suffix = (a.iter().rev().zip(b.iter().rev()))
+            .take(max)
+            .take_while(|(x, y)| x == y)
+            .count();
+        let old_end = a.len() - suffix;
+        let new_end = b.len() - suffix;
+
+        self.0
+            .retain(|ann| ann.range.end < prefix || ann.range.start > old_end);
+        for ann in &mut self.0 {
+            if ann.range.start > old_end {
+                ann.range.start = ann.range.start - old_end + new_end;
+                ann.range.end = ann.range.end - old_end + new_end;
+            }
+        }
+    }
+}
@@ -106,6 +106,39 @@ impl AccelString {
     pub fn text(&self) -> &str {
         &self.label
     }
+
+    /// Construct a copy with the accelerator key at byte `index`
+    ///
+    /// The char at `index` replaces any existing accelerator key(s). Returns
+    /// `None` if `index` is not the start of a char or if this char does not
+    /// correspond to any key.
+    pub fn with_accel_at(&self, index: usize) -> Option<Self> {
+        let c = self.label.get(index..)?.chars().next()?;
+        let keys = find_vkeys(c);
+        if keys.is_empty() {
+            return None;
+        }
+
+        let pos = u32::conv(index);
+        let mut effects = SmallVec::new();
+        effects.push(Effect {
+            start: pos,
+            flags: EffectFlags::UNDERLINE,
+            aux: (),
+        });
+        if index + c.len_utf8() < self.label.len() {
+            effects.push(Effect {
+                start: pos + u32::conv(c.len_utf8()),
+                flags: EffectFlags::empty(),
+                aux: (),
+            });
+        }
+        Some(AccelString {
+            label: self.label.clone(),
+            effects,
+            keys,
+        })
+    }
 }
 
 impl FormattableText for AccelString {
@@ -15,25 +15,266 @@
 use crate::draw::{DrawShared, SizeHandle, ThemeApi};
 use crate::event;
 use crate::event::UpdateHandle;
+use crate::geom::{Coord, Size};
+use crate::WidgetId;
+use image::RgbaImage;
+use raw_window_handle::RawWindowHandle;
+use std::fmt;
 use std::num::NonZeroU32;
 
 /// Identifier for a window or pop-up
 ///
 /// Identifiers should always be unique.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WindowId(NonZeroU32);
 
 impl WindowId {
     /// Construct a [`WindowId`]
     ///
     /// Only for use by the shell!
-    #[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
-    #[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
     pub fn new(n: NonZeroU32) -> WindowId {
         WindowId(n)
     }
 }
 
+/// Description of a monitor (display)
+///
+/// Monitors may be enumerated via [`event::Manager::monitors`]. Position and
+/// size are in physical pixels on the virtual desktop.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorInfo {
+    /// Monitor name, if known
+    pub name: Option<String>,
+    /// Position of the top-left corner
+    pub position: Coord,
+    /// Size (resolution)
+    pub size: Size,
+    /// The scale factor (DPI factor) used for windows on this monitor
+    pub scale_factor: f64,
+}
+
+/// Initial placement of a window
+///
+/// This is returned by [`crate::Window::placement`] and applied by the shell
+/// when the window is created. By default, the shell chooses the monitor and
+/// position.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WindowPlacement {
+    /// Index of the monitor to open on (see [`event::Manager::monitors`])
+    ///
+    /// If the index is out of range, the default monitor is used.
+    pub monitor: Option<usize>,
+    /// Position of the window, relative to the monitor's top-left corner
+    ///
+    /// This is in physical pixels. If `None`, the shell chooses the position.
+    pub position: Option<Coord>,
+    /// Whether to open the window as (borderless) fullscreen on the monitor
+    pub fullscreen: bool,
+}
+
+impl WindowPlacement {
+    /// Open on the given monitor
+    #[inline]
+    pub fn on_monitor(monitor: usize) -> Self {
+        WindowPlacement {
+            monitor: Some(monitor),
+            ..Default::default()
+        }
+    }
+
+    /// Open fullscreen on the given monitor
+    #[inline]
+    pub fn fullscreen_on(monitor: usize) -> Self {
+        WindowPlacement {
+            monitor: Some(monitor),
+            position: None,
+            fullscreen: true,
+        }
+    }
+
+    /// Set the position, relative to the monitor
+    #[inline]
+    pub fn with_position(mut self, position: Coord) -> Self {
+        self.position = Some(position);
+        self
+    }
+}
+
+/// Attributes of a window
+///
+/// This is returned by [`crate::Window::attributes`] and used by the shell
+/// when creating the window. Size limits are also re-applied whenever the
+/// window is resized. Other attributes may be adjusted at run-time via
+/// [`event::Manager`] (e.g. [`event::Manager::set_decorations`]).
+///
+/// Not all attributes are supported by all shells and platforms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WindowAttributes {
+    /// Whether the window has decorations (title bar, border)
+    pub decorations: bool,
+    /// Whether the window background is transparent
+    ///
+    /// Transparency must also be supported by the theme (background colour).
+    pub transparent: bool,
+    /// Whether the window may be resized by the user
+    pub resizable: bool,
+    /// Whether the window is kept above other windows
+    pub always_on_top: bool,
+    /// Initial inner size (physical pixels)
+    ///
+    /// If `None`, the ideal size from the window's size rules is used.
+    pub inner_size: Option<Size>,
+    /// Minimum inner size (physical pixels)
+    ///
+    /// If `None`, the minimum size is derived from the window's size rules
+    /// when [`crate::Window::restrict_dimensions`] allows.
+    pub min_size: Option<Size>,
+    /// Maximum inner size (physical pixels)
+    ///
+    /// If `None`, the maximum size is derived from the window's size rules
+    /// when [`crate::Window::restrict_dimensions`] allows.
+    pub max_size: Option<Size>,
+}
+
+impl Default for WindowAttributes {
+    fn default() -> Self {
+        WindowAttributes {
+            decorations: true,
+            transparent: false,
+            resizable: true,
+            always_on_top: false,
+            inner_size: None,
+            min_size: None,
+            max_size: None,
+        }
+    }
+}
+
+impl WindowAttributes {
+    /// Construct with default values
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the window has decorations
+    #[inline]
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    /// Set whether the window is transparent
+    #[inline]
+    pub fn with_transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    /// Set whether the window is resizable
+    #[inline]
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Set whether the window is kept above others
+    #[inline]
+    pub fn with_always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    /// Set the initial inner size
+    #[inline]
+    pub fn with_inner_size(mut self, size: Size) -> Self {
+        self.inner_size = Some(size);
+        self
+    }
+
+    /// Override the minimum inner size
+    #[inline]
+    pub fn with_min_size(mut self, size: Size) -> Self {
+        self.min_size = Some(size);
+        self
+    }
+
+    /// Override the maximum inner size
+    #[inline]
+    pub fn with_max_size(mut self, size: Size) -> Self {
+        self.max_size = Some(size);
+        self
+    }
+
+    /// Resolve size limits given the window's size rules
+    ///
+    /// Inputs are the calculated minimum and ideal sizes and the result of
+    /// [`crate::Window::restrict_dimensions`]. Returns `(min, max)`.
+    pub fn size_limits(
+        &self,
+        min: Size,
+        ideal: Size,
+        restrict: (bool, bool),
+    ) -> (Option<Size>, Option<Size>) {
+        let min = self.min_size.or_else(|| restrict.0.then(|| min));
+        let max = self.max_size.or_else(|| restrict.1.then(|| ideal));
+        (min, max)
+    }
+}
+
+/// Edge or corner of a window, used for resizing
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResizeDirection {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl ResizeDirection {
+    /// All directions, clockwise from `North`
+    pub const ALL: [ResizeDirection; 8] = [
+        ResizeDirection::North,
+        ResizeDirection::NorthEast,
+        ResizeDirection::East,
+        ResizeDirection::SouthEast,
+        ResizeDirection::South,
+        ResizeDirection::SouthWest,
+        ResizeDirection::West,
+        ResizeDirection::NorthWest,
+    ];
+
+    /// The cursor icon appropriate to resizing in this direction
+    pub fn cursor_icon(self) -> event::CursorIcon {
+        use event::CursorIcon;
+        match self {
+            ResizeDirection::North => CursorIcon::NResize,
+            ResizeDirection::NorthEast => CursorIcon::NeResize,
+            ResizeDirection::East => CursorIcon::EResize,
+            ResizeDirection::SouthEast => CursorIcon::SeResize,
+            ResizeDirection::South => CursorIcon::SResize,
+            ResizeDirection::SouthWest => CursorIcon::SwResize,
+            ResizeDirection::West => CursorIcon::WResize,
+            ResizeDirection::NorthWest => CursorIcon::NwResize,
+        }
+    }
+
+    /// True if this moves the window's top or left edge
+    ///
+    /// Such resizes also move the window origin.
+    pub fn moves_origin(self) -> bool {
+        !matches!(
+            self,
+            ResizeDirection::East | ResizeDirection::SouthEast | ResizeDirection::South
+        )
+    }
+}
+
 bitflags! {
     /// Action required after processing
     ///
@@ -87,12 +328,141 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Features supported by the shell
+    ///
+    /// Shells (and even individual windows) differ in which features they
+    /// support. Widgets may query this via
+    /// [`event::Manager::shell_capabilities`] in order to adapt, e.g. by
+    /// offering an alternative to a pop-up menu. Where a feature is not
+    /// supported, the corresponding [`event::Manager`] methods do nothing or
+    /// return `None` / `false`.
+    #[derive(Default)]
+    pub struct ShellCapabilities: u32 {
+        /// Access to the system clipboard
+        const CLIPBOARD = 1 << 0;
+        /// Pop-ups (see [`event::Manager::add_popup`])
+        const POPUPS = 1 << 1;
+        /// Pop-ups are presented as separate (borderless) windows
+        ///
+        /// Otherwise, pop-ups are drawn as a layer within the parent window and
+        /// are restricted to its bounds.
+        const POPUP_WINDOWS = 1 << 2;
+        /// Additional windows (see [`event::Manager::add_window`])
+        const WINDOWS = 1 << 3;
+        /// Opening URLs in an external application
+        const OPEN_URL = 1 << 4;
+        /// Enumeration of monitors
+        const MONITORS = 1 << 5;
+        /// Control of window decorations, size and state
+        ///
+        /// This covers [`event::Manager::set_decorations`],
+        /// [`event::Manager::set_window_size`], minimize and maximize.
+        const WINDOW_CONTROL = 1 << 6;
+        /// Moving the window via [`event::Manager::drag_window`]
+        const DRAG_WINDOW = 1 << 7;
+        /// Resizing the window via [`event::Manager::drag_resize_window`]
+        const DRAG_RESIZE = 1 << 8;
+        /// Input method (IME) support for text entry
+        const IME = 1 << 9;
+        /// Native file dialogs
+        const FILE_DIALOGS = 1 << 10;
+        /// Capture of rendered output (see [`event::Manager::capture_window`])
+        const CAPTURE = 1 << 11;
+        /// Access to the native window handle, allowing embedding of native
+        /// child surfaces (see [`event::Manager::raw_window_handle`])
+        const NATIVE_SURFACES = 1 << 12;
+    }
+}
+
+/// A request to capture rendered output
+///
+/// This is constructed by [`event::Manager::capture_window`] and
+/// [`event::Manager::capture`] and passed to [`ShellWindow::capture`].
+///
+/// If dropped without calling [`CaptureRequest::finish`] (e.g. because the
+/// window was closed), the request completes with `None` and the hook set by
+/// [`CaptureRequest::set_drop_hook`] (if any) is called with the update handle.
+pub struct CaptureRequest {
+    /// The window to capture, or `None` for the current window
+    pub window: Option<WindowId>,
+    /// The widget to capture, or `None` for the whole window
+    pub widget: Option<WidgetId>,
+    finish: Option<Box<dyn FnOnce(Option<RgbaImage>)>>,
+    handle: UpdateHandle,
+    drop_hook: Option<Box<dyn FnOnce(UpdateHandle)>>,
+}
+
+impl CaptureRequest {
+    pub(crate) fn new(
+        window: Option<WindowId>,
+        widget: Option<WidgetId>,
+        finish: Box<dyn FnOnce(Option<RgbaImage>)>,
+        handle: UpdateHandle,
+    ) -> Self {
+        CaptureRequest {
+            window,
+            widget,
+            finish: Some(finish),
+            handle,
+            drop_hook: None,
+        }
+    }
+
+    /// Set a hook to trigger the update handle if the request is dropped
+    ///
+    /// Shells which may drop requests without calling [`Self::finish`] should
+    /// set this hook in order to trigger the given [`UpdateHandle`] (with
+    /// payload 0) at the next opportunity.
+    pub fn set_drop_hook(&mut self, hook: Box<dyn FnOnce(UpdateHandle)>) {
+        self.drop_hook = Some(hook);
+    }
+
+    /// Complete the request
+    ///
+    /// Pass the captured `image`, or `None` on failure. The shell must then
+    /// trigger the returned [`UpdateHandle`] (with payload 0).
+    pub fn finish(mut self, image: Option<RgbaImage>) -> UpdateHandle {
+        if let Some(finish) = self.finish.take() {
+            finish(image);
+        }
+        self.drop_hook = None;
+        self.handle
+    }
+}
+
+impl Drop for CaptureRequest {
+    fn drop(&mut self) {
+        if let Some(finish) = self.finish.take() {
+            finish(None);
+            if let Some(hook) = self.drop_hook.take() {
+                hook(self.handle);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for CaptureRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CaptureRequest")
+            .field("window", &self.window)
+            .field("widget", &self.widget)
+            .field("handle", &self.handle)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Shell-specific window management and style interface.
 ///
 /// This is implemented by a KAS shell, per window.
-#[cfg_attr(not(feature = "internal_doc"), doc(hidden))]
-#[cfg_attr(doc_cfg, doc(cfg(internal_doc)))]
 pub trait ShellWindow {
+    /// Get the features supported by this shell (for this window)
+    ///
+    /// The default implementation returns [`ShellCapabilities::empty`].
+    fn capabilities(&self) -> ShellCapabilities {
+        ShellCapabilities::empty()
+    }
+
     /// Add a pop-up
     ///
     /// A pop-up may be presented as an overlay layer in the current window or
@@ -132,6 +502,37 @@ pub trait ShellWindow {
     /// Attempt to set clipboard contents
     fn set_clipboard(&mut self, content: String);
 
+    /// Attempt to set clipboard contents, excluding from clipboard history
+    ///
+    /// Shells integrating with a platform clipboard history (or sync) service
+    /// should mark `content` as excluded where this is supported. The default
+    /// implementation simply calls [`ShellWindow::set_clipboard`].
+    fn set_clipboard_sensitive(&mut self, content: String) {
+        self.set_clipboard(content);
+    }
+
+    /// Attempt to open `url` with the system's default handler
+    ///
+    /// Typically this opens a web browser. Returns false if this is not
+    /// supported or failed (though a true result does not guarantee that the
+    /// URL was opened). The default implementation returns false.
+    fn open_url(&mut self, url: &str) -> bool {
+        let _ = url;
+        false
+    }
+
+    /// Capture rendered output
+    ///
+    /// The shell should render the requested window (or widget) to an image,
+    /// then complete the `request` via [`CaptureRequest::finish`]. This may
+    /// happen asynchronously (e.g. after the next frame is drawn).
+    ///
+    /// The default implementation completes the request with `None`.
+    fn capture(&mut self, request: CaptureRequest) {
+        let handle = request.finish(None);
+        self.trigger_update(handle, 0);
+    }
+
     /// Adjust the theme
     ///
     /// Note: theme adjustments apply to all windows, as does the [`TkAction`]
@@ -154,4 +555,151 @@ pub trait ShellWindow {
 
     /// Set the mouse cursor
     fn set_cursor_icon(&mut self, icon: event::CursorIcon);
+
+    /// Enumerate available monitors
+    ///
+    /// The default implementation returns an empty list (unknown).
+    fn monitors(&self) -> Vec<MonitorInfo> {
+        vec![]
+    }
+
+    /// Index of the monitor currently showing this window, if known
+    ///
+    /// This is an index into the list returned by [`ShellWindow::monitors`].
+    fn current_monitor(&self) -> Option<usize> {
+        None
+    }
+
+    /// Set whether the window has decorations
+    ///
+    /// The default implementation does nothing.
+    fn set_decorations(&mut self, _decorations: bool) {}
+
+    /// Set whether the window is resizable
+    ///
+    /// The default implementation does nothing.
+    fn set_resizable(&mut self, _resizable: bool) {}
+
+    /// Set whether the window is kept above other windows
+    ///
+    /// The default implementation does nothing.
+    fn set_always_on_top(&mut self, _always_on_top: bool) {}
+
+    /// Request a new inner size (physical pixels)
+    ///
+    /// The default implementation does nothing.
+    fn set_inner_size(&mut self, _size: Size) {}
+
+    /// Start moving the window with the pressed mouse button
+    ///
+    /// This should be called on a mouse press. The default implementation
+    /// does nothing.
+    fn drag_window(&mut self) {}
+
+    /// Start resizing the window with the pressed mouse button
+    ///
+    /// Returns false if unsupported (the default), in which case the caller
+    /// may resize the window via [`ShellWindow::set_inner_size`] instead.
+    fn drag_resize_window(&mut self, _direction: ResizeDirection) -> bool {
+        false
+    }
+
+    /// Minimize the window
+    ///
+    /// The default implementation does nothing.
+    fn set_minimized(&mut self) {}
+
+    /// Set whether the window is maximized
+    ///
+    /// The default implementation does nothing.
+    fn set_maximized(&mut self, _maximized: bool) {}
+
+    /// Whether the window is maximized
+    ///
+    /// The default implementation returns false.
+    fn is_maximized(&self) -> bool {
+        false
+    }
+
+    /// Get the platform-specific handle of the native window
+    ///
+    /// The default implementation returns `None`.
+    fn raw_window_handle(&self) -> Option<RawWindowHandle> {
+        None
+    }
+
+    /// Load persisted UI state (see [`crate::state`])
+    ///
+    /// The default implementation returns `None`.
+    fn load_state(&self, _key: &str) -> Option<String> {
+        None
+    }
+
+    /// Save persisted UI state (see [`crate::state`])
+    ///
+    /// The default implementation does nothing.
+    fn save_state(&mut self, _key: &str, _value: String) {}
+
+    /// Resolve a localized message (see [`crate::l10n`])
+    ///
+    /// The default implementation returns `None`.
+    fn translate(&self, _key: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the current locale, if a localizer is configured
+    ///
+    /// The default implementation returns `None`.
+    fn locale(&self) -> Option<String> {
+        None
+    }
+
+    /// Switch the current locale
+    ///
+    /// If the locale changes, all windows should be reconfigured
+    /// ([`TkAction::RECONFIGURE`]). The default implementation does nothing.
+    fn set_locale(&mut self, _locale: &str) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Future;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn capture_dropped_with_window() {
+        let (future, finish) = Future::new_pending();
+        let handle = UpdateHandle::new();
+        let mut request = CaptureRequest::new(None, None, finish, handle);
+
+        let triggered = Rc::new(Cell::new(None));
+        let t2 = triggered.clone();
+        request.set_drop_hook(Box::new(move |handle| t2.set(Some(handle))));
+
+        // A window holding a pending capture is closed
+        let pending = vec![request];
+        drop(pending);
+
+        assert_eq!(triggered.get(), Some(handle));
+        assert!(future.is_finished());
+        assert!(future.try_finish().unwrap().is_none());
+    }
+
+    #[test]
+    fn capture_finished() {
+        let (future, finish) = Future::new_pending();
+        let handle = UpdateHandle::new();
+        let mut request = CaptureRequest::new(None, None, finish, handle);
+
+        let triggered = Rc::new(Cell::new(false));
+        let t2 = triggered.clone();
+        request.set_drop_hook(Box::new(move |_| t2.set(true)));
+
+        let image = RgbaImage::new(1, 1);
+        assert_eq!(request.finish(Some(image)), handle);
+        assert!(!triggered.get());
+        assert!(future.try_finish().unwrap().is_some());
+    }
 }
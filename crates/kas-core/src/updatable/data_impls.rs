@@ -8,7 +8,7 @@
 use super::*;
 use crate::event::UpdateHandle;
 use std::fmt::Debug;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, Range};
 
 impl<T: Clone + Debug> ListData for [T] {
     type Key = usize;
@@ -128,6 +128,19 @@ macro_rules! impl_via_deref {
             fn iter_vec_from(&self, start: usize, limit: usize) -> Vec<(Self::Key, Self::Item)> {
                 self.deref().iter_vec_from(start, limit)
             }
+
+            fn logical_len(&self) -> usize {
+                self.deref().logical_len()
+            }
+            fn iter_vec_range(&self, range: Range<usize>) -> Vec<(usize, Self::Key, Self::Item)> {
+                self.deref().iter_vec_range(range)
+            }
+            fn is_group_header(&self, key: &Self::Key) -> bool {
+                self.deref().is_group_header(key)
+            }
+            fn group_header_of(&self, index: usize) -> Option<(usize, Self::Key, Self::Item)> {
+                self.deref().group_header_of(index)
+            }
         }
 
         impl<$t: MatrixData + ?Sized> MatrixData for $derived {
@@ -11,6 +11,7 @@ use crate::updatable::Updatable;
 #[allow(unused)] // doc links
 use std::cell::RefCell;
 use std::fmt::Debug;
+use std::ops::Range;
 
 /// Trait for viewable single data items
 // Note: we require Debug + 'static to allow widgets using this to implement
@@ -98,6 +99,72 @@ pub trait ListData: Debug {
     ///
     /// The result is the same as `self.iter_vec(start + limit).skip(start)`.
     fn iter_vec_from(&self, start: usize, limit: usize) -> Vec<(Self::Key, Self::Item)>;
+
+    /// Number of logical positions
+    ///
+    /// Dense data sources place item `i` (as yielded by
+    /// [`ListData::iter_vec_from`]) at position `i`; for these the default
+    /// implementation returning [`ListData::len`] is correct.
+    ///
+    /// Sparse data sources may leave gaps between items. These should return
+    /// one more than the position of the last item and implement
+    /// [`ListData::iter_vec_range`]. View widgets size their content from this
+    /// bound, showing an (optional) placeholder at each empty position.
+    fn logical_len(&self) -> usize {
+        self.len()
+    }
+
+    /// Iterate over (position, key, value) tuples as a vec
+    ///
+    /// Yields all items with a logical position within `range`, in order of
+    /// position. Positions without an item are omitted.
+    ///
+    /// The default implementation assumes dense data (see
+    /// [`ListData::logical_len`]).
+    fn iter_vec_range(&self, range: Range<usize>) -> Vec<(usize, Self::Key, Self::Item)> {
+        let start = range.start;
+        self.iter_vec_from(start, range.len())
+            .into_iter()
+            .enumerate()
+            .map(|(i, (key, item))| (start + i, key, item))
+            .collect()
+    }
+
+    /// Check whether the item at `key` is a group header
+    ///
+    /// Group headers are drawn distinctly by view widgets (see
+    /// `Driver::set_header`) and may not be selected. The default
+    /// implementation returns `false`.
+    fn is_group_header(&self, key: &Self::Key) -> bool {
+        let _ = key;
+        false
+    }
+
+    /// Find the group header of the item at position `index`
+    ///
+    /// Returns the (position, key, value) of the last group header at or before
+    /// position `index`, if any. This is used by views to draw "sticky" group
+    /// headers.
+    ///
+    /// The default implementation searches backwards using
+    /// [`ListData::is_group_header`], thus is `O(n)` in the size of the group.
+    fn group_header_of(&self, index: usize) -> Option<(usize, Self::Key, Self::Item)> {
+        const CHUNK: usize = 32;
+        let mut end = index.saturating_add(1).min(self.logical_len());
+        while end > 0 {
+            let start = end.saturating_sub(CHUNK);
+            let items = self.iter_vec_range(start..end);
+            let header = items
+                .into_iter()
+                .rev()
+                .find(|item| self.is_group_header(&item.1));
+            if header.is_some() {
+                return header;
+            }
+            end = start;
+        }
+        None
+    }
 }
 
 /// Trait for writable data lists
@@ -109,6 +176,11 @@ pub trait ListDataMut: ListData {
 /// Trait for viewable data matrices
 ///
 /// Data matrices are a kind of table where each cell has the same type.
+///
+/// Matrices may be sparse: [`MatrixData::col_len`] and [`MatrixData::row_len`]
+/// give the logical bounds of the table, while [`MatrixData::contains`] and
+/// [`MatrixData::get_cloned`] need only succeed for cells with data. View
+/// widgets show an (optional) placeholder for empty cells.
 pub trait MatrixData: Debug {
     /// Column key type
     type ColKey: Clone + Debug + PartialEq + Eq;
@@ -134,7 +206,8 @@ pub trait MatrixData: Debug {
 
     /// Get data by key (clone)
     ///
-    /// It is expected that this method succeeds when both keys are valid.
+    /// For dense matrices, it is expected that this method succeeds when both
+    /// keys are valid. Sparse matrices return `None` for empty cells.
     fn get_cloned(&self, key: &Self::Key) -> Option<Self::Item>;
 
     /// Update data, if supported
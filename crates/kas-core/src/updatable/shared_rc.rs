@@ -16,6 +16,7 @@ use crate::event::UpdateHandle;
 use crate::updatable::*;
 use std::cell::RefCell;
 use std::fmt::Debug;
+use std::ops::Range;
 use std::rc::Rc;
 
 /// Wrapper for single-thread shared data
@@ -92,6 +93,22 @@ impl<T: ListDataMut> ListData for SharedRc<T> {
     fn iter_vec_from(&self, start: usize, limit: usize) -> Vec<(Self::Key, Self::Item)> {
         (self.0).1.borrow().iter_vec_from(start, limit)
     }
+
+    fn logical_len(&self) -> usize {
+        (self.0).1.borrow().logical_len()
+    }
+
+    fn iter_vec_range(&self, range: Range<usize>) -> Vec<(usize, Self::Key, Self::Item)> {
+        (self.0).1.borrow().iter_vec_range(range)
+    }
+
+    fn is_group_header(&self, key: &Self::Key) -> bool {
+        (self.0).1.borrow().is_group_header(key)
+    }
+
+    fn group_header_of(&self, index: usize) -> Option<(usize, Self::Key, Self::Item)> {
+        (self.0).1.borrow().group_header_of(index)
+    }
 }
 impl<T: ListDataMut> ListDataMut for SharedRc<T> {
     fn set(&mut self, key: &Self::Key, item: Self::Item) {
@@ -5,8 +5,22 @@
 
 //! Utilities
 
+use crate::{WidgetChildren, WidgetId};
 use std::fmt;
 
+/// Find the index of the child which is an ancestor of (or is) `id`
+///
+/// The `children` must be listed in order (see
+/// [`WidgetChildren#child-order`]); a binary search is used. This replaces
+/// manual scanning over children (`id <= child.id()`).
+pub fn find_child_index<W: WidgetChildren>(children: &[W], id: WidgetId) -> Option<usize> {
+    let index = children.partition_point(|child| child.id() < id);
+    children
+        .get(index)
+        .filter(|child| child.is_ancestor_of(id))
+        .map(|_| index)
+}
+
 /// Format for types supporting Debug
 ///
 /// This requires the "spec" feature and nightly rustc to be useful.
@@ -0,0 +1,55 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Tests for text utilities
+//!
+//! Text preparation requires fonts: these tests load the default system font.
+
+use kas_core::text::{fonts, util, Text, TextApi};
+
+fn prepare(s: &str) -> Text<String> {
+    if let Err(e) = fonts::fonts().select_default() {
+        panic!("Error loading font: {}", e);
+    }
+    let mut text = Text::new_multi(s.to_string());
+    text.prepare();
+    text
+}
+
+#[test]
+fn line_is_rtl_ltr() {
+    let text = prepare("Hello world");
+    assert!(!util::line_is_rtl(&text, 0));
+    assert!(!util::line_is_rtl(&text, 6));
+    assert!(!util::line_is_rtl(&text, text.as_str().len()));
+}
+
+#[test]
+fn line_is_rtl_rtl() {
+    let text = prepare("שלום עולם");
+    assert!(util::line_is_rtl(&text, 0));
+    assert!(util::line_is_rtl(&text, text.as_str().len()));
+}
+
+#[test]
+fn line_is_rtl_mixed() {
+    // An RTL paragraph starting or ending with a Latin run is still RTL
+    let text = prepare("שלום world");
+    assert!(util::line_is_rtl(&text, 0));
+    assert!(util::line_is_rtl(&text, text.as_str().len()));
+    let text = prepare("\u{200F}world שלום");
+    assert!(util::line_is_rtl(&text, 0));
+    assert!(util::line_is_rtl(&text, 4));
+
+    // An LTR paragraph ending with a Hebrew run is LTR
+    let text = prepare("Hello עולם");
+    assert!(!util::line_is_rtl(&text, 0));
+    assert!(!util::line_is_rtl(&text, text.as_str().len()));
+
+    // Direction is resolved per paragraph
+    let text = prepare("Hello\nשלום");
+    assert!(!util::line_is_rtl(&text, 2));
+    assert!(util::line_is_rtl(&text, 8));
+}
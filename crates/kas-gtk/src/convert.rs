@@ -0,0 +1,163 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Translation of GDK input types
+
+use gtk::gdk::keys::{constants as key, Key};
+use gtk::gdk::ModifierType;
+use kas::event::{CursorIcon, ModifiersState, MouseButton, VirtualKeyCode};
+
+/// Translate a GDK key value
+pub(crate) fn vkey(keyval: &Key) -> Option<VirtualKeyCode> {
+    use VirtualKeyCode::*;
+
+    if let Some(c) = keyval.to_lower().to_unicode() {
+        const LETTERS: [VirtualKeyCode; 26] = [
+            A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+        ];
+        const DIGITS: [VirtualKeyCode; 10] =
+            [Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9];
+        match c {
+            'a'..='z' => return Some(LETTERS[usize::from(c as u8 - b'a')]),
+            '0'..='9' => return Some(DIGITS[usize::from(c as u8 - b'0')]),
+            _ => (),
+        }
+    }
+
+    let map = [
+        (key::Escape, Escape),
+        (key::Return, Return),
+        (key::KP_Enter, NumpadEnter),
+        (key::Tab, Tab),
+        (key::ISO_Left_Tab, Tab),
+        (key::BackSpace, Back),
+        (key::Delete, Delete),
+        (key::Insert, Insert),
+        (key::Home, Home),
+        (key::End, End),
+        (key::Page_Up, PageUp),
+        (key::Page_Down, PageDown),
+        (key::Left, Left),
+        (key::Right, Right),
+        (key::Up, Up),
+        (key::Down, Down),
+        (key::space, Space),
+        (key::F1, F1),
+        (key::F2, F2),
+        (key::F3, F3),
+        (key::F4, F4),
+        (key::F5, F5),
+        (key::F6, F6),
+        (key::F7, F7),
+        (key::F8, F8),
+        (key::F9, F9),
+        (key::F10, F10),
+        (key::F11, F11),
+        (key::F12, F12),
+        (key::Print, Snapshot),
+        (key::Scroll_Lock, Scroll),
+        (key::Pause, Pause),
+        (key::Num_Lock, Numlock),
+        (key::Caps_Lock, Capital),
+        (key::Menu, Apps),
+        (key::Shift_L, LShift),
+        (key::Shift_R, RShift),
+        (key::Control_L, LControl),
+        (key::Control_R, RControl),
+        (key::Alt_L, LAlt),
+        (key::Alt_R, RAlt),
+        (key::Super_L, LWin),
+        (key::Super_R, RWin),
+        (key::KP_Add, NumpadAdd),
+        (key::KP_Subtract, NumpadSubtract),
+        (key::KP_Multiply, NumpadMultiply),
+        (key::KP_Divide, NumpadDivide),
+        (key::KP_Decimal, NumpadDecimal),
+        (key::minus, Minus),
+        (key::equal, Equals),
+        (key::comma, Comma),
+        (key::period, Period),
+        (key::slash, Slash),
+        (key::backslash, Backslash),
+        (key::semicolon, Semicolon),
+        (key::apostrophe, Apostrophe),
+        (key::grave, Grave),
+        (key::bracketleft, LBracket),
+        (key::bracketright, RBracket),
+        (key::Copy, Copy),
+        (key::Paste, Paste),
+        (key::Cut, Cut),
+    ];
+    map.iter().find(|(k, _)| k == keyval).map(|(_, vk)| *vk)
+}
+
+/// Translate GDK modifier state
+pub(crate) fn modifiers(state: ModifierType) -> ModifiersState {
+    let mut result = ModifiersState::empty();
+    if state.contains(ModifierType::SHIFT_MASK) {
+        result |= ModifiersState::SHIFT;
+    }
+    if state.contains(ModifierType::CONTROL_MASK) {
+        result |= ModifiersState::CTRL;
+    }
+    if state.contains(ModifierType::MOD1_MASK) {
+        result |= ModifiersState::ALT;
+    }
+    if state.contains(ModifierType::SUPER_MASK) {
+        result |= ModifiersState::LOGO;
+    }
+    result
+}
+
+/// Translate a GDK mouse button number
+pub(crate) fn mouse_button(button: u32) -> MouseButton {
+    match button {
+        1 => MouseButton::Left,
+        2 => MouseButton::Middle,
+        3 => MouseButton::Right,
+        n => MouseButton::Other(n as u16),
+    }
+}
+
+/// Get the CSS cursor name for a [`CursorIcon`]
+pub(crate) fn cursor_name(icon: CursorIcon) -> &'static str {
+    use CursorIcon::*;
+    match icon {
+        Default | Arrow => "default",
+        Crosshair => "crosshair",
+        Hand => "pointer",
+        Move => "move",
+        Text => "text",
+        Wait => "wait",
+        Help => "help",
+        Progress => "progress",
+        NotAllowed => "not-allowed",
+        ContextMenu => "context-menu",
+        Cell => "cell",
+        VerticalText => "vertical-text",
+        Alias => "alias",
+        Copy => "copy",
+        NoDrop => "no-drop",
+        Grab => "grab",
+        Grabbing => "grabbing",
+        AllScroll => "all-scroll",
+        ZoomIn => "zoom-in",
+        ZoomOut => "zoom-out",
+        EResize => "e-resize",
+        NResize => "n-resize",
+        NeResize => "ne-resize",
+        NwResize => "nw-resize",
+        SResize => "s-resize",
+        SeResize => "se-resize",
+        SwResize => "sw-resize",
+        WResize => "w-resize",
+        EwResize => "ew-resize",
+        NsResize => "ns-resize",
+        NeswResize => "nesw-resize",
+        NwseResize => "nwse-resize",
+        ColResize => "col-resize",
+        RowResize => "row-resize",
+    }
+}
@@ -0,0 +1,774 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Drawing API for `kas_gtk`
+//!
+//! Draw commands are recorded per window, then replayed onto a Cairo context
+//! from the GTK draw signal. Glyphs are rastered via `kas_text` and cached as
+//! alpha masks.
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use gtk::cairo::{self, Context, FillRule, Format, ImageSurface, LineCap, LineJoin};
+use gtk::cairo::{LinearGradient, Matrix, RadialGradient};
+use kas::cast::Cast;
+use kas::draw::color::{Rgba, Rgba8Srgb};
+use kas::draw::*;
+use kas::geom::{Coord, Offset, Quad, Rect, Size, Transform, Vec2};
+use kas::text::fonts::FaceId;
+use kas::text::{Effect, Glyph, TextDisplay};
+use kas_text::raster::{raster, Config, SpriteDescriptor};
+use kas_theme::RasterConfig;
+
+/// A rastered glyph
+#[derive(Clone)]
+struct Sprite {
+    surface: ImageSurface,
+    offset: Vec2,
+}
+
+/// Shared draw state
+pub struct DrawPipe {
+    config: Config,
+    glyphs: HashMap<SpriteDescriptor, Option<Sprite>>,
+    images: HashMap<ImageId, ImageSurface>,
+    next_image_id: u32,
+}
+
+impl DrawPipe {
+    /// Construct
+    pub fn new(config: &RasterConfig) -> Self {
+        DrawPipe {
+            config: Config::new(
+                config.mode,
+                config.scale_steps,
+                config.subpixel_threshold,
+                config.subpixel_steps,
+            ),
+            glyphs: Default::default(),
+            images: Default::default(),
+            next_image_id: 0,
+        }
+    }
+
+    /// Construct per-window state
+    pub fn new_window(&self) -> DrawWindow {
+        DrawWindow::default()
+    }
+
+    /// Get a rendered sprite
+    ///
+    /// This returns `None` if there's nothing to render.
+    fn get_glyph(&mut self, face: FaceId, dpem: f32, glyph: Glyph) -> Option<Sprite> {
+        let desc = SpriteDescriptor::new(&self.config, face, glyph, dpem);
+        if let Some(opt_sprite) = self.glyphs.get(&desc).cloned() {
+            opt_sprite
+        } else {
+            self.raster_glyph(desc)
+        }
+    }
+
+    fn raster_glyph(&mut self, desc: SpriteDescriptor) -> Option<Sprite> {
+        let mut sprite = None;
+        if let Some(rs) = raster(&self.config, desc).filter(|rs| rs.size.0 * rs.size.1 > 0) {
+            let (w, h) = rs.size;
+            match ImageSurface::create(Format::A8, w.cast(), h.cast()) {
+                Ok(mut surface) => {
+                    let stride: usize = surface.stride().cast();
+                    if let Ok(mut data) = surface.data() {
+                        let w: usize = w.cast();
+                        for (row, src) in rs.data.chunks_exact(w).enumerate() {
+                            data[row * stride..row * stride + w].copy_from_slice(src);
+                        }
+                    }
+                    sprite = Some(Sprite {
+                        surface,
+                        offset: Vec2(rs.offset.0.cast(), rs.offset.1.cast()),
+                    });
+                }
+                Err(e) => {
+                    log::warn!(
+                        "draw: failed to allocate glyph with size {:?}: {}",
+                        rs.size,
+                        e
+                    );
+                }
+            }
+        } else {
+            // This comes up a lot and is usually harmless
+            log::debug!(
+                "Failed to raster glyph {:?} of face {:?}",
+                desc.glyph(),
+                desc.face()
+            );
+        }
+
+        self.glyphs.insert(desc, sprite.clone());
+        sprite
+    }
+
+    fn text(
+        &mut self,
+        draw: &mut DrawWindow,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        col: Rgba,
+    ) {
+        let pos = pos.round();
+        text.glyphs(|face, dpem, glyph| {
+            if let Some(sprite) = self.get_glyph(face, dpem, glyph) {
+                let pos = pos + Vec2::from(glyph.position).floor() + sprite.offset;
+                draw.push_text(pass, Op::Glyph(sprite.surface, pos, col));
+            }
+        });
+    }
+}
+
+impl DrawSharedImpl for DrawPipe {
+    type Draw = DrawWindow;
+
+    fn image_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
+        let surface = ImageSurface::create(Format::ARgb32, size.0.cast(), size.1.cast())
+            .map_err(|_| ImageError::Allocation)?;
+        self.next_image_id += 1;
+        let id = ImageId::try_new(self.next_image_id).expect("exhausted image IDs");
+        self.images.insert(id, surface);
+        Ok(id)
+    }
+
+    fn image_upload(&mut self, id: ImageId, data: &[u8], format: ImageFormat) {
+        let surface = match self.images.get_mut(&id) {
+            Some(surface) => surface,
+            None => return,
+        };
+        surface.flush();
+        let width: usize = surface.width().cast();
+        let stride: usize = surface.stride().cast();
+        let mut dest = match surface.data() {
+            Ok(dest) => dest,
+            Err(e) => {
+                log::warn!("draw: failed to access image data: {}", e);
+                return;
+            }
+        };
+        match format {
+            ImageFormat::Rgba8 => {
+                // Cairo uses pre-multiplied, native-endian ARGB
+                for (row, src) in data.chunks_exact(4 * width).enumerate() {
+                    let dest = &mut dest[row * stride..row * stride + 4 * width];
+                    for (d, s) in dest.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+                        let a = u32::from(s[3]);
+                        let mul = |c: u8| (u32::from(c) * a / 255) as u8;
+                        let argb = u32::from_be_bytes([s[3], mul(s[0]), mul(s[1]), mul(s[2])]);
+                        d.copy_from_slice(&argb.to_ne_bytes());
+                    }
+                }
+            }
+        }
+    }
+
+    fn image_free(&mut self, id: ImageId) {
+        self.images.remove(&id);
+    }
+
+    fn image_size(&self, id: ImageId) -> Option<(u32, u32)> {
+        self.images
+            .get(&id)
+            .map(|s| (s.width().cast(), s.height().cast()))
+    }
+
+    fn draw_image(&self, draw: &mut Self::Draw, pass: PassId, id: ImageId, rect: Quad) {
+        if let Some(surface) = self.images.get(&id) {
+            draw.push(pass, Op::Image(surface.clone(), rect));
+        }
+    }
+
+    fn draw_text(
+        &mut self,
+        draw: &mut Self::Draw,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        col: Rgba,
+    ) {
+        self.text(draw, pass, pos, text, col);
+    }
+
+    fn draw_text_col_effects(
+        &mut self,
+        draw: &mut Self::Draw,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        col: Rgba,
+        effects: &[Effect<()>],
+    ) {
+        let pos = pos.round();
+        let mut ops = vec![];
+        let for_glyph = |face: FaceId, dpem: f32, glyph: Glyph, _: usize, _: ()| {
+            if let Some(sprite) = self.get_glyph(face, dpem, glyph) {
+                let pos = pos + Vec2::from(glyph.position).floor() + sprite.offset;
+                ops.push(Op::Glyph(sprite.surface, pos, col));
+            }
+        };
+        let mut rects = vec![];
+        let for_rect = |x1, x2, y: f32, h: f32, _, _| {
+            let y = y.ceil();
+            let y2 = y + h.ceil();
+            rects.push(Quad::with_coords(pos + Vec2(x1, y), pos + Vec2(x2, y2)));
+        };
+        text.glyphs_with_effects(effects, (), for_glyph, for_rect);
+
+        for op in ops {
+            draw.push_text(pass, op);
+        }
+        for rect in rects {
+            draw.push_text(pass, Op::Rect(rect, col));
+        }
+    }
+
+    fn draw_text_effects(
+        &mut self,
+        draw: &mut Self::Draw,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        effects: &[Effect<Rgba>],
+    ) {
+        let pos = pos.round();
+        let mut ops = vec![];
+        let for_glyph = |face: FaceId, dpem: f32, glyph: Glyph, _, col: Rgba| {
+            if let Some(sprite) = self.get_glyph(face, dpem, glyph) {
+                let pos = pos + Vec2::from(glyph.position).floor() + sprite.offset;
+                ops.push(Op::Glyph(sprite.surface, pos, col));
+            }
+        };
+        let mut rects = vec![];
+        let for_rect = |x1, x2, y: f32, h: f32, _, col: Rgba| {
+            let y = y.ceil();
+            let y2 = y + h.ceil();
+            let quad = Quad::with_coords(pos + Vec2(x1, y), pos + Vec2(x2, y2));
+            rects.push((quad, col));
+        };
+        text.glyphs_with_effects(effects, Rgba::BLACK, for_glyph, for_rect);
+
+        for op in ops {
+            draw.push_text(pass, op);
+        }
+        for (rect, col) in rects {
+            draw.push_text(pass, Op::Rect(rect, col));
+        }
+    }
+}
+
+/// A recorded draw operation
+enum Op {
+    Rect(Quad, Rgba),
+    Frame(Quad, Quad, Rgba),
+    Gradient(Quad, Option<Quad>, Gradient),
+    Line(Vec2, Vec2, f32, Rgba),
+    Polyline(Vec<Vec2>, bool, Stroke, Rgba),
+    Circle(Quad, f32, Rgba),
+    Circle2Col(Quad, Rgba, Rgba),
+    RoundedFrame(Quad, Quad, f32, Rgba),
+    Shadow(Quad, f32, f32, Rgba),
+    Image(ImageSurface, Quad),
+    Glyph(ImageSurface, Vec2, Rgba),
+}
+
+/// Draw operations for a clip region
+///
+/// Operations are drawn in three layers: `back` (used for shadows), `main`,
+/// then `text`.
+#[derive(Default)]
+struct Pass {
+    // Clip rect (in window coordinates)
+    rect: Rect,
+    // Transform from pass coordinates to window coordinates
+    transform: Transform,
+    // Opacity (if not opaque)
+    alpha: Option<f32>,
+    back: Vec<Op>,
+    main: Vec<Op>,
+    text: Vec<Op>,
+}
+
+/// Per-window draw state
+#[derive(Default)]
+pub struct DrawWindow {
+    passes: Vec<Pass>,
+}
+
+impl DrawWindow {
+    /// Reset for a new frame of the given size
+    pub fn reset(&mut self, size: Size) {
+        self.passes.clear();
+        self.passes.push(Pass {
+            rect: Rect::new(Coord::ZERO, size),
+            ..Default::default()
+        });
+    }
+
+    fn push(&mut self, pass: PassId, op: Op) {
+        self.passes[pass.pass()].main.push(op);
+    }
+
+    fn push_text(&mut self, pass: PassId, op: Op) {
+        self.passes[pass.pass()].text.push(op);
+    }
+
+    /// Replay recorded operations onto `cr`
+    ///
+    /// The context is expected to use physical pixel coordinates.
+    pub fn render(&self, cr: &Context, clear_color: Rgba) -> Result<(), cairo::Error> {
+        set_source(cr, clear_color);
+        cr.paint()?;
+
+        for pass in &self.passes {
+            cr.save()?;
+            let (pos, size) = (pass.rect.pos, pass.rect.size);
+            cr.rectangle(pos.0.into(), pos.1.into(), size.0.into(), size.1.into());
+            cr.clip();
+            let (s, t) = (pass.transform.scale, pass.transform.translate);
+            cr.translate(t.0.into(), t.1.into());
+            cr.scale(s.into(), s.into());
+            if pass.alpha.is_some() {
+                cr.push_group();
+            }
+            for op in pass.back.iter().chain(&pass.main).chain(&pass.text) {
+                render_op(cr, op)?;
+            }
+            if let Some(alpha) = pass.alpha {
+                // NOTE: passes are composited individually, thus contents of
+                // derived passes may show through those of the parent pass.
+                cr.pop_group_to_source()?;
+                cr.paint_with_alpha(alpha.into())?;
+            }
+            cr.restore()?;
+        }
+        Ok(())
+    }
+}
+
+fn set_source(cr: &Context, col: Rgba) {
+    let c: [u8; 4] = Rgba8Srgb::from(col).into();
+    let f = |x: u8| f64::from(x) / 255.0;
+    cr.set_source_rgba(f(c[0]), f(c[1]), f(c[2]), col.a.into());
+}
+
+fn add_color_stop(gradient: &cairo::Gradient, offset: f64, col: Rgba) {
+    let c: [u8; 4] = Rgba8Srgb::from(col).into();
+    let f = |x: u8| f64::from(x) / 255.0;
+    gradient.add_color_stop_rgba(offset, f(c[0]), f(c[1]), f(c[2]), col.a.into());
+}
+
+/// Set a gradient source over `rect`
+///
+/// Note: cairo clamps stop offsets to the range `0..=1`.
+fn set_gradient_source(cr: &Context, rect: Quad, gradient: &Gradient) -> Result<(), cairo::Error> {
+    let size = rect.size();
+    let add_stops = |pattern: &cairo::Gradient| {
+        for (offset, col) in gradient.stops() {
+            add_color_stop(pattern, f64::from(*offset), *col);
+        }
+    };
+    match gradient.shape {
+        GradientShape::Linear { start, end } => {
+            let p0 = rect.a + start * size;
+            let p1 = rect.a + end * size;
+            let (x0, y0, x1, y1) = (p0.0.into(), p0.1.into(), p1.0.into(), p1.1.into());
+            let pattern = LinearGradient::new(x0, y0, x1, y1);
+            add_stops(&pattern);
+            cr.set_source(&pattern)
+        }
+        GradientShape::Radial { centre, radius } => {
+            let c = rect.a + centre * size;
+            let r = radius * size;
+            if !(r.0 > 0.0 && r.1 > 0.0) {
+                // Degenerate ellipse: everything is outside
+                set_source(cr, gradient.color_at(f32::INFINITY));
+                return Ok(());
+            }
+            let pattern = RadialGradient::new(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+            // Map user space to the unit circle
+            let (rx, ry) = (f64::from(r.0), f64::from(r.1));
+            let (cx, cy) = (f64::from(c.0), f64::from(c.1));
+            let matrix = Matrix::new(1.0 / rx, 0.0, 0.0, 1.0 / ry, -cx / rx, -cy / ry);
+            pattern.set_matrix(matrix);
+            add_stops(&pattern);
+            cr.set_source(&pattern)
+        }
+    }
+}
+
+fn rectangle(cr: &Context, q: Quad) {
+    let size = q.size();
+    cr.rectangle(q.a.0.into(), q.a.1.into(), size.0.into(), size.1.into());
+}
+
+/// Add an axis-aligned oval path, scaled by `r` relative to `q`
+fn oval(cr: &Context, q: Quad, r: f32, negative: bool) {
+    let c = (q.a + q.b) * 0.5;
+    let half = q.size() * (0.5 * r);
+    if half.0 <= 0.0 || half.1 <= 0.0 {
+        return;
+    }
+    let m = cr.matrix();
+    cr.translate(c.0.into(), c.1.into());
+    cr.scale(half.0.into(), half.1.into());
+    cr.new_sub_path();
+    if negative {
+        cr.arc_negative(0.0, 0.0, 1.0, 2.0 * PI, 0.0);
+    } else {
+        cr.arc(0.0, 0.0, 1.0, 0.0, 2.0 * PI);
+    }
+    cr.set_matrix(m);
+}
+
+/// Add a rectangle path whose corners are ovals centred on `inner`'s corners
+///
+/// The corner radii are `r` times the distance between `inner` and `outer`.
+fn rounded_rect(cr: &Context, outer: Quad, inner: Quad, r: f32) {
+    let (a, b) = (inner.a, inner.b);
+    let ra = (inner.a - outer.a) * r;
+    let rb = (outer.b - inner.b) * r;
+    let corner = |cx: f32, cy: f32, rx: f32, ry: f32, angle: f64| {
+        if rx <= 0.0 || ry <= 0.0 {
+            cr.line_to(
+                f64::from(cx) + f64::from(rx) * angle.cos().round(),
+                f64::from(cy) + f64::from(ry) * angle.sin().round(),
+            );
+            return;
+        }
+        let m = cr.matrix();
+        cr.translate(cx.into(), cy.into());
+        cr.scale(rx.into(), ry.into());
+        cr.arc(0.0, 0.0, 1.0, angle - 0.5 * PI, angle);
+        cr.set_matrix(m);
+    };
+    cr.new_sub_path();
+    corner(b.0, a.1, rb.0, ra.1, 0.0);
+    corner(b.0, b.1, rb.0, rb.1, 0.5 * PI);
+    corner(a.0, b.1, ra.0, rb.1, PI);
+    corner(a.0, a.1, ra.0, ra.1, 1.5 * PI);
+    cr.close_path();
+}
+
+fn render_op(cr: &Context, op: &Op) -> Result<(), cairo::Error> {
+    cr.set_fill_rule(FillRule::EvenOdd);
+    match op {
+        Op::Rect(q, col) => {
+            set_source(cr, *col);
+            rectangle(cr, *q);
+            cr.fill()?;
+        }
+        Op::Frame(outer, inner, col) => {
+            set_source(cr, *col);
+            rectangle(cr, *outer);
+            rectangle(cr, *inner);
+            cr.fill()?;
+        }
+        Op::Gradient(outer, inner, gradient) => {
+            set_gradient_source(cr, *outer, gradient)?;
+            rectangle(cr, *outer);
+            if let Some(inner) = inner {
+                rectangle(cr, *inner);
+            }
+            cr.fill()?;
+        }
+        Op::Line(p1, p2, radius, col) => {
+            set_source(cr, *col);
+            cr.set_line_width(2.0 * f64::from(*radius));
+            cr.set_line_cap(LineCap::Round);
+            cr.move_to(p1.0.into(), p1.1.into());
+            cr.line_to(p2.0.into(), p2.1.into());
+            cr.stroke()?;
+        }
+        Op::Polyline(points, closed, stroke, col) => {
+            set_source(cr, *col);
+            cr.set_line_width(stroke.width.into());
+            cr.set_line_cap(match stroke.cap {
+                kas::draw::LineCap::Butt => LineCap::Butt,
+                kas::draw::LineCap::Square => LineCap::Square,
+                kas::draw::LineCap::Round => LineCap::Round,
+            });
+            cr.set_line_join(LineJoin::Round);
+            if let Some(dash) = stroke.dash_pattern() {
+                let dash: Vec<f64> = dash.into_iter().map(f64::from).collect();
+                cr.set_dash(&dash, stroke.dash_offset.into());
+            }
+            for p in points {
+                cr.line_to(p.0.into(), p.1.into());
+            }
+            if *closed {
+                cr.close_path();
+            }
+            let result = cr.stroke();
+            cr.set_dash(&[], 0.0);
+            result?;
+        }
+        Op::Circle(q, inner_radius, col) => {
+            set_source(cr, *col);
+            oval(cr, *q, 1.0, false);
+            oval(cr, *q, *inner_radius, true);
+            cr.fill()?;
+        }
+        Op::Circle2Col(q, col1, col2) => {
+            let c = (q.a + q.b) * 0.5;
+            let half = q.size() * 0.5;
+            if half.0 <= 0.0 || half.1 <= 0.0 {
+                return Ok(());
+            }
+            cr.save()?;
+            cr.translate(c.0.into(), c.1.into());
+            cr.scale(half.0.into(), half.1.into());
+            let gradient = RadialGradient::new(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+            add_color_stop(&gradient, 0.0, *col1);
+            add_color_stop(&gradient, 1.0, *col2);
+            cr.set_source(&gradient)?;
+            cr.arc(0.0, 0.0, 1.0, 0.0, 2.0 * PI);
+            cr.fill()?;
+            cr.restore()?;
+        }
+        Op::RoundedFrame(outer, inner, r1, col) => {
+            set_source(cr, *col);
+            rounded_rect(cr, *outer, *inner, 1.0);
+            if *r1 > 0.0 {
+                rounded_rect(cr, *outer, *inner, *r1);
+            } else {
+                rectangle(cr, *inner);
+            }
+            cr.fill()?;
+        }
+        Op::Shadow(q, radius, blur, col) => {
+            // Cairo has no blur: approximate by stacking translucent
+            // rounded rects of increasing size.
+            const STEPS: i32 = 6;
+            let alpha = 1.0 - (1.0 - col.a).powf(1.0 / STEPS as f32);
+            set_source(cr, Rgba { a: alpha, ..*col });
+            let radius = radius.min(0.5 * q.size().min_comp()).max(0.0);
+            let inner = q.shrink(radius);
+            for i in 0..STEPS {
+                let expand = *blur * (4.0 * (i as f32 + 0.5) / STEPS as f32 - 2.0);
+                let r = radius + expand;
+                if r <= 0.0 {
+                    rectangle(cr, q.grow(expand));
+                } else {
+                    rounded_rect(cr, inner.grow(r), inner, 1.0);
+                }
+                cr.fill()?;
+            }
+        }
+        Op::Image(surface, q) => {
+            let size = q.size();
+            let (w, h) = (surface.width(), surface.height());
+            if w == 0 || h == 0 {
+                return Ok(());
+            }
+            cr.save()?;
+            cr.translate(q.a.0.into(), q.a.1.into());
+            cr.scale(
+                f64::from(size.0) / f64::from(w),
+                f64::from(size.1) / f64::from(h),
+            );
+            cr.set_source_surface(surface, 0.0, 0.0)?;
+            cr.paint()?;
+            cr.restore()?;
+        }
+        Op::Glyph(surface, pos, col) => {
+            set_source(cr, *col);
+            cr.mask_surface(surface, pos.0.into(), pos.1.into())?;
+        }
+    }
+    Ok(())
+}
+
+impl DrawImpl for DrawWindow {
+    fn new_pass(
+        &mut self,
+        parent_pass: PassId,
+        rect: Rect,
+        offset: Offset,
+        class: PassType,
+    ) -> PassId {
+        let parent_pass = match class {
+            // Layers are not supported (see DrawSharedImpl::layer_alloc)
+            PassType::Clip | PassType::Layer(_) => parent_pass,
+            PassType::Overlay => PassId::new(0),
+        };
+        let transform = Transform::translation(-Vec2::from(offset));
+        self.new_transform_pass(parent_pass, rect, transform)
+    }
+
+    fn new_transform_pass(
+        &mut self,
+        parent_pass: PassId,
+        rect: Rect,
+        transform: Transform,
+    ) -> PassId {
+        let parent = &self.passes[parent_pass.pass()];
+        let rect = parent.transform.map_rect(rect);
+        let rect = rect.intersection(&parent.rect).unwrap_or(Rect::ZERO);
+        let transform = transform.then(parent.transform);
+        let alpha = parent.alpha;
+        let pass = self.passes.len().cast();
+        self.passes.push(Pass {
+            rect,
+            transform,
+            alpha,
+            ..Default::default()
+        });
+        PassId::new(pass)
+    }
+
+    fn new_alpha_pass(&mut self, parent_pass: PassId, rect: Rect, alpha: f32) -> PassId {
+        let pass = self.new_transform_pass(parent_pass, rect, Transform::IDENTITY);
+        let pass_data = &mut self.passes[pass.pass()];
+        pass_data.alpha = Some(pass_data.alpha.unwrap_or(1.0) * alpha);
+        pass
+    }
+
+    #[inline]
+    fn get_clip_rect(&self, pass: PassId) -> Rect {
+        let pass = &self.passes[pass.pass()];
+        pass.transform.inverse().map_rect(pass.rect)
+    }
+
+    #[inline]
+    fn get_transform(&self, pass: PassId) -> Transform {
+        self.passes[pass.pass()].transform
+    }
+
+    #[inline]
+    fn rect(&mut self, pass: PassId, rect: Quad, col: Rgba) {
+        self.push(pass, Op::Rect(rect, col));
+    }
+
+    #[inline]
+    fn frame(&mut self, pass: PassId, outer: Quad, inner: Quad, col: Rgba) {
+        self.push(pass, Op::Frame(outer, inner, col));
+    }
+
+    #[inline]
+    fn rect_gradient(&mut self, pass: PassId, rect: Quad, gradient: &Gradient) {
+        self.push(pass, Op::Gradient(rect, None, gradient.clone()));
+    }
+
+    #[inline]
+    fn frame_gradient(&mut self, pass: PassId, outer: Quad, inner: Quad, gradient: &Gradient) {
+        let op = Op::Gradient(outer, Some(inner), gradient.clone());
+        self.push(pass, op);
+    }
+
+    #[inline]
+    fn as_rounded_impl(&mut self) -> Option<&mut dyn DrawRoundedImpl> {
+        Some(self)
+    }
+
+    #[inline]
+    fn as_shaded_impl(&mut self) -> Option<&mut dyn DrawShadedImpl> {
+        Some(self)
+    }
+}
+
+impl DrawRoundedImpl for DrawWindow {
+    #[inline]
+    fn rounded_line(&mut self, pass: PassId, p1: Vec2, p2: Vec2, radius: f32, col: Rgba) {
+        self.push(pass, Op::Line(p1, p2, radius, col));
+    }
+
+    #[inline]
+    fn polyline(
+        &mut self,
+        pass: PassId,
+        points: &[Vec2],
+        closed: bool,
+        stroke: &Stroke,
+        col: Rgba,
+    ) {
+        let op = Op::Polyline(points.to_vec(), closed, stroke.clone(), col);
+        self.push(pass, op);
+    }
+
+    #[inline]
+    fn circle(&mut self, pass: PassId, rect: Quad, inner_radius: f32, col: Rgba) {
+        self.push(pass, Op::Circle(rect, inner_radius, col));
+    }
+
+    #[inline]
+    fn circle_2col(&mut self, pass: PassId, rect: Quad, col1: Rgba, col2: Rgba) {
+        let op = Op::Circle2Col(rect, col1, col2);
+        self.passes[pass.pass()].back.push(op);
+    }
+
+    #[inline]
+    fn rounded_frame(&mut self, pass: PassId, outer: Quad, inner: Quad, r1: f32, col: Rgba) {
+        self.push(pass, Op::RoundedFrame(outer, inner, r1, col));
+    }
+
+    /// Cairo gradients cannot follow the frame's shape, thus this is drawn
+    /// using the average of `c1` and `c2`.
+    #[inline]
+    fn rounded_frame_2col(&mut self, pass: PassId, outer: Quad, inner: Quad, c1: Rgba, c2: Rgba) {
+        let col = Rgba {
+            r: 0.5 * (c1.r + c2.r),
+            g: 0.5 * (c1.g + c2.g),
+            b: 0.5 * (c1.b + c2.b),
+            a: 0.5 * (c1.a + c2.a),
+        };
+        let op = Op::RoundedFrame(outer, inner, 0.0, col);
+        self.passes[pass.pass()].back.push(op);
+    }
+
+    #[inline]
+    fn shadow(&mut self, pass: PassId, rect: Quad, radius: f32, blur: f32, col: Rgba) {
+        let op = Op::Shadow(rect, radius, blur, col);
+        self.passes[pass.pass()].back.push(op);
+    }
+
+    /// Not supported: does nothing.
+    #[inline]
+    fn blur(&mut self, _: PassId, _: Quad, _: f32) {}
+}
+
+/// Shading is not supported: these methods draw flat shapes.
+impl DrawShadedImpl for DrawWindow {
+    #[inline]
+    fn shaded_square(&mut self, pass: PassId, rect: Quad, _: (f32, f32), col: Rgba) {
+        self.push(pass, Op::Rect(rect, col));
+    }
+
+    #[inline]
+    fn shaded_circle(&mut self, pass: PassId, rect: Quad, _: (f32, f32), col: Rgba) {
+        self.push(pass, Op::Circle(rect, 0.0, col));
+    }
+
+    #[inline]
+    fn shaded_square_frame(
+        &mut self,
+        pass: PassId,
+        outer: Quad,
+        inner: Quad,
+        _: (f32, f32),
+        outer_col: Rgba,
+        _: Rgba,
+    ) {
+        self.push(pass, Op::Frame(outer, inner, outer_col));
+    }
+
+    #[inline]
+    fn shaded_round_frame(
+        &mut self,
+        pass: PassId,
+        outer: Quad,
+        inner: Quad,
+        _: (f32, f32),
+        col: Rgba,
+    ) {
+        self.push(pass, Op::RoundedFrame(outer, inner, 0.0, col));
+    }
+}
@@ -0,0 +1,479 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Event loop and handling
+//!
+//! GTK owns the main loop; all run-time state is held by a [`Loop`] shared
+//! between signal handlers. Each handler borrows the loop, handles its event,
+//! then calls [`Loop::update`] (the equivalent of winit's `MainEventsCleared`).
+//!
+//! GTK may emit signals re-entrantly (e.g. while showing a window or reading
+//! the clipboard). Such signals find the loop already borrowed and are
+//! dropped; to minimise this, windows are shown and destroyed only after the
+//! borrow is released.
+
+use log::{debug, trace, warn};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use std::time::Instant;
+
+use gtk::prelude::*;
+use gtk::{gdk, glib, Inhibit};
+use kas::event::{ScrollDelta, ScrollSource};
+use kas::geom::Size;
+use kas::{TkAction, WindowId};
+use kas_theme::Theme;
+
+use crate::convert;
+use crate::draw::DrawPipe;
+use crate::shared::{PendingAction, SharedState};
+use crate::window::Window;
+use crate::ProxyAction;
+
+/// Event-loop data structure (i.e. all run-time state)
+pub(crate) struct Loop<T: Theme<DrawPipe>>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Window states
+    windows: HashMap<WindowId, Window<T>>,
+    /// Translates pop-up identifiers to their parent window
+    popups: HashMap<WindowId, WindowId>,
+    /// Shared data passed from Toolkit
+    shared: SharedState<T>,
+    /// Windows to show once the loop is no longer borrowed
+    to_show: Vec<gtk::Window>,
+    /// Windows to destroy once the loop is no longer borrowed
+    to_destroy: Vec<gtk::Window>,
+}
+
+type LoopRc<T> = Rc<RefCell<Loop<T>>>;
+
+impl<T: Theme<DrawPipe> + 'static> Loop<T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct, connecting signals of all windows
+    pub(crate) fn new(windows: Vec<Window<T>>, shared: SharedState<T>) -> LoopRc<T> {
+        let this = Rc::new(RefCell::new(Loop {
+            windows: HashMap::new(),
+            popups: HashMap::new(),
+            shared,
+            to_show: vec![],
+            to_destroy: vec![],
+        }));
+        {
+            let mut inner = this.borrow_mut();
+            for window in windows {
+                inner.insert_window(&this, window);
+            }
+        }
+        Self::finish(&this);
+        this
+    }
+
+    /// Flush persisted state; called after the main loop exits
+    pub(crate) fn on_exit(&mut self) {
+        if let Err(error) = self.shared.state.flush() {
+            warn!("Failed to save UI state: {}", error);
+        }
+    }
+
+    /// Handle an event for window `id`, then update
+    fn handle<F>(this: &LoopRc<T>, id: WindowId, f: F)
+    where
+        F: FnOnce(&mut Window<T>, &mut SharedState<T>),
+    {
+        match this.try_borrow_mut() {
+            Ok(mut inner) => {
+                let inner = &mut *inner;
+                if let Some(window) = inner.windows.get_mut(&id) {
+                    f(window, &mut inner.shared);
+                }
+                inner.update(this);
+            }
+            Err(_) => {
+                debug!("Dropping re-entrant event for window {:?}", id);
+                return;
+            }
+        }
+        Self::finish(this);
+    }
+
+    /// Handle an action from a [`crate::ToolkitProxy`]
+    pub(crate) fn handle_proxy(this: &LoopRc<T>, action: ProxyAction) {
+        match this.try_borrow_mut() {
+            Ok(mut inner) => {
+                let inner = &mut *inner;
+                match action {
+                    ProxyAction::Close(id) => {
+                        let id = inner.popups.get(&id).cloned().unwrap_or(id);
+                        if let Some(window) = inner.windows.get_mut(&id) {
+                            window.send_action(TkAction::CLOSE);
+                        }
+                    }
+                    ProxyAction::CloseAll => {
+                        for window in inner.windows.values_mut() {
+                            window.send_action(TkAction::CLOSE);
+                        }
+                    }
+                    ProxyAction::Update(handle, payload) => {
+                        inner.shared.trigger_update(handle, payload);
+                    }
+                }
+                inner.update(this);
+            }
+            Err(_) => {
+                // We are inside a nested main loop; retry later
+                let weak = Rc::downgrade(this);
+                glib::idle_add_local_once(move || {
+                    if let Some(this) = weak.upgrade() {
+                        Self::handle_proxy(&this, action);
+                    }
+                });
+                return;
+            }
+        }
+        Self::finish(this);
+    }
+
+    /// Show and destroy windows, after releasing the borrow
+    fn finish(this: &LoopRc<T>) {
+        let (to_show, to_destroy) = {
+            let mut inner = this.borrow_mut();
+            let to_show = std::mem::take(&mut inner.to_show);
+            (to_show, std::mem::take(&mut inner.to_destroy))
+        };
+        for window in to_show {
+            window.show_all();
+        }
+        for window in to_destroy {
+            // Safety: the window is not referenced elsewhere
+            unsafe { window.destroy() };
+        }
+    }
+
+    /// Handle a timer for window `id`
+    fn handle_timer(this: &LoopRc<T>, id: WindowId, instant: Instant) {
+        match this.try_borrow_mut() {
+            Ok(mut inner) => {
+                let inner = &mut *inner;
+                if let Some(window) = inner.windows.get_mut(&id) {
+                    // Ignore timers which have been superseded
+                    if window.timer == Some(instant) {
+                        window.timer = None;
+                        window.update_timer(&mut inner.shared);
+                    }
+                }
+                inner.update(this);
+            }
+            Err(_) => {
+                // We are inside a nested main loop; retry later
+                let weak = Rc::downgrade(this);
+                glib::idle_add_local_once(move || {
+                    if let Some(this) = weak.upgrade() {
+                        Self::handle_timer(&this, id, instant);
+                    }
+                });
+                return;
+            }
+        }
+        Self::finish(this);
+    }
+
+    /// Remove a window, calling its closure handler
+    fn close_window(&mut self, window: Window<T>) -> TkAction {
+        self.to_destroy.push(window.window.clone());
+        window.handle_closure(&mut self.shared)
+    }
+
+    /// Add a window and connect its signals
+    fn insert_window(&mut self, this: &LoopRc<T>, window: Window<T>) {
+        let id = window.window_id;
+        let weak = Rc::downgrade(this);
+        let with = move |f: &dyn Fn(&LoopRc<T>)| {
+            if let Some(this) = weak.upgrade() {
+                f(&this);
+            }
+        };
+
+        let w = with.clone();
+        window.window.connect_delete_event(move |_, _| {
+            w(&|this| Self::handle(this, id, |window, _| window.send_action(TkAction::CLOSE)));
+            Inhibit(true)
+        });
+
+        let area = &window.area;
+        let w = with.clone();
+        area.connect_draw(move |_, cr| {
+            w(&|this| match this.try_borrow_mut() {
+                Ok(mut inner) => {
+                    let inner = &mut *inner;
+                    if let Some(window) = inner.windows.get_mut(&id) {
+                        window.do_draw(&mut inner.shared, cr);
+                    }
+                }
+                Err(_) => debug!("Unable to draw window {:?}: re-entrant call", id),
+            });
+            Inhibit(true)
+        });
+
+        let w = with.clone();
+        area.connect_size_allocate(move |area, alloc| {
+            let factor = area.scale_factor();
+            let size = Size(alloc.width() * factor, alloc.height() * factor);
+            w(&|this| Self::handle(this, id, |window, shared| window.do_resize(shared, size)));
+        });
+
+        let w = with.clone();
+        area.connect_scale_factor_notify(move |area| {
+            let factor = area.scale_factor();
+            w(&|this| {
+                Self::handle(this, id, |window, shared| {
+                    window.set_scale_factor(shared, factor)
+                })
+            });
+        });
+
+        let w = with.clone();
+        area.connect_button_press_event(move |area, event| {
+            // GDK reports double/triple clicks as additional events; KAS
+            // counts clicks itself.
+            if event.event_type() == gdk::EventType::ButtonPress {
+                area.grab_focus();
+                let button = convert::mouse_button(event.button());
+                let modifiers = convert::modifiers(event.state());
+                w(&|this| {
+                    Self::handle(this, id, |window, shared| {
+                        let coord = window.coord(event.position());
+                        window.handle_input(shared, |mgr, widget| {
+                            mgr.handle_modifiers(modifiers);
+                            mgr.handle_cursor_moved(widget, coord);
+                            mgr.handle_mouse_input(widget, true, button);
+                        })
+                    })
+                });
+            }
+            Inhibit(true)
+        });
+
+        let w = with.clone();
+        area.connect_button_release_event(move |_, event| {
+            let button = convert::mouse_button(event.button());
+            w(&|this| {
+                Self::handle(this, id, |window, shared| {
+                    let coord = window.coord(event.position());
+                    window.handle_input(shared, |mgr, widget| {
+                        mgr.handle_cursor_moved(widget, coord);
+                        mgr.handle_mouse_input(widget, false, button);
+                    })
+                })
+            });
+            Inhibit(true)
+        });
+
+        let w = with.clone();
+        area.connect_motion_notify_event(move |_, event| {
+            w(&|this| {
+                Self::handle(this, id, |window, shared| {
+                    let coord = window.coord(event.position());
+                    window
+                        .handle_input(shared, |mgr, widget| mgr.handle_cursor_moved(widget, coord))
+                })
+            });
+            Inhibit(true)
+        });
+
+        let w = with.clone();
+        area.connect_leave_notify_event(move |_, _| {
+            w(&|this| {
+                Self::handle(this, id, |window, shared| {
+                    window.handle_input(shared, |mgr, widget| mgr.handle_cursor_left(widget))
+                })
+            });
+            Inhibit(false)
+        });
+
+        let w = with.clone();
+        area.connect_scroll_event(move |_, event| {
+            let delta = match event.direction() {
+                gdk::ScrollDirection::Up => ScrollDelta::LineDelta(0.0, 1.0),
+                gdk::ScrollDirection::Down => ScrollDelta::LineDelta(0.0, -1.0),
+                gdk::ScrollDirection::Left => ScrollDelta::LineDelta(1.0, 0.0),
+                gdk::ScrollDirection::Right => ScrollDelta::LineDelta(-1.0, 0.0),
+                _ => {
+                    let (dx, dy) = event.delta();
+                    ScrollDelta::LineDelta(-dx as f32, -dy as f32)
+                }
+            };
+            let source = match event.source_device().map(|device| device.source()) {
+                Some(gdk::InputSource::Touchpad) => ScrollSource::Touchpad,
+                Some(gdk::InputSource::Touchscreen) => ScrollSource::Touch,
+                _ => ScrollSource::Wheel,
+            };
+            w(&|this| {
+                Self::handle(this, id, |window, shared| {
+                    window.handle_input(shared, |mgr, widget| {
+                        mgr.handle_scroll(widget, delta, source)
+                    })
+                })
+            });
+            Inhibit(true)
+        });
+
+        let w = with.clone();
+        area.connect_key_press_event(move |_, event| {
+            w(&|this| {
+                Self::handle(this, id, |window, shared| {
+                    window.handle_key(shared, event, true)
+                })
+            });
+            Inhibit(true)
+        });
+
+        let w = with.clone();
+        area.connect_key_release_event(move |_, event| {
+            w(&|this| {
+                Self::handle(this, id, |window, shared| {
+                    window.handle_key(shared, event, false)
+                })
+            });
+            Inhibit(true)
+        });
+
+        area.connect_focus_out_event(move |_, _| {
+            with(&|this| {
+                Self::handle(this, id, |window, shared| {
+                    window.handle_input(shared, |mgr, _| mgr.handle_focus_lost())
+                })
+            });
+            Inhibit(false)
+        });
+
+        self.to_show.push(window.window.clone());
+        self.windows.insert(id, window);
+    }
+
+    /// Update, after handling an event
+    fn update(&mut self, this: &LoopRc<T>) {
+        let mut close_all = false;
+        let mut to_close = vec![];
+        for (id, window) in self.windows.iter_mut() {
+            let (action, resume) = window.update(&mut self.shared);
+            if action.contains(TkAction::EXIT) {
+                close_all = true;
+            } else if action.contains(TkAction::CLOSE) {
+                to_close.push(*id);
+            }
+            set_timer(this, window, resume);
+        }
+
+        for id in &to_close {
+            if let Some(window) = self.windows.remove(id) {
+                if self.close_window(window).contains(TkAction::EXIT) {
+                    close_all = true;
+                }
+            }
+        }
+        if close_all {
+            self.close_all();
+        }
+
+        self.handle_pending(this);
+
+        if self.windows.is_empty() {
+            debug!("All windows closed: quitting");
+            gtk::main_quit();
+        }
+    }
+
+    fn close_all(&mut self) {
+        let windows: Vec<_> = self.windows.drain().map(|(_, w)| w).collect();
+        for window in windows {
+            let _ = self.close_window(window);
+        }
+    }
+
+    /// Handle pending actions
+    ///
+    /// Actions are handled in the order queued, after coalescing each batch
+    /// (see [`kas_shell::coalesce`]).
+    fn handle_pending(&mut self, this: &LoopRc<T>) {
+        while !self.shared.pending.is_empty() {
+            let batch = std::mem::take(&mut self.shared.pending);
+            for pending in kas_shell::coalesce(batch, &mut self.shared.stats) {
+                self.handle_pending_item(this, pending);
+            }
+        }
+        trace!("Pending action counters: {:?}", self.shared.stats);
+    }
+
+    fn handle_pending_item(&mut self, this: &LoopRc<T>, pending: PendingAction) {
+        match pending {
+            PendingAction::AddPopup(parent_id, id, popup) => {
+                debug!("Adding overlay");
+                if let Some(window) = self.windows.get_mut(&parent_id) {
+                    window.add_popup(&mut self.shared, id, popup);
+                    self.popups.insert(id, parent_id);
+                }
+            }
+            PendingAction::AddWindow(id, widget) => {
+                debug!("Adding window {}", widget.title());
+                let window = Window::new(&mut self.shared, id, widget);
+                self.insert_window(this, window);
+            }
+            PendingAction::CloseWindow(id) => {
+                let parent_id = self.popups.remove(&id).unwrap_or(id);
+                if let Some(window) = self.windows.get_mut(&parent_id) {
+                    window.send_close(&mut self.shared, id);
+                }
+            }
+            PendingAction::TkAction(action) => {
+                if action.contains(TkAction::CLOSE | TkAction::EXIT) {
+                    self.close_all();
+                } else if !action.is_empty() {
+                    self.shared.stats.actions_applied += 1;
+                    for window in self.windows.values_mut() {
+                        window.handle_shared_action(&mut self.shared, action);
+                    }
+                }
+            }
+            PendingAction::Update(handle, payload) => {
+                for window in self.windows.values_mut() {
+                    window.update_handle(&mut self.shared, handle, payload);
+                }
+            }
+        }
+    }
+}
+
+/// Schedule a GLib timeout to resume `window` at `resume`
+///
+/// Superseded timeouts are not removed but are ignored when they fire.
+fn set_timer<T: Theme<DrawPipe> + 'static>(
+    this: &LoopRc<T>,
+    window: &mut Window<T>,
+    resume: Option<Instant>,
+) where
+    T::Window: kas_theme::Window,
+{
+    if window.timer == resume {
+        return;
+    }
+    window.timer = resume;
+
+    if let Some(instant) = resume {
+        let id = window.window_id;
+        let weak: Weak<RefCell<Loop<T>>> = Rc::downgrade(this);
+        let duration = instant.saturating_duration_since(Instant::now());
+        trace!("Scheduling timer for window {:?} in {:?}", id, duration);
+        glib::timeout_add_local_once(duration, move || {
+            if let Some(this) = weak.upgrade() {
+                Loop::handle_timer(&this, id, instant);
+            }
+        });
+    }
+}
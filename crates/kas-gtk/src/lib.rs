@@ -0,0 +1,295 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! KAS shell over [GTK]
+//!
+//! This crate implements a KAS shell (backend) using [GTK] 3 for windowing and
+//! input and [Cairo] for (software) rendering. Its main use is on platforms
+//! where [WebGPU] is unavailable, or where native window management is
+//! preferred.
+//!
+//! This crate supports themes via the [`kas_theme`] crate. Shaded drawing
+//! primitives are drawn flat.
+//!
+//! The API mirrors that of `kas_wgpu`: construct a [`Toolkit`], add windows,
+//! then [`Toolkit::run`].
+//!
+//! With the `pdf` feature, the [`pdf`] module supports printing widgets to PDF
+//! documents.
+//!
+//! [GTK]: https://gtk.org/
+//! [Cairo]: https://www.cairographics.org/
+//! [WebGPU]: https://github.com/gfx-rs/wgpu-rs
+
+#![cfg_attr(feature = "gat", feature(generic_associated_types))]
+
+mod convert;
+pub mod draw;
+mod event_loop;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+mod shared;
+mod window;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use thiserror::Error;
+
+use gtk::glib;
+use kas::event::UpdateHandle;
+use kas::WindowId;
+use kas_theme::Theme;
+
+use crate::draw::DrawPipe;
+use crate::shared::SharedState;
+use window::Window;
+
+pub use gtk;
+pub use kas;
+pub use kas_shell::ClosedError;
+pub use kas_theme as theme;
+
+/// Possible failures from constructing a [`Toolkit`]
+///
+/// Some variants are undocumented. Users should not match these variants since
+/// they are not considered part of the public API.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum Error {
+    /// GTK initialisation failed
+    ///
+    /// This usually means that no display is available.
+    #[error("failed to initialise GTK")]
+    Init(#[from] glib::BoolError),
+    /// Cairo rendering failed
+    #[error("rendering failed")]
+    Render(#[from] gtk::cairo::Error),
+}
+
+/// A toolkit over GTK
+///
+/// Constructing the toolkit with [`Toolkit::new`] initialises GTK and the font
+/// database. Note that this database is a global singleton and some widgets
+/// and other library code may expect fonts to have been initialised first.
+///
+/// All KAS shells are expected to provide a similar `Toolkit` type and API.
+pub struct Toolkit<T: Theme<DrawPipe>>
+where
+    T::Window: kas_theme::Window,
+{
+    windows: Vec<Window<T>>,
+    shared: SharedState<T>,
+    sender: glib::Sender<ProxyAction>,
+    receiver: glib::Receiver<ProxyAction>,
+}
+
+impl<T: Theme<DrawPipe> + 'static> Toolkit<T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct a new instance with default config
+    #[inline]
+    pub fn new(theme: T) -> Result<Self, Error> {
+        Self::new_custom_config(theme, Default::default())
+    }
+
+    /// Construct an instance with custom config
+    pub fn new_custom_config(
+        theme: T,
+        config: Rc<RefCell<kas::event::Config>>,
+    ) -> Result<Self, Error> {
+        gtk::init()?;
+        let (sender, receiver) = glib::MainContext::channel(glib::PRIORITY_DEFAULT);
+        Ok(Toolkit {
+            windows: vec![],
+            shared: SharedState::new(theme, config),
+            sender,
+            receiver,
+        })
+    }
+
+    /// Access the theme by ref
+    #[inline]
+    pub fn theme(&self) -> &T {
+        &self.shared.theme
+    }
+
+    /// Access the theme by ref mut
+    #[inline]
+    pub fn theme_mut(&mut self) -> &mut T {
+        &mut self.shared.theme
+    }
+
+    /// Set the localizer used to resolve message keys
+    ///
+    /// By default, no localizer is used and message keys resolve to
+    /// themselves. See the [`kas::l10n`] module.
+    #[inline]
+    pub fn set_localizer<L: kas::l10n::Localizer + 'static>(&mut self, localizer: L) {
+        self.shared.localizer = Some(Box::new(localizer));
+    }
+
+    /// Set the localizer used to resolve message keys, inline
+    ///
+    /// See [`Toolkit::set_localizer`].
+    #[inline]
+    pub fn with_localizer<L: kas::l10n::Localizer + 'static>(mut self, localizer: L) -> Self {
+        self.set_localizer(localizer);
+        self
+    }
+
+    /// Set the storage backend for persisted UI state
+    ///
+    /// By default, state is held in memory only ([`kas::state::MemoryStore`]).
+    /// State is flushed on exit. See the [`kas::state`] module.
+    #[inline]
+    pub fn set_state_store<S: kas::state::StateStore + 'static>(&mut self, store: S) {
+        self.shared.state = Box::new(store);
+    }
+
+    /// Set the storage backend for persisted UI state, inline
+    ///
+    /// See [`Toolkit::set_state_store`].
+    #[inline]
+    pub fn with_state_store<S: kas::state::StateStore + 'static>(mut self, store: S) -> Self {
+        self.set_state_store(store);
+        self
+    }
+
+    /// Assume ownership of and display a window
+    ///
+    /// This is a convenience wrapper around [`Toolkit::add_boxed`].
+    ///
+    /// Note: typically, one should have `W: Clone`, enabling multiple usage.
+    #[inline]
+    pub fn add<W: kas::Window + 'static>(&mut self, window: W) -> Result<WindowId, Error> {
+        self.add_boxed(Box::new(window))
+    }
+
+    /// Assume ownership of and display a window, inline
+    ///
+    /// This is a convenience wrapper around [`Toolkit::add_boxed`].
+    ///
+    /// Note: typically, one should have `W: Clone`, enabling multiple usage.
+    #[inline]
+    pub fn with<W: kas::Window + 'static>(mut self, window: W) -> Result<Self, Error> {
+        self.add_boxed(Box::new(window))?;
+        Ok(self)
+    }
+
+    /// Add a boxed window directly
+    pub fn add_boxed(&mut self, widget: Box<dyn kas::Window>) -> Result<WindowId, Error> {
+        let id = self.shared.next_window_id();
+        let win = Window::new(&mut self.shared, id, widget);
+        self.windows.push(win);
+        Ok(id)
+    }
+
+    /// Add a boxed window directly, inline
+    #[inline]
+    pub fn with_boxed(mut self, widget: Box<dyn kas::Window>) -> Result<Self, Error> {
+        self.add_boxed(widget)?;
+        Ok(self)
+    }
+
+    /// Run the main loop.
+    ///
+    /// This returns once all windows have been closed.
+    pub fn run(self) {
+        let el = event_loop::Loop::new(self.windows, self.shared);
+        let weak = Rc::downgrade(&el);
+        self.receiver.attach(None, move |action| {
+            if let Some(el) = weak.upgrade() {
+                event_loop::Loop::handle_proxy(&el, action);
+            }
+            glib::Continue(true)
+        });
+
+        gtk::main();
+        el.borrow_mut().on_exit();
+    }
+
+    /// Create a proxy which can be used to update the UI from another thread
+    pub fn create_proxy(&self) -> ToolkitProxy {
+        ToolkitProxy {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+/// A proxy allowing control of a [`Toolkit`] from another thread.
+///
+/// Created by [`Toolkit::create_proxy`].
+pub struct ToolkitProxy {
+    sender: glib::Sender<ProxyAction>,
+}
+
+impl ToolkitProxy {
+    fn send(&self, action: ProxyAction) -> Result<(), ClosedError> {
+        self.sender.send(action).map_err(|_| ClosedError)
+    }
+
+    /// Close a specific window.
+    pub fn close(&self, id: WindowId) -> Result<(), ClosedError> {
+        self.send(ProxyAction::Close(id))
+    }
+
+    /// Close all windows and terminate the UI.
+    pub fn close_all(&self) -> Result<(), ClosedError> {
+        self.send(ProxyAction::CloseAll)
+    }
+
+    /// Trigger an update handle
+    pub fn trigger_update(&self, handle: UpdateHandle, payload: u64) -> Result<(), ClosedError> {
+        self.send(ProxyAction::Update(handle, payload))
+    }
+}
+
+impl<T: Theme<DrawPipe> + 'static> kas_shell::Shell for Toolkit<T>
+where
+    T::Window: kas_theme::Window,
+{
+    type Error = Error;
+    type Proxy = ToolkitProxy;
+
+    #[inline]
+    fn add_boxed(&mut self, window: Box<dyn kas::Window>) -> Result<WindowId, Error> {
+        Toolkit::add_boxed(self, window)
+    }
+
+    #[inline]
+    fn create_proxy(&self) -> ToolkitProxy {
+        Toolkit::create_proxy(self)
+    }
+
+    #[inline]
+    fn run(self) {
+        Toolkit::run(self)
+    }
+}
+
+impl kas_shell::ShellProxy for ToolkitProxy {
+    #[inline]
+    fn close(&self, id: WindowId) -> Result<(), ClosedError> {
+        ToolkitProxy::close(self, id)
+    }
+
+    #[inline]
+    fn close_all(&self) -> Result<(), ClosedError> {
+        ToolkitProxy::close_all(self)
+    }
+
+    #[inline]
+    fn trigger_update(&self, handle: UpdateHandle, payload: u64) -> Result<(), ClosedError> {
+        ToolkitProxy::trigger_update(self, handle, payload)
+    }
+}
+
+#[derive(Debug)]
+enum ProxyAction {
+    CloseAll,
+    Close(WindowId),
+    Update(UpdateHandle, u64),
+}
@@ -0,0 +1,438 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Printing to PDF
+//!
+//! [`PdfPrinter`] lays out a widget tree to the printable width of a page and
+//! renders it to a PDF document via Cairo, using as many pages as required.
+//! Vector primitives remain vector graphics; text is drawn from rastered
+//! glyphs at the resolution given by [`PageSetup::dpi`].
+//!
+//! The widget tree is laid out at its ideal height, thus content which is
+//! naturally taller than a page is split over multiple pages. Scrollable
+//! widgets still clip their content to their own size; to print the whole
+//! content of a scroll region, print the content widget directly.
+//!
+//! Page break positions may be adjusted by a hook; see
+//! [`PdfPrinter::set_page_break`] and [`keep_together`].
+//!
+//! This module requires the `pdf` feature.
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use gtk::cairo::{Context, PdfSurface};
+use kas::draw::{DrawHandleExt, DrawIface, PassId};
+use kas::event::ManagerState;
+use kas::geom::{Coord, Offset, Rect, Size};
+use kas::layout::SolveCache;
+use kas::WidgetConfig;
+use kas_theme::{Theme, Window as _};
+
+use crate::draw::DrawPipe;
+use crate::shared::SharedState;
+use crate::window::TkWindow;
+use crate::Error;
+
+/// Page size, margins and resolution
+///
+/// Sizes are given in points (1/72 inch), as used by PDF.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageSetup {
+    /// Paper size: `(width, height)`
+    pub paper: (f64, f64),
+    /// Margin applied to each side of the page
+    pub margin: f64,
+    /// Resolution used for layout and text, in dots per inch
+    ///
+    /// Widgets are laid out with scale factor `dpi / 96`.
+    pub dpi: f64,
+}
+
+impl Default for PageSetup {
+    fn default() -> Self {
+        PageSetup::a4()
+    }
+}
+
+impl PageSetup {
+    /// Construct for the given paper size, in millimetres
+    ///
+    /// The default margin is 15mm and resolution is 300 dpi.
+    pub fn new_mm(width: f64, height: f64) -> Self {
+        let to_pt = 72.0 / 25.4;
+        PageSetup {
+            paper: (width * to_pt, height * to_pt),
+            margin: 15.0 * to_pt,
+            dpi: 300.0,
+        }
+    }
+
+    /// ISO A4 paper (210 × 297 mm), portrait
+    pub fn a4() -> Self {
+        PageSetup::new_mm(210.0, 297.0)
+    }
+
+    /// ISO A5 paper (148 × 210 mm), portrait
+    pub fn a5() -> Self {
+        PageSetup::new_mm(148.0, 210.0)
+    }
+
+    /// US Letter paper (8.5 × 11 in), portrait
+    pub fn letter() -> Self {
+        PageSetup::new_mm(215.9, 279.4)
+    }
+
+    /// US Legal paper (8.5 × 14 in), portrait
+    pub fn legal() -> Self {
+        PageSetup::new_mm(215.9, 355.6)
+    }
+
+    /// Use landscape orientation
+    ///
+    /// The longer side of the paper is made horizontal.
+    pub fn landscape(mut self) -> Self {
+        let (w, h) = self.paper;
+        self.paper = (w.max(h), w.min(h));
+        self
+    }
+
+    /// Set the margin, in points
+    pub fn with_margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Set the resolution, in dots per inch
+    pub fn with_dpi(mut self, dpi: f64) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// Scale factor used for layout
+    pub fn scale_factor(&self) -> f32 {
+        (self.dpi / 96.0) as f32
+    }
+
+    /// Size of the printable area, in pixels
+    pub fn content_size(&self) -> Size {
+        let scale = self.dpi / 72.0;
+        let w = (self.paper.0 - 2.0 * self.margin).max(0.0) * scale;
+        let h = (self.paper.1 - 2.0 * self.margin).max(0.0) * scale;
+        Size((w as i32).max(1), (h as i32).max(1))
+    }
+}
+
+/// Page break hook
+///
+/// Arguments are the root widget, the top of the current page and the proposed
+/// page break (the bottom of the printable area), in pixels. The hook returns
+/// the position of the page break to use. Results outside of the range
+/// `top + 1 ..= proposed` are replaced by `proposed`.
+pub type PageBreakFn = dyn FnMut(&dyn WidgetConfig, i32, i32) -> i32;
+
+/// Page break hook: avoid splitting widgets over pages
+///
+/// Where a widget starting on the current page crosses the proposed page
+/// break, the break is moved up to the top of that widget. Where a widget
+/// crosses the break but starts on an earlier page, its children are
+/// considered instead.
+///
+/// This is the default hook used by [`PdfPrinter`].
+pub fn keep_together(widget: &dyn WidgetConfig, top: i32, proposed: i32) -> i32 {
+    fn find(widget: &dyn WidgetConfig, top: i32, proposed: i32) -> Option<i32> {
+        let rect = widget.rect();
+        let (y0, y1) = (rect.pos.1, rect.pos.1 + rect.size.1);
+        if y1 <= proposed || y0 >= proposed {
+            None
+        } else if y0 > top {
+            Some(y0)
+        } else {
+            (0..widget.num_children())
+                .filter_map(|i| widget.get_child(i))
+                .filter_map(|child| find(child, top, proposed))
+                .min()
+        }
+    }
+
+    find(widget, top, proposed).unwrap_or(proposed)
+}
+
+// Split content of the given height into pages, returning `(top, bottom)`
+//
+// Breaks returned by `page_break` outside of `top + 1 ..= proposed` are
+// replaced by `proposed`.
+fn paginate(
+    height: i32,
+    page_height: i32,
+    mut page_break: impl FnMut(i32, i32) -> i32,
+) -> Vec<(i32, i32)> {
+    let mut pages = vec![];
+    let mut top = 0;
+    while top < height {
+        let proposed = top + page_height.max(1);
+        let mut bottom = height;
+        if proposed < height {
+            bottom = page_break(top, proposed);
+            if bottom <= top || bottom > proposed {
+                bottom = proposed;
+            }
+        }
+        pages.push((top, bottom));
+        top = bottom;
+    }
+    pages
+}
+
+/// A printer rendering widgets to PDF documents
+///
+/// Unlike [`crate::Toolkit`], this does not require a display.
+pub struct PdfPrinter<T: Theme<DrawPipe>>
+where
+    T::Window: kas_theme::Window,
+{
+    shared: SharedState<T>,
+    setup: PageSetup,
+    page_break: Box<PageBreakFn>,
+}
+
+impl<T: Theme<DrawPipe>> PdfPrinter<T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct a new instance with default config
+    #[inline]
+    pub fn new(theme: T) -> Self {
+        Self::new_custom_config(theme, Default::default())
+    }
+
+    /// Construct an instance with custom config
+    pub fn new_custom_config(theme: T, config: Rc<RefCell<kas::event::Config>>) -> Self {
+        PdfPrinter {
+            shared: SharedState::new(theme, config),
+            setup: PageSetup::default(),
+            page_break: Box::new(keep_together),
+        }
+    }
+
+    /// Access the theme by ref
+    #[inline]
+    pub fn theme(&self) -> &T {
+        &self.shared.theme
+    }
+
+    /// Access the theme by ref mut
+    #[inline]
+    pub fn theme_mut(&mut self) -> &mut T {
+        &mut self.shared.theme
+    }
+
+    /// Get the page setup
+    #[inline]
+    pub fn page_setup(&self) -> PageSetup {
+        self.setup
+    }
+
+    /// Set the page setup
+    #[inline]
+    pub fn set_page_setup(&mut self, setup: PageSetup) {
+        self.setup = setup;
+    }
+
+    /// Set the page setup, inline
+    #[inline]
+    pub fn with_page_setup(mut self, setup: PageSetup) -> Self {
+        self.setup = setup;
+        self
+    }
+
+    /// Set the page break hook
+    ///
+    /// By default, [`keep_together`] is used. See [`PageBreakFn`].
+    #[inline]
+    pub fn set_page_break<F>(&mut self, f: F)
+    where
+        F: FnMut(&dyn WidgetConfig, i32, i32) -> i32 + 'static,
+    {
+        self.page_break = Box::new(f);
+    }
+
+    /// Set the page break hook, inline
+    #[inline]
+    pub fn with_page_break<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&dyn WidgetConfig, i32, i32) -> i32 + 'static,
+    {
+        self.set_page_break(f);
+        self
+    }
+
+    /// Print `window` to a PDF file at `path`
+    ///
+    /// The window's widget tree is configured and laid out for the page
+    /// setup, then drawn over as many pages as required. The window title is
+    /// not printed.
+    ///
+    /// Returns the number of pages printed.
+    pub fn print<P: AsRef<Path>>(
+        &mut self,
+        window: &mut dyn kas::Window,
+        path: P,
+    ) -> Result<usize, Error> {
+        let setup = self.setup;
+        let shared = &mut self.shared;
+        let scale_factor = setup.scale_factor();
+        let page = setup.content_size();
+
+        let window_id = shared.next_window_id();
+        let mut theme_window = shared.theme.new_window(scale_factor);
+        let mut mgr = ManagerState::new(shared.config.clone(), scale_factor);
+        let mut tkw = TkWindow::new(shared, window_id, None, &mut theme_window);
+        mgr.configure(&mut tkw, &mut *window);
+
+        let mut size_handle = theme_window.size_handle();
+        let mut solve_cache =
+            SolveCache::find_constraints(window.as_widget_mut(), &mut size_handle);
+
+        // Height depends on width; we re-solve until the layout is stable.
+        let mut height = solve_cache.ideal(true).1.max(1);
+        for _ in 0..3 {
+            let rect = Rect::new(Coord::ZERO, Size(page.0, height));
+            let mut tkw = TkWindow::new(shared, window_id, None, &mut theme_window);
+            mgr.update_window_size(rect.size);
+            mgr.with(&mut tkw, |mgr| {
+                solve_cache.apply_rect(window.as_widget_mut(), mgr, rect, true);
+            });
+            let ideal = solve_cache.ideal(true).1.max(1);
+            if ideal == height {
+                break;
+            }
+            height = ideal;
+        }
+
+        let page_break = &mut self.page_break;
+        let pages = paginate(height, page.1, |top, proposed| {
+            page_break(window.as_widget(), top, proposed)
+        });
+
+        let surface = PdfSurface::new(setup.paper.0, setup.paper.1, path)?;
+        let cr = Context::new(&surface)?;
+        let mut draw = shared.draw.draw.new_window();
+        let clear_color = shared.theme.clear_color();
+        for (top, bottom) in pages.iter().cloned() {
+            let size = Size(page.0, bottom - top);
+            draw.reset(size);
+            let clip = Rect::new(Coord::ZERO, size);
+            let offset = Offset(0, top);
+
+            let iface = DrawIface {
+                draw: &mut draw,
+                shared: &mut shared.draw,
+                pass: PassId::new(0),
+            };
+            #[cfg(not(feature = "gat"))]
+            unsafe {
+                // Safety: lifetimes do not escape the returned draw_handle value.
+                let mut draw_handle = shared.theme.draw_handle(iface, &mut theme_window);
+                draw_handle
+                    .with_clip_region(clip, offset, &mut |draw| window.draw(draw, &mgr, false));
+            }
+            #[cfg(feature = "gat")]
+            {
+                let mut draw_handle = shared.theme.draw_handle(iface, &mut theme_window);
+                draw_handle
+                    .with_clip_region(clip, offset, &mut |draw| window.draw(draw, &mgr, false));
+            }
+
+            cr.save()?;
+            cr.translate(setup.margin, setup.margin);
+            let scale = 72.0 / setup.dpi;
+            cr.scale(scale, scale);
+            cr.rectangle(0.0, 0.0, size.0.into(), size.1.into());
+            cr.clip();
+            draw.render(&cr, clear_color)?;
+            cr.restore()?;
+            cr.show_page()?;
+        }
+
+        surface.finish();
+        Ok(pages.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kas::prelude::*;
+
+    widget! {
+        #[derive(Debug, Default)]
+        struct Leaf {
+            #[widget_core]
+            core: CoreData,
+        }
+
+        impl Layout for Leaf {
+            fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+                SizeRules::EMPTY
+            }
+
+            fn draw(&mut self, _: &mut dyn DrawHandle, _: &ManagerState, _: bool) {}
+        }
+    }
+
+    widget! {
+        #[derive(Debug, Default)]
+        #[widget{
+            layout = column: *;
+        }]
+        struct Pair {
+            #[widget_core]
+            core: CoreData,
+            #[widget]
+            a: Leaf,
+            #[widget]
+            b: Leaf,
+        }
+    }
+
+    // Two widgets stacked vertically: `a` of height `h0` then `b` of height `h1`
+    fn pair(h0: i32, h1: i32) -> Pair {
+        let mut w = Pair::default();
+        let rect = |y, h| Rect::new(Coord(0, y), Size(100, h));
+        w.core.rect = rect(0, h0 + h1);
+        w.a.core.rect = rect(0, h0);
+        w.b.core.rect = rect(h0, h1);
+        w
+    }
+
+    #[test]
+    fn widget_crossing_break() {
+        let w = pair(60, 90);
+        assert_eq!(keep_together(&w, 0, 100), 60);
+        let pages = paginate(150, 100, |top, p| keep_together(&w, top, p));
+        assert_eq!(pages, vec![(0, 60), (60, 150)]);
+    }
+
+    #[test]
+    fn widget_taller_than_page() {
+        // `b` is moved to the second page, then split over pages
+        let w = pair(60, 240);
+        let pages = paginate(300, 100, |top, p| keep_together(&w, top, p));
+        assert_eq!(pages, vec![(0, 60), (60, 160), (160, 260), (260, 300)]);
+    }
+
+    #[test]
+    fn hook_out_of_range() {
+        let expected = vec![(0, 100), (100, 200), (200, 250)];
+        assert_eq!(paginate(250, 100, |top, _| top), expected);
+        assert_eq!(paginate(250, 100, |_, _| -5), expected);
+        assert_eq!(paginate(250, 100, |_, p| p + 50), expected);
+        // A valid break is used
+        let pages = paginate(250, 100, |top, _| top + 80);
+        assert_eq!(pages, vec![(0, 80), (80, 160), (160, 240), (240, 250)]);
+    }
+}
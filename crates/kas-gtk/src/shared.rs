@@ -0,0 +1,106 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Shared state
+
+use std::cell::RefCell;
+use std::num::NonZeroU32;
+use std::rc::Rc;
+
+use gtk::{gdk, Clipboard};
+use kas::draw;
+use kas::event::UpdateHandle;
+use kas::l10n::Localizer;
+use kas::state::{MemoryStore, StateStore};
+use kas::{TkAction, WindowId};
+use kas_shell::{Pending, PendingKind, PendingStats};
+use kas_theme::{Theme, ThemeConfig};
+
+use crate::draw::DrawPipe;
+
+/// State shared between windows
+pub struct SharedState<T> {
+    pub draw: draw::SharedState<DrawPipe>,
+    pub theme: T,
+    pub config: Rc<RefCell<kas::event::Config>>,
+    /// Persisted UI state
+    pub state: Box<dyn StateStore>,
+    pub localizer: Option<Box<dyn Localizer>>,
+    pub pending: Vec<PendingAction>,
+    pub stats: PendingStats,
+    window_id: u32,
+}
+
+impl<T: Theme<DrawPipe>> SharedState<T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct
+    pub fn new(mut theme: T, config: Rc<RefCell<kas::event::Config>>) -> Self {
+        let pipe = DrawPipe::new(theme.config().raster());
+        let mut draw = draw::SharedState::new(pipe);
+
+        theme.init(&mut draw);
+
+        SharedState {
+            draw,
+            theme,
+            config,
+            state: Box::new(MemoryStore::new()),
+            localizer: None,
+            pending: vec![],
+            stats: Default::default(),
+            window_id: 0,
+        }
+    }
+
+    pub fn next_window_id(&mut self) -> WindowId {
+        self.window_id += 1;
+        WindowId::new(NonZeroU32::new(self.window_id).unwrap())
+    }
+
+    /// Get clipboard contents
+    ///
+    /// Note: this runs a nested GTK main loop until contents are received.
+    #[inline]
+    pub fn get_clipboard(&mut self) -> Option<String> {
+        let clipboard = Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+        clipboard.wait_for_text().map(|text| text.to_string())
+    }
+
+    #[inline]
+    pub fn set_clipboard(&mut self, content: String) {
+        let clipboard = Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+        clipboard.set_text(&content);
+    }
+
+    pub fn trigger_update(&mut self, handle: UpdateHandle, payload: u64) {
+        self.pending.push(PendingAction::Update(handle, payload));
+    }
+}
+
+pub enum PendingAction {
+    AddPopup(WindowId, WindowId, kas::Popup),
+    AddWindow(WindowId, Box<dyn kas::Window>),
+    CloseWindow(WindowId),
+    Update(kas::event::UpdateHandle, u64),
+    TkAction(TkAction),
+}
+
+impl Pending for PendingAction {
+    fn kind(&self) -> PendingKind {
+        match self {
+            PendingAction::AddPopup(..)
+            | PendingAction::AddWindow(..)
+            | PendingAction::CloseWindow(_) => PendingKind::WindowChange,
+            PendingAction::Update(handle, payload) => PendingKind::Update(*handle, *payload),
+            PendingAction::TkAction(action) => PendingKind::Action(*action),
+        }
+    }
+
+    fn from_action(action: TkAction) -> Self {
+        PendingAction::TkAction(action)
+    }
+}
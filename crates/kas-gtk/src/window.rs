@@ -0,0 +1,753 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! `Window` type
+
+use log::{debug, info, trace, warn};
+use std::time::{Duration, Instant};
+
+use gtk::prelude::*;
+use gtk::{cairo, gdk, glib};
+use kas::cast::{Cast, CastFloat};
+use kas::draw::{DrawIface, DrawShared, PassId, SizeHandle, ThemeApi};
+use kas::event::{CursorIcon, ManagerState, UpdateHandle};
+use kas::geom::{Coord, Rect, Size};
+use kas::layout::SolveCache;
+use kas::{MonitorInfo, ResizeDirection, ShellCapabilities, TkAction, WindowId};
+use kas_theme::{Theme, Window as _};
+
+use crate::convert;
+use crate::draw::{DrawPipe, DrawWindow};
+use crate::shared::{PendingAction, SharedState};
+
+/// Per-window data
+pub(crate) struct Window<T: Theme<DrawPipe>> {
+    pub(crate) widget: Box<dyn kas::Window>,
+    pub(crate) window_id: WindowId,
+    mgr: ManagerState,
+    solve_cache: SolveCache,
+    /// The GTK window
+    pub(crate) window: gtk::Window,
+    /// The drawing area, covering the whole window
+    pub(crate) area: gtk::DrawingArea,
+    /// Physical size of `area`
+    size: Size,
+    draw: DrawWindow,
+    theme_window: T::Window,
+    /// Requested resume time of the pending timer
+    pub(crate) timer: Option<Instant>,
+}
+
+// Public functions, for use by the toolkit
+impl<T: Theme<DrawPipe>> Window<T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct a window
+    ///
+    /// The window is not shown and has no connected signals.
+    pub fn new(
+        shared: &mut SharedState<T>,
+        window_id: WindowId,
+        mut widget: Box<dyn kas::Window>,
+    ) -> Self {
+        let time = Instant::now();
+
+        let window = gtk::Window::new(gtk::WindowType::Toplevel);
+        window.set_title(widget.title());
+        let area = gtk::DrawingArea::new();
+        area.set_can_focus(true);
+        area.add_events(
+            gdk::EventMask::BUTTON_PRESS_MASK
+                | gdk::EventMask::BUTTON_RELEASE_MASK
+                | gdk::EventMask::POINTER_MOTION_MASK
+                | gdk::EventMask::LEAVE_NOTIFY_MASK
+                | gdk::EventMask::SCROLL_MASK
+                | gdk::EventMask::SMOOTH_SCROLL_MASK
+                | gdk::EventMask::KEY_PRESS_MASK
+                | gdk::EventMask::KEY_RELEASE_MASK
+                | gdk::EventMask::FOCUS_CHANGE_MASK,
+        );
+        window.add(&area);
+
+        let scale_factor = window.scale_factor();
+        let mut theme_window = shared.theme.new_window(scale_factor as f32);
+
+        let mut mgr = ManagerState::new(shared.config.clone(), scale_factor as f32);
+        let mut tkw = TkWindow::new(shared, window_id, None, &mut theme_window);
+        mgr.configure(&mut tkw, &mut *widget);
+
+        let mut size_handle = theme_window.size_handle();
+        let solve_cache = SolveCache::find_constraints(widget.as_widget_mut(), &mut size_handle);
+        // Opening a zero-size window causes a crash, so force at least 1x1:
+        let ideal = solve_cache.ideal(true).max(Size(1, 1));
+        let attrs = widget.attributes();
+        let size = attrs.inner_size.unwrap_or(ideal).max(Size(1, 1));
+        window.set_default_size(size.0 / scale_factor, size.1 / scale_factor);
+        window.set_decorated(attrs.decorations);
+        window.set_keep_above(attrs.always_on_top);
+
+        let restrict_dimensions = widget.restrict_dimensions();
+        if !attrs.resizable || (restrict_dimensions.1 && attrs.max_size.is_none()) {
+            // GTK has no maximum size other than via geometry hints; we use
+            // a fixed size instead.
+            window.set_resizable(false);
+        }
+
+        let placement = widget.placement();
+        let monitor = placement
+            .monitor
+            .and_then(|index| gdk::Display::default()?.monitor(index.cast()));
+        if let Some(ref monitor) = monitor {
+            let geom = monitor.geometry();
+            let pos = placement.position.unwrap_or(Coord::ZERO);
+            let factor = monitor.scale_factor();
+            window.move_(geom.x() + pos.0 / factor, geom.y() + pos.1 / factor);
+        } else if let Some(pos) = placement.position {
+            window.move_(pos.0 / scale_factor, pos.1 / scale_factor);
+        }
+        if placement.fullscreen {
+            window.fullscreen();
+        }
+
+        info!("Constucted new window with size {:?}", ideal);
+        let draw = shared.draw.draw.new_window();
+
+        let mut r = Window {
+            widget,
+            window_id,
+            mgr,
+            solve_cache,
+            window,
+            area,
+            size: ideal,
+            draw,
+            theme_window,
+            timer: None,
+        };
+        r.apply_size(shared);
+
+        trace!("Window::new completed in {}µs", time.elapsed().as_micros());
+        r
+    }
+
+    /// Handle a GDK input event
+    ///
+    /// The closure is given the manager and widget.
+    pub fn handle_input<F>(&mut self, shared: &mut SharedState<T>, f: F)
+    where
+        F: FnOnce(&mut kas::event::Manager, &mut dyn kas::Window),
+    {
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| f(mgr, widget));
+    }
+
+    /// Physical coordinate from a GDK (logical) position
+    pub fn coord(&self, pos: (f64, f64)) -> Coord {
+        let factor = f64::from(self.window.scale_factor());
+        Coord(
+            (pos.0 * factor).cast_nearest(),
+            (pos.1 * factor).cast_nearest(),
+        )
+    }
+
+    /// Handle a key press or release
+    pub fn handle_key(
+        &mut self,
+        shared: &mut SharedState<T>,
+        event: &gdk::EventKey,
+        pressed: bool,
+    ) {
+        let scancode = u32::from(event.hardware_keycode());
+        let keyval = event.keyval();
+        let modifiers = convert::modifiers(event.state());
+        self.handle_input(shared, |mgr, widget| {
+            mgr.handle_modifiers(modifiers);
+            if pressed {
+                if let Some(vkey) = convert::vkey(&keyval) {
+                    mgr.handle_key_press(widget, vkey, scancode);
+                }
+                // GTK reports plain characters for shortcuts such as Ctrl+A
+                // (winit reports control codes, which Manager filters out).
+                let shortcut = modifiers.ctrl() || modifiers.alt() || modifiers.logo();
+                if let Some(c) = keyval.to_unicode().filter(|_| !shortcut) {
+                    mgr.handle_received_char(widget, c);
+                }
+            } else {
+                mgr.handle_key_release(scancode);
+            }
+        });
+    }
+
+    /// Handle change in size (physical pixels)
+    pub fn do_resize(&mut self, shared: &mut SharedState<T>, size: Size) {
+        let time = Instant::now();
+        if size == self.size {
+            return;
+        }
+        self.size = size;
+
+        // Note that on resize, width adjustments may affect height
+        // requirements; we therefore refresh size restrictions.
+        self.apply_size(shared);
+
+        trace!(
+            "do_resize completed in {}µs (including apply_size time)",
+            time.elapsed().as_micros()
+        );
+    }
+
+    /// Handle change in scale factor
+    pub fn set_scale_factor(&mut self, shared: &mut SharedState<T>, scale_factor: i32) {
+        let scale_factor = scale_factor as f32;
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        self.mgr
+            .handle_scale_factor_changed(&mut tkw, &mut *self.widget, scale_factor);
+        shared
+            .theme
+            .update_window(&mut self.theme_window, scale_factor);
+        self.solve_cache.invalidate_rule_cache();
+        let alloc = self.area.allocation();
+        let factor = self.window.scale_factor();
+        let size = Size(alloc.width() * factor, alloc.height() * factor);
+        self.size = Size::ZERO;
+        self.do_resize(shared, size);
+    }
+
+    /// Update, after receiving all events
+    pub fn update(&mut self, shared: &mut SharedState<T>) -> (TkAction, Option<Instant>) {
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        let action = self.mgr.update(&mut tkw, &mut *self.widget);
+        drop(tkw);
+
+        if action.contains(TkAction::CLOSE | TkAction::EXIT) {
+            return (action, None);
+        }
+        self.handle_action(shared, action);
+
+        (action, self.mgr.next_resume())
+    }
+
+    /// Handle an action (excludes handling of CLOSE and EXIT)
+    /// Handle an action shared by all windows (e.g. from a theme adjustment)
+    pub fn handle_shared_action(&mut self, shared: &mut SharedState<T>, action: TkAction) {
+        if action.contains(TkAction::REDRAW) {
+            // The theme may have changed
+            self.mgr.theme_changed();
+        }
+        self.handle_action(shared, action);
+    }
+
+    pub fn handle_action(&mut self, shared: &mut SharedState<T>, action: TkAction) {
+        if action.contains(TkAction::RECONFIGURE) {
+            self.reconfigure(shared);
+        }
+        if action.contains(TkAction::THEME_UPDATE) {
+            self.mgr.theme_changed();
+            let scale_factor = self.window.scale_factor() as f32;
+            shared
+                .theme
+                .update_window(&mut self.theme_window, scale_factor);
+        }
+        if action.contains(TkAction::RESIZE) {
+            self.solve_cache.invalidate_rule_cache();
+            self.apply_size(shared);
+        } else if action.contains(TkAction::SET_SIZE) {
+            self.apply_size(shared);
+        }
+        if action.contains(TkAction::REGION_MOVED) {
+            let mut tkw = TkWindow::new(
+                shared,
+                self.window_id,
+                Some(&self.window),
+                &mut self.theme_window,
+            );
+            self.mgr.region_moved(&mut tkw, &mut *self.widget);
+            self.area.queue_draw();
+        } else if action.contains(TkAction::REDRAW) {
+            self.area.queue_draw();
+        }
+    }
+
+    pub fn handle_closure(mut self, shared: &mut SharedState<T>) -> TkAction {
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            widget.handle_closure(mgr);
+        });
+        self.mgr.update(&mut tkw, &mut *self.widget)
+    }
+
+    pub fn update_timer(&mut self, shared: &mut SharedState<T>) {
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            mgr.update_timer(widget);
+        });
+    }
+
+    pub fn update_handle(
+        &mut self,
+        shared: &mut SharedState<T>,
+        handle: UpdateHandle,
+        payload: u64,
+    ) {
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            mgr.update_handle(widget, handle, payload);
+        });
+    }
+
+    pub fn add_popup(&mut self, shared: &mut SharedState<T>, id: WindowId, popup: kas::Popup) {
+        let window = &mut *self.widget;
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        self.mgr.with(&mut tkw, |mgr| {
+            kas::Window::add_popup(window, mgr, id, popup);
+        });
+    }
+
+    pub fn send_action(&mut self, action: TkAction) {
+        self.mgr.send_action(action);
+    }
+
+    pub fn send_close(&mut self, shared: &mut SharedState<T>, id: WindowId) {
+        if id == self.window_id {
+            self.mgr.send_action(TkAction::CLOSE);
+        } else {
+            let mut tkw = TkWindow::new(
+                shared,
+                self.window_id,
+                Some(&self.window),
+                &mut self.theme_window,
+            );
+            let widget = &mut *self.widget;
+            self.mgr.with(&mut tkw, |mgr| {
+                widget.remove_popup(mgr, id);
+            });
+        }
+    }
+
+    pub(crate) fn do_draw(&mut self, shared: &mut SharedState<T>, cr: &cairo::Context) {
+        let time = Instant::now();
+        self.draw.reset(self.size);
+
+        {
+            let draw = DrawIface {
+                draw: &mut self.draw,
+                shared: &mut shared.draw,
+                pass: PassId::new(0),
+            };
+
+            #[cfg(not(feature = "gat"))]
+            unsafe {
+                // Safety: lifetimes do not escape the returned draw_handle value.
+                let mut draw_handle = shared.theme.draw_handle(draw, &mut self.theme_window);
+                self.widget.draw(&mut draw_handle, &self.mgr, false);
+                self.solve_cache
+                    .draw_overflow(self.widget.rect(), &mut draw_handle);
+                self.mgr
+                    .draw_inspector(self.widget.as_widget(), &mut draw_handle);
+                self.mgr
+                    .draw_toasts(self.widget.as_widget(), &mut draw_handle);
+                self.mgr
+                    .draw_tooltip(self.widget.as_widget(), &mut draw_handle);
+            }
+            #[cfg(feature = "gat")]
+            {
+                let mut draw_handle = shared.theme.draw_handle(draw, &mut self.theme_window);
+                self.widget.draw(&mut draw_handle, &self.mgr, false);
+                self.solve_cache
+                    .draw_overflow(self.widget.rect(), &mut draw_handle);
+                self.mgr
+                    .draw_inspector(self.widget.as_widget(), &mut draw_handle);
+                self.mgr
+                    .draw_toasts(self.widget.as_widget(), &mut draw_handle);
+                self.mgr
+                    .draw_tooltip(self.widget.as_widget(), &mut draw_handle);
+            }
+        }
+
+        let time2 = Instant::now();
+        // GTK uses logical coordinates; we draw in physical pixels
+        let factor = 1.0 / f64::from(self.window.scale_factor());
+        cr.scale(factor, factor);
+        if let Err(e) = self.draw.render(cr, shared.theme.clear_color()) {
+            warn!("Failed to render: {}", e);
+        }
+
+        // Report the predicted presentation time and the refresh interval
+        if let Some(clock) = self.area.frame_clock() {
+            let (interval, present) = clock.refresh_info(clock.frame_time());
+            let now = glib::monotonic_time();
+            let mut time = Instant::now();
+            if present > now {
+                time += Duration::from_micros((present - now).cast());
+            }
+            let interval = (interval > 0).then(|| Duration::from_micros(interval.cast()));
+            self.mgr.frame_presented(time, interval);
+        }
+
+        let end = Instant::now();
+        trace!(
+            "do_draw completed in {}µs ({}μs widgets, {}µs render)",
+            (end - time).as_micros(),
+            (time2 - time).as_micros(),
+            (end - time2).as_micros()
+        );
+    }
+}
+
+// Internal functions
+impl<T: Theme<DrawPipe>> Window<T>
+where
+    T::Window: kas_theme::Window,
+{
+    fn reconfigure(&mut self, shared: &mut SharedState<T>) {
+        let time = Instant::now();
+        debug!("Window::reconfigure");
+
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        self.mgr.configure(&mut tkw, &mut *self.widget);
+
+        self.solve_cache.invalidate_rule_cache();
+        self.apply_size(shared);
+        trace!("reconfigure completed in {}µs", time.elapsed().as_micros());
+    }
+
+    fn apply_size(&mut self, shared: &mut SharedState<T>) {
+        let time = Instant::now();
+        let rect = Rect::new(Coord::ZERO, self.size);
+        debug!("Resizing window to rect = {:?}", rect);
+
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        self.mgr.update_window_size(rect.size);
+        let solve_cache = &mut self.solve_cache;
+        let widget = &mut self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            solve_cache.apply_rect(widget.as_widget_mut(), mgr, rect, true);
+            widget.resize_popups(mgr);
+        });
+
+        let (min, _) = self.widget.attributes().size_limits(
+            self.solve_cache.min(true),
+            self.solve_cache.ideal(true),
+            self.widget.restrict_dimensions(),
+        );
+        if let Some(min) = min {
+            let scale_factor = self.window.scale_factor();
+            self.area
+                .set_size_request(min.0 / scale_factor, min.1 / scale_factor);
+        }
+
+        self.area.queue_draw();
+        trace!("apply_size completed in {}µs", time.elapsed().as_micros());
+    }
+}
+
+pub(crate) struct TkWindow<'a, T: Theme<DrawPipe>>
+where
+    T::Window: kas_theme::Window,
+{
+    shared: &'a mut SharedState<T>,
+    window_id: WindowId,
+    window: Option<&'a gtk::Window>,
+    theme_window: &'a mut T::Window,
+}
+
+impl<'a, T: Theme<DrawPipe>> TkWindow<'a, T>
+where
+    T::Window: kas_theme::Window,
+{
+    pub(crate) fn new(
+        shared: &'a mut SharedState<T>,
+        window_id: WindowId,
+        window: Option<&'a gtk::Window>,
+        theme_window: &'a mut T::Window,
+    ) -> Self {
+        TkWindow {
+            shared,
+            window_id,
+            window,
+            theme_window,
+        }
+    }
+}
+
+impl<'a, T> kas::ShellWindow for TkWindow<'a, T>
+where
+    T: Theme<DrawPipe>,
+    T::Window: kas_theme::Window,
+{
+    fn capabilities(&self) -> ShellCapabilities {
+        // Pop-ups are drawn within the parent window
+        let mut caps = ShellCapabilities::CLIPBOARD
+            | ShellCapabilities::WINDOWS
+            | ShellCapabilities::OPEN_URL
+            | ShellCapabilities::MONITORS;
+        if self.window.is_some() {
+            caps |= ShellCapabilities::POPUPS
+                | ShellCapabilities::WINDOW_CONTROL
+                | ShellCapabilities::DRAG_WINDOW
+                | ShellCapabilities::DRAG_RESIZE;
+        }
+        caps
+    }
+
+    fn add_popup(&mut self, popup: kas::Popup) -> Option<WindowId> {
+        self.window.map(|_| {
+            let id = self.shared.next_window_id();
+            self.shared
+                .pending
+                .push(PendingAction::AddPopup(self.window_id, id, popup));
+            id
+        })
+    }
+
+    fn add_window(&mut self, widget: Box<dyn kas::Window>) -> WindowId {
+        // Windows are constructed by the event loop after event handling
+        // completes; this avoids re-entrancy from GTK signals.
+        let id = self.shared.next_window_id();
+        self.shared
+            .pending
+            .push(PendingAction::AddWindow(id, widget));
+        id
+    }
+
+    fn close_window(&mut self, id: WindowId) {
+        self.shared.pending.push(PendingAction::CloseWindow(id));
+    }
+
+    fn trigger_update(&mut self, handle: UpdateHandle, payload: u64) {
+        self.shared.trigger_update(handle, payload);
+    }
+
+    #[inline]
+    fn get_clipboard(&mut self) -> Option<String> {
+        self.shared.get_clipboard()
+    }
+
+    #[inline]
+    fn set_clipboard<'c>(&mut self, content: String) {
+        self.shared.set_clipboard(content);
+    }
+
+    #[inline]
+    fn open_url(&mut self, url: &str) -> bool {
+        kas_shell::open_url(url)
+    }
+
+    fn adjust_theme(&mut self, f: &mut dyn FnMut(&mut dyn ThemeApi) -> TkAction) {
+        let action = f(&mut self.shared.theme);
+        self.shared.pending.push(PendingAction::TkAction(action));
+    }
+
+    fn size_handle(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
+        use kas_theme::Window;
+        let mut size_handle = self.theme_window.size_handle();
+        f(&mut size_handle);
+    }
+
+    fn draw_shared(&mut self, f: &mut dyn FnMut(&mut dyn DrawShared)) {
+        f(&mut self.shared.draw);
+    }
+
+    #[inline]
+    fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        let gdk_window = self.window.and_then(|w| w.window());
+        if let Some(gdk_window) = gdk_window {
+            let display = gdk_window.display();
+            let cursor = gdk::Cursor::from_name(&display, convert::cursor_name(icon));
+            gdk_window.set_cursor(cursor.as_ref());
+        }
+    }
+
+    fn set_decorations(&mut self, decorations: bool) {
+        if let Some(window) = self.window {
+            window.set_decorated(decorations);
+        }
+    }
+
+    fn set_resizable(&mut self, resizable: bool) {
+        if let Some(window) = self.window {
+            window.set_resizable(resizable);
+        }
+    }
+
+    fn set_always_on_top(&mut self, always_on_top: bool) {
+        if let Some(window) = self.window {
+            window.set_keep_above(always_on_top);
+        }
+    }
+
+    fn set_inner_size(&mut self, size: Size) {
+        if let Some(window) = self.window {
+            let factor = window.scale_factor();
+            window.resize((size.0 / factor).max(1), (size.1 / factor).max(1));
+        }
+    }
+
+    fn drag_window(&mut self) {
+        if let (Some(window), Some((button, x, y, time))) = (self.window, current_press()) {
+            window.begin_move_drag(button, x, y, time);
+        }
+    }
+
+    fn drag_resize_window(&mut self, direction: ResizeDirection) -> bool {
+        if let (Some(window), Some((button, x, y, time))) = (self.window, current_press()) {
+            let edge = match direction {
+                ResizeDirection::North => gdk::WindowEdge::North,
+                ResizeDirection::NorthEast => gdk::WindowEdge::NorthEast,
+                ResizeDirection::East => gdk::WindowEdge::East,
+                ResizeDirection::SouthEast => gdk::WindowEdge::SouthEast,
+                ResizeDirection::South => gdk::WindowEdge::South,
+                ResizeDirection::SouthWest => gdk::WindowEdge::SouthWest,
+                ResizeDirection::West => gdk::WindowEdge::West,
+                ResizeDirection::NorthWest => gdk::WindowEdge::NorthWest,
+            };
+            window.begin_resize_drag(edge, button, x, y, time);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn set_minimized(&mut self) {
+        if let Some(window) = self.window {
+            window.iconify();
+        }
+    }
+
+    fn set_maximized(&mut self, maximized: bool) {
+        if let Some(window) = self.window {
+            if maximized {
+                window.maximize();
+            } else {
+                window.unmaximize();
+            }
+        }
+    }
+
+    fn is_maximized(&self) -> bool {
+        self.window.map(|w| w.is_maximized()).unwrap_or(false)
+    }
+
+    #[inline]
+    fn load_state(&self, key: &str) -> Option<String> {
+        self.shared.state.load(key)
+    }
+
+    #[inline]
+    fn save_state(&mut self, key: &str, value: String) {
+        self.shared.state.save(key, value);
+    }
+
+    fn translate(&self, key: &str) -> Option<String> {
+        self.shared.localizer.as_ref()?.translate(key)
+    }
+
+    fn locale(&self) -> Option<String> {
+        let localizer = self.shared.localizer.as_ref()?;
+        Some(localizer.locale().to_string())
+    }
+
+    fn set_locale(&mut self, locale: &str) {
+        if let Some(localizer) = self.shared.localizer.as_mut() {
+            if localizer.set_locale(locale) {
+                let action = TkAction::RECONFIGURE;
+                self.shared.pending.push(PendingAction::TkAction(action));
+            }
+        }
+    }
+
+    fn monitors(&self) -> Vec<MonitorInfo> {
+        let display = match gdk::Display::default() {
+            Some(display) => display,
+            None => return vec![],
+        };
+        (0..display.n_monitors())
+            .filter_map(|i| display.monitor(i))
+            .map(|monitor| monitor_info(&monitor))
+            .collect()
+    }
+
+    fn current_monitor(&self) -> Option<usize> {
+        let gdk_window = self.window?.window()?;
+        let display = gdk_window.display();
+        let current = display.monitor_at_window(&gdk_window)?;
+        (0..display.n_monitors()).position(|i| display.monitor(i).as_ref() == Some(&current))
+    }
+}
+
+/// Button, root coordinates and time of the current (press) event
+fn current_press() -> Option<(i32, i32, i32, u32)> {
+    let event = gtk::current_event()?;
+    let button = event.button()?;
+    let (x, y) = event.root_coords()?;
+    Some((
+        button.cast(),
+        x.cast_nearest(),
+        y.cast_nearest(),
+        event.time(),
+    ))
+}
+
+fn monitor_info(monitor: &gdk::Monitor) -> MonitorInfo {
+    // GDK reports geometry in application pixels
+    let geom = monitor.geometry();
+    let factor = monitor.scale_factor();
+    MonitorInfo {
+        name: monitor.model().map(|s| s.to_string()),
+        position: Coord(geom.x() * factor, geom.y() * factor),
+        size: Size(geom.width() * factor, geom.height() * factor),
+        scale_factor: factor.into(),
+    }
+}
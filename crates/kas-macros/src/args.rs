@@ -13,9 +13,9 @@ use syn::spanned::Spanned;
 use syn::token::{Brace, Colon, Comma, Eq, For, Impl, Paren, Semi};
 use syn::{braced, bracketed, parenthesized, parse_quote};
 use syn::{
-    AttrStyle, Attribute, ConstParam, Expr, Field, Fields, FieldsNamed, GenericParam, Generics,
-    Ident, Index, ItemImpl, Lifetime, LifetimeDef, Member, Path, Token, Type, TypeParam, TypePath,
-    TypeTraitObject, Visibility,
+    AttrStyle, Attribute, ConstParam, Expr, ExprClosure, Field, Fields, FieldsNamed, GenericParam,
+    Generics, Ident, Index, ItemImpl, Lifetime, LifetimeDef, Lit, Member, Path, Token, Type,
+    TypeParam, TypePath, TypeTraitObject, Visibility,
 };
 
 pub struct Child {
@@ -125,7 +125,13 @@ impl Parse for Widget {
                     }
                 } else if attr.path == parse_quote! { widget } {
                     let ident = member(i, field.ident.clone());
-                    let args = syn::parse2(attr.tokens)?;
+                    let args: WidgetAttrArgs = syn::parse2(attr.tokens)?;
+                    if let Some(ref closure) = args.closure {
+                        emit_error!(
+                            closure.span(),
+                            "inline handler closures are only supported by make_widget!"
+                        );
+                    }
                     children.push(Child { ident, args });
                 } else {
                     other_attrs.push(attr);
@@ -356,7 +362,13 @@ mod kw {
     custom_keyword!(align);
     custom_keyword!(halign);
     custom_keyword!(valign);
+    custom_keyword!(stretch);
+    custom_keyword!(margins);
     custom_keyword!(key_nav);
+    custom_keyword!(focus_scope);
+    custom_keyword!(tab_index);
+    custom_keyword!(nav_wrap);
+    custom_keyword!(initial_focus);
     custom_keyword!(hover_highlight);
     custom_keyword!(cursor_icon);
     custom_keyword!(handle);
@@ -408,12 +420,136 @@ impl Handler {
             Handler::Use(n) | Handler::Map(n) | Handler::FlatMap(n) => Some(n),
         }
     }
+    pub fn any_mut(&mut self) -> Option<&mut Ident> {
+        match self {
+            Handler::None | Handler::Discard => None,
+            Handler::Use(n) | Handler::Map(n) | Handler::FlatMap(n) => Some(n),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct WidgetAttrArgs {
     pub update: Option<Ident>,
     pub handler: Handler,
+    /// Inline handler closure (`handler` holds a placeholder name)
+    pub closure: Option<ExprClosure>,
+    /// Stretch priority: one of `none`, `filler`, `low`, `high`, `maximize`
+    pub stretch: Option<Ident>,
+    /// Horizontal alignment
+    pub halign: Option<Ident>,
+    /// Vertical alignment
+    pub valign: Option<Ident>,
+    /// Margins override
+    pub margins: Option<MarginsArg>,
+}
+
+/// Margins: one of `none`, `outer`, `inner`, `text` or a (scaled) size
+#[derive(Debug)]
+pub enum MarginsArg {
+    Ident(Ident),
+    Lit(Lit),
+}
+
+impl ToTokens for MarginsArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            MarginsArg::Ident(ident) => ident.to_tokens(tokens),
+            MarginsArg::Lit(lit) => lit.to_tokens(tokens),
+        }
+    }
+}
+
+const STRETCH_VALUES: &[&str] = &["none", "filler", "low", "high", "maximize"];
+const ALIGN_VALUES: &[&str] = &["centre", "center", "stretch"];
+const HALIGN_VALUES: &[&str] = &["default", "left", "centre", "center", "right", "stretch"];
+const VALIGN_VALUES: &[&str] = &["default", "top", "centre", "center", "bottom", "stretch"];
+const MARGINS_VALUES: &[&str] = &["none", "outer", "inner", "text"];
+
+/// Parse an identifier, which must be one of `values`
+fn parse_ident_in(input: ParseStream, values: &[&str]) -> Result<Ident> {
+    let ident: Ident = input.parse()?;
+    if !values.iter().any(|v| ident == v) {
+        let msg = format!("expected one of: {}", values.join(", "));
+        return Err(Error::new(ident.span(), msg));
+    }
+    Ok(ident)
+}
+
+/// Map an alignment identifier to an `Align` expression
+fn align_expr(ident: Option<&Ident>) -> TokenStream {
+    let ident = match ident {
+        Some(ident) => ident,
+        None => return quote! { None },
+    };
+    let variant = match ident.to_string().as_str() {
+        "default" => "Default",
+        "left" | "top" => "TL",
+        "centre" | "center" => "Center",
+        "right" | "bottom" => "BR",
+        "stretch" => "Stretch",
+        _ => unreachable!(),
+    };
+    let variant = Ident::new(variant, ident.span());
+    quote! { Some(::kas::layout::Align::#variant) }
+}
+
+impl WidgetAttrArgs {
+    /// Get the stretch priority as a `Stretch` expression, if set
+    pub fn stretch_expr(&self) -> Option<TokenStream> {
+        self.stretch.as_ref().map(|ident| {
+            let variant = match ident.to_string().as_str() {
+                "none" => "None",
+                "filler" => "Filler",
+                "low" => "Low",
+                "high" => "High",
+                "maximize" => "Maximize",
+                _ => unreachable!(),
+            };
+            let variant = Ident::new(variant, ident.span());
+            quote! { ::kas::layout::Stretch::#variant }
+        })
+    }
+
+    /// Get the alignment as an `AlignHints` expression, if set
+    pub fn align_expr(&self) -> Option<TokenStream> {
+        if self.halign.is_none() && self.valign.is_none() {
+            return None;
+        }
+        let horiz = align_expr(self.halign.as_ref());
+        let vert = align_expr(self.valign.as_ref());
+        Some(quote! { ::kas::layout::AlignHints::new(#horiz, #vert) })
+    }
+
+    /// Get the margins as a `MarginSelector` expression, if set
+    pub fn margins_expr(&self) -> Option<TokenStream> {
+        self.margins.as_ref().map(|margins| match margins {
+            MarginsArg::Ident(ident) => match ident.to_string().as_str() {
+                "none" => quote! {
+                    ::kas::layout::MarginSelector::Fixed(::kas::layout::Margins::ZERO)
+                },
+                "outer" => quote! { ::kas::layout::MarginSelector::Outer },
+                "inner" => quote! { ::kas::layout::MarginSelector::Inner },
+                "text" => quote! { ::kas::layout::MarginSelector::Text },
+                _ => unreachable!(),
+            },
+            MarginsArg::Lit(lit) => {
+                quote! { ::kas::layout::MarginSelector::ScaledSplat(#lit as f32) }
+            }
+        })
+    }
+}
+
+/// Parse a handler: either a method name or an inline closure
+fn parse_handler_fn(input: ParseStream) -> Result<(Ident, Option<ExprClosure>)> {
+    if input.peek(Token![|]) || input.peek(Token![||]) || input.peek(Token![move]) {
+        let closure: ExprClosure = input.parse()?;
+        // make_widget! replaces this with a unique name
+        let ident = Ident::new("__kas_handler", closure.span());
+        Ok((ident, Some(closure)))
+    } else {
+        Ok((input.parse()?, None))
+    }
 }
 
 impl Parse for WidgetAttrArgs {
@@ -421,6 +557,11 @@ impl Parse for WidgetAttrArgs {
         let mut args = WidgetAttrArgs {
             update: None,
             handler: Handler::None,
+            closure: None,
+            stretch: None,
+            halign: None,
+            valign: None,
+            margins: None,
         };
         if input.is_empty() {
             return Ok(args);
@@ -438,18 +579,53 @@ impl Parse for WidgetAttrArgs {
             } else if args.handler.is_none() && lookahead.peek(kw::flatmap_msg) {
                 let _: kw::flatmap_msg = content.parse()?;
                 let _: Eq = content.parse()?;
-                args.handler = Handler::FlatMap(content.parse()?);
+                let (ident, closure) = parse_handler_fn(&content)?;
+                args.handler = Handler::FlatMap(ident);
+                args.closure = closure;
             } else if args.handler.is_none() && lookahead.peek(kw::map_msg) {
                 let _: kw::map_msg = content.parse()?;
                 let _: Eq = content.parse()?;
-                args.handler = Handler::Map(content.parse()?);
+                let (ident, closure) = parse_handler_fn(&content)?;
+                args.handler = Handler::Map(ident);
+                args.closure = closure;
             } else if args.handler.is_none() && lookahead.peek(kw::use_msg) {
                 let _: kw::use_msg = content.parse()?;
                 let _: Eq = content.parse()?;
-                args.handler = Handler::Use(content.parse()?);
+                let (ident, closure) = parse_handler_fn(&content)?;
+                args.handler = Handler::Use(ident);
+                args.closure = closure;
             } else if args.handler.is_none() && lookahead.peek(kw::discard_msg) {
                 let _: kw::discard_msg = content.parse()?;
                 args.handler = Handler::Discard;
+            } else if args.stretch.is_none() && lookahead.peek(kw::stretch) {
+                let _: kw::stretch = content.parse()?;
+                let _: Eq = content.parse()?;
+                args.stretch = Some(parse_ident_in(&content, STRETCH_VALUES)?);
+            } else if args.halign.is_none() && args.valign.is_none() && lookahead.peek(kw::align) {
+                let _: kw::align = content.parse()?;
+                let _: Eq = content.parse()?;
+                let ident = parse_ident_in(&content, ALIGN_VALUES)?;
+                args.halign = Some(ident.clone());
+                args.valign = Some(ident);
+            } else if args.halign.is_none() && lookahead.peek(kw::halign) {
+                let _: kw::halign = content.parse()?;
+                let _: Eq = content.parse()?;
+                args.halign = Some(parse_ident_in(&content, HALIGN_VALUES)?);
+            } else if args.valign.is_none() && lookahead.peek(kw::valign) {
+                let _: kw::valign = content.parse()?;
+                let _: Eq = content.parse()?;
+                args.valign = Some(parse_ident_in(&content, VALIGN_VALUES)?);
+            } else if args.margins.is_none() && lookahead.peek(kw::margins) {
+                let _: kw::margins = content.parse()?;
+                let _: Eq = content.parse()?;
+                args.margins = Some(if content.peek(Ident) {
+                    MarginsArg::Ident(parse_ident_in(&content, MARGINS_VALUES)?)
+                } else {
+                    match content.parse()? {
+                        lit @ (Lit::Int(_) | Lit::Float(_)) => MarginsArg::Lit(lit),
+                        lit => return Err(Error::new(lit.span(), "expected size or identifier")),
+                    }
+                });
             } else if lookahead.peek(kw::handler) {
                 let tok: Ident = content.parse()?;
                 return Err(Error::new(
@@ -472,7 +648,13 @@ impl Parse for WidgetAttrArgs {
 
 impl ToTokens for WidgetAttrArgs {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        if self.update.is_some() || self.handler.is_some() {
+        if self.update.is_some()
+            || self.handler.is_some()
+            || self.stretch.is_some()
+            || self.halign.is_some()
+            || self.valign.is_some()
+            || self.margins.is_some()
+        {
             let mut args = TokenStream::new();
             if let Some(ref ident) = self.update {
                 args.append_all(quote! { update = #ident });
@@ -487,6 +669,24 @@ impl ToTokens for WidgetAttrArgs {
                 Handler::FlatMap(f) => args.append_all(quote! { flatmap_msg = #f }),
                 Handler::Discard => args.append_all(quote! { discard_msg }),
             }
+            let mut append = |item: TokenStream| {
+                if !args.is_empty() {
+                    args.append(TokenTree::from(Punct::new(',', Spacing::Alone)));
+                }
+                args.append_all(item);
+            };
+            if let Some(ref ident) = self.stretch {
+                append(quote! { stretch = #ident });
+            }
+            if let Some(ref ident) = self.halign {
+                append(quote! { halign = #ident });
+            }
+            if let Some(ref ident) = self.valign {
+                append(quote! { valign = #ident });
+            }
+            if let Some(ref margins) = self.margins {
+                append(quote! { margins = #margins });
+            }
             tokens.append_all(quote! { ( #args ) });
         }
     }
@@ -534,6 +734,22 @@ property!(
     KeyNav: bool = false;
     kw::key_nav : input => input.parse::<syn::LitBool>()?.value;
 );
+property!(
+    FocusScope: bool = false;
+    kw::focus_scope : input => input.parse::<syn::LitBool>()?.value;
+);
+property!(
+    TabIndex: Expr = parse_quote! { None };
+    kw::tab_index : input => input.parse()?;
+);
+property!(
+    NavWrap: bool = true;
+    kw::nav_wrap : input => input.parse::<syn::LitBool>()?.value;
+);
+property!(
+    InitialFocus: Expr = parse_quote! { false };
+    kw::initial_focus : input => input.parse()?;
+);
 property!(
     HoverHighlight: bool = false;
     kw::hover_highlight : input => input.parse::<syn::LitBool>()?.value;
@@ -550,6 +766,10 @@ property!(
 #[derive(Default)]
 pub struct WidgetArgs {
     pub key_nav: KeyNav,
+    pub focus_scope: FocusScope,
+    pub tab_index: TabIndex,
+    pub nav_wrap: NavWrap,
+    pub initial_focus: InitialFocus,
     pub hover_highlight: HoverHighlight,
     pub cursor_icon: CursorIcon,
     pub derive: Option<Member>,
@@ -560,6 +780,10 @@ pub struct WidgetArgs {
 impl Parse for WidgetArgs {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut key_nav = KeyNav::default();
+        let mut focus_scope = FocusScope::default();
+        let mut tab_index = TabIndex::default();
+        let mut nav_wrap = NavWrap::default();
+        let mut initial_focus = InitialFocus::default();
         let mut hover_highlight = HoverHighlight::default();
         let mut cursor_icon = CursorIcon::default();
         let mut derive = None;
@@ -573,6 +797,14 @@ impl Parse for WidgetArgs {
             let lookahead = content.lookahead1();
             if lookahead.peek(kw::key_nav) && key_nav.span.is_none() {
                 key_nav = content.parse()?;
+            } else if lookahead.peek(kw::focus_scope) && focus_scope.span.is_none() {
+                focus_scope = content.parse()?;
+            } else if lookahead.peek(kw::tab_index) && tab_index.span.is_none() {
+                tab_index = content.parse()?;
+            } else if lookahead.peek(kw::nav_wrap) && nav_wrap.span.is_none() {
+                nav_wrap = content.parse()?;
+            } else if lookahead.peek(kw::initial_focus) && initial_focus.span.is_none() {
+                initial_focus = content.parse()?;
             } else if lookahead.peek(kw::hover_highlight) && hover_highlight.span.is_none() {
                 hover_highlight = content.parse()?;
             } else if lookahead.peek(kw::cursor_icon) && cursor_icon.span.is_none() {
@@ -598,6 +830,10 @@ impl Parse for WidgetArgs {
 
         Ok(WidgetArgs {
             key_nav,
+            focus_scope,
+            tab_index,
+            nav_wrap,
+            initial_focus,
             hover_highlight,
             cursor_icon,
             derive,
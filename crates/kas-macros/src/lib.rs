@@ -215,11 +215,17 @@ pub fn make_widget(input: TokenStream) -> TokenStream {
 /// > _Slice_ :\
 /// > &nbsp;&nbsp; `slice` `(` _Direction_ `)` `:` `self` `.` _Member_
 /// >
+/// > _Flow_ :\
+/// > &nbsp;&nbsp; `flow` `:` `self` `.` _Member_
+/// >
 /// > _Frame_ :\
 /// > &nbsp;&nbsp; `frame` `(` _Layout_ `)`
 /// >
+/// > _Region_ :\
+/// > &nbsp;&nbsp; `region` `(` _Ident_ `)` `:` _Layout_
+/// >
 /// > _Layout_ :\
-/// > &nbsp;&nbsp; &nbsp;&nbsp; _Align_ | _Single_ | _List_ | _Slice_ | _Frame_
+/// > &nbsp;&nbsp; &nbsp;&nbsp; _Align_ | _Single_ | _List_ | _Slice_ | _Flow_ | _Frame_ | _Region_
 /// >
 /// > _MakeLayout_:\
 /// > &nbsp;&nbsp; `(` _CoreData_ `;` _Layout_ `)`
@@ -236,6 +242,14 @@ pub fn make_widget(input: TokenStream) -> TokenStream {
 /// _Slice_ is a variant of _List_ over a single struct field, supporting
 /// `AsMut<W>` for some widget type `W`.
 ///
+/// _Flow_ is similar to _Slice_, but places children left-to-right, wrapping
+/// to a new line when the width is exhausted.
+///
+/// _Region_ does not affect layout, but names the contained sub-layout. The
+/// `Rect` assigned to this region may be queried from draw and event-handling
+/// code via `self.core.layout_region("NAME")` (see `CoreData::layout_region`),
+/// allowing custom drawing aligned with the generated layout.
+///
 /// _Member_ is a field name (struct) or number (tuple struct).
 ///
 /// # Example
@@ -3,11 +3,12 @@
 // You may obtain a copy of the License in the LICENSE-APACHE file or at:
 //     https://www.apache.org/licenses/LICENSE-2.0
 
+use crate::args::Child;
 use proc_macro2::{Span, TokenStream as Toks};
 use quote::{quote, TokenStreamExt};
 use syn::parse::{Error, Parse, ParseStream, Result};
 use syn::spanned::Spanned;
-use syn::{braced, bracketed, parenthesized, Expr, LitInt, Member, Token};
+use syn::{braced, bracketed, parenthesized, Expr, Ident, LitInt, Token};
 
 #[allow(non_camel_case_types)]
 mod kw {
@@ -25,8 +26,10 @@ mod kw {
     custom_keyword!(stretch);
     custom_keyword!(frame);
     custom_keyword!(nav_frame);
+    custom_keyword!(region);
     custom_keyword!(list);
     custom_keyword!(slice);
+    custom_keyword!(flow);
     custom_keyword!(grid);
     custom_keyword!(single);
 }
@@ -38,11 +41,8 @@ pub struct Input {
 
 pub struct Tree(Layout);
 impl Tree {
-    pub fn generate<'a, I: ExactSizeIterator<Item = &'a Member>>(
-        &'a self,
-        children: I,
-    ) -> Result<Toks> {
-        self.0.generate(Some(children))
+    pub fn generate(&self, children: &[Child]) -> Result<Toks> {
+        self.0.generate(Some(children), children)
     }
 }
 
@@ -53,8 +53,10 @@ enum Layout {
     Widget(Expr),
     Frame(Box<Layout>),
     NavFrame(Box<Layout>),
+    Region(Ident, Box<Layout>),
     List(Direction, List),
     Slice(Direction, Expr),
+    Flow(Expr),
     Grid(GridDimensions, Vec<(CellInfo, Layout)>),
 }
 
@@ -187,6 +189,14 @@ impl Parse for Layout {
             let _ = parenthesized!(inner in input);
             let layout: Layout = inner.parse()?;
             Ok(Layout::NavFrame(Box::new(layout)))
+        } else if lookahead.peek(kw::region) {
+            let _: kw::region = input.parse()?;
+            let inner;
+            let _ = parenthesized!(inner in input);
+            let name: Ident = inner.parse()?;
+            let _: Token![:] = input.parse()?;
+            let layout: Layout = input.parse()?;
+            Ok(Layout::Region(name, Box::new(layout)))
         } else if lookahead.peek(kw::column) {
             let _: kw::column = input.parse()?;
             let dir = Direction::Down;
@@ -218,6 +228,14 @@ impl Parse for Layout {
             } else {
                 Err(Error::new(input.span(), "expected `self`"))
             }
+        } else if lookahead.peek(kw::flow) {
+            let _: kw::flow = input.parse()?;
+            let _: Token![:] = input.parse()?;
+            if input.peek(Token![self]) {
+                Ok(Layout::Flow(input.parse()?))
+            } else {
+                Err(Error::new(input.span(), "expected `self`"))
+            }
         } else if lookahead.peek(kw::grid) {
             let _: kw::grid = input.parse()?;
             let _: Token![:] = input.parse()?;
@@ -350,36 +368,71 @@ impl quote::ToTokens for GridDimensions {
     }
 }
 
+/// Find the child referenced by `expr`, if `expr` is of the form `self.child`
+fn find_child<'a>(expr: &Expr, all: &'a [Child]) -> Option<&'a Child> {
+    match expr {
+        Expr::Field(field) => match &*field.base {
+            Expr::Path(path) if path.path.is_ident("self") => {
+                all.iter().find(|child| child.ident == field.member)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Apply per-child layout properties (if any) to a child's `layout`
+fn child_props(child: Option<&Child>, mut layout: Toks) -> Toks {
+    let args = match child {
+        Some(child) => &child.args,
+        None => return layout,
+    };
+    if let Some(margins) = args.margins_expr() {
+        layout = quote! { ::kas::layout::Layout::margins(#layout, #margins) };
+    }
+    if let Some(stretch) = args.stretch_expr() {
+        layout = quote! { ::kas::layout::Layout::stretch(#layout, #stretch) };
+    }
+    if let Some(align) = args.align_expr() {
+        layout = quote! { ::kas::layout::Layout::align(#layout, #align) };
+    }
+    layout
+}
+
 impl Layout {
     // Optionally pass in the list of children, but not when already in a
-    // multi-element layout (list/slice/grid).
-    fn generate<'a, I: ExactSizeIterator<Item = &'a Member>>(
-        &'a self,
-        children: Option<I>,
-    ) -> Result<Toks> {
+    // multi-element layout (list/slice/grid). All children are passed as
+    // `all` (for lookup of per-child properties).
+    fn generate(&self, children: Option<&[Child]>, all: &[Child]) -> Result<Toks> {
         Ok(match self {
             Layout::Align(layout, align) => {
-                let inner = layout.generate(children)?;
+                let inner = layout.generate(children, all)?;
                 quote! { ::kas::layout::Layout::align(#inner, #align) }
             }
             Layout::AlignSingle(expr, align) => {
-                quote! { ::kas::layout::Layout::align_single(#expr.as_widget_mut(), #align) }
+                let layout = quote! {
+                    ::kas::layout::Layout::align_single(#expr.as_widget_mut(), #align)
+                };
+                child_props(find_child(expr, all), layout)
+            }
+            Layout::Widget(expr) => {
+                let layout = quote! { ::kas::layout::Layout::single(#expr.as_widget_mut()) };
+                child_props(find_child(expr, all), layout)
             }
-            Layout::Widget(expr) => quote! {
-                ::kas::layout::Layout::single(#expr.as_widget_mut())
-            },
             Layout::Single(span) => {
-                if let Some(mut iter) = children {
-                    if iter.len() != 1 {
+                if let Some(children) = children {
+                    if children.len() != 1 {
                         return Err(Error::new(
                             *span,
                             "layout `single`: widget does not have exactly one child",
                         ));
                     }
-                    let child = iter.next().unwrap();
-                    quote! {
-                        ::kas::layout::Layout::single(self.#child.as_widget_mut())
-                    }
+                    let child = &children[0];
+                    let member = &child.ident;
+                    let layout = quote! {
+                        ::kas::layout::Layout::single(self.#member.as_widget_mut())
+                    };
+                    child_props(Some(child), layout)
                 } else {
                     return Err(Error::new(
                         *span,
@@ -388,7 +441,7 @@ impl Layout {
                 }
             }
             Layout::Frame(layout) => {
-                let inner = layout.generate(children)?;
+                let inner = layout.generate(children, all)?;
                 quote! {
                     let (data, next) = _chain.storage::<::kas::layout::FrameStorage>();
                     _chain = next;
@@ -396,13 +449,23 @@ impl Layout {
                 }
             }
             Layout::NavFrame(layout) => {
-                let inner = layout.generate(children)?;
+                let inner = layout.generate(children, all)?;
                 quote! {
                     let (data, next) = _chain.storage::<::kas::layout::FrameStorage>();
                     _chain = next;
                     ::kas::layout::Layout::nav_frame(data, #inner)
                 }
             }
+            Layout::Region(name, layout) => {
+                let name = name.to_string();
+                let data = quote! { {
+                    let (data, next) = _chain.storage::<::kas::layout::RegionStorage>();
+                    _chain = next;
+                    data
+                } };
+                let inner = layout.generate(children, all)?;
+                quote! { ::kas::layout::Layout::region(#data, #name, #inner) }
+            }
             Layout::List(dir, list) => {
                 let len;
                 let mut items = Toks::new();
@@ -410,17 +473,20 @@ impl Layout {
                     List::List(list) => {
                         len = list.len();
                         for item in list {
-                            let item = item.generate::<std::iter::Empty<&Member>>(None)?;
+                            let item = item.generate(None, all)?;
                             items.append_all(quote! { #item, });
                         }
                     }
                     List::Glob(span) => {
-                        if let Some(iter) = children {
-                            len = iter.len();
-                            for member in iter {
-                                items.append_all(quote! {
-                                    ::kas::layout::Layout::single(self.#member.as_widget_mut()),
-                                });
+                        if let Some(children) = children {
+                            len = children.len();
+                            for child in children {
+                                let member = &child.ident;
+                                let layout = quote! {
+                                    ::kas::layout::Layout::single(self.#member.as_widget_mut())
+                                };
+                                let item = child_props(Some(child), layout);
+                                items.append_all(quote! { #item, });
                             }
                         } else {
                             return Err(Error::new(
@@ -455,6 +521,14 @@ impl Layout {
                 } };
                 quote! { ::kas::layout::Layout::slice(&mut #expr, #dir, #data) }
             }
+            Layout::Flow(expr) => {
+                let data = quote! { {
+                    let (data, next) = _chain.storage::<::kas::layout::FlowStorage>();
+                    _chain = next;
+                    data
+                } };
+                quote! { ::kas::layout::Layout::flow(&mut #expr, #data) }
+            }
             Layout::Grid(dim, cells) => {
                 let (rows, cols) = (dim.rows as usize, dim.cols as usize);
                 let data = quote! { {
@@ -467,7 +541,7 @@ impl Layout {
                 for item in cells {
                     let (row, row_end) = (item.0.row, item.0.row_end);
                     let (col, col_end) = (item.0.col, item.0.col_end);
-                    let layout = item.1.generate::<std::iter::Empty<&Member>>(None)?;
+                    let layout = item.1.generate(None, all)?;
                     items.append_all(quote! {
                         (
                             ::kas::layout::GridChildInfo {
@@ -490,7 +564,7 @@ impl Layout {
 
 pub fn make_layout(input: Input) -> Result<Toks> {
     let core = &input.core;
-    let layout = input.layout.0.generate::<std::iter::Empty<&Member>>(None)?;
+    let layout = input.layout.0.generate(None, &[])?;
     Ok(quote! { {
         use ::kas::WidgetCore;
         let mut _chain = &mut #core.layout;
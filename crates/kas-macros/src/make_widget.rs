@@ -6,14 +6,57 @@
 use crate::args::{ChildType, Handler, MakeWidget};
 use crate::extend_generics;
 use proc_macro2::{Span, TokenStream};
-use proc_macro_error::abort;
+use proc_macro_error::{abort, abort_if_dirty};
 use quote::{quote, TokenStreamExt};
 use std::fmt::Write;
 use syn::parse_quote;
 use syn::spanned::Spanned;
-use syn::{Generics, Ident, ItemImpl, Type, TypePath, WhereClause};
+use syn::{ExprClosure, Generics, Ident, ItemImpl, Pat, ReturnType, Type, TypePath, WhereClause};
+
+/// Convert an inline handler closure `|self, mgr, msg: M| body` to a method
+fn closure_method(ident: &Ident, handler: &Handler, closure: ExprClosure, msg: &Type) -> ItemImpl {
+    if closure.inputs.len() != 3 {
+        abort!(
+            closure.inputs.span(),
+            "handler closures must have signature: |self, mgr, msg: T| ..."
+        );
+    }
+    let mgr = match &closure.inputs[1] {
+        Pat::Type(pat) => &*pat.pat,
+        pat => pat,
+    };
+    let (msg_pat, msg_ty) = match &closure.inputs[2] {
+        Pat::Type(pat) => (&pat.pat, &pat.ty),
+        pat => abort!(pat.span(), "handler closures must specify the message type"),
+    };
+
+    let ret: Type = match closure.output {
+        ReturnType::Type(_, ty) => *ty,
+        ReturnType::Default => match handler {
+            Handler::Map(_) => msg.clone(),
+            Handler::FlatMap(_) => parse_quote! { ::kas::event::Response<#msg> },
+            _ => parse_quote! { () },
+        },
+    };
+    let body = &closure.body;
+
+    parse_quote! {
+        impl Self {
+            #[doc(hidden)]
+            fn #ident(
+                &mut self,
+                #mgr: &mut ::kas::event::Manager,
+                #msg_pat: #msg_ty,
+            ) -> #ret {
+                #body
+            }
+        }
+    }
+}
 
 pub(crate) fn make_widget(mut args: MakeWidget) -> TokenStream {
+    abort_if_dirty();
+
     let mut find_handler_ty_buf: Vec<(Ident, Type)> = vec![];
     // find type of handler's message; return None on error
     let mut find_handler_ty = |handler: &Ident, impls: &Vec<ItemImpl>| -> Option<Type> {
@@ -48,10 +91,9 @@ pub(crate) fn make_widget(mut args: MakeWidget) -> TokenStream {
                                 "handler functions must have signature: fn handler(&mut self, mgr: &mut Manager, msg: T)"
                             );
                         }
-                        let arg = sig.inputs.last().unwrap();
-                        let ty = match arg {
+                        let ty = match sig.inputs.last().unwrap() {
                             syn::FnArg::Typed(arg) => (*arg.ty).clone(),
-                            _ => panic!("expected typed argument"), // nothing else is possible here?
+                            arg => abort!(arg.span(), "expected typed argument"),
                         };
                         x = Some((sig.ident.clone(), ty));
                     }
@@ -130,7 +172,21 @@ pub(crate) fn make_widget(mut args: MakeWidget) -> TokenStream {
     let extra_attrs = args.extra_attrs;
 
     for (index, field) in args.fields.drain(..).enumerate() {
-        let attr = field.widget_attr;
+        let mut attr = field.widget_attr;
+
+        if let Some(ref mut wattr) = attr {
+            if let Some(closure) = wattr.args.closure.take() {
+                name_buf.clear();
+                name_buf
+                    .write_fmt(format_args!("mw_handler_{}", index))
+                    .unwrap();
+                let ident = Ident::new(&name_buf, Span::call_site());
+                let handler = &mut wattr.args.handler;
+                args.impls
+                    .push(closure_method(&ident, handler, closure, &msg));
+                *handler.any_mut().unwrap() = ident;
+            }
+        }
 
         let ident = match &field.ident {
             Some(ref ident) => ident.clone(),
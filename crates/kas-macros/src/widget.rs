@@ -6,12 +6,16 @@
 use crate::args::{Handler, Widget};
 use crate::extend_generics;
 use proc_macro2::TokenStream;
-use proc_macro_error::{emit_call_site_warning, emit_error, emit_warning};
+use proc_macro_error::{abort_if_dirty, emit_call_site_warning, emit_error, emit_warning};
 use quote::{quote, TokenStreamExt};
 use syn::spanned::Spanned;
 use syn::{parse_quote, Result};
 
 pub(crate) fn widget(mut args: Widget) -> Result<TokenStream> {
+    // Errors emitted while parsing may leave args inconsistent (e.g. without
+    // core data); stop here to report only these errors.
+    abort_if_dirty();
+
     let mut toks = quote! { #args };
 
     let name = &args.ident;
@@ -211,6 +215,10 @@ pub(crate) fn widget(mut args: Widget) -> Result<TokenStream> {
 
     if impl_widget_config {
         let key_nav = args.attr_widget.key_nav.value;
+        let focus_scope = args.attr_widget.focus_scope.value;
+        let tab_index = args.attr_widget.tab_index.value;
+        let nav_wrap = args.attr_widget.nav_wrap.value;
+        let initial_focus = args.attr_widget.initial_focus.value;
         let hover_highlight = args.attr_widget.hover_highlight.value;
         let cursor_icon = args.attr_widget.cursor_icon.value;
 
@@ -221,6 +229,18 @@ pub(crate) fn widget(mut args: Widget) -> Result<TokenStream> {
                 fn key_nav(&self) -> bool {
                     #key_nav
                 }
+                fn focus_scope(&self) -> bool {
+                    #focus_scope
+                }
+                fn tab_index(&self) -> Option<u32> {
+                    #tab_index
+                }
+                fn nav_wrap(&self) -> bool {
+                    #nav_wrap
+                }
+                fn initial_focus(&self) -> bool {
+                    #initial_focus
+                }
                 fn hover_highlight(&self) -> bool {
                     #hover_highlight
                 }
@@ -233,6 +253,18 @@ pub(crate) fn widget(mut args: Widget) -> Result<TokenStream> {
         if let Some(span) = args.attr_widget.key_nav.span {
             emit_warning!(span, "unused due to manual impl of `WidgetConfig`");
         }
+        if let Some(span) = args.attr_widget.focus_scope.span {
+            emit_warning!(span, "unused due to manual impl of `WidgetConfig`");
+        }
+        if let Some(span) = args.attr_widget.tab_index.span {
+            emit_warning!(span, "unused due to manual impl of `WidgetConfig`");
+        }
+        if let Some(span) = args.attr_widget.nav_wrap.span {
+            emit_warning!(span, "unused due to manual impl of `WidgetConfig`");
+        }
+        if let Some(span) = args.attr_widget.initial_focus.span {
+            emit_warning!(span, "unused due to manual impl of `WidgetConfig`");
+        }
         if let Some(span) = args.attr_widget.hover_highlight.span {
             emit_warning!(span, "unused due to manual impl of `WidgetConfig`");
         }
@@ -305,7 +337,7 @@ pub(crate) fn widget(mut args: Widget) -> Result<TokenStream> {
         };
 
         let core = args.core_data.as_ref().unwrap();
-        let layout = layout.generate(args.children.iter().map(|c| &c.ident))?;
+        let layout = layout.generate(&args.children)?;
 
         toks.append_all(quote! {
             impl #impl_generics ::kas::Layout for #name #ty_generics #where_clause {
@@ -318,8 +350,20 @@ pub(crate) fn widget(mut args: Widget) -> Result<TokenStream> {
                 #find_id
             }
         });
-    } else if let Some(span) = args.attr_widget.find_id.span {
-        emit_warning!(span, "unused without generated impl of `Layout`");
+    } else {
+        if let Some(span) = args.attr_widget.find_id.span {
+            emit_warning!(span, "unused without generated impl of `Layout`");
+        }
+        for child in args.children.iter() {
+            let child_args = &child.args;
+            let idents = [&child_args.stretch, &child_args.halign, &child_args.valign];
+            for ident in idents.into_iter().flatten() {
+                emit_warning!(ident.span(), "unused without `#[widget(layout = ...)]`");
+            }
+            if let Some(ref margins) = child_args.margins {
+                emit_warning!(margins.span(), "unused without `#[widget(layout = ...)]`");
+            }
+        }
     }
 
     if let Some(index) = handler_impl {
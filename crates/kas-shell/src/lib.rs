@@ -0,0 +1,246 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! KAS shell interface
+//!
+//! A *shell* connects KAS to the operating system (or further abstraction
+//! layers): it creates windows, drives an event loop, translates input events,
+//! provides clipboard access and timers, and renders to a surface. The shells
+//! in the KAS repository are `kas_wgpu` (winit + WebGPU) and `kas_gtk`
+//! (GTK + Cairo).
+//!
+//! This crate documents the interface between KAS and a shell so that other
+//! shells (e.g. over SDL2, a Wayland client library or direct KMS/DRM output)
+//! may be implemented outside of the KAS repository.
+//!
+//! Much of the interface is defined by `kas-core` (under the "Shell API"
+//! headings of its documentation); this crate re-exports the relevant items
+//! and adds traits for the shell entrypoint. Nothing here depends on [winit];
+//! the `winit` feature of `kas-core` is optional (only
+//! `ManagerState::handle_winit` requires it).
+//!
+//! ## Entrypoint
+//!
+//! A shell provides a type implementing [`Shell`], conventionally named
+//! `Toolkit`. This owns shared state (the theme and draw state; see below),
+//! accepts windows via [`Shell::add_boxed`] and runs the event loop via
+//! [`Shell::run`]. A [`ShellProxy`] allows control from other threads.
+//!
+//! ## Windows
+//!
+//! For each window, the shell owns the root widget (a `Box<dyn kas::Window>`),
+//! a [`ManagerState`] and a [`SolveCache`], and implements [`ShellWindow`]
+//! over its window and shared state. The expected life-cycle is:
+//!
+//! 1.  Construct the [`ManagerState`], then call [`ManagerState::configure`]
+//! 2.  Find size constraints via [`SolveCache::find_constraints`] and create
+//!     the platform window accordingly
+//! 3.  On each resize, call [`SolveCache::apply_rect`] then
+//!     [`ManagerState::region_moved`]
+//! 4.  Translate input events and pass them to the `handle_*` methods of
+//!     [`Manager`] (accessed via [`ManagerState::with`])
+//! 5.  After each batch of events, call [`ManagerState::update`] and act on
+//!     the resulting [`TkAction`] (redraw, resize, reconfigure, close)
+//! 6.  Draw when requested (see below)
+//!
+//! Pop-ups requested via [`ShellWindow::add_popup`] may be implemented as
+//! borderless windows or as overlays within the parent window. Window
+//! captures requested via [`ShellWindow::capture`] must always be resolved
+//! (see [`CaptureRequest`]).
+//!
+//! ## Event source
+//!
+//! Input is passed to KAS using its own types: [`VirtualKeyCode`],
+//! [`ModifiersState`], [`MouseButton`], [`ScrollDelta`] (classified by
+//! [`ScrollSource`]) and physical-pixel [`Coord`]s. Where the platform lacks
+//! an equivalent for some event it may be omitted; KAS does not require every
+//! event kind. The platform cursor is set via [`ShellWindow::set_cursor_icon`].
+//!
+//! ## Timers
+//!
+//! After handling events, [`ManagerState::next_resume`] returns the time at
+//! which the window next requires an update, if any. The shell should arrange
+//! to wake at this time and call [`Manager::update_timer`]. Timers may be
+//! coarse or late; widgets must tolerate this.
+//!
+//! ## Clipboard
+//!
+//! Clipboard access is via [`ShellWindow::get_clipboard`] and
+//! [`ShellWindow::set_clipboard`]. A shell without clipboard support may
+//! return `None` and ignore writes.
+//!
+//! ## URLs
+//!
+//! [`ShellWindow::open_url`] should open a URL with the system's default
+//! handler; [`open_url`] is a portable implementation. A shell may instead
+//! use a platform API, or return `false` where unsupported.
+//!
+//! ## Drawing surface
+//!
+//! The shell renders via the traits in [`draw`]: it implements
+//! [`draw::DrawSharedImpl`] (resources shared between windows such as images
+//! and glyph caches) and [`draw::DrawImpl`] (per-window draw passes), and
+//! optionally [`draw::DrawRoundedImpl`] and [`draw::DrawShadedImpl`] as
+//! required by the theme. To draw a window, the shell obtains a draw handle
+//! from the theme, calls `Layout::draw` on the root widget followed by
+//! [`ManagerState::draw_inspector`], [`ManagerState::draw_toasts`] and
+//! [`ManagerState::draw_tooltip`], then renders the accumulated passes to its
+//! surface. How the surface is obtained (a GPU swap chain, a Cairo context, a
+//! DRM framebuffer) is entirely up to the shell. After presenting, the shell
+//! reports the frame's presentation time (and the display's refresh interval,
+//! if known) via [`ManagerState::frame_presented`]; animations are timed from
+//! this.
+//!
+//! ## Stability
+//!
+//! These items are not yet stable: they follow the version of `kas-core`.
+//! The shells in the KAS repository serve as reference implementations.
+//!
+//! [winit]: https://github.com/rust-windowing/winit
+//! [`VirtualKeyCode`]: kas::event::VirtualKeyCode
+//! [`ModifiersState`]: kas::event::ModifiersState
+//! [`MouseButton`]: kas::event::MouseButton
+//! [`ScrollDelta`]: kas::event::ScrollDelta
+//! [`ScrollSource`]: kas::event::ScrollSource
+//! [`Coord`]: kas::geom::Coord
+
+use thiserror::Error;
+
+use kas::event::UpdateHandle;
+
+pub use kas::event::{Manager, ManagerState};
+pub use kas::layout::SolveCache;
+pub use kas::{CaptureRequest, ShellWindow, TkAction, WindowId};
+
+/// Drawing interface implemented by shells
+///
+/// Other types used by these traits are available from [`kas::draw`].
+pub mod draw {
+    pub use kas::draw::{DrawImpl, DrawRoundedImpl, DrawShadedImpl, DrawSharedImpl};
+    pub use kas::draw::{DrawStats, ImageError, ImageFormat, ImageId, PassStats};
+    pub use kas::draw::{PassId, PassType, SharedState};
+}
+
+mod pending;
+pub use pending::{coalesce, Pending, PendingKind, PendingStats};
+
+pub use kas::WindowId;
+
+/// A shell: entrypoint and owner of the event loop
+///
+/// This is implemented by the `Toolkit` type of each shell.
+pub trait Shell {
+    /// Error type for fallible operations
+    type Error: std::error::Error;
+
+    /// Proxy type (see [`ShellProxy`])
+    type Proxy: ShellProxy;
+
+    /// Add a boxed window
+    ///
+    /// The window is created immediately if possible, otherwise when the event
+    /// loop starts.
+    fn add_boxed(&mut self, window: Box<dyn kas::Window>) -> Result<WindowId, Self::Error>;
+
+    /// Add a window
+    #[inline]
+    fn add<W: kas::Window + 'static>(&mut self, window: W) -> Result<WindowId, Self::Error>
+    where
+        Self: Sized,
+    {
+        self.add_boxed(Box::new(window))
+    }
+
+    /// Create a proxy which can be used to control the UI from another thread
+    fn create_proxy(&self) -> Self::Proxy;
+
+    /// Run the event loop
+    ///
+    /// This returns when all windows have been closed, unless the platform
+    /// requires that the process exits instead.
+    fn run(self);
+}
+
+/// A proxy allowing control of a [`Shell`] from another thread
+pub trait ShellProxy: Send {
+    /// Close a specific window
+    fn close(&self, id: WindowId) -> Result<(), ClosedError>;
+
+    /// Close all windows and terminate the UI
+    fn close_all(&self) -> Result<(), ClosedError>;
+
+    /// Trigger an update handle
+    fn trigger_update(&self, handle: UpdateHandle, payload: u64) -> Result<(), ClosedError>;
+}
+
+/// Error type returned by [`ShellProxy`] functions
+///
+/// This error occurs only if the [`Shell`] already terminated.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+#[error("the event loop has terminated")]
+pub struct ClosedError;
+
+/// Open `url` with the system's default handler
+///
+/// This is a portable implementation of `kas::ShellWindow::open_url`, launching
+/// `xdg-open` (on Unix-like systems), `open` (on macOS) or `explorer` (on
+/// Windows) with `url` as the only argument. It does not wait for the handler
+/// to complete. Returns false if the handler could not be launched.
+///
+/// The `url` must start with a scheme (e.g. `https:`); other input, including
+/// anything which could be parsed as an option by the handler, is rejected
+/// (returning false).
+pub fn open_url(url: &str) -> bool {
+    if !has_scheme(url) {
+        return false;
+    }
+
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let program = "xdg-open";
+
+    match std::process::Command::new(program).arg(url).spawn() {
+        Ok(mut child) => {
+            // Reap the process when it exits
+            std::thread::spawn(move || child.wait());
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// True if `url` starts with a scheme
+///
+/// Per RFC 3986: `ALPHA *( ALPHA / DIGIT / "+" / "-" / "." ) ":"`.
+fn has_scheme(url: &str) -> bool {
+    let scheme = match url.split_once(':') {
+        Some((scheme, _)) => scheme,
+        None => return false,
+    };
+    let mut chars = scheme.chars();
+    let first = chars.next();
+    first.map_or(false, |c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn url_scheme() {
+        assert!(has_scheme("https://example.com"));
+        assert!(has_scheme("mailto:someone@example.com"));
+        assert!(has_scheme("svn+ssh://host/repo"));
+        assert!(!has_scheme("-a=/bin/sh"));
+        assert!(!has_scheme("--help:x"));
+        assert!(!has_scheme("example.com"));
+        assert!(!has_scheme(":x"));
+        assert!(!has_scheme("1abc:x"));
+    }
+}
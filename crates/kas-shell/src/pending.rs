@@ -0,0 +1,163 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Pending actions
+
+use kas::event::UpdateHandle;
+use kas::TkAction;
+
+/// Classification of a pending action (see [`Pending`])
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PendingKind {
+    /// An action to apply to all windows
+    Action(TkAction),
+    /// An update to dispatch to all windows
+    Update(UpdateHandle, u64),
+    /// Adds or closes a window (or pop-up)
+    WindowChange,
+    /// Anything else
+    Other,
+}
+
+/// An item in a shell's queue of pending actions
+///
+/// Shells queue these while handling events, then dispatch them in queue order
+/// after [`coalesce`].
+pub trait Pending: Sized {
+    /// Classify the item
+    fn kind(&self) -> PendingKind;
+
+    /// Construct from an action
+    fn from_action(action: TkAction) -> Self;
+}
+
+/// Counters over pending actions
+///
+/// These are used to trace coalescing of actions and updates.
+#[derive(Debug, Default)]
+pub struct PendingStats {
+    /// Number of [`PendingKind::Action`] items received
+    pub actions: u64,
+    /// Number of (coalesced) actions applied to all windows
+    pub actions_applied: u64,
+    /// Number of [`PendingKind::Update`] items received
+    pub updates: u64,
+    /// Number of updates dispatched to all windows, excluding duplicates
+    pub updates_sent: u64,
+}
+
+/// Coalesce a batch of pending actions
+///
+/// The batch should contain all items queued since the last batch was
+/// dispatched. Items keep their queue order, except that:
+///
+/// -   consecutive [`PendingKind::Action`] items are merged into one
+/// -   an update with the same handle and payload as an earlier update in the
+///     batch is dropped, unless a window was added or closed in between
+///
+/// Updates are never compared against those of an earlier (already
+/// dispatched) batch: if a handle is triggered again after dispatch, it is
+/// dispatched again.
+///
+/// Shells count the actions they apply in [`PendingStats::actions_applied`].
+pub fn coalesce<P: Pending>(batch: Vec<P>, stats: &mut PendingStats) -> Vec<P> {
+    let mut out: Vec<P> = Vec::with_capacity(batch.len());
+    let mut updates: Vec<(UpdateHandle, u64)> = vec![];
+    for pending in batch {
+        match pending.kind() {
+            PendingKind::Action(action) => {
+                stats.actions += 1;
+                match out.last().map(|last| last.kind()) {
+                    Some(PendingKind::Action(last)) => {
+                        *out.last_mut().unwrap() = P::from_action(last | action);
+                    }
+                    _ => out.push(pending),
+                }
+            }
+            PendingKind::Update(handle, payload) => {
+                stats.updates += 1;
+                if updates.contains(&(handle, payload)) {
+                    continue;
+                }
+                updates.push((handle, payload));
+                stats.updates_sent += 1;
+                out.push(pending);
+            }
+            PendingKind::WindowChange => {
+                // Windows added since have not seen earlier updates
+                updates.clear();
+                out.push(pending);
+            }
+            PendingKind::Other => out.push(pending),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Item {
+        Close,
+        Update(UpdateHandle, u64),
+        Action(TkAction),
+    }
+
+    impl Pending for Item {
+        fn kind(&self) -> PendingKind {
+            match self {
+                Item::Close => PendingKind::WindowChange,
+                Item::Update(handle, payload) => PendingKind::Update(*handle, *payload),
+                Item::Action(action) => PendingKind::Action(*action),
+            }
+        }
+
+        fn from_action(action: TkAction) -> Self {
+            Item::Action(action)
+        }
+    }
+
+    #[test]
+    fn actions_keep_order() {
+        let mut stats = PendingStats::default();
+        let batch = vec![
+            Item::Action(TkAction::REDRAW),
+            Item::Action(TkAction::RESIZE),
+            Item::Close,
+            Item::Action(TkAction::RECONFIGURE),
+        ];
+
+        // Actions are merged only up to the window change
+        let expected = vec![
+            Item::Action(TkAction::REDRAW | TkAction::RESIZE),
+            Item::Close,
+            Item::Action(TkAction::RECONFIGURE),
+        ];
+        assert_eq!(coalesce(batch, &mut stats), expected);
+        assert_eq!(stats.actions, 4);
+    }
+
+    #[test]
+    fn updates_dedup_per_batch() {
+        let mut stats = PendingStats::default();
+        let handle = UpdateHandle::new();
+        let update = || Item::Update(handle, 0);
+
+        let batch = vec![update(), Item::Update(handle, 1), update()];
+        let expected = vec![update(), Item::Update(handle, 1)];
+        assert_eq!(coalesce(batch, &mut stats), expected);
+
+        // A repeat after a window change is kept
+        let batch = vec![update(), Item::Close, update()];
+        assert_eq!(coalesce(batch, &mut stats).len(), 3);
+
+        // Once dispatched, a repeat in a later batch (of the same frame) is kept
+        assert_eq!(coalesce(vec![update()], &mut stats), vec![update()]);
+        assert_eq!(coalesce(vec![update()], &mut stats), vec![update()]);
+        assert_eq!((stats.updates, stats.updates_sent), (7, 6));
+    }
+}
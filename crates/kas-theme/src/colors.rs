@@ -29,6 +29,12 @@ pub struct Colors<C> {
     pub edit_bg_disabled: C,
     /// Background colour of `EditBox` (error state)
     pub edit_bg_error: C,
+    /// Colour of error indicators (e.g. invalid input)
+    pub error: C,
+    /// Colour of warning indicators (e.g. text annotations)
+    pub warning: C,
+    /// Colour of hyperlink indicators
+    pub link: C,
     /// Theme accent
     ///
     /// This should be a bold colour, used for small details.
@@ -73,6 +79,9 @@ impl From<ColorsSrgb> for ColorsLinear {
             edit_bg: col.edit_bg.into(),
             edit_bg_disabled: col.edit_bg_disabled.into(),
             edit_bg_error: col.edit_bg_error.into(),
+            error: col.error.into(),
+            warning: col.warning.into(),
+            link: col.link.into(),
             text: col.text.into(),
             text_invert: col.text_invert.into(),
             text_disabled: col.text_disabled.into(),
@@ -93,6 +102,9 @@ impl From<ColorsLinear> for ColorsSrgb {
             edit_bg: col.edit_bg.into(),
             edit_bg_disabled: col.edit_bg_disabled.into(),
             edit_bg_error: col.edit_bg_error.into(),
+            error: col.error.into(),
+            warning: col.warning.into(),
+            link: col.link.into(),
             text: col.text.into(),
             text_invert: col.text_invert.into(),
             text_disabled: col.text_disabled.into(),
@@ -128,6 +140,9 @@ impl ColorsSrgb {
             edit_bg: Rgba8Srgb::from_str("#FAFAFA").unwrap(),
             edit_bg_disabled: Rgba8Srgb::from_str("#DCDCDC").unwrap(),
             edit_bg_error: Rgba8Srgb::from_str("#FFBCBC").unwrap(),
+            warning: Rgba8Srgb::from_str("#E09000").unwrap(),
+            link: Rgba8Srgb::from_str("#0050D0").unwrap(),
+            error: Rgba8Srgb::from_str("#E0001C").unwrap(),
             text: Rgba8Srgb::from_str("#000000").unwrap(),
             text_invert: Rgba8Srgb::from_str("#FFFFFF").unwrap(),
             text_disabled: Rgba8Srgb::from_str("#AAAAAA").unwrap(),
@@ -147,6 +162,9 @@ impl ColorsSrgb {
             edit_bg: Rgba8Srgb::from_str("#303030").unwrap(),
             edit_bg_disabled: Rgba8Srgb::from_str("#606060").unwrap(),
             edit_bg_error: Rgba8Srgb::from_str("#FFBCBC").unwrap(),
+            warning: Rgba8Srgb::from_str("#FFB040").unwrap(),
+            link: Rgba8Srgb::from_str("#6CB0FF").unwrap(),
+            error: Rgba8Srgb::from_str("#FF5050").unwrap(),
             text: Rgba8Srgb::from_str("#FFFFFF").unwrap(),
             text_invert: Rgba8Srgb::from_str("#000000").unwrap(),
             text_disabled: Rgba8Srgb::from_str("#CBCBCB").unwrap(),
@@ -166,6 +184,9 @@ impl ColorsSrgb {
             edit_bg: Rgba8Srgb::from_str("#FFFFFF").unwrap(),
             edit_bg_disabled: Rgba8Srgb::from_str("#DCDCDC").unwrap(),
             edit_bg_error: Rgba8Srgb::from_str("#FFBCBC").unwrap(),
+            warning: Rgba8Srgb::from_str("#E09000").unwrap(),
+            link: Rgba8Srgb::from_str("#0050D0").unwrap(),
+            error: Rgba8Srgb::from_str("#E0001C").unwrap(),
             text: Rgba8Srgb::from_str("#000000").unwrap(),
             text_invert: Rgba8Srgb::from_str("#FFFFFF").unwrap(),
             text_disabled: Rgba8Srgb::from_str("#AAAAAA").unwrap(),
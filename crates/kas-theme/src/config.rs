@@ -9,7 +9,10 @@ use crate::{ColorsSrgb, ThemeConfig};
 use kas::draw::TextClass;
 use kas::text::fonts::{fonts, AddMode, FontSelector};
 use kas::TkAction;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Once;
+
+pub(crate) use defaults::TEXT_CLASSES;
 
 /// Event handling configuration
 #[derive(Clone, Debug, PartialEq)]
@@ -33,6 +36,9 @@ pub struct Config {
     color_schemes: BTreeMap<String, ColorsSrgb>,
 
     /// Font aliases, used when searching for a font family matching the key.
+    ///
+    /// Families listed here are tried before fallbacks discovered from system
+    /// fonts (see [`Config::add_fallback_aliases`]).
     #[cfg_attr(feature = "config", serde(default))]
     font_aliases: BTreeMap<String, FontAliases>,
 
@@ -43,6 +49,13 @@ pub struct Config {
     /// Text glyph rastering settings
     #[cfg_attr(feature = "config", serde(default))]
     raster: RasterConfig,
+
+    /// Background blur under pop-ups (standard deviation, in logical pixels)
+    ///
+    /// When positive, pop-up backgrounds are drawn translucent over a blurred
+    /// copy of the content beneath. Support depends on the graphics backend.
+    #[cfg_attr(feature = "config", serde(default))]
+    popup_blur: f32,
 }
 
 impl Default for Config {
@@ -55,6 +68,7 @@ impl Default for Config {
             font_aliases: Default::default(),
             fonts: defaults::fonts(),
             raster: Default::default(),
+            popup_blur: 0.0,
         }
     }
 }
@@ -100,6 +114,54 @@ pub struct RasterConfig {
     /// feature is usually only used for small fonts this likely acceptable.
     #[cfg_attr(feature = "config", serde(default = "defaults::subpixel_steps"))]
     pub subpixel_steps: u8,
+    /// Sub-pixel (LCD) anti-aliasing
+    ///
+    /// This is ignored for windows with a transparent background.
+    #[cfg_attr(feature = "config", serde(default))]
+    pub lcd_order: LcdOrder,
+    /// Coverage gamma
+    ///
+    /// Glyph coverage `c` is mapped to `c.powf(1.0 / gamma)`. Since blending
+    /// happens in linear colour space, text (especially dark text on a light
+    /// background) appears thin with `gamma = 1.0` (no adjustment); values
+    /// around `1.8` give results closer to gamma-correct rendering.
+    #[cfg_attr(feature = "config", serde(default = "defaults::gamma"))]
+    pub gamma: f32,
+}
+
+/// Sub-pixel order for LCD anti-aliasing of text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(serde::Serialize, serde::Deserialize))]
+pub enum LcdOrder {
+    /// Disabled: use grey-scale anti-aliasing
+    None,
+    /// Detect from the display
+    ///
+    /// Window systems do not report the sub-pixel order, thus this assumes
+    /// RGB order on low-DPI displays (scale factor below 1.5) and disables
+    /// LCD anti-aliasing otherwise (and always on macOS).
+    Auto,
+    /// Horizontal red-green-blue sub-pixels
+    Rgb,
+    /// Horizontal blue-green-red sub-pixels
+    Bgr,
+}
+
+impl Default for LcdOrder {
+    fn default() -> Self {
+        LcdOrder::None
+    }
+}
+
+impl LcdOrder {
+    /// Resolve [`LcdOrder::Auto`] for a display with the given scale factor
+    pub fn resolve(self, scale_factor: f32) -> LcdOrder {
+        match self {
+            LcdOrder::Auto if cfg!(target_os = "macos") || scale_factor >= 1.5 => LcdOrder::None,
+            LcdOrder::Auto => LcdOrder::Rgb,
+            order => order,
+        }
+    }
 }
 
 impl Default for RasterConfig {
@@ -109,6 +171,8 @@ impl Default for RasterConfig {
             scale_steps: defaults::scale_steps(),
             subpixel_threshold: defaults::subpixel_threshold(),
             subpixel_steps: defaults::subpixel_steps(),
+            lcd_order: Default::default(),
+            gamma: defaults::gamma(),
         }
     }
 }
@@ -151,6 +215,15 @@ impl Config {
         self.color_schemes.get(&self.active_scheme).cloned()
     }
 
+    /// Background blur under pop-ups
+    ///
+    /// This is the standard deviation of the blur in logical pixels; zero
+    /// when disabled.
+    #[inline]
+    pub fn popup_blur(&self) -> f32 {
+        self.popup_blur
+    }
+
     /// Get an iterator over font mappings
     #[inline]
     pub fn iter_fonts(&self) -> impl Iterator<Item = (&TextClass, &FontSelector<'static>)> {
@@ -171,6 +244,26 @@ impl Config {
         self.dirty = true;
         self.active_scheme = scheme.to_string();
     }
+
+    /// Set background blur under pop-ups (zero to disable)
+    pub fn set_popup_blur(&mut self, blur: f32) {
+        self.dirty = true;
+        self.popup_blur = blur.max(0.0);
+    }
+
+    /// Set the font family for all text classes
+    ///
+    /// The family is followed by the generic `sans-serif` family, thus glyphs
+    /// not covered by `family` are found via the fallback chain of the latter
+    /// (see font aliases).
+    pub fn set_font_family(&mut self, family: &str) {
+        self.dirty = true;
+        let mut selector = FontSelector::new();
+        selector.set_families(vec![family.to_string().into(), "sans-serif".into()]);
+        for class in defaults::TEXT_CLASSES.iter() {
+            self.fonts.insert(*class, selector.clone());
+        }
+    }
 }
 
 /// Other functions
@@ -178,7 +271,7 @@ impl Config {
     /// Currently this is just "set". Later, maybe some type of merge.
     #[allow(clippy::float_cmp)]
     pub fn apply_config(&mut self, other: &Config) -> TkAction {
-        let action = if self.font_size != other.font_size {
+        let action = if self.font_size != other.font_size || self.fonts != other.fonts {
             TkAction::RESIZE | TkAction::THEME_UPDATE
         } else if self != other {
             TkAction::REDRAW
@@ -189,6 +282,108 @@ impl Config {
         *self = other.clone();
         action
     }
+
+    /// Add fallback families for scripts not covered by the generic families
+    ///
+    /// Every face in the font database is read once and its Unicode coverage
+    /// recorded in blocks of 128 code points. For each block with no coverage
+    /// from the families of the generic aliases (`serif`, `sans-serif`,
+    /// `monospace`) or the configured font aliases, the installed family with
+    /// most glyphs in that block is appended to all generic aliases. Thus any
+    /// script (including emoji) for which some system font exists is found.
+    ///
+    /// This must be called after system fonts are loaded and before fonts are
+    /// selected; only the first call has any effect.
+    pub fn add_fallback_aliases(&self) {
+        const GENERIC: [&str; 3] = ["SERIF", "SANS-SERIF", "MONOSPACE"];
+        // Private use areas (mostly icon fonts) are not useful as fallback
+        fn private_use(block: u32) -> bool {
+            (0xE000 >> 7..0xF900 >> 7).contains(&block) || block >= 0xF0000 >> 7
+        }
+
+        static ONCE: Once = Once::new();
+        ONCE.call_once(|| {
+            let fonts = fonts();
+            let fallbacks = {
+                let db = fonts.read_db();
+                let mut base: HashSet<String> = GENERIC
+                    .iter()
+                    .filter_map(|alias| db.font_family_from_alias(alias))
+                    .collect();
+                base.extend(
+                    self.font_aliases
+                        .values()
+                        .flat_map(|a| a.list.iter().cloned()),
+                );
+
+                // Coverage per family: the max over its faces, per block
+                let mut coverage: BTreeMap<&str, HashMap<u32, u32>> = BTreeMap::new();
+                for face in db.db().faces() {
+                    let mut chars = BTreeSet::new();
+                    db.db().with_face_data(face.id, |data, index| {
+                        if let Ok(face) = ttf_parser::Face::from_slice(data, index) {
+                            for table in face.character_mapping_subtables() {
+                                if table.is_unicode() {
+                                    table.codepoints(|c| {
+                                        chars.insert(c);
+                                    });
+                                }
+                            }
+                        }
+                    });
+                    let blocks = coverage.entry(face.family.as_str()).or_default();
+                    let mut face_blocks: HashMap<u32, u32> = HashMap::new();
+                    for c in chars {
+                        *face_blocks.entry(c >> 7).or_default() += 1;
+                    }
+                    for (block, n) in face_blocks {
+                        let count = blocks.entry(block).or_default();
+                        *count = (*count).max(n);
+                    }
+                }
+
+                let covered: HashSet<u32> = coverage
+                    .iter()
+                    .filter(|(family, _)| base.contains(**family))
+                    .flat_map(|(_, blocks)| blocks.keys().cloned())
+                    .collect();
+                let mut best: BTreeMap<u32, (u32, &str)> = BTreeMap::new();
+                for (&family, blocks) in coverage.iter() {
+                    for (block, n) in blocks.iter() {
+                        if covered.contains(block) || private_use(*block) {
+                            continue;
+                        }
+                        let entry = best.entry(*block).or_insert((0, family));
+                        if *n > entry.0 {
+                            *entry = (*n, family);
+                        }
+                    }
+                }
+
+                let mut fallbacks: Vec<String> = vec![];
+                for (_, family) in best.values() {
+                    if !fallbacks.iter().any(|f| f == family) {
+                        fallbacks.push(family.to_string());
+                    }
+                }
+                fallbacks
+            };
+
+            if fallbacks.is_empty() {
+                return;
+            }
+            log::debug!("Font fallbacks: {:?}", fallbacks);
+            fonts.update_db(|db| {
+                for alias in GENERIC.iter() {
+                    db.add_aliases(
+                        alias.to_string().into(),
+                        fallbacks.iter().map(|s| s.clone().into()),
+                        AddMode::Append,
+                    );
+                }
+            });
+        });
+    }
 }
 
 impl ThemeConfig for Config {
@@ -248,6 +443,15 @@ mod defaults {
         schemes
     }
 
+    pub const TEXT_CLASSES: [TextClass; 6] = [
+        TextClass::Label,
+        TextClass::LabelScroll,
+        TextClass::Button,
+        TextClass::Edit,
+        TextClass::EditMulti,
+        TextClass::MenuLabel,
+    ];
+
     pub fn fonts() -> BTreeMap<TextClass, FontSelector<'static>> {
         let mut selector = FontSelector::new();
         selector.set_families(vec!["serif".into()]);
@@ -267,4 +471,7 @@ mod defaults {
     pub fn subpixel_steps() -> u8 {
         5
     }
+    pub fn gamma() -> f32 {
+        1.0
+    }
 }
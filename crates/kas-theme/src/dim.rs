@@ -46,6 +46,8 @@ pub struct Parameters {
     pub shadow_size: Vec2,
     /// Proportional offset of shadow (range: -1..=1)
     pub shadow_rel_offset: Vec2,
+    /// Background blur under pop-ups (standard deviation; 0 to disable)
+    pub popup_blur: f32,
 }
 
 /// Dimensions available within [`Window`]
@@ -69,6 +71,7 @@ pub struct Dimensions {
     pub progress_bar: Size,
     pub shadow_a: Vec2,
     pub shadow_b: Vec2,
+    pub popup_blur: f32,
 }
 
 impl Dimensions {
@@ -111,6 +114,7 @@ impl Dimensions {
             progress_bar: Size::from(params.progress_bar * scale_factor),
             shadow_a: shadow_offset - shadow_size,
             shadow_b: shadow_offset + shadow_size,
+            popup_blur: params.popup_blur * scale_factor,
         }
     }
 }
@@ -134,8 +138,15 @@ impl Window {
         }
     }
 
-    pub fn update(&mut self, dims: &Parameters, pt_size: f32, scale_factor: f32) {
+    pub fn update(
+        &mut self,
+        dims: &Parameters,
+        pt_size: f32,
+        scale_factor: f32,
+        fonts: Rc<LinearMap<TextClass, FontId>>,
+    ) {
         self.dims = Dimensions::new(dims, pt_size, scale_factor);
+        self.fonts = fonts;
     }
 }
 
@@ -252,7 +263,12 @@ impl SizeHandle for Window {
                 TextClass::EditMulti | TextClass::LabelScroll => Stretch::Low,
                 _ => Stretch::None,
             };
-            SizeRules::new(min, ideal, margins, stretch)
+            let mut rules = SizeRules::new(min, ideal, margins, stretch);
+            // The first line starts at the top of the text, thus its baseline
+            // is at the ascent of the first glyph.
+            let marker = text.display().text_glyph_pos(0).next();
+            rules.set_baseline(marker.map(|m| i32::conv_ceil(m.ascent)));
+            rules
         }
     }
 
@@ -12,13 +12,17 @@ use std::f32;
 use std::ops::Range;
 use std::rc::Rc;
 
+use crate::config::TEXT_CLASSES;
 use crate::{dim, ColorsLinear, Config, Theme};
 use kas::cast::Cast;
 use kas::dir::{Direction, Directional};
 use kas::draw::{self, color::Rgba, *};
+use kas::event::Severity;
 use kas::geom::*;
 use kas::text::format::FormattableText;
-use kas::text::{fonts, AccelString, Effect, Text, TextApi, TextDisplay};
+use kas::text::{
+    fonts, AccelString, Annotation, AnnotationClass, Effect, Text, TextApi, TextDisplay,
+};
 use kas::TkAction;
 
 // Used to ensure a rectangular background is inside a circular corner.
@@ -29,6 +33,8 @@ const BG_SHRINK_FACTOR: f32 = 1.0 - std::f32::consts::FRAC_1_SQRT_2;
 const SHADOW_HOVER: f32 = 1.1;
 // Shadow enlargement for pop-ups
 const SHADOW_POPUP: f32 = 1.2;
+// Opacity of pop-up backgrounds when blurring the background
+pub(crate) const BLUR_POPUP_ALPHA: f32 = 0.8;
 
 /// A theme with flat (unshaded) rendering
 #[derive(Clone, Debug)]
@@ -114,6 +120,7 @@ const DIMS: dim::Parameters = dim::Parameters {
     progress_bar: Vec2::splat(8.0),
     shadow_size: Vec2(4.0, 4.0),
     shadow_rel_offset: Vec2(0.2, 0.3),
+    popup_blur: 0.0,
 };
 const DARK_SHADOW_SIZE: Vec2 = Vec2::splat(5.0);
 const DARK_SHADOW_OFFSET: Vec2 = Vec2::ZERO;
@@ -140,8 +147,17 @@ where
         std::borrow::Cow::Borrowed(&self.config)
     }
 
+    #[allow(clippy::float_cmp)]
     fn apply_config(&mut self, config: &Self::Config) -> TkAction {
+        let fonts_changed = self.config.iter_fonts().ne(config.iter_fonts());
         let mut action = self.config.apply_config(config);
+        if self.dims.popup_blur != self.config.popup_blur() {
+            self.dims.popup_blur = self.config.popup_blur();
+            action |= TkAction::THEME_UPDATE;
+        }
+        if fonts_changed && self.fonts.is_some() {
+            self.select_fonts();
+        }
         if let Some(scheme) = self.config.get_active_scheme() {
             action |= self.set_colors(scheme.into());
         }
@@ -153,21 +169,18 @@ where
         if let Err(e) = fonts.select_default() {
             panic!("Error loading font: {}", e);
         }
-        self.fonts = Some(Rc::new(
-            self.config
-                .iter_fonts()
-                .filter_map(|(c, s)| fonts.select_font(s).ok().map(|id| (*c, id)))
-                .collect(),
-        ));
+        self.config.add_fallback_aliases();
+        self.select_fonts();
     }
 
     fn new_window(&self, dpi_factor: f32) -> Self::Window {
-        let fonts = self.fonts.as_ref().unwrap().clone();
+        let fonts = self.fonts.clone().unwrap_or_default();
         dim::Window::new(&self.dims, self.config.font_size(), dpi_factor, fonts)
     }
 
     fn update_window(&self, w: &mut Self::Window, dpi_factor: f32) {
-        w.update(&self.dims, self.config.font_size(), dpi_factor);
+        let fonts = self.fonts.clone().unwrap_or_default();
+        w.update(&self.dims, self.config.font_size(), dpi_factor, fonts);
     }
 
     #[cfg(not(feature = "gat"))]
@@ -206,6 +219,30 @@ where
     }
 }
 
+impl FlatTheme {
+    // Resolve configured font selectors
+    //
+    // Classes without a configured selector use `sans-serif` explicitly since
+    // the default font is resolved before fallback aliases are added.
+    // Existing windows pick up the new fonts in `update_window`.
+    fn select_fonts(&mut self) {
+        let fonts = fonts::fonts();
+        let mut map: LinearMap<TextClass, fonts::FontId> = self
+            .config
+            .iter_fonts()
+            .filter_map(|(c, s)| fonts.select_font(s).ok().map(|id| (*c, id)))
+            .collect();
+        let mut sans = fonts::FontSelector::new();
+        sans.set_families(vec!["sans-serif".into()]);
+        if let Ok(id) = fonts.select_font(&sans) {
+            for class in TEXT_CLASSES.iter() {
+                map.entry(*class).or_insert(id);
+            }
+        }
+        self.fonts = Some(Rc::new(map));
+    }
+}
+
 impl ThemeApi for FlatTheme {
     fn set_font_size(&mut self, pt_size: f32) -> TkAction {
         self.config.set_font_size(pt_size);
@@ -228,6 +265,14 @@ impl ThemeApi for FlatTheme {
         }
         TkAction::empty()
     }
+
+    fn set_font_family(&mut self, family: &str) -> TkAction {
+        self.config.set_font_family(family);
+        if self.fonts.is_some() {
+            self.select_fonts();
+        }
+        TkAction::RESIZE | TkAction::THEME_UPDATE
+    }
 }
 
 impl<'a, DS: DrawSharedImpl> DrawHandle<'a, DS>
@@ -269,6 +314,26 @@ where
             .rounded_frame(outer, inner, BG_SHRINK_FACTOR, col_frame);
         inner
     }
+
+    /// Draw a zig-zag line from `x0` to `x1` with bottom edge at `y`
+    fn zig_zag(&mut self, x0: f32, x1: f32, y: f32, col: Rgba) {
+        let r = 0.5 * self.w.dims.scale_factor.max(1.0);
+        let h = 2.0 * self.w.dims.inner_margin as f32;
+        let y1 = y - r;
+        let y0 = y1 - h;
+        let mut x = x0 + r;
+        let mut up = true;
+        while x < x1 - r {
+            let x2 = (x + h).min(x1 - r);
+            let (a, b) = match up {
+                true => (Vec2(x, y1), Vec2(x2, y0)),
+                false => (Vec2(x, y0), Vec2(x2, y1)),
+            };
+            self.draw.rounded_line(a, b, r, col);
+            x = x2;
+            up = !up;
+        }
+    }
 }
 
 impl<'a, DS: DrawSharedImpl> draw::DrawHandle for DrawHandle<'a, DS>
@@ -292,14 +357,17 @@ where
     ) {
         let mut frame_rect = Default::default();
         let mut shadow = Default::default();
+        let mut blur = 0.0;
         let mut outer_rect = inner_rect;
         if class == PassType::Overlay {
             frame_rect = inner_rect.expand(self.w.dims.frame);
+            let (a, b) = (self.w.dims.shadow_a, self.w.dims.shadow_b);
             shadow = Quad::from(frame_rect);
-            shadow.a += self.w.dims.shadow_a * SHADOW_POPUP;
-            shadow.b += self.w.dims.shadow_b * SHADOW_POPUP;
-            let a = shadow.a.floor();
-            let b = shadow.b.ceil();
+            shadow += (a + b) * (0.5 * SHADOW_POPUP);
+            blur = (b - a).0.max((b - a).1) * (0.25 * SHADOW_POPUP);
+            let outer = shadow.grow(3.0 * blur);
+            let a = outer.a.floor();
+            let b = outer.b.ceil();
             outer_rect = Rect::new(a.into(), (b - a).into());
         }
         let mut draw = self.draw.new_pass(outer_rect, offset, class);
@@ -308,12 +376,18 @@ where
             shadow += offset.into();
             let outer = Quad::from(frame_rect + offset);
             let inner = Quad::from(inner_rect + offset);
+            let radius = self.w.dims.frame as f32;
 
-            draw.rounded_frame_2col(shadow, inner, Rgba::BLACK, Rgba::TRANSPARENT);
+            draw.shadow(shadow, radius, blur, Rgba::BLACK);
 
             draw.rounded_frame(outer, inner, BG_SHRINK_FACTOR, self.cols.frame);
             let inner = outer.shrink(self.w.dims.frame as f32 * BG_SHRINK_FACTOR);
-            draw.rect(inner, self.cols.background);
+            let mut background = self.cols.background;
+            if self.w.dims.popup_blur > 0.0 {
+                draw.blur(inner, self.w.dims.popup_blur);
+                background.a *= BLUR_POPUP_ALPHA;
+            }
+            draw.rect(inner, background);
         }
 
         let mut handle = DrawHandle {
@@ -324,6 +398,36 @@ where
         f(&mut handle);
     }
 
+    fn new_transform_pass(
+        &mut self,
+        rect: Rect,
+        transform: Transform,
+        f: &mut dyn FnMut(&mut dyn draw::DrawHandle),
+    ) {
+        let draw = self.draw.new_transform_pass(rect, transform);
+        let mut handle = DrawHandle {
+            w: self.w,
+            draw,
+            cols: self.cols,
+        };
+        f(&mut handle);
+    }
+
+    fn new_alpha_pass(
+        &mut self,
+        rect: Rect,
+        alpha: f32,
+        f: &mut dyn FnMut(&mut dyn draw::DrawHandle),
+    ) {
+        let draw = self.draw.new_alpha_pass(rect, alpha);
+        let mut handle = DrawHandle {
+            w: self.w,
+            draw,
+            cols: self.cols,
+        };
+        f(&mut handle);
+    }
+
     fn get_clip_rect(&self) -> Rect {
         self.draw.get_clip_rect()
     }
@@ -356,6 +460,100 @@ where
         self.draw.frame(outer, inner, col);
     }
 
+    fn rubber_band(&mut self, rect: Rect) {
+        let outer = Quad::from(rect);
+        let inner = outer.shrink(self.w.dims.scale_factor.max(1.0));
+        let col = self.cols.text_sel_bg;
+        let fill = Rgba {
+            a: col.a * 0.25,
+            ..col
+        };
+        self.draw.rect(inner, fill);
+        self.draw.frame(outer, inner, col);
+    }
+
+    fn drop_marker(&mut self, rect: Rect) {
+        self.draw.rect(Quad::from(rect), self.cols.accent);
+    }
+
+    fn group_header(&mut self, rect: Rect, vert: bool) {
+        let quad = Quad::from(rect);
+        self.draw.rect(quad, self.cols.background);
+        let w = self.w.dims.scale_factor.max(1.0);
+        let a = match vert {
+            true => Vec2(quad.a.0, quad.b.1 - w),
+            false => Vec2(quad.b.0 - w, quad.a.1),
+        };
+        let col = self.cols.frame;
+        self.draw.rect(Quad::with_coords(a, quad.b), col);
+    }
+
+    fn overflow_indicator(&mut self, rect: Rect, axes: (bool, bool)) {
+        let quad = Quad::from(rect);
+        let w = 2.0 * self.w.dims.inner_margin as f32;
+        let col = self.cols.frame;
+        if axes.0 {
+            let a = Vec2(quad.b.0 - w, quad.a.1);
+            self.draw.rect(Quad::with_coords(a, quad.b), col);
+        }
+        if axes.1 {
+            let a = Vec2(quad.a.0, quad.b.1 - w);
+            self.draw.rect(Quad::with_coords(a, quad.b), col);
+        }
+    }
+
+    fn invalid_marker(&mut self, rect: Rect) {
+        // A zig-zag line along the bottom edge
+        let quad = Quad::from(rect);
+        let col = self.cols.error;
+        self.zig_zag(quad.a.0, quad.b.0, quad.b.1, col);
+    }
+
+    fn toast(&mut self, rect: Rect, severity: Severity) {
+        // A bar along the leading edge
+        let quad = Quad::from(rect);
+        let w = (self.w.dims.scale_factor * 4.0).round().max(1.0);
+        let col = match severity {
+            Severity::Info => self.cols.accent,
+            Severity::Warning => self.cols.warning,
+            Severity::Error => self.cols.error,
+        };
+        let b = Vec2(quad.a.0 + w, quad.b.1);
+        self.draw.rect(Quad::with_coords(quad.a, b), col);
+    }
+
+    fn graph_node(&mut self, rect: Rect, selected: bool) {
+        let outer = Quad::from(rect);
+        let w = self.w.dims.scale_factor.max(1.0);
+        let (w, col) = match selected {
+            true => (2.0 * w, self.cols.accent),
+            false => (w, self.cols.frame),
+        };
+        let inner = outer.shrink(w);
+        self.draw.rect(inner, self.cols.background);
+        self.draw.frame(outer, inner, col);
+    }
+
+    fn graph_port(&mut self, rect: Rect, connected: bool, state: InputState) {
+        let outer = Quad::from(rect);
+        let inner = outer.shrink(self.w.dims.scale_factor.max(1.0));
+        let col = match connected {
+            true => self.cols.accent_state(state),
+            false => ColorsLinear::adjust_for_state(self.cols.background, state),
+        };
+        self.draw.circle(outer, 0.0, self.cols.frame);
+        self.draw.circle(inner, 0.0, col);
+    }
+
+    fn graph_link(&mut self, path: &[Vec2], pending: bool) {
+        let w = (self.w.dims.scale_factor * 2.0).round().max(1.0);
+        let mut stroke = Stroke::new(w).with_cap(LineCap::Round);
+        if pending {
+            stroke = stroke.with_dash(&[3.0 * w, 2.0 * w], 0.0);
+        }
+        self.draw.polyline(path, false, &stroke, self.cols.accent);
+    }
+
     fn text(&mut self, pos: Coord, text: &TextDisplay, _: TextClass, state: InputState) {
         let pos = pos;
         let col = if state.disabled() {
@@ -442,6 +640,83 @@ where
         self.draw.text_effects(pos, text, &effects);
     }
 
+    fn text_annotations(
+        &mut self,
+        pos: Coord,
+        text: &TextDisplay,
+        annotations: &[Annotation],
+        state: InputState,
+    ) {
+        let pos = Vec2::from(pos);
+        let w = self.w.dims.scale_factor.max(1.0);
+        for ann in annotations {
+            let col = match (state.disabled(), ann.class) {
+                (true, _) => self.cols.text_disabled,
+                (false, AnnotationClass::Error) => self.cols.error,
+                (false, AnnotationClass::Warning) => self.cols.warning,
+                (false, AnnotationClass::Hyperlink) => self.cols.link,
+            };
+            for (p1, p2) in &text.highlight_lines(ann.range.clone()) {
+                let (p1, p2) = (pos + Vec2::from(*p1), pos + Vec2::from(*p2));
+                match ann.class {
+                    AnnotationClass::Error | AnnotationClass::Warning => {
+                        self.zig_zag(p1.0, p2.0, p2.1, col);
+                    }
+                    AnnotationClass::Hyperlink => {
+                        let quad = Quad::with_coords(Vec2(p1.0, p2.1 - w), p2);
+                        self.draw.rect(quad, col);
+                    }
+                }
+            }
+        }
+    }
+
+    fn text_annotated(
+        &mut self,
+        pos: Coord,
+        text: &dyn TextApi,
+        annotations: &[Annotation],
+        _: TextClass,
+        state: InputState,
+    ) {
+        let (col, link_col) = if state.disabled() {
+            (self.cols.text_disabled, self.cols.text_disabled)
+        } else {
+            (self.cols.text, self.cols.link)
+        };
+        let links = annotations
+            .iter()
+            .filter(|ann| ann.class == AnnotationClass::Hyperlink);
+
+        // Merge formatting effects with link colours
+        let tokens = text.effect_tokens();
+        let mut starts: Vec<u32> = vec![0];
+        starts.extend(tokens.iter().map(|token| token.start));
+        for ann in links.clone() {
+            starts.push(ann.range.start.cast());
+            starts.push(ann.range.end.cast());
+        }
+        starts.sort_unstable();
+        starts.dedup();
+        let effects: Vec<_> = starts
+            .into_iter()
+            .map(|start| {
+                let i = tokens.partition_point(|token| token.start <= start);
+                let flags = i.checked_sub(1).map(|i| tokens[i].flags);
+                let index: usize = start.cast();
+                let is_link = links.clone().any(|ann| ann.range.contains(&index));
+                Effect {
+                    start,
+                    flags: flags.unwrap_or_default(),
+                    aux: if is_link { link_col } else { col },
+                }
+            })
+            .collect();
+
+        self.draw.text_effects(pos.into(), text.display(), &effects);
+        self.text_annotations(pos, text.display(), annotations, state);
+    }
+
     fn edit_marker(&mut self, pos: Coord, text: &TextDisplay, _: TextClass, byte: usize) {
         let width = self.w.dims.font_marker_width;
         let pos = Vec2::from(pos);
@@ -669,4 +944,29 @@ where
         let rect = Quad::from(rect);
         self.draw.image(id, rect);
     }
+
+    fn inspector(&mut self, rect: Rect, class: InspectorClass) {
+        if rect.size.0 <= 0 || rect.size.1 <= 0 {
+            return;
+        }
+        let outer = Quad::from(rect);
+        let (col, width) = match class {
+            InspectorClass::Bounds => (self.cols.frame, 1.0),
+            InspectorClass::Ancestor => (self.cols.accent_soft, 1.0),
+            InspectorClass::Selected => {
+                let mut col = self.cols.accent_soft;
+                col.a = 0.25;
+                self.draw.rect(outer, col);
+                (self.cols.accent, 2.0)
+            }
+            InspectorClass::Panel => {
+                let mut col = self.cols.background;
+                col.a = 0.9;
+                self.draw.rect(outer, col);
+                (self.cols.frame, 1.0)
+            }
+        };
+        let width = width.min(outer.size().min_comp() / 2.0);
+        self.draw.frame(outer, outer.shrink(width), col);
+    }
 }
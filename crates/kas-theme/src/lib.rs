@@ -9,10 +9,16 @@
 //! schemes, some supporting items, and the themes [`FlatTheme`] and
 //! [`ShadedTheme`].
 //!
+//! Themes are written against the draw traits of [`kas::draw`] only, thus may
+//! be used with any shell implementing the required traits (e.g.
+//! [`ShadedTheme`] requires [`DrawRoundedImpl`] and [`DrawShadedImpl`]).
+//!
 //! Custom themes may be built over this crate, optionally including custom draw
-//! routines (e.g. [`DrawShaded`]), provided that the shell implements support.
+//! routines, provided that the shell implements support.
 //! Alternatively this crate may be skipped altogether, especially for a
 //! minimal shell with a custom fixed theme.
+//!
+//! [`DrawRoundedImpl`]: kas::draw::DrawRoundedImpl
 
 #![cfg_attr(doc_cfg, feature(doc_cfg))]
 #![cfg_attr(feature = "gat", feature(generic_associated_types))]
@@ -20,7 +26,6 @@
 
 mod colors;
 mod config;
-mod draw_shaded;
 mod flat_theme;
 #[cfg(feature = "stack_dst")]
 mod multi;
@@ -32,9 +37,11 @@ mod traits;
 pub mod dim;
 pub use kas;
 
+#[doc(no_inline)]
+pub use kas::draw::{DrawShaded, DrawShadedImpl};
+
 pub use colors::{Colors, ColorsLinear, ColorsSrgb};
-pub use config::{Config, RasterConfig};
-pub use draw_shaded::{DrawShaded, DrawShadedImpl};
+pub use config::{Config, LcdOrder, RasterConfig};
 pub use flat_theme::FlatTheme;
 #[cfg(feature = "stack_dst")]
 pub use multi::{MultiTheme, MultiThemeBuilder};
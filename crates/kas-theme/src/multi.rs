@@ -197,6 +197,14 @@ impl<DS> ThemeApi for MultiTheme<DS> {
         action
     }
 
+    fn set_font_family(&mut self, family: &str) -> TkAction {
+        let mut action = TkAction::empty();
+        for theme in &mut self.themes {
+            action = action.max(theme.set_font_family(family));
+        }
+        action
+    }
+
     fn list_schemes(&self) -> Vec<&str> {
         // We list only schemes of the active theme. Probably all themes should
         // have the same schemes anyway.
@@ -8,12 +8,13 @@
 use std::f32;
 use std::ops::Range;
 
+use crate::flat_theme::BLUR_POPUP_ALPHA;
 use crate::{dim, ColorsLinear, Config, FlatTheme, Theme};
-use crate::{DrawShaded, DrawShadedImpl};
 use kas::dir::{Direction, Directional};
 use kas::draw::{self, color::Rgba, *};
+use kas::event::Severity;
 use kas::geom::*;
-use kas::text::{AccelString, Text, TextApi, TextDisplay};
+use kas::text::{AccelString, Annotation, Text, TextApi, TextDisplay};
 use kas::TkAction;
 
 /// A theme using simple shading to give apparent depth to elements
@@ -53,6 +54,13 @@ impl ShadedTheme {
         }
         self
     }
+
+    fn dims(&self) -> dim::Parameters {
+        dim::Parameters {
+            popup_blur: self.flat.config.popup_blur(),
+            ..DIMS
+        }
+    }
 }
 
 const DIMS: dim::Parameters = dim::Parameters {
@@ -68,6 +76,7 @@ const DIMS: dim::Parameters = dim::Parameters {
     progress_bar: Vec2::splat(12.0),
     shadow_size: Vec2::splat(6.0),
     shadow_rel_offset: Vec2::ZERO,
+    popup_blur: 0.0,
 };
 
 pub struct DrawHandle<'a, DS: DrawSharedImpl> {
@@ -101,12 +110,15 @@ where
     }
 
     fn new_window(&self, dpi_factor: f32) -> Self::Window {
-        let fonts = self.flat.fonts.as_ref().unwrap().clone();
-        dim::Window::new(&DIMS, self.flat.config.font_size(), dpi_factor, fonts)
+        let fonts = self.flat.fonts.clone().unwrap_or_default();
+        let dims = self.dims();
+        dim::Window::new(&dims, self.flat.config.font_size(), dpi_factor, fonts)
     }
 
     fn update_window(&self, w: &mut Self::Window, dpi_factor: f32) {
-        w.update(&DIMS, self.flat.config.font_size(), dpi_factor);
+        let fonts = self.flat.fonts.clone().unwrap_or_default();
+        let dims = self.dims();
+        w.update(&dims, self.flat.config.font_size(), dpi_factor, fonts);
     }
 
     #[cfg(not(feature = "gat"))]
@@ -157,6 +169,10 @@ impl ThemeApi for ShadedTheme {
     fn set_scheme(&mut self, name: &str) -> TkAction {
         self.flat.set_scheme(name)
     }
+
+    fn set_font_family(&mut self, family: &str) -> TkAction {
+        self.flat.set_font_family(family)
+    }
 }
 
 impl<'a, DS: DrawSharedImpl> DrawHandle<'a, DS>
@@ -235,13 +251,16 @@ where
         f: &mut dyn FnMut(&mut dyn draw::DrawHandle),
     ) {
         let mut shadow = Default::default();
+        let mut blur = 0.0;
         let mut outer_rect = inner_rect;
         if class == PassType::Overlay {
+            let (a, b) = (self.w.dims.shadow_a, self.w.dims.shadow_b);
             shadow = Quad::from(inner_rect);
-            shadow.a += self.w.dims.shadow_a;
-            shadow.b += self.w.dims.shadow_b;
-            let a = shadow.a.floor();
-            let b = shadow.b.ceil();
+            shadow += (a + b) * 0.5;
+            blur = (b - a).0.max((b - a).1) * 0.25;
+            let outer = shadow.grow(3.0 * blur);
+            let a = outer.a.floor();
+            let b = outer.b.ceil();
             outer_rect = Rect::new(a.into(), (b - a).into());
         }
         let mut draw = self.draw.new_pass(outer_rect, offset, class);
@@ -249,9 +268,14 @@ where
         if class == PassType::Overlay {
             shadow += offset.into();
             let inner = Quad::from(inner_rect + offset);
-            draw.rounded_frame_2col(shadow, inner, Rgba::BLACK, Rgba::TRANSPARENT);
-
-            draw.rect(inner, self.cols.background);
+            draw.shadow(shadow, 0.0, blur, Rgba::BLACK);
+
+            let mut background = self.cols.background;
+            if self.w.dims.popup_blur > 0.0 {
+                draw.blur(inner, self.w.dims.popup_blur);
+                background.a *= BLUR_POPUP_ALPHA;
+            }
+            draw.rect(inner, background);
         }
 
         let mut handle = DrawHandle {
@@ -262,6 +286,36 @@ where
         f(&mut handle);
     }
 
+    fn new_transform_pass(
+        &mut self,
+        rect: Rect,
+        transform: Transform,
+        f: &mut dyn FnMut(&mut dyn draw::DrawHandle),
+    ) {
+        let draw = self.draw.new_transform_pass(rect, transform);
+        let mut handle = DrawHandle {
+            w: self.w,
+            draw,
+            cols: self.cols,
+        };
+        f(&mut handle);
+    }
+
+    fn new_alpha_pass(
+        &mut self,
+        rect: Rect,
+        alpha: f32,
+        f: &mut dyn FnMut(&mut dyn draw::DrawHandle),
+    ) {
+        let draw = self.draw.new_alpha_pass(rect, alpha);
+        let mut handle = DrawHandle {
+            w: self.w,
+            draw,
+            cols: self.cols,
+        };
+        f(&mut handle);
+    }
+
     fn get_clip_rect(&self) -> Rect {
         self.draw.get_clip_rect()
     }
@@ -290,6 +344,42 @@ where
         self.as_flat().selection_box(rect);
     }
 
+    fn rubber_band(&mut self, rect: Rect) {
+        self.as_flat().rubber_band(rect);
+    }
+
+    fn drop_marker(&mut self, rect: Rect) {
+        self.as_flat().drop_marker(rect);
+    }
+
+    fn group_header(&mut self, rect: Rect, vert: bool) {
+        self.as_flat().group_header(rect, vert);
+    }
+
+    fn overflow_indicator(&mut self, rect: Rect, axes: (bool, bool)) {
+        self.as_flat().overflow_indicator(rect, axes);
+    }
+
+    fn invalid_marker(&mut self, rect: Rect) {
+        self.as_flat().invalid_marker(rect);
+    }
+
+    fn toast(&mut self, rect: Rect, severity: Severity) {
+        self.as_flat().toast(rect, severity);
+    }
+
+    fn graph_node(&mut self, rect: Rect, selected: bool) {
+        self.as_flat().graph_node(rect, selected);
+    }
+
+    fn graph_port(&mut self, rect: Rect, connected: bool, state: InputState) {
+        self.as_flat().graph_port(rect, connected, state);
+    }
+
+    fn graph_link(&mut self, path: &[Vec2], pending: bool) {
+        self.as_flat().graph_link(path, pending);
+    }
+
     fn text(&mut self, pos: Coord, text: &TextDisplay, class: TextClass, state: InputState) {
         self.as_flat().text(pos, text, class, state);
     }
@@ -327,6 +417,29 @@ where
             .text_selected_range(pos, text, range, class, state);
     }
 
+    fn text_annotations(
+        &mut self,
+        pos: Coord,
+        text: &TextDisplay,
+        annotations: &[Annotation],
+        state: InputState,
+    ) {
+        self.as_flat()
+            .text_annotations(pos, text, annotations, state);
+    }
+
+    fn text_annotated(
+        &mut self,
+        pos: Coord,
+        text: &dyn TextApi,
+        annotations: &[Annotation],
+        class: TextClass,
+        state: InputState,
+    ) {
+        self.as_flat()
+            .text_annotated(pos, text, annotations, class, state);
+    }
+
     fn edit_marker(&mut self, pos: Coord, text: &TextDisplay, class: TextClass, byte: usize) {
         self.as_flat().edit_marker(pos, text, class, byte);
     }
@@ -341,8 +454,11 @@ where
         let col = col.map(|c| c.into()).unwrap_or(self.cols.accent_soft);
         let col = ColorsLinear::adjust_for_state(col, state);
 
-        self.draw.shaded_round_frame(outer, inner, (0.0, 0.6), col);
-        self.draw.rect(inner, col);
+        // A darker border and lighter top give the appearance of depth
+        let border = col.multiply(0.7);
+        self.draw.rounded_frame(outer, inner, 0.0, border);
+        let gradient = Gradient::vertical(col.multiply(1.15), col.multiply(0.85));
+        self.draw.rect_gradient(inner, &gradient);
 
         if let Some(col) = self.cols.nav_region(state) {
             let outer = outer.shrink(self.w.dims.inner_margin as f32);
@@ -377,13 +493,15 @@ where
         }
     }
 
-    fn scrollbar(&mut self, rect: Rect, h_rect: Rect, _dir: Direction, state: InputState) {
-        // track
-        let outer = Quad::from(rect);
-        let inner = outer.shrink(outer.size().min_comp() / 2.0);
-        let norm = (0.0, -0.7);
+    fn scrollbar(&mut self, rect: Rect, h_rect: Rect, dir: Direction, state: InputState) {
+        // track: inset, with a shadow along the top or left edge
         let col = self.cols.background;
-        self.draw.shaded_round_frame(outer, inner, norm, col);
+        let end = match dir.is_horizontal() {
+            true => Vec2(0.0, 0.4),
+            false => Vec2(0.4, 0.0),
+        };
+        let gradient = Gradient::linear(Vec2::ZERO, end, col.multiply(0.7), col);
+        self.draw.rect_gradient(Quad::from(rect), &gradient);
 
         // handle
         self.draw_handle(h_rect, state);
@@ -426,4 +544,8 @@ where
     fn image(&mut self, id: ImageId, rect: Rect) {
         self.as_flat().image(id, rect);
     }
+
+    fn inspector(&mut self, rect: Rect, class: InspectorClass) {
+        self.as_flat().inspector(rect, class);
+    }
 }
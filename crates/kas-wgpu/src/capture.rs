@@ -0,0 +1,213 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Capture of rendered output
+//!
+//! This supports [`kas::event::Manager::capture_window`] and the headless
+//! shell: rendering happens to an offscreen texture which is then copied back
+//! to CPU memory.
+
+use std::num::NonZeroU32;
+
+use kas::cast::{Cast, Conv};
+use kas::draw::color::Rgba;
+use kas::draw::{DrawHandleExt, DrawIface, PassId};
+use kas::event::ManagerState;
+use kas::geom::{Coord, Rect, Size};
+use kas::WidgetId;
+use kas_theme::Theme;
+
+use crate::draw::{CustomPipe, DrawPipe, DrawWindow, RENDER_TEX_FORMAT};
+use crate::shared::SharedState;
+use crate::{warn_about_error, Error};
+
+/// Draw the widget `id`, translated to the origin
+///
+/// Only this widget and its descendants are drawn. Returns the widget's rect,
+/// or `None` if not found or empty.
+pub(crate) fn draw_widget<C: CustomPipe, T: Theme<DrawPipe<C>>>(
+    shared: &mut SharedState<C, T>,
+    draw: &mut DrawWindow<C::Window>,
+    theme_window: &mut T::Window,
+    mgr: &ManagerState,
+    window: &mut dyn kas::Window,
+    id: WidgetId,
+) -> Option<Rect> {
+    let widget = window.find_leaf_mut(id)?;
+    let rect = widget.rect();
+    if rect.size.0 <= 0 || rect.size.1 <= 0 {
+        return None;
+    }
+    let clip = Rect::new(Coord::ZERO, rect.size);
+    let offset = rect.pos - Coord::ZERO;
+
+    let draw = DrawIface {
+        draw,
+        shared: &mut shared.draw,
+        pass: PassId::new(0),
+    };
+
+    #[cfg(not(feature = "gat"))]
+    unsafe {
+        // Safety: lifetimes do not escape the returned draw_handle value.
+        let mut draw_handle = shared.theme.draw_handle(draw, theme_window);
+        draw_handle.with_clip_region(clip, offset, &mut |draw| widget.draw(draw, mgr, false));
+    }
+    #[cfg(feature = "gat")]
+    {
+        let mut draw_handle = shared.theme.draw_handle(draw, theme_window);
+        draw_handle.with_clip_region(clip, offset, &mut |draw| widget.draw(draw, mgr, false));
+    }
+
+    Some(rect)
+}
+
+/// Create a texture suitable as a render target and copy source
+pub(crate) fn create_texture(device: &wgpu::Device, size: Size) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("offscreen frame"),
+        size: wgpu::Extent3d {
+            width: size.0.cast(),
+            height: size.1.cast(),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: RENDER_TEX_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+    })
+}
+
+/// Copy `texture` to CPU memory, converting to RGBA
+///
+/// The region copied is `size`, from the texture's origin.
+pub(crate) fn read_texture<C: CustomPipe>(
+    pipe: &DrawPipe<C>,
+    texture: &wgpu::Texture,
+    size: Size,
+) -> Result<image::RgbaImage, Error> {
+    let (width, height): (u32, u32) = (size.0.cast(), size.1.cast());
+    // Rows of the copy must be padded to the required alignment:
+    let row_len = 4 * width;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded_row_len = (row_len + align - 1) / align * align;
+
+    let pipe = pipe.gpu();
+    let buffer = pipe.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("readback"),
+        size: u64::from(padded_row_len) * u64::from(height),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = pipe
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("readback"),
+        });
+    encoder.copy_texture_to_buffer(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(padded_row_len),
+                rows_per_image: NonZeroU32::new(height),
+            },
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+    pipe.queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    let mapping = slice.map_async(wgpu::MapMode::Read);
+    pipe.device.poll(wgpu::Maintain::Wait);
+    if let Err(e) = futures::executor::block_on(mapping) {
+        warn_about_error("Failed to map readback buffer", &e);
+        return Err(Error::Readback);
+    }
+
+    let mut pixels = Vec::with_capacity(usize::conv(row_len * height));
+    {
+        let data = slice.get_mapped_range();
+        for row in data.chunks_exact(padded_row_len.cast()) {
+            // RENDER_TEX_FORMAT is BGRA:
+            for px in row[..usize::conv(row_len)].chunks_exact(4) {
+                pixels.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+            }
+        }
+    }
+    buffer.unmap();
+
+    Ok(image::RgbaImage::from_raw(width, height, pixels).unwrap())
+}
+
+/// Copy the region `size` of `pixmap` to an RGBA image
+#[cfg(feature = "software")]
+pub(crate) fn read_pixmap(pixmap: &tiny_skia::Pixmap, size: Size) -> image::RgbaImage {
+    let (width, height): (u32, u32) = (size.0.cast(), size.1.cast());
+    let stride = usize::conv(pixmap.width());
+    let mut pixels = Vec::with_capacity(usize::conv(4 * width * height));
+    for row in pixmap.pixels().chunks_exact(stride).take(height.cast()) {
+        for px in &row[..usize::conv(width)] {
+            let c = px.demultiply();
+            pixels.extend_from_slice(&[c.red(), c.green(), c.blue(), c.alpha()]);
+        }
+    }
+    image::RgbaImage::from_raw(width, height, pixels).unwrap()
+}
+
+/// An off-screen render target for window captures
+pub(crate) enum CaptureTarget {
+    Gpu(wgpu::Texture, wgpu::TextureView),
+    #[cfg(feature = "software")]
+    Soft(tiny_skia::Pixmap),
+}
+
+impl CaptureTarget {
+    /// Construct a target of the given `size`, matching the renderer of `pipe`
+    pub(crate) fn new<C: CustomPipe>(pipe: &DrawPipe<C>, size: Size) -> Self {
+        #[cfg(feature = "software")]
+        if pipe.is_software() {
+            let (w, h) = (size.0.max(1).cast(), size.1.max(1).cast());
+            return CaptureTarget::Soft(tiny_skia::Pixmap::new(w, h).unwrap());
+        }
+        let texture = create_texture(&pipe.gpu().device, size);
+        let view = texture.create_view(&Default::default());
+        CaptureTarget::Gpu(texture, view)
+    }
+
+    /// Render `window` to the target, then read back the region `size`
+    pub(crate) fn render<C: CustomPipe, T: Theme<DrawPipe<C>>>(
+        &mut self,
+        shared: &mut SharedState<C, T>,
+        window: &mut DrawWindow<C::Window>,
+        clear_color: Rgba,
+        size: Size,
+    ) -> Result<image::RgbaImage, Error> {
+        match self {
+            CaptureTarget::Gpu(texture, view) => {
+                let clear_color = crate::window::to_wgpu_color(clear_color);
+                shared.render(window, view, Some(clear_color));
+                read_texture(&shared.draw.draw, texture, size)
+            }
+            #[cfg(feature = "software")]
+            CaptureTarget::Soft(pixmap) => {
+                shared.render_pixmap(window, pixmap, Some(clear_color));
+                Ok(read_pixmap(pixmap, size))
+            }
+        }
+    }
+}
@@ -12,7 +12,7 @@ use std::ops::Range;
 use thiserror::Error;
 
 use kas::cast::{Cast, Conv};
-use kas::draw::{ImageError, PassId};
+use kas::draw::{ImageError, PassId, PassStats};
 use kas::geom::{Quad, Size, Vec2};
 use kas::macros::autoimpl;
 
@@ -68,26 +68,66 @@ impl Atlas {
         });
 
         let view = tex.create_view(&wgpu::TextureViewDescriptor::default());
-
-        let bg = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("atlas texture bind group"),
-            layout: bg_tex_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(sampler),
-                },
-            ],
-        });
+        let bg = create_bind_group(device, bg_tex_layout, sampler, &view);
 
         Atlas { alloc, tex, bg }
     }
 }
 
+fn create_bind_group(
+    device: &wgpu::Device,
+    bg_tex_layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    view: &wgpu::TextureView,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("atlas texture bind group"),
+        layout: bg_tex_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
+}
+
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    bg_common: &wgpu::BindGroupLayout,
+    bg_tex_layout: &wgpu::BindGroupLayout,
+    vertex: wgpu::VertexState,
+    fragment: wgpu::FragmentState,
+) -> wgpu::RenderPipeline {
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("atlas pipeline layout"),
+        bind_group_layouts: &[bg_common, bg_tex_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("atlas render pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex,
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleStrip,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Cw,
+            cull_mode: Some(wgpu::Face::Back), // not required
+            clamp_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: Default::default(),
+        fragment: Some(fragment),
+    })
+}
+
 /// A pipeline for rendering from image atlases
 pub struct Pipeline<I: bytemuck::Pod> {
     tex_size: i32,
@@ -140,29 +180,8 @@ impl<I: bytemuck::Pod> Pipeline<I> {
             ],
         });
 
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("atlas pipeline layout"),
-            bind_group_layouts: &[bg_common, &bg_tex_layout],
-            push_constant_ranges: &[],
-        });
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("atlas render pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex,
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Cw,
-                cull_mode: Some(wgpu::Face::Back), // not required
-                clamp_depth: false,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: Default::default(),
-            fragment: Some(fragment),
-        });
+        let render_pipeline =
+            create_render_pipeline(device, bg_common, &bg_tex_layout, vertex, fragment);
 
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("image sampler"),
@@ -274,6 +293,32 @@ impl<I: bytemuck::Pod> Pipeline<I> {
         &self.atlases[usize::conv(atlas)].tex
     }
 
+    /// Construct an alternative render pipeline over the same atlases
+    ///
+    /// This may be used with [`Pipeline::render_with`], e.g. to draw with a
+    /// different blend state.
+    pub fn new_render_pipeline(
+        &self,
+        device: &wgpu::Device,
+        bg_common: &wgpu::BindGroupLayout,
+        vertex: wgpu::VertexState,
+        fragment: wgpu::FragmentState,
+    ) -> wgpu::RenderPipeline {
+        create_render_pipeline(device, bg_common, &self.bg_tex_layout, vertex, fragment)
+    }
+
+    /// Construct a bind group over a texture `view` not managed by this pipeline
+    ///
+    /// The result is compatible with render pipelines over this pipeline's
+    /// atlases; see also [`Window::render_with_bind_groups`].
+    pub fn new_bind_group(
+        &self,
+        device: &wgpu::Device,
+        view: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        create_bind_group(device, &self.bg_tex_layout, &self.sampler, view)
+    }
+
     /// Enqueue render commands
     pub fn render<'a>(
         &'a self,
@@ -282,22 +327,21 @@ impl<I: bytemuck::Pod> Pipeline<I> {
         rpass: &mut wgpu::RenderPass<'a>,
         bg_common: &'a wgpu::BindGroup,
     ) {
-        if let Some(buffer) = window.buffer.as_ref() {
-            if let Some(pass) = window.passes.get(pass) {
-                if pass.data_range.is_empty() {
-                    return;
-                }
-                rpass.set_pipeline(&self.render_pipeline);
-                rpass.set_bind_group(0, bg_common, &[]);
-                rpass.set_vertex_buffer(0, buffer.slice(pass.data_range.clone()));
-                for (a, atlas) in pass.atlases.iter().enumerate() {
-                    if !atlas.range.is_empty() {
-                        rpass.set_bind_group(1, &self.atlases[a].bg, &[]);
-                        rpass.draw(0..4, atlas.range.clone());
-                    }
-                }
-            }
-        }
+        self.render_with(&self.render_pipeline, window, pass, rpass, bg_common);
+    }
+
+    /// Enqueue render commands using an alternative `render_pipeline`
+    pub fn render_with<'a>(
+        &'a self,
+        render_pipeline: &'a wgpu::RenderPipeline,
+        window: &'a Window<I>,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass<'a>,
+        bg_common: &'a wgpu::BindGroup,
+    ) {
+        window.render_with_bind_groups(render_pipeline, pass, rpass, bg_common, |a| {
+            self.atlases.get(a).map(|atlas| &atlas.bg)
+        });
     }
 }
 
@@ -398,6 +442,53 @@ impl<I: bytemuck::Pod> Window<I> {
         }
     }
 
+    /// Add statistics of a pass to `stats` (after [`Self::write_buffers`])
+    pub fn add_stats(&self, pass: usize, stats: &mut PassStats) {
+        if let Some(pass) = self.passes.get(pass) {
+            let mut drawn = false;
+            for atlas in pass.atlases.iter().filter(|atlas| !atlas.range.is_empty()) {
+                drawn = true;
+                stats.draw_calls += 1;
+                stats.vertices += 4 * (atlas.range.end - atlas.range.start);
+            }
+            if drawn {
+                stats.upload_bytes += pass.data_range.end - pass.data_range.start;
+            }
+        }
+    }
+
+    /// Enqueue render commands
+    ///
+    /// The texture bind group for each atlas index is given by `atlas_bg`;
+    /// atlases without a bind group are skipped.
+    pub fn render_with_bind_groups<'a>(
+        &'a self,
+        render_pipeline: &'a wgpu::RenderPipeline,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass<'a>,
+        bg_common: &'a wgpu::BindGroup,
+        atlas_bg: impl Fn(usize) -> Option<&'a wgpu::BindGroup>,
+    ) {
+        if let Some(buffer) = self.buffer.as_ref() {
+            if let Some(pass) = self.passes.get(pass) {
+                if pass.data_range.is_empty() {
+                    return;
+                }
+                rpass.set_pipeline(render_pipeline);
+                rpass.set_bind_group(0, bg_common, &[]);
+                rpass.set_vertex_buffer(0, buffer.slice(pass.data_range.clone()));
+                for (a, atlas) in pass.atlases.iter().enumerate() {
+                    if !atlas.range.is_empty() {
+                        if let Some(bg) = atlas_bg(a) {
+                            rpass.set_bind_group(1, bg, &[]);
+                            rpass.draw(0..4, atlas.range.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     /// Add a rectangle to the buffer
     pub fn rect(&mut self, pass: PassId, atlas: u32, instance: I) {
         let pass = pass.pass();
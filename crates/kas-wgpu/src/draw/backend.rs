@@ -0,0 +1,477 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Renderer selection
+//!
+//! [`DrawPipe`] and [`DrawWindow`] forward to the GPU renderer or (with feature
+//! `software`) to the software renderer.
+
+use super::*;
+use kas::draw::color::Rgba;
+use kas::draw::*;
+use kas::geom::{Offset, Quad, Vec2};
+use kas::text::{Effect, TextDisplay};
+use kas_theme::RasterConfig;
+
+/// Forward to the variant of `Pipe` or `Window` in use
+macro_rules! dispatch {
+    ($ty:ident, $x:expr, |$v:ident| $call:expr) => {
+        match $x {
+            $ty::Gpu($v) => $call,
+            #[cfg(feature = "software")]
+            $ty::Soft($v) => $call,
+        }
+    };
+}
+
+/// Forward to the variants of a `Pipe` and its `Window`
+macro_rules! dispatch2 {
+    ($pipe:expr, $window:expr, |$p:ident, $w:ident| $call:expr) => {
+        match ($pipe, $window) {
+            (Pipe::Gpu($p), Window::Gpu($w)) => $call,
+            #[cfg(feature = "software")]
+            (Pipe::Soft($p), Window::Soft($w)) => $call,
+            #[cfg(feature = "software")]
+            _ => panic!("DrawWindow was not constructed by this DrawPipe"),
+        }
+    };
+}
+
+impl<C: CustomPipe> DrawPipe<C> {
+    /// Construct, using the GPU renderer
+    pub fn new<CB: CustomPipeBuilder<Pipe = C>>(
+        custom: CB,
+        device_and_queue: (wgpu::Device, wgpu::Queue),
+        raster_config: &RasterConfig,
+    ) -> Self {
+        let pipe = GpuPipe::new(custom, device_and_queue, raster_config);
+        DrawPipe(Pipe::Gpu(pipe))
+    }
+
+    /// Construct, using the software renderer
+    ///
+    /// The custom pipe is not constructed: it requires a graphics device.
+    #[cfg(feature = "software")]
+    pub fn new_software(raster_config: &RasterConfig) -> Self {
+        DrawPipe(Pipe::Soft(soft::SoftPipe::new(raster_config)))
+    }
+
+    /// True if using the software renderer
+    pub fn is_software(&self) -> bool {
+        match self.0 {
+            Pipe::Gpu(_) => false,
+            #[cfg(feature = "software")]
+            Pipe::Soft(_) => true,
+        }
+    }
+
+    /// Access the GPU renderer
+    ///
+    /// Panics if using the software renderer.
+    pub(crate) fn gpu(&self) -> &GpuPipe<C> {
+        match &self.0 {
+            Pipe::Gpu(pipe) => pipe,
+            #[cfg(feature = "software")]
+            Pipe::Soft(_) => panic!("DrawPipe: not using the GPU renderer"),
+        }
+    }
+
+    /// Construct all pipelines not yet constructed
+    ///
+    /// Some pipelines are only constructed on first use. This method may be
+    /// used to construct these in advance, avoiding a delay later.
+    pub fn warm_up(&mut self) {
+        match &mut self.0 {
+            Pipe::Gpu(pipe) => pipe.warm_up(),
+            #[cfg(feature = "software")]
+            Pipe::Soft(_) => (),
+        }
+    }
+
+    /// Construct per-window state
+    pub fn new_window(&self) -> DrawWindow<C::Window> {
+        DrawWindow(match &self.0 {
+            Pipe::Gpu(pipe) => Window::Gpu(pipe.new_window()),
+            #[cfg(feature = "software")]
+            Pipe::Soft(pipe) => Window::Soft(pipe.new_window()),
+        })
+    }
+
+    /// Enable collection of draw statistics for `window`
+    ///
+    /// Statistics may then be read via [`DrawWindow::stats`]. GPU times are
+    /// only measured if the device was created with
+    /// [`wgpu::Features::TIMESTAMP_QUERY`]. The software renderer does not
+    /// collect statistics.
+    pub fn enable_stats(&self, window: &mut DrawWindow<C::Window>) {
+        match (&self.0, &mut window.0) {
+            (Pipe::Gpu(pipe), Window::Gpu(window)) => pipe.enable_stats(window),
+            #[cfg(feature = "software")]
+            _ => (),
+        }
+    }
+
+    /// Process window resize
+    pub fn resize(&self, window: &mut DrawWindow<C::Window>, size: Size) {
+        dispatch2!(&self.0, &mut window.0, |pipe, window| {
+            pipe.resize(window, size)
+        })
+    }
+
+    /// Render batched draw instructions via `rpass`
+    ///
+    /// The software renderer cannot render to a texture: it does nothing here.
+    pub fn render(
+        &mut self,
+        window: &mut DrawWindow<C::Window>,
+        frame_view: &wgpu::TextureView,
+        clear_color: Option<wgpu::Color>,
+    ) {
+        match (&mut self.0, &mut window.0) {
+            (Pipe::Gpu(pipe), Window::Gpu(window)) => pipe.render(window, frame_view, clear_color),
+            #[cfg(feature = "software")]
+            _ => log::warn!("DrawPipe::render: not using the GPU renderer"),
+        }
+    }
+
+    /// Render batched draw instructions to `pixmap` (software renderer only)
+    ///
+    /// If `clear_color` is `None`, the existing contents are drawn over.
+    #[cfg(feature = "software")]
+    pub(crate) fn render_pixmap(
+        &mut self,
+        window: &mut DrawWindow<C::Window>,
+        pixmap: &mut tiny_skia::Pixmap,
+        clear_color: Option<Rgba>,
+    ) {
+        match &mut window.0 {
+            Window::Soft(window) => window.render(pixmap, clear_color),
+            Window::Gpu(_) => {
+                log::warn!("DrawPipe::render_pixmap: not using the software renderer")
+            }
+        }
+    }
+
+    /// Update sub-pixel (LCD) text rendering of `window` for `scale_factor`
+    ///
+    /// The software renderer does not support sub-pixel rendering.
+    pub(crate) fn update_lcd(&self, window: &mut DrawWindow<C::Window>, scale_factor: f32) {
+        match (&self.0, &mut window.0) {
+            (Pipe::Gpu(pipe), Window::Gpu(window)) => {
+                window.text.update_lcd(&pipe.text, scale_factor);
+            }
+            #[cfg(feature = "software")]
+            _ => (),
+        }
+    }
+}
+
+impl<CW: CustomWindow> DrawWindow<CW> {
+    /// Get statistics of the last frame drawn
+    ///
+    /// Returns `None` unless enabled via [`DrawPipe::enable_stats`].
+    pub fn stats(&self) -> Option<&DrawStats> {
+        match &self.0 {
+            Window::Gpu(window) => window.stats.as_ref().map(|stats| stats.get()),
+            #[cfg(feature = "software")]
+            Window::Soft(_) => None,
+        }
+    }
+
+    /// Access the GPU renderer's state, if used
+    pub(crate) fn gpu_mut(&mut self) -> Option<&mut GpuWindow<CW>> {
+        match &mut self.0 {
+            Window::Gpu(window) => Some(window),
+            #[cfg(feature = "software")]
+            Window::Soft(_) => None,
+        }
+    }
+
+    /// Set whether the window background is transparent
+    ///
+    /// Sub-pixel text rendering is disabled over transparent backgrounds.
+    pub(crate) fn set_transparent(&mut self, transparent: bool) {
+        if let Some(window) = self.gpu_mut() {
+            window.text.set_transparent(transparent);
+        }
+    }
+
+    /// Time spent preparing text since the last call (µs)
+    pub(crate) fn text_dur_micros(&mut self) -> u128 {
+        self.gpu_mut().map(|w| w.text.dur_micros()).unwrap_or(0)
+    }
+}
+
+impl<C: CustomPipe> DrawSharedImpl for DrawPipe<C> {
+    type Draw = DrawWindow<C::Window>;
+
+    #[inline]
+    fn image_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
+        dispatch!(Pipe, &mut self.0, |pipe| pipe.image_alloc(size))
+    }
+
+    #[inline]
+    fn layer_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
+        dispatch!(Pipe, &mut self.0, |pipe| pipe.layer_alloc(size))
+    }
+
+    #[inline]
+    fn image_upload(&mut self, id: ImageId, data: &[u8], format: ImageFormat) {
+        dispatch!(Pipe, &mut self.0, |pipe| {
+            pipe.image_upload(id, data, format)
+        })
+    }
+
+    #[inline]
+    fn image_free(&mut self, id: ImageId) {
+        dispatch!(Pipe, &mut self.0, |pipe| pipe.image_free(id))
+    }
+
+    #[inline]
+    fn image_size(&self, id: ImageId) -> Option<(u32, u32)> {
+        dispatch!(Pipe, &self.0, |pipe| pipe.image_size(id))
+    }
+
+    #[inline]
+    fn draw_image(&self, draw: &mut Self::Draw, pass: PassId, id: ImageId, rect: Quad) {
+        dispatch2!(&self.0, &mut draw.0, |pipe, draw| {
+            pipe.draw_image(draw, pass, id, rect)
+        })
+    }
+
+    #[inline]
+    fn draw_text(
+        &mut self,
+        draw: &mut Self::Draw,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        col: Rgba,
+    ) {
+        dispatch2!(&mut self.0, &mut draw.0, |pipe, draw| {
+            pipe.draw_text(draw, pass, pos, text, col)
+        })
+    }
+
+    #[inline]
+    fn draw_text_col_effects(
+        &mut self,
+        draw: &mut Self::Draw,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        col: Rgba,
+        effects: &[Effect<()>],
+    ) {
+        dispatch2!(&mut self.0, &mut draw.0, |pipe, draw| {
+            pipe.draw_text_col_effects(draw, pass, pos, text, col, effects)
+        })
+    }
+
+    #[inline]
+    fn draw_text_effects(
+        &mut self,
+        draw: &mut Self::Draw,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        effects: &[Effect<Rgba>],
+    ) {
+        dispatch2!(&mut self.0, &mut draw.0, |pipe, draw| {
+            pipe.draw_text_effects(draw, pass, pos, text, effects)
+        })
+    }
+}
+
+impl<CW: CustomWindow> DrawImpl for DrawWindow<CW> {
+    #[inline]
+    fn new_pass(
+        &mut self,
+        parent_pass: PassId,
+        rect: Rect,
+        offset: Offset,
+        class: PassType,
+    ) -> PassId {
+        dispatch!(Window, &mut self.0, |w| {
+            w.new_pass(parent_pass, rect, offset, class)
+        })
+    }
+
+    #[inline]
+    fn new_transform_pass(
+        &mut self,
+        parent_pass: PassId,
+        rect: Rect,
+        transform: Transform,
+    ) -> PassId {
+        dispatch!(Window, &mut self.0, |w| {
+            w.new_transform_pass(parent_pass, rect, transform)
+        })
+    }
+
+    #[inline]
+    fn new_alpha_pass(&mut self, parent_pass: PassId, rect: Rect, alpha: f32) -> PassId {
+        dispatch!(Window, &mut self.0, |w| {
+            w.new_alpha_pass(parent_pass, rect, alpha)
+        })
+    }
+
+    #[inline]
+    fn get_clip_rect(&self, pass: PassId) -> Rect {
+        dispatch!(Window, &self.0, |w| w.get_clip_rect(pass))
+    }
+
+    #[inline]
+    fn get_transform(&self, pass: PassId) -> Transform {
+        dispatch!(Window, &self.0, |w| w.get_transform(pass))
+    }
+
+    #[inline]
+    fn rect(&mut self, pass: PassId, rect: Quad, col: Rgba) {
+        dispatch!(Window, &mut self.0, |w| w.rect(pass, rect, col))
+    }
+
+    #[inline]
+    fn frame(&mut self, pass: PassId, outer: Quad, inner: Quad, col: Rgba) {
+        dispatch!(Window, &mut self.0, |w| w.frame(pass, outer, inner, col))
+    }
+
+    #[inline]
+    fn rect_gradient(&mut self, pass: PassId, rect: Quad, gradient: &Gradient) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.rect_gradient(pass, rect, gradient)
+        })
+    }
+
+    #[inline]
+    fn frame_gradient(&mut self, pass: PassId, outer: Quad, inner: Quad, gradient: &Gradient) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.frame_gradient(pass, outer, inner, gradient)
+        })
+    }
+
+    #[inline]
+    fn as_rounded_impl(&mut self) -> Option<&mut dyn DrawRoundedImpl> {
+        Some(self)
+    }
+
+    #[inline]
+    fn as_shaded_impl(&mut self) -> Option<&mut dyn DrawShadedImpl> {
+        Some(self)
+    }
+
+    #[inline]
+    fn extension_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        dispatch!(Window, &mut self.0, |w| w.extension_mut())
+    }
+}
+
+impl<CW: CustomWindow> DrawRoundedImpl for DrawWindow<CW> {
+    #[inline]
+    fn rounded_line(&mut self, pass: PassId, p1: Vec2, p2: Vec2, radius: f32, col: Rgba) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.rounded_line(pass, p1, p2, radius, col)
+        })
+    }
+
+    #[inline]
+    fn polyline(
+        &mut self,
+        pass: PassId,
+        points: &[Vec2],
+        closed: bool,
+        stroke: &Stroke,
+        col: Rgba,
+    ) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.polyline(pass, points, closed, stroke, col)
+        })
+    }
+
+    #[inline]
+    fn circle(&mut self, pass: PassId, rect: Quad, inner_radius: f32, col: Rgba) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.circle(pass, rect, inner_radius, col)
+        })
+    }
+
+    #[inline]
+    fn circle_2col(&mut self, pass: PassId, rect: Quad, col1: Rgba, col2: Rgba) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.circle_2col(pass, rect, col1, col2)
+        })
+    }
+
+    #[inline]
+    fn rounded_frame(&mut self, pass: PassId, outer: Quad, inner: Quad, r1: f32, col: Rgba) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.rounded_frame(pass, outer, inner, r1, col)
+        })
+    }
+
+    #[inline]
+    fn rounded_frame_2col(&mut self, pass: PassId, outer: Quad, inner: Quad, c1: Rgba, c2: Rgba) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.rounded_frame_2col(pass, outer, inner, c1, c2)
+        })
+    }
+
+    #[inline]
+    fn shadow(&mut self, pass: PassId, rect: Quad, radius: f32, blur: f32, col: Rgba) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.shadow(pass, rect, radius, blur, col)
+        })
+    }
+
+    #[inline]
+    fn blur(&mut self, pass: PassId, rect: Quad, blur: f32) {
+        dispatch!(Window, &mut self.0, |w| w.blur(pass, rect, blur))
+    }
+}
+
+impl<CW: CustomWindow> DrawShadedImpl for DrawWindow<CW> {
+    #[inline]
+    fn shaded_square(&mut self, pass: PassId, rect: Quad, norm: (f32, f32), col: Rgba) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.shaded_square(pass, rect, norm, col)
+        })
+    }
+
+    #[inline]
+    fn shaded_circle(&mut self, pass: PassId, rect: Quad, norm: (f32, f32), col: Rgba) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.shaded_circle(pass, rect, norm, col)
+        })
+    }
+
+    #[inline]
+    fn shaded_square_frame(
+        &mut self,
+        pass: PassId,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        outer_col: Rgba,
+        inner_col: Rgba,
+    ) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.shaded_square_frame(pass, outer, inner, norm, outer_col, inner_col)
+        })
+    }
+
+    #[inline]
+    fn shaded_round_frame(
+        &mut self,
+        pass: PassId,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        col: Rgba,
+    ) {
+        dispatch!(Window, &mut self.0, |w| {
+            w.shaded_round_frame(pass, outer, inner, norm, col)
+        })
+    }
+}
@@ -0,0 +1,389 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Background blur pipeline
+//!
+//! Blurring reads content already drawn to the render target: the region is
+//! copied to a scratch texture, blurred horizontally into a second scratch
+//! texture, then blurred vertically back into the target. Since window
+//! surfaces may not be read from, windows using blur are drawn to an
+//! offscreen frame texture, then copied to the surface.
+
+use crate::draw::ShaderManager;
+use kas::cast::{Cast, CastFloat};
+use kas::geom::{Coord, Rect, Size, Vec2};
+use std::mem::size_of;
+use wgpu::util::DeviceExt;
+
+/// Maximum standard deviation of blur (pixels)
+///
+/// Cost is linear in the standard deviation.
+const MAX_SIGMA: f32 = 16.0;
+
+/// Target quad (normalised device coordinates) and source parameters
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct Instance {
+    a: Vec2,
+    b: Vec2,
+    origin: Vec2,
+    limit: Vec2,
+    dir: Vec2,
+    sigma: f32,
+}
+unsafe impl bytemuck::Zeroable for Instance {}
+unsafe impl bytemuck::Pod for Instance {}
+
+impl Instance {
+    /// Construct, drawing to `rect` of a target of size `target`
+    fn new(rect: Rect, target: Size, origin: Coord, limit: Size, dir: Vec2, sigma: f32) -> Self {
+        let target = Vec2::from(target);
+        let ndc = |p: Vec2| Vec2(2.0 * p.0 / target.0 - 1.0, 1.0 - 2.0 * p.1 / target.1);
+        let pos = Vec2::from(rect.pos);
+        Instance {
+            a: ndc(pos),
+            b: ndc(pos + Vec2::from(rect.size)),
+            origin: origin.into(),
+            limit: limit.into(),
+            dir,
+            sigma,
+        }
+    }
+}
+
+/// A texture with bind group for reading
+struct Texture {
+    tex: wgpu::Texture,
+    view: wgpu::TextureView,
+    bg: wgpu::BindGroup,
+    size: Size,
+}
+
+/// A blur of `rect` (target coordinates) before drawing `pass`
+struct Blur {
+    pass: usize,
+    rect: Rect,
+    sigma: f32,
+}
+
+/// Per-window state
+#[derive(Default)]
+pub struct Window {
+    blurs: Vec<Blur>,
+    scratch: Option<(Texture, Texture)>,
+    frame: Option<Texture>,
+}
+
+impl Window {
+    /// True if no blurs are queued
+    pub fn is_empty(&self) -> bool {
+        self.blurs.is_empty()
+    }
+
+    /// True if blurs are queued for `pass`
+    pub fn has_pass(&self, pass: usize) -> bool {
+        self.blurs.iter().any(|blur| blur.pass == pass)
+    }
+
+    /// Queue a blur of `rect` (in target coordinates) before drawing `pass`
+    pub fn blur(&mut self, pass: usize, rect: Rect, sigma: f32) {
+        let sigma = sigma.min(MAX_SIGMA);
+        if rect.size.0 > 0 && rect.size.1 > 0 && sigma > 0.0 {
+            self.blurs.push(Blur { pass, rect, sigma });
+        }
+    }
+
+    /// Clear queued blurs (after rendering a frame)
+    pub fn clear(&mut self) {
+        self.blurs.clear();
+    }
+
+    /// Take the offscreen frame texture, allocating if required
+    ///
+    /// Return this via [`Self::return_frame`] after use.
+    fn take_frame(&mut self, pipe: &Pipeline, device: &wgpu::Device, size: Size) -> Texture {
+        match self.frame.take() {
+            Some(frame) if frame.size == size => frame,
+            _ => {
+                let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_SRC;
+                pipe.new_texture(device, "blur frame", size, usage)
+            }
+        }
+    }
+
+    fn return_frame(&mut self, frame: Texture) {
+        self.frame = Some(frame);
+    }
+}
+
+/// Background blur pipeline
+pub struct Pipeline {
+    bg_layout: wgpu::BindGroupLayout,
+    render_pipeline: wgpu::RenderPipeline,
+}
+
+impl Pipeline {
+    /// Construct
+    pub fn new(device: &wgpu::Device, shaders: &ShaderManager) -> Self {
+        let bg_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("blur bind group layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("blur pipeline_layout"),
+            bind_group_layouts: &[&bg_layout],
+            push_constant_ranges: &[],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("blur render_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shaders.blur,
+                entry_point: "vert_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<Instance>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x2,
+                        1 => Float32x2,
+                        2 => Float32x2,
+                        3 => Float32x2,
+                        4 => Float32x2,
+                        5 => Float32,
+                    ],
+                }],
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                clamp_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: Default::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shaders.blur,
+                entry_point: "frag_main",
+                targets: &[wgpu::ColorTargetState {
+                    format: super::RENDER_TEX_FORMAT,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            }),
+        });
+
+        Pipeline {
+            bg_layout,
+            render_pipeline,
+        }
+    }
+
+    fn new_texture(
+        &self,
+        device: &wgpu::Device,
+        label: &str,
+        size: Size,
+        usage: wgpu::TextureUsages,
+    ) -> Texture {
+        let tex = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width: size.0.cast(),
+                height: size.1.cast(),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: super::RENDER_TEX_FORMAT,
+            usage,
+        });
+        let view = tex.create_view(&wgpu::TextureViewDescriptor::default());
+        let bg = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(label),
+            layout: &self.bg_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&view),
+            }],
+        });
+        Texture {
+            tex,
+            view,
+            bg,
+            size,
+        }
+    }
+
+    /// Draw `instance` to `view` via a new render pass
+    fn draw(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        scissor: Rect,
+        bg: &wgpu::BindGroup,
+        instance: Instance,
+    ) {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("blur instance"),
+            contents: bytemuck::bytes_of(&instance),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("blur render pass"),
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+        rpass.set_scissor_rect(
+            scissor.pos.0.cast(),
+            scissor.pos.1.cast(),
+            scissor.size.0.cast(),
+            scissor.size.1.cast(),
+        );
+        rpass.set_pipeline(&self.render_pipeline);
+        rpass.set_bind_group(0, bg, &[]);
+        rpass.set_vertex_buffer(0, buffer.slice(..));
+        rpass.draw(0..4, 0..1);
+    }
+
+    /// Apply blurs queued for `pass`
+    ///
+    /// The target (of size `size`) is read via `tex` and written via `view`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply(
+        &self,
+        window: &mut Window,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        pass: usize,
+        tex: &wgpu::Texture,
+        view: &wgpu::TextureView,
+        size: Size,
+    ) {
+        let bounds = Rect::new(Coord::ZERO, size);
+        for blur in window.blurs.iter().filter(|blur| blur.pass == pass) {
+            let rect = match blur.rect.intersection(&bounds) {
+                Some(rect) => rect,
+                None => continue,
+            };
+            // The source region includes a margin of 3 standard deviations
+            let margin: i32 = (3.0 * blur.sigma).ceil().cast_nearest();
+            let source = rect.expand(margin).intersection(&bounds).unwrap();
+
+            let scratch_size = match window.scratch.as_ref() {
+                Some((a, _)) => a.size,
+                None => Size::ZERO,
+            };
+            if !(source.size.0 <= scratch_size.0 && source.size.1 <= scratch_size.1) {
+                let size = source.size.max(scratch_size);
+                let usage = wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING;
+                let a = self.new_texture(device, "blur scratch", size, usage);
+                let usage =
+                    wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+                let b = self.new_texture(device, "blur scratch", size, usage);
+                window.scratch = Some((a, b));
+            }
+            let (a, b) = window.scratch.as_ref().unwrap();
+
+            encoder.copy_texture_to_texture(
+                wgpu::ImageCopyTexture {
+                    texture: tex,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: source.pos.0.cast(),
+                        y: source.pos.1.cast(),
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::ImageCopyTexture {
+                    texture: &a.tex,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::Extent3d {
+                    width: source.size.0.cast(),
+                    height: source.size.1.cast(),
+                    depth_or_array_layers: 1,
+                },
+            );
+
+            // Horizontal blur: a → b (at origin)
+            let local = Rect::new(Coord::ZERO, source.size);
+            let dir = Vec2(1.0, 0.0);
+            let instance = Instance::new(local, b.size, Coord::ZERO, source.size, dir, blur.sigma);
+            self.draw(device, encoder, &b.view, local, &a.bg, instance);
+
+            // Vertical blur: b → target (within rect)
+            let dir = Vec2(0.0, 1.0);
+            let instance = Instance::new(rect, size, source.pos, source.size, dir, blur.sigma);
+            self.draw(device, encoder, view, rect, &b.bg, instance);
+        }
+    }
+
+    /// Begin rendering a window using blur
+    ///
+    /// Returns an offscreen frame texture to draw to in place of the window's
+    /// surface. Draw this to the surface using [`Self::end_frame`].
+    pub fn begin_frame(&self, window: &mut Window, device: &wgpu::Device, size: Size) -> Frame {
+        Frame(window.take_frame(self, device, size))
+    }
+
+    /// Copy the offscreen `frame` to the window's surface `view`
+    pub fn end_frame(
+        &self,
+        window: &mut Window,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        frame: Frame,
+    ) {
+        let frame = frame.0;
+        let rect = Rect::new(Coord::ZERO, frame.size);
+        let instance = Instance::new(rect, frame.size, Coord::ZERO, frame.size, Vec2::ZERO, 0.0);
+        self.draw(device, encoder, view, rect, &frame.bg, instance);
+        window.return_frame(frame);
+    }
+}
+
+/// An offscreen frame texture (see [`Pipeline::begin_frame`])
+pub struct Frame(Texture);
+
+impl Frame {
+    /// The frame's texture
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.0.tex
+    }
+
+    /// A view over the frame's texture
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.0.view
+    }
+}
@@ -0,0 +1,154 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Render callbacks
+
+use kas::cast::Cast;
+use kas::draw::DrawHandle;
+use kas::geom::{Rect, Transform, Vec2};
+
+/// Context passed to a render callback
+///
+/// See [`draw_callback`].
+pub struct RenderContext<'a> {
+    /// The graphics device
+    pub device: &'a wgpu::Device,
+    /// The command queue
+    pub queue: &'a wgpu::Queue,
+    /// The frame's command encoder
+    ///
+    /// Commands recorded here are submitted together with the rest of the
+    /// frame.
+    pub encoder: &'a mut wgpu::CommandEncoder,
+    /// The render target
+    ///
+    /// This is the window's frame or an offscreen layer (e.g. when drawing
+    /// within a semi-transparent region), using format [`Self::FORMAT`].
+    pub view: &'a wgpu::TextureView,
+    /// Region to draw, in target coordinates (physical pixels)
+    pub rect: Rect,
+    /// The visible part of `rect`
+    ///
+    /// Output outside this region must not be drawn.
+    pub clip: Rect,
+    /// The window's scale factor
+    pub scale_factor: f32,
+}
+
+impl<'a> RenderContext<'a> {
+    /// Texture format of [`Self::view`]
+    pub const FORMAT: wgpu::TextureFormat = super::RENDER_TEX_FORMAT;
+
+    /// Begin a render pass over the target
+    ///
+    /// Existing content of the target is preserved. The viewport is set to
+    /// [`Self::rect`] (thus normalised device coordinates cover this region)
+    /// and the scissor rect to [`Self::clip`].
+    pub fn begin_render_pass(&mut self) -> wgpu::RenderPass<'_> {
+        let mut rpass = self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("kas-wgpu render callback"),
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view: self.view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+        let (pos, size) = (Vec2::from(self.rect.pos), Vec2::from(self.rect.size));
+        rpass.set_viewport(pos.0, pos.1, size.0, size.1, 0.0, 1.0);
+        let clip = self.clip;
+        rpass.set_scissor_rect(
+            clip.pos.0.cast(),
+            clip.pos.1.cast(),
+            clip.size.0.cast(),
+            clip.size.1.cast(),
+        );
+        rpass
+    }
+}
+
+type Callback = Box<dyn FnOnce(&mut RenderContext)>;
+
+/// Per-window list of render callbacks
+#[derive(Default)]
+pub(crate) struct Window {
+    // Pass, rect (pass coordinates), scale factor and callback
+    callbacks: Vec<(usize, Rect, f32, Option<Callback>)>,
+}
+
+impl Window {
+    /// Invoke callbacks for `pass`
+    ///
+    /// The pass's clip rect and transform to target coordinates are given by
+    /// `region`.
+    pub fn render(
+        &mut self,
+        pass: usize,
+        region: (Rect, Transform),
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+    ) {
+        let iter = self.callbacks.iter_mut().filter(|cb| cb.0 == pass);
+        for (_, rect, scale_factor, f) in iter {
+            let rect = region.1.map_rect(*rect);
+            let clip = match rect.intersection(&region.0) {
+                Some(clip) if clip.size.0 > 0 && clip.size.1 > 0 => clip,
+                _ => continue,
+            };
+            if let Some(f) = f.take() {
+                f(&mut RenderContext {
+                    device,
+                    queue,
+                    encoder,
+                    view,
+                    rect,
+                    clip,
+                    scale_factor: *scale_factor,
+                });
+            }
+        }
+    }
+
+    /// Remove all callbacks (at the end of a frame)
+    pub fn clear(&mut self) {
+        self.callbacks.clear();
+    }
+}
+
+/// Draw via a render callback
+///
+/// This is a simpler alternative to [`super::CustomPipe`] for one-off GPU
+/// content: during a widget's [`kas::Layout::draw`], call this with the
+/// widget's `rect`; `f` is then called once while rendering the frame.
+///
+/// Output of `f` appears above all other content of the same draw pass
+/// (including text) and below later passes (e.g. pop-ups and overlays). Use
+/// [`RenderContext::begin_render_pass`] to respect clipping.
+///
+/// Returns false (without calling `f`) if `draw` is not provided by `kas_wgpu`
+/// or `kas_wgpu` is using its software renderer.
+pub fn draw_callback(
+    draw: &mut dyn DrawHandle,
+    rect: Rect,
+    f: impl FnOnce(&mut RenderContext) + 'static,
+) -> bool {
+    let scale_factor = draw.size_handle().scale_factor();
+    let device = draw.draw_device();
+    let pass = device.get_pass().pass();
+    let ext = device.get_draw_impl_mut().extension_mut();
+    match ext.and_then(|ext| ext.downcast_mut::<Window>()) {
+        Some(window) => {
+            let callback = Some(Box::new(f) as Callback);
+            window.callbacks.push((pass, rect, scale_factor, callback));
+            true
+        }
+        None => false,
+    }
+}
@@ -6,6 +6,7 @@
 //! Common pipeline parts
 
 use kas::cast::Conv;
+use kas::draw::PassStats;
 use kas::macros::autoimpl;
 use std::mem::size_of;
 use std::num::NonZeroU64;
@@ -28,6 +29,11 @@ pub struct Window<V: bytemuck::Pod> {
 }
 
 impl<V: bytemuck::Pod> Window<V> {
+    /// True if no vertices are queued
+    pub fn is_empty(&self) -> bool {
+        self.passes.iter().all(|pd| pd.vertices.is_empty())
+    }
+
     /// Prepare vertex buffers
     pub fn write_buffers(
         &mut self,
@@ -113,6 +119,15 @@ impl<V: bytemuck::Pod> Window<V> {
         }
     }
 
+    /// Add statistics of a pass to `stats` (after [`Self::write_buffers`])
+    pub fn add_stats(&self, pass: usize, stats: &mut PassStats) {
+        if let Some(pass) = self.passes.get(pass).filter(|pass| pass.count > 0) {
+            stats.draw_calls += 1;
+            stats.vertices += pass.count;
+            stats.upload_bytes += pass.data_range.end - pass.data_range.start;
+        }
+    }
+
     pub fn add_vertices(&mut self, pass: usize, slice: &[V]) {
         debug_assert_eq!(slice.len() % 3, 0);
 
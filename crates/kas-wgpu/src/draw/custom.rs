@@ -25,7 +25,7 @@ pub trait CustomPipeBuilder {
     /// Request a device supporting these features and limits
     ///
     /// See [`wgpu::Adapter::request_device`] and [`wgpu::DeviceDescriptor`] doc.
-    fn device_descriptor() -> wgpu::DeviceDescriptor<'static> {
+    fn device_descriptor(&self) -> wgpu::DeviceDescriptor<'static> {
         Default::default()
     }
 
@@ -55,7 +55,8 @@ pub trait CustomPipeBuilder {
 /// [`crate::Toolkit::new_custom`].
 ///
 /// Note that `kas-wgpu` accepts only a single custom pipe. To use more than
-/// one custom graphics pipeline, you must implement your own multiplexer.
+/// one custom graphics pipeline, register each with a
+/// [`super::CustomPipeRegistry`].
 pub trait CustomPipe: 'static {
     /// Associated per-window state for the custom pipe
     type Window: CustomWindow;
@@ -177,7 +178,11 @@ impl CustomWindow for () {
 }
 
 impl<CW: CustomWindow> DrawCustom<CW> for DrawWindow<CW> {
+    /// Custom pipes are not available to the software renderer: there, this
+    /// does nothing.
     fn custom(&mut self, pass: PassId, rect: Rect, param: CW::Param) {
-        self.custom.invoke(pass, rect, param);
+        if let Some(window) = self.gpu_mut() {
+            window.custom.invoke(pass, rect, param);
+        }
     }
 }
@@ -0,0 +1,401 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Registry of multiple custom draw pipes
+
+use super::{CustomPipe, CustomPipeBuilder, CustomWindow};
+use kas::draw::PassId;
+use kas::geom::{Rect, Size};
+use std::any::Any;
+
+/// Key identifying a pipe within a [`CustomPipeRegistry`]
+///
+/// Keys are compared by value. Libraries providing custom pipes should use a
+/// sufficiently unique key, e.g. one prefixed by the crate name.
+pub type CustomPipeKey = &'static str;
+
+/// Object-safe version of [`CustomPipeBuilder`]
+trait PipeBuilderDyn {
+    fn device_descriptor(&self) -> wgpu::DeviceDescriptor<'static>;
+
+    fn build(
+        &mut self,
+        device: &wgpu::Device,
+        bgl_common: &wgpu::BindGroupLayout,
+        tex_format: wgpu::TextureFormat,
+    ) -> Box<dyn PipeDyn>;
+}
+
+impl<CB: CustomPipeBuilder> PipeBuilderDyn for CB
+where
+    <<CB::Pipe as CustomPipe>::Window as CustomWindow>::Param: 'static,
+{
+    fn device_descriptor(&self) -> wgpu::DeviceDescriptor<'static> {
+        CustomPipeBuilder::device_descriptor(self)
+    }
+
+    fn build(
+        &mut self,
+        device: &wgpu::Device,
+        bgl_common: &wgpu::BindGroupLayout,
+        tex_format: wgpu::TextureFormat,
+    ) -> Box<dyn PipeDyn> {
+        Box::new(CustomPipeBuilder::build(
+            self, device, bgl_common, tex_format,
+        ))
+    }
+}
+
+/// Object-safe version of [`CustomPipe`]
+///
+/// Windows are passed as [`WindowDyn`] and downcast to the expected type.
+trait PipeDyn {
+    fn new_window(&self, device: &wgpu::Device) -> Box<dyn WindowDyn>;
+
+    fn resize(
+        &self,
+        window: &mut dyn WindowDyn,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: Size,
+    );
+
+    fn prepare(
+        &self,
+        window: &mut dyn WindowDyn,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    );
+
+    fn render_pass<'a>(
+        &'a self,
+        window: &'a mut dyn WindowDyn,
+        device: &wgpu::Device,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass<'a>,
+        bg_common: &'a wgpu::BindGroup,
+    );
+
+    fn render_final<'a>(
+        &'a self,
+        window: &'a mut dyn WindowDyn,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        frame_view: &wgpu::TextureView,
+        size: Size,
+    );
+}
+
+fn downcast<W: 'static>(window: &mut dyn WindowDyn) -> &mut W {
+    window
+        .as_any_mut()
+        .downcast_mut()
+        .expect("CustomPipeRegistry: window type mismatch")
+}
+
+impl<P: CustomPipe> PipeDyn for P
+where
+    <P::Window as CustomWindow>::Param: 'static,
+{
+    fn new_window(&self, device: &wgpu::Device) -> Box<dyn WindowDyn> {
+        Box::new(CustomPipe::new_window(self, device))
+    }
+
+    fn resize(
+        &self,
+        window: &mut dyn WindowDyn,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: Size,
+    ) {
+        CustomPipe::resize(self, downcast(window), device, queue, size);
+    }
+
+    fn prepare(
+        &self,
+        window: &mut dyn WindowDyn,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        CustomPipe::prepare(self, downcast(window), device, staging_belt, encoder);
+    }
+
+    fn render_pass<'a>(
+        &'a self,
+        window: &'a mut dyn WindowDyn,
+        device: &wgpu::Device,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass<'a>,
+        bg_common: &'a wgpu::BindGroup,
+    ) {
+        let window = downcast(window);
+        CustomPipe::render_pass(self, window, device, pass, rpass, bg_common);
+    }
+
+    fn render_final<'a>(
+        &'a self,
+        window: &'a mut dyn WindowDyn,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        frame_view: &wgpu::TextureView,
+        size: Size,
+    ) {
+        let window = downcast(window);
+        CustomPipe::render_final(self, window, device, encoder, frame_view, size);
+    }
+}
+
+/// Object-safe version of [`CustomWindow`]
+trait WindowDyn: 'static {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Invoke with a type-erased parameter
+    ///
+    /// Returns the parameter on type mismatch.
+    fn invoke(&mut self, pass: PassId, rect: Rect, param: Box<dyn Any>)
+        -> Result<(), Box<dyn Any>>;
+}
+
+impl<CW: CustomWindow> WindowDyn for CW
+where
+    CW::Param: 'static,
+{
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn invoke(
+        &mut self,
+        pass: PassId,
+        rect: Rect,
+        param: Box<dyn Any>,
+    ) -> Result<(), Box<dyn Any>> {
+        let param = param.downcast::<CW::Param>()?;
+        CustomWindow::invoke(self, pass, rect, *param);
+        Ok(())
+    }
+}
+
+/// A registry of custom draw pipes
+///
+/// This allows use of multiple [`CustomPipe`] implementations at once, each
+/// identified by a [`CustomPipeKey`]. Construct via
+/// [`CustomPipeRegistry::builder`] and pass to [`crate::Toolkit::new_custom`].
+///
+/// Widgets invoke a registered pipe via [`super::DrawCustom`] by passing a
+/// [`RegistryParam`]:
+/// ```ignore
+/// let draw = DrawIface::<DrawPipe<RegistryPipe>>::downcast_from(draw).unwrap();
+/// draw.draw.custom(draw.get_pass(), rect, RegistryParam::new("my-pipe", param));
+/// ```
+///
+/// Pipes are rendered in order of registration.
+///
+/// The set of registered pipes is fixed when the toolkit is constructed, since
+/// pipes are built with the graphics device and per-window state is created
+/// with each window. Pipes cannot be registered at run-time (e.g. on first use
+/// by a widget); libraries providing pipes should instead expose a
+/// [`CustomPipeBuilder`] (and key) for the application to register.
+pub struct CustomPipeRegistry {
+    builders: Vec<(CustomPipeKey, Box<dyn PipeBuilderDyn>)>,
+}
+
+impl CustomPipeRegistry {
+    /// Construct with builder pattern
+    pub fn builder() -> CustomPipeRegistryBuilder {
+        CustomPipeRegistryBuilder { builders: vec![] }
+    }
+}
+
+/// Builder for [`CustomPipeRegistry`]
+///
+/// Construct via [`CustomPipeRegistry::builder`].
+pub struct CustomPipeRegistryBuilder {
+    builders: Vec<(CustomPipeKey, Box<dyn PipeBuilderDyn>)>,
+}
+
+impl CustomPipeRegistryBuilder {
+    /// Register a custom pipe
+    ///
+    /// Panics if `key` is already registered.
+    pub fn add<CB>(mut self, key: CustomPipeKey, builder: CB) -> Self
+    where
+        CB: CustomPipeBuilder + 'static,
+        <<CB::Pipe as CustomPipe>::Window as CustomWindow>::Param: 'static,
+    {
+        if self.builders.iter().any(|(k, _)| *k == key) {
+            panic!("CustomPipeRegistryBuilder: duplicate key {:?}", key);
+        }
+        self.builders.push((key, Box::new(builder)));
+        self
+    }
+
+    /// Build
+    pub fn build(self) -> CustomPipeRegistry {
+        CustomPipeRegistry {
+            builders: self.builders,
+        }
+    }
+}
+
+impl CustomPipeBuilder for CustomPipeRegistry {
+    type Pipe = RegistryPipe;
+
+    /// Merge device requirements of all registered pipes
+    ///
+    /// Requested features are combined. Of the limits, only
+    /// `max_push_constant_size` is merged (by taking the maximum); other
+    /// limits use their default values.
+    fn device_descriptor(&self) -> wgpu::DeviceDescriptor<'static> {
+        let mut features = wgpu::Features::empty();
+        let mut limits = wgpu::Limits::default();
+        for (_, builder) in &self.builders {
+            let desc = builder.device_descriptor();
+            features |= desc.features;
+            limits.max_push_constant_size = limits
+                .max_push_constant_size
+                .max(desc.limits.max_push_constant_size);
+        }
+        wgpu::DeviceDescriptor {
+            label: None,
+            features,
+            limits,
+        }
+    }
+
+    fn build(
+        &mut self,
+        device: &wgpu::Device,
+        bgl_common: &wgpu::BindGroupLayout,
+        tex_format: wgpu::TextureFormat,
+    ) -> Self::Pipe {
+        let pipes = self
+            .builders
+            .iter_mut()
+            .map(|(key, builder)| (*key, builder.build(device, bgl_common, tex_format)))
+            .collect();
+        RegistryPipe { pipes }
+    }
+}
+
+/// The pipe constructed by a [`CustomPipeRegistry`]
+pub struct RegistryPipe {
+    pipes: Vec<(CustomPipeKey, Box<dyn PipeDyn>)>,
+}
+
+impl RegistryPipe {
+    /// Iterate over keys of registered pipes
+    pub fn keys(&self) -> impl Iterator<Item = CustomPipeKey> + '_ {
+        self.pipes.iter().map(|(key, _)| *key)
+    }
+}
+
+impl CustomPipe for RegistryPipe {
+    type Window = RegistryWindow;
+
+    fn new_window(&self, device: &wgpu::Device) -> Self::Window {
+        let windows = self
+            .pipes
+            .iter()
+            .map(|(key, pipe)| (*key, pipe.new_window(device)))
+            .collect();
+        RegistryWindow { windows }
+    }
+
+    fn resize(
+        &self,
+        window: &mut Self::Window,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: Size,
+    ) {
+        for ((_, pipe), (_, w)) in self.pipes.iter().zip(window.windows.iter_mut()) {
+            pipe.resize(&mut **w, device, queue, size);
+        }
+    }
+
+    fn prepare(
+        &self,
+        window: &mut Self::Window,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        for ((_, pipe), (_, w)) in self.pipes.iter().zip(window.windows.iter_mut()) {
+            pipe.prepare(&mut **w, device, staging_belt, encoder);
+        }
+    }
+
+    fn render_pass<'a>(
+        &'a self,
+        window: &'a mut Self::Window,
+        device: &wgpu::Device,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass<'a>,
+        bg_common: &'a wgpu::BindGroup,
+    ) {
+        for ((_, pipe), (_, w)) in self.pipes.iter().zip(window.windows.iter_mut()) {
+            pipe.render_pass(&mut **w, device, pass, rpass, bg_common);
+        }
+    }
+
+    fn render_final<'a>(
+        &'a self,
+        window: &'a mut Self::Window,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        frame_view: &wgpu::TextureView,
+        size: Size,
+    ) {
+        for ((_, pipe), (_, w)) in self.pipes.iter().zip(window.windows.iter_mut()) {
+            pipe.render_final(&mut **w, device, encoder, frame_view, size);
+        }
+    }
+}
+
+/// Per-window state for a [`RegistryPipe`]
+pub struct RegistryWindow {
+    windows: Vec<(CustomPipeKey, Box<dyn WindowDyn>)>,
+}
+
+/// Parameter passed to a [`RegistryWindow`]
+///
+/// This identifies the target pipe by key and wraps the parameter expected by
+/// that pipe's [`CustomWindow::Param`].
+pub struct RegistryParam {
+    key: CustomPipeKey,
+    param: Box<dyn Any>,
+}
+
+impl RegistryParam {
+    /// Construct
+    ///
+    /// The type `P` must match [`CustomWindow::Param`] of the pipe registered
+    /// under `key`.
+    pub fn new<P: 'static>(key: CustomPipeKey, param: P) -> Self {
+        RegistryParam {
+            key,
+            param: Box::new(param),
+        }
+    }
+}
+
+impl CustomWindow for RegistryWindow {
+    type Param = RegistryParam;
+
+    fn invoke(&mut self, pass: PassId, rect: Rect, param: Self::Param) {
+        let key = param.key;
+        match self.windows.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, window)) => {
+                if window.invoke(pass, rect, param.param).is_err() {
+                    log::warn!("CustomPipeRegistry: bad parameter type for pipe {:?}", key);
+                }
+            }
+            None => log::warn!("CustomPipeRegistry: no pipe registered as {:?}", key),
+        }
+    }
+}
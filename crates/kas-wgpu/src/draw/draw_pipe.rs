@@ -3,7 +3,7 @@
 // You may obtain a copy of the License in the LICENSE-APACHE file or at:
 //     https://www.apache.org/licenses/LICENSE-2.0
 
-//! Drawing API for `kas_wgpu`
+//! GPU renderer
 
 use std::f32::consts::FRAC_PI_2;
 use wgpu::util::DeviceExt;
@@ -12,11 +12,10 @@ use super::*;
 use kas::cast::Cast;
 use kas::draw::color::Rgba;
 use kas::draw::*;
-use kas::geom::{Quad, Rect, Size, Vec2};
+use kas::geom::{Coord, Offset, Quad, Rect, Size, Transform, Vec2};
 use kas::text::{Effect, TextDisplay};
-use kas_theme::DrawShadedImpl;
 
-impl<C: CustomPipe> DrawPipe<C> {
+impl<C: CustomPipe> GpuPipe<C> {
     /// Construct
     pub fn new<CB: CustomPipeBuilder<Pipe = C>>(
         mut custom: CB,
@@ -71,15 +70,17 @@ impl<C: CustomPipe> DrawPipe<C> {
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
-        let images = images::Images::new(&device, &shaders, &bgl_common);
         let shaded_square = shaded_square::Pipeline::new(&device, &shaders, &bgl_common);
-        let shaded_round = shaded_round::Pipeline::new(&device, &shaders, &bgl_common);
         let flat_round = flat_round::Pipeline::new(&device, &shaders, &bgl_common);
-        let round_2col = round_2col::Pipeline::new(&device, &shaders, &bgl_common);
         let custom = custom.build(&device, &bgl_common, RENDER_TEX_FORMAT);
         let text = text_pipe::Pipeline::new(&device, &shaders, &bgl_common, raster_config);
 
-        DrawPipe {
+        let timestamp_period = device
+            .features()
+            .contains(wgpu::Features::TIMESTAMP_QUERY)
+            .then(|| queue.get_timestamp_period());
+
+        GpuPipe {
             device,
             queue,
             local_pool,
@@ -87,35 +88,118 @@ impl<C: CustomPipe> DrawPipe<C> {
             bgl_common,
             light_norm_buf,
             bg_common: vec![],
-            images,
+            shaders,
+            images: None,
             shaded_square,
-            shaded_round,
+            shaded_round: None,
             flat_round,
-            round_2col,
+            round_2col: None,
+            shadow: None,
+            gradient: None,
+            stroke: None,
+            blur: None,
             custom,
             text,
+            timestamp_period,
         }
     }
 
+    /// Construct all pipelines not yet constructed
+    ///
+    /// Some pipelines are only constructed on first use. This method may be
+    /// used to construct these in advance, avoiding a delay later.
+    pub fn warm_up(&mut self) {
+        self.images();
+        self.shaded_round();
+        self.round_2col();
+        self.shadow();
+        self.gradient();
+        self.stroke();
+        self.blur();
+    }
+
+    fn images(&mut self) -> &mut images::Images {
+        let (device, shaders, bgl) = (&self.device, &self.shaders, &self.bgl_common);
+        self.images
+            .get_or_insert_with(|| images::Images::new(device, shaders, bgl))
+    }
+
+    fn shaded_round(&mut self) -> &shaded_round::Pipeline {
+        let (device, shaders, bgl) = (&self.device, &self.shaders, &self.bgl_common);
+        self.shaded_round
+            .get_or_insert_with(|| shaded_round::Pipeline::new(device, shaders, bgl))
+    }
+
+    fn round_2col(&mut self) -> &round_2col::Pipeline {
+        let (device, shaders, bgl) = (&self.device, &self.shaders, &self.bgl_common);
+        self.round_2col
+            .get_or_insert_with(|| round_2col::Pipeline::new(device, shaders, bgl))
+    }
+
+    fn shadow(&mut self) -> &shadow::Pipeline {
+        let (device, shaders, bgl) = (&self.device, &self.shaders, &self.bgl_common);
+        self.shadow
+            .get_or_insert_with(|| shadow::Pipeline::new(device, shaders, bgl))
+    }
+
+    fn gradient(&mut self) -> &gradient::Pipeline {
+        let (device, shaders, bgl) = (&self.device, &self.shaders, &self.bgl_common);
+        self.gradient
+            .get_or_insert_with(|| gradient::Pipeline::new(device, shaders, bgl))
+    }
+
+    fn stroke(&mut self) -> &stroke::Pipeline {
+        let (device, shaders, bgl) = (&self.device, &self.shaders, &self.bgl_common);
+        self.stroke
+            .get_or_insert_with(|| stroke::Pipeline::new(device, shaders, bgl))
+    }
+
+    fn blur(&mut self) -> &blur::Pipeline {
+        let (device, shaders) = (&self.device, &self.shaders);
+        self.blur
+            .get_or_insert_with(|| blur::Pipeline::new(device, shaders))
+    }
+
     /// Construct per-window state
-    pub fn new_window(&self) -> DrawWindow<C::Window> {
+    pub fn new_window(&self) -> GpuWindow<C::Window> {
         let custom = self.custom.new_window(&self.device);
 
-        DrawWindow {
+        GpuWindow {
             scale: Default::default(),
             clip_regions: vec![Default::default()],
+            pass_targets: vec![Target::Window],
+            alpha_passes: vec![],
+            alpha_layers: vec![],
             images: Default::default(),
             shaded_square: Default::default(),
             shaded_round: Default::default(),
             flat_round: Default::default(),
             round_2col: Default::default(),
+            shadow: Default::default(),
+            gradient: Default::default(),
+            stroke: Default::default(),
+            blur: Default::default(),
             custom,
+            callbacks: Default::default(),
             text: Default::default(),
+            stats: None,
+        }
+    }
+
+    /// Enable collection of draw statistics for `window`
+    ///
+    /// Statistics may then be read via [`GpuWindow::stats`]. GPU times are
+    /// only measured if the device was created with
+    /// [`wgpu::Features::TIMESTAMP_QUERY`].
+    pub fn enable_stats(&self, window: &mut GpuWindow<C::Window>) {
+        if window.stats.is_none() {
+            let stats = stats::Stats::new(&self.device, self.timestamp_period);
+            window.stats = Some(stats);
         }
     }
 
     /// Process window resize
-    pub fn resize(&self, window: &mut DrawWindow<C::Window>, size: Size) {
+    pub fn resize(&self, window: &mut GpuWindow<C::Window>, size: Size) {
         window.clip_regions[0].0.size = size;
 
         let vsize = Vec2::from(size);
@@ -132,75 +216,120 @@ impl<C: CustomPipe> DrawPipe<C> {
     /// Render batched draw instructions via `rpass`
     pub fn render(
         &mut self,
-        window: &mut DrawWindow<C::Window>,
+        window: &mut GpuWindow<C::Window>,
         frame_view: &wgpu::TextureView,
-        clear_color: wgpu::Color,
+        clear_color: Option<wgpu::Color>,
     ) {
+        let mut stats = window.stats.take();
+        if let Some(stats) = stats.as_mut() {
+            stats.begin_frame();
+        }
+
+        self.prepare_alpha_layers(window);
+
         // Update all bind groups. We use a separate bind group for each clip
         // region and update on each render, although they don't always change.
         // NOTE: we could use push constants instead.
-        let mut scale = window.scale;
-        let base_offset = (scale[0], scale[1]);
-        for (region, bg) in window.clip_regions.iter().zip(self.bg_common.iter()) {
-            let offset = Vec2::from(region.1);
-            scale[0] = base_offset.0 - offset.0;
-            scale[1] = base_offset.1 - offset.1;
+        let images = self.images.as_ref();
+        let scales: Vec<Scale> = window
+            .clip_regions
+            .iter()
+            .zip(window.pass_targets.iter())
+            .map(|(region, target)| {
+                let mut scale = window.scale;
+                let layer = window.target_layer(*target);
+                let layer_size = layer.and_then(|id| images.and_then(|im| im.image_size(id)));
+                if let Some(size) = layer_size {
+                    // Layer passes map the layer's area to its whole texture
+                    let vsize = Vec2::from(Size::from(size));
+                    let off = vsize * -0.5;
+                    let scale2 = 2.0 / vsize;
+                    scale = [off.0, off.1, scale2.0, -scale2.1];
+                }
+                // Target coordinates are `p * s + t` for pass coordinates `p`
+                let (s, t) = (region.1.scale, region.1.translate);
+                [
+                    (scale[0] + t.0) / s,
+                    (scale[1] + t.1) / s,
+                    scale[2] * s,
+                    scale[3] * s,
+                ]
+            })
+            .collect();
+        for (scale, bg) in scales.iter().zip(self.bg_common.iter()) {
             self.queue
-                .write_buffer(&bg.0, 0, bytemuck::cast_slice(&scale));
+                .write_buffer(&bg.0, 0, bytemuck::cast_slice(scale));
         }
         let device = &self.device;
         let bg_len = self.bg_common.len();
-        if window.clip_regions.len() > bg_len {
+        if scales.len() > bg_len {
             let (bgl_common, light_norm_buf) = (&self.bgl_common, &self.light_norm_buf);
-            self.bg_common
-                .extend(window.clip_regions[bg_len..].iter().map(|region| {
-                    let offset = Vec2::from(region.1);
-                    scale[0] = base_offset.0 - offset.0;
-                    scale[1] = base_offset.1 - offset.1;
-                    let scale_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                        label: Some("scale_buf"),
-                        contents: bytemuck::cast_slice(&scale),
-                        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-                    });
-                    let bg_common = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                        label: Some("common bind group"),
-                        layout: bgl_common,
-                        entries: &[
-                            wgpu::BindGroupEntry {
-                                binding: 0,
-                                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                                    buffer: &scale_buf,
-                                    offset: 0,
-                                    size: None,
-                                }),
-                            },
-                            wgpu::BindGroupEntry {
-                                binding: 1,
-                                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                                    buffer: light_norm_buf,
-                                    offset: 0,
-                                    size: None,
-                                }),
-                            },
-                        ],
-                    });
-                    (scale_buf, bg_common)
-                }));
+            self.bg_common.extend(scales[bg_len..].iter().map(|scale| {
+                let scale_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("scale_buf"),
+                    contents: bytemuck::cast_slice(scale),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+                let bg_common = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("common bind group"),
+                    layout: bgl_common,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                                buffer: &scale_buf,
+                                offset: 0,
+                                size: None,
+                            }),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                                buffer: light_norm_buf,
+                                offset: 0,
+                                size: None,
+                            }),
+                        },
+                    ],
+                });
+                (scale_buf, bg_common)
+            }));
         }
         self.queue.submit(std::iter::empty());
 
+        if !window.shaded_round.is_empty() {
+            self.shaded_round();
+        }
+        if !window.round_2col.is_empty() {
+            self.round_2col();
+        }
+        if !window.shadow.is_empty() {
+            self.shadow();
+        }
+        if !window.gradient.is_empty() {
+            self.gradient();
+        }
+        if !window.stroke.is_empty() {
+            self.stroke();
+        }
+        if !window.blur.is_empty() {
+            self.blur();
+        }
+
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("render"),
             });
 
-        self.images.prepare(
-            &mut window.images,
-            &self.device,
-            &mut self.staging_belt,
-            &mut encoder,
-        );
+        if let Some(images) = self.images.as_mut() {
+            images.prepare(
+                &mut window.images,
+                &self.device,
+                &mut self.staging_belt,
+                &mut encoder,
+            );
+        }
         window
             .shaded_square
             .write_buffers(&self.device, &mut self.staging_belt, &mut encoder);
@@ -213,6 +342,15 @@ impl<C: CustomPipe> DrawPipe<C> {
         window
             .round_2col
             .write_buffers(&self.device, &mut self.staging_belt, &mut encoder);
+        window
+            .shadow
+            .write_buffers(&self.device, &mut self.staging_belt, &mut encoder);
+        window
+            .gradient
+            .write_buffers(&self.device, &mut self.staging_belt, &mut encoder);
+        window
+            .stroke
+            .write_buffers(&self.device, &mut self.staging_belt, &mut encoder);
         self.custom.prepare(
             &mut window.custom,
             &self.device,
@@ -224,59 +362,129 @@ impl<C: CustomPipe> DrawPipe<C> {
             .text
             .write_buffers(&self.device, &mut self.staging_belt, &mut encoder);
 
+        if let Some(stats) = stats.as_mut() {
+            for pass in 0..window.clip_regions.len() {
+                let mut pass_stats = PassStats::default();
+                window.shadow.add_stats(pass, &mut pass_stats);
+                window.round_2col.add_stats(pass, &mut pass_stats);
+                window.shaded_square.add_stats(pass, &mut pass_stats);
+                window.gradient.add_stats(pass, &mut pass_stats);
+                window.images.add_stats(pass, &mut pass_stats);
+                window.shaded_round.add_stats(pass, &mut pass_stats);
+                window.flat_round.add_stats(pass, &mut pass_stats);
+                window.stroke.add_stats(pass, &mut pass_stats);
+                window.text.add_stats(pass, &mut pass_stats);
+                stats.push_pass(pass_stats);
+            }
+        }
+
+        // Layers must be drawn before any pass using them. Since a layer may
+        // contain other layers, we draw those started last first.
+        let mut layers: Vec<(ImageId, Vec<usize>)> = vec![];
+        let mut window_passes = vec![];
+        for (pass, target) in window.pass_targets.iter().enumerate() {
+            let id = match window.target_layer(*target) {
+                Some(id) => id,
+                None if *target == Target::Window => {
+                    window_passes.push(pass);
+                    continue;
+                }
+                // Alpha pass without a layer (empty region): nothing to draw
+                None => continue,
+            };
+            match layers.iter_mut().find(|layer| layer.0 == id) {
+                Some(layer) => layer.1.push(pass),
+                None => layers.push((id, vec![pass])),
+            }
+        }
+
+        if let Some(images) = self.images.as_ref() {
+            for (id, passes) in layers.iter().rev() {
+                let view = match images.layer_view(*id) {
+                    Some(view) => view,
+                    None => continue,
+                };
+                let mut color_attachments = [wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: true,
+                    },
+                }];
+
+                let texture = images.layer_texture(*id).unwrap();
+                let size = images.image_size(*id).unwrap();
+                let size = Size::from(size);
+
+                for (i, pass) in passes.iter().cloned().enumerate() {
+                    // Blurs read content of previous passes
+                    if let Some(blur) = self.blur.as_ref().filter(|_| i > 0) {
+                        let (device, encoder) = (&self.device, &mut encoder);
+                        blur.apply(&mut window.blur, device, encoder, pass, texture, view, size);
+                    }
+                    if let Some(stats) = stats.as_mut() {
+                        stats.begin_pass(&mut encoder, pass);
+                    }
+                    self.render_pass(window, pass, &mut encoder, &color_attachments);
+                    if let Some(stats) = stats.as_mut() {
+                        stats.end_pass(&mut encoder);
+                    }
+                    self.render_callbacks(window, pass, &mut encoder, view);
+                    color_attachments[0].ops.load = wgpu::LoadOp::Load;
+                }
+            }
+        }
+
+        // Windows using blur are drawn to an offscreen frame (see blur::Pipeline)
+        let size = window.clip_regions[0].0.size;
+        let blur = self.blur.as_ref();
+        let blur = blur.filter(|_| {
+            let uses_blur = window_passes.iter().any(|pass| window.blur.has_pass(*pass));
+            uses_blur && size.0 > 0 && size.1 > 0
+        });
+        let frame = blur.map(|blur| blur.begin_frame(&mut window.blur, &self.device, size));
+
         let mut color_attachments = [wgpu::RenderPassColorAttachment {
-            view: frame_view,
+            view: frame
+                .as_ref()
+                .map(|frame| frame.view())
+                .unwrap_or(frame_view),
             resolve_target: None,
             ops: wgpu::Operations {
-                load: wgpu::LoadOp::Clear(clear_color),
+                load: clear_color.map_or(wgpu::LoadOp::Load, wgpu::LoadOp::Clear),
                 store: true,
             },
         }];
 
         // We use a separate render pass for each clipped region.
-        for (pass, (rect, _)) in window.clip_regions.iter().enumerate() {
-            if rect.size.0 == 0 || rect.size.1 == 0 {
-                continue;
+        for (i, pass) in window_passes.into_iter().enumerate() {
+            if let Some((blur, frame)) = blur.zip(frame.as_ref()).filter(|_| i > 0) {
+                let (device, encoder, texture) = (&self.device, &mut encoder, frame.texture());
+                let view = frame.view();
+                blur.apply(&mut window.blur, device, encoder, pass, texture, view, size);
             }
-            let bg_common = &self.bg_common[pass].1;
-
-            {
-                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: Some("kas-wgpu render pass"),
-                    color_attachments: &color_attachments,
-                    depth_stencil_attachment: None,
-                });
-                rpass.set_scissor_rect(
-                    rect.pos.0.cast(),
-                    rect.pos.1.cast(),
-                    rect.size.0.cast(),
-                    rect.size.1.cast(),
-                );
-
-                self.round_2col
-                    .render(&window.round_2col, pass, &mut rpass, bg_common);
-                self.shaded_square
-                    .render(&window.shaded_square, pass, &mut rpass, bg_common);
-                self.images
-                    .render(&window.images, pass, &mut rpass, bg_common);
-                self.shaded_round
-                    .render(&window.shaded_round, pass, &mut rpass, bg_common);
-                self.flat_round
-                    .render(&window.flat_round, pass, &mut rpass, bg_common);
-                self.custom.render_pass(
-                    &mut window.custom,
-                    &self.device,
-                    pass,
-                    &mut rpass,
-                    bg_common,
-                );
-                self.text.render(&window.text, pass, &mut rpass, bg_common);
+            if let Some(stats) = stats.as_mut() {
+                stats.begin_pass(&mut encoder, pass);
             }
-
+            self.render_pass(window, pass, &mut encoder, &color_attachments);
+            if let Some(stats) = stats.as_mut() {
+                stats.end_pass(&mut encoder);
+            }
+            let view = color_attachments[0].view;
+            self.render_callbacks(window, pass, &mut encoder, view);
             color_attachments[0].ops.load = wgpu::LoadOp::Load;
         }
 
-        let size = window.clip_regions[0].0.size;
+        if let Some((blur, frame)) = blur.zip(frame) {
+            blur.end_frame(
+                &mut window.blur,
+                &self.device,
+                &mut encoder,
+                frame_view,
+                frame,
+            );
+        }
 
         self.custom.render_final(
             &mut window.custom,
@@ -288,48 +496,184 @@ impl<C: CustomPipe> DrawPipe<C> {
 
         // Keep only first clip region (which is the entire window)
         window.clip_regions.truncate(1);
+        window.pass_targets.truncate(1);
+        window.alpha_passes.clear();
+        window.blur.clear();
+        window.callbacks.clear();
+
+        if let Some(stats) = stats.as_mut() {
+            stats.resolve(&mut encoder);
+        }
 
         self.staging_belt.finish();
         self.queue.submit(std::iter::once(encoder.finish()));
 
         use futures::task::SpawnExt;
-        self.local_pool
-            .spawner()
+        let spawner = self.local_pool.spawner();
+        spawner
             .spawn(self.staging_belt.recall())
             .expect("Recall staging belt");
+        if let Some(stats) = stats.as_mut() {
+            stats.finish(&spawner);
+        }
+        window.stats = stats;
         self.local_pool.run_until_stalled();
     }
+
+    /// Allocate layers for alpha passes and composite these into their parents
+    fn prepare_alpha_layers(&mut self, window: &mut GpuWindow<C::Window>) {
+        let count = window.alpha_passes.len();
+        if count == 0 && window.alpha_layers.is_empty() {
+            return;
+        }
+        self.images();
+        let images = self.images.as_mut().unwrap();
+
+        // Free layers not used this frame
+        while window.alpha_layers.len() > count {
+            if let Some((id, _)) = window.alpha_layers.pop().flatten() {
+                images.free(id);
+            }
+        }
+        window.alpha_layers.resize(count, None);
+
+        let iter = window
+            .alpha_passes
+            .iter()
+            .zip(window.alpha_layers.iter_mut());
+        for (alpha_pass, layer) in iter {
+            let size = alpha_pass.rect.size;
+            if layer.map(|(_, layer_size)| layer_size) != Some(size) {
+                if let Some((id, _)) = layer.take() {
+                    images.free(id);
+                }
+                let result = images.alloc_layer(&self.device, (size.0.cast(), size.1.cast()));
+                *layer = result.ok().map(|id| (id, size));
+            }
+
+            if let Some((id, _)) = *layer {
+                let (pass, rect) = (alpha_pass.composite, alpha_pass.rect.into());
+                images.draw_layer(&mut window.images, pass, id, rect, alpha_pass.alpha);
+            }
+        }
+    }
+
+    /// Invoke render callbacks of a single clip region
+    fn render_callbacks(
+        &self,
+        window: &mut GpuWindow<C::Window>,
+        pass: usize,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+    ) {
+        let region = window.clip_regions[pass];
+        let (device, queue) = (&self.device, &self.queue);
+        window
+            .callbacks
+            .render(pass, region, device, queue, encoder, view);
+    }
+
+    /// Render a single clip region via a new render pass
+    fn render_pass(
+        &self,
+        window: &mut GpuWindow<C::Window>,
+        pass: usize,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachments: &[wgpu::RenderPassColorAttachment],
+    ) {
+        let rect = window.clip_regions[pass].0;
+        let bg_common = &self.bg_common[pass].1;
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("kas-wgpu render pass"),
+            color_attachments,
+            depth_stencil_attachment: None,
+        });
+        if rect.size.0 == 0 || rect.size.1 == 0 {
+            // We still run the pass since it may clear the target
+            return;
+        }
+        rpass.set_scissor_rect(
+            rect.pos.0.cast(),
+            rect.pos.1.cast(),
+            rect.size.0.cast(),
+            rect.size.1.cast(),
+        );
+
+        if let Some(pipe) = self.shadow.as_ref() {
+            pipe.render(&window.shadow, pass, &mut rpass, bg_common);
+        }
+        if let Some(pipe) = self.round_2col.as_ref() {
+            pipe.render(&window.round_2col, pass, &mut rpass, bg_common);
+        }
+        self.shaded_square
+            .render(&window.shaded_square, pass, &mut rpass, bg_common);
+        if let Some(pipe) = self.gradient.as_ref() {
+            pipe.render(&window.gradient, pass, &mut rpass, bg_common);
+        }
+        if let Some(images) = self.images.as_ref() {
+            images.render(&window.images, pass, &mut rpass, bg_common);
+        }
+        if let Some(pipe) = self.shaded_round.as_ref() {
+            pipe.render(&window.shaded_round, pass, &mut rpass, bg_common);
+        }
+        self.flat_round
+            .render(&window.flat_round, pass, &mut rpass, bg_common);
+        if let Some(pipe) = self.stroke.as_ref() {
+            pipe.render(&window.stroke, pass, &mut rpass, bg_common);
+        }
+        self.custom.render_pass(
+            &mut window.custom,
+            &self.device,
+            pass,
+            &mut rpass,
+            bg_common,
+        );
+        self.text.render(&window.text, pass, &mut rpass, bg_common);
+    }
 }
 
-impl<C: CustomPipe> DrawSharedImpl for DrawPipe<C> {
-    type Draw = DrawWindow<C::Window>;
+impl<C: CustomPipe> DrawSharedImpl for GpuPipe<C> {
+    type Draw = GpuWindow<C::Window>;
 
     #[inline]
     fn image_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
-        self.images.alloc(size)
+        self.images().alloc(size)
+    }
+
+    #[inline]
+    fn layer_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
+        self.images();
+        let images = self.images.as_mut().unwrap();
+        images.alloc_layer(&self.device, size)
     }
 
     #[inline]
     fn image_upload(&mut self, id: ImageId, data: &[u8], format: ImageFormat) {
-        self.images
-            .upload(&self.device, &self.queue, id, data, format);
+        if let Some(images) = self.images.as_mut() {
+            images.upload(&self.device, &self.queue, id, data, format);
+        }
     }
 
     #[inline]
     fn image_free(&mut self, id: ImageId) {
-        self.images.free(id);
+        if let Some(images) = self.images.as_mut() {
+            images.free(id);
+        }
     }
 
     #[inline]
     fn image_size(&self, id: ImageId) -> Option<(u32, u32)> {
-        self.images.image_size(id)
+        self.images
+            .as_ref()
+            .and_then(|images| images.image_size(id))
     }
 
     #[inline]
     fn draw_image(&self, draw: &mut Self::Draw, pass: PassId, id: ImageId, rect: Quad) {
-        if let Some((atlas, tex)) = self.images.get_im_atlas_coords(id) {
-            draw.images.rect(pass, atlas, tex, rect);
-        };
+        if let Some(images) = self.images.as_ref() {
+            images.draw(&mut draw.images, pass, id, rect);
+        }
     }
 
     #[inline]
@@ -378,7 +722,25 @@ impl<C: CustomPipe> DrawSharedImpl for DrawPipe<C> {
     }
 }
 
-impl<CW: CustomWindow> DrawImpl for DrawWindow<CW> {
+impl<CW: CustomWindow> GpuWindow<CW> {
+    fn push_pass(&mut self, rect: Rect, transform: Transform, target: Target) -> PassId {
+        let pass = self.clip_regions.len().cast();
+        self.clip_regions.push((rect, transform));
+        self.pass_targets.push(target);
+        PassId::new(pass)
+    }
+
+    /// Get the layer drawn to by `target`, if any
+    fn target_layer(&self, target: Target) -> Option<ImageId> {
+        match target {
+            Target::Window => None,
+            Target::Layer(id) => Some(id),
+            Target::Alpha(index) => self.alpha_layers.get(index)?.map(|(id, _)| id),
+        }
+    }
+}
+
+impl<CW: CustomWindow> DrawImpl for GpuWindow<CW> {
     fn new_pass(
         &mut self,
         parent_pass: PassId,
@@ -386,22 +748,79 @@ impl<CW: CustomWindow> DrawImpl for DrawWindow<CW> {
         offset: Offset,
         class: PassType,
     ) -> PassId {
-        let parent = match class {
-            PassType::Clip => &self.clip_regions[parent_pass.pass()],
-            PassType::Overlay => &self.clip_regions[0],
+        let parent_pass = match class {
+            PassType::Clip => parent_pass,
+            PassType::Overlay => PassId::new(0),
+            PassType::Layer(id) => {
+                // Layer coordinates are relative to the layer's origin (rect.pos)
+                let offset = offset + (rect.pos - Coord::ZERO);
+                let transform = Transform::translation(-Vec2::from(offset));
+                let rect = Rect::new(Coord::ZERO, rect.size);
+                return self.push_pass(rect, transform, Target::Layer(id));
+            }
         };
-        let rect = rect - parent.1;
-        let offset = offset + parent.1;
+        let transform = Transform::translation(-Vec2::from(offset));
+        self.new_transform_pass(parent_pass, rect, transform)
+    }
+
+    fn new_transform_pass(
+        &mut self,
+        parent_pass: PassId,
+        rect: Rect,
+        transform: Transform,
+    ) -> PassId {
+        let parent_pass = parent_pass.pass();
+        let parent = &self.clip_regions[parent_pass];
+        let rect = parent.1.map_rect(rect);
         let rect = rect.intersection(&parent.0).unwrap_or(Rect::ZERO);
-        let pass = self.clip_regions.len().cast();
-        self.clip_regions.push((rect, offset));
-        PassId::new(pass)
+        let transform = transform.then(parent.1);
+        self.push_pass(rect, transform, self.pass_targets[parent_pass])
+    }
+
+    fn new_alpha_pass(&mut self, parent_pass: PassId, rect: Rect, alpha: f32) -> PassId {
+        if alpha >= 1.0 {
+            return self.new_transform_pass(parent_pass, rect, Transform::IDENTITY);
+        }
+
+        let parent_pass = parent_pass.pass();
+        let (parent_rect, parent_transform) = self.clip_regions[parent_pass];
+        let rect = parent_transform.map_rect(rect);
+        let rect = rect.intersection(&parent_rect).unwrap_or(Rect::ZERO);
+
+        // Contents are drawn to an offscreen layer covering rect (in target
+        // coordinates), then composited in a pass following the parent's.
+        let target = self.pass_targets[parent_pass];
+        let composite = self.push_pass(rect, Transform::IDENTITY, target);
+        let index = self.alpha_passes.len();
+        self.alpha_passes.push(AlphaPass {
+            composite,
+            rect,
+            alpha,
+        });
+
+        let transform = Transform::translation(-Vec2::from(rect.pos));
+        let transform = parent_transform.then(transform);
+        let rect = Rect::new(Coord::ZERO, rect.size);
+        self.push_pass(rect, transform, Target::Alpha(index))
     }
 
     #[inline]
     fn get_clip_rect(&self, pass: PassId) -> Rect {
         let region = &self.clip_regions[pass.pass()];
-        region.0 + region.1
+        region.1.inverse().map_rect(region.0)
+    }
+
+    fn get_transform(&self, pass: PassId) -> Transform {
+        let pass = pass.pass();
+        let mut transform = self.clip_regions[pass].1;
+        // Alpha passes draw to an offscreen layer positioned within their parent
+        let mut target = self.pass_targets[pass];
+        while let Target::Alpha(index) = target {
+            let alpha = &self.alpha_passes[index];
+            transform = transform.then(Transform::translation(Vec2::from(alpha.rect.pos)));
+            target = self.pass_targets[alpha.composite.pass()];
+        }
+        transform
     }
 
     #[inline]
@@ -413,14 +832,51 @@ impl<CW: CustomWindow> DrawImpl for DrawWindow<CW> {
     fn frame(&mut self, pass: PassId, outer: Quad, inner: Quad, col: Rgba) {
         self.shaded_square.frame(pass, outer, inner, col);
     }
+
+    #[inline]
+    fn rect_gradient(&mut self, pass: PassId, rect: Quad, gradient: &Gradient) {
+        self.gradient.rect(pass, rect, gradient);
+    }
+
+    #[inline]
+    fn frame_gradient(&mut self, pass: PassId, outer: Quad, inner: Quad, gradient: &Gradient) {
+        self.gradient.frame(pass, outer, inner, gradient);
+    }
+
+    #[inline]
+    fn as_rounded_impl(&mut self) -> Option<&mut dyn DrawRoundedImpl> {
+        Some(self)
+    }
+
+    #[inline]
+    fn as_shaded_impl(&mut self) -> Option<&mut dyn DrawShadedImpl> {
+        Some(self)
+    }
+
+    #[inline]
+    fn extension_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        Some(&mut self.callbacks)
+    }
 }
 
-impl<CW: CustomWindow> DrawRoundedImpl for DrawWindow<CW> {
+impl<CW: CustomWindow> DrawRoundedImpl for GpuWindow<CW> {
     #[inline]
     fn rounded_line(&mut self, pass: PassId, p1: Vec2, p2: Vec2, radius: f32, col: Rgba) {
         self.flat_round.line(pass, p1, p2, radius, col);
     }
 
+    #[inline]
+    fn polyline(
+        &mut self,
+        pass: PassId,
+        points: &[Vec2],
+        closed: bool,
+        stroke: &Stroke,
+        col: Rgba,
+    ) {
+        self.stroke.polyline(pass, points, closed, stroke, col);
+    }
+
     #[inline]
     fn circle(&mut self, pass: PassId, rect: Quad, inner_radius: f32, col: Rgba) {
         self.flat_round.circle(pass, rect, inner_radius, col);
@@ -440,9 +896,24 @@ impl<CW: CustomWindow> DrawRoundedImpl for DrawWindow<CW> {
     fn rounded_frame_2col(&mut self, pass: PassId, outer: Quad, inner: Quad, c1: Rgba, c2: Rgba) {
         self.round_2col.frame(pass, outer, inner, c1, c2);
     }
+
+    #[inline]
+    fn shadow(&mut self, pass: PassId, rect: Quad, radius: f32, blur: f32, col: Rgba) {
+        self.shadow.shadow(pass, rect, radius, blur, col);
+    }
+
+    fn blur(&mut self, pass: PassId, rect: Quad, blur: f32) {
+        let (clip, transform) = self.clip_regions[pass.pass()];
+        let quad = transform.map_quad(rect);
+        let (a, b) = (quad.a.round(), quad.b.round());
+        let rect = Rect::new(a.into(), (b - a).into());
+        if let Some(rect) = rect.intersection(&clip) {
+            self.blur.blur(pass.pass(), rect, blur * transform.scale);
+        }
+    }
 }
 
-impl<CW: CustomWindow> DrawShadedImpl for DrawWindow<CW> {
+impl<CW: CustomWindow> DrawShadedImpl for GpuWindow<CW> {
     #[inline]
     fn shaded_square(&mut self, pass: PassId, rect: Quad, norm: (f32, f32), col: Rgba) {
         self.shaded_square
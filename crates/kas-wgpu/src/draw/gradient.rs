@@ -0,0 +1,237 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Gradient fill pipeline
+
+use super::common;
+use crate::draw::ShaderManager;
+use kas::draw::{color::Rgba, Gradient, GradientShape, PassId};
+use kas::geom::{Quad, Vec2};
+use std::mem::size_of;
+
+/// Vertex
+///
+/// -   `screen_pos: Vec2` — screen coordinate
+/// -   `col1: Rgba` — colour at the start of the segment
+/// -   `col2: Rgba` — colour at the end of the segment
+/// -   `grad: Vec2` — gradient coordinate (offset is `grad.0` if linear or
+///     the length of `grad` if radial)
+/// -   `radial: f32` — non-zero if radial
+/// -   `range: [f32; 4]` — segment start and end offsets, then the range of
+///     offsets drawn by this segment
+///
+/// Gradients with more than two stops are drawn as one shape per segment.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Vertex(Vec2, Rgba, Rgba, Vec2, f32, [f32; 4]);
+unsafe impl bytemuck::Zeroable for Vertex {}
+unsafe impl bytemuck::Pod for Vertex {}
+
+pub type Window = common::Window<Vertex>;
+
+/// A pipeline for rendering gradient fills
+pub struct Pipeline {
+    render_pipeline: wgpu::RenderPipeline,
+}
+
+impl Pipeline {
+    /// Construct
+    pub fn new(
+        device: &wgpu::Device,
+        shaders: &ShaderManager,
+        bgl_common: &wgpu::BindGroupLayout,
+    ) -> Self {
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("gradient pipeline_layout"),
+            bind_group_layouts: &[bgl_common],
+            push_constant_ranges: &[],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("gradient render_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shaders.gradient,
+                entry_point: "vert_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x2,
+                        1 => Float32x4,
+                        2 => Float32x4,
+                        3 => Float32x2,
+                        4 => Float32,
+                        5 => Float32x4,
+                    ],
+                }],
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Cw,
+                cull_mode: None,
+                clamp_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: Default::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shaders.gradient,
+                entry_point: "frag_main",
+                targets: &[wgpu::ColorTargetState {
+                    format: super::RENDER_TEX_FORMAT,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            }),
+        });
+
+        Pipeline { render_pipeline }
+    }
+
+    /// Enqueue render commands
+    pub fn render<'a>(
+        &'a self,
+        window: &'a Window,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass<'a>,
+        bg_common: &'a wgpu::BindGroup,
+    ) {
+        window.render(pass, rpass, &self.render_pipeline, bg_common);
+    }
+}
+
+impl Window {
+    pub fn rect(&mut self, pass: PassId, rect: Quad, gradient: &Gradient) {
+        if !rect.a.lt(rect.b) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+
+        let aa = rect.a;
+        let bb = rect.b;
+        let ab = Vec2(aa.0, bb.1);
+        let ba = Vec2(bb.0, aa.1);
+
+        let map = GradientMap::new(rect, gradient);
+        map.for_segments(gradient, |v| {
+            #[rustfmt::skip]
+            self.add_vertices(pass.pass(), &[
+                v(aa), v(ba), v(ab),
+                v(ab), v(ba), v(bb),
+            ]);
+        });
+    }
+
+    pub fn frame(&mut self, pass: PassId, outer: Quad, inner: Quad, gradient: &Gradient) {
+        let aa = outer.a;
+        let bb = outer.b;
+        let mut cc = inner.a;
+        let mut dd = inner.b;
+
+        if !aa.lt(bb) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+        if !aa.le(cc) || !cc.le(bb) {
+            cc = aa;
+        }
+        if !aa.le(dd) || !dd.le(bb) {
+            dd = bb;
+        }
+        if !cc.le(dd) {
+            dd = cc;
+        }
+
+        let ab = Vec2(aa.0, bb.1);
+        let ba = Vec2(bb.0, aa.1);
+        let cd = Vec2(cc.0, dd.1);
+        let dc = Vec2(dd.0, cc.1);
+
+        let map = GradientMap::new(outer, gradient);
+        map.for_segments(gradient, |v| {
+            #[rustfmt::skip]
+            self.add_vertices(pass.pass(), &[
+                // top bar: ba - dc - cc - aa
+                v(ba), v(dc), v(aa),
+                v(aa), v(dc), v(cc),
+                // left bar: aa - cc - cd - ab
+                v(aa), v(cc), v(ab),
+                v(ab), v(cc), v(cd),
+                // bottom bar: ab - cd - dd - bb
+                v(ab), v(cd), v(bb),
+                v(bb), v(cd), v(dd),
+                // right bar: bb - dd - dc - ba
+                v(bb), v(dd), v(ba),
+                v(ba), v(dd), v(dc),
+            ]);
+        });
+    }
+}
+
+/// Maps screen coordinates to gradient coordinates
+struct GradientMap {
+    /// Origin (screen coordinates)
+    origin: Vec2,
+    /// Per-axis scale (radial) or direction scaled by inverse length² (linear)
+    factor: Vec2,
+    radial: bool,
+}
+
+impl GradientMap {
+    fn new(rect: Quad, gradient: &Gradient) -> Self {
+        let size = rect.size();
+        match gradient.shape {
+            GradientShape::Linear { start, end } => {
+                let origin = rect.a + start * size;
+                let d = (end - start) * size;
+                let len2 = d.sum_square();
+                let factor = if len2 > 0.0 { d / len2 } else { Vec2::ZERO };
+                GradientMap {
+                    origin,
+                    factor,
+                    radial: false,
+                }
+            }
+            GradientShape::Radial { centre, radius } => {
+                let origin = rect.a + centre * size;
+                let r = radius * size;
+                let inv = |x: f32| if x > 0.0 { 1.0 / x } else { 0.0 };
+                GradientMap {
+                    origin,
+                    factor: Vec2(inv(r.0), inv(r.1)),
+                    radial: true,
+                }
+            }
+        }
+    }
+
+    fn grad(&self, p: Vec2) -> Vec2 {
+        let p = p - self.origin;
+        if self.radial {
+            p * self.factor
+        } else {
+            Vec2((p * self.factor).sum(), 0.0)
+        }
+    }
+
+    /// Call `f` once per segment with a vertex constructor
+    fn for_segments(&self, gradient: &Gradient, mut f: impl FnMut(&dyn Fn(Vec2) -> Vertex)) {
+        const INF: f32 = 1e9;
+        let radial = if self.radial { 1.0 } else { 0.0 };
+        let stops = gradient.stops();
+        let n = stops.len().max(2) - 1;
+        for i in 0..n {
+            let (s0, c0) = stops[i];
+            let (s1, c1) = stops[(i + 1).min(stops.len() - 1)];
+            let lo = if i == 0 { -INF } else { s0 };
+            let hi = if i + 1 == n { INF } else { s1 };
+            let range = [s0, s1, lo, hi];
+            f(&|p| Vertex(p, c0, c1, self.grad(p), radial, range));
+        }
+    }
+}
@@ -11,8 +11,8 @@ use std::mem::size_of;
 use std::num::NonZeroU32;
 
 use super::{atlases, ShaderManager};
-use kas::cast::Conv;
-use kas::draw::{ImageError, ImageFormat, ImageId, PassId};
+use kas::cast::{Cast, Conv};
+use kas::draw::{ImageError, ImageFormat, ImageId, PassId, PassStats};
 use kas::geom::{Quad, Vec2};
 
 #[derive(Debug)]
@@ -61,23 +61,50 @@ impl Image {
     }
 }
 
+/// A layer: an image used as a render target
+///
+/// Unlike other images, each layer uses a dedicated texture.
+struct Layer {
+    id: ImageId,
+    size: (u32, u32),
+    tex: wgpu::Texture,
+    view: wgpu::TextureView,
+    bg: wgpu::BindGroup,
+}
+
 /// Screen and texture coordinates
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
 pub struct Instance {
+    pub(super) a: Vec2,
+    pub(super) b: Vec2,
+    pub(super) ta: Vec2,
+    pub(super) tb: Vec2,
+}
+unsafe impl bytemuck::Zeroable for Instance {}
+unsafe impl bytemuck::Pod for Instance {}
+
+/// Screen and texture coordinates plus opacity
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct LayerInstance {
     a: Vec2,
     b: Vec2,
     ta: Vec2,
     tb: Vec2,
+    alpha: f32,
 }
-unsafe impl bytemuck::Zeroable for Instance {}
-unsafe impl bytemuck::Pod for Instance {}
+unsafe impl bytemuck::Zeroable for LayerInstance {}
+unsafe impl bytemuck::Pod for LayerInstance {}
 
 /// Image loader and storage
 pub struct Images {
     atlas_pipe: atlases::Pipeline<Instance>,
+    layer_pipeline: wgpu::RenderPipeline,
     last_image_n: u32,
     images: HashMap<ImageId, Image>,
+    // Index is used as the atlas number when drawing; freed slots are re-used
+    layers: Vec<Option<Layer>>,
 }
 
 impl Images {
@@ -87,25 +114,28 @@ impl Images {
         shaders: &ShaderManager,
         bgl_common: &wgpu::BindGroupLayout,
     ) -> Self {
+        let buffers = [wgpu::VertexBufferLayout {
+            array_stride: size_of::<Instance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &wgpu::vertex_attr_array![
+                0 => Float32x2,
+                1 => Float32x2,
+                2 => Float32x2,
+                3 => Float32x2,
+            ],
+        }];
+        let vertex = wgpu::VertexState {
+            module: &shaders.vert_image,
+            entry_point: "main",
+            buffers: &buffers,
+        };
+
         let atlas_pipe = atlases::Pipeline::new(
             device,
             bgl_common,
             2048,
             wgpu::TextureFormat::Rgba8UnormSrgb,
-            wgpu::VertexState {
-                module: &shaders.vert_image,
-                entry_point: "main",
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: size_of::<Instance>() as wgpu::BufferAddress,
-                    step_mode: wgpu::VertexStepMode::Instance,
-                    attributes: &wgpu::vertex_attr_array![
-                        0 => Float32x2,
-                        1 => Float32x2,
-                        2 => Float32x2,
-                        3 => Float32x2,
-                    ],
-                }],
-            },
+            vertex,
             wgpu::FragmentState {
                 module: &shaders.frag_image,
                 entry_point: "main",
@@ -116,10 +146,45 @@ impl Images {
                 }],
             },
         );
+
+        // Layer contents are drawn with alpha blending onto a transparent
+        // background, thus the result has pre-multiplied alpha.
+        let layer_buffers = [wgpu::VertexBufferLayout {
+            array_stride: size_of::<LayerInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &wgpu::vertex_attr_array![
+                0 => Float32x2,
+                1 => Float32x2,
+                2 => Float32x2,
+                3 => Float32x2,
+                4 => Float32,
+            ],
+        }];
+        let layer_pipeline = atlas_pipe.new_render_pipeline(
+            device,
+            bgl_common,
+            wgpu::VertexState {
+                module: &shaders.layer,
+                entry_point: "vert_main",
+                buffers: &layer_buffers,
+            },
+            wgpu::FragmentState {
+                module: &shaders.layer,
+                entry_point: "frag_main",
+                targets: &[wgpu::ColorTargetState {
+                    format: super::RENDER_TEX_FORMAT,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            },
+        );
+
         Images {
             atlas_pipe,
+            layer_pipeline,
             last_image_n: 0,
             images: Default::default(),
+            layers: vec![],
         }
     }
 
@@ -144,6 +209,56 @@ impl Images {
         Ok(id)
     }
 
+    /// Allocate a layer
+    pub fn alloc_layer(
+        &mut self,
+        device: &wgpu::Device,
+        size: (u32, u32),
+    ) -> Result<ImageId, ImageError> {
+        let max_supported = wgpu::Limits::default().max_texture_dimension_2d;
+        if size.0 == 0 || size.1 == 0 || size.0 > max_supported || size.1 > max_supported {
+            return Err(ImageError::Allocation);
+        }
+
+        let id = self.next_image_id();
+        let tex = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("layer"),
+            size: wgpu::Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: super::RENDER_TEX_FORMAT,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_SRC,
+        });
+        let view = tex.create_view(&wgpu::TextureViewDescriptor::default());
+        let bg = self.atlas_pipe.new_bind_group(device, &view);
+        let layer = Some(Layer {
+            id,
+            size,
+            tex,
+            view,
+            bg,
+        });
+
+        match self.layers.iter().position(|layer| layer.is_none()) {
+            Some(index) => self.layers[index] = layer,
+            None => self.layers.push(layer),
+        }
+        Ok(id)
+    }
+
+    fn layer_index(&self, id: ImageId) -> Option<usize> {
+        self.layers
+            .iter()
+            .position(|layer| layer.as_ref().map(|layer| layer.id) == Some(id))
+    }
+
     /// Upload an image to the GPU
     pub fn upload(
         &mut self,
@@ -165,16 +280,22 @@ impl Images {
         }
     }
 
-    /// Free an image allocation
+    /// Free an image or layer allocation
     pub fn free(&mut self, id: ImageId) {
         if let Some(im) = self.images.remove(&id) {
             self.atlas_pipe.deallocate(im.atlas, im.alloc);
+        } else if let Some(index) = self.layer_index(id) {
+            self.layers[index] = None;
         }
     }
 
-    /// Query image size
+    /// Query image or layer size
     pub fn image_size(&self, id: ImageId) -> Option<(u32, u32)> {
-        self.images.get(&id).map(|im| im.size)
+        if let Some(im) = self.images.get(&id) {
+            return Some(im.size);
+        }
+        let index = self.layer_index(id)?;
+        self.layers[index].as_ref().map(|layer| layer.size)
     }
 
     /// Write to textures
@@ -193,6 +314,42 @@ impl Images {
         self.images.get(&id).map(|im| (im.atlas, im.tex_quad))
     }
 
+    /// Draw an image or layer
+    pub fn draw(&self, window: &mut Window, pass: PassId, id: ImageId, rect: Quad) {
+        if let Some((atlas, tex)) = self.get_im_atlas_coords(id) {
+            window.rect(pass, atlas, tex, rect);
+        } else {
+            self.draw_layer(window, pass, id, rect, 1.0);
+        }
+    }
+
+    /// Draw a layer with opacity `alpha`
+    pub fn draw_layer(
+        &self,
+        window: &mut Window,
+        pass: PassId,
+        id: ImageId,
+        rect: Quad,
+        alpha: f32,
+    ) {
+        if let Some(index) = self.layer_index(id) {
+            let tex = Quad::with_coords(Vec2::ZERO, Vec2::splat(1.0));
+            window.layer_rect(pass, index.cast(), tex, rect, alpha);
+        }
+    }
+
+    /// Get the render target view of a layer
+    pub fn layer_view(&self, id: ImageId) -> Option<&wgpu::TextureView> {
+        let index = self.layer_index(id)?;
+        self.layers[index].as_ref().map(|layer| &layer.view)
+    }
+
+    /// Get the texture of a layer
+    pub fn layer_texture(&self, id: ImageId) -> Option<&wgpu::Texture> {
+        let index = self.layer_index(id)?;
+        self.layers[index].as_ref().map(|layer| &layer.tex)
+    }
+
     /// Enqueue render commands
     pub fn render<'a>(
         &'a self,
@@ -203,12 +360,20 @@ impl Images {
     ) {
         self.atlas_pipe
             .render(&window.atlas, pass, rpass, bg_common);
+        window.layers.render_with_bind_groups(
+            &self.layer_pipeline,
+            pass,
+            rpass,
+            bg_common,
+            |index| self.layers[index].as_ref().map(|layer| &layer.bg),
+        );
     }
 }
 
 #[derive(Debug, Default)]
 pub struct Window {
     atlas: atlases::Window<Instance>,
+    layers: atlases::Window<LayerInstance>,
 }
 
 impl Window {
@@ -220,6 +385,13 @@ impl Window {
         encoder: &mut wgpu::CommandEncoder,
     ) {
         self.atlas.write_buffers(device, staging_belt, encoder);
+        self.layers.write_buffers(device, staging_belt, encoder);
+    }
+
+    /// Add statistics of a pass to `stats`
+    pub fn add_stats(&self, pass: usize, stats: &mut PassStats) {
+        self.atlas.add_stats(pass, stats);
+        self.layers.add_stats(pass, stats);
     }
 
     /// Add a rectangle to the buffer
@@ -237,4 +409,20 @@ impl Window {
         };
         self.atlas.rect(pass, atlas, instance);
     }
+
+    /// Add a rectangle drawing a layer with opacity `alpha` to the buffer
+    pub fn layer_rect(&mut self, pass: PassId, layer: u32, tex: Quad, rect: Quad, alpha: f32) {
+        if !rect.a.lt(rect.b) {
+            return;
+        }
+
+        let instance = LayerInstance {
+            a: rect.a,
+            b: rect.b,
+            ta: tex.a,
+            tb: tex.b,
+            alpha,
+        };
+        self.layers.rect(pass, layer, instance);
+    }
 }
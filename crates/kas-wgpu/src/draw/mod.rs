@@ -8,22 +8,36 @@
 //! Extensions to the API of [`kas::draw`], plus some utility types.
 
 mod atlases;
+mod backend;
+mod blur;
+mod callback;
 mod common;
 mod custom;
+mod custom_registry;
 mod draw_pipe;
 mod flat_round;
+mod gradient;
 mod images;
 mod round_2col;
 mod shaded_round;
 mod shaded_square;
 mod shaders;
+mod shadow;
+#[cfg(feature = "software")]
+mod soft;
+mod stats;
+mod stroke;
 mod text_pipe;
 
-use kas::geom::{Offset, Rect};
+use kas::draw::{ImageId, PassId};
+use kas::geom::{Rect, Size, Transform};
 use shaders::ShaderManager;
 use wgpu::TextureFormat;
 
+pub use callback::{draw_callback, RenderContext};
 pub use custom::{CustomPipe, CustomPipeBuilder, CustomWindow, DrawCustom};
+pub use custom_registry::{CustomPipeKey, CustomPipeRegistry, CustomPipeRegistryBuilder};
+pub use custom_registry::{RegistryParam, RegistryPipe, RegistryWindow};
 
 /// Output format
 ///
@@ -33,33 +47,95 @@ pub(crate) const RENDER_TEX_FORMAT: TextureFormat = TextureFormat::Bgra8UnormSrg
 
 type Scale = [f32; 4];
 
-/// Shared pipeline data
-pub struct DrawPipe<C> {
+/// Shared draw state
+///
+/// This uses the GPU renderer, or where no graphics adapter is available, the
+/// software renderer (see [`crate::options::Software`]).
+pub struct DrawPipe<C>(Pipe<C>);
+
+enum Pipe<C> {
+    Gpu(GpuPipe<C>),
+    #[cfg(feature = "software")]
+    Soft(soft::SoftPipe),
+}
+
+/// Per-window draw state
+///
+/// The renderer used matches that of the [`DrawPipe`] constructing this.
+pub struct DrawWindow<CW: CustomWindow>(Window<CW>);
+
+enum Window<CW: CustomWindow> {
+    Gpu(GpuWindow<CW>),
+    #[cfg(feature = "software")]
+    Soft(soft::SoftWindow),
+}
+
+/// Shared pipeline data of the GPU renderer
+pub(crate) struct GpuPipe<C> {
     pub(crate) device: wgpu::Device,
-    queue: wgpu::Queue,
+    pub(crate) queue: wgpu::Queue,
     local_pool: futures::executor::LocalPool,
     staging_belt: wgpu::util::StagingBelt,
     bgl_common: wgpu::BindGroupLayout,
     light_norm_buf: wgpu::Buffer,
     bg_common: Vec<(wgpu::Buffer, wgpu::BindGroup)>,
-    images: images::Images,
+    shaders: ShaderManager,
+    // Pipelines not required by all themes are constructed on first use
+    images: Option<images::Images>,
     shaded_square: shaded_square::Pipeline,
-    shaded_round: shaded_round::Pipeline,
+    shaded_round: Option<shaded_round::Pipeline>,
     flat_round: flat_round::Pipeline,
-    round_2col: round_2col::Pipeline,
+    round_2col: Option<round_2col::Pipeline>,
+    shadow: Option<shadow::Pipeline>,
+    gradient: Option<gradient::Pipeline>,
+    stroke: Option<stroke::Pipeline>,
+    blur: Option<blur::Pipeline>,
     custom: C,
     pub(crate) text: text_pipe::Pipeline,
+    // Timestamp period (ns), if timestamp queries are enabled
+    timestamp_period: Option<f32>,
+}
+
+/// Render target of a pass
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Target {
+    Window,
+    /// A layer allocated by the user
+    Layer(ImageId),
+    /// The offscreen layer of an alpha pass (index into `alpha_passes`)
+    Alpha(usize),
+}
+
+/// A pass drawn to an offscreen layer, then composited with opacity
+struct AlphaPass {
+    /// Pass into which the layer is composited
+    composite: PassId,
+    /// Region covered, in target coordinates of the composite pass
+    rect: Rect,
+    alpha: f32,
 }
 
-/// Per-window pipeline data
-pub struct DrawWindow<CW: CustomWindow> {
+/// Per-window pipeline data of the GPU renderer
+pub(crate) struct GpuWindow<CW: CustomWindow> {
     scale: Scale,
-    clip_regions: Vec<(Rect, Offset)>,
+    // Clip rect (in target coordinates) and transform from pass to target
+    clip_regions: Vec<(Rect, Transform)>,
+    // For each clip region, the render target
+    pass_targets: Vec<Target>,
+    alpha_passes: Vec<AlphaPass>,
+    // Offscreen layers used by alpha passes (by index), re-used across frames
+    alpha_layers: Vec<Option<(ImageId, Size)>>,
     images: images::Window,
     shaded_square: shaded_square::Window,
     shaded_round: shaded_round::Window,
     flat_round: flat_round::Window,
     round_2col: round_2col::Window,
+    shadow: shadow::Window,
+    gradient: gradient::Window,
+    stroke: stroke::Window,
+    blur: blur::Window,
     custom: CW,
+    callbacks: callback::Window,
     pub(crate) text: text_pipe::Window,
+    stats: Option<stats::Stats>,
 }
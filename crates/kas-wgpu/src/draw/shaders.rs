@@ -5,7 +5,7 @@
 
 //! Shader management
 
-use wgpu::{include_spirv, ShaderModule};
+use wgpu::{include_spirv, include_wgsl, ShaderModule};
 
 /// Shader manager
 pub struct ShaderManager {
@@ -21,6 +21,12 @@ pub struct ShaderManager {
     pub frag_shaded_round: ShaderModule,
     pub frag_image: ShaderModule,
     pub frag_glyph: ShaderModule,
+    pub frag_glyph_lcd: ShaderModule,
+    pub layer: ShaderModule,
+    pub shadow: ShaderModule,
+    pub gradient: ShaderModule,
+    pub stroke: ShaderModule,
+    pub blur: ShaderModule,
 }
 
 macro_rules! create {
@@ -44,6 +50,12 @@ impl ShaderManager {
         let frag_shaded_round = create!(device, "shaders/shaded_round.frag.spv");
         let frag_image = create!(device, "shaders/image.frag.spv");
         let frag_glyph = create!(device, "shaders/glyph.frag.spv");
+        let frag_glyph_lcd = device.create_shader_module(&include_wgsl!("shaders/glyph_lcd.wgsl"));
+        let layer = device.create_shader_module(&include_wgsl!("shaders/layer.wgsl"));
+        let shadow = device.create_shader_module(&include_wgsl!("shaders/shadow.wgsl"));
+        let gradient = device.create_shader_module(&include_wgsl!("shaders/gradient.wgsl"));
+        let stroke = device.create_shader_module(&include_wgsl!("shaders/stroke.wgsl"));
+        let blur = device.create_shader_module(&include_wgsl!("shaders/blur.wgsl"));
 
         ShaderManager {
             vert_image,
@@ -58,6 +70,12 @@ impl ShaderManager {
             frag_shaded_round,
             frag_image,
             frag_glyph,
+            frag_glyph_lcd,
+            layer,
+            shadow,
+            gradient,
+            stroke,
+            blur,
         }
     }
 }
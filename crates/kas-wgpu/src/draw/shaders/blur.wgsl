@@ -0,0 +1,72 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+// Shaders for background blur
+//
+// A separable Gaussian blur: each instance draws a quad, reading texels from
+// the source texture along `dir`. Positions are given in normalised device
+// coordinates; fragment positions minus `origin` give source texel
+// coordinates, which are clamped to `limit`.
+
+struct VertexOutput {
+    [[location(0)]] origin: vec2<f32>;
+    [[location(1)]] limit: vec2<f32>;
+    [[location(2)]] dir: vec2<f32>;
+    [[location(3)]] sigma: f32;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vert_main(
+    [[builtin(vertex_index)]] index: u32,
+    [[location(0)]] pos_a: vec2<f32>,
+    [[location(1)]] pos_b: vec2<f32>,
+    [[location(2)]] origin: vec2<f32>,
+    [[location(3)]] limit: vec2<f32>,
+    [[location(4)]] dir: vec2<f32>,
+    [[location(5)]] sigma: f32,
+) -> VertexOutput {
+    // Vertices are: a, (b.x, a.y), (a.x, b.y), b
+    let use_bx = (index & 1u) != 0u;
+    let use_by = (index & 2u) != 0u;
+    let pos = vec2<f32>(select(pos_a.x, pos_b.x, use_bx), select(pos_a.y, pos_b.y, use_by));
+
+    var output: VertexOutput;
+    output.origin = origin;
+    output.limit = limit;
+    output.dir = dir;
+    output.sigma = sigma;
+    output.position = vec4<f32>(pos, 0.0, 1.0);
+    return output;
+}
+
+[[group(0), binding(0)]]
+var tex: texture_2d<f32>;
+
+[[stage(fragment)]]
+fn frag_main(input: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let pos = input.position.xy - input.origin;
+    let max_pos = input.limit - vec2<f32>(1.0, 1.0);
+    let radius = i32(ceil(3.0 * input.sigma));
+    let k = -0.5 / max(input.sigma * input.sigma, 0.0001);
+
+    var sum = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    var weight = 0.0;
+    var i = -radius;
+    loop {
+        if (i > radius) {
+            break;
+        }
+        let p = clamp(pos + input.dir * f32(i), vec2<f32>(0.0, 0.0), max_pos);
+        let w = exp(k * f32(i * i));
+        sum = sum + w * textureLoad(tex, vec2<i32>(p), 0);
+        weight = weight + w;
+
+        continuing {
+            i = i + 1;
+        }
+    }
+    return sum / weight;
+}
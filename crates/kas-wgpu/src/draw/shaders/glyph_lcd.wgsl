@@ -0,0 +1,23 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+// Fragment shader for sub-pixel (LCD) glyphs
+//
+// The texture holds per-channel coverage. Output is coverage multiplied by
+// the input colour; blending is configured by the pipeline.
+
+[[group(1), binding(0)]]
+var tex: texture_2d<f32>;
+[[group(1), binding(1)]]
+var tex_sampler: sampler;
+
+[[stage(fragment)]]
+fn main(
+    [[location(0)]] tex_coord: vec2<f32>,
+    [[location(1)]] col: vec4<f32>,
+) -> [[location(0)]] vec4<f32> {
+    let cov = textureSample(tex, tex_sampler, tex_coord);
+    return vec4<f32>(cov.rgb * col.rgb * col.a, cov.a * col.a);
+}
@@ -0,0 +1,64 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+// Shaders for gradient fills
+//
+// Each segment between two colour stops is drawn as a separate shape; the
+// fragment shader discards fragments whose offset is outside the segment's
+// range, then interpolates between the segment's two colours.
+
+[[block]]
+struct VertexCommon {
+    offset: vec2<f32>;
+    scale: vec2<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> global: VertexCommon;
+
+struct VertexOutput {
+    [[location(0)]] col1: vec4<f32>;
+    [[location(1)]] col2: vec4<f32>;
+    [[location(2)]] grad: vec2<f32>;
+    [[location(3)]] radial: f32;
+    [[location(4)]] range: vec4<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vert_main(
+    [[location(0)]] screen_pos: vec2<f32>,
+    [[location(1)]] col1: vec4<f32>,
+    [[location(2)]] col2: vec4<f32>,
+    [[location(3)]] grad: vec2<f32>,
+    [[location(4)]] radial: f32,
+    [[location(5)]] range: vec4<f32>,
+) -> VertexOutput {
+    var output: VertexOutput;
+    output.col1 = col1;
+    output.col2 = col2;
+    output.grad = grad;
+    output.radial = radial;
+    output.range = range;
+    output.position = vec4<f32>(global.scale * (screen_pos + global.offset), 0.0, 1.0);
+    return output;
+}
+
+[[stage(fragment)]]
+fn frag_main(input: VertexOutput) -> [[location(0)]] vec4<f32> {
+    var s: f32 = input.grad.x;
+    if (input.radial != 0.0) {
+        s = length(input.grad);
+    }
+
+    // range: segment start, segment end, lower and upper bound drawn
+    if (s < input.range.z || s >= input.range.w) {
+        discard;
+    }
+
+    let len = max(input.range.y - input.range.x, 0.000001);
+    let f = clamp((s - input.range.x) / len, 0.0, 1.0);
+    return mix(input.col1, input.col2, vec4<f32>(f, f, f, f));
+}
@@ -0,0 +1,55 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+// Shaders for compositing layers
+//
+// As image.vert and image.frag, but with per-instance opacity. Layer contents
+// have pre-multiplied alpha, thus all channels are scaled by opacity.
+
+[[block]]
+struct VertexCommon {
+    offset: vec2<f32>;
+    scale: vec2<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> global: VertexCommon;
+
+struct VertexOutput {
+    [[location(0)]] tex_pos: vec2<f32>;
+    [[location(1)]] alpha: f32;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vert_main(
+    [[builtin(vertex_index)]] index: u32,
+    [[location(0)]] pos_a: vec2<f32>,
+    [[location(1)]] pos_b: vec2<f32>,
+    [[location(2)]] tex_a: vec2<f32>,
+    [[location(3)]] tex_b: vec2<f32>,
+    [[location(4)]] alpha: f32,
+) -> VertexOutput {
+    // Vertices are: a, (b.x, a.y), (a.x, b.y), b
+    let use_bx = (index & 1u) != 0u;
+    let use_by = (index & 2u) != 0u;
+    let pos = vec2<f32>(select(pos_a.x, pos_b.x, use_bx), select(pos_a.y, pos_b.y, use_by));
+
+    var output: VertexOutput;
+    output.tex_pos = vec2<f32>(select(tex_a.x, tex_b.x, use_bx), select(tex_a.y, tex_b.y, use_by));
+    output.alpha = alpha;
+    output.position = vec4<f32>(global.scale * (pos + global.offset), 0.0, 1.0);
+    return output;
+}
+
+[[group(1), binding(0)]]
+var tex: texture_2d<f32>;
+[[group(1), binding(1)]]
+var tex_sampler: sampler;
+
+[[stage(fragment)]]
+fn frag_main(input: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return textureSample(tex, tex_sampler, input.tex_pos) * input.alpha;
+}
@@ -0,0 +1,67 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+// Shaders for drop shadows
+//
+// A shadow is a rect with rounded corners convolved with a Gaussian kernel.
+// We approximate this using the signed distance to the rounded rect and the
+// error function (exact for straight edges, close enough at corners).
+
+[[block]]
+struct VertexCommon {
+    offset: vec2<f32>;
+    scale: vec2<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> global: VertexCommon;
+
+struct VertexOutput {
+    [[location(0)]] col: vec4<f32>;
+    [[location(1)]] pos: vec2<f32>;
+    [[location(2)]] half_size: vec2<f32>;
+    [[location(3)]] params: vec2<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vert_main(
+    [[location(0)]] screen_pos: vec2<f32>,
+    [[location(1)]] col: vec4<f32>,
+    [[location(2)]] pos: vec2<f32>,
+    [[location(3)]] half_size: vec2<f32>,
+    [[location(4)]] params: vec2<f32>,
+) -> VertexOutput {
+    var output: VertexOutput;
+    output.col = col;
+    output.pos = pos;
+    output.half_size = half_size;
+    output.params = params;
+    output.position = vec4<f32>(global.scale * (screen_pos + global.offset), 0.0, 1.0);
+    return output;
+}
+
+// Approximation of erf (Abramowitz and Stegun 7.1.27; max error 5e-4)
+fn erf(x: f32) -> f32 {
+    let a = abs(x);
+    let t = 1.0 + a * (0.278393 + a * (0.230389 + a * (0.000972 + a * 0.078108)));
+    let t2 = t * t;
+    return sign(x) * (1.0 - 1.0 / (t2 * t2));
+}
+
+[[stage(fragment)]]
+fn frag_main(input: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // params: corner radius, standard deviation of blur
+    let radius = input.params.x;
+    let sigma = max(input.params.y, 0.001);
+
+    // Signed distance to the rounded rect
+    let q = abs(input.pos) - input.half_size + vec2<f32>(radius, radius);
+    let outside = length(max(q, vec2<f32>(0.0, 0.0)));
+    let dist = outside + min(max(q.x, q.y), 0.0) - radius;
+
+    let alpha = 0.5 - 0.5 * erf(dist / (sigma * 1.4142135));
+    return vec4<f32>(input.col.rgb, input.col.a * alpha);
+}
@@ -0,0 +1,70 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+// Shaders for stroked paths
+//
+// Each piece of a path is a straight line with a cap at each end. We compute
+// the signed distance to the piece's outline in local coordinates (along and
+// across the piece) and use this for anti-aliasing.
+
+[[block]]
+struct VertexCommon {
+    offset: vec2<f32>;
+    scale: vec2<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> global: VertexCommon;
+
+struct VertexOutput {
+    [[location(0)]] col: vec4<f32>;
+    [[location(1)]] pos: vec2<f32>;
+    [[location(2)]] params: vec4<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vert_main(
+    [[location(0)]] screen_pos: vec2<f32>,
+    [[location(1)]] col: vec4<f32>,
+    [[location(2)]] pos: vec2<f32>,
+    [[location(3)]] params: vec4<f32>,
+) -> VertexOutput {
+    var output: VertexOutput;
+    output.col = col;
+    output.pos = pos;
+    output.params = params;
+    output.position = vec4<f32>(global.scale * (screen_pos + global.offset), 0.0, 1.0);
+    return output;
+}
+
+[[stage(fragment)]]
+fn frag_main(input: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // params: length, half-width, start cap, end cap
+    // caps: 0 = butt, 1 = square, 2 = round
+    let len = input.params.x;
+    let hw = input.params.y;
+    let u = input.pos.x;
+    let v = abs(input.pos.y);
+
+    // Signed distance beyond the nearest end point (negative inside)
+    let e = max(-u, u - len);
+    var cap: f32 = input.params.w;
+    if (u < 0.5 * len) {
+        cap = input.params.z;
+    }
+
+    var dist: f32;
+    if (cap > 1.5) {
+        dist = length(vec2<f32>(max(e, 0.0), v)) - hw;
+    } else if (cap > 0.5) {
+        dist = max(e - hw, v - hw);
+    } else {
+        dist = max(e, v - hw);
+    }
+
+    let alpha = clamp(0.5 - dist, 0.0, 1.0);
+    return vec4<f32>(input.col.rgb, input.col.a * alpha);
+}
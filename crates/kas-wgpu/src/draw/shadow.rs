@@ -0,0 +1,130 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Drop shadow pipeline
+
+use super::common;
+use crate::draw::ShaderManager;
+use kas::draw::{color::Rgba, PassId};
+use kas::geom::{Quad, Vec2};
+use std::mem::size_of;
+
+/// Vertex
+///
+/// -   `screen_pos: Vec2` — screen coordinate
+/// -   `col: Rgba`
+/// -   `pos: Vec2` — coordinate relative to the centre of the shadow's rect
+/// -   `half_size: Vec2` — half the size of the shadow's rect
+/// -   `params: Vec2` — corner radius and standard deviation of blur
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Vertex(Vec2, Rgba, Vec2, Vec2, Vec2);
+unsafe impl bytemuck::Zeroable for Vertex {}
+unsafe impl bytemuck::Pod for Vertex {}
+
+pub type Window = common::Window<Vertex>;
+
+/// A pipeline for rendering drop shadows
+pub struct Pipeline {
+    render_pipeline: wgpu::RenderPipeline,
+}
+
+impl Pipeline {
+    /// Construct
+    pub fn new(
+        device: &wgpu::Device,
+        shaders: &ShaderManager,
+        bgl_common: &wgpu::BindGroupLayout,
+    ) -> Self {
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("shadow pipeline_layout"),
+            bind_group_layouts: &[bgl_common],
+            push_constant_ranges: &[],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("shadow render_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shaders.shadow,
+                entry_point: "vert_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x2,
+                        1 => Float32x4,
+                        2 => Float32x2,
+                        3 => Float32x2,
+                        4 => Float32x2,
+                    ],
+                }],
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Cw,
+                cull_mode: None,
+                clamp_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: Default::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shaders.shadow,
+                entry_point: "frag_main",
+                targets: &[wgpu::ColorTargetState {
+                    format: super::RENDER_TEX_FORMAT,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            }),
+        });
+
+        Pipeline { render_pipeline }
+    }
+
+    /// Enqueue render commands
+    pub fn render<'a>(
+        &'a self,
+        window: &'a Window,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass<'a>,
+        bg_common: &'a wgpu::BindGroup,
+    ) {
+        window.render(pass, rpass, &self.render_pipeline, bg_common);
+    }
+}
+
+impl Window {
+    pub fn shadow(&mut self, pass: PassId, rect: Quad, radius: f32, blur: f32, col: Rgba) {
+        let size = rect.size();
+        if !(size.0 > 0.0 && size.1 > 0.0) || col.a == 0.0 {
+            // zero / negative size or transparent: nothing to draw
+            return;
+        }
+
+        let half = size * 0.5;
+        let radius = radius.min(half.min_comp()).max(0.0);
+        let blur = blur.max(0.0);
+        let params = Vec2(radius, blur);
+        let mid = (rect.a + rect.b) * 0.5;
+
+        // The shadow is negligible beyond three standard deviations
+        let outer = rect.grow(3.0 * blur);
+        let vertex = |p: Vec2| Vertex(p, col, p - mid, half, params);
+        let aa = vertex(outer.a);
+        let ab = vertex(Vec2(outer.a.0, outer.b.1));
+        let ba = vertex(Vec2(outer.b.0, outer.a.1));
+        let bb = vertex(outer.b);
+
+        #[rustfmt::skip]
+        self.add_vertices(pass.pass(), &[
+            aa, ba, ab,
+            ab, ba, bb,
+        ]);
+    }
+}
@@ -0,0 +1,895 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Software (CPU) renderer
+//!
+//! This is used where no graphics adapter is available (see
+//! [`crate::options::Software`]). Draw commands are recorded per window, then
+//! rastered onto a [`Pixmap`] via `tiny-skia`. Glyphs are rastered via
+//! `kas_text` and cached as alpha masks.
+//!
+//! Compared to the GPU renderer, shading is flat, blur is not supported,
+//! shadows are approximated and layers, custom pipes and draw callbacks are
+//! unavailable.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use kas::cast::{Cast, CastFloat, Conv};
+use kas::draw::color::{Rgba, Rgba8Srgb};
+use kas::draw::*;
+use kas::geom::{Coord, Offset, Quad, Rect, Size, Transform, Vec2};
+use kas::text::fonts::FaceId;
+use kas::text::{Effect, Glyph, TextDisplay};
+use kas_text::raster::{raster, Config, SpriteDescriptor};
+use kas_theme::RasterConfig;
+use tiny_skia::{ClipMask, Color, ColorU8, FillRule, FilterQuality, GradientStop, Paint, Path};
+use tiny_skia::{LinearGradient, RadialGradient, Shader, SpreadMode, StrokeDash};
+use tiny_skia::{PathBuilder, Pattern, Pixmap, PixmapPaint, Point, PremultipliedColorU8};
+
+/// Cubic Bézier control distance approximating a quarter circle
+const KAPPA: f32 = 0.552_284_8;
+
+/// A rastered glyph (alpha mask)
+struct Sprite {
+    size: (u32, u32),
+    data: Vec<u8>,
+    offset: Vec2,
+}
+
+/// Shared draw state of the software renderer
+pub(crate) struct SoftPipe {
+    config: Config,
+    glyphs: HashMap<SpriteDescriptor, Option<Rc<Sprite>>>,
+    images: HashMap<ImageId, Rc<Pixmap>>,
+    next_image_id: u32,
+}
+
+impl SoftPipe {
+    /// Construct
+    pub fn new(config: &RasterConfig) -> Self {
+        SoftPipe {
+            config: Config::new(
+                config.mode,
+                config.scale_steps,
+                config.subpixel_threshold,
+                config.subpixel_steps,
+            ),
+            glyphs: Default::default(),
+            images: Default::default(),
+            next_image_id: 0,
+        }
+    }
+
+    /// Construct per-window state
+    pub fn new_window(&self) -> SoftWindow {
+        SoftWindow::default()
+    }
+
+    /// Process window resize
+    pub fn resize(&self, window: &mut SoftWindow, size: Size) {
+        window.size = size;
+        window.passes[0].rect = Rect::new(Coord::ZERO, size);
+    }
+
+    /// Get a rendered sprite
+    ///
+    /// This returns `None` if there's nothing to render.
+    fn get_glyph(&mut self, face: FaceId, dpem: f32, glyph: Glyph) -> Option<Rc<Sprite>> {
+        let desc = SpriteDescriptor::new(&self.config, face, glyph, dpem);
+        if let Some(opt_sprite) = self.glyphs.get(&desc).cloned() {
+            opt_sprite
+        } else {
+            self.raster_glyph(desc)
+        }
+    }
+
+    fn raster_glyph(&mut self, desc: SpriteDescriptor) -> Option<Rc<Sprite>> {
+        let sprite = match raster(&self.config, desc).filter(|rs| rs.size.0 * rs.size.1 > 0) {
+            Some(rs) => Some(Rc::new(Sprite {
+                size: rs.size,
+                data: rs.data,
+                offset: Vec2(rs.offset.0.cast(), rs.offset.1.cast()),
+            })),
+            None => {
+                // This comes up a lot and is usually harmless
+                log::debug!(
+                    "Failed to raster glyph {:?} of face {:?}",
+                    desc.glyph(),
+                    desc.face()
+                );
+                None
+            }
+        };
+
+        self.glyphs.insert(desc, sprite.clone());
+        sprite
+    }
+
+    fn text(
+        &mut self,
+        draw: &mut SoftWindow,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        col: Rgba,
+    ) {
+        let pos = pos.round();
+        text.glyphs(|face, dpem, glyph| {
+            if let Some(sprite) = self.get_glyph(face, dpem, glyph) {
+                let pos = pos + Vec2::from(glyph.position).floor() + sprite.offset;
+                draw.push_text(pass, Op::Glyph(sprite, pos, col));
+            }
+        });
+    }
+}
+
+impl DrawSharedImpl for SoftPipe {
+    type Draw = SoftWindow;
+
+    fn image_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
+        let pixmap = Pixmap::new(size.0, size.1).ok_or(ImageError::Allocation)?;
+        self.next_image_id += 1;
+        let id = ImageId::try_new(self.next_image_id).expect("exhausted image IDs");
+        self.images.insert(id, Rc::new(pixmap));
+        Ok(id)
+    }
+
+    fn image_upload(&mut self, id: ImageId, data: &[u8], format: ImageFormat) {
+        let image = match self.images.get_mut(&id) {
+            Some(image) => image,
+            None => return,
+        };
+        // Frames already recorded keep the old contents
+        let pixmap = Rc::make_mut(image);
+        match format {
+            ImageFormat::Rgba8 => {
+                // tiny-skia uses pre-multiplied RGBA
+                let pixels = pixmap.pixels_mut();
+                for (d, s) in pixels.iter_mut().zip(data.chunks_exact(4)) {
+                    *d = ColorU8::from_rgba(s[0], s[1], s[2], s[3]).premultiply();
+                }
+            }
+        }
+    }
+
+    fn image_free(&mut self, id: ImageId) {
+        self.images.remove(&id);
+    }
+
+    fn image_size(&self, id: ImageId) -> Option<(u32, u32)> {
+        self.images.get(&id).map(|p| (p.width(), p.height()))
+    }
+
+    fn draw_image(&self, draw: &mut Self::Draw, pass: PassId, id: ImageId, rect: Quad) {
+        if let Some(pixmap) = self.images.get(&id) {
+            draw.push(pass, Op::Image(pixmap.clone(), rect));
+        }
+    }
+
+    fn draw_text(
+        &mut self,
+        draw: &mut Self::Draw,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        col: Rgba,
+    ) {
+        self.text(draw, pass, pos, text, col);
+    }
+
+    fn draw_text_col_effects(
+        &mut self,
+        draw: &mut Self::Draw,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        col: Rgba,
+        effects: &[Effect<()>],
+    ) {
+        let pos = pos.round();
+        let mut ops = vec![];
+        let for_glyph = |face: FaceId, dpem: f32, glyph: Glyph, _: usize, _: ()| {
+            if let Some(sprite) = self.get_glyph(face, dpem, glyph) {
+                let pos = pos + Vec2::from(glyph.position).floor() + sprite.offset;
+                ops.push(Op::Glyph(sprite, pos, col));
+            }
+        };
+        let mut rects = vec![];
+        let for_rect = |x1, x2, y: f32, h: f32, _, _| {
+            let y = y.ceil();
+            let y2 = y + h.ceil();
+            rects.push(Quad::with_coords(pos + Vec2(x1, y), pos + Vec2(x2, y2)));
+        };
+        text.glyphs_with_effects(effects, (), for_glyph, for_rect);
+
+        for op in ops {
+            draw.push_text(pass, op);
+        }
+        for rect in rects {
+            draw.push_text(pass, Op::Rect(rect, col));
+        }
+    }
+
+    fn draw_text_effects(
+        &mut self,
+        draw: &mut Self::Draw,
+        pass: PassId,
+        pos: Vec2,
+        text: &TextDisplay,
+        effects: &[Effect<Rgba>],
+    ) {
+        let pos = pos.round();
+        let mut ops = vec![];
+        let for_glyph = |face: FaceId, dpem: f32, glyph: Glyph, _, col: Rgba| {
+            if let Some(sprite) = self.get_glyph(face, dpem, glyph) {
+                let pos = pos + Vec2::from(glyph.position).floor() + sprite.offset;
+                ops.push(Op::Glyph(sprite, pos, col));
+            }
+        };
+        let mut rects = vec![];
+        let for_rect = |x1, x2, y: f32, h: f32, _, col: Rgba| {
+            let y = y.ceil();
+            let y2 = y + h.ceil();
+            let quad = Quad::with_coords(pos + Vec2(x1, y), pos + Vec2(x2, y2));
+            rects.push((quad, col));
+        };
+        text.glyphs_with_effects(effects, Rgba::BLACK, for_glyph, for_rect);
+
+        for op in ops {
+            draw.push_text(pass, op);
+        }
+        for (rect, col) in rects {
+            draw.push_text(pass, Op::Rect(rect, col));
+        }
+    }
+}
+
+/// A recorded draw operation
+enum Op {
+    Rect(Quad, Rgba),
+    Frame(Quad, Quad, Rgba),
+    Gradient(Quad, Option<Quad>, Gradient),
+    Line(Vec2, Vec2, f32, Rgba),
+    Polyline(Vec<Vec2>, bool, Stroke, Rgba),
+    Circle(Quad, f32, Rgba),
+    Circle2Col(Quad, Rgba, Rgba),
+    RoundedFrame(Quad, Quad, f32, Rgba),
+    Shadow(Quad, f32, f32, Rgba),
+    Image(Rc<Pixmap>, Quad),
+    Glyph(Rc<Sprite>, Vec2, Rgba),
+}
+
+/// Draw operations for a clip region
+///
+/// Operations are drawn in three layers: `back` (used for shadows), `main`,
+/// then `text`.
+#[derive(Default)]
+struct Pass {
+    // Clip rect (in window coordinates)
+    rect: Rect,
+    // Transform from pass coordinates to window coordinates
+    transform: Transform,
+    // Opacity (if not opaque)
+    alpha: Option<f32>,
+    back: Vec<Op>,
+    main: Vec<Op>,
+    text: Vec<Op>,
+}
+
+/// Per-window draw state of the software renderer
+pub(crate) struct SoftWindow {
+    size: Size,
+    passes: Vec<Pass>,
+}
+
+impl Default for SoftWindow {
+    fn default() -> Self {
+        SoftWindow {
+            size: Size::ZERO,
+            passes: vec![Pass::default()],
+        }
+    }
+}
+
+impl SoftWindow {
+    fn push(&mut self, pass: PassId, op: Op) {
+        self.passes[pass.pass()].main.push(op);
+    }
+
+    fn push_text(&mut self, pass: PassId, op: Op) {
+        self.passes[pass.pass()].text.push(op);
+    }
+
+    /// Raster recorded operations onto `pixmap`, then clear the recording
+    ///
+    /// If `clear_color` is `None`, the existing contents are drawn over.
+    pub fn render(&mut self, pixmap: &mut Pixmap, clear_color: Option<Rgba>) {
+        if let Some(col) = clear_color {
+            pixmap.fill(color(col));
+        }
+
+        let (width, height) = (pixmap.width(), pixmap.height());
+        let full = Rect::new(Coord::ZERO, Size(width.cast(), height.cast()));
+        let mut layer: Option<Pixmap> = None;
+        for pass in &self.passes {
+            let rect = match pass.rect.intersection(&full) {
+                Some(rect) if rect.size.0 > 0 && rect.size.1 > 0 => rect,
+                _ => continue,
+            };
+            let mut mask = None;
+            if rect != full {
+                let mut clip = ClipMask::new();
+                let path = PathBuilder::from_rect(to_rect(Quad::from(rect)));
+                clip.set_path(width, height, &path, FillRule::Winding, false);
+                mask = Some(clip);
+            }
+            let (s, t) = (pass.transform.scale, pass.transform.translate);
+            let transform = tiny_skia::Transform::from_row(s, 0.0, 0.0, s, t.0, t.1);
+
+            // NOTE: passes are composited individually, thus contents of
+            // derived passes may show through those of the parent pass.
+            let target = match pass.alpha {
+                None => &mut *pixmap,
+                Some(_) => {
+                    let layer = layer.get_or_insert_with(|| Pixmap::new(width, height).unwrap());
+                    layer.fill(Color::TRANSPARENT);
+                    layer
+                }
+            };
+            let mut painter = Painter {
+                target,
+                transform,
+                mask: mask.as_ref(),
+            };
+            for op in pass.back.iter().chain(&pass.main).chain(&pass.text) {
+                painter.render_op(op);
+            }
+            if let Some(alpha) = pass.alpha {
+                let paint = PixmapPaint {
+                    opacity: alpha,
+                    ..Default::default()
+                };
+                let identity = tiny_skia::Transform::identity();
+                let layer = layer.as_ref().unwrap().as_ref();
+                pixmap.draw_pixmap(0, 0, layer, &paint, identity, None);
+            }
+        }
+
+        self.passes.truncate(1);
+        let pass = &mut self.passes[0];
+        pass.rect = Rect::new(Coord::ZERO, self.size);
+        pass.back.clear();
+        pass.main.clear();
+        pass.text.clear();
+    }
+}
+
+fn color(col: Rgba) -> Color {
+    let c: [u8; 4] = Rgba8Srgb::from(col).into();
+    Color::from_rgba8(c[0], c[1], c[2], c[3])
+}
+
+fn solid(col: Rgba) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(color(col));
+    paint.anti_alias = true;
+    paint
+}
+
+fn point(v: Vec2) -> Point {
+    Point::from_xy(v.0, v.1)
+}
+
+/// Convert to a rect; returns an empty (unit) rect if `q` is degenerate
+fn to_rect(q: Quad) -> tiny_skia::Rect {
+    let size = q.size();
+    tiny_skia::Rect::from_xywh(q.a.0, q.a.1, size.0, size.1)
+        .unwrap_or_else(|| tiny_skia::Rect::from_xywh(q.a.0, q.a.1, 0.0, 0.0).unwrap())
+}
+
+fn rectangle(pb: &mut PathBuilder, q: Quad) {
+    let size = q.size();
+    if size.0 > 0.0 && size.1 > 0.0 {
+        pb.push_rect(q.a.0, q.a.1, size.0, size.1);
+    }
+}
+
+/// Add an axis-aligned oval path, scaled by `r` relative to `q`
+fn oval(pb: &mut PathBuilder, q: Quad, r: f32) {
+    let c = (q.a + q.b) * 0.5;
+    let half = q.size() * (0.5 * r);
+    if half.0 <= 0.0 || half.1 <= 0.0 {
+        return;
+    }
+    let rect = Quad::with_coords(c - half, c + half);
+    pb.push_oval(to_rect(rect));
+}
+
+/// Add a quarter-ellipse arc about `(cx, cy)`, starting from the current point
+///
+/// The arc ends at angle `quadrant × π/2` (clockwise from the x-axis, with y
+/// pointing down); if either radius is zero, lines are added instead.
+fn corner(pb: &mut PathBuilder, c: Vec2, r: Vec2, quadrant: u32) {
+    // End-point direction (d) and start-point direction (p), as unit vectors
+    let (d, p) = match quadrant % 4 {
+        0 => (Vec2(1.0, 0.0), Vec2(0.0, -1.0)),
+        1 => (Vec2(0.0, 1.0), Vec2(1.0, 0.0)),
+        2 => (Vec2(-1.0, 0.0), Vec2(0.0, 1.0)),
+        _ => (Vec2(0.0, -1.0), Vec2(-1.0, 0.0)),
+    };
+    let r = Vec2(r.0.max(0.0), r.1.max(0.0));
+    let start = c + p * r;
+    let end = c + d * r;
+    pb.line_to(start.0, start.1);
+    if r.0 > 0.0 && r.1 > 0.0 {
+        let c1 = start + d * r * KAPPA;
+        let c2 = end + p * r * KAPPA;
+        pb.cubic_to(c1.0, c1.1, c2.0, c2.1, end.0, end.1);
+    } else {
+        pb.line_to(end.0, end.1);
+    }
+}
+
+/// Add a rectangle path whose corners are ovals centred on `inner`'s corners
+///
+/// The corner radii are `r` times the distance between `inner` and `outer`.
+fn rounded_rect(pb: &mut PathBuilder, outer: Quad, inner: Quad, r: f32) {
+    let (a, b) = (inner.a, inner.b);
+    let ra = (inner.a - outer.a) * r;
+    let rb = (outer.b - inner.b) * r;
+    pb.move_to(a.0, a.1 - ra.1);
+    corner(pb, Vec2(b.0, a.1), Vec2(rb.0, ra.1), 0);
+    corner(pb, Vec2(b.0, b.1), Vec2(rb.0, rb.1), 1);
+    corner(pb, Vec2(a.0, b.1), Vec2(ra.0, rb.1), 2);
+    corner(pb, Vec2(a.0, a.1), Vec2(ra.0, ra.1), 3);
+    pb.close();
+}
+
+fn gradient_stops(stops: &[(f32, Rgba)]) -> Vec<GradientStop> {
+    stops
+        .iter()
+        .map(|(offset, col)| GradientStop::new(*offset, color(*col)))
+        .collect()
+}
+
+/// Get a gradient shader over `rect`
+fn gradient_shader(rect: Quad, gradient: &Gradient) -> Shader<'static> {
+    let size = rect.size();
+    let stops = || gradient_stops(gradient.stops());
+    let shader = match gradient.shape {
+        GradientShape::Linear { start, end } => {
+            let p0 = point(rect.a + start * size);
+            let p1 = point(rect.a + end * size);
+            let identity = tiny_skia::Transform::identity();
+            LinearGradient::new(p0, p1, stops(), SpreadMode::Pad, identity)
+        }
+        GradientShape::Radial { centre, radius } => {
+            let c = rect.a + centre * size;
+            let r = radius * size;
+            if !(r.0 > 0.0 && r.1 > 0.0) {
+                // Degenerate ellipse: everything is outside
+                return Shader::SolidColor(color(gradient.color_at(f32::INFINITY)));
+            }
+            // Map the unit circle to the ellipse
+            let transform = tiny_skia::Transform::from_row(r.0, 0.0, 0.0, r.1, c.0, c.1);
+            let o = Point::from_xy(0.0, 0.0);
+            RadialGradient::new(o, o, 1.0, stops(), SpreadMode::Pad, transform)
+        }
+    };
+    // Construction fails where the gradient is degenerate (e.g. start == end)
+    shader.unwrap_or_else(|| Shader::SolidColor(color(gradient.color_at(0.0))))
+}
+
+/// Rasters operations onto a target
+struct Painter<'a> {
+    target: &'a mut Pixmap,
+    transform: tiny_skia::Transform,
+    mask: Option<&'a ClipMask>,
+}
+
+impl<'a> Painter<'a> {
+    fn fill(&mut self, pb: PathBuilder, paint: &Paint) {
+        if let Some(path) = pb.finish() {
+            let (transform, mask) = (self.transform, self.mask);
+            self.target
+                .fill_path(&path, paint, FillRule::EvenOdd, transform, mask);
+        }
+    }
+
+    fn stroke(&mut self, path: Option<Path>, paint: &Paint, stroke: &tiny_skia::Stroke) {
+        if let Some(path) = path {
+            let (transform, mask) = (self.transform, self.mask);
+            self.target
+                .stroke_path(&path, paint, stroke, transform, mask);
+        }
+    }
+
+    fn render_op(&mut self, op: &Op) {
+        match op {
+            Op::Rect(q, col) => {
+                let mut pb = PathBuilder::new();
+                rectangle(&mut pb, *q);
+                self.fill(pb, &solid(*col));
+            }
+            Op::Frame(outer, inner, col) => {
+                let mut pb = PathBuilder::new();
+                rectangle(&mut pb, *outer);
+                rectangle(&mut pb, *inner);
+                self.fill(pb, &solid(*col));
+            }
+            Op::Gradient(outer, inner, gradient) => {
+                let mut pb = PathBuilder::new();
+                rectangle(&mut pb, *outer);
+                if let Some(inner) = inner {
+                    rectangle(&mut pb, *inner);
+                }
+                let mut paint = solid(Rgba::BLACK);
+                paint.shader = gradient_shader(*outer, gradient);
+                self.fill(pb, &paint);
+            }
+            Op::Line(p1, p2, radius, col) => {
+                let mut pb = PathBuilder::new();
+                pb.move_to(p1.0, p1.1);
+                pb.line_to(p2.0, p2.1);
+                let stroke = tiny_skia::Stroke {
+                    width: 2.0 * radius,
+                    line_cap: tiny_skia::LineCap::Round,
+                    ..Default::default()
+                };
+                self.stroke(pb.finish(), &solid(*col), &stroke);
+            }
+            Op::Polyline(points, closed, stroke, col) => {
+                let mut pb = PathBuilder::new();
+                for (i, p) in points.iter().enumerate() {
+                    if i == 0 {
+                        pb.move_to(p.0, p.1);
+                    } else {
+                        pb.line_to(p.0, p.1);
+                    }
+                }
+                if *closed {
+                    pb.close();
+                }
+                let line_cap = match stroke.cap {
+                    LineCap::Butt => tiny_skia::LineCap::Butt,
+                    LineCap::Square => tiny_skia::LineCap::Square,
+                    LineCap::Round => tiny_skia::LineCap::Round,
+                };
+                let dash = stroke.dash_pattern();
+                let stroke = tiny_skia::Stroke {
+                    width: stroke.width,
+                    line_cap,
+                    line_join: tiny_skia::LineJoin::Round,
+                    dash: dash.and_then(|dash| StrokeDash::new(dash, stroke.dash_offset)),
+                    ..Default::default()
+                };
+                self.stroke(pb.finish(), &solid(*col), &stroke);
+            }
+            Op::Circle(q, inner_radius, col) => {
+                let mut pb = PathBuilder::new();
+                oval(&mut pb, *q, 1.0);
+                oval(&mut pb, *q, *inner_radius);
+                self.fill(pb, &solid(*col));
+            }
+            Op::Circle2Col(q, col1, col2) => {
+                let c = (q.a + q.b) * 0.5;
+                let half = q.size() * 0.5;
+                if half.0 <= 0.0 || half.1 <= 0.0 {
+                    return;
+                }
+                let stops = vec![
+                    GradientStop::new(0.0, color(*col1)),
+                    GradientStop::new(1.0, color(*col2)),
+                ];
+                let transform = tiny_skia::Transform::from_row(half.0, 0.0, 0.0, half.1, c.0, c.1);
+                let o = Point::from_xy(0.0, 0.0);
+                let mut paint = solid(*col2);
+                if let Some(shader) =
+                    RadialGradient::new(o, o, 1.0, stops, SpreadMode::Pad, transform)
+                {
+                    paint.shader = shader;
+                }
+                let mut pb = PathBuilder::new();
+                oval(&mut pb, *q, 1.0);
+                self.fill(pb, &paint);
+            }
+            Op::RoundedFrame(outer, inner, r1, col) => {
+                let mut pb = PathBuilder::new();
+                rounded_rect(&mut pb, *outer, *inner, 1.0);
+                if *r1 > 0.0 {
+                    rounded_rect(&mut pb, *outer, *inner, *r1);
+                } else {
+                    rectangle(&mut pb, *inner);
+                }
+                self.fill(pb, &solid(*col));
+            }
+            Op::Shadow(q, radius, blur, col) => {
+                // Blur is not supported: approximate by stacking translucent
+                // rounded rects of increasing size.
+                const STEPS: i32 = 6;
+                let alpha = 1.0 - (1.0 - col.a).powf(1.0 / STEPS as f32);
+                let paint = solid(Rgba { a: alpha, ..*col });
+                let radius = radius.min(0.5 * q.size().min_comp()).max(0.0);
+                let inner = q.shrink(radius);
+                for i in 0..STEPS {
+                    let expand = *blur * (4.0 * (i as f32 + 0.5) / STEPS as f32 - 2.0);
+                    let r = radius + expand;
+                    let mut pb = PathBuilder::new();
+                    if r <= 0.0 {
+                        rectangle(&mut pb, q.grow(expand));
+                    } else {
+                        rounded_rect(&mut pb, inner.grow(r), inner, 1.0);
+                    }
+                    self.fill(pb, &paint);
+                }
+            }
+            Op::Image(pixmap, q) => {
+                let pixmap: &Pixmap = pixmap;
+                let size = q.size();
+                let (w, h) = (pixmap.width(), pixmap.height());
+                if w == 0 || h == 0 {
+                    return;
+                }
+                let (sx, sy) = (size.0 / w.cast::<f32>(), size.1 / h.cast::<f32>());
+                let transform = tiny_skia::Transform::from_row(sx, 0.0, 0.0, sy, q.a.0, q.a.1);
+                let mut paint = solid(Rgba::BLACK);
+                paint.shader = Pattern::new(
+                    pixmap.as_ref(),
+                    SpreadMode::Pad,
+                    FilterQuality::Bilinear,
+                    1.0,
+                    transform,
+                );
+                let mut pb = PathBuilder::new();
+                rectangle(&mut pb, *q);
+                self.fill(pb, &paint);
+            }
+            Op::Glyph(sprite, pos, col) => {
+                let (w, h) = sprite.size;
+                let mut glyph = match Pixmap::new(w, h) {
+                    Some(pixmap) => pixmap,
+                    None => return,
+                };
+                let c: [u8; 4] = Rgba8Srgb::from(*col).into();
+                let mul = |x: u8, a: u8| u8::conv((u32::from(x) * u32::from(a) + 127) / 255);
+                for (d, a) in glyph.pixels_mut().iter_mut().zip(sprite.data.iter()) {
+                    let a = mul(c[3], *a);
+                    let (r, g, b) = (mul(c[0], a), mul(c[1], a), mul(c[2], a));
+                    *d = PremultipliedColorU8::from_rgba(r, g, b, a).unwrap();
+                }
+                let (x, y) = (pos.0.cast_nearest(), pos.1.cast_nearest());
+                let (transform, mask) = (self.transform, self.mask);
+                let paint = PixmapPaint::default();
+                self.target
+                    .draw_pixmap(x, y, glyph.as_ref(), &paint, transform, mask);
+            }
+        }
+    }
+}
+
+impl DrawImpl for SoftWindow {
+    fn new_pass(
+        &mut self,
+        parent_pass: PassId,
+        rect: Rect,
+        offset: Offset,
+        class: PassType,
+    ) -> PassId {
+        let parent_pass = match class {
+            // Layers are not supported (see DrawSharedImpl::layer_alloc)
+            PassType::Clip | PassType::Layer(_) => parent_pass,
+            PassType::Overlay => PassId::new(0),
+        };
+        let transform = Transform::translation(-Vec2::from(offset));
+        self.new_transform_pass(parent_pass, rect, transform)
+    }
+
+    fn new_transform_pass(
+        &mut self,
+        parent_pass: PassId,
+        rect: Rect,
+        transform: Transform,
+    ) -> PassId {
+        let parent = &self.passes[parent_pass.pass()];
+        let rect = parent.transform.map_rect(rect);
+        let rect = rect.intersection(&parent.rect).unwrap_or(Rect::ZERO);
+        let transform = transform.then(parent.transform);
+        let alpha = parent.alpha;
+        let pass = self.passes.len().cast();
+        self.passes.push(Pass {
+            rect,
+            transform,
+            alpha,
+            ..Default::default()
+        });
+        PassId::new(pass)
+    }
+
+    fn new_alpha_pass(&mut self, parent_pass: PassId, rect: Rect, alpha: f32) -> PassId {
+        let pass = self.new_transform_pass(parent_pass, rect, Transform::IDENTITY);
+        let pass_data = &mut self.passes[pass.pass()];
+        pass_data.alpha = Some(pass_data.alpha.unwrap_or(1.0) * alpha);
+        pass
+    }
+
+    #[inline]
+    fn get_clip_rect(&self, pass: PassId) -> Rect {
+        let pass = &self.passes[pass.pass()];
+        pass.transform.inverse().map_rect(pass.rect)
+    }
+
+    #[inline]
+    fn get_transform(&self, pass: PassId) -> Transform {
+        self.passes[pass.pass()].transform
+    }
+
+    #[inline]
+    fn rect(&mut self, pass: PassId, rect: Quad, col: Rgba) {
+        self.push(pass, Op::Rect(rect, col));
+    }
+
+    #[inline]
+    fn frame(&mut self, pass: PassId, outer: Quad, inner: Quad, col: Rgba) {
+        self.push(pass, Op::Frame(outer, inner, col));
+    }
+
+    #[inline]
+    fn rect_gradient(&mut self, pass: PassId, rect: Quad, gradient: &Gradient) {
+        self.push(pass, Op::Gradient(rect, None, gradient.clone()));
+    }
+
+    #[inline]
+    fn frame_gradient(&mut self, pass: PassId, outer: Quad, inner: Quad, gradient: &Gradient) {
+        let op = Op::Gradient(outer, Some(inner), gradient.clone());
+        self.push(pass, op);
+    }
+
+    #[inline]
+    fn as_rounded_impl(&mut self) -> Option<&mut dyn DrawRoundedImpl> {
+        Some(self)
+    }
+
+    #[inline]
+    fn as_shaded_impl(&mut self) -> Option<&mut dyn DrawShadedImpl> {
+        Some(self)
+    }
+}
+
+impl DrawRoundedImpl for SoftWindow {
+    #[inline]
+    fn rounded_line(&mut self, pass: PassId, p1: Vec2, p2: Vec2, radius: f32, col: Rgba) {
+        self.push(pass, Op::Line(p1, p2, radius, col));
+    }
+
+    #[inline]
+    fn polyline(
+        &mut self,
+        pass: PassId,
+        points: &[Vec2],
+        closed: bool,
+        stroke: &Stroke,
+        col: Rgba,
+    ) {
+        let op = Op::Polyline(points.to_vec(), closed, stroke.clone(), col);
+        self.push(pass, op);
+    }
+
+    #[inline]
+    fn circle(&mut self, pass: PassId, rect: Quad, inner_radius: f32, col: Rgba) {
+        self.push(pass, Op::Circle(rect, inner_radius, col));
+    }
+
+    #[inline]
+    fn circle_2col(&mut self, pass: PassId, rect: Quad, col1: Rgba, col2: Rgba) {
+        let op = Op::Circle2Col(rect, col1, col2);
+        self.passes[pass.pass()].back.push(op);
+    }
+
+    #[inline]
+    fn rounded_frame(&mut self, pass: PassId, outer: Quad, inner: Quad, r1: f32, col: Rgba) {
+        self.push(pass, Op::RoundedFrame(outer, inner, r1, col));
+    }
+
+    /// Gradients cannot follow the frame's shape, thus this is drawn using the
+    /// average of `c1` and `c2`.
+    #[inline]
+    fn rounded_frame_2col(&mut self, pass: PassId, outer: Quad, inner: Quad, c1: Rgba, c2: Rgba) {
+        let col = Rgba {
+            r: 0.5 * (c1.r + c2.r),
+            g: 0.5 * (c1.g + c2.g),
+            b: 0.5 * (c1.b + c2.b),
+            a: 0.5 * (c1.a + c2.a),
+        };
+        let op = Op::RoundedFrame(outer, inner, 0.0, col);
+        self.passes[pass.pass()].back.push(op);
+    }
+
+    #[inline]
+    fn shadow(&mut self, pass: PassId, rect: Quad, radius: f32, blur: f32, col: Rgba) {
+        let op = Op::Shadow(rect, radius, blur, col);
+        self.passes[pass.pass()].back.push(op);
+    }
+
+    /// Not supported: does nothing.
+    #[inline]
+    fn blur(&mut self, _: PassId, _: Quad, _: f32) {}
+}
+
+/// Shading is not supported: these methods draw flat shapes.
+impl DrawShadedImpl for SoftWindow {
+    #[inline]
+    fn shaded_square(&mut self, pass: PassId, rect: Quad, _: (f32, f32), col: Rgba) {
+        self.push(pass, Op::Rect(rect, col));
+    }
+
+    #[inline]
+    fn shaded_circle(&mut self, pass: PassId, rect: Quad, _: (f32, f32), col: Rgba) {
+        self.push(pass, Op::Circle(rect, 0.0, col));
+    }
+
+    #[inline]
+    fn shaded_square_frame(
+        &mut self,
+        pass: PassId,
+        outer: Quad,
+        inner: Quad,
+        _: (f32, f32),
+        outer_col: Rgba,
+        _: Rgba,
+    ) {
+        self.push(pass, Op::Frame(outer, inner, outer_col));
+    }
+
+    #[inline]
+    fn shaded_round_frame(
+        &mut self,
+        pass: PassId,
+        outer: Quad,
+        inner: Quad,
+        _: (f32, f32),
+        col: Rgba,
+    ) {
+        self.push(pass, Op::RoundedFrame(outer, inner, 0.0, col));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pixel(pixmap: &Pixmap, x: u32, y: u32) -> [u8; 4] {
+        let c = pixmap.pixel(x, y).unwrap().demultiply();
+        [c.red(), c.green(), c.blue(), c.alpha()]
+    }
+
+    #[test]
+    fn clip_and_alpha() {
+        let size = Size(20, 10);
+        let pipe = SoftPipe::new(&Default::default());
+        let mut window = pipe.new_window();
+        pipe.resize(&mut window, size);
+
+        let full = Quad::from(Rect::new(Coord::ZERO, size));
+        let clip = Rect::new(Coord::ZERO, Size(10, 10));
+        let pass = window.new_pass(PassId::new(0), clip, Offset::ZERO, PassType::Clip);
+        window.rect(pass, full, Rgba::WHITE);
+        let rect = Rect::new(Coord(10, 0), Size(10, 10));
+        let pass = window.new_alpha_pass(PassId::new(0), rect, 0.5);
+        window.rect(pass, full, Rgba::WHITE);
+
+        let mut pixmap = Pixmap::new(20, 10).unwrap();
+        window.render(&mut pixmap, Some(Rgba::BLACK));
+        assert_eq!(pixel(&pixmap, 2, 5), [255, 255, 255, 255]);
+        // Alpha is applied in sRGB space
+        let grey = pixel(&pixmap, 15, 5);
+        assert!((126..=129).contains(&grey[0]), "{:?}", grey);
+
+        // Recorded passes are cleared by render
+        let mut pixmap = Pixmap::new(20, 10).unwrap();
+        window.render(&mut pixmap, Some(Rgba::BLACK));
+        assert_eq!(pixel(&pixmap, 2, 5), [0, 0, 0, 255]);
+    }
+}
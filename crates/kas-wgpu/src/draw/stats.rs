@@ -0,0 +1,239 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Draw statistics collection
+
+use futures::executor::LocalSpawner;
+use futures::task::LocalSpawnExt;
+use instant::Instant;
+use kas::draw::{DrawStats, PassStats};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Maximum number of passes timed per frame (two queries are used per pass)
+const MAX_TIMED_PASSES: u32 = 256;
+
+/// Timestamps read back from the GPU
+#[derive(Default)]
+struct Readback {
+    /// True while the read buffer is mapped or awaiting mapping
+    in_flight: bool,
+    /// Pass index, start and end timestamp of each timed pass
+    result: Option<Vec<(usize, u64, u64)>>,
+}
+
+/// GPU pass timer using timestamp queries
+struct Timer {
+    /// Nanoseconds per timestamp tick
+    period: f32,
+    query_set: wgpu::QuerySet,
+    resolve_buf: wgpu::Buffer,
+    read_buf: Rc<wgpu::Buffer>,
+    readback: Rc<RefCell<Readback>>,
+    /// True if the current frame is being timed
+    active: bool,
+    /// Indices of passes timed this frame, in order of execution
+    passes: Vec<usize>,
+    /// Latest results: GPU time of each timed pass and of the whole frame
+    times: Vec<(usize, Duration)>,
+    total: Option<Duration>,
+}
+
+impl Timer {
+    fn new(device: &wgpu::Device, period: f32) -> Self {
+        let count = 2 * MAX_TIMED_PASSES;
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("pass timestamps"),
+            ty: wgpu::QueryType::Timestamp,
+            count,
+        });
+        let size = u64::from(count) * 8;
+        let resolve_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("timestamp resolve buffer"),
+            size,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let read_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("timestamp read buffer"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Timer {
+            period,
+            query_set,
+            resolve_buf,
+            read_buf: Rc::new(read_buf),
+            readback: Default::default(),
+            active: false,
+            passes: vec![],
+            times: vec![],
+            total: None,
+        }
+    }
+
+    fn begin_frame(&mut self) {
+        let mut readback = self.readback.borrow_mut();
+        if let Some(result) = readback.result.take() {
+            let period = f64::from(self.period);
+            let duration = |ticks: u64| Duration::from_nanos((ticks as f64 * period) as u64);
+            self.times = result
+                .iter()
+                .map(|(pass, start, end)| (*pass, duration(end.saturating_sub(*start))))
+                .collect();
+            let start = result.iter().map(|r| r.1).min();
+            let end = result.iter().map(|r| r.2).max();
+            self.total = start
+                .zip(end)
+                .map(|(start, end)| duration(end.saturating_sub(start)));
+        }
+
+        // We cannot re-use the read buffer until the last result is read
+        self.active = !readback.in_flight;
+        self.passes.clear();
+    }
+
+    fn begin_pass(&mut self, encoder: &mut wgpu::CommandEncoder, pass: usize) -> bool {
+        let index = self.passes.len() as u32;
+        if !self.active || index >= MAX_TIMED_PASSES {
+            return false;
+        }
+        encoder.write_timestamp(&self.query_set, 2 * index);
+        self.passes.push(pass);
+        true
+    }
+
+    fn end_pass(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let index = self.passes.len() as u32 - 1;
+        encoder.write_timestamp(&self.query_set, 2 * index + 1);
+    }
+
+    fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if !self.active || self.passes.is_empty() {
+            return;
+        }
+        let count = 2 * self.passes.len() as u32;
+        encoder.resolve_query_set(&self.query_set, 0..count, &self.resolve_buf, 0);
+        let size = u64::from(count) * 8;
+        encoder.copy_buffer_to_buffer(&self.resolve_buf, 0, &self.read_buf, 0, size);
+    }
+
+    fn read_back(&mut self, spawner: &LocalSpawner) {
+        if !self.active || self.passes.is_empty() {
+            return;
+        }
+        self.readback.borrow_mut().in_flight = true;
+
+        let buf = self.read_buf.clone();
+        let readback = self.readback.clone();
+        let passes = std::mem::take(&mut self.passes);
+        let size = 16 * passes.len() as u64;
+        spawner
+            .spawn_local(async move {
+                let slice = buf.slice(..size);
+                let mut result = None;
+                if slice.map_async(wgpu::MapMode::Read).await.is_ok() {
+                    let data = slice.get_mapped_range();
+                    let ts: Vec<u64> = data
+                        .chunks_exact(8)
+                        .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+                        .collect();
+                    drop(data);
+                    buf.unmap();
+                    let iter = passes.iter().enumerate();
+                    result = Some(iter.map(|(i, p)| (*p, ts[2 * i], ts[2 * i + 1])).collect());
+                }
+                let mut readback = readback.borrow_mut();
+                readback.in_flight = false;
+                readback.result = result;
+            })
+            .expect("Read back timestamps");
+    }
+}
+
+/// Per-window statistics collector
+pub(crate) struct Stats {
+    stats: DrawStats,
+    start: Instant,
+    timer: Option<Timer>,
+    // Whether the current pass is timed
+    timing: bool,
+}
+
+impl Stats {
+    /// Construct
+    ///
+    /// If `timestamp_period` is given, GPU time is measured using timestamp
+    /// queries (which must be supported by the device).
+    pub fn new(device: &wgpu::Device, timestamp_period: Option<f32>) -> Self {
+        Stats {
+            stats: Default::default(),
+            start: Instant::now(),
+            timer: timestamp_period.map(|period| Timer::new(device, period)),
+            timing: false,
+        }
+    }
+
+    /// Statistics of the last frame
+    pub fn get(&self) -> &DrawStats {
+        &self.stats
+    }
+
+    /// Begin collection for a new frame
+    pub fn begin_frame(&mut self) {
+        self.start = Instant::now();
+        self.stats.passes.clear();
+        if let Some(timer) = self.timer.as_mut() {
+            timer.begin_frame();
+        }
+    }
+
+    /// Add statistics for the next pass (in order of pass index)
+    pub fn push_pass(&mut self, stats: PassStats) {
+        self.stats.passes.push(stats);
+    }
+
+    /// Call before encoding render commands for `pass`
+    pub fn begin_pass(&mut self, encoder: &mut wgpu::CommandEncoder, pass: usize) {
+        self.timing = match self.timer.as_mut() {
+            Some(timer) => timer.begin_pass(encoder, pass),
+            None => false,
+        };
+    }
+
+    /// Call after encoding render commands for a pass
+    pub fn end_pass(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if self.timing {
+            if let Some(timer) = self.timer.as_mut() {
+                timer.end_pass(encoder);
+            }
+            self.timing = false;
+        }
+    }
+
+    /// Call after encoding all render passes
+    pub fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if let Some(timer) = self.timer.as_mut() {
+            timer.resolve(encoder);
+        }
+    }
+
+    /// Finish the frame (call after submitting render commands)
+    pub fn finish(&mut self, spawner: &LocalSpawner) {
+        if let Some(timer) = self.timer.as_mut() {
+            timer.read_back(spawner);
+            for (pass, time) in timer.times.iter() {
+                if let Some(stats) = self.stats.passes.get_mut(*pass) {
+                    stats.gpu_time = Some(*time);
+                }
+            }
+            self.stats.gpu_time = timer.total;
+        }
+        self.stats.cpu_time = self.start.elapsed();
+    }
+}
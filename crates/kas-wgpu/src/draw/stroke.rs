@@ -0,0 +1,228 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Stroked path pipeline
+
+use super::common;
+use crate::draw::ShaderManager;
+use kas::draw::{color::Rgba, LineCap, PassId, Stroke};
+use kas::geom::Vec2;
+use std::mem::size_of;
+
+/// Anti-aliasing margin around each piece
+const AA_MARGIN: f32 = 1.0;
+
+// Cap codes, as used by the shader
+const CAP_BUTT: f32 = 0.0;
+const CAP_SQUARE: f32 = 1.0;
+const CAP_ROUND: f32 = 2.0;
+
+/// Vertex
+///
+/// -   `screen_pos: Vec2` — screen coordinate
+/// -   `col: Rgba`
+/// -   `pos: Vec2` — coordinate along and across the piece, relative to its
+///     start point
+/// -   `params: [f32; 4]` — length of piece, half-width, start and end cap
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Vertex(Vec2, Rgba, Vec2, [f32; 4]);
+unsafe impl bytemuck::Zeroable for Vertex {}
+unsafe impl bytemuck::Pod for Vertex {}
+
+pub type Window = common::Window<Vertex>;
+
+/// A pipeline for rendering stroked paths
+pub struct Pipeline {
+    render_pipeline: wgpu::RenderPipeline,
+}
+
+impl Pipeline {
+    /// Construct
+    pub fn new(
+        device: &wgpu::Device,
+        shaders: &ShaderManager,
+        bgl_common: &wgpu::BindGroupLayout,
+    ) -> Self {
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("stroke pipeline_layout"),
+            bind_group_layouts: &[bgl_common],
+            push_constant_ranges: &[],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("stroke render_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shaders.stroke,
+                entry_point: "vert_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x2,
+                        1 => Float32x4,
+                        2 => Float32x2,
+                        3 => Float32x4,
+                    ],
+                }],
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Cw,
+                cull_mode: None,
+                clamp_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: Default::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shaders.stroke,
+                entry_point: "frag_main",
+                targets: &[wgpu::ColorTargetState {
+                    format: super::RENDER_TEX_FORMAT,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            }),
+        });
+
+        Pipeline { render_pipeline }
+    }
+
+    /// Enqueue render commands
+    pub fn render<'a>(
+        &'a self,
+        window: &'a Window,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass<'a>,
+        bg_common: &'a wgpu::BindGroup,
+    ) {
+        window.render(pass, rpass, &self.render_pipeline, bg_common);
+    }
+}
+
+impl Window {
+    /// Draw a polyline
+    ///
+    /// The path is drawn as a sequence of straight pieces (one per segment, or
+    /// one per dash within a segment). Pieces ending at an interior vertex of
+    /// the path get a round end cap, forming a round join with the next.
+    pub fn polyline(
+        &mut self,
+        pass: PassId,
+        points: &[Vec2],
+        closed: bool,
+        stroke: &Stroke,
+        col: Rgba,
+    ) {
+        if points.is_empty() || stroke.width <= 0.0 || col.a == 0.0 {
+            // nothing to draw
+            return;
+        }
+
+        let hw = 0.5 * stroke.width;
+        let cap = match stroke.cap {
+            LineCap::Butt => CAP_BUTT,
+            LineCap::Square => CAP_SQUARE,
+            LineCap::Round => CAP_ROUND,
+        };
+        let end_cap = if closed { CAP_ROUND } else { cap };
+
+        let mut segments: Vec<(Vec2, Vec2)> = points.windows(2).map(|w| (w[0], w[1])).collect();
+        if closed && points.len() > 1 {
+            segments.push((points[points.len() - 1], points[0]));
+        } else if segments.is_empty() {
+            // A single point: draw the caps only
+            segments.push((points[0], points[0]));
+        }
+        let last = segments.len() - 1;
+
+        let dash = match stroke.dash_pattern() {
+            Some(dash) => dash,
+            None => {
+                for (i, (p1, p2)) in segments.iter().enumerate() {
+                    let c1 = if i == 0 && !closed { cap } else { CAP_BUTT };
+                    let c2 = if i == last { end_cap } else { CAP_ROUND };
+                    self.piece(pass, *p1, *p2, hw, (c1, c2), col);
+                }
+                return;
+            }
+        };
+
+        // Find the dash index and distance into this dash at the path start
+        let period: f32 = dash.iter().sum();
+        let mut phase = stroke.dash_offset.rem_euclid(period);
+        let mut index = 0;
+        while phase >= dash[index] {
+            phase -= dash[index];
+            index = (index + 1) % dash.len();
+        }
+        // Whether the current dash started before the current segment
+        let mut continued = false;
+
+        for (i, (p1, p2)) in segments.iter().enumerate() {
+            let d = *p2 - *p1;
+            let len = d.sum_square().sqrt();
+            if len == 0.0 && last > 0 {
+                // skip repeated points
+                continue;
+            }
+            let dir = if len > 0.0 { d / len } else { Vec2(1.0, 0.0) };
+            let mut t = 0.0;
+            loop {
+                let remaining = dash[index] - phase;
+                let finished = t + remaining <= len;
+                let t_end = if finished { t + remaining } else { len };
+                if index % 2 == 0 {
+                    let c1 = if continued { CAP_BUTT } else { cap };
+                    let c2 = match finished {
+                        true => cap,
+                        false if i == last => end_cap,
+                        false => CAP_ROUND,
+                    };
+                    let (a, b) = (*p1 + dir * t, *p1 + dir * t_end);
+                    self.piece(pass, a, b, hw, (c1, c2), col);
+                }
+                if !finished {
+                    phase += len - t;
+                    continued = true;
+                    break;
+                }
+                t = t_end;
+                phase = 0.0;
+                index = (index + 1) % dash.len();
+                continued = false;
+            }
+        }
+    }
+
+    /// Draw a single straight piece from `a` to `b`
+    fn piece(&mut self, pass: PassId, a: Vec2, b: Vec2, hw: f32, caps: (f32, f32), col: Rgba) {
+        let d = b - a;
+        let len = d.sum_square().sqrt();
+        let dir = if len > 0.0 { d / len } else { Vec2(1.0, 0.0) };
+        let norm = Vec2(-dir.1, dir.0);
+
+        let ext = |cap: f32| (if cap > CAP_BUTT { hw } else { 0.0 }) + AA_MARGIN;
+        let (u0, u1) = (-ext(caps.0), len + ext(caps.1));
+        let v = hw + AA_MARGIN;
+        let params = [len, hw, caps.0, caps.1];
+
+        let vertex = |u: f32, v: f32| Vertex(a + dir * u + norm * v, col, Vec2(u, v), params);
+        let aa = vertex(u0, -v);
+        let ab = vertex(u0, v);
+        let ba = vertex(u1, -v);
+        let bb = vertex(u1, v);
+
+        #[rustfmt::skip]
+        self.add_vertices(pass.pass(), &[
+            aa, ba, ab,
+            ab, ba, bb,
+        ]);
+    }
+}
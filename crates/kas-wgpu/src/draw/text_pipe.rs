@@ -4,15 +4,25 @@
 //     https://www.apache.org/licenses/LICENSE-2.0
 
 //! Text drawing pipeline
+//!
+//! Glyphs are rastered to an alpha mask and drawn in the requested colour.
+//! Colour glyphs are instead stored in an RGBA atlas and drawn via the image
+//! shaders, untinted. These come from bitmap fonts (PNG images in CBDT or sbix
+//! tables) or from vector fonts (COLR/CPAL tables), where each layer's outline
+//! is rastered and composited in its palette colour.
+//!
+//! With sub-pixel (LCD) anti-aliasing, glyphs are rastered with per-channel
+//! coverage and drawn in two steps: first the background is attenuated by
+//! coverage, then the text colour (scaled by coverage) is added.
 
-use super::{atlases, ShaderManager};
+use super::{atlases, images, ShaderManager};
 use kas::cast::*;
-use kas::draw::{color::Rgba, PassId};
+use kas::draw::{color::Rgba, PassId, PassStats};
 use kas::geom::{Quad, Vec2};
 use kas::text::fonts::FaceId;
 use kas::text::{Effect, Glyph, TextDisplay};
 use kas_text::raster::{raster, Config, SpriteDescriptor};
-use kas_theme::RasterConfig;
+use kas_theme::{LcdOrder, RasterConfig};
 use rustc_hash::FxHashMap as HashMap;
 use std::mem::size_of;
 use std::num::NonZeroU32;
@@ -23,6 +33,7 @@ use std::num::NonZeroU32;
 /// struct contains everything needed to draw from the sprite.
 #[derive(Clone, Debug)]
 struct Sprite {
+    kind: SpriteKind,
     atlas: u32,
     // TODO(opt): u16 or maybe even u8 would be enough
     size: Vec2,
@@ -30,6 +41,16 @@ struct Sprite {
     tex_quad: Quad,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SpriteKind {
+    /// Alpha mask
+    Mask,
+    /// Per-channel coverage
+    Lcd,
+    /// Colour image
+    Color,
+}
+
 /// Screen and texture coordinates
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
@@ -46,10 +67,17 @@ unsafe impl bytemuck::Pod for Instance {}
 /// A pipeline for rendering text
 pub struct Pipeline {
     config: Config,
+    lcd_config: Config,
+    lcd_order: LcdOrder,
+    gamma: Option<[u8; 256]>,
     atlas_pipe: atlases::Pipeline<Instance>,
+    lcd_pipe: atlases::Pipeline<Instance>,
+    lcd_add: wgpu::RenderPipeline,
+    color_pipe: atlases::Pipeline<images::Instance>,
     glyphs: HashMap<SpriteDescriptor, Option<Sprite>>,
+    lcd_glyphs: HashMap<(SpriteDescriptor, bool), Option<Sprite>>,
     #[allow(clippy::type_complexity)]
-    prepare: Vec<(u32, (u32, u32), (u32, u32), Vec<u8>)>,
+    prepare: Vec<(SpriteKind, u32, (u32, u32), (u32, u32), Vec<u8>)>,
 }
 
 impl Pipeline {
@@ -59,28 +87,112 @@ impl Pipeline {
         bgl_common: &wgpu::BindGroupLayout,
         config: &RasterConfig,
     ) -> Self {
+        let glyph_buffers = [wgpu::VertexBufferLayout {
+            array_stride: size_of::<Instance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &wgpu::vertex_attr_array![
+                0 => Float32x2,
+                1 => Float32x2,
+                2 => Float32x2,
+                3 => Float32x2,
+                4 => Float32x4,
+            ],
+        }];
+        let glyph_vertex = || wgpu::VertexState {
+            module: &shaders.vert_glyph,
+            entry_point: "main",
+            buffers: &glyph_buffers,
+        };
+
         let atlas_pipe = atlases::Pipeline::new(
             device,
             bgl_common,
             512,
             wgpu::TextureFormat::R8Unorm,
+            glyph_vertex(),
+            wgpu::FragmentState {
+                module: &shaders.frag_glyph,
+                entry_point: "main",
+                targets: &[wgpu::ColorTargetState {
+                    format: super::RENDER_TEX_FORMAT,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            },
+        );
+
+        // LCD glyphs: first attenuate the background by (per-channel) coverage
+        let lcd_pipe = atlases::Pipeline::new(
+            device,
+            bgl_common,
+            512,
+            wgpu::TextureFormat::Rgba8Unorm,
+            glyph_vertex(),
+            wgpu::FragmentState {
+                module: &shaders.frag_glyph_lcd,
+                entry_point: "main",
+                targets: &[wgpu::ColorTargetState {
+                    format: super::RENDER_TEX_FORMAT,
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::Zero,
+                            dst_factor: wgpu::BlendFactor::OneMinusSrc,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                        alpha: wgpu::BlendComponent::OVER,
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            },
+        );
+        // ... then add the text colour, scaled by coverage
+        let lcd_add = lcd_pipe.new_render_pipeline(
+            device,
+            bgl_common,
+            glyph_vertex(),
+            wgpu::FragmentState {
+                module: &shaders.frag_glyph_lcd,
+                entry_point: "main",
+                targets: &[wgpu::ColorTargetState {
+                    format: super::RENDER_TEX_FORMAT,
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::One,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                        alpha: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::Zero,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            },
+        );
+
+        let color_pipe = atlases::Pipeline::new(
+            device,
+            bgl_common,
+            1024,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
             wgpu::VertexState {
-                module: &shaders.vert_glyph,
+                module: &shaders.vert_image,
                 entry_point: "main",
                 buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: size_of::<Instance>() as wgpu::BufferAddress,
+                    array_stride: size_of::<images::Instance>() as wgpu::BufferAddress,
                     step_mode: wgpu::VertexStepMode::Instance,
                     attributes: &wgpu::vertex_attr_array![
                         0 => Float32x2,
                         1 => Float32x2,
                         2 => Float32x2,
                         3 => Float32x2,
-                        4 => Float32x4,
                     ],
                 }],
             },
             wgpu::FragmentState {
-                module: &shaders.frag_glyph,
+                module: &shaders.frag_image,
                 entry_point: "main",
                 targets: &[wgpu::ColorTargetState {
                     format: super::RENDER_TEX_FORMAT,
@@ -96,8 +208,16 @@ impl Pipeline {
                 config.subpixel_threshold,
                 config.subpixel_steps,
             ),
+            // LCD glyphs are rastered at three sub-pixel offsets
+            lcd_config: Config::new(config.mode, config.scale_steps, u8::MAX, 3),
+            lcd_order: config.lcd_order,
+            gamma: gamma_lut(config.gamma),
             atlas_pipe,
+            lcd_pipe,
+            lcd_add,
+            color_pipe,
             glyphs: Default::default(),
+            lcd_glyphs: Default::default(),
             prepare: Default::default(),
         }
     }
@@ -105,6 +225,8 @@ impl Pipeline {
     /// Write to textures
     pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
         self.atlas_pipe.prepare(device);
+        self.lcd_pipe.prepare(device);
+        self.color_pipe.prepare(device);
 
         if !self.prepare.is_empty() {
             log::trace!(
@@ -112,10 +234,15 @@ impl Pipeline {
                 self.prepare.len()
             );
         }
-        for (atlas, origin, size, data) in self.prepare.drain(..) {
+        for (kind, atlas, origin, size, data) in self.prepare.drain(..) {
+            let (texture, bytes_per_pixel) = match kind {
+                SpriteKind::Mask => (self.atlas_pipe.get_texture(atlas), 1),
+                SpriteKind::Lcd => (self.lcd_pipe.get_texture(atlas), 4),
+                SpriteKind::Color => (self.color_pipe.get_texture(atlas), 4),
+            };
             queue.write_texture(
                 wgpu::ImageCopyTexture {
-                    texture: self.atlas_pipe.get_texture(atlas),
+                    texture,
                     mip_level: 0,
                     origin: wgpu::Origin3d {
                         x: origin.0,
@@ -127,7 +254,7 @@ impl Pipeline {
                 &data,
                 wgpu::ImageDataLayout {
                     offset: 0,
-                    bytes_per_row: NonZeroU32::new(size.0),
+                    bytes_per_row: NonZeroU32::new(bytes_per_pixel * size.0),
                     rows_per_image: NonZeroU32::new(size.1),
                 },
                 wgpu::Extent3d {
@@ -149,24 +276,55 @@ impl Pipeline {
     ) {
         self.atlas_pipe
             .render(&window.atlas, pass, rpass, bg_common);
+        self.lcd_pipe
+            .render(&window.lcd_mask, pass, rpass, bg_common);
+        self.lcd_pipe
+            .render_with(&self.lcd_add, &window.lcd_add, pass, rpass, bg_common);
+        self.color_pipe
+            .render(&window.color_atlas, pass, rpass, bg_common);
     }
 
     /// Get a rendered sprite
     ///
     /// This returns `None` if there's nothing to render. It may also return
     /// `None` (with a warning) on error.
-    fn get_glyph(&mut self, face: FaceId, dpem: f32, glyph: Glyph) -> Option<Sprite> {
+    fn get_glyph(
+        &mut self,
+        lcd: LcdOrder,
+        face: FaceId,
+        dpem: f32,
+        glyph: Glyph,
+    ) -> Option<Sprite> {
+        if lcd != LcdOrder::None {
+            return self.get_lcd_glyph(lcd == LcdOrder::Bgr, face, dpem, glyph);
+        }
+
         let desc = SpriteDescriptor::new(&self.config, face, glyph, dpem);
         if let Some(opt_sprite) = self.glyphs.get(&desc).cloned() {
             opt_sprite
         } else {
             // NOTE: this branch is *rare*. We don't use HashMap::entry and push
             // rastering to another function to optimise for the common case.
-            self.raster_glyph(desc)
+            self.raster_glyph(desc, face, dpem, glyph)
         }
     }
 
-    fn raster_glyph(&mut self, desc: SpriteDescriptor) -> Option<Sprite> {
+    fn raster_glyph(
+        &mut self,
+        desc: SpriteDescriptor,
+        face: FaceId,
+        dpem: f32,
+        glyph: Glyph,
+    ) -> Option<Sprite> {
+        #[cfg(feature = "emoji")]
+        if let Some(sprite) = self.raster_color_glyph(face, dpem, glyph) {
+            let sprite = Some(sprite);
+            self.glyphs.insert(desc, sprite.clone());
+            return sprite;
+        }
+        #[cfg(not(feature = "emoji"))]
+        let _ = (face, dpem, glyph);
+
         // NOTE: we only need the allocation and coordinates now; the
         // rendering could be offloaded (though this may not be useful).
         let mut sprite = None;
@@ -174,13 +332,17 @@ impl Pipeline {
             match self.atlas_pipe.allocate(rs.size) {
                 Ok((atlas, _, origin, tex_quad)) => {
                     let s = Sprite {
+                        kind: SpriteKind::Mask,
                         atlas,
                         size: Vec2(rs.size.0.cast(), rs.size.1.cast()),
                         offset: Vec2(rs.offset.0.cast(), rs.offset.1.cast()),
                         tex_quad,
                     };
 
-                    self.prepare.push((s.atlas, origin, rs.size, rs.data));
+                    let mut data = rs.data;
+                    self.apply_gamma(&mut data);
+                    self.prepare
+                        .push((SpriteKind::Mask, s.atlas, origin, rs.size, data));
                     sprite = Some(s);
                 }
                 Err(_) => {
@@ -202,12 +364,331 @@ impl Pipeline {
         self.glyphs.insert(desc, sprite.clone());
         sprite
     }
+
+    fn apply_gamma(&self, data: &mut [u8]) {
+        if let Some(lut) = self.gamma.as_ref() {
+            for v in data.iter_mut() {
+                *v = lut[usize::from(*v)];
+            }
+        }
+    }
+
+    /// Get a rendered sprite with sub-pixel anti-aliasing
+    fn get_lcd_glyph(
+        &mut self,
+        bgr: bool,
+        face: FaceId,
+        dpem: f32,
+        glyph: Glyph,
+    ) -> Option<Sprite> {
+        let desc = SpriteDescriptor::new(&self.lcd_config, face, glyph, dpem);
+        if let Some(opt_sprite) = self.lcd_glyphs.get(&(desc, bgr)).cloned() {
+            return opt_sprite;
+        }
+
+        #[cfg(feature = "emoji")]
+        let sprite = self.raster_color_glyph(face, dpem, glyph);
+        #[cfg(not(feature = "emoji"))]
+        let sprite = None;
+
+        let sprite = sprite.or_else(|| self.raster_lcd_glyph(bgr, face, dpem, glyph));
+        self.lcd_glyphs.insert((desc, bgr), sprite.clone());
+        sprite
+    }
+
+    fn raster_lcd_glyph(
+        &mut self,
+        bgr: bool,
+        face: FaceId,
+        dpem: f32,
+        glyph: Glyph,
+    ) -> Option<Sprite> {
+        // Each channel is rastered with the glyph shifted such that the pixel
+        // is centred on the sub-pixel. This is equivalent to a box filter
+        // (one pixel wide) over sub-pixels, reducing colour fringes.
+        let x0 = glyph.position.0.floor();
+        let mut channels = Vec::with_capacity(3);
+        for shift in [1.0 / 3.0, 0.0, -1.0 / 3.0] {
+            let mut g = glyph;
+            g.position.0 += shift;
+            let dx = i32::conv_floor(g.position.0 - x0);
+            let desc = SpriteDescriptor::new(&self.lcd_config, face, g, dpem);
+            let rs = raster(&self.lcd_config, desc)?;
+            channels.push((rs.offset.0 + dx, rs.offset.1, rs.size, rs.data));
+        }
+        if bgr {
+            channels.swap(0, 2);
+        }
+
+        let x_min = channels.iter().map(|c| c.0).min().unwrap();
+        let y_min = channels.iter().map(|c| c.1).min().unwrap();
+        let x_max = channels
+            .iter()
+            .map(|c| c.0 + i32::conv(c.2 .0))
+            .max()
+            .unwrap();
+        let y_max = channels
+            .iter()
+            .map(|c| c.1 + i32::conv(c.2 .1))
+            .max()
+            .unwrap();
+        let size = (u32::conv(x_max - x_min), u32::conv(y_max - y_min));
+        let width = usize::conv(size.0);
+
+        let mut data = vec![0u8; 4 * width * usize::conv(size.1)];
+        for (c, (x, y, (w, h), src)) in channels.iter().enumerate() {
+            let (x, y) = (usize::conv(x - x_min), usize::conv(y - y_min));
+            let (w, h) = (usize::conv(*w), usize::conv(*h));
+            for row in 0..h {
+                for col in 0..w {
+                    data[4 * ((y + row) * width + x + col) + c] = src[row * w + col];
+                }
+            }
+        }
+        for px in data.chunks_exact_mut(4) {
+            px[3] = px[0].max(px[1]).max(px[2]);
+        }
+        self.apply_gamma(&mut data);
+
+        match self.lcd_pipe.allocate(size) {
+            Ok((atlas, _, origin, tex_quad)) => {
+                let sprite = Sprite {
+                    kind: SpriteKind::Lcd,
+                    atlas,
+                    size: Vec2(size.0.cast(), size.1.cast()),
+                    offset: Vec2(x_min.cast(), y_min.cast()),
+                    tex_quad,
+                };
+                self.prepare
+                    .push((SpriteKind::Lcd, atlas, origin, size, data));
+                Some(sprite)
+            }
+            Err(_) => {
+                log::warn!("text_pipe: failed to allocate glyph with size {:?}", size);
+                None
+            }
+        }
+    }
+
+    /// Raster a colour glyph from embedded bitmaps or COLR layers
+    ///
+    /// Returns `None` if the face has no colour data for this glyph.
+    #[cfg(feature = "emoji")]
+    fn raster_color_glyph(&mut self, face: FaceId, dpem: f32, glyph: Glyph) -> Option<Sprite> {
+        let store = kas::text::fonts::fonts().get_face_store(face);
+        let (data, offset) = match color_glyph_image(store.face(), glyph.id.0, dpem) {
+            Some(image) => image,
+            None => self.colr_glyph_image(store.face(), face, dpem, glyph)?,
+        };
+        let (w, h) = data.dimensions();
+
+        match self.color_pipe.allocate((w, h)) {
+            Ok((atlas, _, origin, tex_quad)) => {
+                let sprite = Sprite {
+                    kind: SpriteKind::Color,
+                    atlas,
+                    size: Vec2(w.cast(), h.cast()),
+                    offset,
+                    tex_quad,
+                };
+                self.prepare
+                    .push((SpriteKind::Color, atlas, origin, (w, h), data.into_raw()));
+                Some(sprite)
+            }
+            Err(_) => {
+                log::warn!(
+                    "text_pipe: failed to allocate colour glyph with size {:?}",
+                    (w, h)
+                );
+                None
+            }
+        }
+    }
+
+    /// Composite a colour glyph from COLR layers
+    ///
+    /// Each layer is rastered as an alpha mask (like any other glyph), then
+    /// drawn over lower layers in its palette colour. Returns the image and its
+    /// offset from the glyph origin, or `None` if the glyph has no layers.
+    #[cfg(feature = "emoji")]
+    fn colr_glyph_image(
+        &self,
+        ttf: &ttf_parser::Face,
+        face: FaceId,
+        dpem: f32,
+        glyph: Glyph,
+    ) -> Option<(image::RgbaImage, Vec2)> {
+        use ttf_parser::Tag;
+
+        let colr = ttf.table_data(Tag::from_bytes(b"COLR"))?;
+        let cpal = ttf.table_data(Tag::from_bytes(b"CPAL"))?;
+        let mut layers = vec![];
+        for (id, col) in colr_layers(colr, cpal, glyph.id.0)? {
+            let mut g = glyph;
+            g.id.0 = id;
+            let desc = SpriteDescriptor::new(&self.config, face, g, dpem);
+            if let Some(rs) = raster(&self.config, desc) {
+                layers.push((rs, col));
+            }
+        }
+
+        let x0 = layers.iter().map(|(rs, _)| rs.offset.0).min()?;
+        let y0 = layers.iter().map(|(rs, _)| rs.offset.1).min()?;
+        let x1 = layers
+            .iter()
+            .map(|(rs, _)| rs.offset.0 + i32::conv(rs.size.0))
+            .max()?;
+        let y1 = layers
+            .iter()
+            .map(|(rs, _)| rs.offset.1 + i32::conv(rs.size.1))
+            .max()?;
+        let (w, h) = (usize::conv(x1 - x0), usize::conv(y1 - y0));
+
+        // Composite with premultiplied alpha
+        let mut acc = vec![[0f32; 4]; w * h];
+        for (rs, col) in layers.iter() {
+            let (x, y) = (usize::conv(rs.offset.0 - x0), usize::conv(rs.offset.1 - y0));
+            let lw = usize::conv(rs.size.0);
+            for (i, mask) in rs.data.iter().enumerate() {
+                let a = f32::conv(*mask) * f32::conv(col[3]) / (255.0 * 255.0);
+                let px = &mut acc[(y + i / lw) * w + x + i % lw];
+                for c in 0..3 {
+                    px[c] = f32::conv(col[c]) / 255.0 * a + px[c] * (1.0 - a);
+                }
+                px[3] = a + px[3] * (1.0 - a);
+            }
+        }
+
+        let mut data = Vec::with_capacity(4 * w * h);
+        for px in acc {
+            let a = px[3];
+            for c in 0..3 {
+                let v = if a > 0.0 { px[c] / a } else { 0.0 };
+                data.push(u8::conv_nearest(v * 255.0));
+            }
+            data.push(u8::conv_nearest(a * 255.0));
+        }
+        let image = image::RgbaImage::from_raw(w.cast(), h.cast(), data)?;
+        Some((image, Vec2(x0.cast(), y0.cast())))
+    }
+}
+
+/// Get the layers of glyph `id` from COLR and CPAL tables
+///
+/// Only the (version 0) base glyph and layer records are read; the colours are
+/// taken from the first palette. Palette index `0xFFFF` (the text colour) is
+/// drawn black since colour sprites are not tinted. Returns layer glyph ids
+/// with RGBA colours, bottom first, or `None` if the glyph has no layers or
+/// the tables are malformed.
+#[cfg(feature = "emoji")]
+fn colr_layers(colr: &[u8], cpal: &[u8], id: u16) -> Option<Vec<(u16, [u8; 4])>> {
+    use std::cmp::Ordering;
+
+    let u16_at = |data: &[u8], pos: usize| {
+        let b = data.get(pos..pos + 2)?;
+        Some(u16::from_be_bytes([b[0], b[1]]))
+    };
+    let u32_at = |data: &[u8], pos: usize| {
+        let b = data.get(pos..pos + 4)?;
+        Some(usize::conv(u32::from_be_bytes([b[0], b[1], b[2], b[3]])))
+    };
+
+    let num_base = usize::from(u16_at(colr, 2)?);
+    let base_offset = u32_at(colr, 4)?;
+    let layer_offset = u32_at(colr, 8)?;
+    let num_layers = usize::from(u16_at(colr, 12)?);
+
+    // Base glyph records are sorted by glyph id
+    let (mut lo, mut hi) = (0, num_base);
+    let record = loop {
+        if lo >= hi {
+            return None;
+        }
+        let mid = (lo + hi) / 2;
+        let pos = base_offset + 6 * mid;
+        match u16_at(colr, pos)?.cmp(&id) {
+            Ordering::Less => lo = mid + 1,
+            Ordering::Greater => hi = mid,
+            Ordering::Equal => break pos,
+        }
+    };
+    let first = usize::from(u16_at(colr, record + 2)?);
+    let count = usize::from(u16_at(colr, record + 4)?);
+    if count == 0 || first + count > num_layers {
+        return None;
+    }
+
+    let num_entries = usize::from(u16_at(cpal, 2)?);
+    let num_records = usize::from(u16_at(cpal, 6)?);
+    let records_offset = u32_at(cpal, 8)?;
+    let palette = usize::from(u16_at(cpal, 12)?);
+
+    (first..first + count)
+        .map(|i| {
+            let pos = layer_offset + 4 * i;
+            let glyph = u16_at(colr, pos)?;
+            let index = u16_at(colr, pos + 2)?;
+            if index == 0xFFFF {
+                return Some((glyph, [0, 0, 0, 255]));
+            }
+            let index = usize::from(index);
+            if index >= num_entries || palette + index >= num_records {
+                return None;
+            }
+            let pos = records_offset + 4 * (palette + index);
+            let bgra = cpal.get(pos..pos + 4)?;
+            Some((glyph, [bgra[2], bgra[1], bgra[0], bgra[3]]))
+        })
+        .collect()
+}
+
+/// Decode the colour bitmap of glyph `id`, scaled to `dpem`
+///
+/// Only PNG images from CBDT or sbix tables are supported. Returns the image
+/// and its offset from the glyph origin (with y down), or `None` if the face
+/// has no such image for this glyph.
+#[cfg(feature = "emoji")]
+fn color_glyph_image(
+    face: &ttf_parser::Face,
+    id: u16,
+    dpem: f32,
+) -> Option<(image::RgbaImage, Vec2)> {
+    use image::imageops::{resize, FilterType};
+    use ttf_parser::{GlyphId, RasterImageFormat};
+
+    let ppem = u16::conv_nearest(dpem.max(1.0));
+    let img = face.glyph_raster_image(GlyphId(id), ppem)?;
+    if img.format != RasterImageFormat::PNG {
+        return None;
+    }
+    let data = match image::load_from_memory_with_format(img.data, image::ImageFormat::Png) {
+        Ok(data) => data.into_rgba8(),
+        Err(e) => {
+            log::warn!("text_pipe: failed to decode colour glyph: {}", e);
+            return None;
+        }
+    };
+
+    // Bitmaps are available only at a few sizes: scale to fit dpem
+    let scale = dpem / f32::conv(img.pixels_per_em);
+    let w = u32::conv_nearest(f32::conv(img.width) * scale).max(1);
+    let h = u32::conv_nearest(f32::conv(img.height) * scale).max(1);
+    let data = resize(&data, w, h, FilterType::Triangle);
+    // Image offsets are relative to the bottom-left corner, with y up
+    let x = f32::conv(img.x) * scale;
+    let y = -f32::conv(i32::conv(img.y) + i32::conv(img.height)) * scale;
+    Some((data, Vec2(x, y).round()))
 }
 
 /// Per-window state
 #[derive(Debug, Default)]
 pub struct Window {
     atlas: atlases::Window<Instance>,
+    lcd_mask: atlases::Window<Instance>,
+    lcd_add: atlases::Window<Instance>,
+    color_atlas: atlases::Window<images::Instance>,
+    transparent: bool,
+    lcd: LcdOrder,
     duration: std::time::Duration,
 }
 
@@ -220,6 +701,66 @@ impl Window {
         encoder: &mut wgpu::CommandEncoder,
     ) {
         self.atlas.write_buffers(device, staging_belt, encoder);
+        self.lcd_mask.write_buffers(device, staging_belt, encoder);
+        self.lcd_add.write_buffers(device, staging_belt, encoder);
+        self.color_atlas
+            .write_buffers(device, staging_belt, encoder);
+    }
+
+    /// Add statistics of a pass to `stats`
+    pub fn add_stats(&self, pass: usize, stats: &mut PassStats) {
+        self.atlas.add_stats(pass, stats);
+        self.lcd_mask.add_stats(pass, stats);
+        self.lcd_add.add_stats(pass, stats);
+        self.color_atlas.add_stats(pass, stats);
+    }
+
+    /// Set whether the window background is transparent
+    ///
+    /// Sub-pixel anti-aliasing is disabled for transparent windows since the
+    /// colour behind the window is unknown. Call before [`Self::update_lcd`].
+    pub fn set_transparent(&mut self, transparent: bool) {
+        self.transparent = transparent;
+    }
+
+    /// Resolve sub-pixel anti-aliasing mode for the window's scale factor
+    pub fn update_lcd(&mut self, pipe: &Pipeline, scale_factor: f32) {
+        self.lcd = match self.transparent {
+            true => LcdOrder::None,
+            false => pipe.lcd_order.resolve(scale_factor),
+        };
+    }
+
+    /// Add a glyph sprite at `pos`
+    ///
+    /// Colour glyphs ignore `col`.
+    fn sprite(&mut self, pass: PassId, pos: Vec2, sprite: &Sprite, col: Rgba) {
+        let a = pos + sprite.offset;
+        let b = a + sprite.size;
+        let (ta, tb) = (sprite.tex_quad.a, sprite.tex_quad.b);
+        match sprite.kind {
+            SpriteKind::Mask => {
+                let instance = Instance { a, b, ta, tb, col };
+                self.atlas.rect(pass, sprite.atlas, instance);
+            }
+            SpriteKind::Lcd => {
+                let mask = Rgba::ga(1.0, col.a);
+                let instance = Instance {
+                    a,
+                    b,
+                    ta,
+                    tb,
+                    col: mask,
+                };
+                self.lcd_mask.rect(pass, sprite.atlas, instance);
+                let instance = Instance { a, b, ta, tb, col };
+                self.lcd_add.rect(pass, sprite.atlas, instance);
+            }
+            SpriteKind::Color => {
+                let instance = images::Instance { a, b, ta, tb };
+                self.color_atlas.rect(pass, sprite.atlas, instance);
+            }
+        }
     }
 
     /// Get microseconds used for text during since last call
@@ -238,17 +779,14 @@ impl Window {
         col: Rgba,
     ) {
         let pos = pos.round();
-        let time = std::time::Instant::now();
+        let time = instant::Instant::now();
+        let lcd = self.lcd;
 
         let for_glyph = |face: FaceId, dpem: f32, glyph: Glyph| {
-            if let Some(sprite) = pipe.get_glyph(face, dpem, glyph) {
+            if let Some(sprite) = pipe.get_glyph(lcd, face, dpem, glyph) {
                 let pos = pos + Vec2::from(glyph.position).floor();
-                let a = pos + sprite.offset;
-                let b = a + sprite.size;
-                let (ta, tb) = (sprite.tex_quad.a, sprite.tex_quad.b);
-                let instance = Instance { a, b, ta, tb, col };
                 // TODO(opt): avoid calling repeatedly?
-                self.atlas.rect(pass, sprite.atlas, instance);
+                self.sprite(pass, pos, &sprite, col);
             }
         };
         text.glyphs(for_glyph);
@@ -277,18 +815,15 @@ impl Window {
             return vec![];
         }
 
-        let time = std::time::Instant::now();
+        let time = instant::Instant::now();
+        let lcd = self.lcd;
         let mut rects = vec![];
 
         let mut for_glyph = |face: FaceId, dpem: f32, glyph: Glyph, _: usize, _: ()| {
-            if let Some(sprite) = pipe.get_glyph(face, dpem, glyph) {
+            if let Some(sprite) = pipe.get_glyph(lcd, face, dpem, glyph) {
                 let pos = pos + Vec2::from(glyph.position).floor();
-                let a = pos + sprite.offset;
-                let b = a + sprite.size;
-                let (ta, tb) = (sprite.tex_quad.a, sprite.tex_quad.b);
-                let instance = Instance { a, b, ta, tb, col };
                 // TODO(opt): avoid calling repeatedly?
-                self.atlas.rect(pass, sprite.atlas, instance);
+                self.sprite(pass, pos, &sprite, col);
             }
         };
 
@@ -334,18 +869,15 @@ impl Window {
             return vec![];
         }
 
-        let time = std::time::Instant::now();
+        let time = instant::Instant::now();
+        let lcd = self.lcd;
         let mut rects = vec![];
 
         let for_glyph = |face: FaceId, dpem: f32, glyph: Glyph, _, col: Rgba| {
-            if let Some(sprite) = pipe.get_glyph(face, dpem, glyph) {
+            if let Some(sprite) = pipe.get_glyph(lcd, face, dpem, glyph) {
                 let pos = pos + Vec2::from(glyph.position).floor();
-                let a = pos + sprite.offset;
-                let b = a + sprite.size;
-                let (ta, tb) = (sprite.tex_quad.a, sprite.tex_quad.b);
-                let instance = Instance { a, b, ta, tb, col };
                 // TODO(opt): avoid calling repeatedly?
-                self.atlas.rect(pass, sprite.atlas, instance);
+                self.sprite(pass, pos, &sprite, col);
             }
         };
 
@@ -362,3 +894,90 @@ impl Window {
         rects
     }
 }
+
+fn gamma_lut(gamma: f32) -> Option<[u8; 256]> {
+    if (gamma - 1.0).abs() < 1e-3 || gamma <= 0.0 {
+        return None;
+    }
+    let mut lut = [0; 256];
+    for (i, v) in lut.iter_mut().enumerate() {
+        let c = f32::conv(i) / 255.0;
+        *v = u8::conv_nearest(c.powf(1.0 / gamma) * 255.0);
+    }
+    Some(lut)
+}
+
+#[cfg(all(test, feature = "emoji"))]
+mod test {
+    use super::*;
+
+    // Each test font has a single colour glyph (id 1): an 8x8 red PNG image at
+    // 16 ppem, offset by (1, -2) from the origin (with y up).
+    fn check_font(data: &[u8]) {
+        let face = ttf_parser::Face::from_slice(data, 0).unwrap();
+        assert!(color_glyph_image(&face, 0, 16.0).is_none());
+
+        let (image, offset) = color_glyph_image(&face, 1, 16.0).unwrap();
+        assert_eq!(image.dimensions(), (8, 8));
+        assert_eq!(image.get_pixel(4, 4).0, [255, 0, 0, 255]);
+        assert_eq!(offset, Vec2(1.0, -6.0));
+
+        // Images are scaled to the requested size
+        let (image, offset) = color_glyph_image(&face, 1, 32.0).unwrap();
+        assert_eq!(image.dimensions(), (16, 16));
+        assert_eq!(offset, Vec2(2.0, -12.0));
+    }
+
+    #[test]
+    fn cbdt() {
+        check_font(include_bytes!("../../tests/fonts/cbdt.ttf"));
+    }
+
+    #[test]
+    fn sbix() {
+        check_font(include_bytes!("../../tests/fonts/sbix.ttf"));
+    }
+
+    #[test]
+    fn colr() {
+        #[rustfmt::skip]
+        let colr = [
+            0, 0, // version
+            0, 2, // base glyph records
+            0, 0, 0, 14, // base glyph records offset
+            0, 0, 0, 26, // layer records offset
+            0, 3, // layer records
+            // glyph, first layer, number of layers
+            0, 1, 0, 0, 0, 2,
+            0, 4, 0, 2, 0, 1,
+            // glyph, palette index
+            0, 2, 0, 1,
+            0, 3, 0xFF, 0xFF,
+            0, 5, 0, 2,
+        ];
+        #[rustfmt::skip]
+        let cpal = [
+            0, 0, // version
+            0, 2, // palette entries
+            0, 1, // palettes
+            0, 2, // colour records
+            0, 0, 0, 14, // colour records offset
+            0, 0, // first colour record of palette 0
+            // BGRA
+            0, 0, 255, 255,
+            255, 0, 0, 128,
+        ];
+
+        assert_eq!(
+            colr_layers(&colr, &cpal, 1),
+            Some(vec![(2, [0, 0, 255, 128]), (3, [0, 0, 0, 255])])
+        );
+        assert_eq!(colr_layers(&colr, &cpal, 2), None);
+        assert_eq!(colr_layers(&colr, &cpal, 0), None);
+        // Layer of glyph 4 uses palette index 2: out of range
+        assert_eq!(colr_layers(&colr, &cpal, 4), None);
+        // Truncated tables
+        assert_eq!(colr_layers(&colr[..20], &cpal, 4), None);
+        assert_eq!(colr_layers(&colr, &cpal[..16], 1), None);
+    }
+}
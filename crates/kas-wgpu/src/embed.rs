@@ -0,0 +1,619 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Embedding within an external render loop
+//!
+//! [`Embedded`] drives a single KAS window from an application's own event
+//! and render loop (for example, a game engine or plugin host), without
+//! winit. The application provides the graphics device and queue, forwards
+//! input via [`Embedded::handle_input`], calls [`Embedded::update`] after each
+//! batch of input and renders via [`Embedded::render`] (or
+//! [`Embedded::render_overlay`]) to a texture view of its choice, usually the
+//! current frame of its own surface.
+//!
+//! Render targets must use format [`Embedded::TEXTURE_FORMAT`] and usage
+//! [`wgpu::TextureUsages::RENDER_ATTACHMENT`].
+//!
+//! ```ignore
+//! let theme = kas::theme::FlatTheme::new();
+//! let mut ui = Embedded::new(theme, (device, queue), window, size, 1.0)?;
+//! loop {
+//!     for event in host_events() {
+//!         if let Some(input) = translate(event) {
+//!             ui.handle_input(input);
+//!         }
+//!     }
+//!     ui.update();
+//!     let frame = surface.get_current_texture()?;
+//!     let view = frame.texture.create_view(&Default::default());
+//!     ui.render_overlay(&view);
+//!     frame.present();
+//! }
+//! ```
+//!
+//! Coordinates are in physical pixels relative to the top-left corner of the
+//! rendered area. Pop-ups, additional windows and window controls are not
+//! supported. The cursor icon requested by widgets is available from
+//! [`Embedded::cursor_icon`]; the host is responsible for applying it.
+
+use instant::Instant;
+use log::{debug, trace, warn};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use kas::draw::{DrawIface, PassId};
+use kas::event::{Command, CursorIcon, ManagerState, ModifiersState, MouseButton};
+use kas::event::{ScrollDelta, ScrollSource, UpdateHandle, VirtualKeyCode};
+use kas::geom::{Coord, Rect, Size};
+use kas::layout::SolveCache;
+use kas::{TkAction, WidgetId, WindowId};
+use kas_theme::{Theme, Window as _};
+
+use crate::capture::{create_texture, draw_widget, read_texture};
+use crate::draw::{CustomPipe, CustomPipeBuilder, DrawPipe, DrawWindow};
+use crate::shared::{PendingAction, SharedState};
+use crate::startup::StartupTimings;
+use crate::window::{to_wgpu_color, TkWindow};
+use crate::{warn_about_error, Error, Options};
+
+/// An input event, for use with [`Embedded::handle_input`]
+///
+/// Touch input is not yet supported.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Input {
+    /// The mouse cursor moved to the given coordinate
+    CursorMoved(Coord),
+    /// The mouse cursor left the rendered area
+    CursorLeft,
+    /// A mouse button was pressed (`true`) or released
+    ///
+    /// The press occurs at the last coordinate passed via
+    /// [`Input::CursorMoved`].
+    MouseInput(bool, MouseButton),
+    /// A mouse wheel or touchpad scroll
+    Scroll(ScrollDelta, ScrollSource),
+    /// A key was pressed, with the given scancode
+    ///
+    /// The scancode is used to match the corresponding release. Text input
+    /// should be reported separately via [`Input::ReceivedChar`].
+    KeyPress(VirtualKeyCode, u32),
+    /// A key was released, with the given scancode
+    KeyRelease(u32),
+    /// A character was received
+    ReceivedChar(char),
+    /// The state of modifier keys changed
+    Modifiers(ModifiersState),
+    /// The UI lost keyboard focus
+    FocusLost,
+    /// A command, bypassing shortcut resolution
+    Command(Command),
+}
+
+/// A KAS window embedded in an external render loop
+///
+/// See the [module documentation](self).
+///
+/// Like the [`crate::Toolkit`], construction initialises the font database.
+pub struct Embedded<C: CustomPipe, T: Theme<DrawPipe<C>>> {
+    shared: SharedState<C, T>,
+    id: WindowId,
+    widget: Box<dyn kas::Window>,
+    mgr: ManagerState,
+    solve_cache: SolveCache,
+    size: Size,
+    draw: DrawWindow<C::Window>,
+    theme_window: T::Window,
+    captures: Vec<kas::CaptureRequest>,
+    redraw_requested: bool,
+    closed: bool,
+}
+
+impl<T: Theme<DrawPipe<()>> + 'static> Embedded<(), T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct with default options
+    ///
+    /// The `device` and `queue` are used for all rendering; they may be
+    /// accessed later via [`Embedded::device`] and [`Embedded::queue`].
+    /// The `window` is laid out to `size` (in physical pixels) using the given
+    /// `scale_factor`.
+    ///
+    /// Environment variables may affect option selection; see documentation
+    /// of [`Options::from_env`]. KAS config is provided by
+    /// [`Options::read_config`].
+    #[inline]
+    pub fn new(
+        theme: T,
+        device_and_queue: (wgpu::Device, wgpu::Queue),
+        window: Box<dyn kas::Window>,
+        size: Size,
+        scale_factor: f64,
+    ) -> Result<Self, Error> {
+        let options = Options::from_env();
+        Self::new_custom(
+            (),
+            theme,
+            options,
+            device_and_queue,
+            window,
+            size,
+            scale_factor,
+        )
+    }
+}
+
+impl<C: CustomPipe, T: Theme<DrawPipe<C>> + 'static> Embedded<C, T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Render target format
+    pub const TEXTURE_FORMAT: wgpu::TextureFormat = crate::draw::RENDER_TEX_FORMAT;
+
+    /// Construct an instance with custom options
+    ///
+    /// See [`Embedded::new`]. The device should be constructed with (at
+    /// least) the features and limits of
+    /// [`CustomPipeBuilder::device_descriptor`].
+    pub fn new_custom<CB: CustomPipeBuilder<Pipe = C>>(
+        custom: CB,
+        mut theme: T,
+        options: Options,
+        device_and_queue: (wgpu::Device, wgpu::Queue),
+        mut window: Box<dyn kas::Window>,
+        size: Size,
+        scale_factor: f64,
+    ) -> Result<Self, Error> {
+        let mut startup = StartupTimings::new();
+        options.init_theme_config(&mut theme)?;
+        let config = match options.read_config() {
+            Ok(config) => config,
+            Err(error) => {
+                warn_about_error("Failed to read config", &error);
+                Default::default()
+            }
+        };
+        let config = Rc::new(RefCell::new(config));
+        startup.config = startup.start.elapsed();
+
+        let mut shared = SharedState::with_device(
+            custom,
+            theme,
+            options,
+            config,
+            scale_factor,
+            device_and_queue,
+            startup,
+        );
+        let id = shared.next_window_id();
+        let sf = scale_factor as f32;
+        let mut theme_window = shared.theme.new_window(sf);
+        let mut draw = shared.draw.draw.new_window();
+        shared.draw.draw.update_lcd(&mut draw, sf);
+        if shared.options.draw_stats {
+            shared.draw.draw.enable_stats(&mut draw);
+        }
+
+        let mut mgr = ManagerState::new(shared.config.clone(), sf);
+        let mut tkw = TkWindow::new(&mut shared, id, None, &mut theme_window);
+        mgr.configure(&mut tkw, &mut *window);
+
+        let mut size_handle = theme_window.size_handle();
+        let solve_cache = SolveCache::find_constraints(window.as_widget_mut(), &mut size_handle);
+
+        let mut ui = Embedded {
+            shared,
+            id,
+            widget: window,
+            mgr,
+            solve_cache,
+            size: Size::ZERO,
+            draw,
+            theme_window,
+            captures: vec![],
+            redraw_requested: true,
+            closed: false,
+        };
+        ui.resize(size);
+        Ok(ui)
+    }
+
+    /// Replace the window
+    ///
+    /// The new window is laid out to `size` (in physical pixels) using the
+    /// given `scale_factor`. Returns the previous window.
+    pub fn set_window(
+        &mut self,
+        window: Box<dyn kas::Window>,
+        size: Size,
+        scale_factor: f64,
+    ) -> Box<dyn kas::Window> {
+        let time = Instant::now();
+        let old = std::mem::replace(&mut self.widget, window);
+        let shared = &mut self.shared;
+        shared.scale_factor = scale_factor;
+        let scale_factor = scale_factor as f32;
+        shared
+            .theme
+            .update_window(&mut self.theme_window, scale_factor);
+        shared.draw.draw.update_lcd(&mut self.draw, scale_factor);
+
+        self.mgr = ManagerState::new(shared.config.clone(), scale_factor);
+        let mut tkw = TkWindow::new(shared, self.id, None, &mut self.theme_window);
+        self.mgr.configure(&mut tkw, &mut *self.widget);
+
+        let mut size_handle = self.theme_window.size_handle();
+        let widget = self.widget.as_widget_mut();
+        self.solve_cache = SolveCache::find_constraints(widget, &mut size_handle);
+        self.closed = false;
+        self.size = Size::ZERO;
+        self.resize(size);
+
+        trace!(
+            "Embedded::set_window completed in {}µs",
+            time.elapsed().as_micros()
+        );
+        old
+    }
+
+    /// Access the theme by ref
+    #[inline]
+    pub fn theme(&self) -> &T {
+        &self.shared.theme
+    }
+
+    /// Access the theme by ref mut
+    #[inline]
+    pub fn theme_mut(&mut self) -> &mut T {
+        &mut self.shared.theme
+    }
+
+    /// Access the graphics device
+    #[inline]
+    pub fn device(&self) -> &wgpu::Device {
+        &self.shared.draw.draw.gpu().device
+    }
+
+    /// Access the graphics queue
+    #[inline]
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.shared.draw.draw.gpu().queue
+    }
+
+    /// Access the window
+    #[inline]
+    pub fn window(&self) -> &dyn kas::Window {
+        &*self.widget
+    }
+
+    /// Get the size, in physical pixels
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get the minimum and ideal size, in physical pixels
+    #[inline]
+    pub fn size_hints(&self) -> (Size, Size) {
+        (self.solve_cache.min(true), self.solve_cache.ideal(true))
+    }
+
+    /// Get the cursor icon requested by widgets
+    #[inline]
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.shared.cursor_icon
+    }
+
+    /// True if a redraw is required
+    ///
+    /// This is set by input, updates and resizing, and cleared by rendering.
+    #[inline]
+    pub fn needs_redraw(&self) -> bool {
+        self.redraw_requested
+    }
+
+    /// True if the window was closed
+    ///
+    /// A widget (or the shortcut for [`Command::Close`]) may request that the
+    /// window be closed. The host may remove or replace the window (see
+    /// [`Embedded::set_window`]) or ignore this.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Resize, in physical pixels
+    pub fn resize(&mut self, size: Size) {
+        let size = size.max(Size(1, 1));
+        if size == self.size {
+            return;
+        }
+        self.size = size;
+        self.shared.draw.draw.resize(&mut self.draw, size);
+        self.apply_size();
+    }
+
+    /// Change the scale factor
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        let shared = &mut self.shared;
+        shared.scale_factor = scale_factor;
+        let scale_factor = scale_factor as f32;
+        let mut tkw = TkWindow::new(shared, self.id, None, &mut self.theme_window);
+        self.mgr
+            .handle_scale_factor_changed(&mut tkw, &mut *self.widget, scale_factor);
+        shared
+            .theme
+            .update_window(&mut self.theme_window, scale_factor);
+        shared.draw.draw.update_lcd(&mut self.draw, scale_factor);
+        self.solve_cache.invalidate_rule_cache();
+        self.apply_size();
+    }
+
+    /// Handle an input event
+    ///
+    /// Call [`Embedded::update`] after each batch of input.
+    pub fn handle_input(&mut self, input: Input) {
+        let mut tkw = TkWindow::new(&mut self.shared, self.id, None, &mut self.theme_window);
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| match input {
+            Input::CursorMoved(coord) => mgr.handle_cursor_moved(widget, coord),
+            Input::CursorLeft => mgr.handle_cursor_left(widget),
+            Input::MouseInput(pressed, button) => mgr.handle_mouse_input(widget, pressed, button),
+            Input::Scroll(delta, source) => mgr.handle_scroll(widget, delta, source),
+            Input::KeyPress(vkey, scancode) => mgr.handle_key_press(widget, vkey, scancode),
+            Input::KeyRelease(scancode) => mgr.handle_key_release(scancode),
+            Input::ReceivedChar(c) => mgr.handle_received_char(widget, c),
+            Input::Modifiers(state) => mgr.handle_modifiers(state),
+            Input::FocusLost => mgr.handle_focus_lost(),
+            Input::Command(cmd) => {
+                mgr.handle_command(widget, cmd);
+            }
+        });
+    }
+
+    /// Trigger an update handle
+    pub fn trigger_update(&mut self, handle: UpdateHandle, payload: u64) {
+        self.shared.trigger_update(handle, payload);
+    }
+
+    /// Update, after receiving all events
+    ///
+    /// This handles timers and pending actions. It should be called after
+    /// each batch of input and at least as often as the returned time, if
+    /// any (for example once per frame).
+    ///
+    /// Returns the time at which an update is next required, if any.
+    pub fn update(&mut self) -> Option<Instant> {
+        if self.shared.warm_up_pending {
+            // The first frame has been presented: construct remaining pipelines
+            self.shared.warm_up();
+        }
+
+        let mut tkw = TkWindow::new(&mut self.shared, self.id, None, &mut self.theme_window);
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| mgr.update_timer(widget));
+        let action = self.mgr.update(&mut tkw, &mut *self.widget);
+        self.handle_action(action);
+        self.handle_pending();
+
+        self.mgr.next_resume()
+    }
+
+    /// Render to `frame_view`, clearing with the theme's background colour
+    ///
+    /// The target must use format [`Embedded::TEXTURE_FORMAT`] and should have
+    /// the size last passed to [`Embedded::resize`].
+    pub fn render(&mut self, frame_view: &wgpu::TextureView) {
+        let clear_color = to_wgpu_color(self.shared.theme.clear_color());
+        self.render_impl(frame_view, Some(clear_color));
+    }
+
+    /// Render to `frame_view`, over existing contents
+    ///
+    /// This is like [`Embedded::render`], except that the target is not
+    /// cleared, allowing use as an overlay over the host's own rendering.
+    /// Note that the theme draws an opaque background only where widgets
+    /// do so. Background blur (used by some themes for pop-ups) does not
+    /// blur the host's contents.
+    pub fn render_overlay(&mut self, frame_view: &wgpu::TextureView) {
+        self.render_impl(frame_view, None);
+    }
+
+    fn render_impl(&mut self, frame_view: &wgpu::TextureView, clear: Option<wgpu::Color>) {
+        let time = Instant::now();
+        self.draw_widgets();
+        self.shared.render(&mut self.draw, frame_view, clear);
+        if let Some(stats) = self.draw.stats() {
+            self.mgr.set_draw_stats(stats.clone());
+        }
+
+        let presented = Instant::now();
+        self.mgr.frame_presented(presented, None);
+        self.shared.frame_presented(presented);
+        self.redraw_requested = false;
+        trace!(
+            "Embedded::render completed in {}µs",
+            time.elapsed().as_micros()
+        );
+
+        if !self.captures.is_empty() {
+            self.do_captures();
+        }
+    }
+
+    fn draw_widgets(&mut self) {
+        let shared = &mut self.shared;
+        let draw = DrawIface {
+            draw: &mut self.draw,
+            shared: &mut shared.draw,
+            pass: PassId::new(0),
+        };
+
+        #[cfg(not(feature = "gat"))]
+        unsafe {
+            // Safety: lifetimes do not escape the returned draw_handle value.
+            let mut draw_handle = shared.theme.draw_handle(draw, &mut self.theme_window);
+            self.widget.draw(&mut draw_handle, &self.mgr, false);
+            self.solve_cache
+                .draw_overflow(self.widget.rect(), &mut draw_handle);
+            self.mgr
+                .draw_inspector(self.widget.as_widget(), &mut draw_handle);
+            self.mgr
+                .draw_toasts(self.widget.as_widget(), &mut draw_handle);
+            self.mgr
+                .draw_tooltip(self.widget.as_widget(), &mut draw_handle);
+        }
+        #[cfg(feature = "gat")]
+        {
+            let mut draw_handle = shared.theme.draw_handle(draw, &mut self.theme_window);
+            self.widget.draw(&mut draw_handle, &self.mgr, false);
+            self.solve_cache
+                .draw_overflow(self.widget.rect(), &mut draw_handle);
+            self.mgr
+                .draw_inspector(self.widget.as_widget(), &mut draw_handle);
+            self.mgr
+                .draw_toasts(self.widget.as_widget(), &mut draw_handle);
+            self.mgr
+                .draw_tooltip(self.widget.as_widget(), &mut draw_handle);
+        }
+    }
+
+    fn handle_action(&mut self, action: TkAction) {
+        if action.contains(TkAction::CLOSE | TkAction::EXIT) {
+            self.closed = true;
+            return;
+        }
+        if action.contains(TkAction::RECONFIGURE) {
+            let mut tkw = TkWindow::new(&mut self.shared, self.id, None, &mut self.theme_window);
+            self.mgr.configure(&mut tkw, &mut *self.widget);
+            self.solve_cache.invalidate_rule_cache();
+            self.apply_size();
+        }
+        if action.contains(TkAction::THEME_UPDATE) {
+            self.mgr.theme_changed();
+            let scale_factor = self.shared.scale_factor as f32;
+            self.shared
+                .theme
+                .update_window(&mut self.theme_window, scale_factor);
+        }
+        if action.contains(TkAction::RESIZE) {
+            self.solve_cache.invalidate_rule_cache();
+            self.apply_size();
+        } else if action.contains(TkAction::SET_SIZE) {
+            self.apply_size();
+        }
+        if action.contains(TkAction::REGION_MOVED) {
+            let mut tkw = TkWindow::new(&mut self.shared, self.id, None, &mut self.theme_window);
+            self.mgr.region_moved(&mut tkw, &mut *self.widget);
+            self.redraw_requested = true;
+        } else if action.contains(TkAction::REDRAW) {
+            self.redraw_requested = true;
+        }
+    }
+
+    fn apply_size(&mut self) {
+        let rect = Rect::new(Coord::ZERO, self.size);
+        debug!("Resizing embedded window to rect = {:?}", rect);
+
+        let mut tkw = TkWindow::new(&mut self.shared, self.id, None, &mut self.theme_window);
+        self.mgr.update_window_size(rect.size);
+        let solve_cache = &mut self.solve_cache;
+        let widget = &mut self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            solve_cache.apply_rect(widget.as_widget_mut(), mgr, rect, true);
+            widget.resize_popups(mgr);
+        });
+        self.redraw_requested = true;
+    }
+
+    fn handle_pending(&mut self) {
+        self.shared.queue_dropped_captures();
+        while !self.shared.pending.is_empty() {
+            let batch = std::mem::take(&mut self.shared.pending);
+            for pending in kas_shell::coalesce(batch, &mut self.shared.stats) {
+                self.handle_pending_item(pending);
+            }
+        }
+    }
+
+    fn handle_pending_item(&mut self, pending: PendingAction) {
+        match pending {
+            PendingAction::AddPopup(..) => {
+                // Unreachable: TkWindow does not add pop-ups without a window
+                warn!("Embedded: pop-ups are not supported");
+            }
+            PendingAction::AddWindow(_, widget) => {
+                warn!("Embedded: cannot add window {}", widget.title());
+            }
+            PendingAction::CloseWindow(id) => {
+                if id == self.id {
+                    self.closed = true;
+                }
+            }
+            PendingAction::Capture(request) => {
+                self.captures.push(request);
+                self.redraw_requested = true;
+            }
+            PendingAction::TkAction(action) => {
+                self.shared.stats.actions_applied += 1;
+                if action.contains(TkAction::REDRAW) {
+                    // The theme may have changed
+                    self.mgr.theme_changed();
+                }
+                self.mgr.send_action(action);
+            }
+            PendingAction::Update(handle, payload) => {
+                let mut tkw =
+                    TkWindow::new(&mut self.shared, self.id, None, &mut self.theme_window);
+                let widget = &mut *self.widget;
+                self.mgr.with(&mut tkw, |mgr| {
+                    mgr.update_handle(widget, handle, payload);
+                });
+            }
+        }
+    }
+
+    /// Handle pending capture requests
+    fn do_captures(&mut self) {
+        let size = self.size;
+        let texture = create_texture(self.device(), size);
+        let view = texture.create_view(&Default::default());
+        let clear_color = to_wgpu_color(self.shared.theme.clear_color());
+
+        for request in std::mem::take(&mut self.captures) {
+            let size = match request.widget {
+                None => {
+                    self.draw_widgets();
+                    Some(size)
+                }
+                Some(id) => self.draw_widget(id).map(|rect| rect.size.min(size)),
+            };
+            let shared = &mut self.shared;
+            let image = size.and_then(|size| {
+                shared.render(&mut self.draw, &view, Some(clear_color));
+                match read_texture(&shared.draw.draw, &texture, size) {
+                    Ok(image) => Some(image),
+                    Err(e) => {
+                        warn_about_error("Embedded: capture failed", &e);
+                        None
+                    }
+                }
+            });
+            let handle = request.finish(image);
+            shared.trigger_update(handle, 0);
+        }
+    }
+
+    fn draw_widget(&mut self, id: WidgetId) -> Option<Rect> {
+        draw_widget(
+            &mut self.shared,
+            &mut self.draw,
+            &mut self.theme_window,
+            &self.mgr,
+            &mut *self.widget,
+            id,
+        )
+    }
+}
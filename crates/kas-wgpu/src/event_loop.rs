@@ -5,10 +5,10 @@
 
 //! Event loop and handling
 
-use log::{debug, error};
+use instant::Instant;
+use log::{debug, error, trace, warn};
 use smallvec::SmallVec;
 use std::collections::HashMap;
-use std::time::Instant;
 
 use winit::event::{Event, StartCause};
 use winit::event_loop::{ControlFlow, EventLoopWindowTarget};
@@ -18,8 +18,9 @@ use kas::TkAction;
 use kas_theme::Theme;
 
 use crate::draw::{CustomPipe, DrawPipe};
+use crate::recorder::{RecordedEvent, Recorder, Recording, Replayer};
 use crate::shared::{PendingAction, SharedState};
-use crate::{ProxyAction, Window, WindowId};
+use crate::{warn_about_error, ProxyAction, Window, WindowId};
 
 /// Event-loop data structure (i.e. all run-time state)
 pub(crate) struct Loop<C: CustomPipe, T: Theme<DrawPipe<C>>>
@@ -34,6 +35,10 @@ where
     shared: SharedState<C, T>,
     /// Timer resumes: (time, window index)
     resumes: Vec<(Instant, ww::WindowId)>,
+    /// Event recorder, if enabled
+    recorder: Option<Recorder>,
+    /// Event replay, if enabled
+    replayer: Option<Replayer>,
 }
 
 impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Loop<C, T>
@@ -45,11 +50,28 @@ where
             .iter()
             .map(|w| (w.window_id, w.window.id()))
             .collect();
+
+        let start = Instant::now();
+        let options = &shared.options;
+        let recorder = options
+            .record_path
+            .clone()
+            .map(|path| Recorder::new(start, path));
+        let mut replayer = None;
+        if let Some(path) = options.replay_path.as_ref() {
+            match Recording::read_path(path) {
+                Ok(recording) => replayer = Some(Replayer::new(start, recording)),
+                Err(error) => warn_about_error("Failed to read event recording", &error),
+            }
+        }
+
         Loop {
             windows: windows.drain(..).map(|w| (w.window.id(), w)).collect(),
             id_map,
             shared,
             resumes: vec![],
+            recorder,
+            replayer,
         }
     }
 
@@ -64,6 +86,24 @@ where
         match event {
             WindowEvent { window_id, event } => {
                 if let Some(window) = self.windows.get_mut(&window_id) {
+                    if self.replayer.is_some() {
+                        // Ignore user input while replaying
+                        let recorded = RecordedEvent::from_winit(&event);
+                        if recorded.map(|ev| ev.is_input()).unwrap_or(false) {
+                            return;
+                        }
+                    }
+                    if let Some(recorder) = self.recorder.as_mut() {
+                        recorder.record(window.window_id, &event);
+                    }
+                    #[cfg(feature = "tray")]
+                    if let winit::event::WindowEvent::Focused(false) = event {
+                        let menu = self.shared.tray.as_ref().and_then(|t| t.menu_window);
+                        if menu == Some(window.window_id) {
+                            // The tray menu closes on loss of focus
+                            window.send_action(TkAction::CLOSE);
+                        }
+                    }
                     window.handle_event(&mut self.shared, event);
                 }
             }
@@ -87,6 +127,21 @@ where
                         .pending
                         .push(PendingAction::Update(handle, payload));
                 }
+                #[cfg(feature = "tray")]
+                ProxyAction::Tray(action) => self.handle_tray(elwt, action),
+                #[cfg(target_arch = "wasm32")]
+                ProxyAction::AnimationFrame(id) => {
+                    if let Some(window) = self.windows.get(&id) {
+                        window.window.request_redraw();
+                    }
+                }
+                #[cfg(target_arch = "wasm32")]
+                ProxyAction::Paste(id) => {
+                    if let Some(window) = self.windows.get_mut(&id) {
+                        window.repeat_paste(&mut self.shared);
+                    }
+                    self.shared.discard_clipboard();
+                }
             },
 
             NewEvents(cause) => {
@@ -99,24 +154,22 @@ where
                     } => {
                         debug!("Wakeup: timer (requested: {:?})", requested_resume);
 
-                        let item = self
-                            .resumes
-                            .first()
-                            .cloned()
-                            .unwrap_or_else(|| panic!("timer wakeup without resume"));
-                        assert_eq!(item.0, requested_resume);
-
-                        let resume = if let Some(w) = self.windows.get_mut(&item.1) {
-                            w.update_timer(&mut self.shared)
-                        } else {
-                            // presumably, some window with active timers was removed
-                            None
-                        };
+                        let item = self.resumes.first().cloned();
+                        if let Some(item) = item.filter(|item| item.0 == requested_resume) {
+                            let resume = if let Some(w) = self.windows.get_mut(&item.1) {
+                                w.update_timer(&mut self.shared)
+                            } else {
+                                // presumably, some window with active timers was removed
+                                None
+                            };
 
-                        if let Some(instant) = resume {
-                            self.resumes[0].0 = instant;
-                        } else {
-                            self.resumes.remove(0);
+                            if let Some(instant) = resume {
+                                self.resumes[0].0 = instant;
+                            } else {
+                                self.resumes.remove(0);
+                            }
+                        } else if self.replayer.is_none() {
+                            panic!("timer wakeup without resume");
                         }
                     }
                     StartCause::WaitCancelled { .. } => {
@@ -128,6 +181,8 @@ where
                         debug!("Wakeup: init");
                     }
                 }
+
+                self.replay_events();
             }
 
             MainEventsCleared => {
@@ -175,13 +230,25 @@ where
 
                 self.resumes.sort_by_key(|item| item.0);
 
+                let replay = self.replayer.as_ref().and_then(|r| r.next_time());
+                let resume = self.resumes.first().map(|item| item.0);
+                let resume = match (resume, replay) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+
                 *control_flow = if *control_flow == ControlFlow::Exit || self.windows.is_empty() {
                     self.shared.on_exit();
+                    if let Some(recorder) = self.recorder.as_ref() {
+                        if let Err(error) = recorder.save() {
+                            warn_about_error("Failed to save event recording", &error);
+                        }
+                    }
                     ControlFlow::Exit
                 } else if *control_flow == ControlFlow::Poll {
                     ControlFlow::Poll
-                } else if let Some((instant, _)) = self.resumes.first() {
-                    ControlFlow::WaitUntil(*instant)
+                } else if let Some(instant) = resume {
+                    ControlFlow::WaitUntil(instant)
                 } else {
                     ControlFlow::Wait
                 };
@@ -193,61 +260,206 @@ where
                 }
             }
 
-            RedrawEventsCleared | LoopDestroyed | Suspended | Resumed => return,
+            RedrawEventsCleared => {
+                if self.shared.warm_up_pending {
+                    // The first frame has been presented: construct remaining pipelines
+                    self.shared.warm_up();
+                }
+                return;
+            }
+
+            LoopDestroyed | Suspended | Resumed => return,
         };
 
-        // Create and init() any new windows.
-        while let Some(pending) = self.shared.pending.pop() {
-            match pending {
-                PendingAction::AddPopup(parent_id, id, popup) => {
-                    debug!("Adding overlay");
-                    // TODO: support pop-ups as a special window, where available
-                    self.windows.get_mut(&parent_id).unwrap().add_popup(
-                        &mut self.shared,
-                        id,
-                        popup,
-                    );
-                    self.id_map.insert(id, parent_id);
-                }
-                PendingAction::AddWindow(id, widget) => {
-                    debug!("Adding window {}", widget.title());
-                    match Window::new(&mut self.shared, elwt, id, widget) {
-                        Ok(window) => {
-                            let wid = window.window.id();
-                            self.id_map.insert(id, wid);
-                            self.windows.insert(wid, window);
-                        }
-                        Err(e) => {
-                            error!("Unable to create window: {}", e);
-                        }
-                    };
+        self.handle_pending(elwt, control_flow);
+    }
+
+    /// Handle pending actions
+    ///
+    /// Actions are handled in the order queued, after coalescing each batch
+    /// (see [`kas_shell::coalesce`]).
+    fn handle_pending(
+        &mut self,
+        elwt: &EventLoopWindowTarget<ProxyAction>,
+        control_flow: &mut ControlFlow,
+    ) {
+        // Captures may be dropped e.g. when their window is closed
+        self.shared.queue_dropped_captures();
+        while !self.shared.pending.is_empty() {
+            let batch = std::mem::take(&mut self.shared.pending);
+            for pending in kas_shell::coalesce(batch, &mut self.shared.stats) {
+                self.handle_pending_item(elwt, control_flow, pending);
+            }
+        }
+        trace!("Pending action counters: {:?}", self.shared.stats);
+    }
+
+    fn handle_pending_item(
+        &mut self,
+        elwt: &EventLoopWindowTarget<ProxyAction>,
+        control_flow: &mut ControlFlow,
+        pending: PendingAction,
+    ) {
+        match pending {
+            PendingAction::AddPopup(parent_id, id, popup) => {
+                debug!("Adding overlay");
+                // TODO: support pop-ups as a special window, where available
+                self.windows
+                    .get_mut(&parent_id)
+                    .unwrap()
+                    .add_popup(&mut self.shared, id, popup);
+                self.id_map.insert(id, parent_id);
+            }
+            PendingAction::AddWindow(id, widget) => {
+                debug!("Adding window {}", widget.title());
+                match Window::new(&mut self.shared, elwt, id, widget) {
+                    Ok(window) => {
+                        let wid = window.window.id();
+                        self.id_map.insert(id, wid);
+                        self.windows.insert(wid, window);
+                    }
+                    Err(e) => {
+                        error!("Unable to create window: {}", e);
+                    }
+                };
+            }
+            PendingAction::CloseWindow(id) => {
+                if let Some(wwid) = self.id_map.get(&id) {
+                    if let Some(window) = self.windows.get_mut(wwid) {
+                        window.send_close(&mut self.shared, id);
+                    }
+                    self.id_map.remove(&id);
                 }
-                PendingAction::CloseWindow(id) => {
-                    if let Some(wwid) = self.id_map.get(&id) {
-                        if let Some(window) = self.windows.get_mut(wwid) {
-                            window.send_close(&mut self.shared, id);
-                        }
-                        self.id_map.remove(&id);
+            }
+            PendingAction::Capture(request) => {
+                let wwid = request.window.and_then(|id| self.id_map.get(&id));
+                match wwid.and_then(|wwid| self.windows.get_mut(wwid)) {
+                    Some(window) => window.request_capture(request),
+                    None => {
+                        let handle = request.finish(None);
+                        self.shared.trigger_update(handle, 0);
                     }
                 }
-                PendingAction::TkAction(action) => {
-                    if action.contains(TkAction::CLOSE | TkAction::EXIT) {
-                        for (_, window) in self.windows.drain() {
-                            let _ = window.handle_closure(&mut self.shared);
-                        }
-                        *control_flow = ControlFlow::Poll;
-                    } else {
-                        for (_, window) in self.windows.iter_mut() {
-                            window.handle_action(&mut self.shared, action);
-                        }
+            }
+            PendingAction::TkAction(action) => {
+                if action.contains(TkAction::CLOSE | TkAction::EXIT) {
+                    for (_, window) in self.windows.drain() {
+                        let _ = window.handle_closure(&mut self.shared);
+                    }
+                    *control_flow = ControlFlow::Poll;
+                } else if !action.is_empty() {
+                    self.shared.stats.actions_applied += 1;
+                    for (_, window) in self.windows.iter_mut() {
+                        window.handle_shared_action(&mut self.shared, action);
                     }
                 }
-                PendingAction::Update(handle, payload) => {
-                    for window in self.windows.values_mut() {
-                        window.update_handle(&mut self.shared, handle, payload);
+            }
+            PendingAction::Update(handle, payload) => {
+                for window in self.windows.values_mut() {
+                    window.update_handle(&mut self.shared, handle, payload);
+                }
+            }
+        }
+    }
+
+    /// Handle an action from the tray icon
+    #[cfg(feature = "tray")]
+    fn handle_tray(
+        &mut self,
+        elwt: &EventLoopWindowTarget<ProxyAction>,
+        action: crate::tray::TrayAction,
+    ) {
+        use crate::tray::{TrayAction, TrayEvent};
+        let tray = match self.shared.tray.as_mut() {
+            Some(tray) => tray,
+            None => return,
+        };
+
+        let (x, y) = match action {
+            TrayAction::Activate(x, y) => (x, y),
+            TrayAction::SecondaryActivate => {
+                if let Some((handle, payload)) = tray.event(TrayEvent::SecondaryActivate) {
+                    self.shared.trigger_update(handle, payload);
+                }
+                return;
+            }
+        };
+
+        // Activation toggles the menu, if open
+        if let Some(id) = tray.menu_window.take() {
+            if let Some(window) = self.id_map.get(&id).and_then(|id| self.windows.get_mut(id)) {
+                window.send_action(TkAction::CLOSE);
+                return;
+            }
+        }
+
+        let widget = match tray.build_menu() {
+            Some(widget) => widget,
+            None => {
+                if let Some((handle, payload)) = tray.event(TrayEvent::Activate) {
+                    self.shared.trigger_update(handle, payload);
+                }
+                return;
+            }
+        };
+
+        debug!("Opening tray menu");
+        let id = self.shared.next_window_id();
+        match Window::new(&mut self.shared, elwt, id, widget) {
+            Ok(window) => {
+                window.window.set_decorations(false);
+                window.window.set_always_on_top(true);
+                window
+                    .window
+                    .set_outer_position(winit::dpi::PhysicalPosition::new(x, y));
+                let wid = window.window.id();
+                self.id_map.insert(id, wid);
+                self.windows.insert(wid, window);
+                if let Some(tray) = self.shared.tray.as_mut() {
+                    tray.menu_window = Some(id);
+                }
+            }
+            Err(e) => {
+                error!("Unable to create tray menu: {}", e);
+            }
+        }
+    }
+
+    /// Dispatch all due replayed events
+    fn replay_events(&mut self) {
+        let replayer = match self.replayer.as_mut() {
+            Some(replayer) => replayer,
+            None => return,
+        };
+
+        let now = Instant::now();
+        while let Some(entry) = replayer.pop_due(now) {
+            let window = self
+                .id_map
+                .get(&entry.window)
+                .and_then(|id| self.windows.get_mut(id));
+            let window = match window {
+                Some(window) => window,
+                None => {
+                    warn!("Replay: window {:?} not found", entry.window);
+                    continue;
+                }
+            };
+
+            match entry.event.to_winit() {
+                Some(event) => {
+                    if let Some(recorder) = self.recorder.as_mut() {
+                        recorder.record(entry.window, &event);
                     }
+                    window.handle_event(&mut self.shared, event);
                 }
+                None => Replayer::apply_to_window(&window.window, &entry.event),
             }
         }
+
+        if replayer.next_time().is_none() {
+            // Replay is complete: resume handling of user input
+            self.replayer = None;
+        }
     }
 }
@@ -0,0 +1,84 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Window framebuffer for the software renderer
+//!
+//! Frames are rastered to a [`Pixmap`], then presented via `softbuffer`.
+
+use kas::cast::Cast;
+use kas::geom::Size;
+use kas::raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+use log::warn;
+use softbuffer::GraphicsContext;
+use tiny_skia::Pixmap;
+
+/// A copy of a window's raw handle
+struct Handle(RawWindowHandle);
+
+// Safety: the handle is only used while the window exists (see Framebuffer::new)
+unsafe impl HasRawWindowHandle for Handle {
+    fn raw_window_handle(&self) -> RawWindowHandle {
+        self.0
+    }
+}
+
+/// Framebuffer of a window
+pub(crate) struct Framebuffer {
+    context: GraphicsContext<Handle>,
+    pixmap: Pixmap,
+    buffer: Vec<u32>,
+}
+
+impl Framebuffer {
+    /// Construct for `window`
+    ///
+    /// Safety: the `window` must outlive the returned value.
+    pub unsafe fn new(window: &winit::window::Window, size: Size) -> Option<Self> {
+        let handle = Handle(window.raw_window_handle());
+        let context = match GraphicsContext::new(handle) {
+            Ok(context) => context,
+            Err(_) => {
+                warn!("Failed to create a software framebuffer for the window");
+                return None;
+            }
+        };
+        let (w, h) = Self::dims(size);
+        Some(Framebuffer {
+            context,
+            pixmap: Pixmap::new(w, h)?,
+            buffer: vec![],
+        })
+    }
+
+    /// Dimensions (at least 1×1 and at most `u16::MAX` on each side)
+    fn dims(size: Size) -> (u32, u32) {
+        let clamp = |x: i32| x.clamp(1, u16::MAX.into()).cast();
+        (clamp(size.0), clamp(size.1))
+    }
+
+    /// Resize
+    pub fn resize(&mut self, size: Size) {
+        let (w, h) = Self::dims(size);
+        if (w, h) != (self.pixmap.width(), self.pixmap.height()) {
+            self.pixmap = Pixmap::new(w, h).unwrap();
+        }
+    }
+
+    /// Access the pixmap to render to
+    pub fn pixmap_mut(&mut self) -> &mut Pixmap {
+        &mut self.pixmap
+    }
+
+    /// Present the contents of the pixmap
+    pub fn present(&mut self) {
+        // Pixels are presented as opaque 0RGB
+        self.buffer.clear();
+        self.buffer.extend(self.pixmap.pixels().iter().map(|p| {
+            (u32::from(p.red()) << 16) | (u32::from(p.green()) << 8) | u32::from(p.blue())
+        }));
+        let (w, h) = (self.pixmap.width(), self.pixmap.height());
+        self.context.set_buffer(&self.buffer, w.cast(), h.cast());
+    }
+}
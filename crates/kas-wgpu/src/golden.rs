@@ -0,0 +1,278 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Golden-image assertions
+//!
+//! These utilities render a window via the [`Headless`] shell and compare the
+//! result against a reference ("golden") PNG image, allowing changes to
+//! themes and the draw pipeline to be caught by tests:
+//! ```no_run
+//! # use kas::Window;
+//! # fn window() -> Box<dyn Window> { unimplemented!() }
+//! kas_wgpu::assert_renders_like!(window(), "tests/golden/button.png", 0.05);
+//! ```
+//!
+//! Images are compared per-pixel using a perceptual colour difference (the
+//! YIQ colour space, as used by the [pixelmatch] library), normalised to the
+//! range `0.0 ..= 1.0`. A comparison fails if any pixel differs by more than
+//! the given tolerance, or if image sizes differ.
+//!
+//! On failure, the rendered image and a diff image (highlighting differing
+//! pixels in red) are written as `NAME.actual.png` and `NAME.diff.png`, by
+//! default next to the golden image; set the `KAS_GOLDEN_ARTIFACTS`
+//! environment variable to a directory to write these elsewhere (e.g. for
+//! upload as CI artifacts).
+//!
+//! To regenerate golden images, set the environment variable
+//! `KAS_GOLDEN_UPDATE=1`: comparison is then skipped and the golden image is
+//! (over)written with the rendered result.
+//!
+//! Note that rendering depends on the graphics adapter and fonts available,
+//! thus golden images should be generated on the same platform used to
+//! compare them.
+//!
+//! [pixelmatch]: https://github.com/mapbox/pixelmatch
+
+use image::{Rgba, RgbaImage};
+use log::info;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::{Error, Headless};
+use kas_theme::FlatTheme;
+
+/// Assert that a window renders like a golden image
+///
+/// Usage: `assert_renders_like!(window, path, tolerance)` where `window` is a
+/// [`kas::Window`], `path` is the golden image's path relative to the
+/// calling crate's manifest directory and `tolerance` is the maximum
+/// perceptual difference per pixel (default: `0.0`).
+///
+/// Requires feature `headless`. See the [`golden`](crate::golden) module.
+#[macro_export]
+macro_rules! assert_renders_like {
+    ($window:expr, $path:expr) => {
+        $crate::assert_renders_like!($window, $path, 0.0)
+    };
+    ($window:expr, $path:expr, $tolerance:expr) => {
+        $crate::golden::assert_renders_like(
+            $window,
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+            $tolerance,
+        )
+    };
+}
+
+/// Failure of a golden-image comparison
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum GoldenError {
+    /// The golden image does not exist
+    #[error("golden image {0} not found (set KAS_GOLDEN_UPDATE=1 to create)")]
+    Missing(PathBuf),
+    /// Image sizes differ
+    #[error("rendered size {actual:?} does not match golden size {expected:?}")]
+    SizeMismatch {
+        actual: (u32, u32),
+        expected: (u32, u32),
+    },
+    /// Some pixels differ by more than the tolerance
+    #[error("{count} pixels differ by more than the tolerance (max difference: {max_diff:.3})")]
+    Mismatch { count: usize, max_diff: f32 },
+    /// Rendering failed
+    #[error("rendering failed")]
+    Render(#[from] Error),
+    /// Image encoding or IO error
+    #[error("image error")]
+    Image(#[from] image::ImageError),
+}
+
+/// Result of comparing two images of the same size
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    /// Number of pixels differing by more than the tolerance
+    pub count: usize,
+    /// Maximum perceptual difference of any pixel (`0.0 ..= 1.0`)
+    pub max_diff: f32,
+    /// Diff image: a faded copy of the expected image with differing pixels
+    /// highlighted in red
+    pub diff: RgbaImage,
+}
+
+/// Perceptual difference between two pixels, in the range `0.0 ..= 1.0`
+///
+/// Pixels are blended with white according to alpha, then compared in the
+/// YIQ colour space.
+pub fn pixel_diff(a: Rgba<u8>, b: Rgba<u8>) -> f32 {
+    fn yiq(px: Rgba<u8>) -> (f32, f32, f32) {
+        let alpha = f32::from(px[3]) / 255.0;
+        let blend = |c: u8| 255.0 + (f32::from(c) - 255.0) * alpha;
+        let (r, g, b) = (blend(px[0]), blend(px[1]), blend(px[2]));
+        let y = r * 0.29889531 + g * 0.58662247 + b * 0.11448223;
+        let i = r * 0.59597799 - g * 0.27417610 - b * 0.32180189;
+        let q = r * 0.21147017 - g * 0.52261711 + b * 0.31114694;
+        (y, i, q)
+    }
+    // Maximum possible delta (black vs white)
+    const MAX_DELTA: f32 = 35215.0;
+
+    if a == b {
+        return 0.0;
+    }
+    let (a, b) = (yiq(a), yiq(b));
+    let (dy, di, dq) = (a.0 - b.0, a.1 - b.1, a.2 - b.2);
+    let delta = 0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq;
+    (delta / MAX_DELTA).sqrt().min(1.0)
+}
+
+/// Compare two images of the same size
+///
+/// Panics if image sizes differ.
+pub fn compare(actual: &RgbaImage, expected: &RgbaImage, tolerance: f32) -> Comparison {
+    assert_eq!(actual.dimensions(), expected.dimensions());
+    let mut diff = RgbaImage::new(actual.width(), actual.height());
+    let mut count = 0;
+    let mut max_diff: f32 = 0.0;
+
+    for ((a, e), d) in actual
+        .pixels()
+        .zip(expected.pixels())
+        .zip(diff.pixels_mut())
+    {
+        let delta = pixel_diff(*a, *e);
+        max_diff = max_diff.max(delta);
+        *d = if delta > tolerance {
+            count += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            // Faded grey-scale copy of the expected image
+            let y = pixel_diff(*e, Rgba([0, 0, 0, 255]));
+            let v = 255 - (64.0 * (1.0 - y)) as u8;
+            Rgba([v, v, v, 255])
+        };
+    }
+
+    Comparison {
+        count,
+        max_diff,
+        diff,
+    }
+}
+
+/// True if golden images should be (re)generated
+///
+/// This is controlled by the `KAS_GOLDEN_UPDATE` environment variable.
+pub fn update_mode() -> bool {
+    std::env::var("KAS_GOLDEN_UPDATE")
+        .map(|v| !v.is_empty() && v != "0")
+        .unwrap_or(false)
+}
+
+/// Get the path of an output artifact for golden image `path`
+fn artifact_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = format!("{}.{}.png", stem, suffix);
+    match std::env::var_os("KAS_GOLDEN_ARTIFACTS") {
+        Some(dir) => PathBuf::from(dir).join(name),
+        None => path.with_file_name(name),
+    }
+}
+
+/// Check `actual` against the golden image at `path`
+///
+/// In update mode (see [`update_mode`]), the golden image is written instead.
+/// On failure, output artifacts are written (see the module documentation).
+pub fn check_golden(actual: &RgbaImage, path: &Path, tolerance: f32) -> Result<(), GoldenError> {
+    if update_mode() {
+        info!("Writing golden image {}", path.display());
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(image::ImageError::IoError)?;
+        }
+        actual.save_with_format(path, image::ImageFormat::Png)?;
+        return Ok(());
+    }
+
+    if !path.exists() {
+        return Err(GoldenError::Missing(path.to_path_buf()));
+    }
+    let expected = image::open(path)?.into_rgba8();
+
+    let result = if actual.dimensions() != expected.dimensions() {
+        Err(GoldenError::SizeMismatch {
+            actual: actual.dimensions(),
+            expected: expected.dimensions(),
+        })
+    } else {
+        let comparison = compare(actual, &expected, tolerance);
+        if comparison.count == 0 {
+            return Ok(());
+        }
+        let diff_path = artifact_path(path, "diff");
+        if let Some(dir) = diff_path.parent() {
+            std::fs::create_dir_all(dir).map_err(image::ImageError::IoError)?;
+        }
+        comparison
+            .diff
+            .save_with_format(diff_path, image::ImageFormat::Png)?;
+        Err(GoldenError::Mismatch {
+            count: comparison.count,
+            max_diff: comparison.max_diff,
+        })
+    };
+
+    let actual_path = artifact_path(path, "actual");
+    if let Some(dir) = actual_path.parent() {
+        std::fs::create_dir_all(dir).map_err(image::ImageError::IoError)?;
+    }
+    actual.save_with_format(actual_path, image::ImageFormat::Png)?;
+    result
+}
+
+/// Render `window` and check against the golden image at `path`
+///
+/// Rendering uses the [`Headless`] shell with the default [`FlatTheme`] and
+/// the window's ideal size.
+pub fn renders_like<W: kas::Window + 'static>(
+    window: W,
+    path: &Path,
+    tolerance: f32,
+) -> Result<(), GoldenError> {
+    let mut shell = Headless::new(FlatTheme::new())?;
+    let id = shell.add(window);
+    let image = shell.render(id)?;
+    check_golden(&image, path, tolerance)
+}
+
+/// Assert that `window` renders like the golden image at `path`
+///
+/// Panics on failure; see [`renders_like`]. Usually this is called via the
+/// [`assert_renders_like!`](crate::assert_renders_like) macro.
+#[track_caller]
+pub fn assert_renders_like<W: kas::Window + 'static, P: AsRef<Path>>(
+    window: W,
+    path: P,
+    tolerance: f32,
+) {
+    let path = path.as_ref();
+    if let Err(error) = renders_like(window, path, tolerance) {
+        let artifacts = match &error {
+            GoldenError::SizeMismatch { .. } => {
+                format!("; see {}", artifact_path(path, "actual").display())
+            }
+            GoldenError::Mismatch { .. } => format!(
+                "; see {} and {}",
+                artifact_path(path, "actual").display(),
+                artifact_path(path, "diff").display()
+            ),
+            _ => String::new(),
+        };
+        panic!(
+            "golden image {} does not match: {}{}",
+            path.display(),
+            error,
+            artifacts
+        );
+    }
+}
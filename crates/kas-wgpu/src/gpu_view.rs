@@ -0,0 +1,76 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Widget drawn via a render callback
+
+use crate::draw::{draw_callback, RenderContext};
+use kas::geom::Vec2;
+use kas::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+widget! {
+    /// A widget drawn via a render callback
+    ///
+    /// This widget reserves space within the layout and draws its content
+    /// with the given closure, which is called once each frame the widget is
+    /// drawn (see [`draw_callback`]). The closure receives a [`RenderContext`],
+    /// providing the device, command encoder, target view, rect and scale
+    /// factor; ordering relative to other content and clipping are handled by
+    /// KAS.
+    ///
+    /// To animate, request a redraw via [`Manager::redraw`].
+    ///
+    /// With shells other than `kas_wgpu` (or under its software renderer) this
+    /// widget draws nothing.
+    #[autoimpl(Debug skip render)]
+    pub struct GpuView {
+        #[widget_core]
+        core: CoreData,
+        min_size: Vec2,
+        ideal_size: Vec2,
+        render: Rc<RefCell<dyn FnMut(&mut RenderContext)>>,
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+            let (min, ideal) = match axis.is_horizontal() {
+                true => (self.min_size.0, self.ideal_size.0),
+                false => (self.min_size.1, self.ideal_size.1),
+            };
+            let sf = size_handle.scale_factor();
+            SizeRules::new_scaled(min, ideal, 0.0, Stretch::High, sf)
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
+            let render = self.render.clone();
+            draw_callback(draw, self.rect(), move |ctx| (render.borrow_mut())(ctx));
+        }
+    }
+}
+
+impl GpuView {
+    /// Construct, reserving the given size (logical pixels)
+    ///
+    /// The widget stretches to fill available space.
+    #[inline]
+    pub fn new(size: Vec2, render: impl FnMut(&mut RenderContext) + 'static) -> Self {
+        Self::new_min_ideal(size, size, render)
+    }
+
+    /// Construct with a minimum and ideal size (logical pixels)
+    pub fn new_min_ideal(
+        min_size: Vec2,
+        ideal_size: Vec2,
+        render: impl FnMut(&mut RenderContext) + 'static,
+    ) -> Self {
+        GpuView {
+            core: Default::default(),
+            min_size,
+            ideal_size,
+            render: Rc::new(RefCell::new(render)),
+        }
+    }
+}
@@ -0,0 +1,428 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Headless (offscreen) shell
+//!
+//! This renders windows to an offscreen texture instead of a winit window,
+//! allowing screenshots to be taken without a display server (for example, in
+//! CI or to generate thumbnails). Rendering uses the same [`DrawPipe`] and
+//! theme as the windowed shell, thus output should match pixel-for-pixel
+//! (given the same graphics adapter, scale factor and fonts).
+//!
+//! Input is not supported. Pop-ups cannot be opened since these require a
+//! parent window.
+
+use instant::Instant;
+use log::{debug, trace, warn};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use kas::draw::{DrawIface, PassId};
+use kas::event::{ManagerState, UpdateHandle};
+use kas::geom::{Coord, Rect, Size};
+use kas::layout::SolveCache;
+use kas::{TkAction, WidgetId, WindowId};
+use kas_theme::{Theme, Window as _};
+
+use crate::capture::{create_texture, draw_widget, read_texture};
+use crate::draw::{CustomPipe, CustomPipeBuilder, DrawPipe, DrawWindow};
+use crate::shared::{PendingAction, SharedState};
+use crate::window::{to_wgpu_color, TkWindow};
+use crate::{warn_about_error, Error, Options};
+
+/// Per-window data
+struct Window<C: CustomPipe, T: Theme<DrawPipe<C>>> {
+    id: WindowId,
+    widget: Box<dyn kas::Window>,
+    mgr: ManagerState,
+    solve_cache: SolveCache,
+    size: Size,
+    texture: wgpu::Texture,
+    draw: DrawWindow<C::Window>,
+    theme_window: T::Window,
+}
+
+impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T>
+where
+    T::Window: kas_theme::Window,
+{
+    fn new(
+        shared: &mut SharedState<C, T>,
+        id: WindowId,
+        mut widget: Box<dyn kas::Window>,
+        size: Option<Size>,
+    ) -> Self {
+        let time = Instant::now();
+
+        let scale_factor = shared.scale_factor as f32;
+        let mut theme_window = shared.theme.new_window(scale_factor);
+
+        let mut mgr = ManagerState::new(shared.config.clone(), scale_factor);
+        let mut tkw = TkWindow::new(shared, id, None, &mut theme_window);
+        mgr.configure(&mut tkw, &mut *widget);
+
+        let mut size_handle = theme_window.size_handle();
+        let solve_cache = SolveCache::find_constraints(widget.as_widget_mut(), &mut size_handle);
+        let size = size
+            .unwrap_or_else(|| solve_cache.ideal(true))
+            .max(Size(1, 1));
+        debug!("Constructed headless window with size {:?}", size);
+
+        let mut draw = shared.draw.draw.new_window();
+        shared.draw.draw.resize(&mut draw, size);
+        let texture = create_texture(&shared.draw.draw.gpu().device, size);
+
+        let mut r = Window {
+            id,
+            widget,
+            mgr,
+            solve_cache,
+            size,
+            texture,
+            draw,
+            theme_window,
+        };
+        r.apply_size(shared);
+
+        trace!("Window::new completed in {}µs", time.elapsed().as_micros());
+        r
+    }
+
+    fn resize(&mut self, shared: &mut SharedState<C, T>, size: Size) {
+        let size = size.max(Size(1, 1));
+        if size == self.size {
+            return;
+        }
+
+        self.size = size;
+        shared.draw.draw.resize(&mut self.draw, size);
+        self.texture = create_texture(&shared.draw.draw.gpu().device, size);
+        self.apply_size(shared);
+    }
+
+    fn update_handle(
+        &mut self,
+        shared: &mut SharedState<C, T>,
+        handle: UpdateHandle,
+        payload: u64,
+    ) {
+        let mut tkw = TkWindow::new(shared, self.id, None, &mut self.theme_window);
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            mgr.update_handle(widget, handle, payload);
+        });
+    }
+
+    fn update(&mut self, shared: &mut SharedState<C, T>) {
+        let mut tkw = TkWindow::new(shared, self.id, None, &mut self.theme_window);
+        let action = self.mgr.update(&mut tkw, &mut *self.widget);
+
+        if action.contains(TkAction::RECONFIGURE) {
+            let mut tkw = TkWindow::new(shared, self.id, None, &mut self.theme_window);
+            self.mgr.configure(&mut tkw, &mut *self.widget);
+            self.solve_cache.invalidate_rule_cache();
+            self.apply_size(shared);
+        } else if action.contains(TkAction::RESIZE) {
+            self.solve_cache.invalidate_rule_cache();
+            self.apply_size(shared);
+        } else if action.contains(TkAction::SET_SIZE) {
+            self.apply_size(shared);
+        }
+    }
+
+    fn apply_size(&mut self, shared: &mut SharedState<C, T>) {
+        let rect = Rect::new(Coord::ZERO, self.size);
+        debug!("Resizing headless window to rect = {:?}", rect);
+
+        let mut tkw = TkWindow::new(shared, self.id, None, &mut self.theme_window);
+        self.mgr.update_window_size(rect.size);
+        let solve_cache = &mut self.solve_cache;
+        let widget = &mut self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            solve_cache.apply_rect(widget.as_widget_mut(), mgr, rect, true);
+            widget.resize_popups(mgr);
+        });
+    }
+
+    fn render(&mut self, shared: &mut SharedState<C, T>) -> Result<image::RgbaImage, Error> {
+        let time = Instant::now();
+        self.update(shared);
+
+        {
+            let draw = DrawIface {
+                draw: &mut self.draw,
+                shared: &mut shared.draw,
+                pass: PassId::new(0),
+            };
+
+            #[cfg(not(feature = "gat"))]
+            unsafe {
+                // Safety: lifetimes do not escape the returned draw_handle value.
+                let mut draw_handle = shared.theme.draw_handle(draw, &mut self.theme_window);
+                self.widget.draw(&mut draw_handle, &self.mgr, false);
+                self.solve_cache
+                    .draw_overflow(self.widget.rect(), &mut draw_handle);
+                self.mgr
+                    .draw_inspector(self.widget.as_widget(), &mut draw_handle);
+                self.mgr
+                    .draw_toasts(self.widget.as_widget(), &mut draw_handle);
+                self.mgr
+                    .draw_tooltip(self.widget.as_widget(), &mut draw_handle);
+            }
+            #[cfg(feature = "gat")]
+            {
+                let mut draw_handle = shared.theme.draw_handle(draw, &mut self.theme_window);
+                self.widget.draw(&mut draw_handle, &self.mgr, false);
+                self.solve_cache
+                    .draw_overflow(self.widget.rect(), &mut draw_handle);
+                self.mgr
+                    .draw_inspector(self.widget.as_widget(), &mut draw_handle);
+                self.mgr
+                    .draw_toasts(self.widget.as_widget(), &mut draw_handle);
+                self.mgr
+                    .draw_tooltip(self.widget.as_widget(), &mut draw_handle);
+            }
+        }
+
+        let view = self.texture.create_view(&Default::default());
+        let clear_color = to_wgpu_color(shared.theme.clear_color());
+        shared.render(&mut self.draw, &view, Some(clear_color));
+
+        let image = read_texture(&shared.draw.draw, &self.texture, self.size);
+        trace!("render completed in {}µs", time.elapsed().as_micros());
+        image
+    }
+
+    /// Render the window or a single widget, for a capture request
+    fn capture(
+        &mut self,
+        shared: &mut SharedState<C, T>,
+        widget: Option<WidgetId>,
+    ) -> Result<image::RgbaImage, Error> {
+        let id = match widget {
+            None => return self.render(shared),
+            Some(id) => id,
+        };
+
+        self.update(shared);
+        let rect = draw_widget(
+            shared,
+            &mut self.draw,
+            &mut self.theme_window,
+            &self.mgr,
+            &mut *self.widget,
+            id,
+        )
+        .ok_or(Error::Readback)?;
+
+        let view = self.texture.create_view(&Default::default());
+        let clear_color = to_wgpu_color(shared.theme.clear_color());
+        shared.render(&mut self.draw, &view, Some(clear_color));
+        read_texture(&shared.draw.draw, &self.texture, rect.size.min(self.size))
+    }
+}
+
+/// A headless shell over WebGPU
+///
+/// This is similar to [`crate::Toolkit`], but instead of running an event
+/// loop, windows are rendered on demand via [`Headless::render`].
+/// See the [module documentation](self).
+///
+/// Like the [`crate::Toolkit`], construction initialises the font database.
+/// The scale factor is fixed at 1.
+pub struct Headless<C: CustomPipe, T: Theme<DrawPipe<C>>> {
+    windows: Vec<Window<C, T>>,
+    shared: SharedState<C, T>,
+}
+
+impl<T: Theme<DrawPipe<()>> + 'static> Headless<(), T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct a new instance with default options.
+    ///
+    /// Environment variables may affect option selection; see documentation
+    /// of [`Options::from_env`]. KAS config is provided by
+    /// [`Options::read_config`].
+    #[inline]
+    pub fn new(theme: T) -> Result<Self, Error> {
+        Self::new_custom((), theme, Options::from_env())
+    }
+}
+
+impl<C: CustomPipe, T: Theme<DrawPipe<C>> + 'static> Headless<C, T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct an instance with custom options
+    ///
+    /// See [`crate::Toolkit::new_custom`]. Headless rendering always uses a
+    /// graphics adapter; [`Options::software`] is ignored.
+    pub fn new_custom<CB: CustomPipeBuilder<Pipe = C>>(
+        custom: CB,
+        mut theme: T,
+        mut options: Options,
+    ) -> Result<Self, Error> {
+        options.software = crate::options::Software::Never;
+        options.init_theme_config(&mut theme)?;
+        let config = match options.read_config() {
+            Ok(config) => config,
+            Err(error) => {
+                warn_about_error("Failed to read config", &error);
+                Default::default()
+            }
+        };
+        let config = Rc::new(RefCell::new(config));
+        let shared = SharedState::new(custom, theme, options, config, 1.0);
+        Ok(Headless {
+            windows: vec![],
+            shared: futures::executor::block_on(shared)?,
+        })
+    }
+
+    /// Access the theme by ref
+    #[inline]
+    pub fn theme(&self) -> &T {
+        &self.shared.theme
+    }
+
+    /// Access the theme by ref mut
+    #[inline]
+    pub fn theme_mut(&mut self) -> &mut T {
+        &mut self.shared.theme
+    }
+
+    /// Assume ownership of a window, using its ideal size
+    ///
+    /// This is a convenience wrapper around [`Headless::add_boxed`].
+    #[inline]
+    pub fn add<W: kas::Window + 'static>(&mut self, window: W) -> WindowId {
+        self.add_boxed(Box::new(window), None)
+    }
+
+    /// Add a boxed window directly
+    ///
+    /// If `size` is `None`, the window's ideal size is used.
+    pub fn add_boxed(&mut self, widget: Box<dyn kas::Window>, size: Option<Size>) -> WindowId {
+        let id = self.shared.next_window_id();
+        let win = Window::new(&mut self.shared, id, widget, size);
+        self.windows.push(win);
+        self.handle_pending();
+        id
+    }
+
+    /// Remove a window
+    ///
+    /// Returns the window's widget, if found.
+    pub fn remove(&mut self, id: WindowId) -> Option<Box<dyn kas::Window>> {
+        let index = self.windows.iter().position(|w| w.id == id)?;
+        Some(self.windows.remove(index).widget)
+    }
+
+    /// Get the size of a window
+    ///
+    /// Panics if `id` does not refer to a window of this shell.
+    pub fn size(&self, id: WindowId) -> Size {
+        self.windows[self.index(id)].size
+    }
+
+    /// Resize a window
+    ///
+    /// Panics if `id` does not refer to a window of this shell.
+    pub fn resize(&mut self, id: WindowId, size: Size) {
+        let index = self.index(id);
+        self.windows[index].resize(&mut self.shared, size);
+        self.handle_pending();
+    }
+
+    /// Render a window, returning the image
+    ///
+    /// Panics if `id` does not refer to a window of this shell.
+    pub fn render(&mut self, id: WindowId) -> Result<image::RgbaImage, Error> {
+        let index = self.index(id);
+        let result = self.windows[index].render(&mut self.shared);
+        self.handle_pending();
+        result
+    }
+
+    /// Render a window and save as a PNG file
+    ///
+    /// Panics if `id` does not refer to a window of this shell.
+    pub fn save_png<P: AsRef<Path>>(&mut self, id: WindowId, path: P) -> Result<(), Error> {
+        let image = self.render(id)?;
+        image.save_with_format(path, image::ImageFormat::Png)?;
+        Ok(())
+    }
+
+    fn index(&self, id: WindowId) -> usize {
+        (self.windows.iter().position(|w| w.id == id))
+            .unwrap_or_else(|| panic!("Headless: unknown window {:?}", id))
+    }
+
+    fn handle_pending(&mut self) {
+        self.shared.queue_dropped_captures();
+        while !self.shared.pending.is_empty() {
+            let batch = std::mem::take(&mut self.shared.pending);
+            for pending in kas_shell::coalesce(batch, &mut self.shared.stats) {
+                self.handle_pending_item(pending);
+            }
+        }
+    }
+
+    fn handle_pending_item(&mut self, pending: PendingAction) {
+        match pending {
+            PendingAction::AddPopup(..) => {
+                // Unreachable: TkWindow does not add pop-ups without a window
+                warn!("Headless: pop-ups are not supported");
+            }
+            PendingAction::AddWindow(id, widget) => {
+                debug!("Adding window {}", widget.title());
+                let window = Window::new(&mut self.shared, id, widget, None);
+                self.windows.push(window);
+            }
+            PendingAction::CloseWindow(id) => {
+                self.remove(id);
+            }
+            PendingAction::Capture(request) => {
+                let window = self
+                    .windows
+                    .iter_mut()
+                    .find(|w| Some(w.id) == request.window);
+                let image = match window {
+                    Some(window) => match window.capture(&mut self.shared, request.widget) {
+                        Ok(image) => Some(image),
+                        Err(e) => {
+                            warn_about_error("Headless: capture failed", &e);
+                            None
+                        }
+                    },
+                    None => None,
+                };
+                let handle = request.finish(image);
+                self.shared.trigger_update(handle, 0);
+            }
+            PendingAction::TkAction(action) => {
+                if action.contains(TkAction::CLOSE | TkAction::EXIT) {
+                    self.windows.clear();
+                } else {
+                    self.shared.stats.actions_applied += 1;
+                    for window in self.windows.iter_mut() {
+                        if action.intersects(TkAction::REDRAW | TkAction::THEME_UPDATE) {
+                            // The theme may have changed
+                            window.mgr.theme_changed();
+                        }
+                        window.mgr.send_action(action);
+                    }
+                }
+            }
+            PendingAction::Update(handle, payload) => {
+                for window in self.windows.iter_mut() {
+                    window.update_handle(&mut self.shared, handle, payload);
+                }
+            }
+        }
+    }
+}
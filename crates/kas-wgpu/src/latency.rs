@@ -0,0 +1,164 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Input latency measurement
+//!
+//! When enabled via [`Options`](crate::Options) (see `KAS_LATENCY_STATS` in
+//! [`Options::from_env`](crate::Options::from_env)), the shell timestamps
+//! each input event on receipt, tracks it through dispatch and measures the
+//! time until the frame showing its effect is presented. Statistics are
+//! logged (at level `info`) every few hundred samples and when the window is
+//! closed.
+//!
+//! Latency is measured from receipt of the event by the shell until
+//! presentation of the frame is submitted; the time until the frame actually
+//! appears on the display additionally depends on the present mode,
+//! compositor and display. Input which does not cause a redraw (e.g. motion
+//! over an inactive area) is not counted.
+
+use instant::Instant;
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::Duration;
+use winit::event::WindowEvent;
+
+/// Maximum number of samples retained
+const MAX_SAMPLES: usize = 1024;
+/// Number of new samples between reports
+const REPORT_INTERVAL: usize = 256;
+
+/// Input latency statistics
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LatencyStats {
+    /// Number of samples
+    pub samples: usize,
+    /// Mean time from receipt to completion of dispatch
+    pub dispatch_mean: Duration,
+    /// Minimum time from receipt to presentation
+    pub min: Duration,
+    /// Median time from receipt to presentation
+    pub median: Duration,
+    /// 95th percentile time from receipt to presentation
+    pub p95: Duration,
+    /// Maximum time from receipt to presentation
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    /// Calculate from samples of `(dispatch, total)` durations
+    ///
+    /// Returns `None` if `samples` is empty.
+    pub fn from_samples<I: IntoIterator<Item = (Duration, Duration)>>(samples: I) -> Option<Self> {
+        let mut dispatch_sum = Duration::ZERO;
+        let mut totals = vec![];
+        for (dispatch, total) in samples {
+            dispatch_sum += dispatch;
+            totals.push(total);
+        }
+        if totals.is_empty() {
+            return None;
+        }
+        totals.sort_unstable();
+
+        let n = totals.len();
+        let percentile = |p: usize| totals[(n - 1) * p / 100];
+        Some(LatencyStats {
+            samples: n,
+            dispatch_mean: dispatch_sum / n as u32,
+            min: totals[0],
+            median: percentile(50),
+            p95: percentile(95),
+            max: totals[n - 1],
+        })
+    }
+}
+
+impl fmt::Display for LatencyStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1e3;
+        write!(
+            f,
+            "{} samples: min {:.1}ms, median {:.1}ms, p95 {:.1}ms, max {:.1}ms \
+            (dispatch mean {:.2}ms)",
+            self.samples,
+            ms(self.min),
+            ms(self.median),
+            ms(self.p95),
+            ms(self.max),
+            ms(self.dispatch_mean),
+        )
+    }
+}
+
+/// Per-window latency tracker
+#[derive(Debug, Default)]
+pub(crate) struct LatencyTracker {
+    /// Dispatched inputs: (receipt, dispatch complete)
+    dispatched: Vec<(Instant, Instant)>,
+    /// Inputs awaiting the next frame
+    awaiting: Vec<(Instant, Instant)>,
+    /// Samples: (dispatch, total)
+    samples: VecDeque<(Duration, Duration)>,
+    /// Samples since the last report
+    new_samples: usize,
+}
+
+impl LatencyTracker {
+    /// True if `event` is user input
+    pub fn is_input(event: &WindowEvent) -> bool {
+        matches!(
+            event,
+            WindowEvent::ReceivedCharacter(_)
+                | WindowEvent::KeyboardInput { .. }
+                | WindowEvent::CursorMoved { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::Touch(_)
+        )
+    }
+
+    /// Record an input event, received at `time`, which has been dispatched
+    pub fn dispatched(&mut self, time: Instant) {
+        self.dispatched.push((time, Instant::now()));
+    }
+
+    /// Called after each update with whether a redraw is pending
+    ///
+    /// Dispatched input which did not cause a redraw is discarded.
+    pub fn update(&mut self, redraw: bool) {
+        if redraw {
+            self.awaiting.append(&mut self.dispatched);
+        } else {
+            self.dispatched.clear();
+        }
+    }
+
+    /// Report presentation of a frame at `time`
+    ///
+    /// Returns statistics when a report is due.
+    pub fn presented(&mut self, time: Instant) -> Option<LatencyStats> {
+        for (received, dispatched) in self.awaiting.drain(..) {
+            if self.samples.len() == MAX_SAMPLES {
+                self.samples.pop_front();
+            }
+            let dispatch = dispatched.saturating_duration_since(received);
+            let total = time.saturating_duration_since(received);
+            self.samples.push_back((dispatch, total));
+            self.new_samples += 1;
+        }
+
+        if self.new_samples >= REPORT_INTERVAL {
+            self.new_samples = 0;
+            self.stats()
+        } else {
+            None
+        }
+    }
+
+    /// Get statistics over retained samples
+    pub fn stats(&self) -> Option<LatencyStats> {
+        LatencyStats::from_samples(self.samples.iter().cloned())
+    }
+}
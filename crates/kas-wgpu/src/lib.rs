@@ -14,22 +14,54 @@
 //!
 //! Custom GPU-accelerated drawing is supported via [`draw::CustomPipe`]
 //! (see the [Mandlebrot example](https://github.com/kas-gui/kas/blob/master/kas-wgpu/examples/mandlebrot.rs)).
+//! For one-off GPU content, [`GpuView`] (or [`draw::draw_callback`]) is a
+//! simpler alternative, drawing via a closure.
+//!
+//! Where no graphics adapter is available, a software renderer is used instead
+//! (feature `software`, enabled by default); see [`options::Software`].
 //!
 //! By default, some environment variables are read for configuration.
 //! See [`options::Options::from_env`] for documentation.
 //!
+//! With the `embed` feature, a KAS window may be driven from an external event
+//! and render loop over an existing `wgpu` device; see the `embed` module.
+//!
+//! This crate also supports the web (`wasm32-unknown-unknown`) via winit's
+//! web backend; see the `web` module (only available on this target).
+//!
+//! Printing is not supported by this crate: PDF output is only available from
+//! the `kas-gtk` shell (`pdf` feature), which does not require a display.
+//!
 //! [WebGPU]: https://github.com/gfx-rs/wgpu-rs
 //! [winit]: https://github.com/rust-windowing/winit
 //! [clipboard]: https://crates.io/crates/clipboard
 
 #![cfg_attr(feature = "gat", feature(generic_associated_types))]
 
+mod capture;
 pub mod draw;
+#[cfg(feature = "embed")]
+pub mod embed;
 mod event_loop;
+#[cfg(feature = "software")]
+mod framebuffer;
+#[cfg(feature = "headless")]
+pub mod golden;
+mod gpu_view;
+#[cfg(feature = "headless")]
+pub mod headless;
+pub mod latency;
 pub mod options;
+pub mod recorder;
 mod shared;
+pub mod startup;
+#[cfg(feature = "tray")]
+pub mod tray;
+#[cfg(target_arch = "wasm32")]
+pub mod web;
 mod window;
 
+use instant::Instant;
 use std::cell::RefCell;
 use std::rc::Rc;
 use thiserror::Error;
@@ -42,11 +74,18 @@ use winit::event_loop::{EventLoop, EventLoopProxy, EventLoopWindowTarget};
 
 use crate::draw::{CustomPipe, CustomPipeBuilder, DrawPipe};
 use crate::shared::SharedState;
+use crate::startup::StartupTimings;
 use window::Window;
 
+#[cfg(feature = "embed")]
+pub use embed::Embedded;
+pub use gpu_view::GpuView;
+#[cfg(feature = "headless")]
+pub use headless::Headless;
 pub use options::Options;
 
 pub use kas;
+pub use kas_shell::ClosedError;
 pub use kas_theme as theme;
 pub use wgpu;
 
@@ -61,6 +100,10 @@ pub enum Error {
     ///
     /// This can be a driver/configuration issue or hardware limitation. Note
     /// that for now, `wgpu` only supports DX11, DX12, Vulkan and Metal.
+    /// Unless disabled via [`Options::fallback_adapter`], `wgpu`'s fallback
+    /// adapter is tried before reporting this error. With feature `software`,
+    /// the software renderer is used instead of reporting this error unless
+    /// disabled via [`Options::software`].
     #[error("no graphics adapter found")]
     NoAdapter,
     /// Config load/save error
@@ -70,6 +113,20 @@ pub enum Error {
     /// OS error during window creation
     #[error("operating system error")]
     Window(#[from] OsError),
+    /// Failure reading a rendered frame back from the GPU
+    #[error("failed to read back rendered frame")]
+    Readback,
+    /// Failure creating a window's framebuffer (software renderer)
+    #[error("failed to create window framebuffer")]
+    Framebuffer,
+    /// Image encoding or IO error
+    #[cfg(feature = "headless")]
+    #[error("image error")]
+    Image(#[from] image::ImageError),
+    /// The system tray is not supported on this platform
+    #[cfg(feature = "tray")]
+    #[error("system tray not supported")]
+    TrayUnsupported,
 }
 
 impl From<wgpu::RequestDeviceError> for Error {
@@ -112,10 +169,23 @@ where
     /// Environment variables may affect option selection; see documentation
     /// of [`Options::from_env`]. KAS config is provided by
     /// [`Options::read_config`].
+    ///
+    /// This method blocks on graphics initialisation, thus is not available
+    /// on the web; use [`Toolkit::new_async`] instead.
+    #[cfg(not(target_arch = "wasm32"))]
     #[inline]
     pub fn new(theme: T) -> Result<Self, Error> {
         Self::new_custom((), theme, Options::from_env())
     }
+
+    /// Construct a new instance with default options, asynchronously
+    ///
+    /// This is equivalent to [`Toolkit::new`] but awaits graphics
+    /// initialisation instead of blocking.
+    #[inline]
+    pub async fn new_async(theme: T) -> Result<Self, Error> {
+        Self::new_custom_async((), theme, Options::from_env()).await
+    }
 }
 
 impl<C: CustomPipe, T: Theme<DrawPipe<C>> + 'static> Toolkit<C, T>
@@ -132,12 +202,29 @@ where
     ///
     /// KAS config is provided by [`Options::read_config`] and `theme` is
     /// configured through [`Options::init_theme_config`].
+    ///
+    /// This method blocks on graphics initialisation, thus is not available
+    /// on the web; use [`Toolkit::new_custom_async`] instead.
+    #[cfg(not(target_arch = "wasm32"))]
     #[inline]
     pub fn new_custom<CB: CustomPipeBuilder<Pipe = C>>(
+        custom: CB,
+        theme: T,
+        options: Options,
+    ) -> Result<Self, Error> {
+        futures::executor::block_on(Self::new_custom_async(custom, theme, options))
+    }
+
+    /// Construct an instance with custom options, asynchronously
+    ///
+    /// This is equivalent to [`Toolkit::new_custom`] but awaits graphics
+    /// initialisation instead of blocking.
+    pub async fn new_custom_async<CB: CustomPipeBuilder<Pipe = C>>(
         custom: CB,
         mut theme: T,
         options: Options,
     ) -> Result<Self, Error> {
+        let start = Instant::now();
         let el = EventLoop::with_user_event();
 
         options.init_theme_config(&mut theme)?;
@@ -149,12 +236,13 @@ where
             }
         };
         let config = Rc::new(RefCell::new(config));
+        let config_time = start.elapsed();
         let scale_factor = find_scale_factor(&el);
-        Ok(Toolkit {
-            el,
-            windows: vec![],
-            shared: SharedState::new(custom, theme, options, config, scale_factor)?,
-        })
+        let shared = SharedState::new(custom, theme, options, config, scale_factor);
+        let mut shared = shared.await?;
+        shared.startup.start = start;
+        shared.startup.config = config_time;
+        Ok(Self::from_parts(el, shared))
     }
 
     /// Construct an instance with custom options and config
@@ -164,20 +252,49 @@ where
     ///
     /// Unlike other the constructors, this method does not configure the theme.
     /// The user should call [`Options::init_theme_config`] before this method.
+    ///
+    /// This method blocks on graphics initialisation, thus is not available
+    /// on the web; use [`Toolkit::new_custom_config_async`] instead.
+    #[cfg(not(target_arch = "wasm32"))]
     #[inline]
     pub fn new_custom_config<CB: CustomPipeBuilder<Pipe = C>>(
         custom: CB,
         theme: T,
         options: Options,
         config: Rc<RefCell<kas::event::Config>>,
+    ) -> Result<Self, Error> {
+        let toolkit = Self::new_custom_config_async(custom, theme, options, config);
+        futures::executor::block_on(toolkit)
+    }
+
+    /// Construct an instance with custom options and config, asynchronously
+    ///
+    /// This is equivalent to [`Toolkit::new_custom_config`] but awaits
+    /// graphics initialisation instead of blocking.
+    pub async fn new_custom_config_async<CB: CustomPipeBuilder<Pipe = C>>(
+        custom: CB,
+        theme: T,
+        options: Options,
+        config: Rc<RefCell<kas::event::Config>>,
     ) -> Result<Self, Error> {
         let el = EventLoop::with_user_event();
         let scale_factor = find_scale_factor(&el);
-        Ok(Toolkit {
+        let shared = SharedState::new(custom, theme, options, config, scale_factor);
+        Ok(Self::from_parts(el, shared.await?))
+    }
+
+    fn from_parts(el: EventLoop<ProxyAction>, shared: SharedState<C, T>) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        let shared = {
+            let mut shared = shared;
+            shared.proxy = Some(el.create_proxy());
+            shared
+        };
+        Toolkit {
             el,
             windows: vec![],
-            shared: SharedState::new(custom, theme, options, config, scale_factor)?,
-        })
+            shared,
+        }
     }
 
     /// Access the theme by ref
@@ -192,6 +309,71 @@ where
         &mut self.shared.theme
     }
 
+    /// Set the localizer used to resolve message keys
+    ///
+    /// By default, no localizer is used and message keys resolve to
+    /// themselves. See the [`kas::l10n`] module.
+    #[inline]
+    pub fn set_localizer<L: kas::l10n::Localizer + 'static>(&mut self, localizer: L) {
+        self.shared.localizer = Some(Box::new(localizer));
+    }
+
+    /// Set the localizer used to resolve message keys, inline
+    ///
+    /// See [`Toolkit::set_localizer`].
+    #[inline]
+    pub fn with_localizer<L: kas::l10n::Localizer + 'static>(mut self, localizer: L) -> Self {
+        self.set_localizer(localizer);
+        self
+    }
+
+    /// Set the storage backend for persisted UI state
+    ///
+    /// By default, state is held in memory only ([`kas::state::MemoryStore`]).
+    /// State is flushed on exit. See the [`kas::state`] module.
+    #[inline]
+    pub fn set_state_store<S: kas::state::StateStore + 'static>(&mut self, store: S) {
+        self.shared.state = Box::new(store);
+    }
+
+    /// Set the storage backend for persisted UI state, inline
+    ///
+    /// See [`Toolkit::set_state_store`].
+    #[inline]
+    pub fn with_state_store<S: kas::state::StateStore + 'static>(mut self, store: S) -> Self {
+        self.set_state_store(store);
+        self
+    }
+
+    /// Set the canvas used by the next window
+    ///
+    /// By default, each window creates a new canvas, appended to the document
+    /// body. See the [`web`] module.
+    #[cfg(target_arch = "wasm32")]
+    #[inline]
+    pub fn set_canvas(&mut self, canvas: web::HtmlCanvasElement) {
+        self.shared.canvas = Some(canvas);
+    }
+
+    /// Set the canvas used by the next window, inline
+    ///
+    /// See [`Toolkit::set_canvas`].
+    #[cfg(target_arch = "wasm32")]
+    #[inline]
+    pub fn with_canvas(mut self, canvas: web::HtmlCanvasElement) -> Self {
+        self.set_canvas(canvas);
+        self
+    }
+
+    /// Access start-up timings
+    ///
+    /// This includes only timings measured so far; see the
+    /// [`startup`](crate::startup) module.
+    #[inline]
+    pub fn startup_timings(&self) -> &StartupTimings {
+        &self.shared.startup
+    }
+
     /// Assume ownership of and display a window
     ///
     /// This is a convenience wrapper around [`Toolkit::add_boxed`].
@@ -215,9 +397,11 @@ where
 
     /// Add a boxed window directly
     pub fn add_boxed(&mut self, widget: Box<dyn kas::Window>) -> Result<WindowId, Error> {
+        let time = Instant::now();
         let id = self.shared.next_window_id();
         let win = Window::new(&mut self.shared, &self.el, id, widget)?;
         self.windows.push(win);
+        self.shared.startup.windows += time.elapsed();
         Ok(id)
     }
 
@@ -228,6 +412,17 @@ where
         Ok(self)
     }
 
+    /// Add a system tray icon
+    ///
+    /// Only one tray icon is supported; adding another replaces the first.
+    /// See [`tray`] module documentation.
+    #[cfg(feature = "tray")]
+    pub fn add_tray_icon(&mut self, icon: tray::TrayIcon) -> Result<(), Error> {
+        let state = tray::TrayState::new(icon, self.el.create_proxy());
+        self.shared.tray = Some(state.ok_or(Error::TrayUnsupported)?);
+        Ok(())
+    }
+
     /// Create a proxy which can be used to update the UI from another thread
     pub fn create_proxy(&self) -> ToolkitProxy {
         ToolkitProxy {
@@ -261,11 +456,6 @@ pub struct ToolkitProxy {
     proxy: EventLoopProxy<ProxyAction>,
 }
 
-/// Error type returned by [`ToolkitProxy`] functions.
-///
-/// This error occurs only if the [`Toolkit`] already terminated.
-pub struct ClosedError;
-
 impl ToolkitProxy {
     /// Close a specific window.
     pub fn close(&self, id: WindowId) -> Result<(), ClosedError> {
@@ -287,6 +477,54 @@ impl ToolkitProxy {
             .send_event(ProxyAction::Update(handle, payload))
             .map_err(|_| ClosedError)
     }
+
+    /// Show a desktop notification
+    ///
+    /// Failure to show the notification is logged but not reported.
+    #[cfg(feature = "tray")]
+    pub fn notify(&self, notification: &tray::Notification) {
+        notification.show(self.proxy.clone());
+    }
+}
+
+impl<C: CustomPipe, T: Theme<DrawPipe<C>> + 'static> kas_shell::Shell for Toolkit<C, T>
+where
+    T::Window: kas_theme::Window,
+{
+    type Error = Error;
+    type Proxy = ToolkitProxy;
+
+    #[inline]
+    fn add_boxed(&mut self, window: Box<dyn kas::Window>) -> Result<WindowId, Error> {
+        Toolkit::add_boxed(self, window)
+    }
+
+    #[inline]
+    fn create_proxy(&self) -> ToolkitProxy {
+        Toolkit::create_proxy(self)
+    }
+
+    #[inline]
+    fn run(self) {
+        Toolkit::run(self)
+    }
+}
+
+impl kas_shell::ShellProxy for ToolkitProxy {
+    #[inline]
+    fn close(&self, id: WindowId) -> Result<(), ClosedError> {
+        ToolkitProxy::close(self, id)
+    }
+
+    #[inline]
+    fn close_all(&self) -> Result<(), ClosedError> {
+        ToolkitProxy::close_all(self)
+    }
+
+    #[inline]
+    fn trigger_update(&self, handle: UpdateHandle, payload: u64) -> Result<(), ClosedError> {
+        ToolkitProxy::trigger_update(self, handle, payload)
+    }
 }
 
 #[derive(Debug)]
@@ -294,4 +532,11 @@ enum ProxyAction {
     CloseAll,
     Close(WindowId),
     Update(UpdateHandle, u64),
+    #[cfg(feature = "tray")]
+    Tray(tray::TrayAction),
+    #[cfg(target_arch = "wasm32")]
+    AnimationFrame(winit::window::WindowId),
+    /// Clipboard contents were received: repeat the paste command
+    #[cfg(target_arch = "wasm32")]
+    Paste(winit::window::WindowId),
 }
@@ -30,6 +30,64 @@ pub enum ConfigMode {
     WriteDefault,
 }
 
+/// Pipeline warm-up mode
+///
+/// Draw pipelines not required by all themes (e.g. for shaded drawing and
+/// images) are constructed on demand. This controls whether they are
+/// constructed in advance. See [`Options::from_env`] documentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WarmUp {
+    /// Construct all pipelines before creating any window
+    Eager,
+    /// Construct remaining pipelines once the first frame has been presented
+    AfterFirstFrame,
+    /// Construct pipelines only on first use
+    Lazy,
+}
+
+/// Fallback adapter mode
+///
+/// Where no other graphics adapter is usable (e.g. in a virtual machine or
+/// with missing drivers), `wgpu` may provide a *fallback adapter*. This is
+/// usually a software (CPU) implementation of a graphics API provided by the
+/// platform, e.g. WARP on Windows or Mesa's llvmpipe/lavapipe on Linux.
+///
+/// Where the platform provides no fallback adapter, KAS's own software
+/// renderer may be used instead (see [`Software`]).
+/// See [`Options::from_env`] documentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FallbackAdapter {
+    /// Use the fallback adapter only if no other adapter is found
+    Auto,
+    /// Never use the fallback adapter
+    Never,
+    /// Always use the fallback adapter
+    Always,
+}
+
+/// Software rendering mode
+///
+/// With feature `software` (enabled by default), KAS includes a CPU renderer
+/// (using `tiny-skia`), presenting frames via `softbuffer`. This is much slower
+/// than rendering via a graphics adapter and lacks some effects (shading and
+/// blur), but allows applications to run where no graphics adapter is usable.
+/// Custom draw pipes and draw callbacks (e.g. [`crate::GpuView`]) are not
+/// available to the software renderer.
+///
+/// Without feature `software`, this option is ignored.
+/// See [`Options::from_env`] documentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Software {
+    /// Use the software renderer only if no graphics adapter is found
+    ///
+    /// The fallback adapter (see [`FallbackAdapter`]) is tried first.
+    Auto,
+    /// Never use the software renderer
+    Never,
+    /// Always use the software renderer
+    Always,
+}
+
 /// Shell options
 #[derive(Clone, PartialEq, Hash)]
 pub struct Options {
@@ -43,8 +101,26 @@ pub struct Options {
     pub power_preference: PowerPreference,
     /// Adapter backend. Default value: PRIMARY (Vulkan/Metal/DX12).
     pub backends: Backends,
+    /// Fallback adapter mode. Default: `Auto`.
+    pub fallback_adapter: FallbackAdapter,
+    /// Software rendering mode. Default: `Auto`.
+    pub software: Software,
     /// WGPU's API tracing path
     pub wgpu_trace_path: Option<PathBuf>,
+    /// Path to which window events are recorded. Default: `None`.
+    pub record_path: Option<PathBuf>,
+    /// Path from which window events are replayed. Default: `None`.
+    pub replay_path: Option<PathBuf>,
+    /// Enable key event diagnostics. Default: false.
+    pub key_diagnostics: bool,
+    /// Enable input latency measurement. Default: false.
+    pub latency_stats: bool,
+    /// Enable draw statistics collection. Default: false.
+    pub draw_stats: bool,
+    /// Pipeline warm-up mode. Default: `AfterFirstFrame`.
+    pub warm_up: WarmUp,
+    /// Report start-up timings at level `info`. Default: false.
+    pub startup_stats: bool,
 }
 
 impl Default for Options {
@@ -55,7 +131,16 @@ impl Default for Options {
             config_mode: ConfigMode::Read,
             power_preference: PowerPreference::LowPower,
             backends: Backends::all(),
+            fallback_adapter: FallbackAdapter::Auto,
+            software: Software::Auto,
             wgpu_trace_path: None,
+            record_path: None,
+            replay_path: None,
+            key_diagnostics: false,
+            latency_stats: false,
+            draw_stats: false,
+            warm_up: WarmUp::AfterFirstFrame,
+            startup_stats: false,
         }
     }
 }
@@ -110,7 +195,24 @@ impl Options {
     /// -   `BROWSER_WEBGPU`: web target through webassembly
     /// -   `PRIMARY`: any of Vulkan, Metal or DX12
     /// -   `SECONDARY`: any of GL or DX11
-    /// -   `FALLBACK`: force use of fallback (CPU) rendering
+    /// -   `FALLBACK`: force use of the fallback adapter; equivalent to
+    ///     `KAS_FALLBACK_ADAPTER=Always`
+    ///
+    /// The `KAS_FALLBACK_ADAPTER` variable controls use of `wgpu`'s fallback
+    /// adapter (see [`FallbackAdapter`]):
+    ///
+    /// -   `Auto` (default): use the fallback adapter if no other adapter is
+    ///     found
+    /// -   `Never`: fail if no other adapter is found
+    /// -   `Always`: always use the fallback adapter
+    ///
+    /// The `KAS_SOFTWARE` variable controls use of KAS's software renderer
+    /// (see [`Software`]):
+    ///
+    /// -   `Auto` (default): use the software renderer if no graphics adapter
+    ///     (including the fallback adapter) is found
+    /// -   `Never`: fail if no graphics adapter is found
+    /// -   `Always`: always use the software renderer
     ///
     /// WGPU has an [API tracing] feature for debugging. To use this, ensure the
     /// `wgpu/trace` feature is enabled and set the output path:
@@ -118,6 +220,46 @@ impl Options {
     /// export KAS_WGPU_TRACE_PATH="api_trace"
     /// ```
     ///
+    /// The `KAS_WARM_UP` variable controls when draw pipelines which are not
+    /// required immediately are constructed (see [`WarmUp`]):
+    ///
+    /// -   `Eager`: on start-up, before creating any window
+    /// -   `AfterFirstFrame` (default): after the first frame is presented
+    /// -   `Lazy`: on first use
+    ///
+    /// # Event recording
+    ///
+    /// Window events may be recorded to a file and later replayed, in order
+    /// to reproduce input bugs. See the [`recorder`](crate::recorder) module.
+    ///
+    /// The `KAS_RECORD_PATH` variable, if given, provides a path to which
+    /// events are written on exit. The `KAS_REPLAY_PATH` variable, if given,
+    /// provides a path from which events are read and replayed.
+    /// ```sh
+    /// export KAS_RECORD_PATH="events.yaml"
+    /// ```
+    ///
+    /// # Diagnostics
+    ///
+    /// If `KAS_KEY_DIAGNOSTICS` is set (to any value other than `0`), the
+    /// routing of each key event is logged; see
+    /// [`kas::event::ManagerState::key_diagnostics`]. This may also be toggled
+    /// at run-time via [`kas::event::Manager::set_key_diagnostics`].
+    ///
+    /// If `KAS_LATENCY_STATS` is set (to any value other than `0`), the
+    /// latency from receipt of input to presentation of its effect is
+    /// measured and reported; see the [`latency`](crate::latency) module.
+    ///
+    /// If `KAS_DRAW_STATS` is set (to any value other than `0`), statistics
+    /// of each drawn frame are collected and made available via
+    /// [`kas::event::ManagerState::draw_stats`]. Where the graphics adapter
+    /// supports timestamp queries, GPU time is also measured.
+    ///
+    /// Start-up timings are always measured and logged at level `debug`; if
+    /// `KAS_STARTUP_STATS` is set (to any value other than `0`), these are
+    /// instead logged at level `info`. See the [`startup`](crate::startup)
+    /// module.
+    ///
     /// [API tracing]: https://github.com/gfx-rs/wgpu/wiki/Debugging-wgpu-Applications#tracing-infrastructure
     pub fn from_env() -> Self {
         let mut options = Options::default();
@@ -169,7 +311,10 @@ impl Options {
                 "BROWSER_WEBGPU" => Backends::BROWSER_WEBGPU,
                 "PRIMARY" => Backends::PRIMARY,
                 "SECONDARY" => Backends::SECONDARY,
-                "FALLBACK" => Backends::empty(),
+                "FALLBACK" => {
+                    options.fallback_adapter = FallbackAdapter::Always;
+                    options.backends
+                }
                 other => {
                     warn!("Unexpected environment value: KAS_BACKENDS={}", other);
                     options.backends
@@ -177,17 +322,83 @@ impl Options {
             }
         }
 
+        if let Ok(mut v) = var("KAS_FALLBACK_ADAPTER") {
+            v.make_ascii_uppercase();
+            options.fallback_adapter = match v.as_str() {
+                "AUTO" => FallbackAdapter::Auto,
+                "NEVER" => FallbackAdapter::Never,
+                "ALWAYS" => FallbackAdapter::Always,
+                other => {
+                    warn!(
+                        "Unexpected environment value: KAS_FALLBACK_ADAPTER={}",
+                        other
+                    );
+                    options.fallback_adapter
+                }
+            };
+        }
+
+        if let Ok(mut v) = var("KAS_SOFTWARE") {
+            v.make_ascii_uppercase();
+            options.software = match v.as_str() {
+                "AUTO" => Software::Auto,
+                "NEVER" => Software::Never,
+                "ALWAYS" => Software::Always,
+                other => {
+                    warn!("Unexpected environment value: KAS_SOFTWARE={}", other);
+                    options.software
+                }
+            };
+        }
+
         if let Ok(v) = var("KAS_WGPU_TRACE_PATH") {
             options.wgpu_trace_path = Some(v.into());
         }
 
+        if let Ok(v) = var("KAS_RECORD_PATH") {
+            options.record_path = Some(v.into());
+        }
+
+        if let Ok(v) = var("KAS_REPLAY_PATH") {
+            options.replay_path = Some(v.into());
+        }
+
+        if let Ok(v) = var("KAS_KEY_DIAGNOSTICS") {
+            options.key_diagnostics = v != "0";
+        }
+
+        if let Ok(v) = var("KAS_LATENCY_STATS") {
+            options.latency_stats = v != "0";
+        }
+
+        if let Ok(v) = var("KAS_DRAW_STATS") {
+            options.draw_stats = v != "0";
+        }
+
+        if let Ok(mut v) = var("KAS_WARM_UP") {
+            v.make_ascii_uppercase();
+            options.warm_up = match v.as_str() {
+                "EAGER" => WarmUp::Eager,
+                "AFTERFIRSTFRAME" => WarmUp::AfterFirstFrame,
+                "LAZY" => WarmUp::Lazy,
+                other => {
+                    warn!("Unexpected environment value: KAS_WARM_UP={}", other);
+                    options.warm_up
+                }
+            };
+        }
+
+        if let Ok(v) = var("KAS_STARTUP_STATS") {
+            options.startup_stats = v != "0";
+        }
+
         options
     }
 
-    pub(crate) fn adapter_options(&self) -> wgpu::RequestAdapterOptions {
+    pub(crate) fn adapter_options(&self, fallback: bool) -> wgpu::RequestAdapterOptions {
         wgpu::RequestAdapterOptions {
             power_preference: self.power_preference,
-            force_fallback_adapter: self.backends.is_empty(),
+            force_fallback_adapter: fallback,
             compatible_surface: None,
         }
     }
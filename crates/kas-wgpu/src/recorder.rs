@@ -0,0 +1,340 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Event recording and replay
+//!
+//! Window events may be recorded to a file, then later replayed in place of
+//! user input. This is intended for debugging: a user may capture a
+//! hard-to-reproduce input sequence, which a developer can then replay.
+//!
+//! Recording and replay are enabled via [`Options`](crate::Options); see
+//! `KAS_RECORD_PATH` and `KAS_REPLAY_PATH` in
+//! [`Options::from_env`](crate::Options::from_env). The file format is
+//! selected from the path's extension, as for config files. The recording is
+//! written when the event loop exits and, so that input leading to a crash may
+//! be reproduced, when a panic occurs (via a panic hook).
+//!
+//! Events are replayed at their recorded time (relative to start of the event
+//! loop). Until replay is complete, input events from the windowing system
+//! are ignored (excepting window close requests). Replay is deterministic provided the
+//! application starts in the same state and does not depend on external
+//! input; note however that timers and double-click detection use real time.
+//!
+//! Window sizes are not forced: on replaying a resize, the shell requests that
+//! the window be resized, and the resulting resize event is handled normally.
+//! Scale-factor changes cannot be replayed.
+
+use instant::Instant;
+use kas::WindowId;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, TryLockError};
+use std::time::Duration;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{DeviceId, ElementState, KeyboardInput, ModifiersState, MouseButton};
+use winit::event::{MouseScrollDelta, Touch, TouchPhase, VirtualKeyCode, WindowEvent};
+
+/// A recorded window event
+///
+/// This is a serialisable subset of [`WindowEvent`], including all events
+/// handled by KAS.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordedEvent {
+    /// The window close button was pressed
+    CloseRequested,
+    /// The window was resized to the given physical size
+    Resized(u32, u32),
+    /// The scale factor changed
+    ScaleFactorChanged(f64),
+    /// A character was received
+    ReceivedCharacter(char),
+    /// The window gained (`true`) or lost focus
+    Focused(bool),
+    /// A key was pressed or released
+    KeyboardInput {
+        scancode: u32,
+        vkey: Option<VirtualKeyCode>,
+        pressed: bool,
+        is_synthetic: bool,
+    },
+    /// The modifier state changed
+    ModifiersChanged(ModifiersState),
+    /// The mouse cursor moved to the given physical position
+    CursorMoved(f64, f64),
+    /// The mouse cursor left the window
+    CursorLeft,
+    /// Mouse wheel or touchpad scroll
+    MouseWheel(MouseScrollDelta),
+    /// A mouse button was pressed or released
+    MouseInput { button: MouseButton, pressed: bool },
+    /// A touch event at the given physical position
+    Touch {
+        id: u64,
+        phase: TouchPhase,
+        x: f64,
+        y: f64,
+    },
+}
+
+impl RecordedEvent {
+    /// Translate from a [`WindowEvent`]
+    ///
+    /// Returns `None` for events which are not recorded.
+    pub fn from_winit(event: &WindowEvent) -> Option<Self> {
+        use WindowEvent::*;
+        Some(match event {
+            CloseRequested => RecordedEvent::CloseRequested,
+            Resized(size) => RecordedEvent::Resized(size.width, size.height),
+            ScaleFactorChanged { scale_factor, .. } => {
+                RecordedEvent::ScaleFactorChanged(*scale_factor)
+            }
+            ReceivedCharacter(c) => RecordedEvent::ReceivedCharacter(*c),
+            Focused(state) => RecordedEvent::Focused(*state),
+            KeyboardInput {
+                input,
+                is_synthetic,
+                ..
+            } => RecordedEvent::KeyboardInput {
+                scancode: input.scancode,
+                vkey: input.virtual_keycode,
+                pressed: input.state == ElementState::Pressed,
+                is_synthetic: *is_synthetic,
+            },
+            ModifiersChanged(state) => RecordedEvent::ModifiersChanged(*state),
+            CursorMoved { position, .. } => RecordedEvent::CursorMoved(position.x, position.y),
+            CursorLeft { .. } => RecordedEvent::CursorLeft,
+            MouseWheel { delta, .. } => RecordedEvent::MouseWheel(*delta),
+            MouseInput { state, button, .. } => RecordedEvent::MouseInput {
+                button: *button,
+                pressed: *state == ElementState::Pressed,
+            },
+            Touch(touch) => RecordedEvent::Touch {
+                id: touch.id,
+                phase: touch.phase,
+                x: touch.location.x,
+                y: touch.location.y,
+            },
+            _ => return None,
+        })
+    }
+
+    /// True if this is an input event (i.e. not a window-management event)
+    pub fn is_input(&self) -> bool {
+        !matches!(
+            self,
+            RecordedEvent::CloseRequested
+                | RecordedEvent::Resized(..)
+                | RecordedEvent::ScaleFactorChanged(..)
+        )
+    }
+
+    /// Translate to a [`WindowEvent`]
+    ///
+    /// Returns `None` for window-management events, which cannot be replayed
+    /// directly.
+    #[allow(deprecated)]
+    pub(crate) fn to_winit(&self) -> Option<WindowEvent<'static>> {
+        // SAFETY: the dummy id is only used for event identification and is
+        // never passed back to the windowing system.
+        let device_id = unsafe { DeviceId::dummy() };
+        let modifiers = ModifiersState::empty();
+        let state = |pressed| match pressed {
+            true => ElementState::Pressed,
+            false => ElementState::Released,
+        };
+
+        Some(match *self {
+            RecordedEvent::CloseRequested => WindowEvent::CloseRequested,
+            RecordedEvent::Resized(..) | RecordedEvent::ScaleFactorChanged(_) => return None,
+            RecordedEvent::ReceivedCharacter(c) => WindowEvent::ReceivedCharacter(c),
+            RecordedEvent::Focused(focus) => WindowEvent::Focused(focus),
+            RecordedEvent::KeyboardInput {
+                scancode,
+                vkey,
+                pressed,
+                is_synthetic,
+            } => WindowEvent::KeyboardInput {
+                device_id,
+                input: KeyboardInput {
+                    scancode,
+                    state: state(pressed),
+                    virtual_keycode: vkey,
+                    modifiers,
+                },
+                is_synthetic,
+            },
+            RecordedEvent::ModifiersChanged(state) => WindowEvent::ModifiersChanged(state),
+            RecordedEvent::CursorMoved(x, y) => WindowEvent::CursorMoved {
+                device_id,
+                position: PhysicalPosition::new(x, y),
+                modifiers,
+            },
+            RecordedEvent::CursorLeft => WindowEvent::CursorLeft { device_id },
+            RecordedEvent::MouseWheel(delta) => WindowEvent::MouseWheel {
+                device_id,
+                delta,
+                phase: TouchPhase::Moved,
+                modifiers,
+            },
+            RecordedEvent::MouseInput { button, pressed } => WindowEvent::MouseInput {
+                device_id,
+                state: state(pressed),
+                button,
+                modifiers,
+            },
+            RecordedEvent::Touch { id, phase, x, y } => WindowEvent::Touch(Touch {
+                device_id,
+                phase,
+                location: PhysicalPosition::new(x, y),
+                force: None,
+                id,
+            }),
+        })
+    }
+}
+
+/// An entry in a [`Recording`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Time since start of the event loop
+    pub time: Duration,
+    /// Target window
+    pub window: WindowId,
+    /// The event
+    pub event: RecordedEvent,
+}
+
+/// A sequence of recorded events
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// Events, in order of time
+    pub entries: Vec<Entry>,
+}
+
+impl Recording {
+    /// Read from a file, guessing the format from the path
+    pub fn read_path(path: &Path) -> Result<Self, kas::config::Error> {
+        kas::config::Format::guess_and_read_path(path)
+    }
+
+    /// Write to a file, guessing the format from the path
+    pub fn write_path(&self, path: &Path) -> Result<(), kas::config::Error> {
+        kas::config::Format::guess_and_write_path(path, self)
+    }
+}
+
+/// Records events received by the event loop
+pub(crate) struct Recorder {
+    start: Instant,
+    path: PathBuf,
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl Recorder {
+    pub(crate) fn new(start: Instant, path: PathBuf) -> Self {
+        info!("Recording events to {}", path.display());
+        let recording = Arc::new(Mutex::new(Recording::default()));
+
+        // Save on panic (after the previous hook reports it). The hook does
+        // nothing once the recorder is dropped.
+        let weak = Arc::downgrade(&recording);
+        let hook_path = path.clone();
+        let prev_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            prev_hook(info);
+            let recording = match weak.upgrade() {
+                Some(recording) => recording,
+                None => return,
+            };
+            let result = match recording.try_lock() {
+                Ok(guard) => guard.write_path(&hook_path),
+                Err(TryLockError::Poisoned(error)) => error.into_inner().write_path(&hook_path),
+                Err(TryLockError::WouldBlock) => return,
+            };
+            match result {
+                Ok(()) => eprintln!("Event recording saved to {}", hook_path.display()),
+                Err(error) => eprintln!("Failed to save event recording: {}", error),
+            }
+        }));
+
+        Recorder {
+            start,
+            path,
+            recording,
+        }
+    }
+
+    pub(crate) fn record(&mut self, window: WindowId, event: &WindowEvent) {
+        if let Some(event) = RecordedEvent::from_winit(event) {
+            let time = self.start.elapsed();
+            let entry = Entry {
+                time,
+                window,
+                event,
+            };
+            let mut recording = self.recording.lock().unwrap();
+            recording.entries.push(entry);
+        }
+    }
+
+    pub(crate) fn save(&self) -> Result<(), kas::config::Error> {
+        let recording = self.recording.lock().unwrap();
+        recording.write_path(&self.path)
+    }
+}
+
+/// Replays a [`Recording`]
+pub(crate) struct Replayer {
+    start: Instant,
+    entries: VecDeque<Entry>,
+}
+
+impl Replayer {
+    pub(crate) fn new(start: Instant, recording: Recording) -> Self {
+        Replayer {
+            start,
+            entries: recording.entries.into(),
+        }
+    }
+
+    /// Time of the next event, if any
+    pub(crate) fn next_time(&self) -> Option<Instant> {
+        self.entries.front().map(|entry| self.start + entry.time)
+    }
+
+    /// Take the next event, if due by `now`
+    pub(crate) fn pop_due(&mut self, now: Instant) -> Option<Entry> {
+        if self.next_time()? <= now {
+            let entry = self.entries.pop_front();
+            if self.entries.is_empty() {
+                info!("Replay finished");
+            }
+            entry
+        } else {
+            None
+        }
+    }
+
+    /// Handle a replayed window-management event
+    ///
+    /// Events other than resizes and scale-factor changes are ignored.
+    pub(crate) fn apply_to_window(window: &winit::window::Window, event: &RecordedEvent) {
+        match *event {
+            RecordedEvent::Resized(w, h) => window.set_inner_size(PhysicalSize::new(w, h)),
+            RecordedEvent::ScaleFactorChanged(factor) => {
+                if factor != window.scale_factor() {
+                    warn!(
+                        "Replay: unable to change scale factor from {} to {}",
+                        window.scale_factor(),
+                        factor
+                    );
+                }
+            }
+            _ => (),
+        }
+    }
+}
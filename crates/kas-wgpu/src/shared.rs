@@ -5,35 +5,65 @@
 
 //! Shared state
 
-use log::info;
+use instant::Instant;
+use log::{debug, info, warn};
 use std::cell::RefCell;
 use std::num::NonZeroU32;
 use std::rc::Rc;
+use std::time::Duration;
 
 use crate::draw::{CustomPipe, CustomPipeBuilder, DrawPipe, DrawWindow};
+use crate::options::{FallbackAdapter, Software, WarmUp};
+use crate::startup::StartupTimings;
 use crate::{warn_about_error, Error, Options, WindowId};
 use kas::draw;
-use kas::event::UpdateHandle;
-use kas::TkAction;
+use kas::event::{CursorIcon, UpdateHandle};
+use kas::l10n::Localizer;
+use kas::state::{MemoryStore, StateStore};
+use kas::{CaptureRequest, TkAction};
+use kas_shell::{Pending, PendingKind, PendingStats};
 use kas_theme::{Theme, ThemeConfig};
 
-#[cfg(feature = "clipboard")]
+#[cfg(all(feature = "clipboard", target_arch = "wasm32"))]
+use crate::web::Clipboard;
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
 use window_clipboard::Clipboard;
 
 /// State shared between windows
 pub struct SharedState<C: CustomPipe, T> {
     #[cfg(feature = "clipboard")]
     clipboard: Option<Clipboard>,
-    pub instance: wgpu::Instance,
+    /// The graphics instance (`None` when using an external device or the
+    /// software renderer)
+    pub instance: Option<wgpu::Instance>,
     pub draw: draw::SharedState<DrawPipe<C>>,
     pub theme: T,
     pub config: Rc<RefCell<kas::event::Config>>,
+    /// Persisted UI state
+    pub state: Box<dyn StateStore>,
+    pub localizer: Option<Box<dyn Localizer>>,
     pub pending: Vec<PendingAction>,
+    pub stats: PendingStats,
     /// Newly created windows need to know the scale_factor *before* they are
     /// created. This is used to estimate ideal window size.
     pub scale_factor: f64,
     window_id: u32,
-    options: Options,
+    pub options: Options,
+    pub startup: StartupTimings,
+    /// True when pipeline warm-up should be done (after the first frame)
+    pub warm_up_pending: bool,
+    #[cfg(feature = "tray")]
+    pub tray: Option<crate::tray::TrayState>,
+    /// Last cursor icon requested without a window (used when embedded)
+    pub cursor_icon: CursorIcon,
+    /// Update handles of capture requests dropped without completion
+    pub dropped_captures: Rc<RefCell<Vec<UpdateHandle>>>,
+    /// Proxy used to schedule animation frames
+    #[cfg(target_arch = "wasm32")]
+    pub proxy: Option<winit::event_loop::EventLoopProxy<crate::ProxyAction>>,
+    /// Canvas to use for the next window
+    #[cfg(target_arch = "wasm32")]
+    pub canvas: Option<web_sys::HtmlCanvasElement>,
 }
 
 impl<C: CustomPipe, T: Theme<DrawPipe<C>>> SharedState<C, T>
@@ -41,44 +71,201 @@ where
     T::Window: kas_theme::Window,
 {
     /// Construct
-    pub fn new<CB: CustomPipeBuilder<Pipe = C>>(
+    ///
+    /// Adapter and device requests are asynchronous. On native platforms,
+    /// callers may simply block on the result.
+    ///
+    /// If no graphics adapter is found (or [`Software::Always`] is used), the
+    /// software renderer is used where enabled (see [`Options::software`]).
+    pub async fn new<CB: CustomPipeBuilder<Pipe = C>>(
         custom: CB,
-        mut theme: T,
+        theme: T,
         options: Options,
         config: Rc<RefCell<kas::event::Config>>,
         scale_factor: f64,
     ) -> Result<Self, Error> {
-        let instance = wgpu::Instance::new(options.backend());
-        let adapter_options = options.adapter_options();
-        let req = instance.request_adapter(&adapter_options);
-        let adapter = match futures::executor::block_on(req) {
-            Some(a) => a,
-            None => return Err(Error::NoAdapter),
+        let mut startup = StartupTimings::new();
+        let software = cfg!(feature = "software").then(|| options.software);
+        let result = match software {
+            Some(Software::Always) => Err(Error::NoAdapter),
+            _ => Self::request_device(&custom, &options, &mut startup).await,
+        };
+
+        match result {
+            Ok((instance, device_and_queue)) => {
+                let mut shared = Self::with_device(
+                    custom,
+                    theme,
+                    options,
+                    config,
+                    scale_factor,
+                    device_and_queue,
+                    startup,
+                );
+                shared.instance = Some(instance);
+                Ok(shared)
+            }
+            #[cfg(feature = "software")]
+            Err(error) if software != Some(Software::Never) => {
+                if software == Some(Software::Auto) {
+                    warn!("{}; using the software renderer", error);
+                }
+                let time = Instant::now();
+                let pipe = DrawPipe::new_software(theme.config().raster());
+                startup.pipelines = time.elapsed();
+                Ok(Self::with_pipe(
+                    theme,
+                    options,
+                    config,
+                    scale_factor,
+                    pipe,
+                    startup,
+                ))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Request a graphics adapter and device
+    async fn request_device<CB: CustomPipeBuilder<Pipe = C>>(
+        custom: &CB,
+        options: &Options,
+        startup: &mut StartupTimings,
+    ) -> Result<(wgpu::Instance, (wgpu::Device, wgpu::Queue)), Error> {
+        let mut time = startup.start;
+        let mut lap = |d: &mut Duration| {
+            let now = Instant::now();
+            *d = now - time;
+            time = now;
         };
-        info!("Using graphics adapter: {}", adapter.get_info().name);
 
-        let desc = CB::device_descriptor();
+        let instance = wgpu::Instance::new(options.backend());
+        let mut adapter = None;
+        let fallback = options.fallback_adapter;
+        if fallback != FallbackAdapter::Always {
+            let adapter_options = options.adapter_options(false);
+            adapter = instance.request_adapter(&adapter_options).await;
+        }
+        if adapter.is_none() && fallback != FallbackAdapter::Never {
+            if fallback == FallbackAdapter::Auto {
+                warn!("No graphics adapter found; trying the fallback adapter");
+            }
+            let adapter_options = options.adapter_options(true);
+            adapter = instance.request_adapter(&adapter_options).await;
+        }
+        let adapter = adapter.ok_or(Error::NoAdapter)?;
+        let info = adapter.get_info();
+        info!("Using graphics adapter: {} ({:?})", info.name, info.backend);
+        if info.device_type == wgpu::DeviceType::Cpu {
+            info!("Using a CPU graphics adapter: performance may be poor");
+        }
+        lap(&mut startup.adapter);
+
+        let mut desc = custom.device_descriptor();
+        let timestamps = wgpu::Features::TIMESTAMP_QUERY;
+        if options.draw_stats && adapter.features().contains(timestamps) {
+            desc.features |= timestamps;
+        }
         let trace_path = options.wgpu_trace_path.as_deref();
         let req = adapter.request_device(&desc, trace_path);
-        let device_and_queue = futures::executor::block_on(req)?;
+        let device_and_queue = req.await?;
+        lap(&mut startup.device);
+        Ok((instance, device_and_queue))
+    }
 
+    /// Construct over an existing device
+    ///
+    /// The device should be constructed with (at least) the features and
+    /// limits of [`CustomPipeBuilder::device_descriptor`].
+    pub fn with_device<CB: CustomPipeBuilder<Pipe = C>>(
+        custom: CB,
+        theme: T,
+        options: Options,
+        config: Rc<RefCell<kas::event::Config>>,
+        scale_factor: f64,
+        device_and_queue: (wgpu::Device, wgpu::Queue),
+        mut startup: StartupTimings,
+    ) -> Self {
+        let time = Instant::now();
         let pipe = DrawPipe::new(custom, device_and_queue, theme.config().raster());
+        startup.pipelines = time.elapsed();
+        Self::with_pipe(theme, options, config, scale_factor, pipe, startup)
+    }
+
+    fn with_pipe(
+        mut theme: T,
+        options: Options,
+        config: Rc<RefCell<kas::event::Config>>,
+        scale_factor: f64,
+        mut pipe: DrawPipe<C>,
+        mut startup: StartupTimings,
+    ) -> Self {
+        let time = Instant::now();
+        if options.warm_up == WarmUp::Eager {
+            pipe.warm_up();
+        }
         let mut draw = draw::SharedState::new(pipe);
+        startup.pipelines += time.elapsed();
 
+        let time = Instant::now();
         theme.init(&mut draw);
+        startup.theme = time.elapsed();
 
-        Ok(SharedState {
+        SharedState {
             #[cfg(feature = "clipboard")]
             clipboard: None,
-            instance,
+            instance: None,
             draw,
             theme,
             config,
+            state: Box::new(MemoryStore::new()),
+            localizer: None,
             pending: vec![],
+            stats: Default::default(),
             scale_factor,
             window_id: 0,
             options,
-        })
+            startup,
+            warm_up_pending: false,
+            #[cfg(feature = "tray")]
+            tray: None,
+            cursor_icon: CursorIcon::Default,
+            dropped_captures: Default::default(),
+            #[cfg(target_arch = "wasm32")]
+            proxy: None,
+            #[cfg(target_arch = "wasm32")]
+            canvas: None,
+        }
+    }
+
+    /// Note presentation of a frame at `time`
+    ///
+    /// On the first frame, this reports start-up timings and schedules
+    /// pipeline warm-up (if enabled).
+    pub fn frame_presented(&mut self, time: Instant) {
+        if self.startup.first_frame.is_some() {
+            return;
+        }
+        self.startup.first_frame = Some(time - self.startup.start);
+        self.report_startup();
+        self.warm_up_pending = self.options.warm_up == WarmUp::AfterFirstFrame;
+    }
+
+    /// Construct remaining draw pipelines
+    pub fn warm_up(&mut self) {
+        self.warm_up_pending = false;
+        let time = Instant::now();
+        self.draw.draw.warm_up();
+        self.startup.warm_up = Some(time.elapsed());
+        self.report_startup();
+    }
+
+    fn report_startup(&self) {
+        if self.options.startup_stats {
+            info!("Start-up timings: {}", self.startup);
+        } else {
+            debug!("Start-up timings: {}", self.startup);
+        }
     }
 
     /// Initialise the clipboard context
@@ -95,26 +282,58 @@ where
         }
     }
 
+    /// True if a clipboard context is available
+    pub fn has_clipboard(&self) -> bool {
+        #[cfg(feature = "clipboard")]
+        {
+            self.clipboard.is_some()
+        }
+        #[cfg(not(feature = "clipboard"))]
+        false
+    }
+
     pub fn next_window_id(&mut self) -> WindowId {
         self.window_id += 1;
         WindowId::new(NonZeroU32::new(self.window_id).unwrap())
     }
 
+    /// Render `window` to `frame_view`
+    ///
+    /// If `clear_color` is `None`, the existing contents are drawn over.
     pub fn render(
         &mut self,
         window: &mut DrawWindow<C::Window>,
         frame_view: &wgpu::TextureView,
-        clear_color: wgpu::Color,
+        clear_color: Option<wgpu::Color>,
     ) {
         self.draw.draw.render(window, frame_view, clear_color);
     }
 
+    /// Render `window` to `pixmap` using the software renderer
+    ///
+    /// If `clear_color` is `None`, the existing contents are drawn over.
+    #[cfg(feature = "software")]
+    pub fn render_pixmap(
+        &mut self,
+        window: &mut DrawWindow<C::Window>,
+        pixmap: &mut tiny_skia::Pixmap,
+        clear_color: Option<kas::draw::color::Rgba>,
+    ) {
+        self.draw.draw.render_pixmap(window, pixmap, clear_color);
+    }
+
     #[inline]
-    pub fn get_clipboard(&mut self) -> Option<String> {
+    pub fn get_clipboard(&mut self, _window: Option<&winit::window::Window>) -> Option<String> {
         #[cfg(feature = "clipboard")]
         {
-            self.clipboard.as_ref().and_then(|cb| match cb.read() {
+            #[cfg(target_arch = "wasm32")]
+            let read = |cb: &Clipboard| cb.read(_window.map(|w| w.id()), self.proxy.as_ref());
+            #[cfg(not(target_arch = "wasm32"))]
+            let read = |cb: &Clipboard| cb.read();
+            self.clipboard.as_ref().and_then(|cb| match read(cb) {
                 Ok(c) => Some(c),
+                #[cfg(target_arch = "wasm32")]
+                Err(e) if e.is::<crate::web::ReadPending>() => None,
                 Err(e) => {
                     warn_about_error("Failed to get clipboard contents", e.as_ref());
                     None
@@ -125,6 +344,17 @@ where
         None
     }
 
+    /// Discard clipboard contents received for a repeated paste
+    ///
+    /// See [`crate::web::Clipboard::read`].
+    #[cfg(target_arch = "wasm32")]
+    pub fn discard_clipboard(&mut self) {
+        #[cfg(feature = "clipboard")]
+        if let Some(cb) = self.clipboard.as_ref() {
+            cb.discard();
+        }
+    }
+
     #[inline]
     pub fn set_clipboard(&mut self, _content: String) {
         #[cfg(feature = "clipboard")]
@@ -140,7 +370,22 @@ where
         self.pending.push(PendingAction::Update(handle, payload));
     }
 
-    pub fn on_exit(&self) {
+    /// Set a hook on `request` to trigger its update handle if dropped
+    pub fn track_capture(&self, request: &mut CaptureRequest) {
+        let dropped = self.dropped_captures.clone();
+        request.set_drop_hook(Box::new(move |handle| dropped.borrow_mut().push(handle)));
+    }
+
+    /// Queue updates for capture requests dropped without completion
+    pub fn queue_dropped_captures(&mut self) {
+        let handles = std::mem::take(&mut *self.dropped_captures.borrow_mut());
+        for handle in handles {
+            self.trigger_update(handle, 0);
+        }
+    }
+
+    pub fn on_exit(&mut self) {
+        debug!("Pending action counters: {:?}", self.stats);
         match self
             .options
             .write_config(&self.config.borrow(), &self.theme)
@@ -148,6 +393,9 @@ where
             Ok(()) => (),
             Err(error) => warn_about_error("Failed to save config", &error),
         }
+        if let Err(error) = self.state.flush() {
+            warn_about_error("Failed to save UI state", &error);
+        }
     }
 }
 
@@ -155,6 +403,24 @@ pub enum PendingAction {
     AddPopup(winit::window::WindowId, WindowId, kas::Popup),
     AddWindow(WindowId, Box<dyn kas::Window>),
     CloseWindow(WindowId),
+    Capture(CaptureRequest),
     Update(kas::event::UpdateHandle, u64),
     TkAction(TkAction),
 }
+
+impl Pending for PendingAction {
+    fn kind(&self) -> PendingKind {
+        match self {
+            PendingAction::AddPopup(..)
+            | PendingAction::AddWindow(..)
+            | PendingAction::CloseWindow(_) => PendingKind::WindowChange,
+            PendingAction::Capture(_) => PendingKind::Other,
+            PendingAction::Update(handle, payload) => PendingKind::Update(*handle, *payload),
+            PendingAction::TkAction(action) => PendingKind::Action(*action),
+        }
+    }
+
+    fn from_action(action: TkAction) -> Self {
+        PendingAction::TkAction(action)
+    }
+}
@@ -0,0 +1,90 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Start-up timing
+//!
+//! The shell measures the main phases of start-up: reading config, acquiring
+//! a graphics adapter and device, constructing draw pipelines, initialising
+//! the theme (including loading fonts) and constructing windows, as well as
+//! the time until the first frame is presented. These are reported once the
+//! first frame has been presented, and again after pipeline warm-up (see
+//! [`WarmUp`](crate::options::WarmUp)).
+//!
+//! Timings are logged at level `debug`, or at level `info` when enabled via
+//! [`Options`](crate::Options) (see `KAS_STARTUP_STATS` in
+//! [`Options::from_env`](crate::Options::from_env)). Timings measured before
+//! the event loop starts are available via
+//! [`Toolkit::startup_timings`](crate::Toolkit::startup_timings).
+
+use instant::Instant;
+use std::fmt;
+use std::time::Duration;
+
+/// Start-up timings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StartupTimings {
+    /// Time at which construction of the toolkit started
+    pub start: Instant,
+    /// Time to read config (including theme config)
+    pub config: Duration,
+    /// Time to acquire a graphics adapter
+    pub adapter: Duration,
+    /// Time to acquire a graphics device
+    pub device: Duration,
+    /// Time to construct draw pipelines required immediately
+    ///
+    /// With [`WarmUp::Eager`](crate::options::WarmUp::Eager), this includes
+    /// all pipelines.
+    pub pipelines: Duration,
+    /// Time to initialise the theme (including loading fonts)
+    pub theme: Duration,
+    /// Time to construct windows before the event loop starts
+    pub windows: Duration,
+    /// Time from `start` until presentation of the first frame
+    pub first_frame: Option<Duration>,
+    /// Time to construct remaining pipelines after the first frame
+    pub warm_up: Option<Duration>,
+}
+
+impl StartupTimings {
+    /// Construct, starting now
+    pub(crate) fn new() -> Self {
+        StartupTimings {
+            start: Instant::now(),
+            config: Duration::ZERO,
+            adapter: Duration::ZERO,
+            device: Duration::ZERO,
+            pipelines: Duration::ZERO,
+            theme: Duration::ZERO,
+            windows: Duration::ZERO,
+            first_frame: None,
+            warm_up: None,
+        }
+    }
+}
+
+impl fmt::Display for StartupTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1e3;
+        write!(
+            f,
+            "config {:.1}ms, adapter {:.1}ms, device {:.1}ms, pipelines {:.1}ms, \
+            theme {:.1}ms, windows {:.1}ms",
+            ms(self.config),
+            ms(self.adapter),
+            ms(self.device),
+            ms(self.pipelines),
+            ms(self.theme),
+            ms(self.windows),
+        )?;
+        if let Some(d) = self.first_frame {
+            write!(f, "; first frame at {:.1}ms", ms(d))?;
+        }
+        if let Some(d) = self.warm_up {
+            write!(f, "; warm-up {:.1}ms", ms(d))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! System tray icon and desktop notifications
+//!
+//! A tray icon is added via [`crate::Toolkit::add_tray_icon`]. Activating the
+//! icon (usually a left click) opens its menu, if any; this is a normal KAS
+//! window (typically a column of [`MenuEntry`] widgets) opened as an
+//! undecorated pop-up at the pointer position and closed on loss of focus.
+//!
+//! Notifications are shown via [`crate::ToolkitProxy::notify`].
+//!
+//! Other interactions are reported as a [`TrayEvent`] to widgets subscribed
+//! to a designated [`TypedUpdateHandle`] (see
+//! [`kas::event::Manager::update_on_handle`]); the widget receives
+//! [`kas::event::Event::HandleUpdate`] and may decode the event via
+//! [`TypedUpdateHandle::payload`].
+//!
+//! The tray icon is currently only supported on Linux and BSD (via the
+//! StatusNotifierItem protocol).
+//!
+//! [`MenuEntry`]: https://docs.rs/kas-widgets/latest/kas_widgets/struct.MenuEntry.html
+
+use kas::event::{TypedUpdateHandle, UpdatePayload};
+use kas::WindowId;
+use winit::event_loop::EventLoopProxy;
+
+use crate::ProxyAction;
+
+/// An event from the tray icon or a notification
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrayEvent {
+    /// The tray icon was activated (usually a left click)
+    ///
+    /// This is only sent when the tray icon does not have a menu.
+    Activate,
+    /// Secondary activation of the tray icon (usually a middle click)
+    SecondaryActivate,
+    /// The user invoked the action with index `action` on the notification
+    /// with the given `tag`
+    NotificationAction { tag: u32, action: u16 },
+    /// The notification with the given `tag` was closed
+    NotificationClosed { tag: u32 },
+}
+
+impl UpdatePayload for TrayEvent {
+    fn to_payload(self) -> u64 {
+        match self {
+            TrayEvent::Activate => 0,
+            TrayEvent::SecondaryActivate => 1 << 48,
+            TrayEvent::NotificationAction { tag, action } => {
+                (2 << 48) | (u64::from(tag) << 16) | u64::from(action)
+            }
+            TrayEvent::NotificationClosed { tag } => (3 << 48) | (u64::from(tag) << 16),
+        }
+    }
+
+    fn from_payload(payload: u64) -> Self {
+        let tag = (payload >> 16) as u32;
+        match payload >> 48 {
+            1 => TrayEvent::SecondaryActivate,
+            2 => TrayEvent::NotificationAction {
+                tag,
+                action: payload as u16,
+            },
+            3 => TrayEvent::NotificationClosed { tag },
+            _ => TrayEvent::Activate,
+        }
+    }
+}
+
+/// A system tray icon
+///
+/// Construct, then add via [`crate::Toolkit::add_tray_icon`].
+pub struct TrayIcon {
+    title: String,
+    tooltip: String,
+    icon: Option<(u32, u32, Vec<u8>)>,
+    menu: Option<Box<dyn FnMut() -> Box<dyn kas::Window>>>,
+    events: Option<TypedUpdateHandle<TrayEvent>>,
+}
+
+impl TrayIcon {
+    /// Construct, with the given `title`
+    ///
+    /// The title is also used as the tooltip by default.
+    pub fn new<T: ToString>(title: T) -> Self {
+        let title = title.to_string();
+        TrayIcon {
+            tooltip: title.clone(),
+            title,
+            icon: None,
+            menu: None,
+            events: None,
+        }
+    }
+
+    /// Set the tooltip (inline)
+    pub fn with_tooltip<T: ToString>(mut self, tooltip: T) -> Self {
+        self.tooltip = tooltip.to_string();
+        self
+    }
+
+    /// Set the icon (inline)
+    ///
+    /// The icon is specified as non-premultiplied RGBA data of the given
+    /// `width` and `height`. If the length of `rgba` is not
+    /// `4 * width * height`, the icon is ignored.
+    pub fn with_icon(mut self, width: u32, height: u32, rgba: Vec<u8>) -> Self {
+        if rgba.len() == 4 * (width as usize) * (height as usize) {
+            self.icon = Some((width, height, rgba));
+        } else {
+            log::warn!("TrayIcon::with_icon: bad icon data length");
+        }
+        self
+    }
+
+    /// Set the menu (inline)
+    ///
+    /// The function `f` constructs the menu window each time the tray icon is
+    /// activated. The window is opened without decorations at the pointer
+    /// position and closed when it loses focus. Menu entries should close the
+    /// window when activated (e.g. via [`kas::TkAction::CLOSE`]).
+    pub fn with_menu<F>(mut self, f: F) -> Self
+    where
+        F: FnMut() -> Box<dyn kas::Window> + 'static,
+    {
+        self.menu = Some(Box::new(f));
+        self
+    }
+
+    /// Set the handle on which to send [`TrayEvent`]s (inline)
+    pub fn with_events(mut self, handle: TypedUpdateHandle<TrayEvent>) -> Self {
+        self.events = Some(handle);
+        self
+    }
+}
+
+/// Tray state held by the toolkit
+pub(crate) struct TrayState {
+    menu: Option<Box<dyn FnMut() -> Box<dyn kas::Window>>>,
+    events: Option<TypedUpdateHandle<TrayEvent>>,
+    /// The open menu window, if any
+    pub(crate) menu_window: Option<WindowId>,
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "android"))))]
+    _handle: ksni::Handle<SniTray>,
+}
+
+impl TrayState {
+    /// Create the platform tray icon
+    ///
+    /// Returns `None` where not supported.
+    pub(crate) fn new(icon: TrayIcon, proxy: EventLoopProxy<ProxyAction>) -> Option<Self> {
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "android"))))]
+        {
+            let tray = SniTray {
+                title: icon.title,
+                tooltip: icon.tooltip,
+                icon: icon.icon,
+                proxy,
+            };
+            let service = ksni::TrayService::new(tray);
+            let handle = service.handle();
+            service.spawn();
+            Some(TrayState {
+                menu: icon.menu,
+                events: icon.events,
+                menu_window: None,
+                _handle: handle,
+            })
+        }
+        #[cfg(not(all(unix, not(any(target_os = "macos", target_os = "android")))))]
+        {
+            let _ = (icon, proxy);
+            None
+        }
+    }
+
+    /// Construct the menu window, if any
+    pub(crate) fn build_menu(&mut self) -> Option<Box<dyn kas::Window>> {
+        self.menu.as_mut().map(|f| f())
+    }
+
+    /// Get the update to send for `event`, if any
+    pub(crate) fn event(&self, event: TrayEvent) -> Option<(kas::event::UpdateHandle, u64)> {
+        self.events
+            .map(|handle| (handle.handle(), event.to_payload()))
+    }
+}
+
+/// Action from the tray icon (sent via the event-loop proxy)
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum TrayAction {
+    /// Primary activation, at the given screen position
+    Activate(i32, i32),
+    /// Secondary activation
+    SecondaryActivate,
+}
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "android"))))]
+struct SniTray {
+    title: String,
+    tooltip: String,
+    icon: Option<(u32, u32, Vec<u8>)>,
+    proxy: EventLoopProxy<ProxyAction>,
+}
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "android"))))]
+impl ksni::Tray for SniTray {
+    fn activate(&mut self, x: i32, y: i32) {
+        let action = ProxyAction::Tray(TrayAction::Activate(x, y));
+        let _ = self.proxy.send_event(action);
+    }
+
+    fn secondary_activate(&mut self, _: i32, _: i32) {
+        let action = ProxyAction::Tray(TrayAction::SecondaryActivate);
+        let _ = self.proxy.send_event(action);
+    }
+
+    fn id(&self) -> String {
+        self.title.clone()
+    }
+
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+        match self.icon {
+            Some((width, height, ref rgba)) => {
+                // SNI uses ARGB32 in network byte order
+                let data = rgba
+                    .chunks_exact(4)
+                    .flat_map(|p| [p[3], p[0], p[1], p[2]])
+                    .collect();
+                vec![ksni::Icon {
+                    width: width as i32,
+                    height: height as i32,
+                    data,
+                }]
+            }
+            None => vec![],
+        }
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        ksni::ToolTip {
+            title: self.tooltip.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A desktop notification
+///
+/// Show via [`crate::ToolkitProxy::notify`].
+#[derive(Clone, Debug, Default)]
+pub struct Notification {
+    tag: u32,
+    summary: String,
+    body: String,
+    icon: Option<String>,
+    actions: Vec<String>,
+    events: Option<TypedUpdateHandle<TrayEvent>>,
+}
+
+impl Notification {
+    /// Construct, with the given `summary` (title)
+    pub fn new<T: ToString>(summary: T) -> Self {
+        Notification {
+            summary: summary.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the body text (inline)
+    pub fn with_body<T: ToString>(mut self, body: T) -> Self {
+        self.body = body.to_string();
+        self
+    }
+
+    /// Set the icon, by name or path (inline)
+    pub fn with_icon<T: ToString>(mut self, icon: T) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    /// Add an action (inline)
+    ///
+    /// Actions are reported via [`TrayEvent::NotificationAction`] with the
+    /// index of the action in order of addition. Not all platforms support
+    /// actions.
+    pub fn with_action<T: ToString>(mut self, label: T) -> Self {
+        self.actions.push(label.to_string());
+        self
+    }
+
+    /// Set a tag identifying the notification in [`TrayEvent`]s (inline)
+    pub fn with_tag(mut self, tag: u32) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    /// Set the handle on which to send [`TrayEvent`]s (inline)
+    pub fn with_events(mut self, handle: TypedUpdateHandle<TrayEvent>) -> Self {
+        self.events = Some(handle);
+        self
+    }
+
+    /// Show the notification
+    ///
+    /// Where a handle was set via [`Notification::with_events`], responses
+    /// are sent via `proxy`.
+    pub(crate) fn show(&self, proxy: EventLoopProxy<ProxyAction>) {
+        let mut n = notify_rust::Notification::new();
+        n.summary(&self.summary).body(&self.body);
+        if let Some(ref icon) = self.icon {
+            n.icon(icon);
+        }
+        for (i, label) in self.actions.iter().enumerate() {
+            n.action(&i.to_string(), label);
+        }
+
+        let handle = match n.show() {
+            Ok(handle) => handle,
+            Err(error) => {
+                crate::warn_about_error("Failed to show notification", &error);
+                return;
+            }
+        };
+
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "android"))))]
+        if let Some(events) = self.events {
+            let tag = self.tag;
+            std::thread::spawn(move || {
+                handle.wait_for_action(|action| {
+                    let event = match action.parse::<u16>() {
+                        Ok(action) => TrayEvent::NotificationAction { tag, action },
+                        Err(_) => TrayEvent::NotificationClosed { tag },
+                    };
+                    let update = ProxyAction::Update(events.handle(), event.to_payload());
+                    let _ = proxy.send_event(update);
+                });
+            });
+        }
+        #[cfg(not(all(unix, not(any(target_os = "macos", target_os = "android")))))]
+        let _ = (handle, proxy);
+    }
+}
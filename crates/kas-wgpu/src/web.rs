@@ -0,0 +1,253 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Web (WebAssembly) support
+//!
+//! On `wasm32-unknown-unknown`, this shell runs in the browser using winit's
+//! web backend and WebGPU. Building requires
+//! `RUSTFLAGS=--cfg=web_sys_unstable_apis` (WebGPU and the web Clipboard API
+//! are unstable in `web-sys`).
+//!
+//! Since graphics initialisation is asynchronous on the web, the toolkit must
+//! be constructed with [`Toolkit::new_async`] or [`Toolkit::new_custom_async`]
+//! from within an async context, usually via [`spawn_local`]. Windows are
+//! drawn to a `<canvas>` element: by default, a new canvas is appended to
+//! the document body, while [`Toolkit::set_canvas`] embeds the next window in
+//! an existing canvas. [`Toolkit::run`] does not return.
+//!
+//! ```ignore
+//! use wasm_bindgen::prelude::*;
+//!
+//! #[wasm_bindgen(start)]
+//! pub fn start() {
+//!     kas_wgpu::web::spawn_local(async {
+//!         let theme = kas_theme::FlatTheme::new();
+//!         let mut toolkit = kas_wgpu::Toolkit::new_async(theme).await.unwrap();
+//!         if let Some(canvas) = kas_wgpu::web::canvas_by_id("kas") {
+//!             toolkit.set_canvas(canvas);
+//!         }
+//!         toolkit.with(MyWindow::new()).unwrap().run()
+//!     });
+//! }
+//! ```
+//!
+//! Redraws are scheduled with `requestAnimationFrame`. No system fonts are
+//! available in the browser; fonts must be loaded from data (see
+//! [`load_font_data`]) before the toolkit is constructed.
+//!
+//! Since the web Clipboard API is asynchronous, a paste first requests the
+//! clipboard contents, then (once these arrive) the paste command is repeated
+//! with the contents received. Without `web_sys_unstable_apis`, the clipboard
+//! is local to the application.
+//!
+//! [`Toolkit::new_async`]: crate::Toolkit::new_async
+//! [`Toolkit::new_custom_async`]: crate::Toolkit::new_custom_async
+//! [`Toolkit::set_canvas`]: crate::Toolkit::set_canvas
+//! [`Toolkit::run`]: crate::Toolkit::run
+
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use winit::event_loop::EventLoopProxy;
+use winit::platform::web::WindowExtWebSys;
+use winit::window as ww;
+
+use crate::ProxyAction;
+
+pub use wasm_bindgen_futures::spawn_local;
+pub use web_sys::HtmlCanvasElement;
+
+/// Find a `<canvas>` element in the document by its `id`
+pub fn canvas_by_id(id: &str) -> Option<HtmlCanvasElement> {
+    let document = web_sys::window()?.document()?;
+    document.get_element_by_id(id)?.dyn_into().ok()
+}
+
+/// Load a font from data
+///
+/// Fonts loaded this way are available to all themes. Since no system fonts
+/// are available in the browser, at least one font must be loaded before
+/// constructing the toolkit.
+pub fn load_font_data(data: Vec<u8>) {
+    kas::text::fonts::fonts().update_db(|db| db.load_font_data(data));
+}
+
+/// Append the window's canvas to the document body, unless already attached
+pub(crate) fn attach_canvas(window: &ww::Window) {
+    let canvas = window.canvas();
+    if canvas.parent_node().is_some() {
+        return;
+    }
+    let body = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.body());
+    match body {
+        Some(body) => {
+            if body.append_child(&canvas).is_err() {
+                log::warn!("Failed to attach canvas to document");
+            }
+        }
+        None => log::warn!("Failed to attach canvas: no document body"),
+    }
+}
+
+/// Schedules redraws of a window on the browser's animation frames
+pub(crate) struct AnimationFrame {
+    proxy: EventLoopProxy<ProxyAction>,
+    id: ww::WindowId,
+    pending: Rc<Cell<bool>>,
+}
+
+impl AnimationFrame {
+    pub fn new(proxy: EventLoopProxy<ProxyAction>, id: ww::WindowId) -> Self {
+        AnimationFrame {
+            proxy,
+            id,
+            pending: Default::default(),
+        }
+    }
+
+    /// Request a redraw on the next animation frame
+    ///
+    /// Multiple requests before the next frame are coalesced.
+    pub fn request(&self) {
+        if self.pending.replace(true) {
+            return;
+        }
+        let (proxy, id, pending) = (self.proxy.clone(), self.id, self.pending.clone());
+        let callback = Closure::once_into_js(move || {
+            pending.set(false);
+            let _ = proxy.send_event(ProxyAction::AnimationFrame(id));
+        });
+        let result = web_sys::window()
+            .ok_or(wasm_bindgen::JsValue::NULL)
+            .and_then(|window| window.request_animation_frame(callback.unchecked_ref()));
+        if result.is_err() {
+            log::warn!("requestAnimationFrame failed");
+            self.pending.set(false);
+        }
+    }
+}
+
+/// Clipboard over the web Clipboard API
+///
+/// The API is asynchronous, thus [`Clipboard::read`] first requests the
+/// current contents, then asks the window to repeat the paste command.
+pub(crate) struct Clipboard {
+    // Contents received but not yet read (or, without the Clipboard API, the
+    // last contents written)
+    contents: Rc<RefCell<Option<String>>>,
+}
+
+/// Error returned by [`Clipboard::read`] while contents are requested
+#[cfg_attr(not(web_sys_unstable_apis), allow(dead_code))]
+#[derive(Debug)]
+pub(crate) struct ReadPending;
+
+impl fmt::Display for ReadPending {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "clipboard read pending")
+    }
+}
+
+impl Error for ReadPending {}
+
+impl Clipboard {
+    /// Connect to the clipboard
+    ///
+    /// The window parameter exists for API compatibility with
+    /// `window_clipboard` and is unused. Without `web_sys_unstable_apis`, the
+    /// clipboard is local to the application.
+    pub fn connect(_: &ww::Window) -> Result<Self, Box<dyn Error>> {
+        Ok(Clipboard {
+            contents: Default::default(),
+        })
+    }
+
+    /// Get the clipboard object, if available
+    #[cfg(web_sys_unstable_apis)]
+    fn clipboard() -> Option<web_sys::Clipboard> {
+        let navigator = web_sys::window()?.navigator();
+        let clipboard = js_sys::Reflect::get(&navigator, &"clipboard".into()).ok()?;
+        clipboard.dyn_into().ok()
+    }
+
+    /// Read clipboard contents
+    ///
+    /// If contents were received since the last read, these are returned.
+    /// Otherwise, the current contents are requested and [`ReadPending`] is
+    /// returned; on arrival, [`ProxyAction::Paste`] is sent to repeat the
+    /// paste command in `window`. The request is made while handling the
+    /// paste command, thus browsers accept it as a response to user input.
+    ///
+    /// Without `web_sys_unstable_apis`, this returns the last contents written.
+    pub fn read(
+        &self,
+        window: Option<ww::WindowId>,
+        proxy: Option<&EventLoopProxy<ProxyAction>>,
+    ) -> Result<String, Box<dyn Error>> {
+        #[cfg(web_sys_unstable_apis)]
+        {
+            if let Some(text) = self.contents.borrow_mut().take() {
+                return Ok(text);
+            }
+            let clipboard = Self::clipboard().ok_or("Clipboard API not available")?;
+            let (window, proxy) = match (window, proxy) {
+                (Some(window), Some(proxy)) => (window, proxy.clone()),
+                _ => return Err("clipboard read requires a window".into()),
+            };
+            let contents = self.contents.clone();
+            let promise = clipboard.read_text();
+            spawn_local(async move {
+                match wasm_bindgen_futures::JsFuture::from(promise).await {
+                    Ok(text) => {
+                        *contents.borrow_mut() = Some(text.as_string().unwrap_or_default());
+                        let _ = proxy.send_event(ProxyAction::Paste(window));
+                    }
+                    Err(_) => log::warn!("Failed to read clipboard contents"),
+                }
+            });
+            Err(Box::new(ReadPending))
+        }
+        #[cfg(not(web_sys_unstable_apis))]
+        {
+            let _ = (window, proxy);
+            let contents = self.contents.borrow().clone();
+            contents.ok_or_else(|| "clipboard is empty".into())
+        }
+    }
+
+    /// Discard contents received but not read
+    ///
+    /// This should be called after repeating a paste command, in case the
+    /// command was not handled (thus the contents are stale).
+    pub fn discard(&self) {
+        #[cfg(web_sys_unstable_apis)]
+        self.contents.borrow_mut().take();
+    }
+
+    /// Write clipboard contents
+    pub fn write(&mut self, contents: String) -> Result<(), Box<dyn Error>> {
+        #[cfg(web_sys_unstable_apis)]
+        {
+            let clipboard = Self::clipboard().ok_or("Clipboard API not available")?;
+            let promise = clipboard.write_text(&contents);
+            spawn_local(async move {
+                if wasm_bindgen_futures::JsFuture::from(promise).await.is_err() {
+                    log::warn!("Failed to set clipboard contents");
+                }
+            });
+        }
+        #[cfg(not(web_sys_unstable_apis))]
+        {
+            *self.contents.borrow_mut() = Some(contents);
+        }
+        Ok(())
+    }
+}
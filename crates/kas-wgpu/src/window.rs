@@ -5,25 +5,38 @@
 
 //! `Window` and `WindowList` types
 
-use log::{debug, error, info, trace};
-use std::time::Instant;
+use instant::Instant;
+use log::{debug, error, info, trace, warn};
+use std::time::Duration;
 
 use kas::cast::Cast;
 use kas::draw::{DrawIface, DrawShared, PassId, SizeHandle, ThemeApi};
 use kas::event::{CursorIcon, ManagerState, UpdateHandle};
-use kas::geom::{Coord, Rect, Size};
+use kas::geom::{Coord, Offset, Rect, Size};
 use kas::layout::SolveCache;
-use kas::{TkAction, WindowId};
+use kas::raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+use kas::{CaptureRequest, MonitorInfo, ShellCapabilities, TkAction, WindowId};
 use kas_theme::{Theme, Window as _};
-use winit::dpi::PhysicalSize;
-use winit::error::OsError;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event::WindowEvent;
 use winit::event_loop::EventLoopWindowTarget;
-use winit::window::WindowBuilder;
+use winit::monitor::MonitorHandle;
+use winit::window::{Fullscreen, WindowBuilder};
 
+use crate::capture::{draw_widget, CaptureTarget};
 use crate::draw::{CustomPipe, DrawPipe, DrawWindow};
+#[cfg(feature = "software")]
+use crate::framebuffer::Framebuffer;
+use crate::latency::LatencyTracker;
 use crate::shared::{PendingAction, SharedState};
-use crate::ProxyAction;
+use crate::{Error, ProxyAction};
+
+/// Presentation surface of a window
+enum Surface {
+    Gpu(wgpu::Surface),
+    #[cfg(feature = "software")]
+    Soft(Framebuffer),
+}
 
 /// Per-window data
 pub(crate) struct Window<C: CustomPipe, T: Theme<DrawPipe<C>>> {
@@ -31,12 +44,24 @@ pub(crate) struct Window<C: CustomPipe, T: Theme<DrawPipe<C>>> {
     pub(crate) window_id: WindowId,
     mgr: ManagerState,
     solve_cache: SolveCache,
+    // NOTE: the surface must be dropped before the window
+    surface: Surface,
+    sc_desc: wgpu::SurfaceConfiguration,
     /// The winit window
     pub(crate) window: winit::window::Window,
-    surface: wgpu::Surface,
-    sc_desc: wgpu::SurfaceConfiguration,
     draw: DrawWindow<C::Window>,
     theme_window: T::Window,
+    /// True if a redraw has been requested since the last frame
+    redraw_requested: bool,
+    /// Refresh interval of the current monitor, if known
+    refresh_interval: Option<Duration>,
+    /// Input latency tracker, if enabled
+    latency: Option<LatencyTracker>,
+    /// Pending capture requests, handled after the next frame
+    captures: Vec<CaptureRequest>,
+    /// Animation frame scheduler (web only)
+    #[cfg(target_arch = "wasm32")]
+    frame: Option<crate::web::AnimationFrame>,
 }
 
 // Public functions, for use by the toolkit
@@ -47,14 +72,15 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
         elwt: &EventLoopWindowTarget<ProxyAction>,
         window_id: WindowId,
         mut widget: Box<dyn kas::Window>,
-    ) -> Result<Self, OsError> {
+    ) -> Result<Self, Error> {
         let time = Instant::now();
 
         let scale_factor = shared.scale_factor as f32;
         let mut theme_window = shared.theme.new_window(scale_factor);
 
         let mut mgr = ManagerState::new(shared.config.clone(), scale_factor);
-        let mut tkw = TkWindow::new(shared, None, &mut theme_window);
+        mgr.set_key_diagnostics(shared.options.key_diagnostics);
+        let mut tkw = TkWindow::new(shared, window_id, None, &mut theme_window);
         mgr.configure(&mut tkw, &mut *widget);
 
         let mut size_handle = theme_window.size_handle();
@@ -62,18 +88,58 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
         // Opening a zero-size window causes a crash, so force at least 1x1:
         let ideal = solve_cache.ideal(true).max(Size(1, 1));
 
-        let mut builder = WindowBuilder::new().with_inner_size(ideal);
+        let attrs = widget.attributes();
+        let geometry = widget
+            .state_key()
+            .and_then(|key| shared.state.load(key))
+            .and_then(|value| parse_geometry(&value));
+        let size = geometry.map(|(size, _)| size).or(attrs.inner_size);
+        let size = size.unwrap_or(ideal).max(Size(1, 1));
+        let mut builder = WindowBuilder::new()
+            .with_inner_size(size)
+            .with_decorations(attrs.decorations)
+            .with_transparent(attrs.transparent)
+            .with_resizable(attrs.resizable)
+            .with_always_on_top(attrs.always_on_top);
         let restrict_dimensions = widget.restrict_dimensions();
-        if restrict_dimensions.0 {
-            builder = builder.with_min_inner_size(solve_cache.min(true));
+        let (min, max) = attrs.size_limits(solve_cache.min(true), ideal, restrict_dimensions);
+        if let Some(min) = min {
+            builder = builder.with_min_inner_size(min);
+        }
+        if let Some(max) = max {
+            builder = builder.with_max_inner_size(max);
         }
-        if restrict_dimensions.1 {
-            builder = builder.with_max_inner_size(ideal);
+        let placement = widget.placement();
+        let monitor = placement
+            .monitor
+            .and_then(|index| elwt.available_monitors().nth(index));
+        let saved_pos = geometry.and_then(|(_, pos)| pos);
+        if let Some(pos) = saved_pos.filter(|_| !placement.fullscreen) {
+            builder = builder.with_position(PhysicalPosition::<i32>::from(pos));
+        } else if let Some(ref monitor) = monitor {
+            let origin = Coord::from(monitor.position());
+            let pos = match placement.position {
+                Some(pos) => origin + Offset(pos.0, pos.1),
+                None => origin,
+            };
+            builder = builder.with_position(PhysicalPosition::<i32>::from(pos));
+        } else if let Some(pos) = placement.position {
+            builder = builder.with_position(PhysicalPosition::<i32>::from(pos));
+        }
+        if placement.fullscreen {
+            builder = builder.with_fullscreen(Some(Fullscreen::Borderless(monitor)));
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            use winit::platform::web::WindowBuilderExtWebSys;
+            builder = builder.with_canvas(shared.canvas.take());
         }
         let window = builder
             .with_title(widget.title())
             .with_window_icon(widget.icon())
             .build(elwt)?;
+        #[cfg(target_arch = "wasm32")]
+        crate::web::attach_canvas(&window);
 
         shared.init_clipboard(&window);
 
@@ -84,8 +150,12 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
 
         let mut draw = shared.draw.draw.new_window();
         shared.draw.draw.resize(&mut draw, size);
+        draw.set_transparent(attrs.transparent);
+        shared.draw.draw.update_lcd(&mut draw, scale_factor as f32);
+        if shared.options.draw_stats {
+            shared.draw.draw.enable_stats(&mut draw);
+        }
 
-        let surface = unsafe { shared.instance.create_surface(&window) };
         let sc_desc = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: crate::draw::RENDER_TEX_FORMAT,
@@ -93,8 +163,22 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
             height: size.1.cast(),
             present_mode: wgpu::PresentMode::Mailbox,
         };
-        surface.configure(&shared.draw.draw.device, &sc_desc);
+        let surface = match shared.instance.as_ref() {
+            #[cfg(feature = "software")]
+            _ if shared.draw.draw.is_software() => {
+                // Safety: the framebuffer is dropped before the window
+                let fb = unsafe { Framebuffer::new(&window, size) };
+                Surface::Soft(fb.ok_or(Error::Framebuffer)?)
+            }
+            Some(instance) => {
+                let surface = unsafe { instance.create_surface(&window) };
+                surface.configure(&shared.draw.draw.gpu().device, &sc_desc);
+                Surface::Gpu(surface)
+            }
+            None => panic!("no graphics instance"),
+        };
 
+        let refresh_interval = refresh_interval(&window);
         let mut r = Window {
             widget,
             window_id,
@@ -105,7 +189,19 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
             sc_desc,
             draw,
             theme_window,
+            redraw_requested: false,
+            refresh_interval,
+            latency: shared.options.latency_stats.then(Default::default),
+            captures: vec![],
+            #[cfg(target_arch = "wasm32")]
+            frame: None,
         };
+        #[cfg(target_arch = "wasm32")]
+        {
+            let id = r.window.id();
+            let proxy = shared.proxy.clone();
+            r.frame = proxy.map(|proxy| crate::web::AnimationFrame::new(proxy, id));
+        }
         r.apply_size(shared);
 
         trace!("Window::new completed in {}µs", time.elapsed().as_micros());
@@ -117,34 +213,66 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
         // Note: resize must be handled here to re-configure self.surface.
         match event {
             WindowEvent::Destroyed => (),
-            WindowEvent::Resized(size) => self.do_resize(shared, size),
+            WindowEvent::Resized(size) => {
+                self.do_resize(shared, size);
+                self.save_geometry(shared);
+            }
+            WindowEvent::Moved(_) => {
+                // The window may have moved to another monitor
+                self.refresh_interval = refresh_interval(&self.window);
+                self.save_geometry(shared);
+            }
             WindowEvent::ScaleFactorChanged {
                 scale_factor,
                 new_inner_size,
             } => {
                 // Note: API allows us to set new window size here.
                 shared.scale_factor = scale_factor;
+                self.refresh_interval = refresh_interval(&self.window);
                 let scale_factor = scale_factor as f32;
-                self.mgr.set_scale_factor(scale_factor);
+                let mut tkw = TkWindow::new(
+                    shared,
+                    self.window_id,
+                    Some(&self.window),
+                    &mut self.theme_window,
+                );
+                self.mgr
+                    .handle_scale_factor_changed(&mut tkw, &mut *self.widget, scale_factor);
                 shared
                     .theme
                     .update_window(&mut self.theme_window, scale_factor);
+                shared.draw.draw.update_lcd(&mut self.draw, scale_factor);
                 self.solve_cache.invalidate_rule_cache();
                 self.do_resize(shared, *new_inner_size);
             }
             event => {
-                let mut tkw = TkWindow::new(shared, Some(&self.window), &mut self.theme_window);
+                let received = Instant::now();
+                let is_input = LatencyTracker::is_input(&event);
+                let mut tkw = TkWindow::new(
+                    shared,
+                    self.window_id,
+                    Some(&self.window),
+                    &mut self.theme_window,
+                );
                 let widget = &mut *self.widget;
                 self.mgr.with(&mut tkw, |mgr| {
                     mgr.handle_winit(widget, event);
                 });
+                if let Some(latency) = self.latency.as_mut().filter(|_| is_input) {
+                    latency.dispatched(received);
+                }
             }
         }
     }
 
     /// Update, after receiving all events
     pub fn update(&mut self, shared: &mut SharedState<C, T>) -> (TkAction, Option<Instant>) {
-        let mut tkw = TkWindow::new(shared, Some(&self.window), &mut self.theme_window);
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
         let action = self.mgr.update(&mut tkw, &mut *self.widget);
         drop(tkw);
 
@@ -152,16 +280,29 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
             return (action, None);
         }
         self.handle_action(shared, action);
+        if let Some(latency) = self.latency.as_mut() {
+            latency.update(self.redraw_requested);
+        }
 
         (action, self.mgr.next_resume())
     }
 
     /// Handle an action (excludes handling of CLOSE and EXIT)
+    /// Handle an action shared by all windows (e.g. from a theme adjustment)
+    pub fn handle_shared_action(&mut self, shared: &mut SharedState<C, T>, action: TkAction) {
+        if action.contains(TkAction::REDRAW) {
+            // The theme may have changed
+            self.mgr.theme_changed();
+        }
+        self.handle_action(shared, action);
+    }
+
     pub fn handle_action(&mut self, shared: &mut SharedState<C, T>, action: TkAction) {
         if action.contains(TkAction::RECONFIGURE) {
             self.reconfigure(shared);
         }
         if action.contains(TkAction::THEME_UPDATE) {
+            self.mgr.theme_changed();
             let scale_factor = self.window.scale_factor() as f32;
             shared
                 .theme
@@ -180,16 +321,29 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
             self.window.request_redraw();
         } else*/
         if action.contains(TkAction::REGION_MOVED) {
-            let mut tkw = TkWindow::new(shared, Some(&self.window), &mut self.theme_window);
+            let mut tkw = TkWindow::new(
+                shared,
+                self.window_id,
+                Some(&self.window),
+                &mut self.theme_window,
+            );
             self.mgr.region_moved(&mut tkw, &mut *self.widget);
-            self.window.request_redraw();
+            self.request_redraw();
         } else if action.contains(TkAction::REDRAW) {
-            self.window.request_redraw();
+            self.request_redraw();
         }
     }
 
     pub fn handle_closure(mut self, shared: &mut SharedState<C, T>) -> TkAction {
-        let mut tkw = TkWindow::new(shared, Some(&self.window), &mut self.theme_window);
+        if let Some(stats) = self.latency.as_ref().and_then(|l| l.stats()) {
+            info!("Input latency of window {:?}: {}", self.window_id, stats);
+        }
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
         let widget = &mut *self.widget;
         self.mgr.with(&mut tkw, |mgr| {
             widget.handle_closure(mgr);
@@ -198,7 +352,12 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
     }
 
     pub fn update_timer(&mut self, shared: &mut SharedState<C, T>) -> Option<Instant> {
-        let mut tkw = TkWindow::new(shared, Some(&self.window), &mut self.theme_window);
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
         let widget = &mut *self.widget;
         self.mgr.with(&mut tkw, |mgr| {
             mgr.update_timer(widget);
@@ -206,13 +365,35 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
         self.mgr.next_resume()
     }
 
+    /// Repeat a paste command once clipboard contents are available
+    ///
+    /// See [`crate::web::Clipboard::read`].
+    #[cfg(target_arch = "wasm32")]
+    pub fn repeat_paste(&mut self, shared: &mut SharedState<C, T>) {
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        let widget = &mut *self.widget;
+        self.mgr.with(&mut tkw, |mgr| {
+            mgr.handle_command(widget, kas::event::Command::Paste);
+        });
+    }
+
     pub fn update_handle(
         &mut self,
         shared: &mut SharedState<C, T>,
         handle: UpdateHandle,
         payload: u64,
     ) {
-        let mut tkw = TkWindow::new(shared, Some(&self.window), &mut self.theme_window);
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
         let widget = &mut *self.widget;
         self.mgr.with(&mut tkw, |mgr| {
             mgr.update_handle(widget, handle, payload);
@@ -221,7 +402,12 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
 
     pub fn add_popup(&mut self, shared: &mut SharedState<C, T>, id: WindowId, popup: kas::Popup) {
         let window = &mut *self.widget;
-        let mut tkw = TkWindow::new(shared, Some(&self.window), &mut self.theme_window);
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
         self.mgr.with(&mut tkw, |mgr| {
             kas::Window::add_popup(window, mgr, id, popup);
         });
@@ -231,11 +417,22 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
         self.mgr.send_action(action);
     }
 
+    /// Queue a capture request, to be handled after the next frame
+    pub fn request_capture(&mut self, request: CaptureRequest) {
+        self.captures.push(request);
+        self.request_redraw();
+    }
+
     pub fn send_close(&mut self, shared: &mut SharedState<C, T>, id: WindowId) {
         if id == self.window_id {
             self.mgr.send_action(TkAction::CLOSE);
         } else {
-            let mut tkw = TkWindow::new(shared, Some(&self.window), &mut self.theme_window);
+            let mut tkw = TkWindow::new(
+                shared,
+                self.window_id,
+                Some(&self.window),
+                &mut self.theme_window,
+            );
             let widget = &mut *self.widget;
             self.mgr.with(&mut tkw, |mgr| {
                 widget.remove_popup(mgr, id);
@@ -251,11 +448,27 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
         Size::new(self.sc_desc.width.cast(), self.sc_desc.height.cast())
     }
 
+    fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+        #[cfg(target_arch = "wasm32")]
+        if let Some(frame) = self.frame.as_ref() {
+            // Draw on the browser's next animation frame
+            frame.request();
+            return;
+        }
+        self.window.request_redraw();
+    }
+
     fn reconfigure(&mut self, shared: &mut SharedState<C, T>) {
         let time = Instant::now();
         debug!("Window::reconfigure");
 
-        let mut tkw = TkWindow::new(shared, Some(&self.window), &mut self.theme_window);
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
         self.mgr.configure(&mut tkw, &mut *self.widget);
 
         self.solve_cache.invalidate_rule_cache();
@@ -268,7 +481,13 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
         let rect = Rect::new(Coord::ZERO, self.sc_size());
         debug!("Resizing window to rect = {:?}", rect);
 
-        let mut tkw = TkWindow::new(shared, Some(&self.window), &mut self.theme_window);
+        let mut tkw = TkWindow::new(
+            shared,
+            self.window_id,
+            Some(&self.window),
+            &mut self.theme_window,
+        );
+        self.mgr.update_window_size(rect.size);
         let solve_cache = &mut self.solve_cache;
         let widget = &mut self.widget;
         self.mgr.with(&mut tkw, |mgr| {
@@ -277,19 +496,39 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
         });
 
         let restrict_dimensions = self.widget.restrict_dimensions();
-        if restrict_dimensions.0 {
-            self.window
-                .set_min_inner_size(Some(self.solve_cache.min(true)));
-        };
-        if restrict_dimensions.1 {
-            self.window
-                .set_max_inner_size(Some(self.solve_cache.ideal(true)));
-        };
+        let (min, max) = self.widget.attributes().size_limits(
+            self.solve_cache.min(true),
+            self.solve_cache.ideal(true),
+            restrict_dimensions,
+        );
+        if let Some(min) = min {
+            self.window.set_min_inner_size(Some(min));
+        }
+        if let Some(max) = max {
+            self.window.set_max_inner_size(Some(max));
+        }
 
-        self.window.request_redraw();
+        self.request_redraw();
         trace!("apply_size completed in {}µs", time.elapsed().as_micros());
     }
 
+    /// Save window geometry, if enabled by [`kas::Window::state_key`]
+    fn save_geometry(&self, shared: &mut SharedState<C, T>) {
+        let key = match self.widget.state_key() {
+            Some(key) => key,
+            None => return,
+        };
+        if self.window.is_maximized() || self.window.fullscreen().is_some() {
+            return;
+        }
+        let size: Size = self.window.inner_size().into();
+        let value = match self.window.outer_position() {
+            Ok(pos) => format!("{} {} {} {}", size.0, size.1, pos.x, pos.y),
+            Err(_) => format!("{} {}", size.0, size.1),
+        };
+        shared.state.save(key, value);
+    }
+
     fn do_resize(&mut self, shared: &mut SharedState<C, T>, size: PhysicalSize<u32>) {
         let time = Instant::now();
         let size = size.into();
@@ -301,8 +540,13 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
 
         self.sc_desc.width = size.0.cast();
         self.sc_desc.height = size.1.cast();
-        self.surface
-            .configure(&shared.draw.draw.device, &self.sc_desc);
+        match &mut self.surface {
+            Surface::Gpu(surface) => {
+                surface.configure(&shared.draw.draw.gpu().device, &self.sc_desc);
+            }
+            #[cfg(feature = "software")]
+            Surface::Soft(fb) => fb.resize(size),
+        }
 
         // Note that on resize, width adjustments may affect height
         // requirements; we therefore refresh size restrictions.
@@ -317,43 +561,26 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
     pub(crate) fn do_draw(&mut self, shared: &mut SharedState<C, T>) {
         let time = Instant::now();
 
-        {
-            let draw = DrawIface {
-                draw: &mut self.draw,
-                shared: &mut shared.draw,
-                pass: PassId::new(0),
-            };
-
-            #[cfg(not(feature = "gat"))]
-            unsafe {
-                // Safety: lifetimes do not escape the returned draw_handle value.
-                let mut draw_handle = shared.theme.draw_handle(draw, &mut self.theme_window);
-                self.widget.draw(&mut draw_handle, &self.mgr, false);
-            }
-            #[cfg(feature = "gat")]
-            {
-                let mut draw_handle = shared.theme.draw_handle(draw, &mut self.theme_window);
-                self.widget.draw(&mut draw_handle, &self.mgr, false);
-            }
-        }
+        self.draw_widgets(shared);
 
         let time2 = Instant::now();
-        let frame = match self.surface.get_current_texture() {
-            Ok(frame) => frame,
-            Err(e) => {
-                error!("Failed to get frame texture: {}", e);
-                // It may be possible to recover by calling surface.configure(...) then retrying
-                // surface.get_current_texture(), but is doing so ever useful?
-                return;
-            }
-        };
-        let view = frame.texture.create_view(&Default::default());
-
-        // TODO: check frame.optimal ?
-        let clear_color = to_wgpu_color(shared.theme.clear_color());
-        shared.render(&mut self.draw, &view, clear_color);
+        if !self.render_and_present(shared) {
+            return;
+        }
+        if let Some(stats) = self.draw.stats() {
+            self.mgr.set_draw_stats(stats.clone());
+        }
 
-        frame.present();
+        let presented = Instant::now();
+        self.mgr.frame_presented(presented, self.refresh_interval);
+        shared.frame_presented(presented);
+        self.redraw_requested = false;
+        if let Some(stats) = self.latency.as_mut().and_then(|l| l.presented(presented)) {
+            info!(
+                "Input latency of window {:?} ({:?}): {}",
+                self.window_id, self.sc_desc.present_mode, stats
+            );
+        }
 
         let end = Instant::now();
         // Explanation: 'text' is the time to prepare positioned glyphs, 'frame-
@@ -362,13 +589,128 @@ impl<C: CustomPipe, T: Theme<DrawPipe<C>>> Window<C, T> {
             "do_draw completed in {}µs ({}μs widgets, {}µs text, {}µs render)",
             (end - time).as_micros(),
             (time2 - time).as_micros(),
-            self.draw.text.dur_micros(),
+            self.draw.text_dur_micros(),
             (end - time2).as_micros()
         );
+
+        if !self.captures.is_empty() {
+            self.do_captures(shared);
+        }
+    }
+
+    /// Render the drawn widgets and present the frame
+    ///
+    /// Returns false on failure.
+    fn render_and_present(&mut self, shared: &mut SharedState<C, T>) -> bool {
+        let clear_color = shared.theme.clear_color();
+        match &mut self.surface {
+            Surface::Gpu(surface) => {
+                let frame = match surface.get_current_texture() {
+                    Ok(frame) => frame,
+                    Err(e) => {
+                        error!("Failed to get frame texture: {}", e);
+                        // It may be possible to recover by calling surface.configure(...)
+                        // then retrying surface.get_current_texture(), but is doing so
+                        // ever useful?
+                        return false;
+                    }
+                };
+                let view = frame.texture.create_view(&Default::default());
+
+                // TODO: check frame.optimal ?
+                let clear_color = to_wgpu_color(clear_color);
+                shared.render(&mut self.draw, &view, Some(clear_color));
+                frame.present();
+            }
+            #[cfg(feature = "software")]
+            Surface::Soft(fb) => {
+                shared.render_pixmap(&mut self.draw, fb.pixmap_mut(), Some(clear_color));
+                fb.present();
+            }
+        }
+        true
+    }
+
+    /// Draw the window's widgets (without rendering)
+    fn draw_widgets(&mut self, shared: &mut SharedState<C, T>) {
+        let draw = DrawIface {
+            draw: &mut self.draw,
+            shared: &mut shared.draw,
+            pass: PassId::new(0),
+        };
+
+        #[cfg(not(feature = "gat"))]
+        unsafe {
+            // Safety: lifetimes do not escape the returned draw_handle value.
+            let mut draw_handle = shared.theme.draw_handle(draw, &mut self.theme_window);
+            self.widget.draw(&mut draw_handle, &self.mgr, false);
+            self.solve_cache
+                .draw_overflow(self.widget.rect(), &mut draw_handle);
+            self.mgr
+                .draw_inspector(self.widget.as_widget(), &mut draw_handle);
+            self.mgr
+                .draw_toasts(self.widget.as_widget(), &mut draw_handle);
+            self.mgr
+                .draw_tooltip(self.widget.as_widget(), &mut draw_handle);
+        }
+        #[cfg(feature = "gat")]
+        {
+            let mut draw_handle = shared.theme.draw_handle(draw, &mut self.theme_window);
+            self.widget.draw(&mut draw_handle, &self.mgr, false);
+            self.solve_cache
+                .draw_overflow(self.widget.rect(), &mut draw_handle);
+            self.mgr
+                .draw_inspector(self.widget.as_widget(), &mut draw_handle);
+            self.mgr
+                .draw_toasts(self.widget.as_widget(), &mut draw_handle);
+            self.mgr
+                .draw_tooltip(self.widget.as_widget(), &mut draw_handle);
+        }
+    }
+
+    /// Handle pending capture requests
+    ///
+    /// Each request is drawn again and rendered to an offscreen texture.
+    fn do_captures(&mut self, shared: &mut SharedState<C, T>) {
+        let time = Instant::now();
+        let size = self.sc_size();
+        let mut target = CaptureTarget::new(&shared.draw.draw, size);
+        let clear_color = shared.theme.clear_color();
+
+        for request in std::mem::take(&mut self.captures) {
+            let size = match request.widget {
+                None => {
+                    self.draw_widgets(shared);
+                    Some(size)
+                }
+                Some(id) => draw_widget(
+                    shared,
+                    &mut self.draw,
+                    &mut self.theme_window,
+                    &self.mgr,
+                    &mut *self.widget,
+                    id,
+                )
+                .map(|rect| rect.size.min(size)),
+            };
+            let image = size.and_then(|size| {
+                match target.render(shared, &mut self.draw, clear_color, size) {
+                    Ok(image) => Some(image),
+                    Err(e) => {
+                        warn!("Capture of window {:?} failed: {}", self.window_id, e);
+                        None
+                    }
+                }
+            });
+            let handle = request.finish(image);
+            shared.trigger_update(handle, 0);
+        }
+
+        trace!("do_captures completed in {}µs", time.elapsed().as_micros());
     }
 }
 
-fn to_wgpu_color(c: kas::draw::color::Rgba) -> wgpu::Color {
+pub(crate) fn to_wgpu_color(c: kas::draw::color::Rgba) -> wgpu::Color {
     wgpu::Color {
         r: c.r as f64,
         g: c.g as f64,
@@ -377,11 +719,12 @@ fn to_wgpu_color(c: kas::draw::color::Rgba) -> wgpu::Color {
     }
 }
 
-struct TkWindow<'a, C: CustomPipe, T: Theme<DrawPipe<C>>>
+pub(crate) struct TkWindow<'a, C: CustomPipe, T: Theme<DrawPipe<C>>>
 where
     T::Window: kas_theme::Window,
 {
     shared: &'a mut SharedState<C, T>,
+    window_id: WindowId,
     window: Option<&'a winit::window::Window>,
     theme_window: &'a mut T::Window,
 }
@@ -390,13 +733,15 @@ impl<'a, C: CustomPipe, T: Theme<DrawPipe<C>>> TkWindow<'a, C, T>
 where
     T::Window: kas_theme::Window,
 {
-    fn new(
+    pub(crate) fn new(
         shared: &'a mut SharedState<C, T>,
+        window_id: WindowId,
         window: Option<&'a winit::window::Window>,
         theme_window: &'a mut T::Window,
     ) -> Self {
         TkWindow {
             shared,
+            window_id,
             window,
             theme_window,
         }
@@ -409,6 +754,25 @@ where
     T: Theme<DrawPipe<C>>,
     T::Window: kas_theme::Window,
 {
+    fn capabilities(&self) -> ShellCapabilities {
+        // Pop-ups are drawn within the parent window
+        let mut caps = ShellCapabilities::WINDOWS | ShellCapabilities::OPEN_URL;
+        // Read-back blocks on the GPU, which is not possible on the web
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            caps |= ShellCapabilities::CAPTURE;
+        }
+        if self.window.is_some() {
+            caps |= ShellCapabilities::POPUPS
+                | ShellCapabilities::MONITORS
+                | ShellCapabilities::WINDOW_CONTROL
+                | ShellCapabilities::DRAG_WINDOW
+                | ShellCapabilities::NATIVE_SURFACES;
+        }
+        caps.set(ShellCapabilities::CLIPBOARD, self.shared.has_clipboard());
+        caps
+    }
+
     fn add_popup(&mut self, popup: kas::Popup) -> Option<WindowId> {
         self.window.map(|w| w.id()).map(|parent_id| {
             let id = self.shared.next_window_id();
@@ -443,7 +807,7 @@ where
 
     #[inline]
     fn get_clipboard(&mut self) -> Option<String> {
-        self.shared.get_clipboard()
+        self.shared.get_clipboard(self.window)
     }
 
     #[inline]
@@ -451,6 +815,23 @@ where
         self.shared.set_clipboard(content);
     }
 
+    #[inline]
+    fn open_url(&mut self, url: &str) -> bool {
+        kas_shell::open_url(url)
+    }
+
+    fn capture(&mut self, mut request: CaptureRequest) {
+        if cfg!(target_arch = "wasm32") {
+            // Read-back blocks on the device, which is not possible in a browser
+            let handle = request.finish(None);
+            self.shared.trigger_update(handle, 0);
+            return;
+        }
+        request.window.get_or_insert(self.window_id);
+        self.shared.track_capture(&mut request);
+        self.shared.pending.push(PendingAction::Capture(request));
+    }
+
     fn adjust_theme(&mut self, f: &mut dyn FnMut(&mut dyn ThemeApi) -> TkAction) {
         let action = f(&mut self.shared.theme);
         self.shared.pending.push(PendingAction::TkAction(action));
@@ -469,7 +850,138 @@ where
     #[inline]
     fn set_cursor_icon(&mut self, icon: CursorIcon) {
         if let Some(window) = self.window {
-            window.set_cursor_icon(icon);
+            window.set_cursor_icon(icon.into());
+        } else {
+            self.shared.cursor_icon = icon;
+        }
+    }
+
+    fn set_decorations(&mut self, decorations: bool) {
+        if let Some(window) = self.window {
+            window.set_decorations(decorations);
         }
     }
+
+    fn set_resizable(&mut self, resizable: bool) {
+        if let Some(window) = self.window {
+            window.set_resizable(resizable);
+        }
+    }
+
+    fn set_always_on_top(&mut self, always_on_top: bool) {
+        if let Some(window) = self.window {
+            window.set_always_on_top(always_on_top);
+        }
+    }
+
+    fn set_inner_size(&mut self, size: Size) {
+        if let Some(window) = self.window {
+            window.set_inner_size(size.max(Size(1, 1)));
+        }
+    }
+
+    fn drag_window(&mut self) {
+        if let Some(window) = self.window {
+            if let Err(e) = window.drag_window() {
+                warn!("drag_window failed: {}", e);
+            }
+        }
+    }
+
+    fn set_minimized(&mut self) {
+        if let Some(window) = self.window {
+            window.set_minimized(true);
+        }
+    }
+
+    fn set_maximized(&mut self, maximized: bool) {
+        if let Some(window) = self.window {
+            window.set_maximized(maximized);
+        }
+    }
+
+    fn is_maximized(&self) -> bool {
+        self.window.map(|w| w.is_maximized()).unwrap_or(false)
+    }
+
+    fn raw_window_handle(&self) -> Option<RawWindowHandle> {
+        self.window.map(|w| w.raw_window_handle())
+    }
+
+    fn monitors(&self) -> Vec<MonitorInfo> {
+        self.window
+            .map(|window| window.available_monitors().map(monitor_info).collect())
+            .unwrap_or_default()
+    }
+
+    fn current_monitor(&self) -> Option<usize> {
+        let window = self.window?;
+        let current = window.current_monitor()?;
+        window.available_monitors().position(|m| m == current)
+    }
+
+    #[inline]
+    fn load_state(&self, key: &str) -> Option<String> {
+        self.shared.state.load(key)
+    }
+
+    #[inline]
+    fn save_state(&mut self, key: &str, value: String) {
+        self.shared.state.save(key, value);
+    }
+
+    fn translate(&self, key: &str) -> Option<String> {
+        self.shared.localizer.as_ref()?.translate(key)
+    }
+
+    fn locale(&self) -> Option<String> {
+        let localizer = self.shared.localizer.as_ref()?;
+        Some(localizer.locale().to_string())
+    }
+
+    fn set_locale(&mut self, locale: &str) {
+        if let Some(localizer) = self.shared.localizer.as_mut() {
+            if localizer.set_locale(locale) {
+                let action = TkAction::RECONFIGURE;
+                self.shared.pending.push(PendingAction::TkAction(action));
+            }
+        }
+    }
+}
+
+/// Parse geometry saved by `Window::save_geometry`: size and optional position
+fn parse_geometry(s: &str) -> Option<(Size, Option<Coord>)> {
+    let mut iter = s.split_whitespace().map(|v| v.parse::<i32>().ok());
+    let size = Size(iter.next()??, iter.next()??);
+    let pos = match (iter.next(), iter.next()) {
+        (Some(Some(x)), Some(Some(y))) => Some(Coord(x, y)),
+        _ => None,
+    };
+    Some((size, pos))
+}
+
+// Refresh interval of the monitor showing `window`
+//
+// winit does not report the current video mode, thus we use the fastest mode
+// at the monitor's current resolution. Returns `None` where no modes are
+// reported (e.g. on the web), in which case the interval is measured.
+fn refresh_interval(window: &winit::window::Window) -> Option<Duration> {
+    let monitor = window.current_monitor()?;
+    let size = monitor.size();
+    let hz = monitor
+        .video_modes()
+        .filter(|mode| mode.size() == size)
+        .map(|mode| mode.refresh_rate())
+        .max()
+        .filter(|hz| *hz > 0)?;
+    Some(Duration::from_nanos(1_000_000_000 / u64::from(hz)))
+}
+
+fn monitor_info(monitor: MonitorHandle) -> MonitorInfo {
+    MonitorInfo {
+        name: monitor.name(),
+        position: monitor.position().into(),
+        size: monitor.size().into(),
+        scale_factor: monitor.scale_factor(),
+    }
 }
@@ -0,0 +1,207 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! KAS widget API
+//!
+//! A stable interface for third-party widget libraries.
+//!
+//! This crate re-exports a minimal subset of `kas-core` (core traits,
+//! geometry, layout and event types) under versioned modules. Items within a
+//! versioned module such as [`v1`] will not be removed or changed
+//! incompatibly; breaking changes are instead made in a new module (`v2`),
+//! with the previous module retained for at least one release of this crate.
+//! Compatibility is checked with `cargo semver-checks` in CI, against the
+//! last release of this crate (tagged `kas-widget-api-vX.Y.Z`) or, before the
+//! first release, against the commit introducing the [`unstable`] module.
+//!
+//! Only items which can be frozen are included: plain data types (e.g.
+//! geometry), types with private fields, `#[non_exhaustive]` enums, enums
+//! which are complete by nature (e.g. [`v1::dir::Direction`]), and traits
+//! implemented by widgets, to which only methods with default implementations
+//! are added. Traits implemented by themes and shells (e.g. [`DrawHandle`] and
+//! [`SizeHandle`]) and exhaustive enums which may gain variants (e.g.
+//! [`GrabMode`]) are excluded.
+//!
+//! Widgets still need some excluded items; these are available from the
+//! [`unstable`] module, which carries no compatibility promise.
+//!
+//! Since KAS macros expect `kas` to be in scope, this crate should be renamed
+//! to `kas` when used as a dependency:
+//! ```toml
+//! [dependencies]
+//! kas = { version = "0.1.0", package = "kas-widget-api" }
+//! ```
+//!
+//! The contents of the latest version and of [`unstable`] are also re-exported
+//! at the crate root (macro-generated code uses these paths).
+//!
+//! [`DrawHandle`]: unstable::draw::DrawHandle
+//! [`SizeHandle`]: unstable::draw::SizeHandle
+//! [`GrabMode`]: unstable::event::GrabMode
+
+pub use v1::*;
+
+/// Drawing and sizing (from [`v1::draw`] and [`unstable::draw`])
+pub mod draw {
+    #[doc(no_inline)]
+    pub use crate::unstable::draw::*;
+    #[doc(no_inline)]
+    pub use crate::v1::draw::*;
+}
+
+/// Events (from [`v1::event`] and [`unstable::event`])
+pub mod event {
+    #[doc(no_inline)]
+    pub use crate::unstable::event::*;
+    #[doc(no_inline)]
+    pub use crate::v1::event::*;
+}
+
+/// Layout (from [`v1::layout`] and [`unstable::layout`])
+pub mod layout {
+    #[doc(no_inline)]
+    pub use crate::unstable::layout::*;
+    #[doc(no_inline)]
+    pub use crate::v1::layout::*;
+}
+
+/// Prelude for widget libraries (from [`v1::prelude`] and [`unstable`])
+///
+/// Usage: `use kas::prelude::*;`
+pub mod prelude {
+    #[doc(no_inline)]
+    pub use crate::unstable::draw::{DrawHandle, DrawHandleExt, SizeHandle};
+    #[doc(no_inline)]
+    pub use crate::unstable::layout::Stretch;
+    #[doc(no_inline)]
+    pub use crate::v1::prelude::*;
+}
+
+/// Items required by widgets which cannot be frozen
+///
+/// Nothing in this module is covered by the compatibility promise: items may
+/// change incompatibly in any release (following `kas-core`).
+pub mod unstable {
+    /// Drawing and sizing
+    pub mod draw {
+        #[doc(no_inline)]
+        pub use kas_core::draw::{DrawHandle, DrawHandleExt, SizeHandle, TextClass};
+    }
+
+    /// Events
+    pub mod event {
+        #[doc(no_inline)]
+        pub use kas_core::event::{CursorIcon, GrabMode, PressSource, ScrollDelta};
+        #[doc(no_inline)]
+        pub use kas_core::event::{MouseButton, VirtualKeyCode};
+    }
+
+    /// Layout
+    pub mod layout {
+        #[doc(no_inline)]
+        pub use kas_core::layout::Stretch;
+    }
+}
+
+/// Version 1 of the widget API
+pub mod v1 {
+    #[doc(no_inline)]
+    pub use kas_core::macros;
+    #[doc(no_inline)]
+    pub use kas_core::{Boxed, CoreData, TkAction, WidgetId, Window};
+    #[doc(no_inline)]
+    pub use kas_core::{Layout, Widget, WidgetChildren, WidgetConfig, WidgetCore};
+
+    /// Widget classes
+    pub mod class {
+        #[doc(no_inline)]
+        pub use kas_core::class::{HasBool, HasFormatted, HasStr, HasString, SetAccel};
+    }
+
+    /// Directions
+    pub mod dir {
+        #[doc(no_inline)]
+        pub use kas_core::dir::{Direction, Directional};
+        #[doc(no_inline)]
+        pub use kas_core::dir::{Down, Left, Right, Up};
+    }
+
+    /// Drawing and sizing
+    pub mod draw {
+        #[doc(no_inline)]
+        pub use kas_core::draw::{ImageId, InputState};
+    }
+
+    /// Events
+    pub mod event {
+        #[doc(no_inline)]
+        pub use kas_core::event::{Command, Event, ModifiersState, ScrollSource};
+        #[doc(no_inline)]
+        pub use kas_core::event::{Handler, Manager, ManagerState, SendEvent};
+        #[doc(no_inline)]
+        pub use kas_core::event::{Response, UpdateHandle, VoidMsg};
+    }
+
+    /// Geometry
+    pub mod geom {
+        #[doc(no_inline)]
+        pub use kas_core::geom::{Coord, DVec2, Offset, Quad, Rect, Size, Vec2, Vec3};
+    }
+
+    /// Layout
+    pub mod layout {
+        #[doc(no_inline)]
+        pub use kas_core::layout::{Align, AlignHints, AxisInfo, SizeRules};
+        #[doc(no_inline)]
+        pub use kas_core::layout::{DynRowStorage, FixedGridStorage, FixedRowStorage};
+        #[doc(no_inline)]
+        pub use kas_core::layout::{FlowStorage, FrameStorage, RegionStorage};
+        #[doc(no_inline)]
+        pub use kas_core::layout::{FrameRules, MarginSelector, Margins};
+        #[doc(no_inline)]
+        pub use kas_core::layout::{GridChildInfo, GridDimensions, Layout};
+    }
+
+    /// Text
+    pub mod text {
+        #[doc(no_inline)]
+        pub use kas_core::text::{AccelString, EditableTextApi, Text, TextApi, TextApiExt};
+    }
+
+    /// Utilities
+    pub mod util {
+        #[doc(no_inline)]
+        pub use kas_core::util::TryFormat;
+    }
+
+    /// Prelude for widget libraries
+    ///
+    /// This omits items from [`crate::unstable`]; usually [`crate::prelude`]
+    /// should be used instead.
+    pub mod prelude {
+        #[doc(no_inline)]
+        pub use super::class::*;
+        #[doc(no_inline)]
+        pub use super::dir::{Direction, Directional};
+        #[doc(no_inline)]
+        pub use super::draw::InputState;
+        #[doc(no_inline)]
+        pub use super::event::{Event, Handler, Manager, ManagerState, Response, SendEvent};
+        #[doc(no_inline)]
+        pub use super::event::{UpdateHandle, VoidMsg};
+        #[doc(no_inline)]
+        pub use super::geom::{Coord, Offset, Rect, Size};
+        #[doc(no_inline)]
+        pub use super::layout::{Align, AlignHints, AxisInfo, SizeRules};
+        #[doc(no_inline)]
+        pub use super::macros::*;
+        #[doc(no_inline)]
+        pub use super::text::{AccelString, EditableTextApi, Text, TextApi, TextApiExt};
+        #[doc(no_inline)]
+        pub use super::{Boxed, CoreData, TkAction, WidgetId};
+        #[doc(no_inline)]
+        pub use super::{Layout, Widget, WidgetChildren, WidgetConfig, WidgetCore};
+    }
+}
@@ -0,0 +1,130 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Check that widgets may be defined using only the facade
+//!
+//! Macro-generated code refers to `::kas::*` paths; these must all resolve
+//! within the facade.
+
+extern crate kas_widget_api as kas;
+
+use kas::prelude::*;
+
+widget! {
+    #[derive(Clone, Debug, Default)]
+    struct Leaf {
+        #[widget_core]
+        core: CoreData,
+    }
+
+    impl Layout for Leaf {
+        fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+            SizeRules::fixed(10, (0, 0))
+        }
+
+        fn draw(&mut self, _: &mut dyn DrawHandle, _: &ManagerState, _: bool) {}
+    }
+}
+
+#[derive(Clone, Debug, VoidMsg)]
+enum Item {
+    Clicked,
+}
+
+widget! {
+    #[derive(Clone, Debug)]
+    #[widget{
+        layout = row: *;
+    }]
+    #[handler(msg = Item)]
+    struct Pair {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        a: Leaf,
+        #[widget]
+        b: Leaf,
+    }
+}
+
+// Uses RegionStorage and MarginSelector
+widget! {
+    #[derive(Clone, Debug)]
+    #[widget{
+        layout = column: [
+            region(header): self.a,
+            self.b,
+        ];
+    }]
+    #[handler(msg = VoidMsg)]
+    struct Regions {
+        #[widget_core]
+        core: CoreData,
+        #[widget(margins = none)]
+        a: Leaf,
+        #[widget(margins = 1.5)]
+        b: Leaf,
+    }
+}
+
+// Uses FlowStorage
+widget! {
+    #[derive(Clone, Debug, Default)]
+    #[handler(msg = VoidMsg)]
+    struct Flow {
+        first_id: WidgetId,
+        #[widget_core]
+        core: CoreData,
+        items: Vec<Leaf>,
+    }
+
+    impl WidgetChildren for Self {
+        fn first_id(&self) -> WidgetId {
+            self.first_id
+        }
+        fn record_first_id(&mut self, id: WidgetId) {
+            self.first_id = id;
+        }
+        fn num_children(&self) -> usize {
+            self.items.len()
+        }
+        fn get_child(&self, index: usize) -> Option<&dyn WidgetConfig> {
+            self.items.get(index).map(|w| w.as_widget())
+        }
+        fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+            self.items.get_mut(index).map(|w| w.as_widget_mut())
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> kas::layout::Layout<'_> {
+            make_layout!(self.core; flow: self.items)
+        }
+    }
+}
+
+#[test]
+fn construct() {
+    let pair = Pair {
+        core: Default::default(),
+        a: Leaf::default(),
+        b: Leaf::default(),
+    };
+    assert_eq!(pair.num_children(), 2);
+    assert!(matches!(Item::Clicked, Item::Clicked));
+
+    let regions = Regions {
+        core: Default::default(),
+        a: Leaf::default(),
+        b: Leaf::default(),
+    };
+    assert_eq!(regions.num_children(), 2);
+
+    let flow = Flow {
+        items: vec![Leaf::default(); 3],
+        ..Default::default()
+    };
+    assert_eq!(flow.num_children(), 3);
+}
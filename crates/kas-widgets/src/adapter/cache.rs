@@ -0,0 +1,141 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Cached drawing
+
+use kas::draw::OwnedImageId;
+use kas::layout;
+use kas::prelude::*;
+
+widget! {
+    /// A wrapper caching the drawing of its contents
+    ///
+    /// The inner widget is drawn to an offscreen layer (see
+    /// [`DrawShared::layer_alloc`]), which is then drawn to the window. The
+    /// layer is re-used until invalidated, avoiding the cost of redrawing the
+    /// inner widget each frame. This may be useful for complex subtrees which
+    /// rarely change.
+    ///
+    /// The cache is invalidated on resize, whenever an event is sent to any
+    /// widget in the subtree (including timer and update events), whenever the
+    /// interaction state (e.g. hover or focus) of a widget in the subtree
+    /// changes, when the theme changes (see [`ManagerState::theme_generation`])
+    /// and by [`CacheLayer::invalidate`]. The latter must be called when
+    /// contents are changed by other means, e.g. via `DerefMut`.
+    ///
+    /// Where the graphics backend does not support layers, the inner widget is
+    /// drawn directly. The layer is freed when this widget is dropped.
+    #[autoimpl(Deref, DerefMut on inner)]
+    #[autoimpl(class_traits where W: trait on inner)]
+    #[derive(Debug, Default)]
+    #[handler(msg = <W as Handler>::Msg)]
+    pub struct CacheLayer<W: Widget> {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        pub inner: W,
+        layer: Option<(OwnedImageId, Size)>,
+        // State when last drawn: (theme generation, disabled, widgets with
+        // interaction state)
+        drawn: Option<(u64, bool, Vec<WidgetId>)>,
+    }
+
+    impl Self {
+        /// Construct
+        #[inline]
+        pub fn new(inner: W) -> Self {
+            CacheLayer {
+                core: Default::default(),
+                inner,
+                layer: None,
+                drawn: None,
+            }
+        }
+
+        /// Invalidate the cache
+        ///
+        /// The inner widget will be redrawn on the next frame.
+        pub fn invalidate(&mut self) -> TkAction {
+            self.drawn = None;
+            TkAction::REDRAW
+        }
+    }
+
+    impl WidgetConfig for Self {
+        fn configure(&mut self, _: &mut Manager) {
+            // Widget identifiers may have changed
+            self.drawn = None;
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> layout::Layout<'_> {
+            layout::Layout::single(&mut self.inner)
+        }
+
+        fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
+            self.core_data_mut().rect = rect;
+            self.inner.set_rect(mgr, rect, align);
+            self.drawn = None;
+
+            if self.layer.as_ref().map(|(_, size)| *size) != Some(rect.size) {
+                // The old layer (if any) is freed on drop
+                self.layer = None;
+                self.layer = mgr.draw_shared(|ds| {
+                    match ds.layer_alloc_owned((rect.size.0.cast(), rect.size.1.cast())) {
+                        Ok(id) => Some((id, rect.size)),
+                        Err(error) => {
+                            log::debug!("CacheLayer: failed to allocate layer: {}", error);
+                            None
+                        }
+                    }
+                });
+            }
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let disabled = disabled || self.is_disabled();
+            let id = match self.layer {
+                Some((ref id, _)) => id.id(),
+                None => {
+                    self.inner.draw(draw, mgr, disabled);
+                    return;
+                }
+            };
+
+            let interacting = mgr
+                .interacting_widgets()
+                .filter(|w_id| self.is_ancestor_of(*w_id))
+                .collect();
+            let state = Some((mgr.theme_generation(), disabled, interacting));
+            let rect = self.rect();
+            if self.drawn != state {
+                let inner = &mut self.inner;
+                draw.with_layer(id, rect, &mut |draw| inner.draw(draw, mgr, disabled));
+                self.drawn = state;
+            }
+
+            draw.image(id, rect);
+        }
+    }
+
+    impl SendEvent for Self {
+        fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            if self.is_disabled() {
+                return Response::Unhandled;
+            }
+
+            // Any event may affect the appearance of the subtree
+            self.drawn = None;
+
+            if id < self.id() {
+                self.inner.send(mgr, id, event)
+            } else {
+                debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
+                self.handle(mgr, event)
+            }
+        }
+    }
+}
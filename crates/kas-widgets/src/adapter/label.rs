@@ -69,6 +69,7 @@ widget! {
         /// Note: this must not be called before fonts have been initialised
         /// (usually done by the theme when the main loop starts).
         pub fn set_text<T: Into<AccelString>>(&mut self, text: T) -> TkAction {
+            self.core.bump_generation();
             set_text_and_prepare(&mut self.label, text.into(), self.core.rect.size)
         }
 
@@ -80,7 +81,9 @@ widget! {
 
     impl WidgetConfig for Self {
         fn configure(&mut self, mgr: &mut Manager) {
-            mgr.add_accel_keys(self.inner.id(), self.keys());
+            if let Some(string) = mgr.add_accel_label(self.inner.id(), self.label.text()) {
+                *mgr |= set_text_and_prepare(&mut self.label, string, self.core.rect.size);
+            }
         }
     }
 
@@ -109,6 +112,7 @@ widget! {
 
     impl SetAccel for Self {
         fn set_accel_string(&mut self, string: AccelString) -> TkAction {
+            self.core.bump_generation();
             let mut action = TkAction::empty();
             if self.label.text().keys() != string.keys() {
                 action |= TkAction::RECONFIGURE;
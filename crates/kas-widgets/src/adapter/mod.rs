@@ -5,12 +5,16 @@
 
 //! Adapter widgets (wrappers)
 
+mod cache;
 mod label;
 mod map;
+mod opacity;
 mod reserve;
 mod widget_ext;
 
+pub use cache::CacheLayer;
 pub use label::WithLabel;
 pub use map::MapResponse;
+pub use opacity::Opacity;
 pub use reserve::{Reserve, ReserveP};
 pub use widget_ext::*;
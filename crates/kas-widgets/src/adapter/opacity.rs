@@ -0,0 +1,189 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Opacity adapter
+
+use instant::Instant;
+use kas::event::ScrollEasing;
+use kas::layout;
+use kas::prelude::*;
+use std::time::Duration;
+
+/// Timer payload used for fading
+const TIMER_FADE: u64 = 1 << 61;
+
+/// State of a fade in progress
+#[derive(Clone, Copy, Debug)]
+struct Fade {
+    from: f32,
+    to: f32,
+    start: Instant,
+    duration: Duration,
+}
+
+widget! {
+    /// A wrapper drawing its contents with reduced opacity
+    ///
+    /// The inner widget is drawn as a group with the given opacity (see
+    /// [`DrawHandleExt::with_alpha`]): overlapping parts of the contents do
+    /// not show through each other. The opacity may be animated via
+    /// [`Opacity::fade_to`].
+    ///
+    /// Optionally, a different opacity may be used while disabled (see
+    /// [`Opacity::with_disabled_alpha`]); this is multiplied with the normal
+    /// opacity.
+    ///
+    /// Where the graphics backend does not support layers, the inner widget may
+    /// be drawn with full opacity.
+    #[autoimpl(Deref, DerefMut on inner)]
+    #[autoimpl(class_traits where W: trait on inner)]
+    #[derive(Clone, Debug)]
+    #[handler(msg = <W as Handler>::Msg)]
+    pub struct Opacity<W: Widget> {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        pub inner: W,
+        alpha: f32,
+        disabled_alpha: Option<f32>,
+        fade: Option<Fade>,
+    }
+
+    impl Self {
+        /// Construct, with opacity `alpha`
+        ///
+        /// The value is clamped to `0.0..=1.0`.
+        #[inline]
+        pub fn new(inner: W, alpha: f32) -> Self {
+            Opacity {
+                core: Default::default(),
+                inner,
+                alpha: alpha.clamp(0.0, 1.0),
+                disabled_alpha: None,
+                fade: None,
+            }
+        }
+
+        /// Set the opacity used while disabled, builder style
+        ///
+        /// The value is clamped to `0.0..=1.0`.
+        #[inline]
+        pub fn with_disabled_alpha(mut self, alpha: f32) -> Self {
+            self.disabled_alpha = Some(alpha.clamp(0.0, 1.0));
+            self
+        }
+
+        /// Get the current opacity
+        ///
+        /// During a fade, this is the target opacity.
+        #[inline]
+        pub fn alpha(&self) -> f32 {
+            match self.fade {
+                Some(ref fade) => fade.to,
+                None => self.alpha,
+            }
+        }
+
+        /// Set the opacity
+        ///
+        /// The value is clamped to `0.0..=1.0`. Any fade in progress is stopped.
+        pub fn set_alpha(&mut self, alpha: f32) -> TkAction {
+            self.fade = None;
+            let alpha = alpha.clamp(0.0, 1.0);
+            if alpha == self.alpha {
+                return TkAction::empty();
+            }
+            self.alpha = alpha;
+            TkAction::REDRAW
+        }
+
+        /// Fade to opacity `alpha` over `duration`
+        ///
+        /// The value is clamped to `0.0..=1.0`. The fade starts from the
+        /// current (displayed) opacity.
+        pub fn fade_to(&mut self, mgr: &mut Manager, alpha: f32, duration: Duration) {
+            let alpha = alpha.clamp(0.0, 1.0);
+            if duration == Duration::ZERO || alpha == self.alpha {
+                *mgr |= self.set_alpha(alpha);
+                return;
+            }
+            self.fade = Some(Fade {
+                from: self.alpha,
+                to: alpha,
+                start: mgr.next_frame_time(),
+                duration,
+            });
+            mgr.update_on_frame(self.id(), TIMER_FADE);
+        }
+
+        /// Advance a fade in progress
+        fn animate(&mut self, mgr: &mut Manager) {
+            let fade = match self.fade {
+                Some(fade) => fade,
+                None => return,
+            };
+            let elapsed = mgr.next_frame_time().saturating_duration_since(fade.start);
+            let t = elapsed.as_secs_f64() / fade.duration.as_secs_f64();
+            if t >= 1.0 {
+                self.alpha = fade.to;
+                self.fade = None;
+            } else {
+                let f = ScrollEasing::EaseOutCubic.apply(t) as f32;
+                self.alpha = fade.from + (fade.to - fade.from) * f;
+                mgr.update_on_frame(self.id(), TIMER_FADE);
+            }
+            mgr.redraw(self.id());
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> layout::Layout<'_> {
+            layout::Layout::single(&mut self.inner)
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let disabled = disabled || self.is_disabled();
+            let mut alpha = self.alpha;
+            if disabled {
+                alpha *= self.disabled_alpha.unwrap_or(1.0);
+            }
+            if alpha <= 0.0 {
+                return;
+            }
+
+            let inner = &mut self.inner;
+            draw.with_alpha(alpha, self.core.rect, &mut |draw| {
+                inner.draw(draw, mgr, disabled)
+            });
+        }
+    }
+
+    impl SendEvent for Self {
+        fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            if self.is_disabled() {
+                return Response::Unhandled;
+            }
+
+            if id < self.id() {
+                self.inner.send(mgr, id, event)
+            } else {
+                debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
+                match event {
+                    Event::TimerUpdate(TIMER_FADE) => {
+                        self.animate(mgr);
+                        Response::None
+                    }
+                    event => self.handle(mgr, event),
+                }
+            }
+        }
+    }
+}
+
+impl<W: Widget + Default> Default for Opacity<W> {
+    fn default() -> Self {
+        Self::new(W::default(), 1.0)
+    }
+}
@@ -5,7 +5,7 @@
 
 //! Widget extension traits
 
-use super::{MapResponse, Reserve, WithLabel};
+use super::{CacheLayer, MapResponse, Opacity, Reserve, WithLabel};
 use kas::dir::Directional;
 use kas::draw::SizeHandle;
 use kas::event::{Manager, Response};
@@ -94,5 +94,25 @@ pub trait WidgetExt: Widget {
     {
         WithLabel::new_with_direction(direction, self, label)
     }
+
+    /// Construct a wrapper widget caching the drawing of this widget
+    ///
+    /// See [`CacheLayer`].
+    fn cached(self) -> CacheLayer<Self>
+    where
+        Self: Sized,
+    {
+        CacheLayer::new(self)
+    }
+
+    /// Construct a wrapper widget drawing this widget with opacity `alpha`
+    ///
+    /// See [`Opacity`].
+    fn with_opacity(self, alpha: f32) -> Opacity<Self>
+    where
+        Self: Sized,
+    {
+        Opacity::new(self, alpha)
+    }
 }
 impl<W: Widget + ?Sized> WidgetExt for W {}
@@ -178,18 +178,31 @@ widget! {
         layout_text: layout::TextStorage,
         color: Option<Rgb>,
         label: Text<AccelString>,
+        msg: Option<String>,
+        initial_focus: bool,
         on_push: Option<Rc<dyn Fn(&mut Manager) -> Option<M>>>,
     }
 
     impl WidgetConfig for Self {
         fn configure(&mut self, mgr: &mut Manager) {
+            if let Some(key) = self.msg.as_deref() {
+                let string = AccelString::from(mgr.translate(key));
+                let avail = self.core.rect.size.clamped_sub(self.layout_frame.size);
+                *mgr |= kas::text::util::set_text_and_prepare(&mut self.label, string, avail);
+            }
             mgr.add_accel_keys(self.id(), &self.keys1);
-            mgr.add_accel_keys(self.id(), self.label.text().keys());
+            if let Some(string) = mgr.add_accel_label(self.id(), self.label.text()) {
+                let avail = self.core.rect.size.clamped_sub(self.layout_frame.size);
+                *mgr |= kas::text::util::set_text_and_prepare(&mut self.label, string, avail);
+            }
         }
 
         fn key_nav(&self) -> bool {
             true
         }
+        fn initial_focus(&self) -> bool {
+            self.initial_focus
+        }
         fn hover_highlight(&self) -> bool {
             true
         }
@@ -215,6 +228,8 @@ widget! {
                 layout_text: Default::default(),
                 color: None,
                 label: text,
+                msg: None,
+                initial_focus: false,
                 on_push: None,
             }
         }
@@ -236,6 +251,8 @@ widget! {
                 layout_text: self.layout_text,
                 color: self.color,
                 label: self.label,
+                msg: self.msg,
+                initial_focus: self.initial_focus,
                 on_push: Some(Rc::new(f)),
             }
         }
@@ -255,6 +272,21 @@ widget! {
             TextButton::new(label).on_push(f)
         }
 
+        /// Construct a button from a localized message `key` and event handler `f`
+        ///
+        /// The label is resolved via [`Manager::translate`] when configured,
+        /// and re-resolved when the locale changes; the translation may
+        /// include accelerator marks (`&`). See [`kas::l10n`].
+        #[inline]
+        pub fn msg<F>(key: &str, f: F) -> Self
+        where
+            F: Fn(&mut Manager) -> Option<M> + 'static,
+        {
+            let mut button = TextButton::new(key).on_push(f);
+            button.msg = Some(key.to_string());
+            button
+        }
+
         /// Construct a button with a given `label` and payload `msg`
         ///
         /// On activation (through user input events or [`Event::Activate`]) a clone
@@ -287,6 +319,15 @@ widget! {
             self.color = Some(color);
             self
         }
+
+        /// Give this button initial navigation focus (chain style)
+        ///
+        /// See [`WidgetConfig::initial_focus`]. This is typically used for the
+        /// default button of a dialog.
+        pub fn with_initial_focus(mut self) -> Self {
+            self.initial_focus = true;
+            self
+        }
     }
 
     impl HasStr for Self {
@@ -297,6 +338,7 @@ widget! {
 
     impl SetAccel for Self {
         fn set_accel_string(&mut self, string: AccelString) -> TkAction {
+            self.core.bump_generation();
             let mut action = TkAction::empty();
             if self.label.text().keys() != string.keys() {
                 action |= TkAction::RECONFIGURE;
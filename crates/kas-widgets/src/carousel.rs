@@ -0,0 +1,461 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! A carousel
+
+use instant::Instant;
+use std::ops::{Index, IndexMut};
+use std::time::Duration;
+
+use kas::event::{self, CursorIcon, GrabMode, PressSource};
+use kas::prelude::*;
+
+/// A carousel of boxed widgets
+///
+/// This is a parametrisation of [`Carousel`].
+pub type BoxCarousel<M> = Carousel<Box<dyn Widget<Msg = M>>>;
+
+// Timer payloads
+const TIMER_ANIMATE: u64 = 0;
+const TIMER_CYCLE: u64 = 1;
+
+/// Duration of a slide transition
+const SLIDE_TIME: Duration = Duration::from_millis(250);
+
+widget! {
+    /// A carousel: a paged view over widgets
+    ///
+    /// Like a [`Stack`](crate::Stack), all children ("pages") have equal size
+    /// and only a single page is visible at a time. Below the page, an
+    /// indicator dot is drawn for each page.
+    ///
+    /// The page may be changed by swiping (with touch, or a mouse drag where
+    /// [mouse panning](kas::event::Config::mouse_pan) is enabled), by clicking
+    /// an indicator dot, or programmatically via [`Carousel::slide_to`].
+    /// Page changes are animated by sliding the new page into view.
+    ///
+    /// Optionally, pages may be cycled automatically: see
+    /// [`Carousel::with_cycle`].
+    ///
+    /// The `Carousel` widget forwards messages from children: `M = <W as Handler>::Msg`.
+    ///
+    /// Configuring and resizing elements is O(n) in the number of children.
+    /// Drawing and event handling is O(1).
+    #[autoimpl(Default)]
+    #[derive(Clone, Debug)]
+    pub struct Carousel<W: Widget> {
+        first_id: WidgetId,
+        #[widget_core]
+        core: CoreData,
+        widgets: Vec<W>,
+        active: usize,
+        wrap: bool,
+        cycle: Option<Duration>,
+        page_rect: Rect,
+        dot_size: Size,
+        dot_gap: i32,
+        // Horizontal offset of the active page from its rest position
+        offset: i32,
+        // Page drawn beside the active page while offset is non-zero
+        adjacent: Option<usize>,
+        // Start time and initial offset of the slide animation
+        anim: Option<(Instant, i32)>,
+        // Source, start x-coordinate and initial offset of a swipe
+        press: Option<(PressSource, i32, i32)>,
+    }
+
+    impl WidgetChildren for Self {
+        #[inline]
+        fn first_id(&self) -> WidgetId {
+            self.first_id
+        }
+        fn record_first_id(&mut self, id: WidgetId) {
+            self.first_id = id;
+        }
+        #[inline]
+        fn num_children(&self) -> usize {
+            self.widgets.len()
+        }
+        #[inline]
+        fn get_child(&self, index: usize) -> Option<&dyn WidgetConfig> {
+            self.widgets.get(index).map(|w| w.as_widget())
+        }
+        #[inline]
+        fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+            self.widgets.get_mut(index).map(|w| w.as_widget_mut())
+        }
+    }
+
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            if let Some(interval) = self.cycle {
+                mgr.update_on_timer(interval, self.id(), TIMER_CYCLE);
+            }
+        }
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+            self.dot_size = size_handle.radiobox();
+            self.dot_gap = size_handle.inner_margin().0;
+
+            let mut rules = SizeRules::EMPTY;
+            for child in &mut self.widgets {
+                rules = rules.max(child.size_rules(size_handle, axis));
+            }
+
+            if axis.is_horizontal() {
+                rules.max(SizeRules::fixed(self.dots_width(), (0, 0)))
+            } else {
+                rules.appended(SizeRules::fixed(self.dot_gap + self.dot_size.1, (0, 0)))
+            }
+        }
+
+        fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
+            self.core.rect = rect;
+            let mut page_rect = rect;
+            page_rect.size.1 = (rect.size.1 - self.dot_gap - self.dot_size.1).max(0);
+            self.page_rect = page_rect;
+            for child in &mut self.widgets {
+                child.set_rect(mgr, page_rect, align);
+            }
+        }
+
+        fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
+            if !self.rect().contains(coord) {
+                return None;
+            }
+
+            // While sliding, pages are not where they think they are
+            if self.offset == 0 && self.page_rect.contains(coord) {
+                if let Some(child) = self.widgets.get_mut(self.active) {
+                    if let Some(id) = child.find_id(coord) {
+                        return Some(id);
+                    }
+                }
+            }
+            Some(self.id())
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let disabled = disabled || self.is_disabled();
+
+            let (offset, width) = (self.offset, self.page_rect.size.0);
+            let adjacent = self.adjacent.map(|index| match offset > 0 {
+                true => (index, offset - width),
+                false => (index, offset + width),
+            });
+            for (index, x) in std::iter::once((self.active, offset)).chain(adjacent) {
+                if let Some(child) = self.widgets.get_mut(index) {
+                    draw.with_clip_region(self.page_rect, Offset(-x, 0), &mut |draw| {
+                        child.draw(draw, mgr, disabled)
+                    });
+                }
+            }
+
+            let state = match disabled {
+                true => InputState::DISABLED,
+                false => InputState::empty(),
+            };
+            for index in 0..self.widgets.len() {
+                draw.radiobox(self.dot_rect(index), index == self.active, state);
+            }
+        }
+    }
+
+    impl event::Handler for Self {
+        type Msg = <W as event::Handler>::Msg;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+            match event {
+                Event::TimerUpdate(TIMER_ANIMATE) => self.animate(mgr),
+                Event::TimerUpdate(TIMER_CYCLE) => {
+                    if let Some(interval) = self.cycle {
+                        if self.press.is_none() && !self.widgets.is_empty() {
+                            let index = (self.active + 1) % self.widgets.len();
+                            self.slide(mgr, index, true);
+                        }
+                        mgr.update_on_timer(interval, self.id(), TIMER_CYCLE);
+                    }
+                }
+                Event::PressStart { source, coord, .. } if source.is_primary() => {
+                    let dot = (0..self.widgets.len()).find(|i| self.dot_rect(*i).contains(coord));
+                    if let Some(index) = dot {
+                        self.slide_to(mgr, index);
+                    } else if self.press.is_none()
+                        && (source.is_touch() || mgr.config_enable_mouse_pan())
+                    {
+                        let icon = Some(CursorIcon::Grabbing);
+                        if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, icon) {
+                            self.anim = None;
+                            self.press = Some((source, coord.0, self.offset));
+                        }
+                    } else {
+                        return Response::Unhandled;
+                    }
+                }
+                Event::PressMove { source, coord, .. }
+                    if self.press.map(|press| press.0) == Some(source) =>
+                {
+                    let (_, x0, offset) = self.press.unwrap();
+                    self.set_offset(offset + coord.0 - x0);
+                    mgr.redraw(self.id());
+                }
+                Event::PressEnd { source, .. } if self.press.map(|press| press.0) == Some(source) => {
+                    self.press = None;
+                    let (offset, width) = (self.offset, self.page_rect.size.0);
+                    if let Some(index) = self.adjacent.filter(|_| offset.abs() > width / 4) {
+                        self.adjacent = Some(self.active);
+                        self.active = index;
+                        self.offset += match offset > 0 {
+                            true => -width,
+                            false => width,
+                        };
+                    }
+                    self.start_animation(mgr);
+                }
+                _ => return Response::Unhandled,
+            }
+            Response::None
+        }
+    }
+
+    impl event::SendEvent for Self {
+        fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            if self.is_disabled() {
+                return Response::Unhandled;
+            }
+
+            if id < self.id() {
+                match mgr.send_to_child(&mut self.widgets, id, event.clone()) {
+                    None | Some((_, Response::Unhandled)) => (),
+                    Some((index, Response::Focus(rect))) => {
+                        *mgr |= self.set_active(index);
+                        return Response::Focus(rect);
+                    }
+                    Some((_, r)) => return r,
+                }
+            } else {
+                debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
+            }
+
+            self.handle(mgr, event)
+        }
+    }
+
+    impl Index<usize> for Self {
+        type Output = W;
+
+        fn index(&self, index: usize) -> &Self::Output {
+            &self.widgets[index]
+        }
+    }
+
+    impl IndexMut<usize> for Self {
+        fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+            &mut self.widgets[index]
+        }
+    }
+}
+
+impl<W: Widget> Carousel<W> {
+    /// Construct a new instance
+    ///
+    /// The first page is initially active.
+    pub fn new(widgets: Vec<W>) -> Self {
+        Carousel {
+            widgets,
+            ..Default::default()
+        }
+    }
+
+    /// Allow swiping from the last page to the first and vice-versa
+    #[inline]
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Cycle automatically to the next page every `interval`
+    ///
+    /// Cycling continues from the last page to the first.
+    #[inline]
+    pub fn with_cycle(mut self, interval: Duration) -> Self {
+        self.cycle = Some(interval);
+        self
+    }
+
+    /// Get the index of the active page
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// Change the active page via index, without animation
+    ///
+    /// It is not required that `active < self.len()`; if not, no page will be
+    /// drawn or respond to events.
+    pub fn set_active(&mut self, active: usize) -> TkAction {
+        let moving = self.offset != 0;
+        self.offset = 0;
+        self.adjacent = None;
+        self.anim = None;
+        if self.active == active && !moving {
+            TkAction::empty()
+        } else {
+            self.active = active;
+            TkAction::REGION_MOVED
+        }
+    }
+
+    /// Slide to the page at `index`
+    ///
+    /// Does nothing if `index` is the active page or out of bounds.
+    pub fn slide_to(&mut self, mgr: &mut Manager, index: usize) {
+        self.slide(mgr, index, index > self.active);
+    }
+
+    /// Get a direct reference to the active page, if any
+    pub fn active(&self) -> Option<&W> {
+        self.widgets.get(self.active)
+    }
+
+    /// Get a direct mutable reference to the active page, if any
+    pub fn active_mut(&mut self) -> Option<&mut W> {
+        self.widgets.get_mut(self.active)
+    }
+
+    /// True if there are no pages
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Returns the number of pages
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    /// Append a page
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action).
+    pub fn push(&mut self, widget: W) -> TkAction {
+        self.widgets.push(widget);
+        TkAction::RECONFIGURE
+    }
+
+    /// Remove the last page
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action) if any widget is
+    /// removed.
+    pub fn pop(&mut self) -> (Option<W>, TkAction) {
+        let action = match self.widgets.is_empty() {
+            true => TkAction::empty(),
+            false => TkAction::RECONFIGURE,
+        };
+        (self.widgets.pop(), action)
+    }
+
+    /// Iterate over pages
+    pub fn iter(&self) -> impl Iterator<Item = &W> {
+        self.widgets.iter()
+    }
+
+    /// Mutably iterate over pages
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut W> {
+        self.widgets.iter_mut()
+    }
+
+    fn dots_width(&self) -> i32 {
+        let n = i32::conv(self.widgets.len());
+        n * self.dot_size.0 + (n - 1).max(0) * self.dot_gap
+    }
+
+    fn dot_rect(&self, index: usize) -> Rect {
+        let rect = self.core.rect;
+        let x = rect.pos.0 + (rect.size.0 - self.dots_width()) / 2;
+        let x = x + i32::conv(index) * (self.dot_size.0 + self.dot_gap);
+        let y = rect.pos2().1 - self.dot_size.1;
+        Rect::new(Coord(x, y), self.dot_size)
+    }
+
+    /// The page to the right (`next`) or left of the active page, if any
+    fn neighbour(&self, next: bool) -> Option<usize> {
+        let len = self.widgets.len();
+        if next {
+            if self.active + 1 < len {
+                Some(self.active + 1)
+            } else {
+                (self.wrap && len > 1).then(|| 0)
+            }
+        } else if self.active > 0 {
+            Some(self.active - 1)
+        } else {
+            (self.wrap && len > 1).then(|| len - 1)
+        }
+    }
+
+    /// Set the offset while swiping
+    fn set_offset(&mut self, offset: i32) {
+        let width = self.page_rect.size.0;
+        let offset = offset.max(-width).min(width);
+        self.adjacent = match offset {
+            x if x < 0 => self.neighbour(true),
+            x if x > 0 => self.neighbour(false),
+            _ => None,
+        };
+        // Resist swiping beyond the first/last page
+        self.offset = match self.adjacent {
+            Some(_) => offset,
+            None => offset / 4,
+        };
+    }
+
+    fn slide(&mut self, mgr: &mut Manager, index: usize, forwards: bool) {
+        if index == self.active || index >= self.widgets.len() {
+            return;
+        }
+        self.adjacent = Some(self.active);
+        self.active = index;
+        let width = self.page_rect.size.0;
+        self.offset = match forwards {
+            true => width,
+            false => -width,
+        };
+        self.start_animation(mgr);
+    }
+
+    fn start_animation(&mut self, mgr: &mut Manager) {
+        if self.offset == 0 {
+            self.adjacent = None;
+            self.anim = None;
+        } else {
+            self.anim = Some((Instant::now(), self.offset));
+            mgr.update_on_frame(self.id(), TIMER_ANIMATE);
+        }
+        mgr.redraw(self.id());
+    }
+
+    fn animate(&mut self, mgr: &mut Manager) {
+        let (start, from) = match self.anim {
+            Some(anim) => anim,
+            None => return,
+        };
+
+        let elapsed = mgr.next_frame_time().saturating_duration_since(start);
+        let t = elapsed.as_secs_f32() / SLIDE_TIME.as_secs_f32();
+        if t >= 1.0 {
+            self.offset = 0;
+            self.adjacent = None;
+            self.anim = None;
+            // Pages may now receive events at their true position
+            mgr.send_action(TkAction::REGION_MOVED);
+        } else {
+            // Ease out (cubic)
+            let f = (1.0 - t).powi(3);
+            self.offset = (from as f32 * f).cast_nearest();
+            mgr.update_on_frame(self.id(), TIMER_ANIMATE);
+        }
+        mgr.redraw(self.id());
+    }
+}
@@ -11,19 +11,35 @@ use std::rc::Rc;
 
 widget! {
     /// A bare checkbox (no label)
+    ///
+    /// The state may be persisted via [`CheckBoxBare::with_state_key`].
     #[autoimpl(Debug skip on_toggle)]
     #[derive(Clone, Default)]
-    #[widget{
-        key_nav = true;
-        hover_highlight = true;
-    }]
     pub struct CheckBoxBare<M: 'static> {
         #[widget_core]
         core: CoreData,
         state: bool,
+        state_key: Option<String>,
         on_toggle: Option<Rc<dyn Fn(&mut Manager, bool) -> Option<M>>>,
     }
 
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            if let Some(key) = self.state_key.as_deref() {
+                if let Some(state) = mgr.load_state(key).and_then(|v| v.parse().ok()) {
+                    self.state = state;
+                }
+            }
+        }
+
+        fn key_nav(&self) -> bool {
+            true
+        }
+        fn hover_highlight(&self) -> bool {
+            true
+        }
+    }
+
     impl Layout for Self {
         fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
             let size = size_handle.checkbox();
@@ -51,6 +67,7 @@ widget! {
             CheckBoxBare {
                 core: Default::default(),
                 state: false,
+                state_key: None,
                 on_toggle: None,
             }
         }
@@ -68,6 +85,7 @@ widget! {
             CheckBoxBare {
                 core: self.core,
                 state: self.state,
+                state_key: self.state_key,
                 on_toggle: Some(Rc::new(f)),
             }
         }
@@ -95,6 +113,16 @@ widget! {
             self.state = state;
             self
         }
+
+        /// Persist the state under `key` (inline)
+        ///
+        /// The state is saved when toggled and restored (without calling the
+        /// toggle handler) when next configured. See [`kas::state`].
+        #[inline]
+        pub fn with_state_key<K: ToString>(mut self, key: K) -> Self {
+            self.state_key = Some(key.to_string());
+            self
+        }
     }
 
     impl HasBool for Self {
@@ -121,6 +149,9 @@ widget! {
                 Event::Activate => {
                     self.state = !self.state;
                     mgr.redraw(self.id());
+                    if let Some(key) = self.state_key.as_deref() {
+                        mgr.save_state(key, self.state.to_string());
+                    }
                     Response::update_or_msg(self.on_toggle.as_ref().and_then(|f| f(mgr, self.state)))
                 }
                 _ => Response::Unhandled,
@@ -149,7 +180,7 @@ widget! {
 
     impl WidgetConfig for Self {
         fn configure(&mut self, mgr: &mut Manager) {
-            mgr.add_accel_keys(self.checkbox.id(), self.label.keys());
+            self.label.add_accel_label(mgr, self.checkbox.id());
         }
     }
 
@@ -212,5 +243,14 @@ widget! {
             self.checkbox = self.checkbox.with_state(state);
             self
         }
+
+        /// Persist the state under `key` (inline)
+        ///
+        /// See [`CheckBoxBare::with_state_key`].
+        #[inline]
+        pub fn with_state_key<K: ToString>(mut self, key: K) -> Self {
+            self.checkbox = self.checkbox.with_state_key(key);
+            self
+        }
     }
 }
@@ -10,13 +10,15 @@ use kas::draw::TextClass;
 use kas::event::{self, Command, GrabMode};
 use kas::layout;
 use kas::prelude::*;
-use kas::WindowId;
+use kas::{ShellCapabilities, WindowId};
 use std::rc::Rc;
 
 widget! {
     /// A pop-up multiple choice menu
     ///
     /// A combobox presents a menu with a fixed set of choices when clicked.
+    /// Where the shell does not support pop-ups (see
+    /// [`kas::ShellCapabilities`]), clicking instead selects the next choice.
     #[autoimpl(Debug skip on_select)]
     #[derive(Clone)]
     #[widget{
@@ -61,6 +63,8 @@ widget! {
         type Msg = M;
 
         fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+            // Without pop-up support we cycle through choices instead
+            let popups = mgr.shell_capabilities().contains(ShellCapabilities::POPUPS);
             let open_popup = |s: &mut Self, mgr: &mut Manager, key_focus: bool| {
                 s.popup_id = mgr.add_popup(kas::Popup {
                     id: s.popup.id(),
@@ -75,8 +79,10 @@ widget! {
                 Event::Activate => {
                     if let Some(id) = self.popup_id {
                         mgr.close_window(id, true);
-                    } else {
+                    } else if popups {
                         open_popup(self, mgr, true);
+                    } else {
+                        return self.select_next(mgr);
                     }
                 }
                 Event::PressStart {
@@ -103,7 +109,7 @@ widget! {
                     coord,
                     ..
                 } => {
-                    if self.popup_id.is_none() {
+                    if self.popup_id.is_none() && popups {
                         open_popup(self, mgr, false);
                     }
                     let cond = self.popup.inner.rect().contains(coord);
@@ -117,7 +123,9 @@ widget! {
                     if let Some(id) = end_id {
                         if id == self.id() {
                             if self.opening {
-                                if self.popup_id.is_none() {
+                                if !popups {
+                                    return self.select_next(mgr);
+                                } else if self.popup_id.is_none() {
                                     open_popup(self, mgr, false);
                                 }
                                 return Response::None;
@@ -325,6 +333,20 @@ impl<M: 'static> ComboBox<M> {
 }
 
 impl<M: 'static> ComboBox<M> {
+    /// Select the next choice (wrapping)
+    fn select_next(&mut self, mgr: &mut Manager) -> Response<M> {
+        if self.is_empty() {
+            return Response::None;
+        }
+        let index = (self.active + 1) % self.len();
+        *mgr |= self.set_active(index);
+        if let Some(ref f) = self.on_select {
+            Response::update_or_msg((f)(mgr, index))
+        } else {
+            Response::Update
+        }
+    }
+
     fn map_response(
         &mut self,
         mgr: &mut Manager,
@@ -355,6 +377,7 @@ impl<M: 'static> ComboBox<M> {
             },
             Response::Pan(delta) => Response::Pan(delta),
             Response::Focus(x) => Response::Focus(x),
+            Response::Reveal(x, align) => Response::Reveal(x, align),
             Response::Update | Response::Select => {
                 if let Some(id) = self.popup_id {
                     mgr.close_window(id, true);
@@ -382,6 +405,8 @@ impl<M: 'static> ComboBox<M> {
                     Response::Update
                 }
             }
+            // Other responses carry no message
+            r => r.try_into().unwrap_or(Response::None),
         }
     }
 }
@@ -0,0 +1,341 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Client-side window decorations
+//!
+//! These widgets allow an undecorated window (see
+//! [`kas::WindowAttributes::decorations`]) to draw its own title bar and resize
+//! borders.
+
+use crate::{ElidedLabel, TextButton};
+use kas::event::{GrabMode, PressSource};
+use kas::prelude::*;
+use kas::text::util::Elide;
+use kas::ResizeDirection;
+
+/// Width of resize borders (logical pixels)
+const BORDER: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, VoidMsg)]
+enum TitleBarControl {
+    Minimize,
+    Maximize,
+    Close,
+}
+
+widget! {
+    /// A window title bar
+    ///
+    /// This displays the window title and minimize, maximize and close
+    /// buttons. Dragging the title bar moves the window while double-clicking
+    /// toggles maximization.
+    #[derive(Clone, Debug)]
+    #[widget{
+        layout = row: *;
+        find_id = self.find_button(coord).or(Some(self.id()));
+    }]
+    pub struct TitleBar {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        title: ElidedLabel,
+        #[widget(use_msg = handle_control)]
+        minimize: TextButton<TitleBarControl>,
+        #[widget(use_msg = handle_control)]
+        maximize: TextButton<TitleBarControl>,
+        #[widget(use_msg = handle_control)]
+        close: TextButton<TitleBarControl>,
+    }
+
+    impl Self {
+        /// Construct, with the given window `title`
+        pub fn new<T: ToString>(title: T) -> Self {
+            TitleBar {
+                core: Default::default(),
+                title: ElidedLabel::new(title),
+                minimize: TextButton::new_msg("–", TitleBarControl::Minimize),
+                maximize: TextButton::new_msg("□", TitleBarControl::Maximize),
+                close: TextButton::new_msg("×", TitleBarControl::Close),
+            }
+        }
+
+        /// Set how the title is elided when too long (inline)
+        ///
+        /// By default, the title is elided in the middle.
+        pub fn with_elide(mut self, mode: Elide) -> Self {
+            self.title = self.title.with_elide(mode);
+            self
+        }
+
+        /// Set the displayed title
+        pub fn set_title<T: ToString>(&mut self, title: T) -> TkAction {
+            self.title.set_string(title.to_string())
+        }
+
+        fn find_button(&mut self, coord: Coord) -> Option<WidgetId> {
+            self.minimize
+                .find_id(coord)
+                .or_else(|| self.maximize.find_id(coord))
+                .or_else(|| self.close.find_id(coord))
+        }
+
+        fn handle_control(&mut self, mgr: &mut Manager, control: TitleBarControl) {
+            match control {
+                TitleBarControl::Minimize => mgr.minimize_window(),
+                TitleBarControl::Maximize => {
+                    let maximized = mgr.is_maximized();
+                    mgr.set_maximized(!maximized);
+                }
+                TitleBarControl::Close => mgr.send_action(TkAction::CLOSE),
+            }
+        }
+    }
+
+    impl Handler for Self {
+        type Msg = VoidMsg;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+            match event {
+                Event::PressStart { source, .. } if source.is_primary() => {
+                    if let PressSource::Mouse(_, 2) = source {
+                        let maximized = mgr.is_maximized();
+                        mgr.set_maximized(!maximized);
+                    } else {
+                        mgr.drag_window();
+                    }
+                    Response::None
+                }
+                _ => Response::Unhandled,
+            }
+        }
+    }
+}
+
+/// Message from a [`ResizeHandle`] (used only when the shell does not support
+/// resizing via [`Manager::drag_resize_window`])
+#[derive(Clone, Copy, Debug, VoidMsg)]
+enum ResizeMsg {
+    Start,
+    Move(ResizeDirection, Offset),
+}
+
+widget! {
+    /// An invisible handle on a window edge or corner
+    #[derive(Clone, Debug)]
+    #[widget{
+        cursor_icon = self.direction.cursor_icon();
+    }]
+    struct ResizeHandle {
+        #[widget_core]
+        core: CoreData,
+        direction: ResizeDirection,
+        press_source: Option<PressSource>,
+        press_coord: Coord,
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+            SizeRules::EMPTY
+        }
+
+        fn draw(&mut self, _: &mut dyn DrawHandle, _: &ManagerState, _: bool) {}
+    }
+
+    impl Handler for Self {
+        type Msg = ResizeMsg;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+            match event {
+                Event::PressStart { source, coord, .. } if source.is_primary() => {
+                    if mgr.drag_resize_window(self.direction) {
+                        return Response::None;
+                    }
+                    // Fallback: we can only resize without moving the origin
+                    if self.direction.moves_origin() {
+                        return Response::None;
+                    }
+                    let icon = Some(self.direction.cursor_icon());
+                    if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, icon) {
+                        self.press_source = Some(source);
+                        self.press_coord = coord;
+                        return Response::Msg(ResizeMsg::Start);
+                    }
+                    Response::None
+                }
+                Event::PressMove { source, coord, .. } if Some(source) == self.press_source => {
+                    let delta = coord - self.press_coord;
+                    Response::Msg(ResizeMsg::Move(self.direction, delta))
+                }
+                Event::PressEnd { source, .. } if Some(source) == self.press_source => {
+                    self.press_source = None;
+                    Response::None
+                }
+                _ => Response::Unhandled,
+            }
+        }
+    }
+
+    impl Self {
+        fn new(direction: ResizeDirection) -> Self {
+            ResizeHandle {
+                core: Default::default(),
+                direction,
+                press_source: None,
+                press_coord: Coord::ZERO,
+            }
+        }
+    }
+}
+
+widget! {
+    /// A frame providing window resize borders
+    ///
+    /// This should wrap the whole content of an undecorated window. Pressing
+    /// on the (invisible) border around the content resizes the window.
+    /// Where the shell does not support interactive resizing, only the right
+    /// and bottom edges may be used.
+    #[autoimpl(Deref, DerefMut on inner)]
+    #[derive(Clone, Debug)]
+    #[handler(msg = <W as Handler>::Msg)]
+    pub struct ResizeFrame<W: Widget> {
+        #[widget_core]
+        core: CoreData,
+        border: i32,
+        start_size: Size,
+        #[widget(use_msg = handle_resize)]
+        n: ResizeHandle,
+        #[widget(use_msg = handle_resize)]
+        ne: ResizeHandle,
+        #[widget(use_msg = handle_resize)]
+        e: ResizeHandle,
+        #[widget(use_msg = handle_resize)]
+        se: ResizeHandle,
+        #[widget(use_msg = handle_resize)]
+        s: ResizeHandle,
+        #[widget(use_msg = handle_resize)]
+        sw: ResizeHandle,
+        #[widget(use_msg = handle_resize)]
+        w: ResizeHandle,
+        #[widget(use_msg = handle_resize)]
+        nw: ResizeHandle,
+        #[widget]
+        pub inner: W,
+    }
+
+    impl Self {
+        /// Construct
+        pub fn new(inner: W) -> Self {
+            ResizeFrame {
+                core: Default::default(),
+                border: 0,
+                start_size: Size::ZERO,
+                n: ResizeHandle::new(ResizeDirection::North),
+                ne: ResizeHandle::new(ResizeDirection::NorthEast),
+                e: ResizeHandle::new(ResizeDirection::East),
+                se: ResizeHandle::new(ResizeDirection::SouthEast),
+                s: ResizeHandle::new(ResizeDirection::South),
+                sw: ResizeHandle::new(ResizeDirection::SouthWest),
+                w: ResizeHandle::new(ResizeDirection::West),
+                nw: ResizeHandle::new(ResizeDirection::NorthWest),
+                inner,
+            }
+        }
+
+        fn handles_mut(&mut self) -> [&mut ResizeHandle; 8] {
+            [
+                &mut self.n,
+                &mut self.ne,
+                &mut self.e,
+                &mut self.se,
+                &mut self.s,
+                &mut self.sw,
+                &mut self.w,
+                &mut self.nw,
+            ]
+        }
+
+        fn handle_resize(&mut self, mgr: &mut Manager, msg: ResizeMsg) {
+            match msg {
+                ResizeMsg::Start => self.start_size = self.rect().size,
+                ResizeMsg::Move(direction, delta) => {
+                    let mut size = self.start_size;
+                    if direction != ResizeDirection::South {
+                        size.0 += delta.0;
+                    }
+                    if direction != ResizeDirection::East {
+                        size.1 += delta.1;
+                    }
+                    mgr.set_window_size(size.max(Size(1, 1)));
+                }
+            }
+        }
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+            self.border = (BORDER * size_handle.scale_factor()).cast_nearest();
+            let mut rules = self.inner.size_rules(size_handle, axis);
+            rules.append(SizeRules::fixed(2 * self.border, (0, 0)));
+            rules
+        }
+
+        fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
+            self.core.rect = rect;
+            let b = self.border;
+            self.inner.set_rect(mgr, rect.shrink(b), align);
+
+            // Corner handles extend along the edges by this much:
+            let c = 4 * b;
+            let (x0, y0) = (rect.pos.0, rect.pos.1);
+            let (x1, y1) = (rect.pos.0 + rect.size.0, rect.pos.1 + rect.size.1);
+            let (w, h) = ((rect.size.0 - 2 * c).max(0), (rect.size.1 - 2 * c).max(0));
+            let rects = [
+                Rect::new(Coord(x0 + c, y0), Size(w, b)),
+                Rect::new(Coord(x1 - c, y0), Size(c, b)),
+                Rect::new(Coord(x1 - b, y0 + c), Size(b, h)),
+                Rect::new(Coord(x1 - c, y1 - b), Size(c, b)),
+                Rect::new(Coord(x0 + c, y1 - b), Size(w, b)),
+                Rect::new(Coord(x0, y1 - b), Size(c, b)),
+                Rect::new(Coord(x0, y0 + c), Size(b, h)),
+                Rect::new(Coord(x0, y0), Size(c, b)),
+            ];
+            for (handle, rect) in self.handles_mut().into_iter().zip(rects) {
+                handle.set_rect(mgr, rect, AlignHints::NONE);
+            }
+        }
+
+        fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
+            if !self.rect().contains(coord) {
+                return None;
+            }
+            let (b, c) = (self.border, 4 * self.border);
+            let rect = self.core.rect;
+            if rect.shrink(b).contains(coord) {
+                return self.inner.find_id(coord);
+            }
+
+            // Distance from the left, top, right and bottom edges:
+            let (l, t) = (coord.0 - rect.pos.0, coord.1 - rect.pos.1);
+            let (r, b) = (rect.pos2().0 - 1 - coord.0, rect.pos2().1 - 1 - coord.1);
+            let direction = match () {
+                _ if l < c && t < c => ResizeDirection::NorthWest,
+                _ if r < c && t < c => ResizeDirection::NorthEast,
+                _ if l < c && b < c => ResizeDirection::SouthWest,
+                _ if r < c && b < c => ResizeDirection::SouthEast,
+                _ if t < self.border => ResizeDirection::North,
+                _ if b < self.border => ResizeDirection::South,
+                _ if l < self.border => ResizeDirection::West,
+                _ => ResizeDirection::East,
+            };
+            let index = ResizeDirection::ALL.iter().position(|d| *d == direction);
+            Some(self.handles_mut()[index.unwrap()].id())
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let disabled = disabled || self.is_disabled();
+            self.inner.draw(draw, mgr, disabled);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Draw statistics display
+
+use kas::draw::TextClass;
+use kas::geom::Vec2;
+use kas::{layout, prelude::*};
+use std::time::Duration;
+
+const UNAVAILABLE: &str = "Draw statistics unavailable (set KAS_DRAW_STATS=1)";
+
+widget! {
+    /// A debug overlay showing draw statistics
+    ///
+    /// This widget periodically displays the statistics of the last frame
+    /// drawn (see [`ManagerState::draw_stats`]): draw calls, vertex counts and
+    /// buffer uploads per pass, and CPU and GPU times. Statistics must be
+    /// enabled by the shell (for `kas-wgpu`, set `KAS_DRAW_STATS=1`).
+    ///
+    /// Note that this widget's own text contributes to the statistics shown.
+    #[derive(Clone, Debug)]
+    pub struct DrawStatsView {
+        #[widget_core]
+        core: CoreData,
+        text: Text<String>,
+        layout_text: layout::TextStorage,
+        interval: Duration,
+    }
+
+    impl Self {
+        /// Construct, updating every 500ms
+        #[inline]
+        pub fn new() -> Self {
+            DrawStatsView {
+                core: Default::default(),
+                text: Text::new_multi(UNAVAILABLE.to_string()),
+                layout_text: Default::default(),
+                interval: Duration::from_millis(500),
+            }
+        }
+
+        /// Set the update interval, builder style
+        #[inline]
+        pub fn with_interval(mut self, interval: Duration) -> Self {
+            self.interval = interval;
+            self
+        }
+
+        /// Get the displayed text
+        pub fn text(&self) -> &str {
+            self.text.text()
+        }
+
+        fn update(&mut self, mgr: &mut Manager) {
+            let s = match mgr.draw_stats() {
+                Some(stats) => stats.to_string(),
+                None => UNAVAILABLE.to_string(),
+            };
+            if *self.text.text() != s {
+                let avail = Size::from(Vec2::from(self.text.env().bounds));
+                let action = kas::text::util::set_text_and_prepare(&mut self.text, s, avail);
+                *mgr |= action | TkAction::REDRAW;
+            }
+        }
+    }
+
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            mgr.update_on_timer(Duration::ZERO, self.id(), 0);
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> layout::Layout<'_> {
+            layout::Layout::text(&mut self.layout_text, &mut self.text, TextClass::Label)
+        }
+    }
+
+    impl Handler for Self {
+        type Msg = VoidMsg;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+            match event {
+                Event::TimerUpdate(_) => {
+                    self.update(mgr);
+                    mgr.update_on_timer(self.interval, self.id(), 0);
+                    Response::None
+                }
+                _ => Response::Unhandled,
+            }
+        }
+    }
+}
+
+impl Default for DrawStatsView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -5,16 +5,18 @@
 
 //! Text widgets
 
-use super::Scrollable;
+use super::{Scrollable, Validation, Validator};
 use kas::draw::TextClass;
 use kas::event::components::{TextInput, TextInputAction};
-use kas::event::{self, Command, ScrollDelta};
+use kas::event::{self, Command, MouseButton, PressSource, ScrollDelta};
 use kas::geom::Vec2;
 use kas::layout;
 use kas::prelude::*;
-use kas::text::SelectionHelper;
+use kas::text::{util, Annotation, AnnotationAction, Annotations, SelectionHelper};
+use log::warn;
 use std::fmt::Debug;
 use std::ops::Range;
+use std::rc::Rc;
 use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
 
 #[derive(Clone, Debug, PartialEq)]
@@ -23,6 +25,7 @@ enum LastEdit {
     Insert,
     Delete,
     Paste,
+    Transform,
 }
 
 impl Default for LastEdit {
@@ -31,6 +34,15 @@ impl Default for LastEdit {
     }
 }
 
+/// State saved before an edit, allowing a [`Validator`] to revert it
+struct Snapshot {
+    text: String,
+    edit_pos: usize,
+    sel_pos: usize,
+    old_state: Option<(String, usize, usize)>,
+    last_edit: LastEdit,
+}
+
 enum EditAction {
     None,
     Unhandled,
@@ -104,6 +116,21 @@ pub trait EditGuard: Debug + Sized + 'static {
     fn update(edit: &mut EditField<Self>) {
         let _ = edit;
     }
+
+    /// Annotation guard
+    ///
+    /// This function is called when the mouse cursor moves onto or off an
+    /// annotation (see [`EditField::set_annotations`]) or when an annotation
+    /// is clicked. Its return value is converted to [`Response::None`] or
+    /// [`Response::Msg`].
+    fn annotation(
+        edit: &mut EditField<Self>,
+        mgr: &mut Manager,
+        action: AnnotationAction,
+    ) -> Option<Self::Msg> {
+        let _ = (edit, mgr, action);
+        None
+    }
 }
 
 impl EditGuard for () {
@@ -166,6 +193,178 @@ impl<F: FnMut(&str) + 'static> EditGuard for EditUpdate<F> {
     }
 }
 
+/// A text transformer for an [`EditField`]
+///
+/// Transformers rewrite text as it is typed, for example to replace quotes
+/// with typographic quotes or to expand abbreviations. They are applied by
+/// [`EditField::with_transform`] after the user inserts a character (but not on
+/// paste or programmatic updates).
+///
+/// Each transformation is a discrete edit: undo reverts the transformation
+/// but not the preceding input.
+pub trait TextTransform: Debug {
+    /// Transform text after input
+    ///
+    /// Inputs are the full `text`, the edit position `pos` (a byte index,
+    /// immediately after the input) and the `inserted` text.
+    ///
+    /// To rewrite text, return the byte `range` to replace and its
+    /// replacement. The range must not extend past `pos` and must start and
+    /// end on grapheme cluster boundaries; otherwise it is ignored.
+    fn transform(&self, text: &str, pos: usize, inserted: &str) -> Option<(Range<usize>, String)>;
+}
+
+/// A [`TextTransform`] replacing straight quotes with typographic quotes
+///
+/// A quote is considered opening at the start of the text or after white
+/// space or an opening bracket; otherwise it is closing. A closing single
+/// quote is also used as an apostrophe.
+#[derive(Clone, Debug, Default)]
+pub struct SmartQuotes;
+
+impl TextTransform for SmartQuotes {
+    fn transform(&self, text: &str, pos: usize, inserted: &str) -> Option<(Range<usize>, String)> {
+        let (open, close) = match inserted {
+            "\"" => ('\u{201C}', '\u{201D}'),
+            "'" => ('\u{2018}', '\u{2019}'),
+            _ => return None,
+        };
+        let start = pos - inserted.len();
+        let opening = text[0..start]
+            .chars()
+            .next_back()
+            .map(|c| c.is_whitespace() || "([{".contains(c))
+            .unwrap_or(true);
+        let quote = if opening { open } else { close };
+        Some((start..pos, quote.to_string()))
+    }
+}
+
+/// A [`TextTransform`] expanding abbreviations
+///
+/// When a word matching an abbreviation is followed by white space or
+/// punctuation, the word is replaced by its expansion.
+#[derive(Clone, Debug, Default)]
+pub struct Snippets {
+    snippets: Vec<(String, String)>,
+}
+
+impl Snippets {
+    /// Construct with no abbreviations
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an abbreviation (inline)
+    #[inline]
+    pub fn with<S: ToString, T: ToString>(mut self, abbrev: S, expansion: T) -> Self {
+        self.insert(abbrev, expansion);
+        self
+    }
+
+    /// Add an abbreviation
+    ///
+    /// This replaces any existing expansion of `abbrev`.
+    pub fn insert<S: ToString, T: ToString>(&mut self, abbrev: S, expansion: T) {
+        let abbrev = abbrev.to_string();
+        let expansion = expansion.to_string();
+        if let Some(item) = self.snippets.iter_mut().find(|item| item.0 == abbrev) {
+            item.1 = expansion;
+        } else {
+            self.snippets.push((abbrev, expansion));
+        }
+    }
+}
+
+impl TextTransform for Snippets {
+    fn transform(&self, text: &str, pos: usize, inserted: &str) -> Option<(Range<usize>, String)> {
+        let c = inserted.chars().next_back()?;
+        if !(c.is_whitespace() || c.is_ascii_punctuation()) {
+            return None;
+        }
+        let end = pos - inserted.len();
+        let (start, word) = text[0..end].split_word_bound_indices().next_back()?;
+        (self.snippets.iter())
+            .find(|item| item.0 == word)
+            .map(|item| (start..end, item.1.clone()))
+    }
+}
+
+/// Settings for numeric drag-adjustment ("scrubbing")
+///
+/// See [`EditField::with_scrub`]. While the field does not have keyboard
+/// focus, click-dragging horizontally adjusts the value proportionally to the
+/// drag distance. Holding Shift while dragging gives fine steps (10% of
+/// `step`) and holding Ctrl gives coarse steps (10 times `step`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scrub {
+    /// Value change per logical pixel of drag distance
+    pub step: f64,
+    /// Inclusive range to which the value is clamped, if any
+    pub range: Option<(f64, f64)>,
+    /// Number of decimal places displayed
+    pub decimals: usize,
+}
+
+impl Scrub {
+    /// Scrub integer values, by `step` per logical pixel
+    #[inline]
+    pub fn integer(step: f64) -> Self {
+        Scrub {
+            step,
+            range: None,
+            decimals: 0,
+        }
+    }
+
+    /// Scrub floating-point values, by `step` per logical pixel
+    ///
+    /// Values are displayed with the given number of `decimals`.
+    #[inline]
+    pub fn float(step: f64, decimals: usize) -> Self {
+        Scrub {
+            step,
+            range: None,
+            decimals,
+        }
+    }
+
+    /// Clamp values to the inclusive range `min..=max`
+    #[inline]
+    #[must_use]
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    fn clamp(&self, value: f64) -> f64 {
+        match self.range {
+            Some((min, max)) => value.max(min).min(max),
+            None => value,
+        }
+    }
+
+    fn format(&self, value: f64) -> String {
+        let s = format!("{:.*}", self.decimals, value);
+        // Avoid displaying "-0"
+        match s.strip_prefix('-') {
+            Some(rest) if rest.chars().all(|c| c == '0' || c == '.') => rest.to_string(),
+            _ => s,
+        }
+    }
+}
+
+/// State of an in-progress scrub
+#[derive(Clone, Copy, Debug)]
+struct ScrubState {
+    source: PressSource,
+    start: Coord,
+    value: f64,
+    /// False until the drag threshold has been exceeded
+    active: bool,
+}
+
 widget! {
     /// A text-edit box
     ///
@@ -310,6 +509,42 @@ impl<G: EditGuard> EditBox<G> {
         self
     }
 
+    /// Set whether this `EditBox` holds sensitive content
+    ///
+    /// See [`EditField::sensitive`].
+    #[inline]
+    pub fn sensitive(mut self, sensitive: bool) -> Self {
+        self.inner = self.inner.sensitive(sensitive);
+        self
+    }
+
+    /// Set a text transformer (inline)
+    ///
+    /// See [`EditField::with_transform`].
+    #[inline]
+    pub fn with_transform<T: TextTransform + 'static>(mut self, transform: T) -> Self {
+        self.inner = self.inner.with_transform(transform);
+        self
+    }
+
+    /// Set an input validator (inline)
+    ///
+    /// See [`EditField::with_validator`].
+    #[inline]
+    pub fn with_validator<V: Validator + 'static>(mut self, validator: V) -> Self {
+        self.inner = self.inner.with_validator(validator);
+        self
+    }
+
+    /// Enable numeric drag-adjustment (inline)
+    ///
+    /// See [`EditField::with_scrub`].
+    #[inline]
+    pub fn with_scrub(mut self, scrub: Scrub) -> Self {
+        self.inner = self.inner.with_scrub(scrub);
+        self
+    }
+
     /// Get whether the widget currently has keyboard input focus
     #[inline]
     pub fn has_key_focus(&self) -> bool {
@@ -337,8 +572,9 @@ widget! {
     ///
     /// Usually one uses a derived type like [`EditBox`] instead. This field does
     /// not draw any background or borders, thus (1) there is no visual indication
-    /// that this is an edit field, and (2) there is no indication for disabled or
-    /// error states. The parent widget is responsible for this.
+    /// that this is an edit field, and (2) there is no indication for disabled
+    /// state and only a minimal indication of error state. The parent widget is
+    /// responsible for this.
     ///
     /// This widget is intended for use with short input strings. Internally it
     /// uses a [`String`], for which edits have `O(n)` cost.
@@ -350,7 +586,7 @@ widget! {
     #[widget{
         key_nav = true;
         hover_highlight = true;
-        cursor_icon = event::CursorIcon::Text;
+        cursor_icon = self.cursor();
     }]
     pub struct EditField<G: EditGuard = ()> {
         #[widget_core]
@@ -358,6 +594,7 @@ widget! {
         view_offset: Offset,
         editable: bool,
         multi_line: bool,
+        sensitive: bool,
         text: Text<String>,
         required: Vec2,
         selection: SelectionHelper,
@@ -367,6 +604,12 @@ widget! {
         has_key_focus: bool,
         error_state: bool,
         input_handler: TextInput,
+        transform: Option<Rc<dyn TextTransform>>,
+        validator: Option<Rc<dyn Validator>>,
+        annotations: Annotations,
+        hover_annotation: Option<usize>,
+        scrub: Option<Scrub>,
+        scrub_state: Option<ScrubState>,
         /// The associated [`EditGuard`] implementation
         pub guard: G,
     }
@@ -429,6 +672,10 @@ widget! {
                         state,
                     );
                 }
+                if !self.annotations.is_empty() {
+                    let annotations = self.annotations.as_slice();
+                    draw.text_annotations(self.rect().pos, self.text.as_ref(), annotations, state);
+                }
                 if mgr.has_char_focus(self.id()).0 {
                     draw.edit_marker(
                         self.rect().pos,
@@ -438,6 +685,9 @@ widget! {
                     );
                 }
             });
+            if self.error_state && !state.disabled() {
+                draw.invalid_marker(self.rect());
+            }
         }
     }
 
@@ -454,13 +704,19 @@ widget! {
                 return TkAction::empty();
             }
 
+            self.core.bump_generation();
             self.text.set_string(string);
             self.selection.clear();
+            self.annotations.clear();
+            self.hover_annotation = None;
             if kas::text::fonts::fonts().num_faces() > 0 {
                 if let Some(req) = self.text.prepare() {
                     self.required = req.into();
                 }
             }
+            if let Some(validator) = self.validator.as_ref() {
+                self.error_state = !validator.is_valid(self.text.text());
+            }
             let _ = G::update(self);
             TkAction::REDRAW
         }
@@ -493,34 +749,82 @@ widget! {
                 Event::LostCharFocus => {
                     self.has_key_focus = false;
                     mgr.redraw(self.id());
-                    G::focus_lost(self, mgr)
-                        .map(|msg| msg.into())
-                        .unwrap_or(Response::None)
+                    self.focus_lost(mgr)
                 }
                 Event::LostSelFocus => {
                     self.selection.set_empty();
                     mgr.redraw(self.id());
                     Response::None
                 }
+                Event::DisabledChanged(true) => {
+                    mgr.clear_sel_focus(self.id());
+                    self.selection.set_empty();
+                    mgr.redraw(self.id());
+                    if self.has_key_focus {
+                        self.has_key_focus = false;
+                        return self.focus_lost(mgr);
+                    }
+                    Response::None
+                }
                 Event::Command(cmd, shift) => {
                     // Note: we can receive a Command without char focus, but should
                     // ensure we have focus before acting on it.
                     request_focus(self, mgr);
                     if self.has_key_focus {
+                        let snapshot = self.snapshot();
                         match self.control_key(mgr, cmd, shift) {
                             EditAction::None => Response::None,
                             EditAction::Unhandled => Response::Unhandled,
-                            EditAction::Activate => Response::none_or_msg(G::activate(self, mgr)),
-                            EditAction::Edit => Response::update_or_msg(G::edit(self, mgr)),
+                            EditAction::Activate => self.activate(mgr),
+                            EditAction::Edit => self.edited(mgr, snapshot),
                         }
                     } else {
                         Response::Unhandled
                     }
                 }
-                Event::ReceivedCharacter(c) => match self.received_char(mgr, c) {
-                    false => Response::Unhandled,
-                    true => Response::update_or_msg(G::edit(self, mgr)),
-                },
+                Event::ReceivedCharacter(c) => {
+                    let snapshot = self.snapshot();
+                    match self.received_char(mgr, c) {
+                        false => Response::Unhandled,
+                        true => self.edited(mgr, snapshot),
+                    }
+                }
+                Event::PressStart { source, coord, .. }
+                    if self.scrub.is_some()
+                        && !self.has_key_focus
+                        && source == PressSource::Mouse(MouseButton::Left, 1) =>
+                {
+                    self.start_scrub(mgr, source, coord);
+                    Response::None
+                }
+                Event::PressMove { source, coord, delta, .. }
+                    if self.scrub_state.map(|s| s.source == source).unwrap_or(false) =>
+                {
+                    self.scrub_move(mgr, coord, delta)
+                }
+                Event::PressEnd { source, coord, .. }
+                    if self.scrub_state.map(|s| s.source == source).unwrap_or(false) =>
+                {
+                    let state = self.scrub_state.take().unwrap();
+                    if !state.active {
+                        // A click without drag: focus and place the cursor
+                        request_focus(self, mgr);
+                        if self.has_key_focus {
+                            self.set_edit_pos_from_coord(mgr, coord);
+                            self.selection.set_empty();
+                        }
+                    }
+                    Response::None
+                }
+                Event::CursorMove { coord } => {
+                    let index = self.annotation_at(coord);
+                    if index == self.hover_annotation {
+                        return Response::None;
+                    }
+                    self.hover_annotation = index;
+                    let action = AnnotationAction::Hover(index);
+                    Response::none_or_msg(G::annotation(self, mgr, action))
+                }
                 Event::Scroll(delta) => {
                     let delta2 = match delta {
                         ScrollDelta::LineDelta(x, y) => {
@@ -528,7 +832,7 @@ widget! {
                             let dist = 3.0 * self.text.env().height(Default::default());
                             Offset((x * dist).cast_nearest(), (y * dist).cast_nearest())
                         }
-                        ScrollDelta::PixelDelta(coord) => coord,
+                        ScrollDelta::PixelDelta(delta) => Offset::from(delta),
                     };
                     match self.pan_delta(mgr, delta2) {
                         delta if delta == Offset::ZERO => Response::None,
@@ -560,6 +864,12 @@ widget! {
                                 self.selection.expand(&self.text, repeats);
                             }
                         }
+                        if clear && repeats == 1 {
+                            if let Some(index) = self.annotation_at(coord) {
+                                let action = AnnotationAction::Click(index);
+                                return Response::none_or_msg(G::annotation(self, mgr, action));
+                            }
+                        }
                         Response::None
                     }
                 },
@@ -606,6 +916,7 @@ impl EditField<()> {
             view_offset: Default::default(),
             editable: true,
             multi_line: false,
+            sensitive: false,
             text: Text::new(Default::default(), text),
             required: Vec2::ZERO,
             selection: SelectionHelper::new(len, len),
@@ -615,6 +926,12 @@ impl EditField<()> {
             has_key_focus: false,
             error_state: false,
             input_handler: Default::default(),
+            transform: None,
+            validator: None,
+            annotations: Annotations::new(),
+            hover_annotation: None,
+            scrub: None,
+            scrub_state: None,
             guard: (),
         }
     }
@@ -633,6 +950,7 @@ impl EditField<()> {
             view_offset: self.view_offset,
             editable: self.editable,
             multi_line: self.multi_line,
+            sensitive: self.sensitive,
             text: self.text,
             required: self.required,
             selection: self.selection,
@@ -642,6 +960,12 @@ impl EditField<()> {
             has_key_focus: self.has_key_focus,
             error_state: self.error_state,
             input_handler: self.input_handler,
+            transform: self.transform,
+            validator: self.validator,
+            annotations: self.annotations,
+            hover_annotation: self.hover_annotation,
+            scrub: self.scrub,
+            scrub_state: self.scrub_state,
             guard,
         };
         let _ = G::update(&mut edit);
@@ -729,6 +1053,94 @@ impl<G: EditGuard> EditField<G> {
         self
     }
 
+    /// Set whether this `EditField` holds sensitive content
+    ///
+    /// Text cut or copied from a sensitive field is set via
+    /// [`Manager::set_clipboard_sensitive`], which may exclude it from
+    /// clipboard history.
+    #[inline]
+    pub fn sensitive(mut self, sensitive: bool) -> Self {
+        self.sensitive = sensitive;
+        self
+    }
+
+    /// Set a text transformer (inline)
+    ///
+    /// The `transform` is applied after each character input by the user,
+    /// for example to insert typographic quotes (see [`SmartQuotes`]) or to
+    /// expand abbreviations (see [`Snippets`]). This replaces any previous
+    /// transformer.
+    #[inline]
+    pub fn with_transform<T: TextTransform + 'static>(mut self, transform: T) -> Self {
+        self.set_transform(Some(Rc::new(transform)));
+        self
+    }
+
+    /// Set or clear the text transformer
+    ///
+    /// See [`Self::with_transform`].
+    #[inline]
+    pub fn set_transform(&mut self, transform: Option<Rc<dyn TextTransform>>) {
+        self.transform = transform;
+    }
+
+    /// Set an input validator (inline)
+    ///
+    /// The `validator` filters each edit (see [`Validator::filter`]) and
+    /// determines whether the contents are valid. While invalid, the error
+    /// state is set (see [`Self::has_error`]) and the [`EditGuard`] is not
+    /// notified of activation or loss of focus, thus only valid values are
+    /// emitted. This replaces any previous validator.
+    #[inline]
+    pub fn with_validator<V: Validator + 'static>(mut self, validator: V) -> Self {
+        self.set_validator(Some(Rc::new(validator)));
+        self
+    }
+
+    /// Set or clear the input validator
+    ///
+    /// See [`Self::with_validator`]. The error state is updated immediately.
+    pub fn set_validator(&mut self, validator: Option<Rc<dyn Validator>>) {
+        self.error_state = match validator.as_ref() {
+            Some(v) => !v.is_valid(self.text.text()),
+            None => false,
+        };
+        self.validator = validator;
+    }
+
+    /// Enable numeric drag-adjustment ("scrubbing")
+    ///
+    /// While the field does not have keyboard focus, click-dragging
+    /// horizontally adjusts its (numeric) value; see [`Scrub`]. Each change is
+    /// reported to the [`EditGuard`] as an edit. A click without dragging
+    /// focusses the field for text input as usual.
+    ///
+    /// This is usually combined with a [`Numeric`](crate::Numeric) validator.
+    #[inline]
+    pub fn with_scrub(mut self, scrub: Scrub) -> Self {
+        self.scrub = Some(scrub);
+        self
+    }
+
+    /// Set or clear drag-adjustment settings
+    ///
+    /// See [`Self::with_scrub`].
+    #[inline]
+    pub fn set_scrub(&mut self, scrub: Option<Scrub>) {
+        self.scrub = scrub;
+        self.scrub_state = None;
+    }
+
+    /// Get whether the contents are valid
+    ///
+    /// This is always true when no [`Validator`] is set.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        (self.validator.as_ref())
+            .map(|v| v.is_valid(self.text.text()))
+            .unwrap_or(true)
+    }
+
     /// Get whether the widget currently has keyboard input focus
     #[inline]
     pub fn has_key_focus(&self) -> bool {
@@ -749,6 +1161,212 @@ impl<G: EditGuard> EditField<G> {
         self.error_state = error_state;
     }
 
+    /// Get the text annotations
+    #[inline]
+    pub fn annotations(&self) -> &[Annotation] {
+        self.annotations.as_slice()
+    }
+
+    /// Set text annotations (inline)
+    ///
+    /// See [`Self::set_annotations`].
+    #[inline]
+    pub fn with_annotations(mut self, annotations: Vec<Annotation>) -> Self {
+        let _ = self.set_annotations(annotations);
+        self
+    }
+
+    /// Set text annotations
+    ///
+    /// Annotations mark byte ranges of the text, for example to underline
+    /// spelling mistakes or hyperlinks; the theme determines how each
+    /// [`kas::text::AnnotationClass`] is drawn. Empty and overlapping
+    /// annotations are discarded.
+    ///
+    /// When the user edits the text, annotations touching the edited range
+    /// are removed and those following are moved; the owner may update
+    /// annotations from [`EditGuard::edit`]. Setting the text via
+    /// [`HasString::set_string`] removes all annotations.
+    ///
+    /// Hovering over and clicking annotations is reported via
+    /// [`EditGuard::annotation`].
+    pub fn set_annotations(&mut self, annotations: Vec<Annotation>) -> TkAction {
+        self.annotations.set(annotations);
+        self.hover_annotation = None;
+        TkAction::REDRAW
+    }
+
+    /// Find the annotation under `coord`, if any
+    fn annotation_at(&self, coord: Coord) -> Option<usize> {
+        if self.annotations.is_empty() {
+            return None;
+        }
+        let rel_pos = (coord - self.rect().pos + self.view_offset).into();
+        let index = self.text.text_index_nearest(rel_pos);
+        self.annotations.find(index)
+    }
+
+    /// Move the edit cursor to the end of the text, clearing any selection
+    pub(crate) fn move_cursor_to_end(&mut self, mgr: &mut Manager) {
+        self.selection.set_pos(self.text.str_len());
+        self.edit_x_coord = None;
+        self.set_view_offset_from_edit_pos();
+        mgr.redraw(self.id());
+    }
+
+    fn activate(&mut self, mgr: &mut Manager) -> Response<G::Msg> {
+        match self.is_valid() {
+            true => Response::none_or_msg(G::activate(self, mgr)),
+            false => Response::None,
+        }
+    }
+
+    fn focus_lost(&mut self, mgr: &mut Manager) -> Response<G::Msg> {
+        match self.is_valid() {
+            true => Response::none_or_msg(G::focus_lost(self, mgr)),
+            false => Response::None,
+        }
+    }
+
+    fn edited(&mut self, mgr: &mut Manager, snapshot: Option<Snapshot>) -> Response<G::Msg> {
+        let accepted = self.validate_edit(mgr, snapshot.as_ref());
+        if let Some(snapshot) = snapshot {
+            self.annotations
+                .apply_edit(&snapshot.text, self.text.text());
+        }
+        match accepted {
+            true => Response::update_or_msg(G::edit(self, mgr)),
+            false => Response::None,
+        }
+    }
+
+    fn cursor(&self) -> event::CursorIcon {
+        if self.scrub.is_some() && !self.has_key_focus {
+            event::CursorIcon::EwResize
+        } else {
+            event::CursorIcon::Text
+        }
+    }
+
+    fn start_scrub(&mut self, mgr: &mut Manager, source: PressSource, coord: Coord) {
+        let scrub = self.scrub.unwrap();
+        let value = self.text.text().trim().parse::<f64>().unwrap_or(0.0);
+        let cursor = Some(event::CursorIcon::EwResize);
+        if mgr.request_grab(self.id(), source, coord, event::GrabMode::Grab, cursor) {
+            self.scrub_state = Some(ScrubState {
+                source,
+                start: coord,
+                value: scrub.clamp(value),
+                active: false,
+            });
+        }
+    }
+
+    fn scrub_move(&mut self, mgr: &mut Manager, coord: Coord, delta: Offset) -> Response<G::Msg> {
+        let scrub = self.scrub.unwrap();
+        let state = self.scrub_state.as_mut().unwrap();
+        if !state.active {
+            if !mgr.config_test_pan_thresh(coord - state.start) {
+                return Response::None;
+            }
+            state.active = true;
+        }
+
+        let modifiers = mgr.modifiers();
+        let factor = if modifiers.shift() {
+            0.1
+        } else if modifiers.ctrl() {
+            10.0
+        } else {
+            1.0
+        };
+        let dist = f64::from(delta.0) / f64::from(mgr.scale_factor());
+        state.value = scrub.clamp(state.value + dist * scrub.step * factor);
+
+        let text = scrub.format(state.value);
+        if *self.text.text() == text {
+            return Response::None;
+        }
+        let snapshot = self.snapshot();
+        self.text.set_string(text);
+        self.selection.clear();
+        self.last_edit = LastEdit::None;
+        if let Some(req) = self.text.prepare() {
+            self.required = req.into();
+        }
+        mgr.redraw(self.id());
+        self.edited(mgr, snapshot)
+    }
+
+    /// Save state before an edit
+    ///
+    /// This is only needed when a validator or annotations are present.
+    fn snapshot(&self) -> Option<Snapshot> {
+        if self.validator.is_none() && self.annotations.is_empty() {
+            return None;
+        }
+        Some(Snapshot {
+            text: self.text.clone_string(),
+            edit_pos: self.selection.edit_pos(),
+            sel_pos: self.selection.sel_pos(),
+            old_state: self.old_state.clone(),
+            last_edit: self.last_edit.clone(),
+        })
+    }
+
+    /// Apply the validator, if any, after an edit
+    ///
+    /// Returns false if the edit was rejected (and reverted).
+    fn validate_edit(&mut self, mgr: &mut Manager, snapshot: Option<&Snapshot>) -> bool {
+        let validator = match self.validator.clone() {
+            Some(validator) => validator,
+            None => return true,
+        };
+
+        let pos = self.selection.edit_pos();
+        let accepted = match validator.filter(self.text.text(), pos) {
+            Validation::Accept => true,
+            Validation::Reject => {
+                if let Some(snapshot) = snapshot {
+                    self.text.set_string(snapshot.text.clone());
+                    self.selection.set_edit_pos(snapshot.edit_pos);
+                    self.selection.set_sel_pos(snapshot.sel_pos);
+                    self.old_state = snapshot.old_state.clone();
+                    self.last_edit = snapshot.last_edit.clone();
+                }
+                false
+            }
+            Validation::Replace(text, mut pos) => {
+                if !text.is_char_boundary(pos) {
+                    warn!("EditField: validator returned invalid position {}", pos);
+                    pos = text.len();
+                }
+                self.text.set_string(text);
+                self.selection.set_pos(pos);
+                true
+            }
+        };
+
+        self.error_state = !validator.is_valid(self.text.text());
+        if !self.text.required_action().is_ready() {
+            if let Some(req) = self.text.prepare() {
+                self.required = req.into();
+            }
+            self.set_view_offset_from_edit_pos();
+        }
+        mgr.redraw(self.id());
+        accepted
+    }
+
+    fn copy_to_clipboard(&self, mgr: &mut Manager, range: Range<usize>) {
+        let content = self.text.text()[range].to_string();
+        if self.sensitive {
+            mgr.set_clipboard_sensitive(content);
+        } else {
+            mgr.set_clipboard(content);
+        }
+    }
+
     // returns true on success, false on unhandled event
     fn received_char(&mut self, mgr: &mut Manager, c: char) -> bool {
         if !self.editable {
@@ -771,6 +1389,8 @@ impl<G: EditGuard> EditField<G> {
             let _ = self.text.insert_char(pos, c);
             self.selection.set_pos(pos + c.len_utf8());
         }
+        let mut buf = [0u8; 4];
+        self.apply_transform(c.encode_utf8(&mut buf));
         self.edit_x_coord = None;
         if let Some(req) = self.text.prepare() {
             self.required = req.into();
@@ -787,6 +1407,11 @@ impl<G: EditGuard> EditField<G> {
 
         let mut buf = [0u8; 4];
         let pos = self.selection.edit_pos();
+        // Left/Right move visually, thus are swapped within right-to-left text
+        let key = match util::line_is_rtl(&self.text, pos) {
+            true => key.mirrored(),
+            false => key,
+        };
         let selection = self.selection.range();
         let have_sel = selection.end > selection.start;
         let string;
@@ -967,14 +1592,15 @@ impl<G: EditGuard> EditField<G> {
                 Action::Move(self.text.str_len(), None)
             }
             Command::Cut if have_sel => {
-                mgr.set_clipboard((self.text.text()[selection.clone()]).into());
+                self.copy_to_clipboard(mgr, selection.clone());
                 Action::Delete(selection.clone())
             }
             Command::Copy if have_sel => {
-                mgr.set_clipboard((self.text.text()[selection.clone()]).into());
+                self.copy_to_clipboard(mgr, selection.clone());
                 Action::None
             }
-            Command::Paste => {
+            // We only support plain text, so both paste commands are the same
+            Command::Paste | Command::PasteAsPlainText => {
                 if let Some(content) = mgr.get_clipboard() {
                     let mut end = content.len();
                     if !self.multi_line {
@@ -1018,6 +1644,7 @@ impl<G: EditGuard> EditField<G> {
             Action::Activate => EditAction::Activate,
             Action::Edit => EditAction::Edit,
             Action::Insert(s, edit) => {
+                let transform = edit == LastEdit::Insert;
                 let mut pos = pos;
                 if have_sel {
                     self.old_state =
@@ -1036,6 +1663,9 @@ impl<G: EditGuard> EditField<G> {
                     self.text.replace_range(pos..pos, s);
                 }
                 self.selection.set_pos(pos + s.len());
+                if transform {
+                    self.apply_transform(s);
+                }
                 self.edit_x_coord = None;
                 EditAction::Edit
             }
@@ -1077,6 +1707,46 @@ impl<G: EditGuard> EditField<G> {
         result
     }
 
+    /// Apply the text transformer, if any, after insertion of `inserted`
+    fn apply_transform(&mut self, inserted: &str) {
+        let transform = match self.transform.as_ref() {
+            Some(transform) => transform.clone(),
+            None => return,
+        };
+        let pos = self.selection.edit_pos();
+        let text = self.text.text();
+        let (range, replacement) = match transform.transform(text, pos, inserted) {
+            Some(result) => result,
+            None => return,
+        };
+
+        let is_boundary = |index| {
+            let mut cursor = GraphemeCursor::new(index, text.len(), true);
+            cursor.is_boundary(text, 0).unwrap_or(false)
+        };
+        if range.start > range.end
+            || range.end > pos
+            || !is_boundary(range.start)
+            || !is_boundary(range.end)
+        {
+            warn!(
+                "EditField: ignoring transform with invalid range {:?}",
+                range
+            );
+            return;
+        }
+        if text[range.clone()] == replacement {
+            return;
+        }
+
+        // The transformation is a discrete step in the undo history
+        self.old_state = Some((self.text.clone_string(), pos, self.selection.sel_pos()));
+        self.last_edit = LastEdit::Transform;
+        self.text.replace_range(range.clone(), &replacement);
+        self.selection
+            .set_pos(pos - range.len() + replacement.len());
+    }
+
     fn set_edit_pos_from_coord(&mut self, mgr: &mut Manager, coord: Coord) {
         let rel_pos = (coord - self.rect().pos + self.view_offset).into();
         self.selection
@@ -0,0 +1,210 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! A wrapping row of widgets
+
+use kas::{event, layout, prelude::*};
+use std::ops::{Index, IndexMut};
+
+/// A flow box of boxed widgets
+///
+/// This is parameterised over the handler message type.
+///
+/// See documentation of [`FlowBox`] type.
+pub type BoxFlowBox<M> = FlowBox<Box<dyn Widget<Msg = M>>>;
+
+widget! {
+    /// A wrapping row of widgets
+    ///
+    /// Children are placed left-to-right, starting a new line whenever the
+    /// available width is exhausted. Each line is as tall as its tallest
+    /// child. The horizontal alignment hint applies to each line: `Stretch`
+    /// distributes excess width over children, while other values position
+    /// the line's contents within the available width.
+    ///
+    /// Since the height depends on the width, a `FlowBox` usually works best
+    /// within a column or a vertically-scrollable region.
+    ///
+    /// The `FlowBox` widget forwards messages from children: `M = <W as Handler>::Msg`.
+    ///
+    /// ## Performance
+    ///
+    /// Most operations are `O(n)` in the number of children.
+    #[autoimpl(Default)]
+    #[derive(Clone, Debug)]
+    #[handler(msg=<W as Handler>::Msg)]
+    pub struct FlowBox<W: Widget> {
+        first_id: WidgetId,
+        #[widget_core]
+        core: CoreData,
+        widgets: Vec<W>,
+        data: layout::FlowStorage,
+    }
+
+    impl WidgetChildren for Self {
+        #[inline]
+        fn first_id(&self) -> WidgetId {
+            self.first_id
+        }
+        fn record_first_id(&mut self, id: WidgetId) {
+            self.first_id = id;
+        }
+        #[inline]
+        fn num_children(&self) -> usize {
+            self.widgets.len()
+        }
+        #[inline]
+        fn get_child(&self, index: usize) -> Option<&dyn WidgetConfig> {
+            self.widgets.get(index).map(|w| w.as_widget())
+        }
+        #[inline]
+        fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+            self.widgets.get_mut(index).map(|w| w.as_widget_mut())
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> layout::Layout<'_> {
+            make_layout!(self.core; flow: self.widgets)
+        }
+    }
+
+    impl event::SendEvent for Self {
+        fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            if !self.is_disabled() {
+                let self_id = self.id();
+                if let Some(r) =
+                    mgr.dispatch_to_child(self_id, &mut self.widgets, id, event, |_, _, msg| {
+                        Response::Msg(msg)
+                    })
+                {
+                    return r;
+                }
+            }
+
+            Response::Unhandled
+        }
+    }
+}
+
+impl<W: Widget> FlowBox<W> {
+    /// Construct a new instance
+    #[inline]
+    pub fn new(widgets: Vec<W>) -> Self {
+        FlowBox {
+            widgets,
+            ..Default::default()
+        }
+    }
+
+    /// Number of lines, as of the last resize
+    #[inline]
+    pub fn num_lines(&self) -> usize {
+        self.data.num_lines()
+    }
+
+    /// True if there are no child widgets
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Returns the number of child widgets
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    /// Remove all child widgets
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action) if any widget is
+    /// removed.
+    pub fn clear(&mut self) -> TkAction {
+        let action = match self.widgets.is_empty() {
+            true => TkAction::empty(),
+            false => TkAction::RECONFIGURE,
+        };
+        self.widgets.clear();
+        action
+    }
+
+    /// Append a child widget
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action).
+    pub fn push(&mut self, widget: W) -> TkAction {
+        self.widgets.push(widget);
+        TkAction::RECONFIGURE
+    }
+
+    /// Remove the last child widget
+    ///
+    /// Returns `None` if there are no children. Otherwise, this
+    /// triggers a reconfigure before the next draw operation.
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action) if any widget is
+    /// removed.
+    pub fn pop(&mut self) -> (Option<W>, TkAction) {
+        let action = match self.widgets.is_empty() {
+            true => TkAction::empty(),
+            false => TkAction::RECONFIGURE,
+        };
+        (self.widgets.pop(), action)
+    }
+
+    /// Inserts a child widget position `index`
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action).
+    pub fn insert(&mut self, index: usize, widget: W) -> TkAction {
+        self.widgets.insert(index, widget);
+        TkAction::RECONFIGURE
+    }
+
+    /// Removes the child widget at position `index`
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action).
+    pub fn remove(&mut self, index: usize) -> (W, TkAction) {
+        let r = self.widgets.remove(index);
+        (r, TkAction::RECONFIGURE)
+    }
+
+    /// Append child widgets from an iterator
+    ///
+    /// Triggers a [reconfigure action](Manager::send_action) if any widgets
+    /// are added.
+    pub fn extend<T: IntoIterator<Item = W>>(&mut self, iter: T) -> TkAction {
+        let len = self.widgets.len();
+        self.widgets.extend(iter);
+        match len == self.widgets.len() {
+            true => TkAction::empty(),
+            false => TkAction::RECONFIGURE,
+        }
+    }
+
+    /// Iterate over childern
+    pub fn iter(&self) -> impl Iterator<Item = &W> {
+        self.widgets.iter()
+    }
+
+    /// Mutably iterate over childern
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut W> {
+        self.widgets.iter_mut()
+    }
+}
+
+impl<W: Widget> Index<usize> for FlowBox<W> {
+    type Output = W;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.widgets[index]
+    }
+}
+
+impl<W: Widget> IndexMut<usize> for FlowBox<W> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.widgets[index]
+    }
+}
@@ -29,9 +29,9 @@ widget! {
     /// Note that all child widgets are stored in a list internally. The order of
     /// widgets in that list does not affect display position, but does have a few
     /// effects: (a) widgets may be accessed in this order via indexing, (b) widgets
-    /// are configured and drawn in this order, (c) navigating
-    /// through widgets with the Tab key currently uses the list order (though it
-    /// may be changed in the future to use display order).
+    /// are configured and drawn in this order, (c) where multiple widgets occupy
+    /// the same cell, navigation with the Tab key visits these in list order.
+    /// Otherwise, navigation follows display order: by row, then by column.
     ///
     /// There is no protection against multiple widgets occupying the same cell.
     /// If this does happen, the last widget in that cell will appear on top, but
@@ -91,22 +91,20 @@ widget! {
     impl event::SendEvent for Self {
         fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
             if !self.is_disabled() {
-                for child in self.widgets.iter_mut() {
-                    if id <= child.1.id() {
-                        let r = child.1.send(mgr, id, event);
-                        return match Response::try_from(r) {
-                            Ok(r) => r,
-                            Err(msg) => {
-                                log::trace!(
-                                    "Received by {} from {}: {:?}",
-                                    self.id(),
-                                    id,
-                                    kas::util::TryFormat(&msg)
-                                );
-                                Response::Msg(msg)
-                            }
-                        };
-                    }
+                if let Some(index) = self.find_child(id) {
+                    let r = self.widgets[index].1.send(mgr, id, event);
+                    return match Response::try_from(r) {
+                        Ok(r) => r,
+                        Err(msg) => {
+                            log::trace!(
+                                "Received by {} from {}: {:?}",
+                                self.id(),
+                                id,
+                                kas::util::TryFormat(&msg)
+                            );
+                            Response::Msg(msg)
+                        }
+                    };
                 }
             }
 
@@ -210,16 +208,14 @@ impl<'a, W: Widget> GridBuilder<'a, W> {
 
     /// Add a child widget
     ///
-    /// The child is added to the end of the "list", thus appears last in
-    /// navigation order.
+    /// The child is added to the end of the "list".
     pub fn push(&mut self, info: GridChildInfo, widget: W) {
         self.0.push((info, widget));
     }
 
     /// Add a child widget to the given cell
     ///
-    /// The child is added to the end of the "list", thus appears last in
-    /// navigation order.
+    /// The child is added to the end of the "list".
     pub fn push_cell(&mut self, row: u32, col: u32, widget: W) {
         let info = GridChildInfo::new(row, col);
         self.push(info, widget);
@@ -227,8 +223,7 @@ impl<'a, W: Widget> GridBuilder<'a, W> {
 
     /// Add a child widget to the given cell, builder style
     ///
-    /// The child is added to the end of the "list", thus appears last in
-    /// navigation order.
+    /// The child is added to the end of the "list".
     pub fn with_cell(self, row: u32, col: u32, widget: W) -> Self {
         self.with_cell_span(row, col, 1, 1, widget)
     }
@@ -238,8 +233,7 @@ impl<'a, W: Widget> GridBuilder<'a, W> {
     /// Parameters `row_span` and `col_span` are the number of rows/columns
     /// spanned and should each be at least 1.
     ///
-    /// The child is added to the end of the "list", thus appears last in
-    /// navigation order.
+    /// The child is added to the end of the "list".
     pub fn push_cell_span(&mut self, row: u32, col: u32, row_span: u32, col_span: u32, widget: W) {
         let info = GridChildInfo {
             row,
@@ -255,8 +249,7 @@ impl<'a, W: Widget> GridBuilder<'a, W> {
     /// Parameters `row_span` and `col_span` are the number of rows/columns
     /// spanned and should each be at least 1.
     ///
-    /// The child is added to the end of the "list", thus appears last in
-    /// navigation order.
+    /// The child is added to the end of the "list".
     pub fn with_cell_span(
         mut self,
         row: u32,
@@ -0,0 +1,407 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Hyperlinks
+
+use kas::draw::TextClass;
+use kas::event::{self, Command, CursorIcon, GrabMode, PressSource};
+use kas::geom::Vec2;
+use kas::prelude::*;
+use kas::text::format::FormattableText;
+use kas::text::{Annotation, AnnotationClass};
+use log::warn;
+use std::ops::Range;
+use std::rc::Rc;
+
+type OnOpen<M> = Rc<dyn Fn(&mut Manager, &str) -> Option<M>>;
+
+/// Open `url` via `on_open` if set, otherwise via the system
+fn open_url<M>(mgr: &mut Manager, url: &str, on_open: Option<&OnOpen<M>>) -> Response<M> {
+    if let Some(f) = on_open {
+        return Response::none_or_msg(f(mgr, url));
+    }
+    if !mgr.open_url(url) {
+        warn!("Hyperlink: failed to open URL: {}", url);
+    }
+    Response::None
+}
+
+widget! {
+    /// A hyperlink
+    ///
+    /// The label is drawn with the theme's link styling. The mouse cursor
+    /// changes to a hand on hover. The link may be activated by clicking or,
+    /// when it has navigation focus, via the keyboard.
+    ///
+    /// By default, activation opens the URL with the system's default handler
+    /// (usually a web browser; see [`Manager::open_url`]). Alternatively, a
+    /// handler may be set with [`Hyperlink::on_open`] or the URL emitted as a
+    /// message via [`Hyperlink::emit_url`].
+    ///
+    /// See also [`LinkLabel`] for links within a larger text.
+    #[autoimpl(Debug skip on_open)]
+    #[derive(Clone)]
+    pub struct Hyperlink<M: 'static = VoidMsg> {
+        #[widget_core]
+        core: CoreData,
+        label: Text<String>,
+        url: String,
+        annotation: [Annotation; 1],
+        on_open: Option<OnOpen<M>>,
+    }
+
+    impl Hyperlink<VoidMsg> {
+        /// Construct with the given `label` and `url`
+        #[inline]
+        pub fn new<S: ToString, U: ToString>(label: S, url: U) -> Self {
+            let label = label.to_string();
+            let annotation = Annotation::new(0..label.len(), AnnotationClass::Hyperlink);
+            Hyperlink {
+                core: Default::default(),
+                label: Text::new_multi(label),
+                url: url.to_string(),
+                annotation: [annotation],
+                on_open: None,
+            }
+        }
+
+        /// Set a handler, called on activation
+        ///
+        /// On activation the closure `f` is called with the URL (instead of
+        /// opening the URL). Its result is converted to [`Response::Msg`] or
+        /// [`Response::None`] and returned to the parent.
+        #[inline]
+        pub fn on_open<M, F>(self, f: F) -> Hyperlink<M>
+        where
+            F: Fn(&mut Manager, &str) -> Option<M> + 'static,
+        {
+            Hyperlink {
+                core: self.core,
+                label: self.label,
+                url: self.url,
+                annotation: self.annotation,
+                on_open: Some(Rc::new(f)),
+            }
+        }
+
+        /// Emit the URL as a message on activation
+        ///
+        /// This is a parametrisation of [`Hyperlink::on_open`].
+        #[inline]
+        pub fn emit_url(self) -> Hyperlink<String> {
+            self.on_open(|_, url| Some(url.to_string()))
+        }
+    }
+
+    impl Self {
+        /// Get the URL
+        #[inline]
+        pub fn url(&self) -> &str {
+            &self.url
+        }
+
+        /// Set the URL
+        #[inline]
+        pub fn set_url<U: ToString>(&mut self, url: U) {
+            self.url = url.to_string();
+        }
+    }
+
+    impl WidgetConfig for Self {
+        fn key_nav(&self) -> bool {
+            true
+        }
+        fn hover_highlight(&self) -> bool {
+            true
+        }
+        fn cursor_icon(&self) -> CursorIcon {
+            CursorIcon::Hand
+        }
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+            size_handle.text_bound(&mut self.label, TextClass::Label, axis)
+        }
+
+        fn set_rect(&mut self, _: &mut Manager, rect: Rect, align: AlignHints) {
+            self.core.rect = rect;
+            self.label.update_env(|env| {
+                env.set_bounds(rect.size.into());
+                env.set_align(align.unwrap_or(Align::Default, Align::Center));
+            });
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let state = self.input_state(mgr, disabled);
+            let pos = self.core.rect.pos;
+            draw.text_annotated(pos, &self.label, &self.annotation, TextClass::Label, state);
+            draw.nav_frame(self.core.rect, state);
+        }
+    }
+
+    impl event::Handler for Self {
+        type Msg = M;
+
+        #[inline]
+        fn activation_via_press(&self) -> bool {
+            true
+        }
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+            match event {
+                Event::Activate => open_url(mgr, &self.url, self.on_open.as_ref()),
+                _ => Response::Unhandled,
+            }
+        }
+    }
+
+    impl HasStr for Self {
+        fn get_str(&self) -> &str {
+            self.label.as_str()
+        }
+    }
+
+    impl HasString for Self {
+        fn set_string(&mut self, string: String) -> TkAction {
+            self.core.bump_generation();
+            self.annotation[0].range = 0..string.len();
+            kas::text::util::set_string_and_prepare(&mut self.label, string, self.core.rect.size)
+        }
+    }
+}
+
+widget! {
+    /// A text label containing hyperlinks
+    ///
+    /// This is a label (optionally using formatted text; see
+    /// [`kas::text::format`]) in which some byte ranges are links
+    /// (see [`LinkLabel::with_link`]). Links are drawn with the theme's link
+    /// styling and the mouse cursor changes to a hand while over a link.
+    ///
+    /// A link is activated by clicking it or, when the label has navigation
+    /// focus, via the keyboard: <kbd>Left</kbd> and <kbd>Right</kbd> select a
+    /// link, which is then activated by <kbd>Enter</kbd> or <kbd>Space</kbd>.
+    /// As with [`Hyperlink`], activation opens the URL unless a handler is set
+    /// via [`LinkLabel::on_open`].
+    #[autoimpl(Debug skip on_open)]
+    #[derive(Clone)]
+    pub struct LinkLabel<T: FormattableText + 'static, M: 'static = VoidMsg> {
+        #[widget_core]
+        core: CoreData,
+        label: Text<T>,
+        links: Vec<Annotation>,
+        urls: Vec<String>,
+        focus: usize,
+        hover: Option<usize>,
+        press: Option<(PressSource, usize)>,
+        on_open: Option<OnOpen<M>>,
+    }
+
+    impl<T: FormattableText + 'static> LinkLabel<T, VoidMsg> {
+        /// Construct from `label`, without links
+        #[inline]
+        pub fn new(label: T) -> Self {
+            LinkLabel {
+                core: Default::default(),
+                label: Text::new_multi(label),
+                links: vec![],
+                urls: vec![],
+                focus: 0,
+                hover: None,
+                press: None,
+                on_open: None,
+            }
+        }
+
+        /// Set a handler, called on activation of a link
+        ///
+        /// On activation the closure `f` is called with the link's URL
+        /// (instead of opening the URL). Its result is converted to
+        /// [`Response::Msg`] or [`Response::None`] and returned to the parent.
+        #[inline]
+        pub fn on_open<M, F>(self, f: F) -> LinkLabel<T, M>
+        where
+            F: Fn(&mut Manager, &str) -> Option<M> + 'static,
+        {
+            LinkLabel {
+                core: self.core,
+                label: self.label,
+                links: self.links,
+                urls: self.urls,
+                focus: self.focus,
+                hover: self.hover,
+                press: self.press,
+                on_open: Some(Rc::new(f)),
+            }
+        }
+
+        /// Emit the URL as a message on activation of a link
+        ///
+        /// This is a parametrisation of [`LinkLabel::on_open`].
+        #[inline]
+        pub fn emit_url(self) -> LinkLabel<T, String> {
+            self.on_open(|_, url| Some(url.to_string()))
+        }
+    }
+
+    impl Self {
+        /// Add a link over the given byte `range` of the text (inline)
+        ///
+        /// The `range` refers to the displayed text (i.e. excluding any
+        /// formatting mark-up). Empty ranges and ranges overlapping an existing
+        /// link are ignored.
+        pub fn with_link<U: ToString>(mut self, range: Range<usize>, url: U) -> Self {
+            let i = self.links.partition_point(|link| link.range.end <= range.start);
+            let overlaps = self.links.get(i).map(|link| link.range.start < range.end);
+            if range.is_empty() || overlaps.unwrap_or(false) {
+                warn!("LinkLabel::with_link: ignoring empty or overlapping range {:?}", range);
+                return self;
+            }
+            self.links.insert(i, Annotation::new(range, AnnotationClass::Hyperlink));
+            self.urls.insert(i, url.to_string());
+            self
+        }
+
+        /// Get the number of links
+        #[inline]
+        pub fn num_links(&self) -> usize {
+            self.urls.len()
+        }
+
+        /// Get the byte range and URL of link `index`
+        pub fn link(&self, index: usize) -> Option<(Range<usize>, &str)> {
+            let range = self.links.get(index)?.range.clone();
+            Some((range, &self.urls[index]))
+        }
+
+        /// Find the link under `coord`, if any
+        fn link_at(&self, coord: Coord) -> Option<usize> {
+            let rel_pos = (coord - self.core.rect.pos).into();
+            let index = self.label.text_index_nearest(rel_pos);
+            let i = self.links.partition_point(|link| link.range.end <= index);
+            self.links.get(i).filter(|link| link.range.start <= index).map(|_| i)
+        }
+
+        /// Get the rect of the first line of link `index`
+        fn link_rect(&self, index: usize) -> Option<Rect> {
+            let range = self.links.get(index)?.range.clone();
+            let (p1, p2) = *self.label.as_ref().highlight_lines(range).first()?;
+            let pos = self.core.rect.pos;
+            let a = pos + Offset::from(Vec2::from(p1));
+            let b = pos + Offset::from(Vec2::from(p2));
+            Some(Rect::new(a, Size::from(b - a)))
+        }
+
+        fn set_hover(&mut self, mgr: &mut Manager, hover: Option<usize>) {
+            if hover != self.hover {
+                self.hover = hover;
+                mgr.update_hover_cursor(self.id(), self.cursor_icon());
+            }
+        }
+    }
+
+    impl WidgetConfig for Self {
+        fn key_nav(&self) -> bool {
+            !self.urls.is_empty()
+        }
+        fn cursor_icon(&self) -> CursorIcon {
+            match self.hover {
+                Some(_) => CursorIcon::Hand,
+                None => CursorIcon::Default,
+            }
+        }
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+            size_handle.text_bound(&mut self.label, TextClass::Label, axis)
+        }
+
+        fn set_rect(&mut self, _: &mut Manager, rect: Rect, align: AlignHints) {
+            self.core.rect = rect;
+            self.label.update_env(|env| {
+                env.set_bounds(rect.size.into());
+                env.set_align(align.unwrap_or(Align::Default, Align::Center));
+            });
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let state = self.input_state(mgr, disabled);
+            let pos = self.core.rect.pos;
+            draw.text_annotated(pos, &self.label, &self.links, TextClass::Label, state);
+            if let Some(rect) = self.link_rect(self.focus) {
+                draw.nav_frame(rect, state);
+            }
+        }
+    }
+
+    impl event::Handler for Self {
+        type Msg = M;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+            match event {
+                Event::Activate => match self.urls.get(self.focus) {
+                    Some(url) => open_url(mgr, url, self.on_open.as_ref()),
+                    None => Response::Unhandled,
+                },
+                Event::Command(cmd, _) => {
+                    let focus = match cmd {
+                        Command::Left => self.focus.checked_sub(1),
+                        Command::Right => Some(self.focus + 1).filter(|i| *i < self.urls.len()),
+                        _ => None,
+                    };
+                    match focus {
+                        Some(focus) => {
+                            self.focus = focus;
+                            mgr.redraw(self.id());
+                            Response::None
+                        }
+                        None => Response::Unhandled,
+                    }
+                }
+                Event::CursorMove { coord } => {
+                    let hover = self.link_at(coord);
+                    self.set_hover(mgr, hover);
+                    Response::None
+                }
+                Event::PressStart { source, coord, .. } if source.is_primary() => {
+                    match self.link_at(coord) {
+                        Some(index) => {
+                            if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None) {
+                                self.press = Some((source, index));
+                            }
+                            Response::None
+                        }
+                        None => Response::Unhandled,
+                    }
+                }
+                Event::PressEnd { source, end_id, coord } => {
+                    match self.press.take() {
+                        Some((s, index)) if s == source => {
+                            if end_id == Some(self.id()) && self.link_at(coord) == Some(index) {
+                                self.focus = index;
+                                let url = &self.urls[index];
+                                return open_url(mgr, url, self.on_open.as_ref());
+                            }
+                            Response::None
+                        }
+                        press => {
+                            self.press = press;
+                            Response::Unhandled
+                        }
+                    }
+                }
+                _ => Response::Unhandled,
+            }
+        }
+    }
+
+    impl HasStr for Self {
+        fn get_str(&self) -> &str {
+            self.label.as_str()
+        }
+    }
+}
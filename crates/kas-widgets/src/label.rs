@@ -5,8 +5,12 @@
 
 //! Text widgets
 
+use crate::scroll_label::read_only_command;
 use kas::draw::TextClass;
+use kas::event::components::{TextInput, TextInputAction};
 use kas::text::format::{EditableText, FormattableText};
+use kas::text::util::{set_text_elided, Elide};
+use kas::text::SelectionHelper;
 use kas::{event, prelude::*};
 
 widget! {
@@ -14,11 +18,21 @@ widget! {
     ///
     /// This type is generic over the text type. Some aliases are available:
     /// [`StrLabel`], [`StringLabel`], [`AccelLabel`].
+    ///
+    /// Text may optionally be selected and copied; see [`Label::selectable`].
+    ///
+    /// Localized labels may be constructed from a message key; see
+    /// [`StringLabel::msg`] and [`AccelLabel::msg`].
     #[derive(Clone, Default, Debug)]
     pub struct Label<T: FormattableText + 'static> {
         #[widget_core]
         core: CoreData,
         label: Text<T>,
+        msg: Option<(String, fn(String) -> T)>,
+        truncated: bool,
+        selectable: bool,
+        selection: SelectionHelper,
+        input_handler: TextInput,
     }
 
     impl Layout for Self {
@@ -28,22 +42,99 @@ widget! {
         }
 
         fn set_rect(&mut self, _: &mut Manager, rect: Rect, align: AlignHints) {
+            let marker = self.label.text_glyph_pos(0).next();
+            let baseline = marker.map(|m| i32::conv_ceil(m.ascent));
+            let (rect, align) = align.baseline_rect(rect, baseline);
             self.core.rect = rect;
-            self.label.update_env(|env| {
+            let req = self.label.update_env(|env| {
                 env.set_bounds(rect.size.into());
                 env.set_align(align.unwrap_or(Align::Default, Align::Center));
             });
+            self.truncated = kas::text::util::is_truncated(req, rect.size);
         }
 
         #[cfg(feature = "min_spec")]
         default fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
             let state = self.input_state(mgr, disabled);
-            draw.text_effects(self.core.rect.pos, &self.label, TextClass::Label, state);
+            if !self.draw_selected(draw, state) {
+                draw.text_effects(self.core.rect.pos, &self.label, TextClass::Label, state);
+            }
         }
         #[cfg(not(feature = "min_spec"))]
         fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
             let state = self.input_state(mgr, disabled);
-            draw.text_effects(self.core.rect.pos, &self.label, TextClass::Label, state);
+            if !self.draw_selected(draw, state) {
+                draw.text_effects(self.core.rect.pos, &self.label, TextClass::Label, state);
+            }
+        }
+    }
+
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            if let Some((key, make)) = self.msg.as_ref() {
+                let text = make(mgr.translate(key));
+                let size = self.core.rect.size;
+                *mgr |= kas::text::util::set_text_and_prepare(&mut self.label, text, size);
+            }
+        }
+
+        fn tooltip(&self) -> Option<String> {
+            // Show the full text when truncated
+            self.truncated.then(|| self.label.as_str().to_string())
+        }
+
+        fn cursor_icon(&self) -> event::CursorIcon {
+            if self.selectable {
+                event::CursorIcon::Text
+            } else {
+                event::CursorIcon::Default
+            }
+        }
+    }
+
+    impl event::Handler for Self {
+        type Msg = VoidMsg;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+            if !self.selectable {
+                return Response::Unhandled;
+            }
+            match event {
+                Event::Command(cmd, shift) => {
+                    let id = self.id();
+                    read_only_command(mgr, id, &self.label, &mut self.selection, cmd, shift)
+                        .map(|_| Response::None)
+                        .unwrap_or(Response::Unhandled)
+                }
+                Event::LostSelFocus => {
+                    self.selection.set_empty();
+                    mgr.redraw(self.id());
+                    Response::None
+                }
+                event => match self.input_handler.handle(mgr, self.id(), event) {
+                    TextInputAction::None | TextInputAction::Focus => Response::None,
+                    TextInputAction::Unhandled => Response::Unhandled,
+                    TextInputAction::Pan(delta) => Response::Pan(delta),
+                    TextInputAction::Cursor(coord, anchor, clear, repeats) => {
+                        if (clear && repeats <= 1) || mgr.request_sel_focus(self.id()) {
+                            let rel_pos = (coord - self.core.rect.pos).into();
+                            let pos = self.label.text_index_nearest(rel_pos);
+                            self.selection.set_edit_pos(pos);
+                            if anchor {
+                                self.selection.set_anchor();
+                            }
+                            if clear {
+                                self.selection.set_empty();
+                            }
+                            if repeats > 1 {
+                                self.selection.expand(&self.label, repeats);
+                            }
+                            mgr.redraw(self.id());
+                        }
+                        Response::None
+                    }
+                },
+            }
         }
     }
 
@@ -58,6 +149,7 @@ widget! {
         T: EditableText,
     {
         fn set_string(&mut self, string: String) -> TkAction {
+            self.core.bump_generation();
             kas::text::util::set_string_and_prepare(&mut self.label, string, self.core.rect.size)
         }
     }
@@ -83,6 +175,9 @@ impl Layout for AccelLabel {
 impl<'a> Layout for Label<&'a str> {
     fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
         let state = self.input_state(mgr, disabled);
+        if self.draw_selected(draw, state) {
+            return;
+        }
         draw.text(
             self.core.rect.pos,
             self.label.as_ref(),
@@ -95,6 +190,9 @@ impl<'a> Layout for Label<&'a str> {
 impl Layout for StringLabel {
     fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
         let state = self.input_state(mgr, disabled);
+        if self.draw_selected(draw, state) {
+            return;
+        }
         draw.text(
             self.core.rect.pos,
             self.label.as_ref(),
@@ -131,7 +229,54 @@ impl<T: FormattableText + 'static> Label<T> {
         Label {
             core: Default::default(),
             label: Text::new_multi(label),
+            msg: None,
+            truncated: false,
+            selectable: false,
+            selection: SelectionHelper::new(0, 0),
+            input_handler: Default::default(),
+        }
+    }
+
+    /// Set whether text may be selected (inline)
+    ///
+    /// When true, text may be selected using the mouse or touch and, while the
+    /// label has selection focus, the keyboard, and copied to the clipboard.
+    /// Only one widget at a time shows a selection (see
+    /// [`Manager::request_sel_focus`]).
+    ///
+    /// By default, labels are not selectable.
+    #[inline]
+    pub fn selectable(mut self, selectable: bool) -> Self {
+        self.selectable = selectable;
+        self
+    }
+
+    /// Get whether text may be selected
+    #[inline]
+    pub fn is_selectable(&self) -> bool {
+        self.selectable
+    }
+
+    /// Get the selected range, if any
+    #[inline]
+    pub fn selection(&self) -> Option<std::ops::Range<usize>> {
+        Some(self.selection.range()).filter(|range| !range.is_empty())
+    }
+
+    /// Draw with selection highlight, returning false if there is no selection
+    fn draw_selected(&self, draw: &mut dyn DrawHandle, state: InputState) -> bool {
+        if self.selection.is_empty() {
+            return false;
         }
+        let range = self.selection.range();
+        draw.text_selected(
+            self.core.rect.pos,
+            &self.label,
+            range,
+            TextClass::Label,
+            state,
+        );
+        true
     }
 
     /// Set text in an existing `Label`
@@ -139,6 +284,7 @@ impl<T: FormattableText + 'static> Label<T> {
     /// Note: this must not be called before fonts have been initialised
     /// (usually done by the theme when the main loop starts).
     pub fn set_text(&mut self, text: T) -> TkAction {
+        self.core.bump_generation();
         kas::text::util::set_text_and_prepare(&mut self.label, text, self.core.rect.size)
     }
 }
@@ -155,6 +301,18 @@ pub type StrLabel = Label<&'static str>;
 /// Label with `String` as backing type
 pub type StringLabel = Label<String>;
 
+impl StringLabel {
+    /// Construct from a localized message `key`
+    ///
+    /// The text is resolved via [`Manager::translate`] when configured, and
+    /// re-resolved when the locale changes. See [`kas::l10n`].
+    pub fn msg(key: &str) -> Self {
+        let mut label = Label::new(key.to_string());
+        label.msg = Some((key.to_string(), |text| text));
+        label
+    }
+}
+
 /// A label supporting an accelerator key
 ///
 /// Accelerator keys are not useful on plain labels. To be useful, a parent
@@ -170,14 +328,37 @@ pub type StringLabel = Label<String>;
 pub type AccelLabel = Label<AccelString>;
 
 impl AccelLabel {
+    /// Construct from a localized message `key`
+    ///
+    /// The translation may include accelerator marks (`&`). See
+    /// [`StringLabel::msg`].
+    pub fn msg(key: &str) -> Self {
+        let mut label = Label::new(AccelString::from(key));
+        label.msg = Some((key.to_string(), AccelString::from));
+        label
+    }
+
     /// Get the accelerator keys
     pub fn keys(&self) -> &[event::VirtualKeyCode] {
         self.label.text().keys()
     }
+
+    /// Register accelerator keys for `id`
+    ///
+    /// This calls [`Manager::add_accel_label`], adopting any reassigned
+    /// mnemonic so that the drawn underline matches the effective key.
+    pub fn add_accel_label(&mut self, mgr: &mut Manager, id: WidgetId) {
+        if let Some(string) = mgr.add_accel_label(id, self.label.text()) {
+            let action =
+                kas::text::util::set_text_and_prepare(&mut self.label, string, self.core.rect.size);
+            *mgr |= action;
+        }
+    }
 }
 
 impl SetAccel for AccelLabel {
     fn set_accel_string(&mut self, string: AccelString) -> TkAction {
+        self.core.bump_generation();
         let mut action = TkAction::empty();
         if self.label.text().keys() != string.keys() {
             action |= TkAction::RECONFIGURE;
@@ -185,3 +366,106 @@ impl SetAccel for AccelLabel {
         action | kas::text::util::set_text_and_prepare(&mut self.label, string, self.core.rect.size)
     }
 }
+
+widget! {
+    /// A single-line label which elides text to fit
+    ///
+    /// When the available width is insufficient, text is shortened and an
+    /// ellipsis (`…`) inserted according to the [`Elide`] mode (by default,
+    /// in the middle); the full text is then shown as a tooltip. This is
+    /// intended for long, path-like labels such as titles.
+    #[derive(Clone, Debug)]
+    pub struct ElidedLabel {
+        #[widget_core]
+        core: CoreData,
+        full: String,
+        label: Text<String>,
+        mode: Elide,
+        elided: bool,
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+            // Size requirements are those of the full text
+            if *self.label.text() != self.full {
+                self.label.set_text(self.full.clone());
+            }
+            let mut rules = size_handle.text_bound(&mut self.label, TextClass::MenuLabel, axis);
+            if axis.is_horizontal() {
+                rules.set_stretch(Stretch::Low);
+            }
+            rules
+        }
+
+        fn set_rect(&mut self, _: &mut Manager, rect: Rect, align: AlignHints) {
+            self.core.rect = rect;
+            self.label.update_env(|env| {
+                env.set_bounds(rect.size.into());
+                env.set_align(align.unwrap_or(Align::Default, Align::Center));
+            });
+            self.elided = set_text_elided(&mut self.label, &self.full, self.mode);
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let state = self.input_state(mgr, disabled);
+            draw.text(self.core.rect.pos, self.label.as_ref(), TextClass::MenuLabel, state);
+        }
+    }
+
+    impl WidgetConfig for Self {
+        fn tooltip(&self) -> Option<String> {
+            self.elided.then(|| self.full.clone())
+        }
+    }
+
+    impl HasStr for Self {
+        fn get_str(&self) -> &str {
+            &self.full
+        }
+    }
+
+    impl HasString for Self {
+        fn set_string(&mut self, string: String) -> TkAction {
+            self.core.bump_generation();
+            self.full = string;
+            // Text is (re-)elided on resize
+            TkAction::RESIZE
+        }
+    }
+}
+
+impl ElidedLabel {
+    /// Construct from `text`, eliding in the middle
+    ///
+    /// At least four grapheme clusters are kept at each end.
+    #[inline]
+    pub fn new<S: ToString>(text: S) -> Self {
+        let full = text.to_string();
+        ElidedLabel {
+            core: Default::default(),
+            label: Text::new_single(full.clone()),
+            full,
+            mode: Elide::Middle {
+                min_start: 4,
+                min_end: 4,
+            },
+            elided: false,
+        }
+    }
+
+    /// Set the elision mode (inline)
+    #[inline]
+    pub fn with_elide(mut self, mode: Elide) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the elision mode
+    ///
+    /// This takes effect on the next resize.
+    #[inline]
+    pub fn set_elide(&mut self, mode: Elide) -> TkAction {
+        self.mode = mode;
+        TkAction::RESIZE
+    }
+}
@@ -13,17 +13,23 @@
 //!
 //! -   [`Frame`]: a simple frame around a single child
 //! -   [`ScrollRegion`]: may be larger on the inside than the outside
+//! -   [`ZoomView`]: a scalable view over a single child
 //! -   [`Stack`]: a stack of widgets in the same rect (TODO: `TabbedStack`)
+//! -   [`Carousel`]: a paged view over widgets, with swipe navigation
 //! -   [`List`]: a dynamic row / column of children
+//! -   [`FlowBox`]: a row of children, wrapping to new lines as required
 //! -   [`Splitter`]: similar to [`List`] but with resizing handles
 //! -   [`Window`] is usually the root widget and has special handling for
 //!     pop-ups and callbacks
+//! -   [`ResizeFrame`] provides resize borders for an undecorated window
+//! -   [`NodeGraph`]: an editor of nodes (widgets) connected by links
 //!
 //! ## Menus
 //!
 //! -   [`ComboBox`]: a simple pop-up selector
 //! -   [`MenuBar`], [`SubMenu`]: menu parent widgets
 //! -   [`MenuEntry`], [`MenuToggle`], [`Separator`]: menu entries
+//! -   [`CheckMenuEntry`], [`RadioMenuEntry`], [`MenuSection`]: more menu entries
 //!
 //! ## Controls
 //!
@@ -31,6 +37,7 @@
 //! -   [`CheckBox`]: a checkable box
 //! -   [`RadioBox`]: a checkable box bound to a group
 //! -   [`EditBox`]: a text-editing box
+//! -   [`Hyperlink`]: a link, opening a URL when activated
 //! -   [`ScrollBar`]: a scrollbar
 //! -   [`Slider`]: a slider
 //!
@@ -39,6 +46,12 @@
 //! -   [`Filler`]: an empty widget, sometimes used to fill space
 //! -   [`Separator`]: a visible bar to separate things
 //! -   [`Label`]: a simple text label
+//! -   [`ElidedLabel`]: a single-line label which elides text to fit
+//! -   [`LinkLabel`]: a text label containing links
+//! -   [`TitleBar`]: a title bar for an undecorated window
+//! -   [`StatusBar`]: a status bar showing transient messages and sections
+//! -   [`DrawStatsView`]: a debug overlay showing draw statistics
+//! -   [`NativeSurface`]: a region reserved for a native child surface
 //!
 //! ## Components
 //!
@@ -53,20 +66,27 @@
 #![cfg_attr(feature = "min_spec", feature(min_specialization))]
 
 mod button;
+mod carousel;
 mod checkbox;
 mod combobox;
+mod decorations;
 mod dialog;
 mod drag;
+mod draw_stats;
 mod editbox;
 mod filler;
+mod flow_box;
 mod frame;
 mod grid;
+mod hyperlink;
 mod label;
 mod list;
 #[macro_use]
 mod macros;
 mod menu;
+mod native_surface;
 mod nav_frame;
+mod node_graph;
 mod progress;
 mod radiobox;
 mod scroll;
@@ -77,32 +97,47 @@ mod slider;
 mod splitter;
 mod sprite;
 mod stack;
+mod status_bar;
+mod validator;
 mod window;
+mod zoom;
 
 pub mod adapter;
 pub mod view;
 
 pub use button::{Button, TextButton};
+pub use carousel::{BoxCarousel, Carousel};
 pub use checkbox::{CheckBox, CheckBoxBare};
 pub use combobox::ComboBox;
+pub use decorations::{ResizeFrame, TitleBar};
 pub use dialog::MessageBox;
 pub use drag::DragHandle;
-pub use editbox::{EditBox, EditField, EditGuard};
+pub use draw_stats::DrawStatsView;
+pub use editbox::{EditBox, EditField, EditGuard, Scrub, SmartQuotes, Snippets, TextTransform};
 pub use filler::Filler;
+pub use flow_box::{BoxFlowBox, FlowBox};
 pub use frame::Frame;
 pub use grid::{BoxGrid, Grid};
-pub use label::{AccelLabel, Label, StrLabel, StringLabel};
+pub use hyperlink::{Hyperlink, LinkLabel};
+pub use label::{AccelLabel, ElidedLabel, Label, StrLabel, StringLabel};
 pub use list::*;
 pub use menu::*;
+pub use native_surface::{NativeSurface, NativeSurfaceHandle, SurfaceGeometry, SurfaceInput};
 pub use nav_frame::NavFrame;
+pub use node_graph::{Link, NodeGraph, NodeGraphMsg};
 pub use progress::ProgressBar;
 pub use radiobox::{RadioBox, RadioBoxBare};
 pub use scroll::{ScrollComponent, ScrollRegion};
 pub use scroll_label::ScrollLabel;
-pub use scrollbar::{ScrollBar, ScrollBarRegion, ScrollBars, Scrollable};
+pub use scrollbar::{ScrollBar, ScrollBarRegion, ScrollBars, ScrollState, Scrollable};
 pub use separator::Separator;
 pub use slider::{Slider, SliderType};
 pub use splitter::*;
 pub use sprite::Image;
 pub use stack::{BoxStack, RefStack, Stack};
+pub use status_bar::{StatusBar, StatusMessage};
+#[cfg(feature = "regex")]
+pub use validator::RegexValidator;
+pub use validator::{Mask, MaxLength, Numeric, Validation, Validator};
 pub use window::Window;
+pub use zoom::ZoomView;
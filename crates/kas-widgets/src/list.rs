@@ -6,8 +6,15 @@
 //! A row or column with run-time adjustable contents
 
 use kas::dir::{Down, Right};
+use kas::event::{CursorIcon, GrabMode, PressSource};
 use kas::{event, layout, prelude::*};
 use std::ops::{Index, IndexMut};
+use std::rc::Rc;
+
+/// Timer payload used for auto-scrolling while reordering
+const AUTO_SCROLL_TIMER: u64 = 1 << 61;
+/// Maximum auto-scroll distance per frame
+const MAX_AUTO_SCROLL: i32 = 40;
 
 /// Support for optionally-indexed messages
 pub trait FromIndexed<T> {
@@ -163,10 +170,19 @@ widget! {
     /// Configuring and resizing elements is O(n) in the number of children.
     /// Drawing and event handling is O(log n) in the number of children (assuming
     /// only a small number are visible at any one time).
+    ///
+    /// ## Animation
+    ///
+    /// Optionally, children may be animated to their new positions when
+    /// children are added or removed; see [`Self::with_animation`].
+    ///
+    /// ## Reordering
+    ///
+    /// Optionally, users may drag children to new positions; see
+    /// [`Self::on_reorder`].
     #[autoimpl(Clone where W: Clone)]
-    #[autoimpl(Debug)]
+    #[autoimpl(Debug skip reorder)]
     #[autoimpl(Default where D: Default)]
-    #[handler(msg=M)]
     pub struct GenericList<
         D: Directional,
         W: Widget,
@@ -178,6 +194,14 @@ widget! {
         widgets: Vec<W>,
         data: layout::DynRowStorage,
         direction: D,
+        /// True if horizontal and mirrored (right-to-left layout)
+        mirrored: bool,
+        align: AlignHints,
+        anim: layout::RectAnimation,
+        reorder: Option<Rc<dyn Fn(&mut Manager, usize, usize) -> Option<M>>>,
+        drag: Option<ReorderDrag>,
+        /// Visible part of the list (as last drawn)
+        visible: Rect,
         _pd: std::marker::PhantomData<M>,
     }
 
@@ -207,27 +231,116 @@ widget! {
         fn layout(&mut self) -> layout::Layout<'_> {
             make_layout!(self.core; slice(self.direction): self.widgets)
         }
+
+        fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
+            self.core.rect = rect;
+            self.align = align;
+            let direction = self.direction.as_direction();
+            self.mirrored = mgr.layout_direction().apply(direction) != direction;
+            let old = self.anim.begin(self.widgets.iter().map(|w| w.rect()));
+            self.layout().set_rect(mgr, rect, align);
+            let id = self.id();
+            self.anim.start(mgr, id, &mut self.widgets, old, align);
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            self.visible = draw.get_clip_rect();
+            let state = self.input_state(mgr, disabled);
+            self.layout().draw(draw, mgr, state);
+
+            let sf = draw.size_handle().scale_factor();
+            let thickness: i32 = (2.0 * sf).cast_nearest();
+            let thickness = thickness.max(2);
+            if let Some(rect) = self.drop_marker_rect(thickness) {
+                draw.drop_marker(rect);
+            }
+        }
+    }
+
+    impl event::Handler for Self {
+        type Msg = M;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+            match event {
+                Event::TimerUpdate(layout::RectAnimation::TIMER) => {
+                    let id = self.id();
+                    if self.anim.animate(mgr, id, &mut self.widgets) {
+                        let (rect, align) = (self.core.rect, self.align);
+                        self.layout().set_rect(mgr, rect, align);
+                        mgr.redraw(id);
+                    }
+                    Response::None
+                }
+                Event::PressMove { source, coord, .. } if self.is_dragging(source) => {
+                    let id = self.id();
+                    let visible = self.visible;
+                    let drag = self.drag.as_mut().unwrap();
+                    drag.coord = coord;
+                    drag.visible_pos = visible.pos;
+                    if !drag.active {
+                        if !mgr.config_test_pan_thresh(coord - drag.start) {
+                            return Response::None;
+                        }
+                        drag.active = true;
+                        mgr.update_grab_cursor(id, CursorIcon::Grabbing);
+                    }
+                    self.update_drag(mgr)
+                }
+                Event::PressEnd { source, .. } if self.is_dragging(source) => {
+                    let drag = self.drag.take().unwrap();
+                    mgr.redraw(self.id());
+                    if drag.active {
+                        self.finish_drag(mgr, drag)
+                    } else {
+                        Response::None
+                    }
+                }
+                Event::TimerUpdate(AUTO_SCROLL_TIMER) => {
+                    let visible = self.visible;
+                    match self.drag.as_mut().filter(|drag| drag.active) {
+                        Some(drag) => {
+                            // Content has moved under the pointer while scrolling
+                            drag.coord += visible.pos - drag.visible_pos;
+                            drag.visible_pos = visible.pos;
+                            self.update_drag(mgr)
+                        }
+                        None => Response::None,
+                    }
+                }
+                _ => Response::Unhandled,
+            }
+        }
     }
 
     impl event::SendEvent for Self {
         fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            if id == self.id() {
+                return Manager::handle_generic(self, mgr, event);
+            }
             if !self.is_disabled() {
-                for (i, child) in self.widgets.iter_mut().enumerate() {
-                    if id <= child.id() {
-                        let r = child.send(mgr, id, event);
-                        return match Response::try_from(r) {
-                            Ok(r) => r,
-                            Err(msg) => {
-                                log::trace!(
-                                    "Received by {} from {}: {:?}",
-                                    self.id(),
-                                    id,
-                                    kas::util::TryFormat(&msg)
-                                );
-                                Response::Msg(FromIndexed::from_indexed(i, msg))
-                            }
-                        };
+                // Presses not handled by children may start a drag
+                let press = match event {
+                    Event::PressStart { source, coord, .. }
+                        if self.reorder.is_some() && source.is_primary() =>
+                    {
+                        Some((source, coord))
                     }
+                    _ => None,
+                };
+
+                let self_id = self.id();
+                if let Some(r) =
+                    mgr.dispatch_to_child(self_id, &mut self.widgets, id, event, |_, i, msg| {
+                        Response::Msg(FromIndexed::from_indexed(i, msg))
+                    })
+                {
+                    if let (Response::Unhandled, Some((source, coord))) = (&r, press) {
+                        if let Some(index) = self.find_child_index(id) {
+                            self.start_drag(mgr, index, source, coord);
+                            return Response::None;
+                        }
+                    }
+                    return r;
                 }
             }
 
@@ -266,10 +379,59 @@ widget! {
                 widgets,
                 data: Default::default(),
                 direction,
+                mirrored: false,
+                align: Default::default(),
+                anim: Default::default(),
+                reorder: None,
+                drag: None,
+                visible: Rect::ZERO,
                 _pd: Default::default(),
             }
         }
 
+        /// Enable reordering by drag (inline)
+        ///
+        /// Users may then drag children to a new position using the primary
+        /// mouse button or touch, where the press is not handled by the child
+        /// itself. While dragging, a marker is drawn at the drop position and
+        /// an enclosing [`ScrollRegion`](crate::ScrollRegion) scrolls when the
+        /// pointer nears its edge.
+        ///
+        /// On drop, the child is moved from index `from` to index `to` and
+        /// `f(mgr, from, to)` is called; the message returned, if any, is
+        /// passed to the parent.
+        #[inline]
+        pub fn on_reorder<F>(mut self, f: F) -> Self
+        where
+            F: Fn(&mut Manager, usize, usize) -> Option<M> + 'static,
+        {
+            self.reorder = Some(Rc::new(f));
+            self
+        }
+
+        /// Set whether to animate layout changes (inline)
+        ///
+        /// When enabled, children move (and resize) smoothly to their new
+        /// positions after children are added, removed or replaced, instead
+        /// of snapping. The duration is given by
+        /// [`Config::layout_anim_duration`](kas::event::Config::layout_anim_duration);
+        /// animation is disabled when reduced motion is requested.
+        ///
+        /// By default, layout changes are not animated.
+        #[inline]
+        pub fn with_animation(mut self, animate: bool) -> Self {
+            self.anim.set_enabled(animate);
+            self
+        }
+
+        /// Set whether to animate layout changes
+        ///
+        /// See [`Self::with_animation`].
+        #[inline]
+        pub fn set_animation(&mut self, animate: bool) {
+            self.anim.set_enabled(animate);
+        }
+
         /// Get the direction of contents
         pub fn direction(&self) -> Direction {
             self.direction.as_direction()
@@ -306,6 +468,7 @@ widget! {
                 false => TkAction::RECONFIGURE,
             };
             self.widgets.clear();
+            self.anim.note_change();
             action
         }
 
@@ -314,6 +477,7 @@ widget! {
         /// Triggers a [reconfigure action](Manager::send_action).
         pub fn push(&mut self, widget: W) -> TkAction {
             self.widgets.push(widget);
+            self.anim.note_change();
             TkAction::RECONFIGURE
         }
 
@@ -329,6 +493,7 @@ widget! {
                 true => TkAction::empty(),
                 false => TkAction::RECONFIGURE,
             };
+            self.anim.note_change();
             (self.widgets.pop(), action)
         }
 
@@ -339,6 +504,7 @@ widget! {
         /// Triggers a [reconfigure action](Manager::send_action).
         pub fn insert(&mut self, index: usize, widget: W) -> TkAction {
             self.widgets.insert(index, widget);
+            self.anim.note_change();
             TkAction::RECONFIGURE
         }
 
@@ -349,6 +515,7 @@ widget! {
         /// Triggers a [reconfigure action](Manager::send_action).
         pub fn remove(&mut self, index: usize) -> (W, TkAction) {
             let r = self.widgets.remove(index);
+            self.anim.note_change();
             (r, TkAction::RECONFIGURE)
         }
 
@@ -362,6 +529,7 @@ widget! {
         // we somehow test "has compatible size"?
         pub fn replace(&mut self, index: usize, mut widget: W) -> (W, TkAction) {
             std::mem::swap(&mut widget, &mut self.widgets[index]);
+            self.anim.note_change();
             (widget, TkAction::RECONFIGURE)
         }
 
@@ -372,6 +540,7 @@ widget! {
         pub fn extend<T: IntoIterator<Item = W>>(&mut self, iter: T) -> TkAction {
             let len = self.widgets.len();
             self.widgets.extend(iter);
+            self.anim.note_change();
             match len == self.widgets.len() {
                 true => TkAction::empty(),
                 false => TkAction::RECONFIGURE,
@@ -393,6 +562,7 @@ widget! {
                     self.widgets.push(f(i));
                 }
             }
+            self.anim.note_change();
             TkAction::RECONFIGURE
         }
 
@@ -405,6 +575,7 @@ widget! {
         pub fn retain<F: FnMut(&W) -> bool>(&mut self, f: F) -> TkAction {
             let len = self.widgets.len();
             self.widgets.retain(f);
+            self.anim.note_change();
             match len == self.widgets.len() {
                 true => TkAction::empty(),
                 false => TkAction::RECONFIGURE,
@@ -427,14 +598,140 @@ widget! {
 
         /// Get the index of the child which is an ancestor of `id`, if any
         pub fn find_child_index(&self, id: WidgetId) -> Option<usize> {
-            if id >= self.first_id {
-                for (i, child) in self.widgets.iter().enumerate() {
-                    if id <= child.id() {
-                        return Some(i);
+            kas::util::find_child_index(&self.widgets, id)
+        }
+
+        fn is_dragging(&self, source: PressSource) -> bool {
+            self.drag.as_ref().map(|drag| drag.source == source).unwrap_or(false)
+        }
+
+        fn start_drag(
+            &mut self,
+            mgr: &mut Manager,
+            index: usize,
+            source: PressSource,
+            coord: Coord,
+        ) {
+            if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None) {
+                self.drag = Some(ReorderDrag {
+                    source,
+                    index,
+                    start: coord,
+                    coord,
+                    visible_pos: self.visible.pos,
+                    active: false,
+                    target: index,
+                });
+            }
+        }
+
+        /// Position of `coord` along the main axis, and the span of `rect`
+        fn axis_pos(&self, coord: Coord, rect: Rect) -> (i32, i32, i32) {
+            match self.direction.is_vertical() {
+                false => (coord.0, rect.pos.0, rect.pos.0 + rect.size.0),
+                true => (coord.1, rect.pos.1, rect.pos.1 + rect.size.1),
+            }
+        }
+
+        /// Update the drop position and auto-scroll for the drag
+        fn update_drag(&mut self, mgr: &mut Manager) -> Response<M> {
+            let coord = self.drag.as_ref().unwrap().coord;
+            let reversed = self.direction.is_reversed() ^ self.mirrored;
+            let target = (self.widgets.iter())
+                .position(|w| {
+                    let (c, a, b) = self.axis_pos(coord, w.rect());
+                    let mid = a + (b - a) / 2;
+                    if reversed {
+                        c > mid
+                    } else {
+                        c < mid
                     }
-                }
+                })
+                .unwrap_or(self.widgets.len());
+
+            let drag = self.drag.as_mut().unwrap();
+            if drag.target != target {
+                drag.target = target;
+                mgr.redraw(self.id());
+            }
+
+            // Scroll when within this distance of the visible edge
+            let (c, a, b) = self.axis_pos(coord, self.visible);
+            let zone: i32 = (32.0 * mgr.scale_factor()).cast_nearest();
+            let zone = zone.min((b - a) / 4);
+            let d = if c < a + zone {
+                -((a + zone - c) / 4).max(1).min(MAX_AUTO_SCROLL)
+            } else if c >= b - zone {
+                ((c - (b - zone)) / 4 + 1).min(MAX_AUTO_SCROLL)
+            } else {
+                return Response::None;
+            };
+
+            mgr.update_on_frame(self.id(), AUTO_SCROLL_TIMER);
+            // Pan deltas are subtracted from the scroll offset
+            match self.direction.is_vertical() {
+                false => Response::Pan(Offset(-d, 0)),
+                true => Response::Pan(Offset(0, -d)),
             }
-            None
+        }
+
+        fn finish_drag(&mut self, mgr: &mut Manager, drag: ReorderDrag) -> Response<M> {
+            let len = self.widgets.len();
+            if drag.index >= len {
+                return Response::None;
+            }
+            let from = drag.index;
+            let to = match drag.target > from {
+                true => drag.target - 1,
+                false => drag.target,
+            };
+            let to = to.min(len - 1);
+            if from == to {
+                return Response::None;
+            }
+
+            let widget = self.widgets.remove(from);
+            self.widgets.insert(to, widget);
+            self.anim.note_change();
+            *mgr |= TkAction::RECONFIGURE;
+
+            let f = self.reorder.clone().unwrap();
+            Response::none_or_msg(f(mgr, from, to))
+        }
+
+        /// Rect of the drop marker, if any
+        fn drop_marker_rect(&self, thickness: i32) -> Option<Rect> {
+            let drag = self.drag.as_ref().filter(|drag| drag.active)?;
+            let t = drag.target;
+            if t == drag.index || t == drag.index + 1 {
+                // Dropping here would not move the child
+                return None;
+            }
+
+            // Main-axis position of the start or end of a child
+            let reversed = self.direction.is_reversed() ^ self.mirrored;
+            let edge = |w: &W, end: bool| {
+                let (_, a, b) = self.axis_pos(Coord::ZERO, w.rect());
+                if end != reversed {
+                    b
+                } else {
+                    a
+                }
+            };
+            let before = t.checked_sub(1).and_then(|i| self.widgets.get(i));
+            let pos = match (before, self.widgets.get(t)) {
+                (Some(w0), Some(w1)) => (edge(w0, true) + edge(w1, false)) / 2,
+                (Some(w0), None) => edge(w0, true),
+                (None, Some(w1)) => edge(w1, false),
+                (None, None) => return None,
+            };
+
+            let p = pos - thickness / 2;
+            let rect = self.core.rect;
+            Some(match self.direction.is_vertical() {
+                false => Rect::new(Coord(p, rect.pos.1), Size(thickness, rect.size.1)),
+                true => Rect::new(Coord(rect.pos.0, p), Size(rect.size.0, thickness)),
+            })
         }
     }
 
@@ -453,6 +750,23 @@ widget! {
     }
 }
 
+/// State of a drag-to-reorder operation
+#[derive(Clone, Debug)]
+struct ReorderDrag {
+    source: PressSource,
+    /// Index of the dragged child
+    index: usize,
+    start: Coord,
+    /// Last pointer position
+    coord: Coord,
+    /// Position of the visible rect when `coord` was last updated
+    visible_pos: Coord,
+    /// True once the drag threshold has been exceeded
+    active: bool,
+    /// Drop position, in `0..=len`
+    target: usize,
+}
+
 struct ListIter<'a, W: Widget> {
     list: &'a [W],
 }
@@ -7,12 +7,18 @@
 
 use std::ops::{Deref, DerefMut};
 
+mod check_entry;
+mod enabled;
 mod menu_entry;
 mod menubar;
+mod section;
 mod submenu;
 
+pub use check_entry::{CheckMenuEntry, RadioMenuEntry};
+pub use enabled::EnabledBinding;
 pub use menu_entry::{MenuEntry, MenuToggle};
 pub use menubar::MenuBar;
+pub use section::MenuSection;
 pub use submenu::SubMenu;
 
 use kas::{event, prelude::*};
@@ -105,6 +111,18 @@ impl<M: 'static> WidgetConfig for Box<dyn Menu<Msg = M>> {
     fn key_nav(&self) -> bool {
         self.as_ref().key_nav()
     }
+    fn focus_scope(&self) -> bool {
+        self.as_ref().focus_scope()
+    }
+    fn tab_index(&self) -> Option<u32> {
+        self.as_ref().tab_index()
+    }
+    fn nav_wrap(&self) -> bool {
+        self.as_ref().nav_wrap()
+    }
+    fn initial_focus(&self) -> bool {
+        self.as_ref().initial_focus()
+    }
     fn hover_highlight(&self) -> bool {
         self.as_ref().hover_highlight()
     }
@@ -0,0 +1,442 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Checkable menu entries
+
+use super::menu_entry::ShortcutText;
+use super::Menu;
+use kas::draw::TextClass;
+use kas::event::{self, Command};
+use kas::{layout, prelude::*};
+use log::trace;
+use std::rc::Rc;
+
+widget! {
+    /// Space reserved for a check mark, drawn by the parent
+    #[derive(Clone, Debug, Default)]
+    struct Mark {
+        #[widget_core]
+        core: CoreData,
+        radio: bool,
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+            let size = match self.radio {
+                false => size_handle.checkbox(),
+                true => size_handle.radiobox(),
+            };
+            self.core.rect.size = size;
+            let margins = size_handle.outer_margins();
+            SizeRules::extract_fixed(axis, size, margins)
+        }
+
+        fn set_rect(&mut self, _: &mut Manager, rect: Rect, align: AlignHints) {
+            let rect = align
+                .complete(Align::Center, Align::Center)
+                .aligned_rect(self.rect().size, rect);
+            self.core.rect = rect;
+        }
+
+        fn draw(&mut self, _: &mut dyn DrawHandle, _: &ManagerState, _: bool) {}
+    }
+}
+
+widget! {
+    /// A menu entry with a check mark
+    ///
+    /// Unlike [`MenuToggle`](super::MenuToggle), the whole entry is a single
+    /// navigable item and the check mark is drawn as part of the entry. The
+    /// state is toggled on activation.
+    #[autoimpl(Debug skip on_toggle)]
+    #[derive(Clone, Default)]
+    pub struct CheckMenuEntry<M: 'static> {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        mark: Mark,
+        label: Text<AccelString>,
+        layout_label: layout::TextStorage,
+        shortcut: ShortcutText,
+        layout_row: layout::FixedRowStorage<3>,
+        layout_frame: layout::FrameStorage,
+        state: bool,
+        on_toggle: Option<Rc<dyn Fn(&mut Manager, bool) -> Option<M>>>,
+    }
+
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            if let Some(string) = mgr.add_accel_label(self.id(), self.label.text()) {
+                let avail = self.core.rect.size.clamped_sub(self.layout_frame.size);
+                *mgr |= kas::text::util::set_text_and_prepare(&mut self.label, string, avail);
+            }
+            self.shortcut.configure(mgr);
+        }
+
+        fn key_nav(&self) -> bool {
+            true
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> layout::Layout<'_> {
+            let mark = layout::Layout::single(&mut self.mark);
+            let label = &mut self.label;
+            let label = layout::Layout::text(&mut self.layout_label, label, TextClass::MenuLabel);
+            let list = [mark, label, self.shortcut.layout()].into_iter();
+            let inner = layout::Layout::list(list, Direction::Right, &mut self.layout_row);
+            layout::Layout::frame(&mut self.layout_frame, inner)
+        }
+
+        fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
+            self.rect().contains(coord).then(|| self.id())
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let state = self.input_state(mgr, disabled);
+            draw.menu_entry(self.core.rect, state);
+            draw.checkbox(self.mark.rect(), self.state, state);
+            draw.text_accel(
+                self.layout_label.pos,
+                &self.label,
+                mgr.show_accel_labels(),
+                TextClass::MenuLabel,
+                state,
+            );
+            self.shortcut.draw(draw, state);
+        }
+    }
+
+    impl CheckMenuEntry<VoidMsg> {
+        /// Construct a checkable menu entry with a given `label`
+        #[inline]
+        pub fn new<T: Into<AccelString>>(label: T) -> Self {
+            CheckMenuEntry {
+                core: Default::default(),
+                mark: Default::default(),
+                label: Text::new_single(label.into()),
+                layout_label: Default::default(),
+                shortcut: Default::default(),
+                layout_row: Default::default(),
+                layout_frame: Default::default(),
+                state: false,
+                on_toggle: None,
+            }
+        }
+
+        /// Set event handler `f`
+        ///
+        /// On toggle (through user input events or [`Event::Activate`]) the
+        /// closure `f` is called. The result of `f` is converted to
+        /// [`Response::Msg`] or [`Response::Update`] and returned to the parent.
+        #[inline]
+        pub fn on_toggle<M, F>(self, f: F) -> CheckMenuEntry<M>
+        where
+            F: Fn(&mut Manager, bool) -> Option<M> + 'static,
+        {
+            CheckMenuEntry {
+                core: self.core,
+                mark: self.mark,
+                label: self.label,
+                layout_label: self.layout_label,
+                shortcut: self.shortcut,
+                layout_row: self.layout_row,
+                layout_frame: self.layout_frame,
+                state: self.state,
+                on_toggle: Some(Rc::new(f)),
+            }
+        }
+    }
+
+    impl Self {
+        /// Construct a checkable menu entry with a given `label` and event handler `f`
+        ///
+        /// On toggle (through user input events or [`Event::Activate`]) the
+        /// closure `f` is called. The result of `f` is converted to
+        /// [`Response::Msg`] or [`Response::Update`] and returned to the parent.
+        #[inline]
+        pub fn new_on<T: Into<AccelString>, F>(label: T, f: F) -> Self
+        where
+            F: Fn(&mut Manager, bool) -> Option<M> + 'static,
+        {
+            CheckMenuEntry::new(label).on_toggle(f)
+        }
+
+        /// Set the initial state
+        #[inline]
+        pub fn with_state(mut self, state: bool) -> Self {
+            self.state = state;
+            self
+        }
+
+        /// Display the shortcut bound to `cmd`, builder style
+        ///
+        /// See [`MenuEntry::with_shortcut`](super::MenuEntry::with_shortcut).
+        pub fn with_shortcut(mut self, cmd: Command) -> Self {
+            self.shortcut.set_command(cmd);
+            self
+        }
+
+        /// Display fixed shortcut text, builder style
+        pub fn with_shortcut_text<S: ToString>(mut self, text: S) -> Self {
+            self.shortcut.set_text(text.to_string());
+            self
+        }
+    }
+
+    impl HasBool for Self {
+        fn get_bool(&self) -> bool {
+            self.state
+        }
+
+        fn set_bool(&mut self, state: bool) -> TkAction {
+            self.state = state;
+            TkAction::REDRAW
+        }
+    }
+
+    impl Handler for Self {
+        type Msg = M;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+            match event {
+                Event::Activate => {
+                    self.state = !self.state;
+                    mgr.redraw(self.id());
+                    let state = self.state;
+                    Response::update_or_msg(self.on_toggle.as_ref().and_then(|f| f(mgr, state)))
+                }
+                _ => Response::Unhandled,
+            }
+        }
+    }
+
+    impl event::SendEvent for Self {
+        fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            if self.is_disabled() {
+                return Response::Unhandled;
+            }
+            debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
+            Manager::handle_generic(self, mgr, event)
+        }
+    }
+
+    impl Menu for Self {}
+}
+
+widget! {
+    /// A menu entry with a radio mark
+    ///
+    /// All instances of [`RadioMenuEntry`] constructed over the same `handle`
+    /// form a group, of which only one entry is selected. An entry is
+    /// selected on activation.
+    #[autoimpl(Debug skip on_select)]
+    #[derive(Clone)]
+    pub struct RadioMenuEntry<M: 'static> {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        mark: Mark,
+        label: Text<AccelString>,
+        layout_label: layout::TextStorage,
+        shortcut: ShortcutText,
+        layout_row: layout::FixedRowStorage<3>,
+        layout_frame: layout::FrameStorage,
+        state: bool,
+        handle: UpdateHandle,
+        on_select: Option<Rc<dyn Fn(&mut Manager) -> Option<M>>>,
+    }
+
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            mgr.update_on_handle(self.handle, self.id());
+            if let Some(string) = mgr.add_accel_label(self.id(), self.label.text()) {
+                let avail = self.core.rect.size.clamped_sub(self.layout_frame.size);
+                *mgr |= kas::text::util::set_text_and_prepare(&mut self.label, string, avail);
+            }
+            self.shortcut.configure(mgr);
+        }
+
+        fn key_nav(&self) -> bool {
+            true
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> layout::Layout<'_> {
+            let mark = layout::Layout::single(&mut self.mark);
+            let label = &mut self.label;
+            let label = layout::Layout::text(&mut self.layout_label, label, TextClass::MenuLabel);
+            let list = [mark, label, self.shortcut.layout()].into_iter();
+            let inner = layout::Layout::list(list, Direction::Right, &mut self.layout_row);
+            layout::Layout::frame(&mut self.layout_frame, inner)
+        }
+
+        fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
+            self.rect().contains(coord).then(|| self.id())
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let state = self.input_state(mgr, disabled);
+            draw.menu_entry(self.core.rect, state);
+            draw.radiobox(self.mark.rect(), self.state, state);
+            draw.text_accel(
+                self.layout_label.pos,
+                &self.label,
+                mgr.show_accel_labels(),
+                TextClass::MenuLabel,
+                state,
+            );
+            self.shortcut.draw(draw, state);
+        }
+    }
+
+    impl RadioMenuEntry<VoidMsg> {
+        /// Construct a radio menu entry with a given `label`
+        ///
+        /// All instances of [`RadioMenuEntry`] constructed over the same
+        /// `handle` will be considered part of a single group.
+        #[inline]
+        pub fn new<T: Into<AccelString>>(label: T, handle: UpdateHandle) -> Self {
+            RadioMenuEntry {
+                core: Default::default(),
+                mark: Mark {
+                    core: Default::default(),
+                    radio: true,
+                },
+                label: Text::new_single(label.into()),
+                layout_label: Default::default(),
+                shortcut: Default::default(),
+                layout_row: Default::default(),
+                layout_frame: Default::default(),
+                state: false,
+                handle,
+                on_select: None,
+            }
+        }
+
+        /// Set event handler `f`
+        ///
+        /// On selection (through user input events or [`Event::Activate`]) the
+        /// closure `f` is called. The result of `f` is converted to
+        /// [`Response::Msg`] or [`Response::Update`] and returned to the parent.
+        ///
+        /// No handler is called on deselection, but [`Response::Update`] is returned.
+        #[inline]
+        pub fn on_select<M, F>(self, f: F) -> RadioMenuEntry<M>
+        where
+            F: Fn(&mut Manager) -> Option<M> + 'static,
+        {
+            RadioMenuEntry {
+                core: self.core,
+                mark: self.mark,
+                label: self.label,
+                layout_label: self.layout_label,
+                shortcut: self.shortcut,
+                layout_row: self.layout_row,
+                layout_frame: self.layout_frame,
+                state: self.state,
+                handle: self.handle,
+                on_select: Some(Rc::new(f)),
+            }
+        }
+    }
+
+    impl Self {
+        /// Construct a radio menu entry with given `label`, `handle` and event handler `f`
+        ///
+        /// All instances of [`RadioMenuEntry`] constructed over the same
+        /// `handle` will be considered part of a single group.
+        ///
+        /// On selection (through user input events or [`Event::Activate`]) the
+        /// closure `f` is called. The result of `f` is converted to
+        /// [`Response::Msg`] or [`Response::Update`] and returned to the parent.
+        #[inline]
+        pub fn new_on<T: Into<AccelString>, F>(label: T, handle: UpdateHandle, f: F) -> Self
+        where
+            F: Fn(&mut Manager) -> Option<M> + 'static,
+        {
+            RadioMenuEntry::new(label, handle).on_select(f)
+        }
+
+        /// Set the initial state
+        #[inline]
+        pub fn with_state(mut self, state: bool) -> Self {
+            self.state = state;
+            self
+        }
+
+        /// Display the shortcut bound to `cmd`, builder style
+        ///
+        /// See [`MenuEntry::with_shortcut`](super::MenuEntry::with_shortcut).
+        pub fn with_shortcut(mut self, cmd: Command) -> Self {
+            self.shortcut.set_command(cmd);
+            self
+        }
+
+        /// Display fixed shortcut text, builder style
+        pub fn with_shortcut_text<S: ToString>(mut self, text: S) -> Self {
+            self.shortcut.set_text(text.to_string());
+            self
+        }
+    }
+
+    impl HasBool for Self {
+        fn get_bool(&self) -> bool {
+            self.state
+        }
+
+        fn set_bool(&mut self, state: bool) -> TkAction {
+            self.state = state;
+            TkAction::REDRAW
+        }
+    }
+
+    impl Handler for Self {
+        type Msg = M;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<M> {
+            match event {
+                Event::Activate => {
+                    if !self.state {
+                        trace!("RadioMenuEntry: set {}", self.id());
+                        self.state = true;
+                        mgr.redraw(self.id());
+                        mgr.trigger_update(self.handle, self.id().into());
+                        Response::update_or_msg(self.on_select.as_ref().and_then(|f| f(mgr)))
+                    } else {
+                        Response::None
+                    }
+                }
+                Event::HandleUpdate { payload, .. } => {
+                    // Other users of the update handle may send other payloads
+                    match WidgetId::try_from(payload) {
+                        Ok(id) if self.state && id != self.id() => {
+                            trace!("RadioMenuEntry: unset {}", self.id());
+                            self.state = false;
+                            mgr.redraw(self.id());
+                            Response::Update
+                        }
+                        _ => Response::None,
+                    }
+                }
+                _ => Response::Unhandled,
+            }
+        }
+    }
+
+    impl event::SendEvent for Self {
+        fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            // Group updates must be received while disabled
+            if self.is_disabled() && !matches!(event, Event::HandleUpdate { .. }) {
+                return Response::Unhandled;
+            }
+            debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
+            Manager::handle_generic(self, mgr, event)
+        }
+    }
+
+    impl Menu for Self {}
+}
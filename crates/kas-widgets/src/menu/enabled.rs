@@ -0,0 +1,82 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Enabled-state binding
+
+use kas::event::UpdateHandle;
+use kas::prelude::*;
+use kas::updatable::{SingleData, Updatable};
+use std::rc::Rc;
+
+trait BoolData: SingleData<Item = bool> + Updatable {}
+impl<T: SingleData<Item = bool> + Updatable> BoolData for T {}
+
+/// Binding of a widget's enabled state to shared data
+///
+/// The data (e.g. a [`kas::updatable::SharedRc<bool>`]) should be `true` when
+/// the widget is enabled. Where the data has an [`UpdateHandle`], bound widgets
+/// are updated when this handle is triggered (e.g. after
+/// [`SingleData::update`]).
+///
+/// Widgets using this binding (see [`super::MenuEntry::with_enabled`]) are
+/// disabled while the data is `false`: they are drawn greyed, do not respond to
+/// input and do not register accelerator keys.
+///
+/// This type is a shared reference: the same binding may be used by multiple
+/// widgets, e.g. a menu entry and a toolbar button for the same command.
+#[derive(Clone, Debug)]
+pub struct EnabledBinding(Rc<dyn BoolData>);
+
+impl EnabledBinding {
+    /// Construct over shared data
+    pub fn new<T: SingleData<Item = bool> + Updatable + 'static>(data: T) -> Self {
+        EnabledBinding(Rc::new(data))
+    }
+
+    /// Get the current enabled state
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.0.get_cloned()
+    }
+
+    /// Get the update handle, if any
+    #[inline]
+    pub fn update_handle(&self) -> Option<UpdateHandle> {
+        self.0.update_handle()
+    }
+
+    /// Configure a bound widget
+    ///
+    /// This requests updates for widget `id` on the data's [`UpdateHandle`]
+    /// and returns the action required to apply the current state.
+    pub fn configure<W: WidgetCore + ?Sized>(&self, mgr: &mut Manager, widget: &mut W) -> TkAction {
+        if let Some(handle) = self.update_handle() {
+            mgr.update_on_handle(handle, widget.id());
+        }
+        widget.set_disabled(!self.is_enabled())
+    }
+
+    /// Handle an update
+    ///
+    /// Returns true if `handle` belongs to this binding. Where the enabled
+    /// state changed, the widget is updated and (to update accelerator keys)
+    /// reconfigured.
+    pub fn handle_update<W: WidgetCore + ?Sized>(
+        &self,
+        mgr: &mut Manager,
+        widget: &mut W,
+        handle: UpdateHandle,
+    ) -> bool {
+        if self.update_handle() != Some(handle) {
+            return false;
+        }
+        let disabled = !self.is_enabled();
+        if disabled != widget.is_disabled() {
+            *mgr |= widget.set_disabled(disabled);
+            mgr.reconfigure_subtree(widget.id());
+        }
+        true
+    }
+}
@@ -5,12 +5,66 @@
 
 //! Menu Entries
 
-use super::Menu;
+use super::{EnabledBinding, Menu};
 use crate::{AccelLabel, CheckBoxBare};
 use kas::draw::TextClass;
-use kas::{layout, prelude::*};
+use kas::event::{Command, Shortcuts};
+use kas::{event, layout, prelude::*};
 use std::fmt::Debug;
 
+/// Shortcut text, displayed right-aligned by menu entries
+#[derive(Clone, Debug, Default)]
+pub(super) struct ShortcutText {
+    cmd: Option<Command>,
+    text: Text<String>,
+    store: layout::TextStorage,
+}
+
+impl ShortcutText {
+    /// Display the shortcut bound to `cmd`
+    pub fn set_command(&mut self, cmd: Command) {
+        self.cmd = Some(cmd);
+    }
+
+    /// Display fixed `text`
+    pub fn set_text(&mut self, text: String) {
+        self.cmd = None;
+        self.text = Text::new_single(text);
+    }
+
+    /// Update text from the bound command, if any
+    pub fn configure(&mut self, mgr: &mut Manager) {
+        if let Some(cmd) = self.cmd {
+            let text = mgr
+                .config()
+                .shortcuts()
+                .find(cmd)
+                .map(|(modifiers, vkey)| Shortcuts::describe(modifiers, vkey))
+                .unwrap_or_default();
+            if *self.text.text() != text {
+                let avail = Size::ZERO; // we are not yet sized; force a resize
+                *mgr |= kas::text::util::set_text_and_prepare(&mut self.text, text, avail);
+            }
+        }
+    }
+
+    /// Make a layout, placing the text at the end of the available space
+    pub fn layout(&mut self) -> layout::Layout<'_> {
+        let text = layout::Layout::text(&mut self.store, &mut self.text, TextClass::MenuLabel);
+        layout::Layout::align(text, AlignHints::new(Some(Align::BR), None))
+    }
+
+    /// Draw the text
+    pub fn draw(&mut self, draw: &mut dyn DrawHandle, state: InputState) {
+        draw.text(
+            self.store.pos,
+            self.text.as_ref(),
+            TextClass::MenuLabel,
+            state,
+        );
+    }
+}
+
 widget! {
     /// A standard menu entry
     #[derive(Clone, Debug, Default)]
@@ -19,13 +73,26 @@ widget! {
         core: kas::CoreData,
         label: Text<AccelString>,
         layout_label: layout::TextStorage,
+        shortcut: ShortcutText,
+        layout_row: layout::FixedRowStorage<2>,
         layout_frame: layout::FrameStorage,
         msg: M,
+        enabled: Option<EnabledBinding>,
     }
 
     impl WidgetConfig for Self {
         fn configure(&mut self, mgr: &mut Manager) {
-            mgr.add_accel_keys(self.id(), self.label.text().keys());
+            if let Some(binding) = self.enabled.clone() {
+                let action = binding.configure(mgr, self);
+                *mgr |= action;
+            }
+            if !self.is_disabled() {
+                if let Some(string) = mgr.add_accel_label(self.id(), self.label.text()) {
+                    let avail = self.core.rect.size.clamped_sub(self.layout_frame.size);
+                    *mgr |= kas::text::util::set_text_and_prepare(&mut self.label, string, avail);
+                }
+            }
+            self.shortcut.configure(mgr);
         }
 
         fn key_nav(&self) -> bool {
@@ -35,19 +102,24 @@ widget! {
 
     impl Layout for Self {
         fn layout(&mut self) -> layout::Layout<'_> {
-            let inner = layout::Layout::text(&mut self.layout_label, &mut self.label, TextClass::MenuLabel);
+            let label = &mut self.label;
+            let label = layout::Layout::text(&mut self.layout_label, label, TextClass::MenuLabel);
+            let list = [label, self.shortcut.layout()].into_iter();
+            let inner = layout::Layout::list(list, Direction::Right, &mut self.layout_row);
             layout::Layout::frame(&mut self.layout_frame, inner)
         }
 
         fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
-            draw.menu_entry(self.core.rect, self.input_state(mgr, disabled));
+            let state = self.input_state(mgr, disabled);
+            draw.menu_entry(self.core.rect, state);
             draw.text_accel(
                 self.layout_label.pos,
                 &self.label,
                 mgr.show_accel_labels(),
                 TextClass::MenuLabel,
-                self.input_state(mgr, disabled),
+                state,
             );
+            self.shortcut.draw(draw, state);
         }
     }
 
@@ -62,11 +134,41 @@ widget! {
                 core: Default::default(),
                 label: Text::new_single(label.into()),
                 layout_label: Default::default(),
+                shortcut: Default::default(),
+                layout_row: Default::default(),
                 layout_frame: Default::default(),
                 msg,
+                enabled: None,
             }
         }
 
+        /// Display the shortcut bound to `cmd`, builder style
+        ///
+        /// The key combination bound to `cmd` in the shortcut configuration
+        /// (if any) is shown at the end of the entry. Note that this does not
+        /// bind `cmd` to this entry; the application should handle `cmd`.
+        pub fn with_shortcut(mut self, cmd: Command) -> Self {
+            self.shortcut.set_command(cmd);
+            self
+        }
+
+        /// Display fixed shortcut text, builder style
+        ///
+        /// The `text` (e.g. `"Ctrl+S"`) is shown at the end of the entry.
+        pub fn with_shortcut_text<S: ToString>(mut self, text: S) -> Self {
+            self.shortcut.set_text(text.to_string());
+            self
+        }
+
+        /// Bind enabled state, builder style
+        ///
+        /// The entry is disabled while the `binding` is false.
+        /// See [`EnabledBinding`].
+        pub fn with_enabled(mut self, binding: EnabledBinding) -> Self {
+            self.enabled = Some(binding);
+            self
+        }
+
         /// Replace the message value
         pub fn set_msg(&mut self, msg: M) {
             self.msg = msg;
@@ -81,6 +183,7 @@ widget! {
 
     impl SetAccel for Self {
         fn set_accel_string(&mut self, string: AccelString) -> TkAction {
+            self.core.bump_generation();
             let mut action = TkAction::empty();
             if self.label.text().keys() != string.keys() {
                 action |= TkAction::RECONFIGURE;
@@ -101,6 +204,25 @@ widget! {
         }
     }
 
+    impl event::SendEvent for Self {
+        fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            // Updates to the binding must be received while disabled
+            if let Event::HandleUpdate { handle, .. } = event {
+                if let Some(binding) = self.enabled.clone() {
+                    if binding.handle_update(mgr, self, handle) {
+                        return Response::None;
+                    }
+                }
+            }
+
+            if self.is_disabled() {
+                return Response::Unhandled;
+            }
+            debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
+            Manager::handle_generic(self, mgr, event)
+        }
+    }
+
     impl Menu for Self {}
 }
 
@@ -121,7 +243,7 @@ widget! {
 
     impl WidgetConfig for Self {
         fn configure(&mut self, mgr: &mut Manager) {
-            mgr.add_accel_keys(self.checkbox.id(), self.label.keys());
+            self.label.add_accel_label(mgr, self.checkbox.id());
         }
     }
 
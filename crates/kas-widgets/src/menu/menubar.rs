@@ -7,7 +7,7 @@
 
 use super::{Menu, SubMenu};
 use crate::IndexedList;
-use kas::event::{self, Command, GrabMode};
+use kas::event::{self, GrabMode};
 use kas::prelude::*;
 
 widget! {
@@ -148,27 +148,26 @@ widget! {
                 Event::Command(cmd, _) => {
                     // Arrow keys can switch to the next / previous menu
                     // as well as to the first / last item of an open menu.
-                    use Command::{Left, Up};
-                    let is_vert = self.bar.direction().is_vertical();
-                    let reverse = self.bar.direction().is_reversed() ^ matches!(cmd, Left | Up);
-                    match cmd.as_direction().map(|d| d.is_vertical()) {
-                        Some(v) if v == is_vert => {
-                            for i in 0..self.bar.len() {
-                                if self.bar[i].menu_is_open() {
-                                    let mut j = isize::conv(i);
-                                    j = if reverse { j - 1 } else { j + 1 };
-                                    j = j.rem_euclid(self.bar.len().cast());
-                                    self.bar[i].set_menu_path(mgr, None, true);
-                                    let w = &mut self.bar[usize::conv(j)];
-                                    w.set_menu_path(mgr, Some(w.id()), true);
-                                    break;
-                                }
+                    // Keys are mapped to logical directions (for RTL layout).
+                    let dir = match cmd.as_direction() {
+                        Some(dir) => mgr.layout_direction().apply(dir),
+                        None => return Response::Unhandled,
+                    };
+                    let reverse = self.bar.direction().is_reversed() ^ dir.is_reversed();
+                    if dir.is_vertical() == self.bar.direction().is_vertical() {
+                        for i in 0..self.bar.len() {
+                            if self.bar[i].menu_is_open() {
+                                let mut j = isize::conv(i);
+                                j = if reverse { j - 1 } else { j + 1 };
+                                j = j.rem_euclid(self.bar.len().cast());
+                                self.bar[i].set_menu_path(mgr, None, true);
+                                let w = &mut self.bar[usize::conv(j)];
+                                w.set_menu_path(mgr, Some(w.id()), true);
+                                break;
                             }
                         }
-                        Some(_) => {
-                            mgr.next_nav_focus(self, reverse, true);
-                        }
-                        None => return Response::Unhandled,
+                    } else {
+                        mgr.next_nav_focus(self, reverse, true);
                     }
                 }
                 _ => return Response::Unhandled,
@@ -0,0 +1,74 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Labelled menu separator
+
+use super::Menu;
+use crate::Separator;
+use kas::draw::TextClass;
+use kas::{layout, prelude::*};
+use std::fmt::Debug;
+
+widget! {
+    /// A separator with a section label
+    ///
+    /// This widget draws a bar above a (non-interactive) label. It may be used
+    /// to divide a menu into named sections.
+    #[derive(Clone, Debug, Default)]
+    #[handler(msg=M)]
+    pub struct MenuSection<M: Debug + 'static> {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        bar: Separator<M>,
+        label: Text<String>,
+        layout_label: layout::TextStorage,
+        layout_col: layout::FixedRowStorage<2>,
+    }
+
+    impl Self {
+        /// Construct with the given section `label`
+        #[inline]
+        pub fn new<S: ToString>(label: S) -> Self {
+            MenuSection {
+                core: Default::default(),
+                bar: Separator::infer(),
+                label: Text::new_single(label.to_string()),
+                layout_label: Default::default(),
+                layout_col: Default::default(),
+            }
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> layout::Layout<'_> {
+            let bar = layout::Layout::single(&mut self.bar);
+            let label = &mut self.label;
+            let label = layout::Layout::text(&mut self.layout_label, label, TextClass::MenuLabel);
+            let list = [bar, label].into_iter();
+            layout::Layout::list(list, Direction::Down, &mut self.layout_col)
+        }
+
+        fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
+            self.rect().contains(coord).then(|| self.id())
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            self.bar.draw(draw, mgr, disabled);
+            let pos = self.layout_label.pos;
+            let state = InputState::DISABLED;
+            draw.text(pos, self.label.as_ref(), TextClass::MenuLabel, state);
+        }
+    }
+
+    impl HasStr for Self {
+        fn get_str(&self) -> &str {
+            self.label.as_str()
+        }
+    }
+
+    /// A section separator is a valid menu widget
+    impl Menu for Self {}
+}
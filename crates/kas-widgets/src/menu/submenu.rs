@@ -90,8 +90,10 @@ widget! {
         }
 
         fn handle_dir_key(&mut self, mgr: &mut Manager, cmd: Command) -> Response<W::Msg> {
+            // Keys are mapped to logical directions (for RTL layout)
+            let cmd_dir = cmd.as_direction().map(|d| mgr.layout_direction().apply(d));
             if self.menu_is_open() {
-                if let Some(dir) = cmd.as_direction() {
+                if let Some(dir) = cmd_dir {
                     if dir.is_vertical() == self.list.direction().is_vertical() {
                         let rev = dir.is_reversed() ^ self.list.direction().is_reversed();
                         mgr.next_nav_focus(self, rev, true);
@@ -110,7 +112,7 @@ widget! {
                 } else {
                     Response::Unhandled
                 }
-            } else if Some(self.direction.as_direction()) == cmd.as_direction() {
+            } else if Some(self.direction.as_direction()) == cmd_dir {
                 self.open_menu(mgr, true);
                 Response::None
             } else {
@@ -124,9 +126,13 @@ widget! {
             cmgr.mgr().push_accel_layer(true);
             self.list.configure_recurse(cmgr.child());
             self.core_data_mut().id = cmgr.next_id(self.id());
+            self.core_data_mut().bump_generation();
             let mgr = cmgr.mgr();
             mgr.pop_accel_layer(self.id());
-            mgr.add_accel_keys(self.id(), self.label.text().keys());
+            if let Some(string) = mgr.add_accel_label(self.id(), self.label.text()) {
+                let avail = self.core.rect.size.clamped_sub(self.frame_store.size);
+                *mgr |= kas::text::util::set_text_and_prepare(&mut self.label, string, avail);
+            }
         }
 
         fn key_nav(&self) -> bool {
@@ -175,6 +181,7 @@ widget! {
                     debug_assert_eq!(Some(id), self.popup_id);
                     self.popup_id = None;
                 }
+                Event::DisabledChanged(true) => self.close_menu(mgr, false),
                 Event::Command(cmd, _) => return self.handle_dir_key(mgr, cmd),
                 _ => return Response::Unhandled,
             }
@@ -195,6 +202,7 @@ widget! {
                     Response::None => Response::None,
                     Response::Pan(delta) => Response::Pan(delta),
                     Response::Focus(rect) => Response::Focus(rect),
+                    Response::Reveal(rect, align) => Response::Reveal(rect, align),
                     Response::Unhandled => match event {
                         Event::Command(cmd, _) if self.popup_id.is_some() => {
                             self.handle_dir_key(mgr, cmd)
@@ -209,6 +217,7 @@ widget! {
                         self.close_menu(mgr, true);
                         r
                     }
+                    r => r,
                 }
             } else {
                 Manager::handle_generic(self, mgr, event)
@@ -266,6 +275,7 @@ widget! {
 
     impl SetAccel for Self {
         fn set_accel_string(&mut self, string: AccelString) -> TkAction {
+            self.core.bump_generation();
             let mut action = TkAction::empty();
             if self.label.text().keys() != string.keys() {
                 action |= TkAction::RECONFIGURE;
@@ -0,0 +1,235 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Native child surface placeholder
+
+use kas::event::{self, Command, GrabMode, PressSource, ScrollDelta, ScrollSource};
+use kas::geom::Vec2;
+use kas::prelude::*;
+use kas::raw_window_handle::RawWindowHandle;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Geometry of a [`NativeSurface`]
+///
+/// All values are in physical pixels relative to the window's client area.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfaceGeometry {
+    /// The full region reserved for the surface
+    pub rect: Rect,
+    /// The visible part of `rect`
+    ///
+    /// This is smaller than `rect` when the surface is partially scrolled out
+    /// of view or otherwise clipped, and has zero size when fully clipped.
+    /// The native surface should be clipped to this region.
+    pub visible: Rect,
+    /// The window's scale factor
+    pub scale_factor: f32,
+}
+
+#[derive(Debug, Default)]
+struct SurfaceState {
+    window: Option<RawWindowHandle>,
+    geometry: Option<SurfaceGeometry>,
+    changed: bool,
+}
+
+/// Shared handle to the state of a [`NativeSurface`]
+///
+/// This is obtained from [`NativeSurface::handle`] and may be kept by the
+/// application (it is cheap to clone) in order to position its native surface.
+/// Note that the handle is not thread-safe; the surface must be managed from
+/// the UI thread.
+#[derive(Clone, Debug, Default)]
+pub struct NativeSurfaceHandle(Rc<RefCell<SurfaceState>>);
+
+impl NativeSurfaceHandle {
+    /// Get the handle of the parent window
+    ///
+    /// This is `None` before the widget is configured or when the shell does
+    /// not support [`kas::ShellCapabilities::NATIVE_SURFACES`].
+    pub fn raw_window_handle(&self) -> Option<RawWindowHandle> {
+        self.0.borrow().window
+    }
+
+    /// Get the current geometry
+    ///
+    /// This is `None` before the widget is first drawn.
+    pub fn geometry(&self) -> Option<SurfaceGeometry> {
+        self.0.borrow().geometry
+    }
+
+    /// Check for changes
+    ///
+    /// Returns true (once) if the parent window or geometry has changed since
+    /// the last call. Applications should call this after each frame and
+    /// reposition their surface when it returns true.
+    pub fn take_changed(&self) -> bool {
+        std::mem::take(&mut self.0.borrow_mut().changed)
+    }
+
+    fn set_window(&self, window: Option<RawWindowHandle>) {
+        let mut state = self.0.borrow_mut();
+        if state.window != window {
+            state.window = window;
+            state.changed = true;
+        }
+    }
+
+    fn set_geometry(&self, geometry: SurfaceGeometry) {
+        let mut state = self.0.borrow_mut();
+        if state.geometry != Some(geometry) {
+            state.geometry = Some(geometry);
+            state.changed = true;
+        }
+    }
+}
+
+/// Input received over a [`NativeSurface`]
+///
+/// Positions are in physical pixels relative to the surface's top-left corner.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SurfaceInput {
+    /// Start of a mouse click or touch
+    PressStart { source: PressSource, pos: Offset },
+    /// Movement of a press (mouse is grabbed until the press ends)
+    PressMove { source: PressSource, pos: Offset },
+    /// End of a press
+    PressEnd { source: PressSource, pos: Offset },
+    /// Mouse movement without a press
+    CursorMove { pos: Offset },
+    /// Scroll wheel or touchpad scroll
+    Scroll(ScrollDelta, ScrollSource),
+    /// Text input
+    ReceivedCharacter(char),
+    /// Keyboard command (with shift state)
+    Command(Command, bool),
+    /// The surface lost keyboard focus
+    FocusLost,
+}
+
+widget! {
+    /// A region reserved for a native child surface
+    ///
+    /// This widget allows embedding of content rendered outside of KAS, e.g. by
+    /// a video player, web view or 3D engine using its own swapchain. It
+    /// reserves space within the layout but draws nothing; the application is
+    /// expected to create a native child surface of the parent window (see
+    /// [`NativeSurfaceHandle::raw_window_handle`]) and keep it positioned at
+    /// [`NativeSurfaceHandle::geometry`].
+    ///
+    /// The geometry is updated each time the widget is drawn, accounting for
+    /// scroll offsets, zoom and clip regions. When the widget is not drawn
+    /// (e.g. it is on a hidden page of a [`crate::Stack`]) the geometry is not
+    /// updated; the application is responsible for hiding the surface.
+    ///
+    /// Where the platform delivers input over the surface to the parent window,
+    /// this widget forwards it as [`SurfaceInput`] messages.
+    #[derive(Debug)]
+    #[widget{
+        key_nav = true;
+    }]
+    pub struct NativeSurface {
+        #[widget_core]
+        core: CoreData,
+        min_size: Vec2,
+        ideal_size: Vec2,
+        handle: NativeSurfaceHandle,
+    }
+
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            self.handle.set_window(mgr.raw_window_handle());
+        }
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+            let (min, ideal) = match axis.is_horizontal() {
+                true => (self.min_size.0, self.ideal_size.0),
+                false => (self.min_size.1, self.ideal_size.1),
+            };
+            let sf = size_handle.scale_factor();
+            SizeRules::new_scaled(min, ideal, 0.0, Stretch::High, sf)
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
+            let scale_factor = draw.size_handle().scale_factor();
+            let device = draw.draw_device();
+            let transform = device.get_transform();
+            let rect = transform.map_rect(self.rect());
+            let visible = match device.get_clip_rect().intersection(&self.rect()) {
+                Some(visible) => transform.map_rect(visible),
+                None => Rect::new(rect.pos, Size::ZERO),
+            };
+            self.handle.set_geometry(SurfaceGeometry {
+                rect,
+                visible,
+                scale_factor,
+            });
+        }
+    }
+
+    impl event::Handler for Self {
+        type Msg = SurfaceInput;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+            let origin = self.rect().pos;
+            let input = match event {
+                Event::PressStart { source, coord, .. } => {
+                    mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None);
+                    mgr.request_char_focus(self.id());
+                    let pos = coord - origin;
+                    SurfaceInput::PressStart { source, pos }
+                }
+                Event::PressMove { source, coord, .. } => {
+                    let pos = coord - origin;
+                    SurfaceInput::PressMove { source, pos }
+                }
+                Event::PressEnd { source, coord, .. } => {
+                    let pos = coord - origin;
+                    SurfaceInput::PressEnd { source, pos }
+                }
+                Event::CursorMove { coord } => SurfaceInput::CursorMove { pos: coord - origin },
+                Event::Scroll(delta) => SurfaceInput::Scroll(delta, mgr.scroll_source()),
+                Event::ReceivedCharacter(c) => SurfaceInput::ReceivedCharacter(c),
+                Event::Command(cmd, shift) => SurfaceInput::Command(cmd, shift),
+                Event::LostCharFocus => SurfaceInput::FocusLost,
+                _ => return Response::Unhandled,
+            };
+            Response::Msg(input)
+        }
+    }
+}
+
+impl NativeSurface {
+    /// Construct, reserving the given size
+    ///
+    /// The size is in logical pixels and is used both as the minimum and the
+    /// ideal size; the widget stretches to fill available space.
+    #[inline]
+    pub fn new(size: Vec2) -> Self {
+        Self::new_min_ideal(size, size)
+    }
+
+    /// Construct with a minimum and ideal size (logical pixels)
+    #[inline]
+    pub fn new_min_ideal(min_size: Vec2, ideal_size: Vec2) -> Self {
+        NativeSurface {
+            core: Default::default(),
+            min_size,
+            ideal_size,
+            handle: Default::default(),
+        }
+    }
+
+    /// Get a handle to the surface state
+    ///
+    /// This handle may be used to position the native surface.
+    #[inline]
+    pub fn handle(&self) -> NativeSurfaceHandle {
+        self.handle.clone()
+    }
+}
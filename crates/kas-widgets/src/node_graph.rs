@@ -0,0 +1,815 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Node-graph editor
+
+use std::ops::{Index, IndexMut};
+
+use crate::zoom::map_event;
+use kas::event::{self, CursorIcon, GrabMode, PressSource, ScrollDelta};
+use kas::geom::{Transform, Vec2};
+use kas::prelude::*;
+
+/// Number of line segments used to approximate each link
+const LINK_SEGMENTS: usize = 24;
+
+/// A link from an output port to an input port
+///
+/// Ports are identified by `(node, port)` index pairs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Link {
+    /// Node and output port index
+    pub from: (usize, usize),
+    /// Node and input port index
+    pub to: (usize, usize),
+}
+
+/// Message type of [`NodeGraph`]
+#[derive(Clone, Debug)]
+pub enum NodeGraphMsg<M> {
+    /// A message from the node at the given index
+    Node(usize, M),
+    /// The user created a link
+    ///
+    /// Each input port accepts at most one link; any other link to the same
+    /// input has been removed (without a [`NodeGraphMsg::Disconnect`]).
+    Connect(Link),
+    /// The user removed a link
+    Disconnect(Link),
+    /// The user moved nodes: a list of `(node, new position)` pairs
+    Move(Vec<(usize, Coord)>),
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    pos: Coord,
+    size: Size,
+    inputs: usize,
+    outputs: usize,
+    selected: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Port {
+    Input(usize, usize),
+    Output(usize, usize),
+}
+
+#[derive(Clone, Debug)]
+enum Drag {
+    // Moving the selected nodes; origins are positions at start
+    Nodes {
+        source: PressSource,
+        start: Coord,
+        origins: Vec<(usize, Coord)>,
+        active: bool,
+    },
+    // Creating a link from an output port to the pointer
+    Link {
+        source: PressSource,
+        from: (usize, usize),
+        coord: Coord,
+    },
+    // Rubber-band selection; initial is the selection to add to
+    Band {
+        source: PressSource,
+        start: Coord,
+        end: Coord,
+        initial: Vec<bool>,
+    },
+}
+
+impl Drag {
+    fn source(&self) -> PressSource {
+        match self {
+            Drag::Nodes { source, .. } | Drag::Link { source, .. } | Drag::Band { source, .. } => {
+                *source
+            }
+        }
+    }
+}
+
+widget! {
+    /// A node-graph editor
+    ///
+    /// Nodes are arbitrary child widgets placed at user-draggable positions on
+    /// an unbounded canvas, each with a number of input ports (on its left
+    /// edge) and output ports (on its right edge). Links connect an output
+    /// port to an input port of another node; each input accepts at most one
+    /// link.
+    ///
+    /// Nodes are sized to their ideal size. The canvas may be panned by
+    /// dragging with touch or a non-primary mouse button and zoomed via the
+    /// mouse wheel or pinch gestures (see [`ZoomView`](crate::ZoomView)).
+    ///
+    /// With the primary mouse button or touch:
+    ///
+    /// -   dragging a node moves all selected nodes (clicking a node selects
+    ///     it; <kbd>Ctrl</kbd>+click toggles selection)
+    /// -   dragging from an output port to an input port creates a link
+    /// -   dragging from a linked input port detaches the link, which may be
+    ///     dropped on another input or on empty space to remove it
+    /// -   dragging the mouse over empty space selects nodes with a rubber
+    ///     band (adding to the selection with <kbd>Shift</kbd> or
+    ///     <kbd>Ctrl</kbd>)
+    ///
+    /// The graph updates itself and reports these changes via
+    /// [`NodeGraphMsg`]; node messages are wrapped as
+    /// [`NodeGraphMsg::Node`].
+    ///
+    /// Configuring and resizing elements is O(n) in the number of nodes.
+    /// Drawing is O(n + m) in the number of nodes and links.
+    #[derive(Clone, Debug)]
+    pub struct NodeGraph<W: Widget> {
+        first_id: WidgetId,
+        #[widget_core]
+        core: CoreData,
+        widgets: Vec<W>,
+        nodes: Vec<Node>,
+        links: Vec<Link>,
+        transform: Transform,
+        limits: (f32, f32),
+        port_size: i32,
+        drag: Option<Drag>,
+        // Last known pointer position, used as the centre of wheel zoom
+        cursor: Option<Coord>,
+    }
+
+    impl WidgetChildren for Self {
+        #[inline]
+        fn first_id(&self) -> WidgetId {
+            self.first_id
+        }
+        fn record_first_id(&mut self, id: WidgetId) {
+            self.first_id = id;
+        }
+        #[inline]
+        fn num_children(&self) -> usize {
+            self.widgets.len()
+        }
+        #[inline]
+        fn get_child(&self, index: usize) -> Option<&dyn WidgetConfig> {
+            self.widgets.get(index).map(|w| w.as_widget())
+        }
+        #[inline]
+        fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+            self.widgets.get_mut(index).map(|w| w.as_widget_mut())
+        }
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+            self.port_size = i32::conv_nearest(size_handle.pixels_from_em(0.6));
+            for (w, node) in self.widgets.iter_mut().zip(self.nodes.iter_mut()) {
+                let rules = w.size_rules(size_handle, axis);
+                node.size.set_component(axis, rules.ideal_size());
+            }
+            let sf = size_handle.scale_factor();
+            SizeRules::new_scaled(100.0, 400.0, 0.0, Stretch::High, sf)
+        }
+
+        fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, _: AlignHints) {
+            self.core.rect = rect;
+            for index in 0..self.widgets.len() {
+                self.place_node(mgr, index);
+            }
+        }
+
+        fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
+            if !self.rect().contains(coord) {
+                return None;
+            }
+            let coord = self.transform.inverse().map_coord(coord);
+            if self.port_at(coord).is_some() {
+                return Some(self.id());
+            }
+            // Later nodes are drawn on top
+            for w in self.widgets.iter_mut().rev() {
+                if let Some(id) = w.find_id(coord) {
+                    return Some(id);
+                }
+            }
+            Some(self.id())
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let disabled = disabled || self.is_disabled();
+            let state = match disabled {
+                true => InputState::DISABLED,
+                false => InputState::empty(),
+            };
+            let visible = self.transform.inverse().map_rect(self.core.rect);
+
+            let mut links: Vec<_> = self
+                .links
+                .iter()
+                .map(|link| {
+                    let from = self.port_centre(Port::Output(link.from.0, link.from.1));
+                    let to = self.port_centre(Port::Input(link.to.0, link.to.1));
+                    (self.link_path(from, to), false)
+                })
+                .collect();
+            let mut band = None;
+            match self.drag {
+                Some(Drag::Link { from, coord, .. }) => {
+                    let from = self.port_centre(Port::Output(from.0, from.1));
+                    links.push((self.link_path(from, coord.into()), true));
+                }
+                Some(Drag::Band { start, end, .. }) => band = Some(band_rect(start, end)),
+                _ => (),
+            }
+            let ports: Vec<_> = self
+                .ports()
+                .map(|port| (port, self.port_rect(port), self.is_connected(port)))
+                .collect();
+
+            let port_size = self.port_size;
+            let (widgets, nodes) = (&mut self.widgets, &self.nodes);
+            draw.with_transform(self.core.rect, self.transform, &mut |draw| {
+                for (path, pending) in &links {
+                    draw.graph_link(path, *pending);
+                }
+
+                // Each node is drawn in its own pass, over links and
+                // earlier nodes.
+                for (index, (w, node)) in widgets.iter_mut().zip(nodes.iter()).enumerate() {
+                    let rect = w.rect().expand(port_size);
+                    if rect.intersection(&visible).is_none() {
+                        continue;
+                    }
+                    draw.with_clip_region(visible, Offset::ZERO, &mut |draw| {
+                        draw.graph_node(w.rect(), node.selected);
+                        w.draw(draw, mgr, disabled);
+                        for (_, rect, connected) in ports
+                            .iter()
+                            .filter(|(port, _, _)| port.node() == index)
+                        {
+                            draw.graph_port(*rect, *connected, state);
+                        }
+                    });
+                }
+
+                if let Some(rect) = band {
+                    draw.with_clip_region(visible, Offset::ZERO, &mut |draw| {
+                        draw.rubber_band(rect)
+                    });
+                }
+            });
+        }
+    }
+
+    impl event::Handler for Self {
+        type Msg = NodeGraphMsg<<W as event::Handler>::Msg>;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+            match event {
+                Event::PressStart { source, coord, .. } => self.press_start(mgr, source, coord),
+                Event::PressMove { source, coord, .. }
+                    if self.drag.as_ref().map(|drag| drag.source()) == Some(source) =>
+                {
+                    self.cursor = Some(coord);
+                    self.press_move(mgr, coord);
+                    Response::None
+                }
+                Event::PressEnd { source, coord, .. }
+                    if self.drag.as_ref().map(|drag| drag.source()) == Some(source) =>
+                {
+                    self.press_end(mgr, coord)
+                }
+                Event::PressEnd { .. } => Response::None,
+                Event::Gesture(gesture) => {
+                    let translation = Transform::translation(gesture.translation.into());
+                    let mut action = self.set_transform(self.transform.then(translation));
+                    action |= self.zoom_about(gesture.scale as f32, gesture.focus.into());
+                    *mgr |= action;
+                    Response::None
+                }
+                Event::Scroll(delta) => {
+                    let factor = match delta {
+                        ScrollDelta::LineDelta(_, y) => 1.1f32.powf(y),
+                        ScrollDelta::PixelDelta(d) => 1.1f32.powf((d.1 / 20.0) as f32),
+                    };
+                    let rect = self.core.rect;
+                    let centre = match self.cursor {
+                        Some(coord) => Vec2::from(coord),
+                        None => Vec2::from(rect.pos) + Vec2::from(rect.size) * 0.5,
+                    };
+                    *mgr |= self.zoom_about(factor, centre);
+                    Response::None
+                }
+                Event::CursorMove { coord } => {
+                    self.cursor = Some(coord);
+                    Response::None
+                }
+                _ => Response::Unhandled,
+            }
+        }
+    }
+
+    impl event::SendEvent for Self {
+        fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            if self.is_disabled() {
+                return Response::Unhandled;
+            }
+
+            if id < self.id() {
+                let inverse = self.transform.inverse();
+                let child_event = map_event(inverse, event.clone());
+                let self_id = self.id();
+                let f = |_: &mut Manager, i, msg| Response::Msg(NodeGraphMsg::Node(i, msg));
+                let r = mgr.dispatch_to_child(self_id, &mut self.widgets, id, child_event, f);
+                match r {
+                    None | Some(Response::Unhandled) => (),
+                    Some(Response::Focus(rect)) => {
+                        return Response::Focus(self.transform.map_rect(rect));
+                    }
+                    Some(Response::Reveal(rect, align)) => {
+                        return Response::Reveal(self.transform.map_rect(rect), align);
+                    }
+                    Some(r) => return r,
+                }
+
+                // Presses not handled by a node select and drag it
+                if let Event::PressStart { source, coord, .. } = event {
+                    if let Some(index) = self.find_child_index(id) {
+                        return self.press_node(mgr, index, source, coord);
+                    }
+                }
+            } else {
+                debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
+            }
+
+            self.handle(mgr, event)
+        }
+    }
+
+    impl Index<usize> for Self {
+        type Output = W;
+
+        fn index(&self, index: usize) -> &Self::Output {
+            &self.widgets[index]
+        }
+    }
+
+    impl IndexMut<usize> for Self {
+        fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+            &mut self.widgets[index]
+        }
+    }
+}
+
+impl<W: Widget> Default for NodeGraph<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Widget> NodeGraph<W> {
+    /// Construct an empty graph, with scale limits `0.25..=4.0`
+    pub fn new() -> Self {
+        NodeGraph {
+            first_id: Default::default(),
+            core: Default::default(),
+            widgets: vec![],
+            nodes: vec![],
+            links: vec![],
+            transform: Transform::IDENTITY,
+            limits: (0.25, 4.0),
+            port_size: 0,
+            drag: None,
+            cursor: None,
+        }
+    }
+
+    /// Add a node, builder style
+    ///
+    /// The node is placed at `pos` relative to the canvas origin and has
+    /// the given number of `inputs` and `outputs` ports.
+    pub fn with_node(mut self, widget: W, pos: Coord, inputs: usize, outputs: usize) -> Self {
+        let _ = self.push_node(widget, pos, inputs, outputs);
+        self
+    }
+
+    /// Add a link, builder style
+    pub fn with_link(mut self, link: Link) -> Self {
+        let _ = self.connect(link);
+        self
+    }
+
+    /// Set the minimum and maximum scale factors, builder style
+    #[inline]
+    pub fn with_limits(mut self, min: f32, max: f32) -> Self {
+        assert!(0.0 < min && min <= max);
+        self.limits = (min, max);
+        self.transform.scale = self.transform.scale.clamp(min, max);
+        self
+    }
+
+    /// True if there are no nodes
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Returns the number of nodes
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    /// Append a node
+    ///
+    /// See [`NodeGraph::with_node`].
+    /// Triggers a [reconfigure action](Manager::send_action).
+    pub fn push_node(&mut self, widget: W, pos: Coord, inputs: usize, outputs: usize) -> TkAction {
+        self.widgets.push(widget);
+        self.nodes.push(Node {
+            pos,
+            size: Size::ZERO,
+            inputs,
+            outputs,
+            selected: false,
+        });
+        TkAction::RECONFIGURE
+    }
+
+    /// Remove the node at `index`
+    ///
+    /// Links to and from this node are removed; indices of later nodes are
+    /// decremented (including within links).
+    ///
+    /// Panics if `index` is out of bounds.
+    /// Triggers a [reconfigure action](Manager::send_action).
+    pub fn remove_node(&mut self, index: usize) -> (W, TkAction) {
+        let w = self.widgets.remove(index);
+        self.nodes.remove(index);
+        self.links
+            .retain(|link| link.from.0 != index && link.to.0 != index);
+        for link in &mut self.links {
+            if link.from.0 > index {
+                link.from.0 -= 1;
+            }
+            if link.to.0 > index {
+                link.to.0 -= 1;
+            }
+        }
+        self.drag = None;
+        (w, TkAction::RECONFIGURE)
+    }
+
+    /// Get the position of the node at `index` on the canvas
+    pub fn node_pos(&self, index: usize) -> Coord {
+        self.nodes[index].pos
+    }
+
+    /// Set the position of the node at `index` on the canvas
+    pub fn set_node_pos(&mut self, index: usize, pos: Coord) -> TkAction {
+        self.nodes[index].pos = pos;
+        TkAction::SET_SIZE
+    }
+
+    /// Get the list of links
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    /// Add a link
+    ///
+    /// Any existing link to the same input port is removed.
+    pub fn connect(&mut self, link: Link) -> TkAction {
+        debug_assert!(link.from.1 < self.nodes[link.from.0].outputs);
+        debug_assert!(link.to.1 < self.nodes[link.to.0].inputs);
+        self.links.retain(|l| l.to != link.to);
+        self.links.push(link);
+        TkAction::REDRAW
+    }
+
+    /// Remove a link
+    ///
+    /// Returns true if the link existed.
+    pub fn disconnect(&mut self, link: Link) -> (bool, TkAction) {
+        let len = self.links.len();
+        self.links.retain(|l| *l != link);
+        match self.links.len() < len {
+            true => (true, TkAction::REDRAW),
+            false => (false, TkAction::empty()),
+        }
+    }
+
+    /// Iterate over indices of selected nodes
+    pub fn selected(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(move |i| self.nodes[*i].selected)
+    }
+
+    /// Select or deselect the node at `index`
+    pub fn set_selected(&mut self, index: usize, selected: bool) -> TkAction {
+        if self.nodes[index].selected == selected {
+            return TkAction::empty();
+        }
+        self.nodes[index].selected = selected;
+        TkAction::REDRAW
+    }
+
+    /// Iterate over nodes
+    pub fn iter(&self) -> impl Iterator<Item = &W> {
+        self.widgets.iter()
+    }
+
+    /// Mutably iterate over nodes
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut W> {
+        self.widgets.iter_mut()
+    }
+
+    /// Get the current transform
+    ///
+    /// This maps canvas coordinates to coordinates of this widget.
+    #[inline]
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Set the transform
+    ///
+    /// The scale factor is clamped to the limits.
+    pub fn set_transform(&mut self, mut transform: Transform) -> TkAction {
+        transform.scale = transform.scale.clamp(self.limits.0, self.limits.1);
+        if transform == self.transform {
+            return TkAction::empty();
+        }
+        self.transform = transform;
+        TkAction::REGION_MOVED
+    }
+
+    /// Scale by `factor` about a fixed point `centre`
+    ///
+    /// The `centre` is in the coordinate system of this widget (not the
+    /// canvas). The resulting scale factor is clamped to the limits.
+    pub fn zoom_about(&mut self, factor: f32, centre: Vec2) -> TkAction {
+        let scale = self.transform.scale * factor;
+        let factor = scale.clamp(self.limits.0, self.limits.1) / self.transform.scale;
+        let transform = self.transform.then(Transform::scale_about(factor, centre));
+        self.set_transform(transform)
+    }
+
+    fn place_node(&mut self, mgr: &mut Manager, index: usize) {
+        let node = &self.nodes[index];
+        let rect = Rect::new(self.core.rect.pos + (node.pos - Coord::ZERO), node.size);
+        self.widgets[index].set_rect(mgr, rect, AlignHints::NONE);
+    }
+
+    fn to_canvas(&self, coord: Coord) -> Coord {
+        self.transform.inverse().map_coord(coord)
+    }
+
+    fn ports(&self) -> impl Iterator<Item = Port> + '_ {
+        self.nodes.iter().enumerate().flat_map(|(i, node)| {
+            let inputs = (0..node.inputs).map(move |j| Port::Input(i, j));
+            inputs.chain((0..node.outputs).map(move |j| Port::Output(i, j)))
+        })
+    }
+
+    fn port_rect(&self, port: Port) -> Rect {
+        let (node, index, n, input) = match port {
+            Port::Input(node, index) => (node, index, self.nodes[node].inputs, true),
+            Port::Output(node, index) => (node, index, self.nodes[node].outputs, false),
+        };
+        let rect = self.widgets[node].rect();
+        let x = match input {
+            true => rect.pos.0,
+            false => rect.pos2().0,
+        };
+        let y = rect.pos.1 + rect.size.1 * i32::conv(index + 1) / i32::conv(n + 1);
+        let s = self.port_size;
+        Rect::new(Coord(x - s / 2, y - s / 2), Size::splat(s))
+    }
+
+    fn port_centre(&self, port: Port) -> Vec2 {
+        let rect = self.port_rect(port);
+        Vec2::from(rect.pos) + Vec2::from(rect.size) * 0.5
+    }
+
+    fn port_at(&self, coord: Coord) -> Option<Port> {
+        let margin = self.port_size / 4;
+        self.ports()
+            .find(|port| self.port_rect(*port).expand(margin).contains(coord))
+    }
+
+    fn is_connected(&self, port: Port) -> bool {
+        self.links.iter().any(|link| match port {
+            Port::Input(node, index) => link.to == (node, index),
+            Port::Output(node, index) => link.from == (node, index),
+        })
+    }
+
+    /// Approximate a link from `p0` to `p3` by a cubic bézier
+    fn link_path(&self, p0: Vec2, p3: Vec2) -> Vec<Vec2> {
+        let dx = ((p3.0 - p0.0).abs() * 0.5).max(2.0 * f32::conv(self.port_size));
+        let (p1, p2) = (p0 + Vec2(dx, 0.0), p3 - Vec2(dx, 0.0));
+        (0..=LINK_SEGMENTS)
+            .map(|i| {
+                let t = f32::conv(i) / f32::conv(LINK_SEGMENTS);
+                let s = 1.0 - t;
+                p0 * (s * s * s)
+                    + p1 * (3.0 * s * s * t)
+                    + p2 * (3.0 * s * t * t)
+                    + p3 * (t * t * t)
+            })
+            .collect()
+    }
+
+    fn press_node(
+        &mut self,
+        mgr: &mut Manager,
+        index: usize,
+        source: PressSource,
+        coord: Coord,
+    ) -> Response<<Self as event::Handler>::Msg> {
+        if !source.is_primary() {
+            return self.press_start(mgr, source, coord);
+        }
+
+        if mgr.modifiers().ctrl() {
+            self.nodes[index].selected = !self.nodes[index].selected;
+        } else if !self.nodes[index].selected {
+            for (i, node) in self.nodes.iter_mut().enumerate() {
+                node.selected = i == index;
+            }
+        }
+        mgr.redraw(self.id());
+
+        if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None) {
+            let origins = self.selected().map(|i| (i, self.nodes[i].pos)).collect();
+            self.drag = Some(Drag::Nodes {
+                source,
+                start: self.to_canvas(coord),
+                origins,
+                active: false,
+            });
+        }
+        Response::None
+    }
+
+    fn press_start(
+        &mut self,
+        mgr: &mut Manager,
+        source: PressSource,
+        coord: Coord,
+    ) -> Response<<Self as event::Handler>::Msg> {
+        self.cursor = Some(coord);
+        let canvas = self.to_canvas(coord);
+        let icon = Some(CursorIcon::Crosshair);
+
+        if source.is_primary() {
+            match self.port_at(canvas) {
+                Some(Port::Output(node, index)) => {
+                    if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, icon) {
+                        let from = (node, index);
+                        self.drag = Some(Drag::Link {
+                            source,
+                            from,
+                            coord: canvas,
+                        });
+                        mgr.redraw(self.id());
+                    }
+                    return Response::None;
+                }
+                Some(Port::Input(node, index)) => {
+                    // Detach the existing link, if any
+                    let pos = self.links.iter().position(|link| link.to == (node, index));
+                    if let Some(i) = pos {
+                        if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, icon) {
+                            let link = self.links.remove(i);
+                            let from = link.from;
+                            self.drag = Some(Drag::Link {
+                                source,
+                                from,
+                                coord: canvas,
+                            });
+                            mgr.redraw(self.id());
+                            return Response::Msg(NodeGraphMsg::Disconnect(link));
+                        }
+                    }
+                    return Response::None;
+                }
+                None => (),
+            }
+
+            if !source.is_touch() {
+                if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None) {
+                    let modifiers = mgr.modifiers();
+                    let add = modifiers.shift() || modifiers.ctrl();
+                    let initial = self.nodes.iter().map(|node| add && node.selected).collect();
+                    self.drag = Some(Drag::Band {
+                        source,
+                        start: canvas,
+                        end: canvas,
+                        initial,
+                    });
+                    self.update_band();
+                    mgr.redraw(self.id());
+                }
+                return Response::None;
+            }
+        }
+
+        let icon = Some(CursorIcon::Grabbing);
+        mgr.request_grab(self.id(), source, coord, GrabMode::Gesture, icon);
+        Response::None
+    }
+
+    fn press_move(&mut self, mgr: &mut Manager, coord: Coord) {
+        let canvas = self.to_canvas(coord);
+        let mut moves = vec![];
+        match self.drag.as_mut() {
+            Some(Drag::Nodes {
+                start,
+                origins,
+                active,
+                ..
+            }) => {
+                let delta = canvas - *start;
+                if !*active {
+                    if !mgr.config_test_pan_thresh(delta) {
+                        return;
+                    }
+                    *active = true;
+                    mgr.update_grab_cursor(self.core.id, CursorIcon::Grabbing);
+                }
+                moves = origins.iter().map(|(i, pos)| (*i, *pos + delta)).collect();
+            }
+            Some(Drag::Link { coord, .. }) => *coord = canvas,
+            Some(Drag::Band { end, .. }) => {
+                *end = canvas;
+                self.update_band();
+            }
+            None => return,
+        }
+
+        for (index, pos) in moves {
+            self.nodes[index].pos = pos;
+            self.place_node(mgr, index);
+        }
+        *mgr |= TkAction::REGION_MOVED;
+        mgr.redraw(self.id());
+    }
+
+    fn press_end(
+        &mut self,
+        mgr: &mut Manager,
+        coord: Coord,
+    ) -> Response<<Self as event::Handler>::Msg> {
+        mgr.redraw(self.id());
+        match self.drag.take() {
+            Some(Drag::Nodes {
+                origins, active, ..
+            }) if active => {
+                let moves = origins
+                    .iter()
+                    .map(|(i, _)| (*i, self.nodes[*i].pos))
+                    .collect();
+                Response::Msg(NodeGraphMsg::Move(moves))
+            }
+            Some(Drag::Link { from, .. }) => match self.port_at(self.to_canvas(coord)) {
+                Some(Port::Input(node, index)) if node != from.0 => {
+                    let link = Link {
+                        from,
+                        to: (node, index),
+                    };
+                    let _ = self.connect(link);
+                    Response::Msg(NodeGraphMsg::Connect(link))
+                }
+                _ => Response::None,
+            },
+            _ => Response::None,
+        }
+    }
+
+    /// Update selection from the rubber band, if any
+    fn update_band(&mut self) {
+        if let Some(Drag::Band {
+            start,
+            end,
+            initial,
+            ..
+        }) = self.drag.as_ref()
+        {
+            let band = band_rect(*start, *end);
+            for ((w, node), initial) in self.widgets.iter().zip(&mut self.nodes).zip(initial) {
+                node.selected = *initial || w.rect().intersection(&band).is_some();
+            }
+        }
+    }
+}
+
+impl Port {
+    fn node(&self) -> usize {
+        match self {
+            Port::Input(node, _) | Port::Output(node, _) => *node,
+        }
+    }
+}
+
+fn band_rect(a: Coord, b: Coord) -> Rect {
+    let pos = a.min(b);
+    Rect::new(pos, (a.max(b) - pos).into())
+}
@@ -58,14 +58,15 @@ widget! {
                     }
                 }
                 Event::HandleUpdate { payload, .. } => {
-                    let id = WidgetId::try_from(payload).unwrap();
-                    if self.state && id != self.id() {
-                        trace!("RadioBoxBare: unset {}", self.id());
-                        self.state = false;
-                        mgr.redraw(self.id());
-                        Response::Update
-                    } else {
-                        Response::None
+                    // Other users of the update handle may send other payloads
+                    match WidgetId::try_from(payload) {
+                        Ok(id) if self.state && id != self.id() => {
+                            trace!("RadioBoxBare: unset {}", self.id());
+                            self.state = false;
+                            mgr.redraw(self.id());
+                            Response::Update
+                        }
+                        _ => Response::None,
                     }
                 }
                 _ => Response::Unhandled,
@@ -192,7 +193,7 @@ widget! {
 
     impl WidgetConfig for Self {
         fn configure(&mut self, mgr: &mut Manager) {
-            mgr.add_accel_keys(self.radiobox.id(), self.label.keys());
+            self.label.add_accel_label(mgr, self.radiobox.id());
         }
     }
 
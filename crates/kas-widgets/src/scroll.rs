@@ -6,34 +6,65 @@
 //! Scroll region
 
 use super::Scrollable;
+use instant::Instant;
 use kas::draw::TextClass;
 use kas::event::ScrollDelta::{LineDelta, PixelDelta};
-use kas::event::{self, Command, PressSource};
+use kas::event::{self, Command, PressSource, ScrollEasing, ScrollSource};
+use kas::geom::DVec2;
 use kas::prelude::*;
 use std::fmt::Debug;
+use std::time::Duration;
+
+/// Smooth-scroll animation state
+#[derive(Clone, Debug, PartialEq)]
+struct ScrollAnim {
+    start: Instant,
+    duration: Duration,
+    easing: ScrollEasing,
+    from: DVec2,
+    to: DVec2,
+}
 
 /// Logic for a scroll region
 ///
 /// This struct handles some scroll logic. It does not provide scrollbars.
+///
+/// The offset is tracked with sub-pixel precision (see
+/// [`Self::exact_offset`]) while [`Self::offset`] is snapped to whole pixels
+/// for rendering.
 #[derive(Clone, Debug, PartialEq)]
 pub struct ScrollComponent {
+    window_size: Size,
+    content_size: Size,
     max_offset: Offset,
     offset: Offset,
+    exact: DVec2,
     scroll_rate: f32,
+    anim: Option<ScrollAnim>,
 }
 
 impl Default for ScrollComponent {
     #[inline]
     fn default() -> Self {
         ScrollComponent {
+            window_size: Size::ZERO,
+            content_size: Size::ZERO,
             max_offset: Offset::ZERO,
             offset: Offset::ZERO,
+            exact: DVec2::ZERO,
             scroll_rate: 30.0,
+            anim: None,
         }
     }
 }
 
 impl ScrollComponent {
+    /// Timer payload used for smooth-scroll animation
+    ///
+    /// Widgets using [`Self::scroll_by_event`] must pass
+    /// [`Event::TimerUpdate`] with this payload through to that method.
+    pub const TIMER_ANIMATE: u64 = 1 << 61;
+
     /// Get the maximum offset
     ///
     /// Note: the minimum offset is always zero.
@@ -42,15 +73,41 @@ impl ScrollComponent {
         self.max_offset
     }
 
+    /// Get the window (viewport) size, as last set by [`Self::set_sizes`]
+    #[inline]
+    pub fn window_size(&self) -> Size {
+        self.window_size
+    }
+
+    /// Get the content size, as last set by [`Self::set_sizes`]
+    #[inline]
+    pub fn content_size(&self) -> Size {
+        self.content_size
+    }
+
     /// Get the current offset
     ///
     /// To translate a coordinate from the outer region to a coordinate of the
     /// scrolled region, add this offset.
+    ///
+    /// This is [`Self::exact_offset`] rounded to the nearest pixel.
     #[inline]
     pub fn offset(&self) -> Offset {
         self.offset
     }
 
+    /// Get the current offset, with sub-pixel precision
+    #[inline]
+    pub fn exact_offset(&self) -> DVec2 {
+        self.exact
+    }
+
+    /// True if a smooth-scroll animation is in progress
+    #[inline]
+    pub fn is_animating(&self) -> bool {
+        self.anim.is_some()
+    }
+
     /// Set sizes:
     ///
     /// -   `window_size`: size of scroll region on the outside
@@ -60,8 +117,10 @@ impl ScrollComponent {
     /// change in offset. In practice the caller will likely be performing all
     /// required updates regardless and the return value can be safely ignored.
     pub fn set_sizes(&mut self, window_size: Size, content_size: Size) -> TkAction {
+        self.window_size = window_size;
+        self.content_size = content_size;
         self.max_offset = Offset::from(content_size) - Offset::from(window_size);
-        self.set_offset(self.offset)
+        self.set_exact(self.exact)
     }
 
     /// Set the scroll offset
@@ -69,9 +128,19 @@ impl ScrollComponent {
     /// The offset is clamped to the available scroll range.
     /// Returns [`TkAction::empty()`] if the offset is identical to the old offset,
     /// or [`TkAction::REGION_MOVED`] if the offset changes.
+    ///
+    /// This cancels any smooth-scroll animation.
     #[inline]
     pub fn set_offset(&mut self, offset: Offset) -> TkAction {
-        let offset = offset.clamp(Offset::ZERO, self.max_offset);
+        self.anim = None;
+        self.set_exact(DVec2::from(offset))
+    }
+
+    /// Set the exact offset (clamped), without affecting animation
+    fn set_exact(&mut self, offset: DVec2) -> TkAction {
+        let offset = offset.min(DVec2::from(self.max_offset)).max(DVec2::ZERO);
+        self.exact = offset;
+        let offset = Offset::from(offset);
         if offset == self.offset {
             TkAction::empty()
         } else {
@@ -80,6 +149,65 @@ impl ScrollComponent {
         }
     }
 
+    /// Scroll by `delta` immediately, returning the unused part
+    fn scroll_by_exact(&mut self, delta: DVec2) -> (TkAction, Response<VoidMsg>) {
+        self.anim = None;
+        let old_offset = self.exact;
+        let action = self.set_exact(old_offset - delta);
+        let delta = Offset::from(delta - (old_offset - self.exact));
+        let response = match delta {
+            delta if delta == Offset::ZERO => Response::None,
+            delta => Response::Pan(delta),
+        };
+        (action, response)
+    }
+
+    /// Start or extend a smooth-scroll animation by `delta`
+    fn animate_by(
+        &mut self,
+        mgr: &mut Manager,
+        w_id: WidgetId,
+        delta: DVec2,
+        duration: Duration,
+    ) -> Response<VoidMsg> {
+        // Successive steps accumulate from the current target
+        let from = self.anim.as_ref().map(|anim| anim.to).unwrap_or(self.exact);
+        let to = (from - delta)
+            .min(DVec2::from(self.max_offset))
+            .max(DVec2::ZERO);
+        if to != self.exact {
+            self.anim = Some(ScrollAnim {
+                start: Instant::now(),
+                duration,
+                easing: mgr.config().scroll_easing(),
+                from: self.exact,
+                to,
+            });
+            mgr.update_on_frame(w_id, Self::TIMER_ANIMATE);
+        }
+        match Offset::from(delta - (from - to)) {
+            delta if delta == Offset::ZERO => Response::None,
+            delta => Response::Pan(delta),
+        }
+    }
+
+    /// Advance the smooth-scroll animation for the next frame
+    fn animate(&mut self, mgr: &mut Manager, w_id: WidgetId) -> TkAction {
+        let anim = match self.anim.clone() {
+            Some(anim) => anim,
+            None => return TkAction::empty(),
+        };
+        let elapsed = mgr.next_frame_time().saturating_duration_since(anim.start);
+        let t = elapsed.as_secs_f64() / anim.duration.as_secs_f64();
+        if t >= 1.0 {
+            self.anim = None;
+        } else {
+            mgr.update_on_frame(w_id, Self::TIMER_ANIMATE);
+        }
+        let f = anim.easing.apply(t);
+        self.set_exact(anim.from + (anim.to - anim.from) * f)
+    }
+
     /// Set the scroll rate
     ///
     /// This affects how fast arrow keys and the mouse wheel scroll (but not
@@ -102,6 +230,12 @@ impl ScrollComponent {
             Event::PressEnd { coord, .. } => {
                 *coord += self.offset;
             }
+            Event::CursorMove { coord } => {
+                *coord += self.offset;
+            }
+            Event::Gesture(gesture) => {
+                gesture.focus += DVec2::from(self.offset);
+            }
             _ => {}
         };
         event
@@ -120,17 +254,93 @@ impl ScrollComponent {
     pub fn focus_rect(&mut self, rect: Rect, window_rect: Rect) -> (Rect, TkAction) {
         let v = rect.pos - window_rect.pos;
         let off = Offset::from(rect.size) - Offset::from(window_rect.size);
-        let offset = self.offset.max(v + off).min(v);
+        let offset = self.offset.max(v.saturating_add(off)).min(v);
+        let action = self.set_offset(offset);
+        (rect - self.offset, action)
+    }
+
+    /// Handle [`Response::Reveal`]
+    ///
+    /// This is similar to [`Self::focus_rect`], except that on axes with an
+    /// alignment hint, `rect` is aligned within `window_rect` accordingly
+    /// (where possible).
+    pub fn reveal_rect(
+        &mut self,
+        rect: Rect,
+        align: AlignHints,
+        window_rect: Rect,
+    ) -> (Rect, TkAction) {
+        let offset = self.reveal_offset(rect, align, window_rect);
         let action = self.set_offset(offset);
         (rect - self.offset, action)
     }
 
+    /// Handle [`Response::Reveal`] with a smooth-scroll animation
+    ///
+    /// This is identical to [`Self::reveal_rect`] except that the scroll is
+    /// animated over
+    /// [`Config::scroll_anim_duration`](kas::event::Config::scroll_anim_duration)
+    /// (see [`Self::scroll_by_event`] regarding [`Self::TIMER_ANIMATE`]). The
+    /// returned `Rect` is adjusted for the final scroll offset.
+    pub fn reveal_rect_animated(
+        &mut self,
+        mgr: &mut Manager,
+        w_id: WidgetId,
+        rect: Rect,
+        align: AlignHints,
+        window_rect: Rect,
+    ) -> (Rect, TkAction) {
+        let offset = self.reveal_offset(rect, align, window_rect);
+        let offset = offset.min(self.max_offset).max(Offset::ZERO);
+        let duration = mgr.config().scroll_anim_duration();
+        if duration == Duration::ZERO {
+            let action = self.set_offset(offset);
+            return (rect - self.offset, action);
+        }
+
+        let to = DVec2::from(offset);
+        if to != self.exact {
+            self.anim = Some(ScrollAnim {
+                start: Instant::now(),
+                duration,
+                easing: mgr.config().scroll_easing(),
+                from: self.exact,
+                to,
+            });
+            mgr.update_on_frame(w_id, Self::TIMER_ANIMATE);
+        }
+        (rect - offset, TkAction::empty())
+    }
+
+    /// Calculate the (unclamped) offset revealing `rect`
+    fn reveal_offset(&self, rect: Rect, align: AlignHints, window_rect: Rect) -> Offset {
+        let v = rect.pos - window_rect.pos;
+        let off = Offset::from(rect.size) - Offset::from(window_rect.size);
+        let min = self.offset.max(v + off).min(v);
+        let component = |align, v: i32, off: i32, min: i32| match align {
+            Some(Align::TL) => v,
+            Some(Align::Center) => v + off / 2,
+            Some(Align::BR) => v + off,
+            _ => min,
+        };
+        Offset(
+            component(align.horiz, v.0, off.0, min.0),
+            component(align.vert, v.1, off.1, min.1),
+        )
+    }
+
     /// Use an event to scroll, if possible
     ///
     /// Handles keyboard (Home/End, Page Up/Down and arrow keys), mouse wheel
     /// and touchpad scroll events. Also handles mouse/touch drag events *if*
     /// the `on_press_start` closure activates a mouse/touch grab.
     ///
+    /// Mouse-wheel steps are animated when
+    /// [`Config::scroll_anim_duration`](kas::event::Config::scroll_anim_duration)
+    /// is non-zero. The animation uses [`Event::TimerUpdate`] with payload
+    /// [`Self::TIMER_ANIMATE`] on the widget `w_id`; this event must be passed
+    /// to this method.
+    ///
     /// Behaviour on [`Event::PressStart`] is configurable: the closure is called on
     /// this event and should call [`Manager::request_grab`] if the press should
     /// scroll by drag. This allows control of which mouse button(s) are used and
@@ -147,12 +357,18 @@ impl ScrollComponent {
     ///     -> Response<Msg>
     /// {
     ///     let window_size = Size(100, 80);
-    ///     let (action, response) = scroll.scroll_by_event(event, window_size, |source, _, coord| {
-    ///         if source.is_primary() {
-    ///             let icon = Some(kas::event::CursorIcon::Grabbing);
-    ///             mgr.request_grab(id, source, coord, kas::event::GrabMode::Grab, icon);
-    ///         }
-    ///     });
+    ///     let (action, response) = scroll.scroll_by_event(
+    ///         mgr,
+    ///         id,
+    ///         event,
+    ///         window_size,
+    ///         |mgr, source, _, coord| {
+    ///             if source.is_primary() {
+    ///                 let icon = Some(kas::event::CursorIcon::Grabbing);
+    ///                 mgr.request_grab(id, source, coord, kas::event::GrabMode::Grab, icon);
+    ///             }
+    ///         },
+    ///     );
     ///     *mgr |= action;
     ///     response.void_into()
     /// }
@@ -162,12 +378,13 @@ impl ScrollComponent {
     /// the returned [`Response`] is either `None` or `Unhandled(..)`.
     /// If the returned [`TkAction`] is not `None`, the scroll offset has been
     /// updated and the second return value is `Response::None`.
-    #[inline]
-    pub fn scroll_by_event<PS: FnMut(PressSource, WidgetId, Coord)>(
+    pub fn scroll_by_event<PS: FnOnce(&mut Manager, PressSource, WidgetId, Coord)>(
         &mut self,
+        mgr: &mut Manager,
+        w_id: WidgetId,
         event: Event,
         window_size: Size,
-        mut on_press_start: PS,
+        on_press_start: PS,
     ) -> (TkAction, Response<VoidMsg>) {
         let mut action = TkAction::empty();
         let mut response = Response::None;
@@ -185,8 +402,8 @@ impl ScrollComponent {
                     Command::Right => LineDelta(1.0, 0.0),
                     Command::Up => LineDelta(0.0, 1.0),
                     Command::Down => LineDelta(0.0, -1.0),
-                    Command::PageUp => PixelDelta(Offset(0, window_size.1 / 2)),
-                    Command::PageDown => PixelDelta(Offset(0, -(window_size.1 / 2))),
+                    Command::PageUp => PixelDelta(DVec2(0.0, f64::from(window_size.1 / 2))),
+                    Command::PageDown => PixelDelta(DVec2(0.0, -f64::from(window_size.1 / 2))),
                     _ => return (action, Response::Unhandled),
                 };
 
@@ -195,37 +412,37 @@ impl ScrollComponent {
                         (-self.scroll_rate * x).cast_nearest(),
                         (self.scroll_rate * y).cast_nearest(),
                     ),
-                    PixelDelta(d) => d,
+                    PixelDelta(d) => Offset::from(d),
                 };
-                action = self.set_offset(self.offset - d);
+                action = self.set_offset(self.offset.saturating_sub(d));
             }
             Event::Scroll(delta) => {
                 let d = match delta {
-                    LineDelta(x, y) => Offset(
-                        (-self.scroll_rate * x).cast_nearest(),
-                        (self.scroll_rate * y).cast_nearest(),
+                    LineDelta(x, y) => DVec2(
+                        f64::from(-self.scroll_rate * x),
+                        f64::from(self.scroll_rate * y),
                     ),
                     PixelDelta(d) => d,
                 };
-                let old_offset = self.offset;
-                action = self.set_offset(old_offset - d);
-                let delta = d - (old_offset - self.offset);
-                if delta != Offset::ZERO {
-                    response = Response::Pan(delta);
+                let duration = mgr.config().scroll_anim_duration();
+                if mgr.scroll_source() == ScrollSource::Wheel && duration > Duration::ZERO {
+                    response = self.animate_by(mgr, w_id, d, duration);
+                } else {
+                    return self.scroll_by_exact(d);
                 }
             }
+            Event::TimerUpdate(Self::TIMER_ANIMATE) => {
+                action = self.animate(mgr, w_id);
+            }
             Event::PressStart {
                 source,
                 start_id,
                 coord,
-            } => on_press_start(source, start_id, coord),
-            Event::PressMove { mut delta, .. } => {
-                let old_offset = self.offset;
-                action = self.set_offset(old_offset - delta);
-                delta -= old_offset - self.offset;
-                if delta != Offset::ZERO {
-                    response = Response::Pan(delta);
-                }
+            } => on_press_start(mgr, source, start_id, coord),
+            Event::PressMove { delta, .. } => return self.scroll_by_exact(DVec2::from(delta)),
+            Event::Gesture(gesture) => {
+                // Scale and rotation are ignored here
+                return self.scroll_by_exact(gesture.translation);
             }
             Event::PressEnd { .. } => (), // consume due to request
             _ => response = Response::Unhandled,
@@ -241,6 +458,8 @@ widget! {
     ///
     /// Scrollbars are not included; use [`ScrollBarRegion`] if you want those.
     ///
+    /// The scroll offset may be persisted via [`ScrollRegion::with_state_key`].
+    ///
     /// [`ScrollBarRegion`]: crate::ScrollBarRegion
     #[autoimpl(Deref, DerefMut on inner)]
     #[autoimpl(class_traits where W: trait on inner)]
@@ -253,6 +472,8 @@ widget! {
         offset: Offset,
         frame_size: Size,
         scroll: ScrollComponent,
+        state_key: Option<String>,
+        restore: Option<Offset>,
         #[widget]
         inner: W,
     }
@@ -267,10 +488,37 @@ widget! {
                 offset: Default::default(),
                 frame_size: Default::default(),
                 scroll: Default::default(),
+                state_key: None,
+                restore: None,
                 inner,
             }
         }
 
+        /// Persist the scroll offset under `key`
+        ///
+        /// The offset is saved whenever it changes and restored when next
+        /// configured. See [`kas::state`].
+        #[inline]
+        pub fn set_state_key<K: ToString>(&mut self, key: K) {
+            self.state_key = Some(key.to_string());
+        }
+
+        /// Persist the scroll offset under `key` (inline)
+        ///
+        /// See [`ScrollRegion::set_state_key`].
+        #[inline]
+        pub fn with_state_key<K: ToString>(mut self, key: K) -> Self {
+            self.set_state_key(key);
+            self
+        }
+
+        fn save_offset(&self, mgr: &mut Manager) {
+            if let Some(key) = self.state_key.as_deref() {
+                let offset = self.scroll.offset();
+                mgr.save_state(key, format!("{} {}", offset.0, offset.1));
+            }
+        }
+
         /// Access inner widget directly
         #[inline]
         pub fn inner(&self) -> &W {
@@ -297,6 +545,16 @@ widget! {
             self.scroll.max_offset()
         }
 
+        #[inline]
+        fn content_size(&self) -> Size {
+            self.scroll.content_size()
+        }
+
+        #[inline]
+        fn viewport_size(&self) -> Size {
+            self.scroll.window_size()
+        }
+
         #[inline]
         fn scroll_offset(&self) -> Offset {
             self.scroll.offset()
@@ -305,6 +563,7 @@ widget! {
         #[inline]
         fn set_scroll_offset(&mut self, mgr: &mut Manager, offset: Offset) -> Offset {
             *mgr |= self.scroll.set_offset(offset);
+            self.save_offset(mgr);
             self.scroll.offset()
         }
     }
@@ -312,6 +571,12 @@ widget! {
     impl WidgetConfig for Self {
         fn configure(&mut self, mgr: &mut Manager) {
             mgr.register_nav_fallback(self.id());
+            if let Some(key) = self.state_key.as_deref() {
+                self.restore = mgr.load_state(key).and_then(|value| {
+                    let mut iter = value.split_whitespace().map(|v| v.parse().ok());
+                    Some(Offset(iter.next()??, iter.next()??))
+                });
+            }
         }
     }
 
@@ -339,6 +604,9 @@ widget! {
             let _ = self
                 .scroll
                 .set_sizes(rect.size, child_size + self.frame_size);
+            if let Some(offset) = self.restore.take() {
+                let _ = self.scroll.set_offset(offset);
+            }
         }
 
         fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
@@ -367,6 +635,22 @@ widget! {
                 return Response::Unhandled;
             }
 
+            let offset = self.scroll.offset();
+            let response = self.send_inner(mgr, id, event);
+            if self.scroll.offset() != offset {
+                self.save_offset(mgr);
+            }
+            response
+        }
+    }
+
+    impl Self {
+        fn send_inner(
+            &mut self,
+            mgr: &mut Manager,
+            id: WidgetId,
+            event: Event,
+        ) -> Response<<W as event::Handler>::Msg> {
             if id <= self.inner.id() {
                 let child_event = self.scroll.offset_event(event.clone());
                 match self.inner.send(mgr, id, child_event) {
@@ -382,18 +666,61 @@ widget! {
                         *mgr |= action;
                         return Response::Focus(rect);
                     }
+                    Response::Reveal(rect, align) => {
+                        let (rect, action) = (self.scroll).reveal_rect_animated(
+                            mgr,
+                            self.id(),
+                            rect,
+                            align,
+                            self.core.rect,
+                        );
+                        *mgr |= action;
+                        return Response::Reveal(rect, align);
+                    }
+                    Response::SizeChanged => {
+                        // Content size affects our scroll bounds but (usually)
+                        // not our own minimum size: re-solve locally if possible
+                        let rect = self.core.rect;
+                        let (w_rules, h_rules) = mgr.size_handle(|size_handle| {
+                            let w_rules = self.size_rules(size_handle, AxisInfo::new(false, None));
+                            let h_rules =
+                                self.size_rules(size_handle, AxisInfo::new(true, Some(rect.size.0)));
+                            (w_rules, h_rules)
+                        });
+                        if w_rules.min_size() > rect.size.0 || h_rules.min_size() > rect.size.1 {
+                            return Response::SizeChanged;
+                        }
+                        self.set_rect(mgr, rect, AlignHints::NONE);
+                        *mgr |= TkAction::REGION_MOVED;
+                        return Response::None;
+                    }
                     r => return r,
                 }
             } else {
                 debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
             };
 
+            if let Event::Gesture(gesture) = event {
+                // Offer pinch-zoom and rotation to the content first
+                if gesture.is_pinch() {
+                    let inner_id = self.inner.id();
+                    let event = self.scroll.offset_event(event.clone());
+                    match self.inner.send(mgr, inner_id, event) {
+                        Response::Unhandled => (),
+                        r => return r,
+                    }
+                }
+            }
+
             let id = self.id();
+            let size = self.core.rect.size;
             let (action, response) =
                 self.scroll
-                    .scroll_by_event(event, self.core.rect.size, |source, _, coord| {
-                        if source.is_primary() && mgr.config_enable_mouse_pan() {
-                            let icon = Some(event::CursorIcon::Grabbing);
+                    .scroll_by_event(mgr, id, event, size, |mgr, source, _, coord| {
+                        let icon = Some(event::CursorIcon::Grabbing);
+                        if source.is_touch() {
+                            mgr.request_grab(id, source, coord, event::GrabMode::Gesture, icon);
+                        } else if source.is_primary() && mgr.config_enable_mouse_pan() {
                             mgr.request_grab(id, source, coord, event::GrabMode::Grab, icon);
                         }
                     });
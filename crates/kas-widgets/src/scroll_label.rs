@@ -12,7 +12,91 @@ use kas::event::{self, Command, ScrollDelta};
 use kas::geom::Vec2;
 use kas::prelude::*;
 use kas::text::format::{EditableText, FormattableText};
-use kas::text::SelectionHelper;
+use kas::text::{util, SelectionHelper};
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+/// Handle a selection command on read-only text
+///
+/// This handles copying (via [`Manager::set_clipboard`]), select-all, clearing
+/// the selection and cursor movement, where `shift` extends the selection.
+///
+/// Returns `None` when `cmd` is not handled, otherwise whether the edit
+/// position moved. Requests a redraw of `id` as required.
+pub(crate) fn read_only_command<T: FormattableText + 'static>(
+    mgr: &mut Manager,
+    id: WidgetId,
+    text: &Text<T>,
+    selection: &mut SelectionHelper,
+    cmd: Command,
+    shift: bool,
+) -> Option<bool> {
+    let pos = selection.edit_pos();
+    let s = text.as_str();
+    // Left/Right move visually, thus are swapped within right-to-left text
+    let cmd = match util::line_is_rtl(text, pos) {
+        true => cmd.mirrored(),
+        false => cmd,
+    };
+    let new_pos = match cmd {
+        Command::Escape | Command::Deselect if !selection.is_empty() => {
+            selection.set_empty();
+            mgr.redraw(id);
+            return Some(false);
+        }
+        Command::SelectAll => {
+            selection.set_sel_pos(0);
+            selection.set_edit_pos(s.len());
+            mgr.redraw(id);
+            return Some(pos != s.len());
+        }
+        Command::Cut | Command::Copy if !selection.is_empty() => {
+            mgr.set_clipboard(s[selection.range()].to_string());
+            return Some(false);
+        }
+        Command::Left => {
+            let mut cursor = GraphemeCursor::new(pos, s.len(), true);
+            cursor.prev_boundary(s, 0).unwrap().unwrap_or(pos)
+        }
+        Command::Right => {
+            let mut cursor = GraphemeCursor::new(pos, s.len(), true);
+            cursor.next_boundary(s, 0).unwrap().unwrap_or(pos)
+        }
+        Command::WordLeft => (s[0..pos].split_word_bound_indices())
+            .rev()
+            .find(|(_, word)| !word.trim_start().is_empty())
+            .map(|(index, _)| index)
+            .unwrap_or(0),
+        Command::WordRight => (s[pos..].split_word_bound_indices())
+            .skip(1)
+            .find(|(_, word)| !word.trim_start().is_empty())
+            .map(|(index, _)| pos + index)
+            .unwrap_or(s.len()),
+        Command::Up | Command::Down => {
+            let x = (text.text_glyph_pos(pos).next_back())
+                .map(|r| r.pos.0)
+                .unwrap_or(0.0);
+            let line = text.find_line(pos).map(|r| r.0).unwrap_or(0);
+            let line = match cmd {
+                Command::Up if line == 0 => return Some(false),
+                Command::Up => line - 1,
+                _ => line + 1,
+            };
+            text.line_index_nearest(line, x).unwrap_or(s.len())
+        }
+        Command::Home => text.find_line(pos).map(|r| r.1.start).unwrap_or(0),
+        Command::End => text.find_line(pos).map(|r| r.1.end).unwrap_or(s.len()),
+        Command::DocHome => 0,
+        Command::DocEnd => s.len(),
+        _ => return None,
+    };
+
+    selection.set_edit_pos(new_pos);
+    if !shift {
+        selection.set_empty();
+    }
+    mgr.redraw(id);
+    Some(new_pos != pos)
+}
 
 widget! {
     /// A text label supporting scrolling and selection
@@ -142,6 +226,7 @@ widget! {
         T: EditableText,
     {
         fn set_string(&mut self, string: String) -> TkAction {
+            self.core.bump_generation();
             let avail = self.core.rect.size;
             kas::text::util::set_string_and_prepare(&mut self.text, string, avail)
         }
@@ -152,26 +237,19 @@ widget! {
 
         fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
             match event {
-                Event::Command(cmd, _) => match cmd {
-                    Command::Escape | Command::Deselect if !self.selection.is_empty() => {
-                        self.selection.set_empty();
-                        mgr.redraw(self.id());
-                        Response::None
-                    }
-                    Command::SelectAll => {
-                        self.selection.set_sel_pos(0);
-                        self.selection.set_edit_pos(self.text.str_len());
-                        mgr.redraw(self.id());
-                        Response::None
-                    }
-                    Command::Cut | Command::Copy => {
-                        let range = self.selection.range();
-                        mgr.set_clipboard((self.text.as_str()[range]).to_string());
-                        Response::None
+                Event::Command(cmd, shift) => {
+                    let id = self.id();
+                    match read_only_command(mgr, id, &self.text, &mut self.selection, cmd, shift) {
+                        Some(moved) => {
+                            if moved {
+                                self.set_view_offset_from_edit_pos();
+                            }
+                            Response::None
+                        }
+                        // TODO: scroll by command
+                        None => Response::Unhandled,
                     }
-                    // TODO: scroll by command
-                    _ => Response::Unhandled,
-                },
+                }
                 Event::LostSelFocus => {
                     self.selection.set_empty();
                     mgr.redraw(self.id());
@@ -184,7 +262,7 @@ widget! {
                             let dist = 3.0 * self.text.env().height(Default::default());
                             Offset((x * dist).cast_nearest(), (y * dist).cast_nearest())
                         }
-                        ScrollDelta::PixelDelta(coord) => coord,
+                        ScrollDelta::PixelDelta(delta) => Offset::from(delta),
                     };
                     match self.pan_delta(mgr, delta2) {
                         delta if delta == Offset::ZERO => Response::None,
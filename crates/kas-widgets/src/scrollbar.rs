@@ -274,10 +274,54 @@ widget! {
     }
 }
 
+/// Scroll state of a [`Scrollable`] widget
+///
+/// [`ScrollBars`] (and thus [`ScrollBarRegion`]) publish this message via the
+/// message bus whenever the scroll offset or sizes change. Widgets such as a
+/// minimap or position indicator may receive it as [`Event::Bus`] after
+/// subscribing via [`Manager::subscribe`]`::<ScrollState>`. To observe a
+/// scrollable without showing bars, use [`ScrollBars::with_bars`]`(false, false)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScrollState {
+    /// The publishing widget
+    pub id: WidgetId,
+    /// Current scroll offset
+    pub offset: Offset,
+    /// Maximum scroll offset
+    pub max_offset: Offset,
+    /// Size of the scrolled content
+    pub content_size: Size,
+    /// Size of the viewport
+    pub viewport_size: Size,
+}
+
+impl ScrollState {
+    /// True if the viewport shows the start of the content on both axes
+    #[inline]
+    pub fn at_start(&self) -> bool {
+        self.offset.0 <= 0 && self.offset.1 <= 0
+    }
+
+    /// Fraction of content scrolled past on each axis (0 to 1)
+    ///
+    /// Axes on which scrolling is not possible report 0.
+    pub fn fraction(&self) -> (f32, f32) {
+        let axis = |offset: i32, max: i32| match max > 0 {
+            true => (offset as f32 / max as f32).max(0.0).min(1.0),
+            false => 0.0,
+        };
+        (
+            axis(self.offset.0, self.max_offset.0),
+            axis(self.offset.1, self.max_offset.1),
+        )
+    }
+}
+
 /// Additional functionality on scrollable widgets
 ///
 /// This trait should be implemented by widgets supporting scrolling, enabling
-/// a parent (such as the [`ScrollBars`] wrapper) to add controls.
+/// a parent (such as the [`ScrollBars`] wrapper) to add controls. To observe
+/// scrolling from elsewhere, see [`ScrollState`].
 ///
 /// The implementing widget may use event handlers to scroll itself (e.g. in
 /// reaction to a mouse wheel or touch-drag), but when doing so should emit
@@ -311,14 +355,44 @@ pub trait Scrollable: Widget {
     /// resulting offset is returned.
     fn set_scroll_offset(&mut self, mgr: &mut Manager, offset: Offset) -> Offset;
 
+    /// Get the size of the scrolled content
+    ///
+    /// The default implementation calculates this from
+    /// [`Self::viewport_size`] and [`Self::max_scroll_offset`].
+    #[inline]
+    fn content_size(&self) -> Size {
+        let max_offset = self.max_scroll_offset().max(Offset::ZERO);
+        Size::from(Offset::from(self.viewport_size()) + max_offset)
+    }
+
+    /// Get the size of the viewport (the visible part of the content)
+    ///
+    /// The default implementation returns the size of the widget's rect.
+    #[inline]
+    fn viewport_size(&self) -> Size {
+        self.rect().size
+    }
+
+    /// Get the scroll state
+    #[inline]
+    fn scroll_state(&self) -> ScrollState {
+        ScrollState {
+            id: self.id(),
+            offset: self.scroll_offset(),
+            max_offset: self.max_scroll_offset(),
+            content_size: self.content_size(),
+            viewport_size: self.viewport_size(),
+        }
+    }
+
     /// Scroll by a delta
     ///
     /// Returns the remaining (unused) delta.
     #[inline]
     fn scroll_by_delta(&mut self, mgr: &mut Manager, delta: Offset) -> Offset {
         let old_offset = self.scroll_offset();
-        let new_offset = self.set_scroll_offset(mgr, old_offset - delta);
-        delta - old_offset + new_offset
+        let new_offset = self.set_scroll_offset(mgr, old_offset.saturating_sub(delta));
+        delta.saturating_add(new_offset - old_offset)
     }
 }
 
@@ -364,6 +438,15 @@ widget! {
             ScrollBarRegion(self.0.with_bars(horiz, vert))
         }
 
+        /// Persist the scroll offset under `key` (inline)
+        ///
+        /// See [`ScrollRegion::set_state_key`].
+        #[inline]
+        pub fn with_state_key<K: ToString>(mut self, key: K) -> Self {
+            self.0.inner_mut().set_state_key(key);
+            self
+        }
+
         /// Set which scroll bars are visible
         ///
         /// Calling this method also disables automatic scroll bars.
@@ -404,6 +487,14 @@ widget! {
             self.0.inner.max_scroll_offset()
         }
         #[inline]
+        fn content_size(&self) -> Size {
+            self.0.inner.content_size()
+        }
+        #[inline]
+        fn viewport_size(&self) -> Size {
+            self.0.inner.viewport_size()
+        }
+        #[inline]
         fn scroll_offset(&self) -> Offset {
             self.0.inner.scroll_offset()
         }
@@ -439,6 +530,7 @@ widget! {
         vert_bar: ScrollBar<kas::dir::Down>,
         #[widget]
         inner: W,
+        published: Option<ScrollState>,
     }
 
     impl Self {
@@ -455,6 +547,16 @@ widget! {
                 horiz_bar: ScrollBar::new(),
                 vert_bar: ScrollBar::new(),
                 inner,
+                published: None,
+            }
+        }
+
+        /// Publish [`ScrollState`] if changed
+        fn publish_state(&mut self, mgr: &mut Manager) {
+            let state = self.scroll_state();
+            if self.published != Some(state) {
+                self.published = Some(state);
+                mgr.publish(state);
             }
         }
 
@@ -534,12 +636,21 @@ widget! {
             self.inner.max_scroll_offset()
         }
         #[inline]
+        fn content_size(&self) -> Size {
+            self.inner.content_size()
+        }
+        #[inline]
+        fn viewport_size(&self) -> Size {
+            self.inner.viewport_size()
+        }
+        #[inline]
         fn scroll_offset(&self) -> Offset {
             self.inner.scroll_offset()
         }
         fn set_scroll_offset(&mut self, mgr: &mut Manager, offset: Offset) -> Offset {
             let offset = self.inner.set_scroll_offset(mgr, offset);
             *mgr |= self.horiz_bar.set_value(offset.0) | self.vert_bar.set_value(offset.1);
+            self.publish_state(mgr);
             offset
         }
     }
@@ -595,6 +706,7 @@ widget! {
                     .set_rect(mgr, Rect { pos, size }, AlignHints::NONE);
                 let _ = self.vert_bar.set_limits(max_scroll_offset.1, rect.size.1);
             }
+            self.publish_state(mgr);
         }
 
         fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
@@ -643,7 +755,7 @@ widget! {
                 return Response::Unhandled;
             }
 
-            if id <= self.horiz_bar.id() {
+            let response = if id <= self.horiz_bar.id() {
                 self.horiz_bar
                     .send(mgr, id, event)
                     .try_into()
@@ -662,20 +774,18 @@ widget! {
                         Response::None
                     })
             } else if id <= self.inner.id() {
-                match self.inner.send(mgr, id, event) {
-                    Response::Focus(rect) => {
-                        // We assume that the scrollable inner already updated its
-                        // offset; we just update the bar positions
-                        let offset = self.inner.scroll_offset();
-                        *mgr |= self.horiz_bar.set_value(offset.0) | self.vert_bar.set_value(offset.1);
-                        Response::Focus(rect)
-                    }
-                    r => r,
-                }
+                let r = self.inner.send(mgr, id, event);
+                // The inner may have scrolled (e.g. on Response::Focus or during
+                // an animation); we just update the bar positions
+                let offset = self.inner.scroll_offset();
+                *mgr |= self.horiz_bar.set_value(offset.0) | self.vert_bar.set_value(offset.1);
+                r
             } else {
                 debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
                 self.handle(mgr, event)
-            }
+            };
+            self.publish_state(mgr);
+            response
         }
     }
 }
@@ -70,6 +70,7 @@ widget! {
     /// A resizable row/column widget
     ///
     /// Similar to [`crate::List`] but with draggable handles between items.
+    /// Handle positions may be persisted via [`Splitter::with_state_key`].
     // TODO: better doc
     #[derive(Clone, Default, Debug)]
     #[handler(msg=<W as event::Handler>::Msg)]
@@ -81,6 +82,18 @@ widget! {
         handles: Vec<DragHandle>,
         data: layout::DynRowStorage,
         direction: D,
+        state_key: Option<String>,
+        restore: Option<Vec<f32>>,
+    }
+
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            if let Some(key) = self.state_key.as_deref() {
+                self.restore = mgr.load_state(key).and_then(|value| {
+                    value.split_whitespace().map(|v| v.parse().ok()).collect()
+                });
+            }
+        }
     }
 
     impl WidgetChildren for Self {
@@ -178,6 +191,10 @@ widget! {
 
                 n += 1;
             }
+
+            if let Some(fractions) = self.restore.take() {
+                self.restore_handles(mgr, &fractions);
+            }
         }
 
         fn spatial_nav(&mut self, _: &mut Manager, _: bool, _: Option<usize>) -> Option<usize> {
@@ -244,6 +261,7 @@ widget! {
                                 // Message is the new offset relative to the track;
                                 // the handle has already adjusted its position
                                 self.adjust_size(mgr, n);
+                                self.save_handles(mgr);
                                 Response::None
                             });
                     }
@@ -294,6 +312,46 @@ impl<D: Directional, W: Widget> Splitter<D, W> {
             handles,
             data: Default::default(),
             direction,
+            state_key: None,
+            restore: None,
+        }
+    }
+
+    /// Persist handle positions under `key` (inline)
+    ///
+    /// Positions are saved (as fractions of the splitter's size) whenever a
+    /// handle is dragged and restored when next configured. See [`kas::state`].
+    #[inline]
+    pub fn with_state_key<K: ToString>(mut self, key: K) -> Self {
+        self.state_key = Some(key.to_string());
+        self
+    }
+
+    fn save_handles(&self, mgr: &mut Manager) {
+        let key = match self.state_key.as_deref() {
+            Some(key) => key,
+            None => return,
+        };
+        let size = f32::conv(self.core.rect.size.extract(self.direction).max(1));
+        let fractions: Vec<String> = self
+            .handles
+            .iter()
+            .map(|h| (h.rect().pos - self.core.rect.pos).extract(self.direction))
+            .map(|pos| format!("{:.4}", f32::conv(pos) / size))
+            .collect();
+        mgr.save_state(key, fractions.join(" "));
+    }
+
+    fn restore_handles(&mut self, mgr: &mut Manager, fractions: &[f32]) {
+        let size = f32::conv(self.core.rect.size.extract(self.direction));
+        for (n, fraction) in fractions.iter().enumerate().take(self.handles.len()) {
+            let track = self.handles[n].track();
+            let pos: i32 = (fraction * size).cast_nearest();
+            let mut offset = Offset::ZERO;
+            let track_pos = (track.pos - self.core.rect.pos).extract(self.direction);
+            offset.set_component(self.direction, pos - track_pos);
+            let _ = self.handles[n].set_offset(offset);
+            self.adjust_size(mgr, n);
         }
     }
 
@@ -304,7 +362,8 @@ impl<D: Directional, W: Widget> Splitter<D, W> {
 
         let hrect = self.handles[n].rect();
         let width1 = (hrect.pos - self.core.rect.pos).extract(self.direction);
-        let width2 = (self.core.rect.size - hrect.size).extract(self.direction) - width1;
+        let size = self.core.rect.size.clamped_sub(hrect.size);
+        let width2 = (size.extract(self.direction) - width1).max(0);
 
         let dim = (self.direction, self.num_children());
         let mut setter =
@@ -97,16 +97,14 @@ widget! {
     impl event::SendEvent for Self {
         fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
             if !self.is_disabled() {
-                for (index, child) in self.widgets.iter_mut().enumerate() {
-                    if id <= child.id() {
-                        return match child.send(mgr, id, event) {
-                            Response::Focus(rect) => {
-                                *mgr |= self.set_active(index);
-                                Response::Focus(rect)
-                            }
-                            r => r,
-                        };
-                    }
+                if let Some((index, r)) = mgr.send_to_child(&mut self.widgets, id, event) {
+                    return match r {
+                        Response::Focus(rect) => {
+                            *mgr |= self.set_active(index);
+                            Response::Focus(rect)
+                        }
+                        r => r,
+                    };
                 }
             }
 
@@ -0,0 +1,210 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Status bar
+
+use instant::Instant;
+use kas::draw::TextClass;
+use kas::geom::Vec2;
+use kas::{layout, prelude::*};
+use std::time::Duration;
+
+/// A message for a [`StatusBar`]
+///
+/// Any widget may post a message to the status bar(s) of its window via the
+/// message bus, e.g. `mgr.publish(StatusMessage::Clear)`, or via the shorthands
+/// [`StatusBar::show`] and [`StatusBar::set_section`]. The sender does not
+/// need to know of the status bar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StatusMessage {
+    /// Show a transient message
+    ///
+    /// If a duration is given, the message area reverts to its default text
+    /// after this time (unless replaced by another message first).
+    Show(String, Option<Duration>),
+    /// Revert the message area to its default text
+    Clear,
+    /// Set the text of the permanent section with the given key
+    ///
+    /// A new section is appended if no section with this key exists.
+    Section(&'static str, String),
+}
+
+/// A permanent section of a status bar
+#[derive(Clone, Debug)]
+struct Section {
+    key: &'static str,
+    text: Text<String>,
+    store: layout::TextStorage,
+}
+
+widget! {
+    /// A status bar
+    ///
+    /// The status bar has a left-aligned message area, showing transient
+    /// messages (or some default text), followed by right-aligned permanent
+    /// sections addressed by key.
+    ///
+    /// The status bar receives [`StatusMessage`]s published within the same
+    /// window (see [`Manager::publish`]), thus a status message may be posted
+    /// from anywhere via [`StatusBar::show`].
+    #[derive(Clone, Debug, Default)]
+    pub struct StatusBar {
+        #[widget_core]
+        core: CoreData,
+        default: String,
+        message: Text<String>,
+        layout_message: layout::TextStorage,
+        sections: Vec<Section>,
+        layout_row: layout::DynRowStorage,
+        revert_at: Option<Instant>,
+        generation: u64,
+    }
+
+    impl Self {
+        /// Construct an empty status bar
+        #[inline]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Set the default text of the message area, builder style
+        #[inline]
+        pub fn with_default<S: ToString>(mut self, text: S) -> Self {
+            self.default = text.to_string();
+            self.message = Text::new_single(self.default.clone());
+            self
+        }
+
+        /// Add a permanent section, builder style
+        ///
+        /// Sections are placed in order of addition.
+        #[inline]
+        pub fn with_section<S: ToString>(mut self, key: &'static str, text: S) -> Self {
+            self.sections.push(Section {
+                key,
+                text: Text::new_single(text.to_string()),
+                store: Default::default(),
+            });
+            self
+        }
+
+        /// Show a transient message on status bars in this window for `duration`
+        ///
+        /// This publishes [`StatusMessage::Show`] via the message bus.
+        pub fn show<S: ToString>(mgr: &mut Manager, text: S, duration: Duration) {
+            mgr.publish(StatusMessage::Show(text.to_string(), Some(duration)));
+        }
+
+        /// Set a permanent section on status bars in this window
+        ///
+        /// This publishes [`StatusMessage::Section`] via the message bus.
+        pub fn set_section<S: ToString>(mgr: &mut Manager, key: &'static str, text: S) {
+            mgr.publish(StatusMessage::Section(key, text.to_string()));
+        }
+
+        /// Get the text of the message area
+        pub fn message(&self) -> &str {
+            self.message.text()
+        }
+
+        /// Get the text of the section with the given `key`, if any
+        pub fn section(&self, key: &str) -> Option<&str> {
+            self.sections
+                .iter()
+                .find(|s| s.key == key)
+                .map(|s| s.text.text().as_str())
+        }
+
+        /// Apply a status message directly
+        pub fn post(&mut self, mgr: &mut Manager, msg: StatusMessage) {
+            match msg {
+                StatusMessage::Show(text, duration) => {
+                    self.generation = self.generation.wrapping_add(1);
+                    self.revert_at = duration.map(|d| Instant::now() + d);
+                    if let Some(d) = duration {
+                        mgr.update_on_timer(d, self.id(), self.generation);
+                    }
+                    *mgr |= Self::set_text(&mut self.message, text);
+                }
+                StatusMessage::Clear => self.revert(mgr),
+                StatusMessage::Section(key, text) => {
+                    if let Some(section) = self.sections.iter_mut().find(|s| s.key == key) {
+                        *mgr |= Self::set_text(&mut section.text, text);
+                    } else {
+                        self.sections.push(Section {
+                            key,
+                            text: Text::new_single(text),
+                            store: Default::default(),
+                        });
+                        *mgr |= TkAction::RESIZE;
+                    }
+                }
+            }
+        }
+
+        fn revert(&mut self, mgr: &mut Manager) {
+            self.revert_at = None;
+            let text = self.default.clone();
+            *mgr |= Self::set_text(&mut self.message, text);
+        }
+
+        fn set_text(text: &mut Text<String>, s: String) -> TkAction {
+            if *text.text() == s {
+                return TkAction::empty();
+            }
+            let avail = Size::from(Vec2::from(text.env().bounds));
+            kas::text::util::set_text_and_prepare(text, s, avail) | TkAction::REDRAW
+        }
+    }
+
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            mgr.subscribe::<StatusMessage>(self.id());
+            // Timers are cleared on reconfigure
+            if let Some(time) = self.revert_at {
+                let delay = time.saturating_duration_since(Instant::now());
+                mgr.update_on_timer(delay, self.id(), self.generation);
+            }
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> layout::Layout<'_> {
+            let text = &mut self.message;
+            let message = layout::Layout::text(&mut self.layout_message, text, TextClass::Label);
+            let mut items = vec![layout::Layout::stretch(message, Stretch::Low)];
+            for section in self.sections.iter_mut() {
+                let text = &mut section.text;
+                let text = layout::Layout::text(&mut section.store, text, TextClass::Label);
+                let align = AlignHints::new(Some(Align::BR), None);
+                items.push(layout::Layout::align(text, align));
+            }
+            layout::Layout::list(items.into_iter(), Direction::Right, &mut self.layout_row)
+        }
+    }
+
+    impl Handler for Self {
+        type Msg = VoidMsg;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+            match event {
+                Event::Bus(msg) => {
+                    if let Some(msg) = msg.downcast_ref::<StatusMessage>() {
+                        self.post(mgr, msg.clone());
+                    }
+                    Response::None
+                }
+                Event::TimerUpdate(payload) => {
+                    if payload == self.generation && self.revert_at.is_some() {
+                        self.revert(mgr);
+                    }
+                    Response::None
+                }
+                _ => Response::Unhandled,
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Input validators for [`EditField`](crate::EditField)
+
+use std::fmt::Debug;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Result of [`Validator::filter`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Validation {
+    /// Accept the edit
+    Accept,
+    /// Reject the edit, restoring the previous contents
+    Reject,
+    /// Replace the contents with the given text and edit position
+    ///
+    /// The edit position is a byte index into the new text.
+    Replace(String, usize),
+}
+
+/// An input validator for an [`EditField`](crate::EditField)
+///
+/// A validator has two roles: *filtering* each edit as it happens (for
+/// example, rejecting letters in a numeric field) and checking whether the
+/// contents form a *valid* value. Contents may be acceptable but not valid
+/// during editing (e.g. `-` in a numeric field): such input is marked as
+/// invalid and the field's [`EditGuard`](crate::EditGuard) is not notified of
+/// activation or loss of focus.
+pub trait Validator: Debug {
+    /// Filter an edit
+    ///
+    /// This is called after each edit with the new `text` and edit position
+    /// `pos` (a byte index).
+    ///
+    /// The default implementation accepts all edits.
+    fn filter(&self, text: &str, pos: usize) -> Validation {
+        let _ = (text, pos);
+        Validation::Accept
+    }
+
+    /// Check whether `text` is a valid value
+    ///
+    /// The default implementation returns `true`.
+    fn is_valid(&self, text: &str) -> bool {
+        let _ = text;
+        true
+    }
+}
+
+/// A validator for numeric input
+///
+/// Allows only digits, a leading minus sign (unless [`Numeric::unsigned`])
+/// and, for floating-point input, a single decimal point.
+#[derive(Clone, Debug)]
+pub struct Numeric {
+    float: bool,
+    negative: bool,
+    range: Option<(f64, f64)>,
+}
+
+impl Numeric {
+    /// Construct for integer input
+    #[inline]
+    pub fn integer() -> Self {
+        Numeric {
+            float: false,
+            negative: true,
+            range: None,
+        }
+    }
+
+    /// Construct for floating-point input
+    #[inline]
+    pub fn float() -> Self {
+        Numeric {
+            float: true,
+            negative: true,
+            range: None,
+        }
+    }
+
+    /// Disallow negative values
+    #[inline]
+    #[must_use]
+    pub fn unsigned(mut self) -> Self {
+        self.negative = false;
+        self
+    }
+
+    /// Restrict valid values to the inclusive range `min..=max`
+    #[inline]
+    #[must_use]
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+}
+
+impl Validator for Numeric {
+    fn filter(&self, text: &str, _: usize) -> Validation {
+        let mut have_point = false;
+        for (i, c) in text.char_indices() {
+            match c {
+                '0'..='9' => (),
+                '-' if self.negative && i == 0 => (),
+                '.' if self.float && !have_point => have_point = true,
+                _ => return Validation::Reject,
+            }
+        }
+        Validation::Accept
+    }
+
+    fn is_valid(&self, text: &str) -> bool {
+        let value = if self.float {
+            match text.parse::<f64>() {
+                Ok(x) if x.is_finite() => x,
+                _ => return false,
+            }
+        } else {
+            match text.parse::<i64>() {
+                Ok(x) => x as f64,
+                Err(_) => return false,
+            }
+        };
+        match self.range {
+            Some((min, max)) => min <= value && value <= max,
+            None => true,
+        }
+    }
+}
+
+/// A validator limiting input length
+///
+/// Length is measured in grapheme clusters (user-perceived characters).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxLength(pub usize);
+
+impl Validator for MaxLength {
+    fn filter(&self, text: &str, _: usize) -> Validation {
+        match text.graphemes(true).count() > self.0 {
+            false => Validation::Accept,
+            true => Validation::Reject,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MaskChar {
+    Digit,
+    Letter,
+    Alphanumeric,
+    Literal(char),
+}
+
+impl MaskChar {
+    fn accepts(self, c: char) -> bool {
+        match self {
+            MaskChar::Digit => c.is_ascii_digit(),
+            MaskChar::Letter => c.is_alphabetic(),
+            MaskChar::Alphanumeric => c.is_alphanumeric(),
+            MaskChar::Literal(l) => c == l,
+        }
+    }
+}
+
+/// A validator formatting input according to a mask
+///
+/// The mask is a string where `#` stands for a digit, `A` for a letter, `*`
+/// for a letter or digit and `\` escapes the following character; all other
+/// characters are literals. For example, `"(###) ###-####"` is a phone-number
+/// mask.
+///
+/// Literals are inserted automatically as input is typed; characters not
+/// matching the mask are dropped. Input is valid when the mask is filled.
+#[derive(Clone, Debug)]
+pub struct Mask {
+    mask: Vec<MaskChar>,
+}
+
+impl Mask {
+    /// Construct from a mask string
+    pub fn new(mask: &str) -> Self {
+        let mut chars = mask.chars();
+        let mut v = Vec::with_capacity(mask.len());
+        while let Some(c) = chars.next() {
+            v.push(match c {
+                '#' => MaskChar::Digit,
+                'A' => MaskChar::Letter,
+                '*' => MaskChar::Alphanumeric,
+                '\\' => match chars.next() {
+                    Some(c) => MaskChar::Literal(c),
+                    None => break,
+                },
+                c => MaskChar::Literal(c),
+            });
+        }
+        Mask { mask: v }
+    }
+}
+
+impl Validator for Mask {
+    fn filter(&self, text: &str, pos: usize) -> Validation {
+        let mut out = String::with_capacity(text.len());
+        let mut new_pos = 0;
+        // Literals are only emitted once followed by input
+        let mut pending = String::new();
+        let mut input = text.char_indices().peekable();
+
+        let mut complete = true;
+        'mask: for m in self.mask.iter().cloned() {
+            if let MaskChar::Literal(l) = m {
+                pending.push(l);
+                if input.peek().map(|(_, c)| *c == l).unwrap_or(false) {
+                    input.next();
+                }
+                continue;
+            }
+
+            loop {
+                let (i, c) = match input.next() {
+                    Some(item) => item,
+                    None => {
+                        complete = false;
+                        break 'mask;
+                    }
+                };
+                if m.accepts(c) {
+                    out.push_str(&pending);
+                    pending.clear();
+                    out.push(c);
+                    if i < pos {
+                        new_pos = out.len();
+                    }
+                    break;
+                }
+            }
+        }
+        if complete {
+            out.push_str(&pending);
+            if pos >= text.len() {
+                new_pos = out.len();
+            }
+        }
+
+        if out == text {
+            Validation::Accept
+        } else {
+            Validation::Replace(out, new_pos)
+        }
+    }
+
+    fn is_valid(&self, text: &str) -> bool {
+        let mut chars = text.chars();
+        self.mask
+            .iter()
+            .all(|m| chars.next().map(|c| m.accepts(c)).unwrap_or(false))
+            && chars.next().is_none()
+    }
+}
+
+/// A validator matching input against a regular expression
+///
+/// Input is valid when the whole text matches the expression. Edits are not
+/// filtered (partial input rarely matches).
+#[cfg(feature = "regex")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "regex")))]
+#[derive(Clone, Debug)]
+pub struct RegexValidator {
+    regex: dep_regex::Regex,
+}
+
+#[cfg(feature = "regex")]
+impl RegexValidator {
+    /// Construct from a pattern
+    ///
+    /// The pattern is anchored to match the whole input.
+    pub fn new(pattern: &str) -> Result<Self, dep_regex::Error> {
+        let regex = dep_regex::Regex::new(&format!("^(?:{})$", pattern))?;
+        Ok(RegexValidator { regex })
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Validator for RegexValidator {
+    fn is_valid(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
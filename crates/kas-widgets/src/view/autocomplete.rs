@@ -0,0 +1,312 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Auto-complete edit box
+
+use super::filter_list::FilteredList;
+use super::{driver, Driver, ListView, SelectionMode};
+use crate::{EditBox, Scrollable};
+use kas::dir::Down;
+use kas::event::{ChildMsg, Command};
+use kas::layout;
+use kas::prelude::*;
+use kas::updatable::filter::Filter;
+use kas::updatable::{ListData, SingleData, Updatable, UpdatableHandler};
+use kas::WindowId;
+use UpdatableHandler as UpdHandler;
+
+widget! {
+    /// An edit box offering suggestions from a list
+    ///
+    /// As the user types, items of the suggestion source `T` are matched
+    /// against the text using filter `F` (for example
+    /// [`kas::updatable::filter::ContainsCaseInsensitive`]) and matches are
+    /// shown in a pop-up [`ListView`] below the edit box.
+    ///
+    /// While the pop-up is open, <kbd>Up</kbd> and <kbd>Down</kbd> highlight a
+    /// suggestion; <kbd>Enter</kbd> or <kbd>Tab</kbd> accepts the highlighted
+    /// suggestion, as does clicking a suggestion; <kbd>Escape</kbd> closes the
+    /// pop-up. <kbd>Down</kbd> opens the pop-up when closed. On acceptance the
+    /// text is replaced with the suggestion (formatted via [`ToString`]) and
+    /// the suggestion is returned as a message.
+    ///
+    /// The pop-up list only allocates widgets for visible suggestions, thus
+    /// large sources are supported, though note that filtering is `O(n)` in
+    /// the size of the source on each edit.
+    #[derive(Debug)]
+    pub struct AutoComplete<
+        T: ListData + UpdHandler<T::Key, V::Msg> + 'static,
+        F: Filter<T::Item> + SingleData<Item = String>,
+        V: Driver<T::Item> = driver::Default,
+    > {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        edit: EditBox,
+        #[widget]
+        list: ListView<Down, FilteredList<T, F>, V>,
+        highlight: Option<usize>,
+        popup_id: Option<WindowId>,
+    }
+
+    impl Self where V: Default {
+        /// Construct with the given suggestion source and filter
+        pub fn new(data: T, filter: F) -> Self {
+            Self::new_with_driver(<V as Default>::default(), data, filter)
+        }
+    }
+    impl Self {
+        /// Construct with explicit driver for suggestions
+        pub fn new_with_driver(view: V, data: T, filter: F) -> Self {
+            let _ = filter.update(String::new());
+            let data = FilteredList::new(data, filter);
+            let list = ListView::new_with_driver(view, data)
+                .with_selection_mode(SelectionMode::Single)
+                .with_num_visible(8);
+            AutoComplete {
+                core: Default::default(),
+                edit: EditBox::new(""),
+                list,
+                highlight: None,
+                popup_id: None,
+            }
+        }
+
+        /// Access the suggestion source
+        pub fn data(&self) -> &T {
+            &self.list.data().data
+        }
+
+        /// Mutably access the suggestion source
+        ///
+        /// It may be necessary to use [`AutoComplete::update_view`] to update
+        /// suggestions after changes to the source.
+        pub fn data_mut(&mut self) -> &mut T {
+            &mut self.list.data_mut().data
+        }
+
+        /// Get the number of current suggestions
+        pub fn num_suggestions(&self) -> usize {
+            self.list.data().len()
+        }
+
+        /// Get the index of the highlighted suggestion, if any
+        pub fn highlighted(&self) -> Option<usize> {
+            self.highlight
+        }
+
+        /// True if the pop-up is open
+        pub fn is_open(&self) -> bool {
+            self.popup_id.is_some()
+        }
+
+        /// Manually trigger an update to handle changed data
+        pub fn update_view(&mut self, mgr: &mut Manager) {
+            let _ = self.list.data().refresh();
+            self.list.update_view(mgr);
+            self.set_highlight(mgr, None);
+        }
+
+        fn open_popup(&mut self, mgr: &mut Manager) -> bool {
+            if self.popup_id.is_none() && self.list.data().len() > 0 {
+                self.popup_id = mgr.add_popup(kas::Popup {
+                    id: self.list.id(),
+                    parent: self.id(),
+                    direction: Direction::Down,
+                });
+                // Opening a pop-up clears navigation focus: restore it
+                mgr.set_nav_focus((*self.edit).id(), true);
+            }
+            self.popup_id.is_some()
+        }
+
+        fn close_popup(&mut self, mgr: &mut Manager) {
+            if let Some(id) = self.popup_id.take() {
+                mgr.close_window(id, true);
+            }
+        }
+
+        fn set_highlight(&mut self, mgr: &mut Manager, index: Option<usize>) {
+            self.highlight = index;
+            self.list.clear_selected();
+            if let Some(index) = index {
+                if let Some((key, _)) = self.list.data().iter_vec_from(index, 1).pop() {
+                    let _ = self.list.select(key);
+                }
+                self.list.scroll_to(mgr, index);
+            }
+            mgr.redraw(self.list.id());
+        }
+
+        /// Re-query suggestions after an edit
+        fn edited(&mut self, mgr: &mut Manager) {
+            let text = self.edit.get_string();
+            if text == self.list.data().filter.get_cloned() {
+                return;
+            }
+            let _ = self.list.data().filter.update(text.clone());
+            self.update_view(mgr);
+            let _ = self.list.set_scroll_offset(mgr, Offset::ZERO);
+            if text.is_empty() || self.list.data().len() == 0 {
+                self.close_popup(mgr);
+            } else {
+                self.open_popup(mgr);
+            }
+        }
+
+        /// Handle a command while the pop-up is open
+        ///
+        /// Returns `None` if the command should be passed to the edit box.
+        fn popup_command(&mut self, mgr: &mut Manager, cmd: Command) -> Option<Response<T::Item>>
+        where
+            T::Item: ToString,
+        {
+            let last = self.list.data().len().wrapping_sub(1);
+            let index = match (cmd, self.highlight) {
+                (Command::Escape, _) => {
+                    self.close_popup(mgr);
+                    return Some(Response::None);
+                }
+                (Command::Return | Command::Tab, Some(index)) => {
+                    return Some(self.accept(mgr, index));
+                }
+                (Command::Return | Command::Tab, None) => {
+                    self.close_popup(mgr);
+                    return None;
+                }
+                _ if last == usize::MAX => return None,
+                (Command::Down, None) => Some(0),
+                (Command::Down, Some(index)) => Some((index + 1).min(last)),
+                (Command::Up, None) => Some(last),
+                (Command::Up, Some(index)) => index.checked_sub(1),
+                (Command::PageDown, index) => {
+                    Some(index.map(|i| (i + 8).min(last)).unwrap_or(0))
+                }
+                (Command::PageUp, index) => Some(index.unwrap_or(0).saturating_sub(8)),
+                _ => return None,
+            };
+            self.set_highlight(mgr, index);
+            Some(Response::None)
+        }
+
+        /// Accept suggestion `index`
+        fn accept(&mut self, mgr: &mut Manager, index: usize) -> Response<T::Item>
+        where
+            T::Item: ToString,
+        {
+            self.close_popup(mgr);
+            let item = match self.list.data().iter_vec_from(index, 1).pop() {
+                Some((_, item)) => item,
+                None => return Response::None,
+            };
+            let text = item.to_string();
+            *mgr |= self.edit.set_string(text.clone());
+            self.edit.move_cursor_to_end(mgr);
+            let _ = self.list.data().filter.update(text);
+            self.update_view(mgr);
+            Response::Msg(item)
+        }
+
+        fn list_response(
+            &mut self,
+            mgr: &mut Manager,
+            r: Response<ChildMsg<T::Key, <V::Widget as Handler>::Msg>>,
+        ) -> Response<T::Item>
+        where
+            T::Item: ToString,
+        {
+            match Response::try_from(r) {
+                Ok(r) => r,
+                Err(ChildMsg::Select(_, ctx)) => self.accept(mgr, ctx.index),
+                Err(_) => Response::None,
+            }
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> layout::Layout<'_> {
+            layout::Layout::single(&mut self.edit)
+        }
+
+        fn spatial_nav(&mut self, _: &mut Manager, _: bool, from: Option<usize>) -> Option<usize> {
+            // The pop-up list is not navigable
+            match from {
+                None => Some(0),
+                Some(_) => None,
+            }
+        }
+    }
+
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            // We must refresh suggestions when the source changes
+            if let Some(handle) = self.list.data().data.update_handle() {
+                mgr.update_on_handle(handle, self.id());
+            }
+        }
+    }
+
+    impl Handler for Self where T::Item: ToString {
+        type Msg = T::Item;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Self::Msg> {
+            match event {
+                Event::HandleUpdate { .. } => {
+                    self.update_view(mgr);
+                    Response::None
+                }
+                Event::PressStart { start_id, .. } => {
+                    // This is sent here first while the pop-up is open
+                    if self.list.is_ancestor_of(start_id) {
+                        let r = self.list.send(mgr, start_id, event);
+                        self.list_response(mgr, r)
+                    } else if self.edit.is_ancestor_of(start_id) {
+                        self.edit.send(mgr, start_id, event).void_into()
+                    } else {
+                        self.close_popup(mgr);
+                        Response::Unhandled
+                    }
+                }
+                Event::PopupRemoved(id) => {
+                    if self.popup_id == Some(id) {
+                        self.popup_id = None;
+                    }
+                    Response::None
+                }
+                _ => Response::Unhandled,
+            }
+        }
+    }
+
+    impl SendEvent for Self where T::Item: ToString {
+        fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            if self.is_disabled() {
+                return Response::Unhandled;
+            }
+
+            if id <= self.edit.id() {
+                if let Event::Command(cmd, _) = event {
+                    if self.popup_id.is_some() {
+                        if let Some(r) = self.popup_command(mgr, cmd) {
+                            return r;
+                        }
+                    } else if cmd == Command::Down && self.open_popup(mgr) {
+                        return Response::None;
+                    }
+                }
+                let r = self.edit.send(mgr, id, event);
+                if matches!(r, Response::Update) {
+                    self.edited(mgr);
+                }
+                r.void_into()
+            } else if id <= self.list.id() {
+                let r = self.list.send(mgr, id, event);
+                self.list_response(mgr, r)
+            } else {
+                Manager::handle_generic(self, mgr, event)
+            }
+        }
+    }
+}
@@ -56,6 +56,28 @@ pub trait Driver<T>: Debug + 'static {
     /// Often it will be sufficient to implement custom handling/update logic
     /// in only one of these places.
     fn get(&self, widget: &Self::Widget) -> Option<T>;
+
+    /// Set the viewed data for a group header
+    ///
+    /// This is used instead of [`Driver::set`] for items which the data source
+    /// marks as a group header (see
+    /// [`kas::updatable::ListData::is_group_header`]), allowing the driver to
+    /// present these distinctly. The default implementation calls
+    /// [`Driver::set`].
+    fn set_header(&self, widget: &mut Self::Widget, data: T) -> TkAction {
+        self.set(widget, data)
+    }
+
+    /// Set the widget to represent an empty position
+    ///
+    /// Sparse data sources (see [`kas::updatable::ListData::logical_len`] and
+    /// [`kas::updatable::MatrixData`]) may have positions without data. When
+    /// this method returns an action, the widget is shown as a placeholder at
+    /// such positions; when it returns `None` (the default), nothing is shown.
+    fn set_empty(&self, widget: &mut Self::Widget) -> Option<TkAction> {
+        let _ = widget;
+        None
+    }
 }
 
 /// Default view widget constructor
@@ -90,6 +112,9 @@ macro_rules! impl_via_to_string {
                 widget.set_string(data.to_string())
             }
             fn get(&self, _: &Self::Widget) -> Option<$t> { None }
+            fn set_empty(&self, widget: &mut Self::Widget) -> Option<TkAction> {
+                Some(widget.set_string(String::new()))
+            }
         }
         impl Driver<$t> for DefaultNav {
             type Msg = VoidMsg;
@@ -101,6 +126,9 @@ macro_rules! impl_via_to_string {
                 widget.set_string(data.to_string())
             }
             fn get(&self, _: &Self::Widget) -> Option<$t> { None }
+            fn set_empty(&self, widget: &mut Self::Widget) -> Option<TkAction> {
+                Some(widget.set_string(String::new()))
+            }
         }
     };
     ($t:ty, $($tt:ty),+) => {
@@ -172,6 +200,12 @@ where
     fn get(&self, widget: &Self::Widget) -> Option<T> {
         Default.get(widget)
     }
+    fn set_header(&self, widget: &mut Self::Widget, data: T) -> TkAction {
+        Default.set_header(widget, data)
+    }
+    fn set_empty(&self, widget: &mut Self::Widget) -> Option<TkAction> {
+        Default.set_empty(widget)
+    }
 }
 
 impl<G: EditGuard + std::default::Default> Driver<String> for Widget<EditField<G>> {
@@ -187,6 +221,9 @@ impl<G: EditGuard + std::default::Default> Driver<String> for Widget<EditField<G
     fn get(&self, widget: &Self::Widget) -> Option<String> {
         Some(widget.get_string())
     }
+    fn set_empty(&self, widget: &mut Self::Widget) -> Option<TkAction> {
+        Some(widget.set_string(String::new()))
+    }
 }
 impl<G: EditGuard + std::default::Default> Driver<String> for Widget<EditBox<G>> {
     type Msg = G::Msg;
@@ -201,6 +238,9 @@ impl<G: EditGuard + std::default::Default> Driver<String> for Widget<EditBox<G>>
     fn get(&self, widget: &Self::Widget) -> Option<String> {
         Some(widget.get_string())
     }
+    fn set_empty(&self, widget: &mut Self::Widget) -> Option<TkAction> {
+        Some(widget.set_string(String::new()))
+    }
 }
 
 impl<D: Directional + std::default::Default> Driver<f32> for Widget<ProgressBar<D>> {
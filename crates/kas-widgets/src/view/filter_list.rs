@@ -32,22 +32,22 @@ use UpdatableHandler as UpdHandler;
 /// Warning: this implementation is `O(n)` where `n = data.len()` and not well
 /// optimised, thus is expected to be slow on large data lists.
 #[derive(Clone, Debug)]
-struct FilteredList<T: ListData, F: Filter<T::Item>> {
+pub(super) struct FilteredList<T: ListData, F: Filter<T::Item>> {
     /// Direct access to unfiltered data
     ///
     /// If adjusting this, one should call [`FilteredList::refresh`] after.
-    data: T,
+    pub(super) data: T,
     /// Direct access to the filter
     ///
     /// If adjusting this, one should call [`FilteredList::refresh`] after.
-    filter: F,
+    pub(super) filter: F,
     view: RefCell<Vec<T::Key>>, // TODO: does this need to be in a RefCell?
 }
 
 impl<T: ListData, F: Filter<T::Item>> FilteredList<T, F> {
     /// Construct and apply filter
     #[inline]
-    fn new(data: T, filter: F) -> Self {
+    pub(super) fn new(data: T, filter: F) -> Self {
         let len = data.len().cast();
         let view = RefCell::new(Vec::with_capacity(len));
         let s = FilteredList { data, filter, view };
@@ -61,7 +61,7 @@ impl<T: ListData, F: Filter<T::Item>> FilteredList<T, F> {
     /// Calling this directly may be useful in case the data is modified.
     ///
     /// An update should be triggered using the returned handle.
-    fn refresh(&self) -> Option<UpdateHandle> {
+    pub(super) fn refresh(&self) -> Option<UpdateHandle> {
         let mut view = self.view.borrow_mut();
         view.clear();
         for (key, item) in self.data.iter_vec(usize::MAX) {
@@ -143,6 +143,10 @@ impl<T: ListData + 'static, F: Filter<T::Item>> ListData for FilteredList<T, F>
         }
         v
     }
+
+    fn is_group_header(&self, key: &Self::Key) -> bool {
+        self.data.is_group_header(key)
+    }
 }
 
 widget! {
@@ -363,6 +367,16 @@ widget! {
             self.list.max_scroll_offset()
         }
 
+        #[inline]
+        fn content_size(&self) -> Size {
+            self.list.content_size()
+        }
+
+        #[inline]
+        fn viewport_size(&self) -> Size {
+            self.list.viewport_size()
+        }
+
         #[inline]
         fn scroll_offset(&self) -> Offset {
             self.list.scroll_offset()
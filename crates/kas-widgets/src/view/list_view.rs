@@ -5,22 +5,30 @@
 
 //! List view widget
 
-use super::{driver, Driver, PressPhase, SelectionError, SelectionMode};
+use super::rubber_band::{item_range, RubberBand, AUTO_SCROLL_PAYLOAD};
+use super::{content_len, first_item, saturating_i32, VisibleLen};
+use super::{driver, Driver, PressPhase, SelectionError, SelectionMode, WidgetPool};
 #[allow(unused)] // doc links
 use crate::ScrollBars;
 use crate::{ScrollComponent, Scrollable};
-use kas::event::{ChildMsg, Command, CursorIcon, GrabMode, PressSource};
+use instant::Instant;
+use kas::event::{ChildMsg, Command, ConfigureManager, CursorIcon, GrabMode, PressSource};
+use kas::event::{InputKind, ItemContext};
 use kas::layout::solve_size_rules;
 use kas::prelude::*;
 use kas::updatable::{ListData, UpdatableHandler};
 use linear_map::set::LinearSet;
 use log::{debug, trace};
-use std::time::Instant;
+use std::ops::Range;
 use UpdatableHandler as UpdHandler;
 
 #[derive(Clone, Debug, Default)]
 struct WidgetData<K, W> {
     key: Option<K>,
+    /// False when hidden (at an empty position without placeholder)
+    shown: bool,
+    /// True when viewing a group header
+    header: bool,
     widget: W,
 }
 
@@ -40,6 +48,20 @@ widget! {
     ///
     /// This widget is [`Scrollable`], supporting keyboard, wheel and drag
     /// scrolling. You may wish to wrap this widget with [`ScrollBars`].
+    ///
+    /// Very long lists are supported, but the scrollable content length
+    /// saturates at `i32::MAX` pixels: items beyond this cannot be reached by
+    /// scrolling.
+    ///
+    /// Sparse data is supported: items are placed at their logical position
+    /// (see [`ListData::logical_len`]) with [`Driver::set_empty`] placeholders
+    /// between.
+    ///
+    /// Items which the data marks as group headers (see
+    /// [`ListData::is_group_header`]) are set using [`Driver::set_header`],
+    /// drawn with a distinct background and may not be selected. Optionally,
+    /// the header of the first visible group may "stick" to the start of the
+    /// view (see [`ListView::with_sticky_headers`]).
     #[derive(Clone, Debug)]
     #[handler(msg=ChildMsg<T::Key, <V::Widget as Handler>::Msg>)]
     pub struct ListView<
@@ -55,12 +77,20 @@ widget! {
         view: V,
         data: T,
         widgets: Vec<WidgetData<T::Key, V::Widget>>,
+        /// Widget used to draw a sticky group header, if enabled
+        sticky: Option<WidgetData<T::Key, V::Widget>>,
+        sticky_headers: bool,
+        pool: Option<WidgetPool<V::Widget>>,
         /// The number of widgets in use (cur_len ≤ widgets.len())
         cur_len: u32,
         /// The first visible data item
         direction: D,
         align_hints: AlignHints,
+        visible_len: VisibleLen,
+        /// Resolved value of `visible_len`
         ideal_visible: i32,
+        /// Window size along the main axis, as last seen
+        window_len: i32,
         child_size_min: i32,
         child_size_ideal: i32,
         child_inter_margin: i32,
@@ -71,7 +101,8 @@ widget! {
         selection: LinearSet<T::Key>,
         press_event: Option<PressSource>,
         press_phase: PressPhase,
-        press_target: Option<T::Key>,
+        press_target: Option<(T::Key, ItemContext)>,
+        band: Option<RubberBand<T::Key>>,
     }
 
     impl Self where D: Default, V: Default {
@@ -114,10 +145,15 @@ widget! {
                 view,
                 data,
                 widgets: Default::default(),
+                sticky: None,
+                sticky_headers: false,
+                pool: None,
                 cur_len: 0,
                 direction,
                 align_hints: Default::default(),
+                visible_len: VisibleLen::Fixed(5),
                 ideal_visible: 5,
+                window_len: 0,
                 child_size_min: 0,
                 child_size_ideal: 0,
                 child_inter_margin: 0,
@@ -128,6 +164,7 @@ widget! {
                 press_event: None,
                 press_phase: PressPhase::None,
                 press_target: None,
+                band: None,
             }
         }
 
@@ -221,7 +258,7 @@ widget! {
         ///
         /// Returns `true` if selected, `false` if already selected.
         /// Fails if selection mode does not permit selection or if the key is
-        /// invalid.
+        /// invalid or a group header.
         ///
         /// Does not send [`ChildMsg`] responses.
         pub fn select(&mut self, key: T::Key) -> Result<bool, SelectionError> {
@@ -233,6 +270,9 @@ widget! {
             if !self.data.contains_key(&key) {
                 return Err(SelectionError::Key);
             }
+            if self.data.is_group_header(&key) {
+                return Err(SelectionError::Header);
+            }
             Ok(self.selection.insert(key))
         }
 
@@ -246,12 +286,25 @@ widget! {
             self.selection.remove(key)
         }
 
+        /// Use a shared widget pool, builder style
+        ///
+        /// Surplus view widgets are returned to the pool, and new view widgets
+        /// are taken from the pool where possible. See [`WidgetPool`].
+        pub fn with_pool(mut self, pool: WidgetPool<V::Widget>) -> Self {
+            self.pool = Some(pool);
+            self
+        }
+
         /// Manually trigger an update to handle changed data
         pub fn update_view(&mut self, mgr: &mut Manager) {
             let data = &self.data;
             self.selection.retain(|key| data.contains_key(key));
             for w in &mut self.widgets {
                 w.key = None;
+                w.shown = false;
+            }
+            if let Some(w) = self.sticky.as_mut() {
+                w.key = None;
             }
             self.update_widgets(mgr);
             // Force SET_SIZE so that scroll-bar wrappers get updated
@@ -259,6 +312,21 @@ widget! {
             *mgr |= TkAction::SET_SIZE;
         }
 
+        /// Scroll to make the item at `index` visible
+        ///
+        /// Does nothing if `index` is out of range.
+        pub fn scroll_to(&mut self, mgr: &mut Manager, index: usize) {
+            if index >= self.data.logical_len() {
+                return;
+            }
+            let solver = self.position_solver(mgr);
+            let (_, action) = self.scroll.focus_rect(solver.rect(index), self.core.rect);
+            if !action.is_empty() {
+                *mgr |= action;
+                self.update_widgets(mgr);
+            }
+        }
+
         /// Get the direction of contents
         pub fn direction(&self) -> Direction {
             self.direction.as_direction()
@@ -268,49 +336,88 @@ widget! {
         ///
         /// This affects the (ideal) size request and whether children are sized
         /// according to their ideal or minimum size but not the minimum size.
-        pub fn with_num_visible(mut self, number: i32) -> Self {
-            self.ideal_visible = number;
+        pub fn with_num_visible(self, number: i32) -> Self {
+            self.with_visible_len(VisibleLen::Fixed(number))
+        }
+
+        /// Set the preferred number of items visible, possibly adaptive (inline)
+        ///
+        /// See [`VisibleLen`]. The default is `VisibleLen::Fixed(5)`.
+        pub fn with_visible_len(mut self, len: VisibleLen) -> Self {
+            self.visible_len = len;
+            self.resolve_visible_len();
+            self
+        }
+
+        /// Set the preferred number of items visible, possibly adaptive
+        ///
+        /// See [`VisibleLen`].
+        pub fn set_visible_len(&mut self, len: VisibleLen) -> TkAction {
+            self.visible_len = len;
+            self.resolve_visible_len();
+            TkAction::RESIZE
+        }
+
+        /// Get the preferred number of items visible
+        pub fn visible_len(&self) -> VisibleLen {
+            self.visible_len
+        }
+
+        /// Resolve `visible_len`, returning true if `ideal_visible` changed
+        fn resolve_visible_len(&mut self) -> bool {
+            let item_size = self.child_size_ideal + self.child_inter_margin;
+            let data_len = self.data.len();
+            let len = self.visible_len.resolve(data_len, item_size, self.window_len);
+            std::mem::replace(&mut self.ideal_visible, len) != len
+        }
+
+        /// Enable sticky group headers (inline)
+        ///
+        /// When enabled, the group header (see [`ListData::group_header_of`])
+        /// of the first visible item is drawn over the start of the view. This
+        /// is not supported with reversed directions.
+        pub fn with_sticky_headers(mut self, sticky: bool) -> Self {
+            self.sticky_headers = sticky;
             self
         }
 
         /// Construct a position solver. Note: this does more work and updates to
         /// self than is necessary in several cases where it is used.
         fn position_solver(&mut self, mgr: &mut Manager) -> PositionSolver {
-            let data_len = self.data.len();
-            let data_len32 = i32::conv(data_len);
+            let data_len = self.data.logical_len();
             let view_size = self.rect().size;
             let mut content_size = view_size;
-            let mut skip;
+            let skip;
             if self.direction.is_horizontal() {
-                skip = Offset(self.child_size.0 + self.child_inter_margin, 0);
-                content_size.0 = (skip.0 * data_len32 - self.child_inter_margin).max(0);
+                skip = Offset((self.child_size.0 + self.child_inter_margin).max(1), 0);
+                content_size.0 = content_len(skip.0, data_len, self.child_inter_margin);
             } else {
-                skip = Offset(0, self.child_size.1 + self.child_inter_margin);
-                content_size.1 = (skip.1 * data_len32 - self.child_inter_margin).max(0);
+                skip = Offset(0, (self.child_size.1 + self.child_inter_margin).max(1));
+                content_size.1 = content_len(skip.1, data_len, self.child_inter_margin);
             }
             *mgr |= self.scroll.set_sizes(view_size, content_size);
 
-            let offset = u64::conv(self.scroll_offset().extract(self.direction));
+            let offset = self.scroll_offset().extract(self.direction);
             // first visible data item, in downward direction:
-            let mut first_data = usize::conv(offset / u64::conv(skip.extract(self.direction)));
+            let mut first_data = first_item(offset, skip.extract(self.direction)).min(data_len);
 
             // set_rect allocates enough widgets to view a page; we update widget-data allocations
             let cur_len = self.widgets.len().min(data_len - first_data);
             self.cur_len = cur_len.cast();
 
-            let mut pos_start = self.core.rect.pos + self.frame_offset;
-            if self.direction.is_reversed() {
+            let mut reversed = None;
+            if self.direction.is_reversed() && data_len > 0 {
                 first_data = (data_len - first_data).saturating_sub(cur_len);
-                pos_start += skip * i32::conv(data_len - 1);
-                skip = skip * -1;
+                reversed = Some(data_len - 1);
             }
 
             PositionSolver {
-                pos_start,
+                pos_start: self.core.rect.pos + self.frame_offset,
                 skip,
                 size: self.child_size,
                 first_data,
                 cur_len,
+                reversed,
             }
         }
 
@@ -319,28 +426,136 @@ widget! {
             let solver = self.position_solver(mgr);
 
             let mut action = TkAction::empty();
-            for (i, item) in self
-                .data
-                .iter_vec_from(solver.first_data, solver.cur_len)
-                .into_iter()
-                .enumerate()
-            {
-                let i = solver.first_data + i;
-                let key = Some(item.0.clone());
+            let range = solver.first_data..(solver.first_data + solver.cur_len);
+            let mut items = self.data.iter_vec_range(range.clone()).into_iter().peekable();
+            for i in range {
                 let w = &mut self.widgets[i % solver.cur_len];
-                if key != w.key {
-                    w.key = key;
-                    action |= self.view.set(&mut w.widget, item.1);
+                if let Some((_, key, item)) = items.next_if(|item| item.0 == i) {
+                    let header = self.data.is_group_header(&key);
+                    if w.key.as_ref() != Some(&key) || !w.shown || w.header != header {
+                        w.key = Some(key);
+                        w.shown = true;
+                        w.header = header;
+                        action |= match header {
+                            false => self.view.set(&mut w.widget, item),
+                            true => self.view.set_header(&mut w.widget, item),
+                        };
+                    }
+                } else if w.key.is_some() || !w.shown {
+                    // Empty position of sparse data
+                    w.key = None;
+                    w.header = false;
+                    w.shown = match self.view.set_empty(&mut w.widget) {
+                        Some(act) => {
+                            action |= act;
+                            true
+                        }
+                        None => false,
+                    };
                 }
                 let rect = solver.rect(i);
                 if w.widget.rect() != rect {
                     w.widget.set_rect(mgr, rect, self.align_hints);
                 }
             }
+            action |= self.update_sticky(mgr, &solver);
             *mgr |= action;
             let dur = (Instant::now() - time).as_micros();
             trace!("ListView::update_widgets completed in {}μs", dur);
         }
+
+        /// Update the sticky group header, if enabled
+        fn update_sticky(&mut self, mgr: &mut Manager, solver: &PositionSolver) -> TkAction {
+            let w = match self.sticky.as_mut() {
+                Some(w) => w,
+                None => return TkAction::empty(),
+            };
+            w.shown = false;
+            if solver.cur_len == 0 || solver.reversed.is_some() {
+                return TkAction::empty();
+            }
+
+            let offset = self.scroll.offset().extract(self.direction);
+            let skip = solver.skip.extract(self.direction);
+            let first = first_item(offset, skip);
+            let (index, key, item) = match self.data.group_header_of(first) {
+                Some(header) => header,
+                None => return TkAction::empty(),
+            };
+            let start = solver.pos_start.extract(self.direction);
+            let top = start.saturating_add(offset);
+            if solver.rect(index).pos.extract(self.direction) >= top {
+                // The header is fully visible
+                return TkAction::empty();
+            }
+
+            // The next group header pushes this one out of the view
+            let mut pos = top;
+            let next = first.saturating_add(1);
+            let range = next..next.saturating_add(1);
+            if let Some((i, next, _)) = self.data.iter_vec_range(range).pop() {
+                if self.data.is_group_header(&next) {
+                    let next_pos = solver.rect(i).pos.extract(self.direction);
+                    pos = pos.min(next_pos.saturating_sub(skip));
+                }
+            }
+
+            let mut action = TkAction::empty();
+            if w.key.as_ref() != Some(&key) {
+                w.key = Some(key);
+                action = self.view.set_header(&mut w.widget, item);
+            }
+            w.shown = true;
+            let mut rect = Rect::new(solver.pos_start, solver.size);
+            rect.pos.set_component(self.direction, pos);
+            if w.widget.rect() != rect {
+                w.widget.set_rect(mgr, rect, self.align_hints);
+            }
+            action
+        }
+
+        /// Update selection from the rubber band, if any
+        fn update_band(&mut self, mgr: &mut Manager) {
+            if self.band.is_none() {
+                return;
+            }
+            let solver = self.position_solver(mgr);
+            let band = self.band.as_ref().unwrap();
+            let rect = band.rect();
+            let (pos, size) = (solver.pos_start, solver.size);
+            let range = if self.direction.is_vertical() {
+                if rect.pos.0 < pos.0 + size.0 && rect.pos.0 + rect.size.0 > pos.0 {
+                    let (a, b) = (rect.pos.1, rect.pos.1 + rect.size.1);
+                    item_range(a, b, pos.1, solver.skip.1, size.1, self.data.logical_len())
+                } else {
+                    0..0
+                }
+            } else if rect.pos.1 < pos.1 + size.1 && rect.pos.1 + rect.size.1 > pos.1 {
+                let (a, b) = (rect.pos.0, rect.pos.0 + rect.size.0);
+                item_range(a, b, pos.0, solver.skip.0, size.0, self.data.logical_len())
+            } else {
+                0..0
+            };
+            let range = solver.data_range(range);
+            let data = &self.data;
+            let hits = data.iter_vec_range(range).into_iter().map(|item| item.1);
+            band.apply(&mut self.selection, hits.filter(|key| !data.is_group_header(key)));
+            mgr.redraw(self.id());
+            if band.auto_scroll_delta(self.core.rect) != Offset::ZERO {
+                mgr.update_on_frame(self.id(), AUTO_SCROLL_PAYLOAD);
+            }
+        }
+
+        /// Context for a message from child `index`
+        fn item_context(&mut self, mgr: &mut Manager, index: usize, event: &Event) -> ItemContext {
+            let solver = self.position_solver(mgr);
+            let index = if solver.cur_len > 0 { solver.child_to_data(index) } else { index };
+            ItemContext {
+                index,
+                column: None,
+                input: InputKind::from_event(event),
+            }
+        }
     }
 
     impl Scrollable for Self {
@@ -348,8 +563,8 @@ widget! {
             // TODO: maybe we should support a scrollbar on the other axis?
             // We would need to report a fake min-child-size to enable scrolling.
             let item_min = self.child_size_min + self.child_inter_margin;
-            let num = i32::conv(self.data.len());
-            let min_size = (item_min * num - self.child_inter_margin).max(0);
+            let len = self.data.logical_len();
+            let min_size = content_len(item_min, len, self.child_inter_margin);
             (
                 self.direction.is_horizontal() && min_size > size.0,
                 self.direction.is_vertical() && min_size > size.1,
@@ -361,6 +576,16 @@ widget! {
             self.scroll.max_offset()
         }
 
+        #[inline]
+        fn content_size(&self) -> Size {
+            self.scroll.content_size()
+        }
+
+        #[inline]
+        fn viewport_size(&self) -> Size {
+            self.scroll.window_size()
+        }
+
         #[inline]
         fn scroll_offset(&self) -> Offset {
             self.scroll.offset()
@@ -384,14 +609,22 @@ widget! {
         }
         #[inline]
         fn num_children(&self) -> usize {
-            self.widgets.len()
+            self.widgets.len() + usize::from(self.sticky.is_some())
         }
         #[inline]
         fn get_child(&self, index: usize) -> Option<&dyn WidgetConfig> {
-            self.widgets.get(index).map(|w| w.widget.as_widget())
+            // The sticky header widget, if any, follows the list widgets
+            let sticky = self.sticky.as_ref().filter(|_| index == self.widgets.len());
+            self.widgets
+                .get(index)
+                .or(sticky)
+                .map(|w| w.widget.as_widget())
         }
         #[inline]
         fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+            if index == self.widgets.len() {
+                return self.sticky.as_mut().map(|w| w.widget.as_widget_mut());
+            }
             self.widgets
                 .get_mut(index)
                 .map(|w| w.widget.as_widget_mut())
@@ -399,6 +632,22 @@ widget! {
     }
 
     impl WidgetConfig for Self {
+        fn configure_recurse(&mut self, mut cmgr: ConfigureManager) {
+            self.record_first_id(cmgr.peek_next());
+            for i in 0..self.widgets.len() {
+                self.widgets[i].widget.configure_recurse(cmgr.child());
+            }
+            if let Some(w) = self.sticky.as_mut() {
+                w.widget.configure_recurse(cmgr.child());
+            }
+            // Leave room to configure more widgets without a full reconfigure
+            let used = u32::from(cmgr.peek_next()) - u32::from(self.first_id());
+            cmgr.reserve_ids(self.id(), used.max(16));
+            self.core_data_mut().id = cmgr.next_id(self.id());
+            self.core_data_mut().bump_generation();
+            self.configure(cmgr.mgr());
+        }
+
         fn configure(&mut self, mgr: &mut Manager) {
             if let Some(handle) = self.data.update_handle() {
                 mgr.update_on_handle(handle, self.id());
@@ -420,6 +669,7 @@ widget! {
                 self.child_size_ideal = rules.ideal_size();
                 let m = rules.margins_i32();
                 self.child_inter_margin = m.0.max(m.1).max(inner_margin);
+                self.resolve_visible_len();
                 rules.multiply_with_margin(2, self.ideal_visible);
                 rules.set_stretch(rules.stretch().max(Stretch::High));
             }
@@ -432,14 +682,22 @@ widget! {
         fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, mut align: AlignHints) {
             self.core.rect = rect;
 
-            let mut child_size = rect.size - self.frame_size;
+            // Adaptive sizes depend on the window size and data length, which
+            // may have changed since size rules were computed
+            self.window_len = mgr.window_size().extract(self.direction);
+            if self.resolve_visible_len() {
+                trace!("visible_len changed: triggering RESIZE");
+                *mgr |= TkAction::RESIZE;
+            }
+
+            let mut child_size = rect.size.clamped_sub(self.frame_size);
             let num = if self.direction.is_horizontal() {
                 if child_size.0 >= self.ideal_visible * self.child_size_ideal {
                     child_size.0 = self.child_size_ideal;
                 } else {
                     child_size.0 = self.child_size_min;
                 }
-                let skip = child_size.0 + self.child_inter_margin;
+                let skip = (child_size.0 + self.child_inter_margin).max(1);
                 align.horiz = None;
                 (rect.size.0 + skip - 1) / skip + 1
             } else {
@@ -448,7 +706,7 @@ widget! {
                 } else {
                     child_size.1 = self.child_size_min;
                 }
-                let skip = child_size.1 + self.child_inter_margin;
+                let skip = (child_size.1 + self.child_inter_margin).max(1);
                 align.vert = None;
                 (rect.size.1 + skip - 1) / skip + 1
             };
@@ -460,24 +718,48 @@ widget! {
             let num = usize::conv(num);
             if old_num < num {
                 debug!("allocating widgets (old len = {}, new = {})", old_num, num);
-                *mgr |= TkAction::RECONFIGURE;
+                mgr.reconfigure_subtree(self.id());
                 self.widgets.reserve(num - old_num);
                 mgr.size_handle(|size_handle| {
                     for _ in old_num..num {
-                        let mut widget = self.view.new();
+                        let mut widget = match self.pool.as_ref().and_then(|pool| pool.take()) {
+                            Some(widget) => widget,
+                            None => self.view.new(),
+                        };
                         solve_size_rules(
                             &mut widget,
                             size_handle,
                             Some(child_size.0),
                             Some(child_size.1),
                         );
-                        self.widgets.push(WidgetData { key: None, widget });
+                        self.widgets.push(WidgetData {
+                            key: None,
+                            shown: false,
+                            header: false,
+                            widget,
+                        });
                     }
                 });
+            } else if let Some(pool) = self.pool.as_ref().filter(|_| num < old_num) {
+                pool.put(self.widgets.drain(num..).map(|w| w.widget));
             } else if num + 64 <= old_num {
                 // Free memory (rarely useful?)
                 self.widgets.truncate(num);
             }
+            if self.sticky_headers && self.sticky.is_none() {
+                mgr.reconfigure_subtree(self.id());
+                let mut widget = self.view.new();
+                let size = (Some(child_size.0), Some(child_size.1));
+                mgr.size_handle(|size_handle| {
+                    solve_size_rules(&mut widget, size_handle, size.0, size.1);
+                });
+                self.sticky = Some(WidgetData {
+                    key: None,
+                    shown: false,
+                    header: true,
+                    widget,
+                });
+            }
             self.update_widgets(mgr);
         }
 
@@ -492,7 +774,7 @@ widget! {
             }
 
             let solver = self.position_solver(mgr);
-            let last_data = self.data.len() - 1;
+            let last_data = self.data.logical_len() - 1;
             let data = if let Some(index) = from {
                 let data = solver.child_to_data(index);
                 if !reverse && data < last_data {
@@ -528,9 +810,17 @@ widget! {
             }
 
             let coord = coord + self.scroll.offset();
+            if let Some(w) = self.sticky.as_ref().filter(|w| w.shown) {
+                // The sticky header hides content but is not interactive
+                if w.widget.rect().contains(coord) {
+                    return Some(self.id());
+                }
+            }
             for child in &mut self.widgets[..self.cur_len.cast()] {
-                if let Some(id) = child.widget.find_id(coord) {
-                    return Some(id);
+                if child.shown {
+                    if let Some(id) = child.widget.find_id(coord) {
+                        return Some(id);
+                    }
                 }
             }
             Some(self.id())
@@ -539,8 +829,15 @@ widget! {
         fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
             let disabled = disabled || self.is_disabled();
             let offset = self.scroll_offset();
+            let is_vert = self.direction.is_vertical();
             draw.with_clip_region(self.core.rect, offset, &mut |draw| {
                 for child in &mut self.widgets[..self.cur_len.cast()] {
+                    if !child.shown {
+                        continue;
+                    }
+                    if child.header {
+                        draw.group_header(child.widget.rect(), is_vert);
+                    }
                     child.widget.draw(draw, mgr, disabled);
                     if let Some(ref key) = child.key {
                         if self.selection.contains(key) {
@@ -548,6 +845,13 @@ widget! {
                         }
                     }
                 }
+                if let Some(w) = self.sticky.as_mut().filter(|w| w.shown) {
+                    draw.group_header(w.widget.rect(), is_vert);
+                    w.widget.draw(draw, mgr, disabled);
+                }
+                if let Some(ref band) = self.band {
+                    draw.rubber_band(band.rect());
+                }
             });
         }
     }
@@ -558,6 +862,15 @@ widget! {
                 return Response::Unhandled;
             }
 
+            if let Some(w) = self.sticky.as_mut() {
+                if w.widget.is_ancestor_of(id) {
+                    // The sticky header is not interactive
+                    let event = self.scroll.offset_event(event);
+                    let _ = w.widget.send(mgr, id, event);
+                    return Response::None;
+                }
+            }
+
             if id < self.id() {
                 let child_event = self.scroll.offset_event(event.clone());
                 let index;
@@ -594,7 +907,10 @@ widget! {
                                 if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None) {
                                     self.press_event = Some(source);
                                     self.press_phase = PressPhase::Start(coord);
-                                    self.press_target = key;
+                                    let ctx = self.item_context(mgr, index, &event);
+                                    let data = &self.data;
+                                    let key = key.filter(|key| !data.is_group_header(key));
+                                    self.press_target = key.map(|key| (key, ctx));
                                 }
                                 return Response::None;
                             }
@@ -612,25 +928,38 @@ widget! {
                         self.update_widgets(mgr);
                         return Response::Focus(rect);
                     }
-                    (Some(key), Response::Select) => {
+                    (_, Response::Reveal(rect, align)) => {
+                        let (rect, action) = (self.scroll).reveal_rect_animated(
+                            mgr,
+                            self.id(),
+                            rect,
+                            align,
+                            self.core.rect,
+                        );
+                        *mgr |= action;
+                        self.update_widgets(mgr);
+                        return Response::Reveal(rect, align);
+                    }
+                    (Some(key), Response::Select) if !self.data.is_group_header(&key) => {
+                        let ctx = self.item_context(mgr, index, &event);
                         return match self.sel_mode {
                             SelectionMode::None => Response::None,
                             SelectionMode::Single => {
                                 self.selection.clear();
                                 self.selection.insert(key.clone());
-                                Response::Msg(ChildMsg::Select(key))
+                                Response::Msg(ChildMsg::Select(key, ctx))
                             }
                             SelectionMode::Multiple => {
                                 if self.selection.remove(&key) {
-                                    Response::Msg(ChildMsg::Deselect(key))
+                                    Response::Msg(ChildMsg::Deselect(key, ctx))
                                 } else {
                                     self.selection.insert(key.clone());
-                                    Response::Msg(ChildMsg::Select(key))
+                                    Response::Msg(ChildMsg::Select(key, ctx))
                                 }
                             }
                         };
                     }
-                    (None, Response::Select) => return Response::None,
+                    (_, Response::Select) => return Response::None,
                     (_, Response::Update) => return Response::None,
                     (key, Response::Msg(msg)) => {
                         trace!(
@@ -643,12 +972,15 @@ widget! {
                             if let Some(handle) = self.data.handle(&key, &msg) {
                                 mgr.trigger_update(handle, 0);
                             }
-                            return Response::Msg(ChildMsg::Child(key, msg));
+                            let ctx = self.item_context(mgr, index, &event);
+                            return Response::Msg(ChildMsg::Child(key, msg, ctx));
                         } else {
                             log::warn!("ListView: response from widget with no key");
                             return Response::None;
                         }
                     }
+                    // Other responses carry no message
+                    (_, r) => return r.try_into().unwrap_or(Response::None),
                 }
             } else {
                 debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
@@ -658,6 +990,58 @@ widget! {
                         self.update_view(mgr);
                         return Response::Update;
                     }
+                    Event::PressStart { source, coord, .. }
+                        if source.is_primary()
+                            && !source.is_touch()
+                            && matches!(self.sel_mode, SelectionMode::Multiple) =>
+                    {
+                        // Press on empty space: start rubber-band selection
+                        if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None) {
+                            let offset = self.scroll_offset();
+                            let modifiers = mgr.modifiers();
+                            let selection = &self.selection;
+                            let band = RubberBand::new(source, coord, offset, modifiers, selection);
+                            self.band = Some(band);
+                            self.update_band(mgr);
+                        }
+                        return Response::None;
+                    }
+                    Event::PressMove { source, coord, .. }
+                        if self.band.as_ref().map(|band| band.source) == Some(source) =>
+                    {
+                        let offset = self.scroll_offset();
+                        if let Some(band) = self.band.as_mut() {
+                            band.set_end(coord, offset);
+                        }
+                        self.update_band(mgr);
+                        return Response::None;
+                    }
+                    Event::PressEnd { source, .. }
+                        if self.band.as_ref().map(|band| band.source) == Some(source) =>
+                    {
+                        let band = self.band.take().unwrap();
+                        mgr.redraw(self.id());
+                        return if band.changed(&self.selection) {
+                            ChildMsg::SelectionChanged.into()
+                        } else {
+                            Response::None
+                        };
+                    }
+                    Event::TimerUpdate(AUTO_SCROLL_PAYLOAD) => {
+                        let delta = match self.band.as_ref() {
+                            Some(band) => band.auto_scroll_delta(self.core.rect),
+                            None => return Response::None,
+                        };
+                        if delta != Offset::ZERO {
+                            let offset = self.scroll_offset() + delta;
+                            let offset = self.set_scroll_offset(mgr, offset);
+                            if let Some(band) = self.band.as_mut() {
+                                band.update_offset(offset);
+                            }
+                            self.update_band(mgr);
+                        }
+                        return Response::None;
+                    }
                     Event::PressMove { source, coord, .. } if self.press_event == Some(source) => {
                         if let PressPhase::Start(start_coord) = self.press_phase {
                             if mgr.config_test_pan_thresh(coord - start_coord) {
@@ -681,20 +1065,20 @@ widget! {
                             SelectionMode::None => Response::None,
                             SelectionMode::Single => {
                                 self.selection.clear();
-                                if let Some(ref key) = self.press_target {
+                                if let Some((ref key, ctx)) = self.press_target {
                                     self.selection.insert(key.clone());
-                                    ChildMsg::Select(key.clone()).into()
+                                    ChildMsg::Select(key.clone(), ctx).into()
                                 } else {
                                     Response::None
                                 }
                             }
                             SelectionMode::Multiple => {
-                                if let Some(ref key) = self.press_target {
+                                if let Some((ref key, ctx)) = self.press_target {
                                     if self.selection.remove(key) {
-                                        ChildMsg::Deselect(key.clone()).into()
+                                        ChildMsg::Deselect(key.clone(), ctx).into()
                                     } else {
                                         self.selection.insert(key.clone());
-                                        ChildMsg::Select(key.clone()).into()
+                                        ChildMsg::Select(key.clone(), ctx).into()
                                     }
                                 } else {
                                     Response::None
@@ -713,7 +1097,7 @@ widget! {
                     .nav_focus()
                     .and_then(|id| self.find_child(id))
                     .map(|index| solver.child_to_data(index));
-                let last = self.data.len().wrapping_sub(1);
+                let last = self.data.logical_len().wrapping_sub(1);
                 let is_vert = self.direction.is_vertical();
                 let len = solver.cur_len;
 
@@ -744,9 +1128,10 @@ widget! {
                     Response::None
                 }
             } else {
+                let size = self.core.rect.size;
                 let (action, response) =
                     self.scroll
-                        .scroll_by_event(event, self.core.rect.size, |source, _, coord| {
+                        .scroll_by_event(mgr, id, event, size, |mgr, source, _, coord| {
                             if source.is_primary() && mgr.config_enable_mouse_pan() {
                                 let icon = Some(CursorIcon::Grabbing);
                                 mgr.request_grab(id, source, coord, GrabMode::Grab, icon);
@@ -768,6 +1153,8 @@ struct PositionSolver {
     size: Size,
     first_data: usize,
     cur_len: usize,
+    /// Index of the last data item, if the direction is reversed
+    reversed: Option<usize>,
 }
 
 impl PositionSolver {
@@ -780,9 +1167,28 @@ impl PositionSolver {
         data
     }
 
+    /// Map a data index to a visual index (position from `pos_start`)
+    fn visual_index(&self, i: usize) -> usize {
+        match self.reversed {
+            Some(last) => last.saturating_sub(i),
+            None => i,
+        }
+    }
+
+    /// Map a range of visual indices to data indices
+    fn data_range(&self, range: Range<usize>) -> Range<usize> {
+        match self.reversed {
+            Some(last) => (last + 1 - range.end)..(last + 1 - range.start),
+            None => range,
+        }
+    }
+
     /// Rect of data item i
     fn rect(&self, i: usize) -> Rect {
-        let pos = self.pos_start + self.skip * i32::conv(i);
+        let n = saturating_i32(self.visual_index(i));
+        let pos = self
+            .pos_start
+            .saturating_add_offset(self.skip.saturating_mul(n));
         Rect::new(pos, self.size)
     }
 }
@@ -5,20 +5,23 @@
 
 //! List view widget
 
-use super::{driver, Driver, PressPhase, SelectionError, SelectionMode};
+use super::rubber_band::{item_range, RubberBand, AUTO_SCROLL_PAYLOAD};
+use super::{content_len, first_item, saturating_i32, VisibleLen};
+use super::{driver, Driver, PressPhase, SelectionError, SelectionMode, WidgetPool};
 #[allow(unused)] // doc links
 use crate::ScrollBars;
 use crate::{ScrollComponent, Scrollable};
-use kas::event::{ChildMsg, Command, CursorIcon, GrabMode, PressSource};
+use instant::Instant;
+use kas::event::{ChildMsg, Command, ConfigureManager, CursorIcon, GrabMode, PressSource};
+use kas::event::{InputKind, ItemContext};
 use kas::layout::solve_size_rules;
 use kas::prelude::*;
 use kas::updatable::{MatrixData, UpdatableHandler};
 use linear_map::set::LinearSet;
 use log::{debug, trace};
-use std::time::Instant;
 use UpdatableHandler as UpdHandler;
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 struct Dim {
     rows: i32,
     cols: i32,
@@ -46,6 +49,12 @@ widget! {
     ///
     /// This widget is [`Scrollable`], supporting keyboard, wheel and drag
     /// scrolling. You may wish to wrap this widget with [`ScrollBars`].
+    ///
+    /// Sparse data is supported: content size is determined by the logical
+    /// bounds [`MatrixData::col_len`] and [`MatrixData::row_len`], and empty
+    /// cells are shown using [`Driver::set_empty`] placeholders. Placeholder
+    /// cells keep their key, thus may be clicked and (if the driver's widget
+    /// is editable) assigned a value via [`MatrixData::update`].
     #[derive(Clone, Debug)]
     #[handler(msg=ChildMsg<T::Key, <V::Widget as Handler>::Msg>)]
     pub struct MatrixView<
@@ -60,8 +69,14 @@ widget! {
         view: V,
         data: T,
         widgets: Vec<WidgetData<T::Key, V::Widget>>,
+        pool: Option<WidgetPool<V::Widget>>,
         align_hints: AlignHints,
+        visible_rows: VisibleLen,
+        visible_cols: VisibleLen,
+        /// Resolved values of `visible_rows`, `visible_cols`
         ideal_len: Dim,
+        /// Window size, as last seen
+        window_size: Size,
         alloc_len: Dim,
         cur_len: Dim,
         child_size_min: Size,
@@ -74,7 +89,8 @@ widget! {
         selection: LinearSet<T::Key>,
         press_event: Option<PressSource>,
         press_phase: PressPhase,
-        press_target: Option<T::Key>,
+        press_target: Option<(T::Key, ItemContext)>,
+        band: Option<RubberBand<T::Key>>,
     }
 
     impl Self where V: Default {
@@ -94,8 +110,12 @@ widget! {
                 view,
                 data,
                 widgets: Default::default(),
+                pool: None,
                 align_hints: Default::default(),
+                visible_rows: VisibleLen::Fixed(3),
+                visible_cols: VisibleLen::Fixed(5),
                 ideal_len: Dim { rows: 3, cols: 5 },
+                window_size: Size::ZERO,
                 alloc_len: Dim::default(),
                 cur_len: Dim::default(),
                 child_size_min: Size::ZERO,
@@ -108,6 +128,7 @@ widget! {
                 press_event: None,
                 press_phase: PressPhase::None,
                 press_target: None,
+                band: None,
             }
         }
 
@@ -226,6 +247,15 @@ widget! {
             self.selection.remove(key)
         }
 
+        /// Use a shared widget pool, builder style
+        ///
+        /// Surplus view widgets are returned to the pool, and new view widgets
+        /// are taken from the pool where possible. See [`WidgetPool`].
+        pub fn with_pool(mut self, pool: WidgetPool<V::Widget>) -> Self {
+            self.pool = Some(pool);
+            self
+        }
+
         /// Manually trigger an update to handle changed data
         pub fn update_view(&mut self, mgr: &mut Manager) {
             let data = &self.data;
@@ -243,23 +273,62 @@ widget! {
         ///
         /// This affects the (ideal) size request and whether children are sized
         /// according to their ideal or minimum size but not the minimum size.
-        pub fn with_num_visible(mut self, rows: i32, cols: i32) -> Self {
-            self.ideal_len = Dim { rows, cols };
+        pub fn with_num_visible(self, rows: i32, cols: i32) -> Self {
+            self.with_visible_len(VisibleLen::Fixed(rows), VisibleLen::Fixed(cols))
+        }
+
+        /// Set the preferred number of items visible, possibly adaptive (inline)
+        ///
+        /// See [`VisibleLen`]. The default is `VisibleLen::Fixed(3)` rows and
+        /// `VisibleLen::Fixed(5)` columns.
+        pub fn with_visible_len(mut self, rows: VisibleLen, cols: VisibleLen) -> Self {
+            self.visible_rows = rows;
+            self.visible_cols = cols;
+            self.resolve_visible_len();
             self
         }
 
+        /// Set the preferred number of items visible, possibly adaptive
+        ///
+        /// See [`VisibleLen`].
+        pub fn set_visible_len(&mut self, rows: VisibleLen, cols: VisibleLen) -> TkAction {
+            self.visible_rows = rows;
+            self.visible_cols = cols;
+            self.resolve_visible_len();
+            TkAction::RESIZE
+        }
+
+        /// Get the preferred number of items visible: `(rows, cols)`
+        pub fn visible_len(&self) -> (VisibleLen, VisibleLen) {
+            (self.visible_rows, self.visible_cols)
+        }
+
+        /// Resolve visible lengths, returning true if `ideal_len` changed
+        fn resolve_visible_len(&mut self) -> bool {
+            let item_size = self.child_size_ideal + self.child_inter_margin;
+            let (data, window) = (&self.data, self.window_size);
+            let len = Dim {
+                rows: self.visible_rows.resolve(data.row_len(), item_size.1, window.1),
+                cols: self.visible_cols.resolve(data.col_len(), item_size.0, window.0),
+            };
+            std::mem::replace(&mut self.ideal_len, len) != len
+        }
+
         fn update_widgets(&mut self, mgr: &mut Manager) {
             let time = Instant::now();
 
-            let data_len = Size(self.data.col_len().cast(), self.data.row_len().cast());
             let view_size = self.rect().size;
-            let skip = self.child_size + self.child_inter_margin;
-            let content_size = (skip.cwise_mul(data_len) - self.child_inter_margin).max(Size::ZERO);
+            let skip = (self.child_size + self.child_inter_margin).max(Size::splat(1));
+            let margin = self.child_inter_margin;
+            let content_size = Size(
+                content_len(skip.0, self.data.col_len(), margin.0),
+                content_len(skip.1, self.data.row_len(), margin.1),
+            );
             *mgr |= self.scroll.set_sizes(view_size, content_size);
 
             let offset = self.scroll_offset();
-            let first_col = usize::conv(u64::conv(offset.0) / u64::conv(skip.0));
-            let first_row = usize::conv(u64::conv(offset.1) / u64::conv(skip.1));
+            let first_col = first_item(offset.0, skip.0);
+            let first_row = first_item(offset.1, skip.1);
             let cols = self
                 .data
                 .col_iter_vec_from(first_col, self.alloc_len.cols.cast());
@@ -286,11 +355,17 @@ widget! {
                         if let Some(item) = self.data.get_cloned(&key) {
                             w.key = Some(key.clone());
                             action |= self.view.set(&mut w.widget, item);
+                        } else if let Some(act) = self.view.set_empty(&mut w.widget) {
+                            // Placeholder for an empty cell of sparse data
+                            w.key = Some(key.clone());
+                            action |= act;
                         } else {
                             w.key = None; // disables drawing and clicking
                         }
                     }
-                    rect.pos = pos_start + skip.cwise_mul(Size(ci.cast(), ri.cast()));
+                    let n = Offset(saturating_i32(ci), saturating_i32(ri));
+                    let offset = Offset::from(skip).saturating_cwise_mul(n);
+                    rect.pos = pos_start.saturating_add_offset(offset);
                     if w.widget.rect() != rect {
                         w.widget.set_rect(mgr, rect, self.align_hints);
                     }
@@ -300,13 +375,59 @@ widget! {
             let dur = (Instant::now() - time).as_micros();
             trace!("MatrixView::update_widgets completed in {}μs", dur);
         }
+
+        /// Update selection from the rubber band, if any
+        fn update_band(&mut self, mgr: &mut Manager) {
+            let band = match self.band.as_ref() {
+                Some(band) => band,
+                None => return,
+            };
+            let rect = band.rect();
+            let end = rect.pos2();
+            let pos = self.core.rect.pos + self.frame_offset;
+            let skip = self.child_size + self.child_inter_margin;
+            let size = self.child_size;
+            let (col_len, row_len) = (self.data.col_len(), self.data.row_len());
+            let cols = item_range(rect.pos.0, end.0, pos.0, skip.0, size.0, col_len);
+            let rows = item_range(rect.pos.1, end.1, pos.1, skip.1, size.1, row_len);
+            let cols = self.data.col_iter_vec_from(cols.start, cols.len());
+            let rows = self.data.row_iter_vec_from(rows.start, rows.len());
+            let data = &self.data;
+            let hits = rows
+                .iter()
+                .flat_map(|row| cols.iter().map(move |col| T::make_key(row, col)))
+                .filter(|key| data.contains(key));
+            band.apply(&mut self.selection, hits);
+            mgr.redraw(self.id());
+            if band.auto_scroll_delta(self.core.rect) != Offset::ZERO {
+                mgr.update_on_frame(self.id(), AUTO_SCROLL_PAYLOAD);
+            }
+        }
+
+        /// Context for a message from child `index`
+        fn item_context(&self, index: usize, event: &Event) -> ItemContext {
+            // Data indices are recovered from the position of the child
+            let skip = self.child_size + self.child_inter_margin;
+            let pos_start = self.core.rect.pos + self.frame_offset;
+            let pos = self.widgets[index].widget.rect().pos - pos_start;
+            let col = usize::conv(pos.0.max(0) / skip.0.max(1));
+            let row = usize::conv(pos.1.max(0) / skip.1.max(1));
+            ItemContext {
+                index: row,
+                column: Some(col),
+                input: InputKind::from_event(event),
+            }
+        }
     }
 
     impl Scrollable for Self {
         fn scroll_axes(&self, size: Size) -> (bool, bool) {
             let item_min = self.child_size_min + self.child_inter_margin;
-            let data_len = Size(self.data.col_len().cast(), self.data.row_len().cast());
-            let min_size = (item_min.cwise_mul(data_len) - self.child_inter_margin).max(Size::ZERO);
+            let margin = self.child_inter_margin;
+            let min_size = Size(
+                content_len(item_min.0, self.data.col_len(), margin.0),
+                content_len(item_min.1, self.data.row_len(), margin.1),
+            );
             (min_size.0 > size.0, min_size.1 > size.1)
         }
 
@@ -315,6 +436,16 @@ widget! {
             self.scroll.max_offset()
         }
 
+        #[inline]
+        fn content_size(&self) -> Size {
+            self.scroll.content_size()
+        }
+
+        #[inline]
+        fn viewport_size(&self) -> Size {
+            self.scroll.window_size()
+        }
+
         #[inline]
         fn scroll_offset(&self) -> Offset {
             self.scroll.offset()
@@ -353,6 +484,19 @@ widget! {
     }
 
     impl WidgetConfig for Self {
+        fn configure_recurse(&mut self, mut cmgr: ConfigureManager) {
+            self.record_first_id(cmgr.peek_next());
+            for i in 0..self.widgets.len() {
+                self.widgets[i].widget.configure_recurse(cmgr.child());
+            }
+            // Leave room to configure more widgets without a full reconfigure
+            let used = u32::from(cmgr.peek_next()) - u32::from(self.first_id());
+            cmgr.reserve_ids(self.id(), used.max(16));
+            self.core_data_mut().id = cmgr.next_id(self.id());
+            self.core_data_mut().bump_generation();
+            self.configure(cmgr.mgr());
+        }
+
         fn configure(&mut self, mgr: &mut Manager) {
             if let Some(handle) = self.data.update_handle() {
                 mgr.update_on_handle(handle, self.id());
@@ -377,6 +521,7 @@ widget! {
             self.child_inter_margin
                 .set_component(axis, (m.0 + m.1).max(inner_margin));
 
+            self.resolve_visible_len();
             let ideal_len = match axis.is_vertical() {
                 false => self.ideal_len.cols,
                 true => self.ideal_len.rows,
@@ -393,7 +538,15 @@ widget! {
         fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
             self.core.rect = rect;
 
-            let mut child_size = rect.size - self.frame_size;
+            // Adaptive sizes depend on the window size and data length, which
+            // may have changed since size rules were computed
+            self.window_size = mgr.window_size();
+            if self.resolve_visible_len() {
+                trace!("visible_len changed: triggering RESIZE");
+                *mgr |= TkAction::RESIZE;
+            }
+
+            let mut child_size = rect.size.clamped_sub(self.frame_size);
             if child_size.0 >= self.ideal_len.cols * self.child_size_ideal.0 {
                 child_size.0 = self.child_size_ideal.0;
             } else {
@@ -407,7 +560,7 @@ widget! {
             self.child_size = child_size;
             self.align_hints = align;
 
-            let skip = child_size + self.child_inter_margin;
+            let skip = (child_size + self.child_inter_margin).max(Size::splat(1));
             let vis_len = (rect.size + skip - Size::splat(1)).cwise_div(skip) + Size::splat(1);
             self.alloc_len = Dim {
                 cols: vis_len.0,
@@ -418,11 +571,14 @@ widget! {
             let num = usize::conv(vis_len.0) * usize::conv(vis_len.1);
             if old_num < num {
                 debug!("allocating widgets (old len = {}, new = {})", old_num, num);
-                *mgr |= TkAction::RECONFIGURE;
+                mgr.reconfigure_subtree(self.id());
                 self.widgets.reserve(num - old_num);
                 mgr.size_handle(|size_handle| {
                     for _ in old_num..num {
-                        let mut widget = self.view.new();
+                        let mut widget = match self.pool.as_ref().and_then(|pool| pool.take()) {
+                            Some(widget) => widget,
+                            None => self.view.new(),
+                        };
                         solve_size_rules(
                             &mut widget,
                             size_handle,
@@ -432,6 +588,8 @@ widget! {
                         self.widgets.push(WidgetData { key: None, widget });
                     }
                 });
+            } else if let Some(pool) = self.pool.as_ref().filter(|_| num < old_num) {
+                pool.put(self.widgets.drain(num..).map(|w| w.widget));
             } else if num + 64 <= self.widgets.len() {
                 // Free memory (rarely useful?)
                 self.widgets.truncate(num);
@@ -473,8 +631,8 @@ widget! {
                 // Simplified version of logic in update_widgets
                 let skip = self.child_size + self.child_inter_margin;
                 let offset = self.scroll_offset();
-                let ci = usize::conv(u64::conv(offset.0) / u64::conv(skip.0));
-                let ri = usize::conv(u64::conv(offset.1) / u64::conv(skip.1));
+                let ci = first_item(offset.0, skip.0);
+                let ri = first_item(offset.1, skip.1);
                 let (rows, cols): (usize, usize) = (self.cur_len.rows.cast(), self.cur_len.cols.cast());
                 let mut data = (ci % cols) * rows + (ri % rows);
                 if reverse {
@@ -519,6 +677,9 @@ widget! {
                         }
                     }
                 }
+                if let Some(ref band) = self.band {
+                    draw.rubber_band(band.rect());
+                }
             });
         }
     }
@@ -565,7 +726,8 @@ widget! {
                                 if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None) {
                                     self.press_event = Some(source);
                                     self.press_phase = PressPhase::Start(coord);
-                                    self.press_target = key;
+                                    let ctx = self.item_context(index, &event);
+                                    self.press_target = key.map(|key| (key, ctx));
                                 }
                                 return Response::None;
                             }
@@ -583,20 +745,33 @@ widget! {
                         self.update_widgets(mgr);
                         return Response::Focus(rect);
                     }
+                    (_, Response::Reveal(rect, align)) => {
+                        let (rect, action) = (self.scroll).reveal_rect_animated(
+                            mgr,
+                            self.id(),
+                            rect,
+                            align,
+                            self.core.rect,
+                        );
+                        *mgr |= action;
+                        self.update_widgets(mgr);
+                        return Response::Reveal(rect, align);
+                    }
                     (Some(key), Response::Select) => {
+                        let ctx = self.item_context(index, &event);
                         return match self.sel_mode {
                             SelectionMode::None => Response::None,
                             SelectionMode::Single => {
                                 self.selection.clear();
                                 self.selection.insert(key.clone());
-                                Response::Msg(ChildMsg::Select(key))
+                                Response::Msg(ChildMsg::Select(key, ctx))
                             }
                             SelectionMode::Multiple => {
                                 if self.selection.remove(&key) {
-                                    Response::Msg(ChildMsg::Deselect(key))
+                                    Response::Msg(ChildMsg::Deselect(key, ctx))
                                 } else {
                                     self.selection.insert(key.clone());
-                                    Response::Msg(ChildMsg::Select(key))
+                                    Response::Msg(ChildMsg::Select(key, ctx))
                                 }
                             }
                         };
@@ -614,12 +789,15 @@ widget! {
                             if let Some(handle) = self.data.handle(&key, &msg) {
                                 mgr.trigger_update(handle, 0);
                             }
-                            return Response::Msg(ChildMsg::Child(key, msg));
+                            let ctx = self.item_context(index, &event);
+                            return Response::Msg(ChildMsg::Child(key, msg, ctx));
                         } else {
                             log::warn!("MatrixView: response from widget with no key");
                             return Response::None;
                         }
                     }
+                    // Other responses carry no message
+                    (_, r) => return r.try_into().unwrap_or(Response::None),
                 }
             } else {
                 debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
@@ -628,6 +806,58 @@ widget! {
                         self.update_view(mgr);
                         return Response::Update;
                     }
+                    Event::PressStart { source, coord, .. }
+                        if source.is_primary()
+                            && !source.is_touch()
+                            && matches!(self.sel_mode, SelectionMode::Multiple) =>
+                    {
+                        // Press on empty space: start rubber-band selection
+                        if mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None) {
+                            let offset = self.scroll_offset();
+                            let modifiers = mgr.modifiers();
+                            let selection = &self.selection;
+                            let band = RubberBand::new(source, coord, offset, modifiers, selection);
+                            self.band = Some(band);
+                            self.update_band(mgr);
+                        }
+                        return Response::None;
+                    }
+                    Event::PressMove { source, coord, .. }
+                        if self.band.as_ref().map(|band| band.source) == Some(source) =>
+                    {
+                        let offset = self.scroll_offset();
+                        if let Some(band) = self.band.as_mut() {
+                            band.set_end(coord, offset);
+                        }
+                        self.update_band(mgr);
+                        return Response::None;
+                    }
+                    Event::PressEnd { source, .. }
+                        if self.band.as_ref().map(|band| band.source) == Some(source) =>
+                    {
+                        let band = self.band.take().unwrap();
+                        mgr.redraw(self.id());
+                        return if band.changed(&self.selection) {
+                            ChildMsg::SelectionChanged.into()
+                        } else {
+                            Response::None
+                        };
+                    }
+                    Event::TimerUpdate(AUTO_SCROLL_PAYLOAD) => {
+                        let delta = match self.band.as_ref() {
+                            Some(band) => band.auto_scroll_delta(self.core.rect),
+                            None => return Response::None,
+                        };
+                        if delta != Offset::ZERO {
+                            let offset = self.scroll_offset() + delta;
+                            let offset = self.set_scroll_offset(mgr, offset);
+                            if let Some(band) = self.band.as_mut() {
+                                band.update_offset(offset);
+                            }
+                            self.update_band(mgr);
+                        }
+                        return Response::None;
+                    }
                     Event::PressMove { source, coord, .. } if self.press_event == Some(source) => {
                         if let PressPhase::Start(start_coord) = self.press_phase {
                             if mgr.config_test_pan_thresh(coord - start_coord) {
@@ -651,20 +881,20 @@ widget! {
                             SelectionMode::None => Response::None,
                             SelectionMode::Single => {
                                 self.selection.clear();
-                                if let Some(ref key) = self.press_target {
+                                if let Some((ref key, ctx)) = self.press_target {
                                     self.selection.insert(key.clone());
-                                    ChildMsg::Select(key.clone()).into()
+                                    ChildMsg::Select(key.clone(), ctx).into()
                                 } else {
                                     Response::None
                                 }
                             }
                             SelectionMode::Multiple => {
-                                if let Some(ref key) = self.press_target {
+                                if let Some((ref key, ctx)) = self.press_target {
                                     if self.selection.remove(key) {
-                                        ChildMsg::Deselect(key.clone()).into()
+                                        ChildMsg::Deselect(key.clone(), ctx).into()
                                     } else {
                                         self.selection.insert(key.clone());
-                                        ChildMsg::Select(key.clone()).into()
+                                        ChildMsg::Select(key.clone(), ctx).into()
                                     }
                                 } else {
                                     Response::None
@@ -683,8 +913,8 @@ widget! {
 
                 let skip = self.child_size + self.child_inter_margin;
                 let offset = self.scroll_offset();
-                let first_col = usize::conv(u64::conv(offset.0) / u64::conv(skip.0));
-                let first_row = usize::conv(u64::conv(offset.1) / u64::conv(skip.1));
+                let first_col = first_item(offset.0, skip.0);
+                let first_row = first_item(offset.1, skip.1);
                 let col_start = (first_col / cols) * cols;
                 let row_start = (first_row / rows) * rows;
 
@@ -730,8 +960,9 @@ widget! {
                 }
                 (TkAction::empty(), Response::None)
             } else {
+                let size = self.core.rect.size;
                 self.scroll
-                    .scroll_by_event(event, self.core.rect.size, |source, _, coord| {
+                    .scroll_by_event(mgr, id, event, size, |mgr, source, _, coord| {
                         if source.is_primary() && mgr.config_enable_mouse_pan() {
                             let icon = Some(CursorIcon::Grabbing);
                             mgr.request_grab(id, source, coord, GrabMode::Grab, icon);
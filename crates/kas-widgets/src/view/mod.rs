@@ -56,7 +56,8 @@
 //!     data items, thus allowing good scaling to large data sets (this depends
 //!     on the performance of the model)
 //! -   supporting scrolling (see [`super::Scrollable`])
-//! -   supporting item selection
+//! -   supporting item selection, including drag-select (rubber band) when
+//!     [`SelectionMode::Multiple`] is used
 //! -   controlling scrolling and selection via otherwise unhandled events
 //!
 //! The following views are provided:
@@ -64,7 +65,12 @@
 //! -   [`SingleView`] creates a view over a [`SingleData`] object (no scrolling
 //!     or selection support)
 //! -   [`ListView`] creates a scrollable list view over a [`ListData`] object
+//! -   [`MatrixView`] creates a scrollable matrix view over a [`MatrixData`] object
+//!
+//! Where many views using the same driver are resized together, a
+//! [`WidgetPool`] may be shared between them to recycle view widgets.
 
+use kas::cast::{Conv, ConvFloat};
 #[allow(unused)]
 use kas::event::UpdateHandle;
 use kas::macros::VoidMsg;
@@ -72,17 +78,22 @@ use kas::macros::VoidMsg;
 use kas::updatable::{ListData, MatrixData, SharedRc, SingleData};
 use thiserror::Error;
 
+mod autocomplete;
 mod filter_list;
 mod list_view;
 mod matrix_view;
+mod pool;
+mod rubber_band;
 mod single_view;
 
 pub mod driver;
 
+pub use autocomplete::AutoComplete;
 pub use driver::Driver;
 pub use filter_list::FilterListView;
 pub use list_view::ListView;
 pub use matrix_view::MatrixView;
+pub use pool::WidgetPool;
 pub use single_view::SingleView;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -93,6 +104,13 @@ enum PressPhase {
 }
 
 /// Selection mode used by [`ListView`]
+///
+/// With `Multiple` selection, a mouse drag starting over empty space within the
+/// view performs rubber-band selection: items intersecting the band are
+/// selected. By default the band replaces the current selection; with
+/// <kbd>Shift</kbd> held items are added and with <kbd>Ctrl</kbd> held items
+/// are toggled. The view scrolls when the pointer is dragged beyond its edge
+/// and sends [`kas::event::ChildMsg::SelectionChanged`] on release.
 #[derive(Clone, Copy, Debug, VoidMsg)]
 pub enum SelectionMode {
     None,
@@ -112,4 +130,64 @@ pub enum SelectionError {
     Disabled,
     #[error("invalid key or index")]
     Key,
+    #[error("item is a group header")]
+    Header,
+}
+
+/// Preferred number of visible items of a view
+///
+/// This determines the ideal size of a [`ListView`] (along its axis of
+/// scrolling) or [`MatrixView`] (per axis), and thus how much space the view
+/// requests when sharing space with other widgets (e.g. within a splitter).
+/// It also affects whether children are sized according to their ideal or
+/// minimum size, but not the minimum size of the view.
+///
+/// Adaptive values are recomputed whenever the view is resized (including on
+/// changes to the data).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VisibleLen {
+    /// A fixed number of items
+    Fixed(i32),
+    /// The number of data items, clamped to `min..=max`
+    Data { min: i32, max: i32 },
+    /// As many items as fit within `fraction` of the window's size, clamped
+    /// to `min..=max`
+    Window { fraction: f32, min: i32, max: i32 },
+}
+
+impl VisibleLen {
+    /// Resolve the number of items
+    ///
+    /// Inputs are the number of data items, the size of each item (including
+    /// margin) and the window's size, all along the relevant axis.
+    pub fn resolve(self, data_len: usize, item_size: i32, window_size: i32) -> i32 {
+        let (n, min, max) = match self {
+            VisibleLen::Fixed(n) => return n,
+            VisibleLen::Data { min, max } => (saturating_i32(data_len), min, max),
+            VisibleLen::Window { fraction, min, max } => {
+                let len = fraction * f32::conv(window_size);
+                (i32::conv_floor(len / f32::conv(item_size.max(1))), min, max)
+            }
+        };
+        n.min(max).max(min)
+    }
+}
+
+/// Convert an item count or index to `i32`, saturating at `i32::MAX`
+fn saturating_i32(n: usize) -> i32 {
+    i32::try_from(n).unwrap_or(i32::MAX)
+}
+
+/// Length of `n` items spaced `skip` apart, excluding the trailing `margin`
+///
+/// The result saturates at `i32::MAX`: items beyond this cannot be scrolled to.
+fn content_len(skip: i32, n: usize, margin: i32) -> i32 {
+    skip.saturating_mul(saturating_i32(n))
+        .saturating_sub(margin)
+        .max(0)
+}
+
+/// Index of the first item visible at scroll `offset`, given item spacing `skip`
+fn first_item(offset: i32, skip: i32) -> usize {
+    usize::conv(offset.max(0) / skip.max(1))
 }
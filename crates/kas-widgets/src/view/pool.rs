@@ -0,0 +1,106 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Widget recycling pool
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// A shared pool of unused view widgets
+///
+/// Views such as [`super::ListView`] and [`super::MatrixView`] allocate view
+/// widgets on resize. By default, surplus widgets are kept by each view. When
+/// a pool is attached (e.g. via [`super::ListView::with_pool`]), surplus
+/// widgets are instead returned to the pool, and new widgets are taken from the
+/// pool where available before constructing new ones via [`super::Driver::new`].
+///
+/// This type is a shared reference: clones refer to the same pool. A pool
+/// should only be shared between views using equivalent drivers, since widgets
+/// are not reconstructed when moving between views (though their data is set
+/// via [`super::Driver::set`] before use).
+pub struct WidgetPool<W> {
+    widgets: Rc<RefCell<Vec<W>>>,
+    limit: usize,
+}
+
+impl<W> WidgetPool<W> {
+    /// Construct an empty pool
+    ///
+    /// By default, the pool keeps up to 1024 widgets.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_limit(1024)
+    }
+
+    /// Construct an empty pool, keeping at most `limit` widgets
+    ///
+    /// Widgets returned to a full pool are dropped.
+    #[inline]
+    pub fn with_limit(limit: usize) -> Self {
+        WidgetPool {
+            widgets: Default::default(),
+            limit,
+        }
+    }
+
+    /// Number of widgets available
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.widgets.borrow().len()
+    }
+
+    /// True if no widgets are available
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.widgets.borrow().is_empty()
+    }
+
+    /// Drop all widgets in the pool
+    pub fn clear(&self) {
+        self.widgets.borrow_mut().clear();
+    }
+
+    /// Take a widget from the pool, if available
+    #[inline]
+    pub fn take(&self) -> Option<W> {
+        self.widgets.borrow_mut().pop()
+    }
+
+    /// Return widgets to the pool
+    ///
+    /// Widgets in excess of the pool's limit are dropped.
+    pub fn put<I: IntoIterator<Item = W>>(&self, iter: I) {
+        let mut widgets = self.widgets.borrow_mut();
+        let space = self.limit.saturating_sub(widgets.len());
+        widgets.extend(iter.into_iter().take(space));
+    }
+}
+
+impl<W> Default for WidgetPool<W> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W> Clone for WidgetPool<W> {
+    #[inline]
+    fn clone(&self) -> Self {
+        WidgetPool {
+            widgets: self.widgets.clone(),
+            limit: self.limit,
+        }
+    }
+}
+
+impl<W> fmt::Debug for WidgetPool<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WidgetPool")
+            .field("len", &self.len())
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
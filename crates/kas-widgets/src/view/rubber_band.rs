@@ -0,0 +1,164 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Rubber-band (drag) selection
+
+use kas::event::{ModifiersState, PressSource};
+use kas::geom::{Coord, Offset, Rect, Size};
+use linear_map::set::LinearSet;
+use std::ops::Range;
+
+/// Timer payload used for auto-scrolling during rubber-band selection
+pub(super) const AUTO_SCROLL_PAYLOAD: u64 = 1 << 60;
+/// Maximum auto-scroll distance per frame
+const MAX_AUTO_SCROLL: i32 = 40;
+
+/// How a rubber-band selection combines with the prior selection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BandMode {
+    /// Replace the selection (no modifier)
+    Replace,
+    /// Add to the selection (Shift)
+    Add,
+    /// Toggle selection of items (Ctrl)
+    Toggle,
+}
+
+/// State of an active rubber-band selection
+///
+/// Coordinates are stored relative to the view's content (i.e. including the
+/// scroll offset) so that the band is anchored to content while scrolling.
+#[derive(Clone, Debug)]
+pub(super) struct RubberBand<K> {
+    pub source: PressSource,
+    start: Coord,
+    end: Coord,
+    /// Last pointer position, in window coordinates
+    coord: Coord,
+    mode: BandMode,
+    initial: LinearSet<K>,
+}
+
+impl<K: Clone + Eq> RubberBand<K> {
+    /// Start a band at `coord` (window coordinates)
+    pub fn new(
+        source: PressSource,
+        coord: Coord,
+        offset: Offset,
+        modifiers: ModifiersState,
+        selection: &LinearSet<K>,
+    ) -> Self {
+        let mode = if modifiers.ctrl() {
+            BandMode::Toggle
+        } else if modifiers.shift() {
+            BandMode::Add
+        } else {
+            BandMode::Replace
+        };
+        RubberBand {
+            source,
+            start: coord + offset,
+            end: coord + offset,
+            coord,
+            mode,
+            initial: selection.clone(),
+        }
+    }
+
+    /// Move the free corner of the band to `coord` (window coordinates)
+    pub fn set_end(&mut self, coord: Coord, offset: Offset) {
+        self.coord = coord;
+        self.end = coord + offset;
+    }
+
+    /// Re-anchor the free corner after scrolling
+    pub fn update_offset(&mut self, offset: Offset) {
+        self.end = self.coord + offset;
+    }
+
+    /// The band's rect, in content coordinates
+    pub fn rect(&self) -> Rect {
+        let pos = self.start.min(self.end);
+        let size = Size::from(self.start.max(self.end) - pos);
+        Rect::new(pos, size)
+    }
+
+    /// Auto-scroll delta, given the view's `rect`
+    ///
+    /// This is non-zero when the pointer lies outside of `rect` and has
+    /// magnitude proportional to the distance (clamped per axis).
+    pub fn auto_scroll_delta(&self, rect: Rect) -> Offset {
+        let max = MAX_AUTO_SCROLL;
+        let axis = |c: i32, a: i32, b: i32| {
+            if c < a {
+                ((c - a) / 2).max(-max).min(-1)
+            } else if c >= b {
+                ((c - b) / 2 + 1).min(max)
+            } else {
+                0
+            }
+        };
+        let end = rect.pos2();
+        Offset(
+            axis(self.coord.0, rect.pos.0, end.0),
+            axis(self.coord.1, rect.pos.1, end.1),
+        )
+    }
+
+    /// Compute the new selection from the set of items within the band
+    ///
+    /// Returns true when `selection` was changed.
+    pub fn apply<I: IntoIterator<Item = K>>(&self, selection: &mut LinearSet<K>, hits: I) -> bool {
+        let mut new = match self.mode {
+            BandMode::Replace => LinearSet::new(),
+            BandMode::Add | BandMode::Toggle => self.initial.clone(),
+        };
+        for key in hits {
+            if self.mode == BandMode::Toggle && self.initial.contains(&key) {
+                new.remove(&key);
+            } else {
+                new.insert(key);
+            }
+        }
+
+        let changed = new.len() != selection.len() || new.iter().any(|k| !selection.contains(k));
+        if changed {
+            *selection = new;
+        }
+        changed
+    }
+
+    /// True if the selection differs from that at the start of the band
+    pub fn changed(&self, selection: &LinearSet<K>) -> bool {
+        selection.len() != self.initial.len() || selection.iter().any(|k| !self.initial.contains(k))
+    }
+}
+
+/// Range of items intersecting the band along one axis
+///
+/// Item `i` covers `pos + skip * i` to `pos + skip * i + len` (where `skip`
+/// may be negative) and the band covers `a` to `b`. The result is clamped to
+/// `0..n`. Calculation uses 64-bit arithmetic to avoid overflow.
+pub(super) fn item_range(a: i32, b: i32, pos: i32, skip: i32, len: i32, n: usize) -> Range<usize> {
+    let (a, b, pos, skip, len) = (
+        i64::from(a),
+        i64::from(b),
+        i64::from(pos),
+        i64::from(skip),
+        i64::from(len),
+    );
+    let (a, b, pos, skip) = if skip < 0 {
+        (-b, -a, -pos - len, -skip)
+    } else {
+        (a, b, pos, skip)
+    };
+    if skip == 0 || a >= b {
+        return 0..0;
+    }
+    let first = (a - pos - len).div_euclid(skip) + 1;
+    let end = (b - pos + skip - 1).div_euclid(skip);
+    let clamp = |i: i64| usize::try_from(i.max(0)).unwrap_or(usize::MAX).min(n);
+    clamp(first)..clamp(end).max(clamp(first))
+}
@@ -8,7 +8,7 @@
 use kas::layout;
 use kas::prelude::*;
 use kas::Icon;
-use kas::{Future, WindowId};
+use kas::{Future, WindowAttributes, WindowId, WindowPlacement};
 use smallvec::SmallVec;
 use std::error::Error;
 use std::path::Path;
@@ -21,6 +21,10 @@ widget! {
         #[widget_core]
         core: CoreData,
         restrict_dimensions: (bool, bool),
+        attributes: WindowAttributes,
+        placement: WindowPlacement,
+        state_key: Option<String>,
+        layout_direction: Option<LayoutDirection>,
         title: String,
         #[widget]
         w: W,
@@ -29,6 +33,14 @@ widget! {
         icon: Option<Icon>,
     }
 
+    impl WidgetConfig for Self {
+        fn configure(&mut self, mgr: &mut Manager) {
+            if let Some(dir) = self.layout_direction {
+                mgr.set_layout_direction(Some(dir));
+            }
+        }
+    }
+
     impl Layout for Self {
         #[inline]
         fn layout(&mut self) -> layout::Layout<'_> {
@@ -84,6 +96,18 @@ widget! {
             self.restrict_dimensions
         }
 
+        fn attributes(&self) -> WindowAttributes {
+            self.attributes.clone()
+        }
+
+        fn placement(&self) -> WindowPlacement {
+            self.placement.clone()
+        }
+
+        fn state_key(&self) -> Option<&str> {
+            self.state_key.as_deref()
+        }
+
         fn add_popup(&mut self, mgr: &mut Manager, id: WindowId, popup: kas::Popup) {
             let index = self.popups.len();
             self.popups.push((id, popup));
@@ -122,6 +146,10 @@ impl<W: Widget> Window<W> {
         Window {
             core: Default::default(),
             restrict_dimensions: (true, false),
+            attributes: Default::default(),
+            placement: Default::default(),
+            state_key: None,
+            layout_direction: None,
             title: title.to_string(),
             w,
             popups: Default::default(),
@@ -137,6 +165,56 @@ impl<W: Widget> Window<W> {
         self.restrict_dimensions = (min, max);
     }
 
+    /// Set window attributes
+    ///
+    /// Attributes are applied on window creation, except that size limits are
+    /// re-applied on resize (e.g. after `*mgr |= TkAction::RESIZE`). Use
+    /// methods on [`Manager`] to adjust other attributes at run-time.
+    pub fn set_attributes(&mut self, attributes: WindowAttributes) {
+        self.attributes = attributes;
+    }
+
+    /// Set window attributes (inline)
+    #[inline]
+    pub fn with_attributes(mut self, attributes: WindowAttributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Set the initial placement (monitor, position, fullscreen)
+    ///
+    /// By default, placement is chosen by the shell.
+    pub fn set_placement(&mut self, placement: WindowPlacement) {
+        self.placement = placement;
+    }
+
+    /// Set the initial placement (inline)
+    #[inline]
+    pub fn with_placement(mut self, placement: WindowPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Persist window size and position under `key` (inline)
+    ///
+    /// Saved geometry is restored when the window is next created, taking
+    /// priority over the attributes and placement. See [`kas::state`].
+    #[inline]
+    pub fn with_state_key<K: ToString>(mut self, key: K) -> Self {
+        self.state_key = Some(key.to_string());
+        self
+    }
+
+    /// Set the layout direction (inline)
+    ///
+    /// By default, the direction follows the locale (see
+    /// [`Manager::set_layout_direction`]).
+    #[inline]
+    pub fn with_layout_direction(mut self, direction: LayoutDirection) -> Self {
+        self.layout_direction = Some(direction);
+        self
+    }
+
     /// Set a closure to be called on destruction, and return a future
     ///
     /// This is a convenience wrapper around [`Window::on_drop_boxed`].
@@ -230,7 +308,11 @@ impl<W: Widget> Window<W> {
         let ideal = cache.ideal(false);
         let m = cache.margins();
 
-        let is_reversed = popup.direction.is_reversed();
+        // Under RTL layout, popups open in the mirrored direction and
+        // drop-downs are aligned to the right edge of their anchor
+        let layout_dir = mgr.layout_direction();
+        let direction = layout_dir.apply(popup.direction);
+        let is_reversed = direction.is_reversed();
         let place_in = |rp, rs: i32, cp: i32, cs: i32, ideal, m: (u16, u16)| -> (i32, i32) {
             let m: (i32, i32) = (m.0.into(), m.1.into());
             let before: i32 = cp - (rp + m.1);
@@ -250,17 +332,21 @@ impl<W: Widget> Window<W> {
                 (cp + cs + m.0, after)
             }
         };
-        let place_out = |rp, rs, cp: i32, cs, ideal: i32| -> (i32, i32) {
-            let pos = cp.min(rp + rs - ideal).max(rp);
+        let place_out = |rp, rs, cp: i32, cs, ideal: i32, end: bool| -> (i32, i32) {
             let size = ideal.max(cs).min(rs);
-            (pos, size)
+            let pos = match end {
+                false => cp.min(rp + rs - ideal),
+                true => (cp + cs - size).min(rp + rs - size),
+            };
+            (pos.max(rp), size)
         };
-        let rect = if popup.direction.is_horizontal() {
+        let rect = if direction.is_horizontal() {
             let (x, w) = place_in(r.pos.0, r.size.0, c.pos.0, c.size.0, ideal.0, m.horiz);
-            let (y, h) = place_out(r.pos.1, r.size.1, c.pos.1, c.size.1, ideal.1);
+            let (y, h) = place_out(r.pos.1, r.size.1, c.pos.1, c.size.1, ideal.1, false);
             Rect::new(Coord(x, y), Size::new(w, h))
         } else {
-            let (x, w) = place_out(r.pos.0, r.size.0, c.pos.0, c.size.0, ideal.0);
+            let end = layout_dir.is_rtl();
+            let (x, w) = place_out(r.pos.0, r.size.0, c.pos.0, c.size.0, ideal.0, end);
             let (y, h) = place_in(r.pos.1, r.size.1, c.pos.1, c.size.1, ideal.1, m.vert);
             Rect::new(Coord(x, y), Size::new(w, h))
         };
@@ -0,0 +1,190 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Zoomable view
+
+use kas::event::{CursorIcon, GrabMode};
+use kas::geom::{DVec2, Transform, Vec2};
+use kas::{layout, prelude::*};
+
+widget! {
+    /// A zoomable view over a single child
+    ///
+    /// The child is laid out over the whole rect of this widget, but drawn
+    /// scaled and translated by a [`Transform`] (see
+    /// [`DrawHandleExt::with_transform`]); changing the transform does not
+    /// require re-layout. Input coordinates are mapped through the inverse
+    /// transform.
+    ///
+    /// The view may be zoomed via pinch gestures and panned by dragging (on
+    /// input not handled by the child) or programmatically via
+    /// [`ZoomView::set_transform`] and [`ZoomView::zoom_about`]. Rotation
+    /// is not supported.
+    ///
+    /// Pop-ups opened by the child are not transformed.
+    #[autoimpl(Deref, DerefMut on inner)]
+    #[autoimpl(class_traits where W: trait on inner)]
+    #[derive(Clone, Debug)]
+    #[handler(msg = <W as Handler>::Msg)]
+    pub struct ZoomView<W: Widget> {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        pub inner: W,
+        transform: Transform,
+        limits: (f32, f32),
+    }
+
+    impl Self {
+        /// Construct, with scale limits `0.25..=8.0`
+        #[inline]
+        pub fn new(inner: W) -> Self {
+            ZoomView {
+                core: Default::default(),
+                inner,
+                transform: Transform::IDENTITY,
+                limits: (0.25, 8.0),
+            }
+        }
+
+        /// Set the minimum and maximum scale factors, builder style
+        #[inline]
+        pub fn with_limits(mut self, min: f32, max: f32) -> Self {
+            assert!(0.0 < min && min <= max);
+            self.limits = (min, max);
+            self.transform.scale = self.transform.scale.clamp(min, max);
+            self
+        }
+
+        /// Get the current transform
+        ///
+        /// This maps child coordinates to coordinates of this widget.
+        #[inline]
+        pub fn transform(&self) -> Transform {
+            self.transform
+        }
+
+        /// Set the transform
+        ///
+        /// The scale factor is clamped to the limits.
+        pub fn set_transform(&mut self, mut transform: Transform) -> TkAction {
+            transform.scale = transform.scale.clamp(self.limits.0, self.limits.1);
+            if transform == self.transform {
+                return TkAction::empty();
+            }
+            self.transform = transform;
+            TkAction::REGION_MOVED
+        }
+
+        /// Reset to the identity transform
+        #[inline]
+        pub fn reset(&mut self) -> TkAction {
+            self.set_transform(Transform::IDENTITY)
+        }
+
+        /// Scale by `factor` about a fixed point `centre`
+        ///
+        /// The `centre` is in the coordinate system of this widget (not the
+        /// child). The resulting scale factor is clamped to the limits.
+        pub fn zoom_about(&mut self, factor: f32, centre: Vec2) -> TkAction {
+            let scale = self.transform.scale * factor;
+            let factor = scale.clamp(self.limits.0, self.limits.1) / self.transform.scale;
+            let transform = self.transform.then(Transform::scale_about(factor, centre));
+            self.set_transform(transform)
+        }
+    }
+
+    impl Layout for Self {
+        fn layout(&mut self) -> layout::Layout<'_> {
+            layout::Layout::single(&mut self.inner)
+        }
+
+        fn find_id(&mut self, coord: Coord) -> Option<WidgetId> {
+            if !self.rect().contains(coord) {
+                return None;
+            }
+            let coord = self.transform.inverse().map_coord(coord);
+            self.inner.find_id(coord).or(Some(self.id()))
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, mgr: &ManagerState, disabled: bool) {
+            let disabled = disabled || self.is_disabled();
+            let inner = &mut self.inner;
+            draw.with_transform(self.core.rect, self.transform, &mut |draw| {
+                inner.draw(draw, mgr, disabled)
+            });
+        }
+    }
+
+    impl SendEvent for Self {
+        fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+            if self.is_disabled() {
+                return Response::Unhandled;
+            }
+
+            if id < self.id() {
+                let inverse = self.transform.inverse();
+                match self.inner.send(mgr, id, map_event(inverse, event.clone())) {
+                    Response::Unhandled => (),
+                    Response::Focus(rect) => {
+                        return Response::Focus(self.transform.map_rect(rect));
+                    }
+                    Response::Reveal(rect, align) => {
+                        return Response::Reveal(self.transform.map_rect(rect), align);
+                    }
+                    r => return r,
+                }
+            } else {
+                debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
+            }
+
+            match event {
+                Event::PressStart { source, coord, .. } if source.is_primary() => {
+                    let icon = Some(CursorIcon::Grabbing);
+                    mgr.request_grab(self.id(), source, coord, GrabMode::Gesture, icon);
+                    Response::None
+                }
+                Event::Gesture(gesture) => {
+                    let translation = Transform::translation(gesture.translation.into());
+                    let mut action = self.set_transform(self.transform.then(translation));
+                    action |= self.zoom_about(gesture.scale as f32, gesture.focus.into());
+                    *mgr |= action;
+                    Response::None
+                }
+                Event::PressEnd { .. } => Response::None,
+                event => self.handle(mgr, event),
+            }
+        }
+    }
+}
+
+/// Map the coordinates of an event through `transform`
+pub(crate) fn map_event(transform: Transform, mut event: Event) -> Event {
+    match &mut event {
+        Event::PressStart { coord, .. }
+        | Event::PressEnd { coord, .. }
+        | Event::CursorMove { coord } => {
+            *coord = transform.map_coord(*coord);
+        }
+        Event::PressMove { coord, delta, .. } => {
+            *coord = transform.map_coord(*coord);
+            *delta = (Vec2::from(*delta) * transform.scale).into();
+        }
+        Event::Gesture(gesture) => {
+            let s = f64::from(transform.scale);
+            let t = DVec2(transform.translate.0.into(), transform.translate.1.into());
+            gesture.focus = gesture.focus * s + t;
+            gesture.translation = gesture.translation * s;
+        }
+        _ => {}
+    }
+    event
+}
+
+impl<W: Widget + Default> Default for ZoomView<W> {
+    fn default() -> Self {
+        Self::new(W::default())
+    }
+}
@@ -12,9 +12,8 @@ use log::info;
 use std::f32::consts::PI;
 use std::time::Duration;
 
-use kas::draw::{color, Draw, DrawIface, DrawRounded, PassType};
+use kas::draw::{color, Draw, DrawRounded, DrawRoundedDyn};
 use kas::geom::{Offset, Quad, Vec2};
-use kas::shell::draw::DrawPipe;
 use kas::text::util::set_text_and_prepare;
 use kas::widgets::Window;
 use kas::{event, prelude::*};
@@ -71,14 +70,12 @@ widget! {
 
             // We use the low-level draw device to draw our clock. This means it is
             // not themeable, but gives us much more flexible draw routines.
-            let draw = draw.draw_device();
-            // Rust does not (yet!) support downcast to trait-object, thus we must use the shell's
-            // DrawPipe (which is otherwise an implementation detail). This supports DrawRounded.
-            let mut draw = DrawIface::<DrawPipe<()>>::downcast_from(draw).unwrap();
+            // DrawRoundedDyn provides DrawRounded over any shell supporting it.
+            let mut device = DrawRoundedDyn::downcast_from(draw.draw_device()).unwrap();
 
             let rect = self.core.rect;
             let quad = Quad::from(rect);
-            draw.circle(quad, 0.95, col_face);
+            device.circle(quad, 0.95, col_face);
 
             let half = (quad.b.1 - quad.a.1) / 2.0;
             let centre = quad.a + half;
@@ -90,27 +87,29 @@ widget! {
                 let l = if d % 3 == 0 { 2.0 * l } else { l };
                 let t = d as f32 * (PI / 6.0);
                 let v = Vec2(t.sin(), -t.cos());
-                draw.rounded_line(centre + v * (r - l), centre + v * r, w, col_face);
+                device.rounded_line(centre + v * (r - l), centre + v * r, w, col_face);
             }
 
-            draw.text(self.date_pos.into(), self.date.as_ref(), col_date);
-            draw.text(self.time_pos.into(), self.time.as_ref(), col_time);
-
-            // We use a new pass to control the draw order (force in front).
-            let mut draw = draw.new_pass(rect, Offset::ZERO, PassType::Clip);
-            let mut line_seg = |t: f32, r1: f32, r2: f32, w, col| {
-                let v = Vec2(t.sin(), -t.cos());
-                draw.rounded_line(centre + v * r1, centre + v * r2, w, col);
-            };
+            device.text(self.date_pos.into(), self.date.as_ref(), col_date);
+            device.text(self.time_pos.into(), self.time.as_ref(), col_time);
 
             let secs = self.now.time().num_seconds_from_midnight();
             let a_sec = f32::conv(secs % 60) * (PI / 30.0);
             let a_min = f32::conv(secs % 3600) * (PI / 1800.0);
             let a_hour = f32::conv(secs % 43200) * (PI / (21600.0));
 
-            line_seg(a_hour, 0.0, half * 0.55, half * 0.03, col_hands);
-            line_seg(a_min, 0.0, half * 0.8, half * 0.015, col_hands);
-            line_seg(a_sec, 0.0, half * 0.9, half * 0.005, col_secs);
+            // We use a new pass to control the draw order (force in front).
+            draw.with_clip_region(rect, Offset::ZERO, &mut |draw| {
+                let mut device = DrawRoundedDyn::downcast_from(draw.draw_device()).unwrap();
+                let mut line_seg = |t: f32, r1: f32, r2: f32, w, col| {
+                    let v = Vec2(t.sin(), -t.cos());
+                    device.rounded_line(centre + v * r1, centre + v * r2, w, col);
+                };
+
+                line_seg(a_hour, 0.0, half * 0.55, half * 0.03, col_hands);
+                line_seg(a_min, 0.0, half * 0.8, half * 0.015, col_hands);
+                line_seg(a_sec, 0.0, half * 0.9, half * 0.005, col_secs);
+            });
         }
     }
 
@@ -42,7 +42,7 @@ macro_rules! cursor {
 fn main() -> Result<(), kas::shell::Error> {
     env_logger::init();
 
-    // These are winit::window::CursorIcon enum variants
+    // These are all CursorIcon enum variants
     let column = Column::new(vec![
         cursor!(Default),
         cursor!(Crosshair),
@@ -274,12 +274,13 @@ fn main() -> Result<(), kas::shell::Error> {
                 }
                 fn set_radio(&mut self, mgr: &mut Manager, msg: ChildMsg<usize, EntryMsg>) {
                     match msg {
-                        ChildMsg::Select(_) | ChildMsg::Deselect(_) => (),
-                        ChildMsg::Child(n, EntryMsg::Select) => {
+                        ChildMsg::Select(..) | ChildMsg::Deselect(..) => (),
+                        ChildMsg::SelectionChanged => (),
+                        ChildMsg::Child(n, EntryMsg::Select, _) => {
                             let text = self.list.data_mut().set_active(n);
                             *mgr |= self.display.set_string(text);
                         }
-                        ChildMsg::Child(n, EntryMsg::Update(text)) => {
+                        ChildMsg::Child(n, EntryMsg::Update(text), _) => {
                             if n == self.list.data().get_active() {
                                 *mgr |= self.display.set_string(text);
                             }
@@ -336,7 +336,7 @@ fn main() -> Result<(), kas::shell::Error> {
                             mgr.adjust_theme(|theme| theme.set_scheme(&name));
                         }
                         Menu::Disabled(state) => {
-                            *mgr |= self.gallery.set_disabled(state);
+                            mgr.set_disabled(&mut self.gallery, state);
                         }
                         Menu::Quit => {
                             *mgr |= TkAction::EXIT;
@@ -91,7 +91,7 @@ struct PipeBuilder;
 impl CustomPipeBuilder for PipeBuilder {
     type Pipe = Pipe;
 
-    fn device_descriptor() -> wgpu::DeviceDescriptor<'static> {
+    fn device_descriptor(&self) -> wgpu::DeviceDescriptor<'static> {
         wgpu::DeviceDescriptor {
             label: None,
             features: wgpu::Features::PUSH_CONSTANTS | SHADER_FLOAT64,
@@ -376,13 +376,16 @@ widget! {
                 Event::Scroll(delta) => {
                     let factor = match delta {
                         event::ScrollDelta::LineDelta(_, y) => -0.5 * y as f64,
-                        event::ScrollDelta::PixelDelta(coord) => -0.01 * coord.1 as f64,
+                        event::ScrollDelta::PixelDelta(delta) => -0.01 * delta.1,
                     };
                     self.alpha = self.alpha * 2f64.powf(factor);
                     mgr.redraw(self.id());
                     Response::Msg(())
                 }
-                Event::Pan { alpha, delta } => {
+                Event::Gesture(gesture) => {
+                    // The gesture scales and rotates about its focal point:
+                    let (alpha, delta) = gesture.transform();
+
                     // Our full transform (from screen coordinates to world coordinates) is:
                     // f(p) = α_w * α_v * p + α_w * δ_v + δ_w
                     // where _w indicate world transforms (self.alpha, self.delta)
@@ -405,7 +408,7 @@ widget! {
                         self.id(),
                         source,
                         coord,
-                        event::GrabMode::PanFull,
+                        event::GrabMode::Gesture,
                         Some(event::CursorIcon::Grabbing),
                     );
                     Response::None
@@ -0,0 +1,80 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Web example
+//!
+//! A counter running in the browser. See `README.md` for build instructions.
+
+use kas::layout;
+use kas::macros::{make_layout, make_widget};
+use kas::prelude::*;
+use kas::widgets::{Label, TextButton, Window};
+
+/// Construct the UI
+pub fn window() -> Box<dyn kas::Window> {
+    let counter = make_widget! {
+        #[handler(msg = VoidMsg)]
+        struct {
+            #[widget]
+            display: Label<String> = Label::from("0"),
+            #[widget(use_msg = update)]
+            b_decr = TextButton::new_msg("−", -1),
+            #[widget(use_msg = update)]
+            b_incr = TextButton::new_msg("+", 1),
+            count: i32 = 0,
+        }
+        impl Self {
+            fn update(&mut self, mgr: &mut Manager, incr: i32) {
+                self.count += incr;
+                *mgr |= self.display.set_string(self.count.to_string());
+            }
+        }
+        impl Layout for Self {
+            fn layout<'a>(&'a mut self) -> layout::Layout<'a> {
+                make_layout!(self.core;
+                    column: [
+                        align(center): self.display,
+                        row: [self.b_decr, self.b_incr],
+                    ]
+                )
+            }
+        }
+    };
+
+    Box::new(Window::new("Counter", counter))
+}
+
+/// Start the UI in the canvas with id `canvas_id`
+///
+/// The browser provides no fonts to WebAssembly, thus `font` must contain
+/// the data of a font file (TrueType or OpenType).
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn start(canvas_id: &str, font: Vec<u8>) {
+    use kas::shell::web;
+
+    console_error_panic_hook::set_once();
+    web::load_font_data(font);
+    let canvas = web::canvas_by_id(canvas_id);
+
+    web::spawn_local(async move {
+        let theme = kas::theme::ShadedTheme::new().with_font_size(24.0);
+        let mut toolkit = match kas::shell::Toolkit::new_async(theme).await {
+            Ok(toolkit) => toolkit,
+            Err(error) => {
+                log::error!("Failed to initialise: {}", error);
+                return;
+            }
+        };
+        if let Some(canvas) = canvas {
+            toolkit.set_canvas(canvas);
+        }
+        if let Err(error) = toolkit.add_boxed(window()) {
+            log::error!("Failed to add window: {}", error);
+            return;
+        }
+        toolkit.run()
+    });
+}
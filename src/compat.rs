@@ -0,0 +1,164 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Compatibility shims for the old `Toolkit` / `TkWidget` API
+//!
+//! Early versions of KAS used a `Toolkit` trait implemented by each shell, a
+//! `TkWidget` trait through which widgets read and wrote toolkit-side state,
+//! and `handle_action` methods receiving an `Action` code. These have been
+//! replaced by [`Manager`], [`Handler`] and the [`class`](crate::class)
+//! traits. The items in this module are deprecated wrappers over the new APIs,
+//! allowing existing code to be ported incrementally.
+//!
+//! # Migration
+//!
+//! 1.  Import `kas::compat::*`. Generic code bounded on `Toolkit` compiles
+//!     against [`shell::Toolkit`](crate::shell::Toolkit).
+//! 2.  Handler parameters of type `&mut dyn TkWindow` become
+//!     `&mut TkWindow` (an alias for [`Manager`]). Since [`TkWidget`] is
+//!     implemented for [`Manager`], calls such as
+//!     `tk.set_text(&mut self.label, "text")` continue to work.
+//! 3.  Widgets with a `handle_action` method implement [`ActionHandler`],
+//!     then forward events from [`Handler::handle`] via [`handle_action`]:
+//!     ```ignore
+//!     impl Handler for MyButton {
+//!         type Msg = ButtonMsg;
+//!         fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<ButtonMsg> {
+//!             kas::compat::handle_action(self, mgr, event)
+//!         }
+//!     }
+//!     ```
+//! 4.  Replace each deprecated item with the replacement named in its
+//!     deprecation note, then remove the `compat` import.
+#![allow(deprecated)]
+
+use crate::class::{HasBool, HasStr, HasString};
+use crate::event::{Command, Event, Handler, Manager, Response};
+use crate::{Window, WindowId};
+
+/// Old name for the event manager handle
+#[deprecated(since = "0.11.0", note = "use `&mut kas::event::Manager`")]
+pub type TkWindow<'a> = Manager<'a>;
+
+/// The old shell interface
+///
+/// This is implemented by [`shell::Toolkit`](crate::shell::Toolkit).
+#[deprecated(since = "0.11.0", note = "use `kas::shell::Toolkit` directly")]
+pub trait Toolkit: Sized {
+    /// Error type
+    type Error: std::error::Error;
+
+    /// Add a window
+    fn add<W: Window + 'static>(&mut self, window: W) -> Result<WindowId, Self::Error> {
+        self.add_boxed(Box::new(window))
+    }
+
+    /// Add a boxed window
+    fn add_boxed(&mut self, window: Box<dyn Window>) -> Result<WindowId, Self::Error>;
+
+    /// Run the main loop
+    fn run(self) -> !;
+}
+
+#[cfg(feature = "wgpu")]
+impl<C, T> Toolkit for kas_wgpu::Toolkit<C, T>
+where
+    C: kas_wgpu::draw::CustomPipe,
+    T: kas_wgpu::theme::Theme<kas_wgpu::draw::DrawPipe<C>> + 'static,
+    T::Window: kas_wgpu::theme::Window,
+{
+    type Error = kas_wgpu::Error;
+
+    fn add_boxed(&mut self, window: Box<dyn Window>) -> Result<WindowId, Self::Error> {
+        kas_wgpu::Toolkit::add_boxed(self, window)
+    }
+
+    fn run(self) -> ! {
+        kas_wgpu::Toolkit::run(self)
+    }
+}
+
+/// The old widget-state interface
+///
+/// Widgets now own their state; this reads and writes it via the
+/// [`class`](crate::class) traits, passing any resulting
+/// [`TkAction`](crate::TkAction) to the [`Manager`].
+#[deprecated(
+    since = "0.11.0",
+    note = "use `kas::class` trait methods and apply the resulting `TkAction` to the `Manager`"
+)]
+pub trait TkWidget {
+    /// Get a widget's boolean state
+    fn get_bool(&self, widget: &dyn HasBool) -> bool {
+        widget.get_bool()
+    }
+
+    /// Set a widget's boolean state
+    fn set_bool(&mut self, widget: &mut dyn HasBool, state: bool);
+
+    /// Get a widget's text
+    fn get_text(&self, widget: &dyn HasStr) -> String {
+        widget.get_string()
+    }
+
+    /// Set a widget's text
+    fn set_text(&mut self, widget: &mut dyn HasString, text: &str);
+}
+
+impl<'a> TkWidget for Manager<'a> {
+    fn set_bool(&mut self, widget: &mut dyn HasBool, state: bool) {
+        *self |= widget.set_bool(state);
+    }
+
+    fn set_text(&mut self, widget: &mut dyn HasString, text: &str) {
+        *self |= widget.set_str(text);
+    }
+}
+
+/// Old action codes
+///
+/// These correspond to a subset of [`Event`].
+#[deprecated(since = "0.11.0", note = "match on `kas::event::Event`")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Widget activation (e.g. a button press)
+    Activate,
+    /// Keyboard command, with shift state
+    Command(Command, bool),
+}
+
+impl Action {
+    /// Translate from an [`Event`], if possible
+    pub fn from_event(event: Event) -> Result<Self, Event> {
+        match event {
+            Event::Activate => Ok(Action::Activate),
+            Event::Command(cmd, shift) => Ok(Action::Command(cmd, shift)),
+            event => Err(event),
+        }
+    }
+}
+
+/// The old action-handling interface
+#[deprecated(since = "0.11.0", note = "implement `kas::event::Handler::handle`")]
+pub trait ActionHandler: Handler {
+    /// Handle an action
+    fn handle_action(&mut self, mgr: &mut Manager, action: Action) -> Response<Self::Msg>;
+}
+
+/// Forward an event to [`ActionHandler::handle_action`]
+///
+/// Events without an equivalent [`Action`] yield [`Response::Unhandled`], as
+/// does the default implementation of [`Handler::handle`].
+#[deprecated(since = "0.11.0", note = "implement `kas::event::Handler::handle`")]
+pub fn handle_action<W: ActionHandler + ?Sized>(
+    widget: &mut W,
+    mgr: &mut Manager,
+    event: Event,
+) -> Response<W::Msg> {
+    match Action::from_event(event) {
+        Ok(action) => widget.handle_action(mgr, action),
+        Err(_) => Response::Unhandled,
+    }
+}
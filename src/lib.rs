@@ -33,6 +33,12 @@ pub mod prelude;
 // macro re-exports
 pub mod macros;
 
+// testing utilities
+pub mod test;
+
+// deprecated API shims
+pub mod compat;
+
 // include most of kas_core, excluding macros and prelude:
 #[cfg(feature = "config")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "config")))]
@@ -42,7 +48,7 @@ pub use kas_core::config;
 pub use kas_core::ShellWindow;
 pub use kas_core::{cast, class, dir, draw, event, geom, layout, text, updatable, util};
 pub use kas_core::{Boxed, Layout, Window};
-pub use kas_core::{CoreData, Future, Popup, TkAction, WidgetId, WindowId};
+pub use kas_core::{CoreData, Future, Popup, ShellCapabilities, TkAction, WidgetId, WindowId};
 pub use kas_core::{Widget, WidgetChildren, WidgetConfig, WidgetCore};
 
 pub use kas_widgets as widgets;
@@ -127,6 +127,10 @@
 //! -   `column` or `row`: these are synonyms for `list(down)` and `list(right)`
 //! -   `grid: { ... }` — child widgets are arranged in a grid (see examples)
 //!
+//! Any sub-layout may be named via `region(NAME): LAYOUT`; the `Rect` assigned
+//! to this region may then be queried from draw or event-handling code via
+//! [`CoreData::layout_region`], e.g. `self.core.layout_region("header")`.
+//!
 //! Additional parameters are optional:
 //!
 //! -   `area=FIELD` where `FIELD` is the name of a child widget — in this case,
@@ -159,6 +163,22 @@
 //! -   `halign = ...` — one of `default`, `left`, `centre`, `center`, `right`, `stretch`
 //! -   `valign = ...` — one of `default`, `top`, `centre`, `center`, `bottom`, `stretch`
 //!
+//! The margins around a child may be overridden (see [`MarginSelector`]):
+//!
+//! -   `margins = ...` — one of `none`, `outer`, `inner`, `text`, or a size in
+//!     logical pixels (e.g. `margins = 4`), applied on each side
+//!
+//! The stretch priority of a child may be overridden, for example to make one
+//! child of a `row` absorb surplus space (e.g. `#[widget(stretch = high)]`).
+//! This replaces the [`Stretch`] priority of the child's [`SizeRules`] on both
+//! axes:
+//!
+//! -   `stretch = ...` — one of `none`, `filler`, `low`, `high`, `maximize`
+//!
+//! These parameters are only used by layouts generated via the `layout`
+//! property, and are supported by both [`widget`] and [`make_widget`], e.g.
+//! `#[widget(halign = right, margins = none)]`.
+//!
 //! ### WidgetConfig
 //!
 //! The [`WidgetConfig`] trait allows additional configuration of widget
@@ -174,6 +194,14 @@
 //!
 //! -   `key_nav = false`: a boolean, describing whether the widget supports
 //!     keyboard navigation (see [`WidgetConfig::key_nav`])
+//!  -  `focus_scope = false`: a boolean, describing whether Tab navigation
+//!     cycles within this widget (see [`WidgetConfig::focus_scope`])
+//!  -  `tab_index = None`: an expression of type `Option<u32>`, giving an
+//!     explicit position in Tab navigation order (see [`WidgetConfig::tab_index`])
+//!  -  `nav_wrap = true`: a boolean, describing whether Tab navigation wraps
+//!     around within this widget (see [`WidgetConfig::nav_wrap`])
+//!  -  `initial_focus = false`: an expression of type `bool`, marking the
+//!     widget to receive initial navigation focus (see [`WidgetConfig::initial_focus`])
 //!  -  `hover_highlight = false`: a boolean, describing whether to request a
 //!     redraw when mouse-hover status changes (see [`WidgetConfig::hover_highlight`])
 //!  -   `cursor_icon = kas::event::CursorIcon::Default`: the cursor icon to use
@@ -453,12 +481,26 @@
 //! #[widget] display: for<W: Widget<Msg = VoidMsg>> Frame<W> =
 //!     Frame::new(Label::new("example")),
 //! ```
+//!
+//! ### Inline handlers
+//!
+//! Where a child's [message handler](#handling-response-messages-from-children)
+//! is short, it may be written inline as a closure instead of a named method.
+//! The closure takes `self`, the [`Manager`] and the message; the message type
+//! must be specified. The return type follows the keyword used, as for handler
+//! methods. For example:
+//! ```nocompile
+//! #[widget(use_msg = |self, _, msg: OkCancel| self.answer = Some(msg))] _ = buttons,
+//! ```
+//!
+//! Each closure is converted to a hidden method on the anonymous struct.
+//! This is only supported by `make_widget!`, not by [`widget`].
 
 // Imported for doc-links
 #[allow(unused)]
 use crate::{
-    event::{Handler, Response, SendEvent},
-    layout::AlignHints,
+    event::{Handler, Manager, Response, SendEvent},
+    layout::{AlignHints, MarginSelector, SizeRules, Stretch},
     CoreData, Layout, Widget, WidgetChildren, WidgetConfig, WidgetCore, WidgetId,
 };
 
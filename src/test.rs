@@ -0,0 +1,824 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Utilities for behavioural testing of widgets
+//!
+//! A [`TestWindow`] drives a window without a graphical shell. Input is
+//! simulated through the real [`event::Manager`] dispatch, thus widgets
+//! observe the same event sequences (hover, press, focus changes, commands)
+//! as they would when driven by a windowing system.
+//!
+//! Widgets are addressed by *path*: a list of child indices (as used by
+//! [`WidgetChildren::get_child`]) from the window root.
+//!
+//! ```no_run
+//! use kas::event::Command;
+//! use kas::test::TestWindow;
+//! use kas::widgets::{EditBox, Window};
+//!
+//! let window = Window::new("Test", EditBox::new("Hello"));
+//! let mut tw = TestWindow::new(window);
+//! tw.click(&[0]);
+//! tw.key(Command::End);
+//! tw.type_text(", world!");
+//! tw.key(Command::SelectAll);
+//! tw.key(Command::Copy);
+//! assert_eq!(tw.clipboard(), Some("Hello, world!"));
+//! tw.assert_label(&[0], "Hello, world!");
+//! ```
+//!
+//! Widgets may also be tested in isolation: [`TestWindow::send_to`] sends an
+//! [`Event`](event::Event) directly to a widget and returns its typed
+//! [`Response`]. Timers may be fired without waiting using [`TestWindow::tick`].
+//!
+//! Sizing uses [`TestDims`], which has fixed metrics independent of any theme
+//! or scale factor, thus layout rects are reproducible across platforms (aside
+//! from text, which depends on the font). [`TestShell`] is the mock
+//! [`ShellWindow`] used by [`TestWindow`]; both may be used independently.
+//!
+//! Note that text layout requires fonts: [`TestWindow::new`] loads the default
+//! system font and panics if this is unavailable.
+//!
+//! Drawing is not supported. Image resources may be allocated, but contents
+//! are discarded.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::cast::{Cast, CastFloat, ConvFloat};
+use crate::draw::{DrawShared, ImageError, ImageFormat, ImageId, InputState, SizeHandle};
+use crate::draw::{TextClass, ThemeApi};
+use crate::event::{self, Command, CursorIcon, Event, Manager, ManagerState, MouseButton};
+use crate::event::{ModifiersState, Response, UpdateHandle, VirtualKeyCode};
+use crate::geom::{Coord, Offset, Rect, Size};
+use crate::layout::{AxisInfo, FrameRules, Margins, SizeRules, SolveCache, Stretch};
+use crate::text::{TextApi, TextApiExt};
+use crate::widgets::{AccelLabel, EditBox, EditField, ScrollLabel, StrLabel, StringLabel};
+use crate::{Layout, Popup, ShellCapabilities, ShellWindow, TkAction, Window, WindowId};
+use crate::{Widget, WidgetChildren, WidgetConfig, WidgetCore};
+
+/// A [`SizeHandle`] with fixed metrics, independent of any theme
+///
+/// Sizes are in pixels and assume 96 DPI with a scale factor of 1. Frames,
+/// margins and control sizes are small constants; text is measured using
+/// the loaded fonts at the configured size.
+#[derive(Clone, Debug)]
+pub struct TestDims {
+    dpp: f32,
+    pt_size: f32,
+    line_height: i32,
+    min_line_length: i32,
+}
+
+impl Default for TestDims {
+    fn default() -> Self {
+        TestDims::new(10.0)
+    }
+}
+
+impl TestDims {
+    /// Construct, using the given font size (in points)
+    pub fn new(pt_size: f32) -> Self {
+        let dpp = 96.0 / 72.0;
+        let dpem = dpp * pt_size;
+        TestDims {
+            dpp,
+            pt_size,
+            line_height: i32::conv_ceil(1.2 * dpem),
+            min_line_length: (8.0 * dpem).cast_nearest(),
+        }
+    }
+}
+
+impl SizeHandle for TestDims {
+    fn scale_factor(&self) -> f32 {
+        1.0
+    }
+
+    fn pixels_from_points(&self, pt: f32) -> f32 {
+        self.dpp * pt
+    }
+
+    fn pixels_from_em(&self, em: f32) -> f32 {
+        self.dpp * self.pt_size * em
+    }
+
+    fn frame(&self, _vert: bool) -> FrameRules {
+        FrameRules::new_sym(4, 0, 2)
+    }
+    fn menu_frame(&self, _vert: bool) -> FrameRules {
+        FrameRules::new_sym(2, 0, 0)
+    }
+    fn separator(&self) -> Size {
+        Size::splat(4)
+    }
+
+    fn nav_frame(&self, _vert: bool) -> FrameRules {
+        FrameRules::new_sym(2, 0, 0)
+    }
+
+    fn inner_margin(&self) -> Size {
+        Size::splat(2)
+    }
+
+    fn outer_margins(&self) -> Margins {
+        Margins::splat(4)
+    }
+
+    fn frame_margins(&self) -> Margins {
+        Margins::splat(2)
+    }
+
+    fn text_margins(&self) -> Margins {
+        Margins::splat(2)
+    }
+
+    fn line_height(&self, _: TextClass) -> i32 {
+        self.line_height
+    }
+
+    fn text_bound(&self, text: &mut dyn TextApi, class: TextClass, axis: AxisInfo) -> SizeRules {
+        let required = text.update_env(|env| {
+            env.set_dpp(self.dpp);
+            env.set_pt_size(self.pt_size);
+
+            let mut bounds = crate::text::Vec2::INFINITY;
+            if let Some(size) = axis.size_other_if_fixed(false) {
+                bounds.1 = size.cast();
+            } else if let Some(size) = axis.size_other_if_fixed(true) {
+                bounds.0 = size.cast();
+            }
+            env.set_bounds(bounds);
+
+            env.set_wrap(matches!(
+                class,
+                TextClass::Label | TextClass::EditMulti | TextClass::LabelScroll
+            ));
+        });
+
+        let margins = (2, 2);
+        if axis.is_horizontal() {
+            let bound = i32::conv_ceil(required.0);
+            let min = self.min_line_length;
+            let (min, ideal) = match class {
+                TextClass::Edit => (min, 2 * min),
+                TextClass::EditMulti => (min, 3 * min),
+                _ => (bound.min(min), bound),
+            };
+            SizeRules::new(min, ideal, margins, Stretch::Low)
+        } else {
+            let min = match class {
+                TextClass::Label => i32::conv_ceil(required.1),
+                TextClass::MenuLabel | TextClass::Button | TextClass::Edit => self.line_height,
+                TextClass::EditMulti | TextClass::LabelScroll => self.line_height * 3,
+            };
+            let ideal = i32::conv_ceil(required.1).max(min);
+            SizeRules::new(min, ideal, margins, Stretch::None)
+        }
+    }
+
+    fn edit_marker_width(&self) -> f32 {
+        2.0
+    }
+
+    fn button_surround(&self, _vert: bool) -> FrameRules {
+        FrameRules::new_sym(4, 2, 4)
+    }
+
+    fn edit_surround(&self, _vert: bool) -> FrameRules {
+        FrameRules::new_sym(4, 2, 0)
+    }
+
+    fn checkbox(&self) -> Size {
+        Size::splat(18)
+    }
+
+    fn radiobox(&self) -> Size {
+        self.checkbox()
+    }
+
+    fn scrollbar(&self) -> (Size, i32) {
+        (Size(8, 8), 24)
+    }
+
+    fn slider(&self) -> (Size, i32) {
+        (Size(12, 25), 60)
+    }
+
+    fn progress_bar(&self) -> Size {
+        Size(24, 8)
+    }
+}
+
+impl ThemeApi for TestDims {
+    fn set_font_size(&mut self, pt_size: f32) -> TkAction {
+        *self = TestDims::new(pt_size);
+        TkAction::RESIZE | TkAction::THEME_UPDATE
+    }
+
+    fn set_scheme(&mut self, _: &str) -> TkAction {
+        TkAction::empty()
+    }
+
+    fn list_schemes(&self) -> Vec<&str> {
+        vec![]
+    }
+}
+
+/// Image store which tracks sizes only
+#[derive(Debug, Default)]
+struct TestImages {
+    next_id: u32,
+    sizes: HashMap<ImageId, Size>,
+}
+
+impl DrawShared for TestImages {
+    fn image_alloc(&mut self, size: (u32, u32)) -> Result<ImageId, ImageError> {
+        self.next_id += 1;
+        let id = ImageId::try_new(self.next_id).ok_or(ImageError::Allocation)?;
+        self.sizes.insert(id, size.into());
+        Ok(id)
+    }
+
+    fn image_upload(&mut self, _: ImageId, _: &[u8], _: ImageFormat) {}
+
+    fn image_from_path(&mut self, path: &Path) -> Result<ImageId, ImageError> {
+        // Image contents are never used, thus we only check the file exists
+        std::fs::metadata(path)?;
+        self.image_alloc((1, 1))
+    }
+
+    fn image_free_from_path(&mut self, _: &Path) {}
+
+    fn image_free(&mut self, id: ImageId) {
+        self.sizes.remove(&id);
+    }
+
+    fn image_size(&self, id: ImageId) -> Option<Size> {
+        self.sizes.get(&id).cloned()
+    }
+}
+
+/// A mock [`ShellWindow`]
+///
+/// This records requests (pop-ups, window closures, updates, theme actions)
+/// for processing by the owner, emulates a clipboard and otherwise does
+/// nothing. Additional windows are not supported; their widgets are dropped.
+pub struct TestShell {
+    dims: TestDims,
+    images: TestImages,
+    clipboard: Option<String>,
+    next_window_id: u32,
+    new_popups: Vec<(WindowId, Popup)>,
+    closed: Vec<WindowId>,
+    updates: Vec<(UpdateHandle, u64)>,
+    action: TkAction,
+    cursor_icon: CursorIcon,
+}
+
+impl Default for TestShell {
+    fn default() -> Self {
+        TestShell::new(TestDims::default())
+    }
+}
+
+impl TestShell {
+    /// Construct, using the given metrics
+    pub fn new(dims: TestDims) -> Self {
+        TestShell {
+            dims,
+            images: TestImages::default(),
+            clipboard: None,
+            next_window_id: 0,
+            new_popups: vec![],
+            closed: vec![],
+            updates: vec![],
+            action: TkAction::empty(),
+            cursor_icon: CursorIcon::Default,
+        }
+    }
+
+    /// Access the metrics
+    pub fn dims(&self) -> &TestDims {
+        &self.dims
+    }
+
+    /// Get the clipboard contents
+    pub fn clipboard(&self) -> Option<&str> {
+        self.clipboard.as_deref()
+    }
+
+    /// Get the current mouse cursor icon
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.cursor_icon
+    }
+
+    /// Take the list of pop-ups requested since the last call
+    pub fn take_popups(&mut self) -> Vec<(WindowId, Popup)> {
+        std::mem::take(&mut self.new_popups)
+    }
+
+    /// Take the list of windows closed since the last call
+    pub fn take_closed(&mut self) -> Vec<WindowId> {
+        std::mem::take(&mut self.closed)
+    }
+
+    /// Take the list of updates triggered since the last call
+    pub fn take_updates(&mut self) -> Vec<(UpdateHandle, u64)> {
+        std::mem::take(&mut self.updates)
+    }
+
+    /// Take the action resulting from theme adjustments since the last call
+    pub fn take_action(&mut self) -> TkAction {
+        std::mem::take(&mut self.action)
+    }
+
+    fn next_window_id(&mut self) -> WindowId {
+        self.next_window_id += 1;
+        WindowId::new(NonZeroU32::new(self.next_window_id).unwrap())
+    }
+}
+
+impl ShellWindow for TestShell {
+    fn capabilities(&self) -> ShellCapabilities {
+        ShellCapabilities::POPUPS | ShellCapabilities::CLIPBOARD
+    }
+
+    fn add_popup(&mut self, popup: Popup) -> Option<WindowId> {
+        let id = self.next_window_id();
+        self.new_popups.push((id, popup));
+        Some(id)
+    }
+
+    fn add_window(&mut self, _: Box<dyn Window>) -> WindowId {
+        // Additional windows are not supported; the widget is dropped.
+        self.next_window_id()
+    }
+
+    fn close_window(&mut self, id: WindowId) {
+        self.closed.push(id);
+    }
+
+    fn trigger_update(&mut self, handle: UpdateHandle, payload: u64) {
+        self.updates.push((handle, payload));
+    }
+
+    fn get_clipboard(&mut self) -> Option<String> {
+        self.clipboard.clone()
+    }
+
+    fn set_clipboard(&mut self, content: String) {
+        self.clipboard = Some(content);
+    }
+
+    fn adjust_theme(&mut self, f: &mut dyn FnMut(&mut dyn ThemeApi) -> TkAction) {
+        let action = f(&mut self.dims);
+        self.action |= action;
+    }
+
+    fn size_handle(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
+        f(&mut self.dims);
+    }
+
+    fn draw_shared(&mut self, f: &mut dyn FnMut(&mut dyn DrawShared)) {
+        f(&mut self.images);
+    }
+
+    fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        self.cursor_icon = icon;
+    }
+}
+
+/// A headless window for testing
+///
+/// See the [module documentation](self).
+pub struct TestWindow<W: Window> {
+    widget: W,
+    id: WindowId,
+    mgr: ManagerState,
+    shell: TestShell,
+    solve_cache: SolveCache,
+    size: Size,
+    closed: bool,
+    next_scancode: u32,
+}
+
+impl<W: Window> TestWindow<W> {
+    /// Construct, using the window's ideal size
+    pub fn new(widget: W) -> Self {
+        TestWindow::new_with_dims(widget, TestDims::default())
+    }
+
+    /// Construct with the given metrics, using the window's ideal size
+    pub fn new_with_dims(widget: W, dims: TestDims) -> Self {
+        let fonts = crate::text::fonts::fonts();
+        if let Err(e) = fonts.select_default() {
+            panic!("Error loading font: {}", e);
+        }
+
+        let config = Rc::new(RefCell::new(event::Config::default()));
+        let mut shell = TestShell::new(dims);
+        let id = shell.next_window_id();
+
+        let mut widget = widget;
+        let mut mgr = ManagerState::new(config, 1.0);
+        mgr.configure(&mut shell, &mut widget);
+        let solve_cache = SolveCache::find_constraints(widget.as_widget_mut(), &mut shell.dims);
+        let size = solve_cache.ideal(true).max(Size(1, 1));
+
+        let mut window = TestWindow {
+            widget,
+            id,
+            mgr,
+            shell,
+            solve_cache,
+            size,
+            closed: false,
+            next_scancode: 0,
+        };
+        window.apply_size();
+        window.update();
+        window
+    }
+
+    /// Access the window widget
+    pub fn widget(&self) -> &W {
+        &self.widget
+    }
+
+    /// Access the window widget mutably
+    ///
+    /// Any [`TkAction`] returned by methods on the widget should be passed to
+    /// [`TestWindow::send_action`].
+    pub fn widget_mut(&mut self) -> &mut W {
+        &mut self.widget
+    }
+
+    /// Get the window size
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Resize the window
+    pub fn resize(&mut self, size: Size) {
+        self.size = size;
+        self.apply_size();
+        self.update();
+    }
+
+    /// True if the window has requested closure (or app exit)
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Access the mock shell
+    pub fn shell(&self) -> &TestShell {
+        &self.shell
+    }
+
+    /// Get the clipboard contents
+    pub fn clipboard(&self) -> Option<&str> {
+        self.shell.clipboard()
+    }
+
+    /// Set the clipboard contents
+    pub fn set_clipboard(&mut self, content: String) {
+        self.shell.clipboard = Some(content);
+    }
+
+    /// Get the current mouse cursor icon
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.shell.cursor_icon()
+    }
+
+    /// Find the widget at `path`
+    ///
+    /// Panics if the path does not exist.
+    pub fn find(&self, path: &[usize]) -> &dyn WidgetConfig {
+        self.find_with_offset(path).0
+    }
+
+    /// Find the widget at `path` and downcast to type `T`
+    ///
+    /// Panics if the path does not exist or the widget is not of type `T`.
+    pub fn get<T: Any>(&self, path: &[usize]) -> &T {
+        let w = self.find(path);
+        w.as_any().downcast_ref::<T>().unwrap_or_else(|| {
+            panic!(
+                "widget at path {:?} is {}, not {}",
+                path,
+                w.widget_name(),
+                std::any::type_name::<T>()
+            )
+        })
+    }
+
+    /// Find the widget at `path` and downcast to type `T`, mutably
+    ///
+    /// Any [`TkAction`] returned by methods on the widget should be passed to
+    /// [`TestWindow::send_action`].
+    ///
+    /// Panics if the path does not exist or the widget is not of type `T`.
+    pub fn get_mut<T: Any>(&mut self, path: &[usize]) -> &mut T {
+        find_mut(self.widget.as_widget_mut(), path)
+    }
+
+    /// Get the rect of the widget at `path`, relative to the window
+    ///
+    /// Panics if the path does not exist.
+    pub fn rect(&self, path: &[usize]) -> Rect {
+        let (w, offset) = self.find_with_offset(path);
+        w.rect() - offset
+    }
+
+    /// Assert that the widget at `path` has rect `rect`
+    ///
+    /// See [`TestWindow::rect`].
+    #[track_caller]
+    pub fn assert_rect(&self, path: &[usize], rect: Rect) {
+        let actual = self.rect(path);
+        assert_eq!(actual, rect, "unexpected rect of widget at path {:?}", path);
+    }
+
+    /// True if the widget at `path` has keyboard navigation focus
+    pub fn has_nav_focus(&self, path: &[usize]) -> bool {
+        self.mgr.nav_focus(self.find(path).id())
+    }
+
+    /// True if the widget at `path` has character (keyboard input) focus
+    pub fn has_char_focus(&self, path: &[usize]) -> bool {
+        self.mgr.has_char_focus(self.find(path).id()).0
+    }
+
+    /// Force the visual interaction state of the widget at `path`
+    ///
+    /// See [`ManagerState::simulate_state`].
+    pub fn simulate_state(&mut self, path: &[usize], state: InputState) {
+        let id = self.find(path).id();
+        self.mgr.simulate_state(id, state);
+    }
+
+    /// Assert that the widget at `path` has keyboard navigation focus
+    #[track_caller]
+    pub fn assert_nav_focus(&self, path: &[usize]) {
+        assert!(
+            self.has_nav_focus(path),
+            "widget at path {:?} does not have navigation focus",
+            path
+        );
+    }
+
+    /// Assert that the widget at `path` has character focus
+    #[track_caller]
+    pub fn assert_char_focus(&self, path: &[usize]) {
+        assert!(
+            self.has_char_focus(path),
+            "widget at path {:?} does not have character focus",
+            path
+        );
+    }
+
+    /// Get the text of the label or edit widget at `path`
+    ///
+    /// Supports [`StrLabel`], [`StringLabel`], [`AccelLabel`],
+    /// `ScrollLabel<String>`, [`EditBox`] and [`EditField`] (with the default
+    /// guard). For other types, use [`TestWindow::get`] directly.
+    ///
+    /// Panics if the path does not exist or the widget type is not supported.
+    pub fn label(&self, path: &[usize]) -> String {
+        use crate::class::HasStr;
+        let w = self.find(path);
+        let any = w.as_any();
+        if let Some(w) = any.downcast_ref::<StrLabel>() {
+            w.get_string()
+        } else if let Some(w) = any.downcast_ref::<StringLabel>() {
+            w.get_string()
+        } else if let Some(w) = any.downcast_ref::<AccelLabel>() {
+            w.get_string()
+        } else if let Some(w) = any.downcast_ref::<ScrollLabel<String>>() {
+            w.get_string()
+        } else if let Some(w) = any.downcast_ref::<EditBox>() {
+            w.get_string()
+        } else if let Some(w) = any.downcast_ref::<EditField>() {
+            w.get_string()
+        } else {
+            panic!(
+                "widget at path {:?} is {}, which has no supported text",
+                path,
+                w.widget_name()
+            );
+        }
+    }
+
+    /// Assert that the widget at `path` has text `text`
+    ///
+    /// See [`TestWindow::label`].
+    #[track_caller]
+    pub fn assert_label(&self, path: &[usize], text: &str) {
+        let label = self.label(path);
+        assert_eq!(label, text, "unexpected text of widget at path {:?}", path);
+    }
+
+    /// Simulate a click on the centre of the widget at `path`
+    ///
+    /// This moves the mouse cursor, then presses and releases the left button.
+    pub fn click(&mut self, path: &[usize]) {
+        let (w, offset) = self.find_with_offset(path);
+        let rect = w.rect();
+        let coord = rect.pos + rect.size / 2 - offset;
+        self.click_at(coord);
+    }
+
+    /// Simulate a click at `coord` (relative to the window)
+    pub fn click_at(&mut self, coord: Coord) {
+        self.with(|mgr, widget| mgr.handle_cursor_moved(widget, coord));
+        self.with(|mgr, widget| mgr.handle_mouse_input(widget, true, MouseButton::Left));
+        self.with(|mgr, widget| mgr.handle_mouse_input(widget, false, MouseButton::Left));
+    }
+
+    /// Simulate text entry
+    ///
+    /// Characters are sent to the widget with character focus, if any.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.with(|mgr, widget| mgr.handle_received_char(widget, c));
+        }
+    }
+
+    /// Simulate a [`Command`], as if triggered by its keyboard shortcut
+    ///
+    /// Returns true if some widget handled the command.
+    pub fn key(&mut self, cmd: Command) -> bool {
+        let mut handled = false;
+        self.with(|mgr, widget| handled = mgr.handle_command(widget, cmd));
+        handled
+    }
+
+    /// Simulate pressing and releasing a key
+    ///
+    /// This is processed like a physical key press: it may trigger a
+    /// [`Command`] (subject to modifiers; see [`TestWindow::set_modifiers`]),
+    /// an accelerator key or navigation. Text input is not generated; use
+    /// [`TestWindow::type_text`] for that.
+    pub fn press_key(&mut self, vkey: VirtualKeyCode) {
+        self.next_scancode += 1;
+        let scancode = self.next_scancode;
+        self.with(|mgr, widget| mgr.handle_key_press(widget, vkey, scancode));
+        self.with(|mgr, _| mgr.handle_key_release(scancode));
+    }
+
+    /// Set the state of modifier keys
+    pub fn set_modifiers(&mut self, state: ModifiersState) {
+        self.with(|mgr, _| mgr.handle_modifiers(state));
+    }
+
+    /// Fire the next pending timer
+    ///
+    /// Time is not simulated; instead, all timers scheduled at the same time
+    /// as the earliest pending timer are fired immediately. Returns false if
+    /// no timer is pending.
+    pub fn tick(&mut self) -> bool {
+        let now = match self.mgr.next_resume() {
+            Some(time) => time,
+            None => return false,
+        };
+        self.with(|mgr, widget| mgr.update_timer_at(widget, now));
+        true
+    }
+
+    /// Send `event` directly to the widget of type `T` at `path`
+    ///
+    /// The widget's typed response is returned; parent widgets do not see the
+    /// event or response. This is useful for unit-testing a widget's handler.
+    ///
+    /// Panics if the path does not exist or the widget is not of type `T`.
+    pub fn send_to<T: Widget>(&mut self, path: &[usize], event: Event) -> Response<T::Msg> {
+        let mut response = None;
+        let widget = self.widget.as_widget_mut();
+        self.mgr.with(&mut self.shell, |mgr| {
+            let w: &mut T = find_mut(widget, path);
+            let id = w.id();
+            response = Some(w.send(mgr, id, event));
+        });
+        self.update();
+        response.unwrap()
+    }
+
+    /// Apply a [`TkAction`]
+    pub fn send_action(&mut self, action: TkAction) {
+        self.mgr.send_action(action);
+        self.update();
+    }
+
+    /// Run `f` on a [`Manager`], then process all resulting actions
+    ///
+    /// This may be used to simulate input not directly supported by
+    /// [`TestWindow`].
+    pub fn with<F: FnOnce(&mut Manager, &mut W)>(&mut self, f: F) {
+        let widget = &mut self.widget;
+        self.mgr.with(&mut self.shell, |mgr| f(mgr, widget));
+        self.update();
+    }
+
+    fn find_with_offset(&self, path: &[usize]) -> (&dyn WidgetConfig, Offset) {
+        let mut w = self.widget.as_widget();
+        let mut offset = Offset::ZERO;
+        for (i, index) in path.iter().enumerate() {
+            offset += w.translation();
+            w = w
+                .get_child(*index)
+                .unwrap_or_else(|| panic!("widget path {:?} not found (at index {})", path, i));
+        }
+        (w, offset)
+    }
+
+    fn apply_size(&mut self) {
+        let rect = Rect::new(Coord::ZERO, self.size);
+        let solve_cache = &mut self.solve_cache;
+        let widget = &mut self.widget;
+        self.mgr.with(&mut self.shell, |mgr| {
+            solve_cache.apply_rect(widget.as_widget_mut(), mgr, rect, true);
+            widget.resize_popups(mgr);
+        });
+    }
+
+    fn update(&mut self) {
+        loop {
+            let widget = &mut self.widget;
+            let new_popups = self.shell.take_popups();
+            let closed = self.shell.take_closed();
+            let updates = self.shell.take_updates();
+            let own_id = self.id;
+            let mut close_self = false;
+            self.mgr.with(&mut self.shell, |mgr| {
+                for (id, popup) in new_popups {
+                    widget.add_popup(mgr, id, popup);
+                }
+                for id in closed {
+                    if id == own_id {
+                        close_self = true;
+                    } else {
+                        widget.remove_popup(mgr, id);
+                    }
+                }
+                for (handle, payload) in updates {
+                    mgr.update_handle(widget, handle, payload);
+                }
+            });
+            if close_self {
+                self.mgr.send_action(TkAction::CLOSE);
+            }
+
+            let mut action = self.mgr.update(&mut self.shell, &mut self.widget);
+            action |= self.shell.take_action();
+            self.handle_action(action);
+
+            let shell = &self.shell;
+            if shell.new_popups.is_empty() && shell.closed.is_empty() && shell.updates.is_empty() {
+                break;
+            }
+        }
+    }
+
+    fn handle_action(&mut self, action: TkAction) {
+        if action.contains(TkAction::CLOSE) || action.contains(TkAction::EXIT) {
+            self.closed = true;
+        }
+        if action.contains(TkAction::RECONFIGURE) {
+            self.mgr.configure(&mut self.shell, &mut self.widget);
+            self.solve_cache.invalidate_rule_cache();
+            self.apply_size();
+        } else if action.contains(TkAction::RESIZE) {
+            self.solve_cache.invalidate_rule_cache();
+            self.apply_size();
+        } else if action.contains(TkAction::SET_SIZE) {
+            self.apply_size();
+        }
+        if action.contains(TkAction::REGION_MOVED) {
+            self.mgr.region_moved(&mut self.shell, &mut self.widget);
+        }
+    }
+}
+
+fn find_mut<'a, T: Any>(mut w: &'a mut dyn WidgetConfig, path: &[usize]) -> &'a mut T {
+    for (i, index) in path.iter().enumerate() {
+        w = w
+            .get_child_mut(*index)
+            .unwrap_or_else(|| panic!("widget path {:?} not found (at index {})", path, i));
+    }
+    let name = w.widget_name();
+    w.as_any_mut().downcast_mut::<T>().unwrap_or_else(|| {
+        panic!(
+            "widget at path {:?} is {}, not {}",
+            path,
+            name,
+            std::any::type_name::<T>()
+        )
+    })
+}
@@ -0,0 +1,111 @@
+//! Test use of multiple custom draw pipes via a registry
+#![cfg(feature = "headless")]
+
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use kas::draw::{Draw, DrawIface, PassId};
+use kas::prelude::*;
+use kas::shell::draw::{CustomPipe, CustomPipeBuilder, CustomPipeRegistry, CustomWindow};
+use kas::shell::draw::{DrawCustom, DrawPipe, RegistryParam, RegistryPipe};
+use kas::shell::{wgpu, Headless, Options};
+use kas::theme::FlatTheme;
+use kas::widgets::Window;
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+/// A pipe logging each window constructed and each parameter received
+struct Recorder<P> {
+    name: &'static str,
+    log: Log,
+    _param: PhantomData<P>,
+}
+
+impl<P> Recorder<P> {
+    fn new(name: &'static str, log: &Log) -> Self {
+        let log = log.clone();
+        let _param = PhantomData;
+        Recorder { name, log, _param }
+    }
+}
+
+impl<P: Display + 'static> CustomPipeBuilder for Recorder<P> {
+    type Pipe = Recorder<P>;
+
+    fn build(
+        &mut self,
+        _: &wgpu::Device,
+        _: &wgpu::BindGroupLayout,
+        _: wgpu::TextureFormat,
+    ) -> Self::Pipe {
+        Recorder::new(self.name, &self.log)
+    }
+}
+
+impl<P: Display + 'static> CustomPipe for Recorder<P> {
+    type Window = Recorder<P>;
+
+    fn new_window(&self, _: &wgpu::Device) -> Self::Window {
+        self.log.borrow_mut().push(format!("{}: window", self.name));
+        Recorder::new(self.name, &self.log)
+    }
+}
+
+impl<P: Display + 'static> CustomWindow for Recorder<P> {
+    type Param = P;
+
+    fn invoke(&mut self, _: PassId, _: Rect, param: Self::Param) {
+        let entry = format!("{}: {}", self.name, param);
+        self.log.borrow_mut().push(entry);
+    }
+}
+
+widget! {
+    /// A widget drawing via both registered pipes
+    #[derive(Debug, Default)]
+    struct Custom {
+        #[widget_core]
+        core: CoreData,
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+            SizeRules::fixed(10, (0, 0))
+        }
+
+        fn draw(&mut self, draw: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
+            let draw = draw.draw_device();
+            let draw = DrawIface::<DrawPipe<RegistryPipe>>::downcast_from(draw).unwrap();
+            let (pass, rect) = (draw.get_pass(), self.core.rect);
+            draw.draw.custom(pass, rect, RegistryParam::new("test-b", "two"));
+            draw.draw.custom(pass, rect, RegistryParam::new("test-a", 1u32));
+            // Unknown keys and mismatching parameter types are ignored
+            draw.draw.custom(pass, rect, RegistryParam::new("test-c", 3u32));
+            draw.draw.custom(pass, rect, RegistryParam::new("test-a", "four"));
+        }
+    }
+}
+
+#[test]
+fn two_pipes() {
+    let log = Log::default();
+    let registry = CustomPipeRegistry::builder()
+        .add("test-a", Recorder::<u32>::new("a", &log))
+        .add("test-b", Recorder::<&'static str>::new("b", &log))
+        .build();
+
+    let theme = FlatTheme::new();
+    let mut headless = match Headless::new_custom(registry, theme, Options::from_env()) {
+        Ok(headless) => headless,
+        Err(e) => {
+            eprintln!("Skipping test: no graphics adapter ({})", e);
+            return;
+        }
+    };
+    let id = headless.add(Window::new("Custom pipes", Custom::default()));
+    headless.render(id).unwrap();
+
+    assert_eq!(*log.borrow(), ["a: window", "b: window", "b: two", "a: 1"]);
+}
@@ -0,0 +1,25 @@
+use kas::prelude::*;
+use kas::widgets::{Label, TextButton};
+
+widget! {
+    #[derive(Debug)]
+    #[widget{
+        layout = frame(column: [
+            region(header): self.title,
+            row: [self.ok, self.cancel],
+        ]);
+    }]
+    #[handler(msg = VoidMsg)]
+    struct Dialog {
+        #[widget_core]
+        core: CoreData,
+        #[widget(stretch = high)]
+        title: Label<String>,
+        #[widget(discard_msg)]
+        ok: TextButton<()>,
+        #[widget(discard_msg, halign = right, margins = none)]
+        cancel: TextButton<()>,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use kas::prelude::*;
+use kas::widgets::Label;
+
+widget! {
+    #[derive(Debug)]
+    #[widget{
+        layout = single;
+    }]
+    #[handler(msg = VoidMsg)]
+    struct Wrapper {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        label: Label<String>,
+    }
+}
+
+fn main() {
+    let _ = Wrapper {
+        core: Default::default(),
+        label: Label::from("text"),
+    };
+}
@@ -0,0 +1,268 @@
+//! Test view widgets over very large data sources
+//!
+//! Content sizes of these views exceed `i32::MAX` pixels; geometry must
+//! saturate rather than overflow.
+
+use kas::dir::{Directional, Down, Up};
+use kas::event::{Command, Event, UpdateHandle, VoidMsg};
+use kas::geom::{Offset, Size};
+use kas::test::TestWindow;
+use kas::updatable::{ListData, MatrixData, Updatable, UpdatableHandler};
+use kas::widgets::view::{driver, ListView, MatrixView};
+use kas::widgets::{Scrollable, Window};
+use kas::{WidgetChildren, WidgetCore};
+use std::ops::Range;
+
+const BILLION: usize = 1_000_000_000;
+
+type ListV<D> = ListView<D, Rows, driver::DefaultNav>;
+
+/// A list of `usize` values `0..len`, generated on demand
+#[derive(Debug)]
+struct Rows(usize);
+
+impl Updatable for Rows {
+    fn update_handle(&self) -> Option<UpdateHandle> {
+        None
+    }
+}
+impl UpdatableHandler<usize, VoidMsg> for Rows {
+    fn handle(&self, _: &usize, _: &VoidMsg) -> Option<UpdateHandle> {
+        None
+    }
+}
+impl ListData for Rows {
+    type Key = usize;
+    type Item = usize;
+
+    fn len(&self) -> usize {
+        self.0
+    }
+    fn contains_key(&self, key: &usize) -> bool {
+        *key < self.0
+    }
+    fn get_cloned(&self, key: &usize) -> Option<usize> {
+        self.contains_key(key).then(|| *key)
+    }
+    fn update(&self, _: &usize, _: usize) -> Option<UpdateHandle> {
+        None
+    }
+    fn iter_vec_from(&self, start: usize, limit: usize) -> Vec<(usize, usize)> {
+        let end = self.0.min(start.saturating_add(limit));
+        (start.min(end)..end).map(|i| (i, i)).collect()
+    }
+}
+
+/// A sparse list of `len / STEP` values, located at multiples of `STEP`
+#[derive(Debug)]
+struct Sparse(usize);
+
+const STEP: usize = 1000;
+
+impl Updatable for Sparse {
+    fn update_handle(&self) -> Option<UpdateHandle> {
+        None
+    }
+}
+impl UpdatableHandler<usize, VoidMsg> for Sparse {
+    fn handle(&self, _: &usize, _: &VoidMsg) -> Option<UpdateHandle> {
+        None
+    }
+}
+impl ListData for Sparse {
+    type Key = usize;
+    type Item = usize;
+
+    fn len(&self) -> usize {
+        (self.0 + STEP - 1) / STEP
+    }
+    fn contains_key(&self, key: &usize) -> bool {
+        *key < self.0 && *key % STEP == 0
+    }
+    fn get_cloned(&self, key: &usize) -> Option<usize> {
+        self.contains_key(key).then(|| *key)
+    }
+    fn update(&self, _: &usize, _: usize) -> Option<UpdateHandle> {
+        None
+    }
+    fn iter_vec_from(&self, start: usize, limit: usize) -> Vec<(usize, usize)> {
+        let end = self.len().min(start.saturating_add(limit));
+        (start.min(end)..end)
+            .map(|i| (i * STEP, i * STEP))
+            .collect()
+    }
+    fn logical_len(&self) -> usize {
+        self.0
+    }
+    fn iter_vec_range(&self, range: Range<usize>) -> Vec<(usize, usize, usize)> {
+        let start = (range.start + STEP - 1) / STEP;
+        let end = (range.end.min(self.0) + STEP - 1) / STEP;
+        (start..end.max(start))
+            .map(|i| (i * STEP, i * STEP, i * STEP))
+            .collect()
+    }
+}
+
+/// A matrix with `rows` rows and `cols` columns, generated on demand
+#[derive(Debug)]
+struct Table {
+    rows: usize,
+    cols: usize,
+}
+
+impl Updatable for Table {
+    fn update_handle(&self) -> Option<UpdateHandle> {
+        None
+    }
+}
+impl UpdatableHandler<(usize, usize), VoidMsg> for Table {
+    fn handle(&self, _: &(usize, usize), _: &VoidMsg) -> Option<UpdateHandle> {
+        None
+    }
+}
+impl MatrixData for Table {
+    type ColKey = usize;
+    type RowKey = usize;
+    type Key = (usize, usize);
+    type Item = usize;
+
+    fn col_len(&self) -> usize {
+        self.cols
+    }
+    fn row_len(&self) -> usize {
+        self.rows
+    }
+    fn contains(&self, key: &(usize, usize)) -> bool {
+        key.0 < self.rows && key.1 < self.cols
+    }
+    fn get_cloned(&self, key: &(usize, usize)) -> Option<usize> {
+        self.contains(key).then(|| key.0 ^ key.1)
+    }
+    fn update(&self, _: &(usize, usize), _: usize) -> Option<UpdateHandle> {
+        None
+    }
+    fn col_iter_vec_from(&self, start: usize, limit: usize) -> Vec<usize> {
+        let end = self.cols.min(start.saturating_add(limit));
+        (start.min(end)..end).collect()
+    }
+    fn row_iter_vec_from(&self, start: usize, limit: usize) -> Vec<usize> {
+        let end = self.rows.min(start.saturating_add(limit));
+        (start.min(end)..end).collect()
+    }
+    fn make_key(row: &usize, col: &usize) -> (usize, usize) {
+        (*row, *col)
+    }
+}
+
+/// Scroll the view at path `[0]` (of type `W`) to its maximum offset
+fn scroll_to_end<W: Scrollable + 'static, T: kas::Window>(tw: &mut TestWindow<T>) {
+    tw.with(|mgr, window| {
+        let w = window.get_child_mut(0).unwrap();
+        let w = w.as_any_mut().downcast_mut::<W>().unwrap();
+        let max = w.max_scroll_offset();
+        assert!(max.0 >= 0 && max.1 >= 0);
+        let offset = w.set_scroll_offset(mgr, max);
+        assert_eq!(offset, max);
+    });
+}
+
+fn check_list<D: Directional + Default + 'static>(len: usize) {
+    let view = ListV::<D>::new_with_driver(driver::DefaultNav, Rows(len));
+    let mut tw = TestWindow::new(Window::new("Large list", view));
+    tw.resize(Size(200, 300));
+
+    let (horiz, vert) = tw.get::<ListV<D>>(&[0]).scroll_axes(Size(200, 300));
+    assert!(!horiz && vert);
+    let max = tw.get::<ListV<D>>(&[0]).max_scroll_offset();
+    assert!(max.1 > 0);
+
+    scroll_to_end::<ListV<D>, _>(&mut tw);
+    for cmd in [Command::End, Command::PageUp, Command::Home, Command::End] {
+        let _ = tw.send_to::<ListV<D>>(&[0], Event::Command(cmd, false));
+    }
+    for cmd in [Command::Down, Command::PageDown, Command::Up] {
+        let _ = tw.send_to::<ListV<D>>(&[0], Event::Command(cmd, false));
+    }
+
+    // Children are positioned within (saturated) content bounds
+    let view = tw.get::<ListV<D>>(&[0]);
+    assert!(view.num_children() > 0);
+    for i in 0..view.num_children() {
+        let rect = view.get_child(i).unwrap().rect();
+        assert!(rect.pos.1 >= 0 && rect.size.1 > 0);
+    }
+}
+
+#[test]
+fn list_billion_rows() {
+    check_list::<Down>(BILLION);
+}
+
+#[test]
+fn list_beyond_i32() {
+    check_list::<Down>(usize::try_from(i32::MAX).unwrap() + 7);
+}
+
+#[test]
+fn list_reversed_billion_rows() {
+    check_list::<Up>(BILLION);
+}
+
+#[test]
+fn matrix_billion_cells() {
+    type View = MatrixView<Table, driver::DefaultNav>;
+    let table = Table {
+        rows: BILLION,
+        cols: 3 * BILLION,
+    };
+    let view = View::new_with_driver(driver::DefaultNav, table);
+    let mut tw = TestWindow::new(Window::new("Large matrix", view));
+    tw.resize(Size(400, 300));
+
+    let (horiz, vert) = tw.get::<View>(&[0]).scroll_axes(Size(400, 300));
+    assert!(horiz && vert);
+
+    scroll_to_end::<View, _>(&mut tw);
+    for cmd in [Command::End, Command::PageUp, Command::Home, Command::End] {
+        let _ = tw.send_to::<View>(&[0], Event::Command(cmd, false));
+    }
+}
+
+#[test]
+fn list_empty() {
+    check_empty::<Down>();
+    check_empty::<Up>();
+}
+
+fn check_empty<D: Directional + Default + 'static>() {
+    let view = ListV::<D>::new_with_driver(driver::DefaultNav, Rows(0));
+    let mut tw = TestWindow::new(Window::new("Empty list", view));
+    tw.resize(Size(200, 300));
+    assert_eq!(tw.get::<ListV<D>>(&[0]).max_scroll_offset(), Offset::ZERO);
+    let _ = tw.send_to::<ListV<D>>(&[0], Event::Command(Command::End, false));
+}
+
+#[test]
+fn list_sparse() {
+    type View = ListView<Down, Sparse, driver::DefaultNav>;
+    let view = View::new_with_driver(driver::DefaultNav, Sparse(BILLION));
+    let mut tw = TestWindow::new(Window::new("Sparse list", view));
+    tw.resize(Size(200, 300));
+
+    // Content size is determined by the logical length, not the item count
+    let dense = ListV::<Down>::new_with_driver(driver::DefaultNav, Rows(BILLION));
+    let mut dense = TestWindow::new(Window::new("Dense list", dense));
+    dense.resize(Size(200, 300));
+    let max = tw.get::<View>(&[0]).max_scroll_offset();
+    assert_eq!(max, dense.get::<ListV<Down>>(&[0]).max_scroll_offset());
+
+    // Placeholders are shown at empty positions
+    let view = tw.get::<View>(&[0]);
+    assert!(view.num_children() > 1);
+    for i in 0..view.num_children() {
+        assert!(view.get_child(i).unwrap().rect().size.1 > 0);
+    }
+
+    scroll_to_end::<View, _>(&mut tw);
+    let _ = tw.send_to::<View>(&[0], Event::Command(Command::Home, false));
+}
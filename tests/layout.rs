@@ -0,0 +1,156 @@
+//! Test layout solving of widgets
+
+use kas::layout::AspectRatio;
+use kas::prelude::*;
+use kas::test::TestWindow;
+use kas::widgets::{Label, StringLabel, Window};
+use std::cell::Cell;
+use std::rc::Rc;
+
+widget! {
+    /// A fixed-size widget counting calls to `size_rules`
+    #[derive(Debug)]
+    struct Counter {
+        #[widget_core]
+        core: CoreData,
+        calls: Rc<Cell<usize>>,
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+            self.calls.set(self.calls.get() + 1);
+            SizeRules::fixed(10, (0, 0))
+        }
+    }
+}
+
+widget! {
+    /// A widget preferring a 2:1 aspect ratio
+    #[derive(Debug, Default)]
+    struct Aspect {
+        #[widget_core]
+        core: CoreData,
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, _: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+            let ratio = AspectRatio::new(2.0, 1.0);
+            SizeRules::with_aspect(axis, ratio, 10, 20, (0, 0), Stretch::None)
+        }
+    }
+}
+
+/// Re-solve size rules of the window's child (as on a local size change)
+fn solve_child<W: kas::Window>(tw: &mut TestWindow<W>) {
+    tw.with(|mgr, window| {
+        let w = window.get_child_mut(0).unwrap();
+        let width = w.rect().size.0;
+        mgr.size_handle(|size_handle| {
+            w.size_rules(size_handle, AxisInfo::new(false, None));
+            w.size_rules(size_handle, AxisInfo::new(true, Some(width)));
+        });
+    });
+}
+
+#[test]
+fn size_rules_cache() {
+    let calls = Rc::new(Cell::new(0));
+    let counter = Counter {
+        core: Default::default(),
+        calls: calls.clone(),
+    };
+    let widget = make_widget! {
+        #[widget{
+            layout = column: *;
+        }]
+        #[handler(msg = VoidMsg)]
+        struct {
+            #[widget] label: StringLabel = Label::new("Hello".to_string()),
+            #[widget] counter: Counter = counter,
+        }
+    };
+    let mut tw = TestWindow::new(Window::new("Size rules cache", widget));
+
+    // Rules are cached while content is unchanged
+    solve_child(&mut tw);
+    let n = calls.get();
+    solve_child(&mut tw);
+    assert_eq!(calls.get(), n);
+
+    // Changing the label's content invalidates the column's cached rules
+    let label = tw.get_mut::<StringLabel>(&[0, 0]);
+    let _ = label.set_string("Hello, world!".to_string());
+    solve_child(&mut tw);
+    assert!(calls.get() > n);
+}
+
+#[test]
+fn wrapped_text_in_column() {
+    let calls = Rc::new(Cell::new(0));
+    let counter = Counter {
+        core: Default::default(),
+        calls: calls.clone(),
+    };
+    let text = "A long label which wraps over several lines when the window is narrow";
+    let widget = make_widget! {
+        #[widget{
+            layout = column: *;
+        }]
+        #[handler(msg = VoidMsg)]
+        struct {
+            #[widget] label: StringLabel = Label::new(text.to_string()),
+            #[widget] counter: Counter = counter,
+        }
+    };
+    let mut tw = TestWindow::new(Window::new("Wrapped text", widget));
+    let size = tw.size();
+    let line = tw.rect(&[0, 0]);
+
+    // Text wraps to the width assigned by the column, below which the next
+    // child is placed
+    tw.resize(Size(size.0 / 3, size.1 * 3));
+    let wrapped = tw.rect(&[0, 0]);
+    assert!(wrapped.size.0 < line.size.0);
+    assert!(wrapped.size.1 > line.size.1);
+    let counter = tw.rect(&[0, 1]);
+    assert!(counter.pos.1 >= wrapped.pos.1 + wrapped.size.1);
+
+    // Horizontal rules do not depend on the height, thus are not re-solved
+    // when only the height changes
+    let n = calls.get();
+    tw.resize(Size(size.0 / 3, size.1 * 4));
+    assert_eq!(calls.get(), n);
+    assert_eq!(tw.rect(&[0, 0]), wrapped);
+}
+
+#[test]
+fn aspect_ratio_in_row() {
+    let widget = make_widget! {
+        #[widget{
+            layout = column: *;
+        }]
+        #[handler(msg = VoidMsg)]
+        struct {
+            #[widget] row = make_widget! {
+                #[widget{
+                    layout = row: *;
+                }]
+                #[handler(msg = VoidMsg)]
+                struct {
+                    #[widget] aspect: Aspect = Aspect::default(),
+                    #[widget] label: StringLabel = Label::new("Label".to_string()),
+                }
+            },
+            #[widget] label: StringLabel = Label::new("Below".to_string()),
+        }
+    };
+    let mut tw = TestWindow::new(Window::new("Aspect ratio", widget));
+
+    // The row's children are re-solved for the height assigned to the row
+    for height in [100, 200] {
+        tw.resize(Size(600, height));
+        let rect = tw.rect(&[0, 0, 0]);
+        assert_eq!(rect.size.1, tw.rect(&[0, 0]).size.1);
+        assert_eq!(rect.size.0, 2 * rect.size.1);
+    }
+}
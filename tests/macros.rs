@@ -0,0 +1,30 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Macro tests
+//!
+//! UI tests check that valid input compiles and that invalid input reports
+//! the expected diagnostics (`tests/ui/*.stderr`). After intentional changes
+//! to diagnostics, regenerate these with:
+//! ```sh
+//! TRYBUILD=overwrite cargo test --test macros
+//! ```
+//!
+//! Expansion tests compare macro output against `tests/expand/*.expanded.rs`.
+//! These require `cargo-expand`, hence are ignored by default. Missing
+//! snapshots are written on first run; refresh with `MACROTEST=overwrite`.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass_*.rs");
+    t.compile_fail("tests/ui/fail_*.rs");
+}
+
+#[test]
+#[ignore = "requires cargo-expand"]
+fn expand() {
+    macrotest::expand("tests/expand/*.rs");
+}
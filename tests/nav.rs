@@ -0,0 +1,153 @@
+//! Test keyboard navigation
+
+use kas::event::{ModifiersState, VirtualKeyCode};
+use kas::prelude::*;
+use kas::test::TestWindow;
+use kas::widgets::Window;
+
+widget! {
+    /// A navigable widget with an optional tab index
+    #[derive(Debug)]
+    #[widget{
+        key_nav = true;
+        tab_index = self.index;
+    }]
+    struct Item {
+        #[widget_core]
+        core: CoreData,
+        index: Option<u32>,
+    }
+
+    impl Self {
+        fn new(index: Option<u32>) -> Self {
+            Item {
+                core: Default::default(),
+                index,
+            }
+        }
+    }
+
+    impl Layout for Self {
+        fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+            SizeRules::fixed(10, (0, 0))
+        }
+    }
+}
+
+widget! {
+    /// A focus scope over two items
+    #[derive(Debug)]
+    #[widget{
+        focus_scope = true;
+        layout = row: *;
+    }]
+    #[handler(msg = VoidMsg)]
+    struct Scope {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        first: Item,
+        #[widget]
+        second: Item,
+    }
+
+    impl Self {
+        fn new() -> Self {
+            Scope {
+                core: Default::default(),
+                first: Item::new(None),
+                second: Item::new(None),
+            }
+        }
+    }
+}
+
+fn tab<W: kas::Window>(tw: &mut TestWindow<W>, shift: bool) {
+    if shift {
+        tw.set_modifiers(ModifiersState::SHIFT);
+    }
+    tw.press_key(VirtualKeyCode::Tab);
+    tw.set_modifiers(ModifiersState::empty());
+}
+
+#[test]
+fn tab_index_order() {
+    let widget = make_widget! {
+        #[widget{
+            layout = column: *;
+        }]
+        #[handler(msg = VoidMsg)]
+        struct {
+            #[widget] a: Item = Item::new(None),
+            #[widget] b: Item = Item::new(Some(2)),
+            #[widget] c: Item = Item::new(None),
+            #[widget] d: Item = Item::new(Some(1)),
+        }
+    };
+    let mut tw = TestWindow::new(Window::new("Tab index", widget));
+
+    // Explicit indices come first, then document order
+    for path in [[0, 3], [0, 1], [0, 0], [0, 2], [0, 3]] {
+        tab(&mut tw, false);
+        tw.assert_nav_focus(&path);
+    }
+
+    tab(&mut tw, true);
+    tw.assert_nav_focus(&[0, 2]);
+}
+
+#[test]
+fn wrap_in_scope() {
+    let widget = make_widget! {
+        #[widget{
+            layout = column: *;
+        }]
+        #[handler(msg = VoidMsg)]
+        struct {
+            #[widget] a: Item = Item::new(None),
+            #[widget] scope: Scope = Scope::new(),
+            #[widget] b: Item = Item::new(None),
+        }
+    };
+    let mut tw = TestWindow::new(Window::new("Focus scope", widget));
+
+    // Once inside the scope, navigation cycles within it
+    let paths: [&[usize]; 4] = [&[0, 0], &[0, 1, 0], &[0, 1, 1], &[0, 1, 0]];
+    for path in paths {
+        tab(&mut tw, false);
+        tw.assert_nav_focus(path);
+    }
+
+    tab(&mut tw, true);
+    tw.assert_nav_focus(&[0, 1, 1]);
+    tab(&mut tw, true);
+    tw.assert_nav_focus(&[0, 1, 0]);
+}
+
+#[test]
+fn escape_leaves_scope() {
+    let widget = make_widget! {
+        #[widget{
+            layout = column: *;
+        }]
+        #[handler(msg = VoidMsg)]
+        struct {
+            #[widget] a: Item = Item::new(None),
+            #[widget] scope: Scope = Scope::new(),
+            #[widget] b: Item = Item::new(None),
+        }
+    };
+    let mut tw = TestWindow::new(Window::new("Focus scope", widget));
+
+    tab(&mut tw, false);
+    tab(&mut tw, false);
+    tw.assert_nav_focus(&[0, 1, 0]);
+
+    // The scope is not navigable, thus focus moves to the widget following it
+    tw.press_key(VirtualKeyCode::Escape);
+    tw.assert_nav_focus(&[0, 2]);
+
+    // Navigation is no longer confined to the scope
+    tab(&mut tw, false);
+    tw.assert_nav_focus(&[0, 0]);
+}
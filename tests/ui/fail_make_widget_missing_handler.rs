@@ -0,0 +1,15 @@
+use kas::event::VoidMsg;
+use kas::macros::make_widget;
+use kas::widgets::TextButton;
+
+fn main() {
+    let _ = make_widget! {
+        #[widget{
+            layout = single;
+        }]
+        #[handler(msg = VoidMsg)]
+        struct {
+            #[widget(use_msg = missing)] button = TextButton::new_msg("x", 1),
+        }
+    };
+}
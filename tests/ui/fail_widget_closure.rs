@@ -0,0 +1,18 @@
+use kas::prelude::*;
+use kas::widgets::TextButton;
+
+widget! {
+    #[derive(Debug)]
+    #[widget{
+        layout = single;
+    }]
+    #[handler(msg = VoidMsg)]
+    struct Parent {
+        #[widget_core]
+        core: CoreData,
+        #[widget(use_msg = |self, _mgr, _n: i32| ())]
+        child: TextButton<i32>,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use kas::prelude::*;
+
+widget! {
+    #[derive(Debug)]
+    #[handler(msg = VoidMsg)]
+    struct NoCore { value: i32 }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use kas::prelude::*;
+
+widget! {
+    #[derive(Debug)]
+    #[handler(msg = VoidMsg)]
+    struct TwoCores {
+        #[widget_core]
+        core: CoreData,
+        #[widget_core]
+        core2: CoreData,
+    }
+}
+
+fn main() {}
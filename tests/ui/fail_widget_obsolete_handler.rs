@@ -0,0 +1,18 @@
+use kas::prelude::*;
+use kas::widgets::Label;
+
+widget! {
+    #[derive(Debug)]
+    #[widget{
+        layout = single;
+    }]
+    #[handler(msg = VoidMsg)]
+    struct Parent {
+        #[widget_core]
+        core: CoreData,
+        #[widget(handler = on_msg)]
+        child: Label<String>,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use kas::event::{Manager, VoidMsg};
+use kas::macros::make_widget;
+use kas::widgets::{Label, TextButton};
+
+fn main() {
+    let _ = make_widget! {
+        #[widget{
+            layout = row: *;
+        }]
+        #[handler(msg = VoidMsg)]
+        struct {
+            #[widget] label: Label<String> = Label::from("0"),
+            #[widget(use_msg = increment)] button = TextButton::new_msg("+", 1),
+            #[widget(use_msg = |self, _mgr, n: i32| self.count -= n)]
+            decr = TextButton::new_msg("−", 1),
+            count: i32 = 0,
+        }
+        impl Self {
+            fn increment(&mut self, _: &mut Manager, n: i32) {
+                self.count += n;
+            }
+        }
+    };
+}
@@ -0,0 +1,84 @@
+//! Test persistence of widget identifiers across reconfigure
+
+use kas::prelude::*;
+use kas::test::TestWindow;
+use kas::widgets::{Column, Label, StringLabel, Window};
+
+type Labels = Column<StringLabel>;
+
+fn new_window(n: usize) -> TestWindow<Window<Labels>> {
+    let labels = (0..n).map(|i| Label::new(format!("Label {}", i))).collect();
+    TestWindow::new(Window::new("Widget ids", Column::new(labels)))
+}
+
+/// Identifiers of the column and its children
+fn ids(tw: &TestWindow<Window<Labels>>) -> (WidgetId, Vec<WidgetId>) {
+    let column = tw.get::<Labels>(&[0]);
+    let children = (0..column.num_children())
+        .map(|i| column.get_child(i).unwrap().id())
+        .collect();
+    (column.id(), children)
+}
+
+#[test]
+fn insert_child() {
+    let mut tw = new_window(4);
+    let (column, before) = ids(&tw);
+    let window = tw.widget().id();
+
+    let action = tw
+        .get_mut::<Labels>(&[0])
+        .insert(2, Label::new("New".to_string()));
+    tw.send_action(action);
+
+    let (new_column, after) = ids(&tw);
+    assert_eq!(after.len(), 5);
+    assert_eq!(&after[..2], &before[..2]);
+    assert_eq!(&after[3..], &before[2..]);
+    assert!(!before.contains(&after[2]));
+    assert_eq!(new_column, column);
+    assert_eq!(tw.widget().id(), window);
+
+    // Inserting at the start and appending also keep existing ids
+    let action = tw
+        .get_mut::<Labels>(&[0])
+        .insert(0, Label::new("First".to_string()));
+    tw.send_action(action);
+    let action = tw
+        .get_mut::<Labels>(&[0])
+        .push(Label::new("Last".to_string()));
+    tw.send_action(action);
+    let (new_column, again) = ids(&tw);
+    assert_eq!(&again[1..6], &after[..]);
+    assert_eq!(new_column, column);
+}
+
+#[test]
+fn remove_child() {
+    let mut tw = new_window(4);
+    let (column, before) = ids(&tw);
+
+    let (_, action) = tw.get_mut::<Labels>(&[0]).remove(1);
+    tw.send_action(action);
+
+    let (new_column, after) = ids(&tw);
+    assert_eq!(after, [before[0], before[2], before[3]]);
+    assert_eq!(new_column, column);
+
+    // Removal leaves room to insert again
+    let action = tw
+        .get_mut::<Labels>(&[0])
+        .insert(1, Label::new("New".to_string()));
+    tw.send_action(action);
+    let (_, again) = ids(&tw);
+    assert_eq!(again[0], before[0]);
+    assert_eq!(&again[2..], &before[2..]);
+}
+
+#[test]
+fn reconfigure_keeps_ids() {
+    let mut tw = new_window(4);
+    let before = ids(&tw);
+    tw.send_action(TkAction::RECONFIGURE);
+    assert_eq!(ids(&tw), before);
+}